@@ -0,0 +1,153 @@
+//! Measures parsing throughput in statements/sec per syntax, through dynsyn parsers, and through corresponding sophia parsers directly. Difference between them is overhead of dynsyn wrapper.
+//!
+//! Run with `cargo bench --bench throughput`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rdf_dynsyn::{
+    parser::{quads::DynSynQuadParserFactory, triples::DynSynTripleParserFactory},
+    syntax::{self, RdfSyntax},
+};
+use sophia_api::{
+    parser::{QuadParser, TripleParser},
+    quad::stream::QuadSource,
+    triple::stream::TripleSource,
+};
+use sophia_term::BoxTerm;
+use sophia_turtle::parser::{
+    nq::NQuadsParser, nt::NTriplesParser, trig::TriGParser, turtle::TurtleParser,
+};
+
+const STATEMENTS: usize = 10_000;
+
+/// Generate a document with `n` statements, in given line based, or turtle family syntax.
+fn generate_doc(syntax_: RdfSyntax, n: usize) -> String {
+    let mut doc = String::new();
+    let trig = syntax_ == syntax::TRIG;
+    if trig {
+        doc.push_str("<http://ex.org/g> {\n");
+    }
+    for i in 0..n {
+        let graph = if syntax_ == syntax::N_QUADS {
+            " <http://ex.org/g>"
+        } else {
+            ""
+        };
+        doc.push_str(&format!(
+            "<http://ex.org/s{}> <http://ex.org/p{}> \"object {}\"{} .\n",
+            i / 10,
+            i % 7,
+            i,
+            graph
+        ));
+    }
+    if trig {
+        doc.push_str("}\n");
+    }
+    doc
+}
+
+fn count_triples<TS: TripleSource>(mut source: TS) -> usize {
+    let mut count = 0;
+    source.for_each_triple(|_| count += 1).unwrap();
+    count
+}
+
+fn count_quads<QS: QuadSource>(mut source: QS) -> usize {
+    let mut count = 0;
+    source.for_each_quad(|_| count += 1).unwrap();
+    count
+}
+
+fn triple_parsing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("triple_parsing");
+    group.throughput(Throughput::Elements(STATEMENTS as u64));
+    let factory = DynSynTripleParserFactory::default();
+
+    for syntax_ in [syntax::N_TRIPLES, syntax::TURTLE] {
+        let doc = generate_doc(syntax_, STATEMENTS);
+        let name = syntax_.name().unwrap();
+        let dynsyn = factory
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap();
+
+        group.bench_with_input(BenchmarkId::new("sophia", name), &doc, |b, doc| {
+            b.iter(|| {
+                if syntax_ == syntax::N_TRIPLES {
+                    count_triples(NTriplesParser {}.parse_str(doc))
+                } else {
+                    count_triples(TurtleParser { base: None }.parse_str(doc))
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("dynsyn", name), &doc, |b, doc| {
+            b.iter(|| count_triples(dynsyn.parse_str(doc)))
+        });
+        group.bench_with_input(BenchmarkId::new("dynsyn_scoped", name), &doc, |b, doc| {
+            b.iter(|| {
+                let mut count = 0;
+                dynsyn
+                    .parse_str(doc)
+                    .for_each_triple_scoped(|_| count += 1)
+                    .unwrap();
+                count
+            })
+        });
+    }
+    group.finish();
+}
+
+fn quad_parsing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("quad_parsing");
+    group.throughput(Throughput::Elements(STATEMENTS as u64));
+    let factory = DynSynQuadParserFactory::default();
+
+    for syntax_ in [syntax::N_QUADS, syntax::TRIG] {
+        let doc = generate_doc(syntax_, STATEMENTS);
+        let name = syntax_.name().unwrap();
+        let dynsyn = factory
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap();
+
+        group.bench_with_input(BenchmarkId::new("sophia", name), &doc, |b, doc| {
+            b.iter(|| {
+                if syntax_ == syntax::N_QUADS {
+                    count_quads(NQuadsParser {}.parse_str(doc))
+                } else {
+                    count_quads(TriGParser { base: None }.parse_str(doc))
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("dynsyn", name), &doc, |b, doc| {
+            b.iter(|| count_quads(dynsyn.parse_str(doc)))
+        });
+        group.bench_with_input(BenchmarkId::new("dynsyn_scoped", name), &doc, |b, doc| {
+            b.iter(|| {
+                let mut count = 0;
+                dynsyn
+                    .parse_str(doc)
+                    .for_each_quad_scoped(|_| count += 1)
+                    .unwrap();
+                count
+            })
+        });
+    }
+
+    // Triples adapted into quads of a configured graph.
+    let doc = generate_doc(syntax::N_TRIPLES, STATEMENTS);
+    let dynsyn = factory
+        .try_new_parser::<BoxTerm>(
+            syntax::N_TRIPLES,
+            None,
+            Some(BoxTerm::new_iri("http://ex.org/g").unwrap()),
+        )
+        .unwrap();
+    group.bench_with_input(
+        BenchmarkId::new("dynsyn_adapted", "n-triples"),
+        &doc,
+        |b, doc| b.iter(|| count_quads(dynsyn.parse_str(doc))),
+    );
+    group.finish();
+}
+
+criterion_group!(benches, triple_parsing, quad_parsing);
+criterion_main!(benches);
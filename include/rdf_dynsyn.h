@@ -0,0 +1,43 @@
+#ifndef RDF_DYNSYN_H
+#define RDF_DYNSYN_H
+
+/* Generated with cbindgen from `ffi` module of rdf_dynsyn. Don't edit manually. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+// Status of a dynsyn ffi call.
+typedef enum DynsynStatus {
+  // Call succeeded.
+  DYNSYN_STATUS_OK = 0,
+  // An argument was a null pointer, or not a valid utf-8 string.
+  DYNSYN_STATUS_INVALID_ARGUMENT = 1,
+  // A syntax string doesn't identify any known syntax.
+  DYNSYN_STATUS_UNKNOWN_SYNTAX = 2,
+  // Conversion failed. Output may have been partially written.
+  DYNSYN_STATUS_CONVERSION_FAILED = 3,
+  // Conversion panicked. Output may have been partially written.
+  DYNSYN_STATUS_PANICKED = 4,
+} DynsynStatus;
+
+// A callback, that receives chunks of converted document. `data` is valid only for duration of the call.
+typedef void (*DynsynWriteCallback)(const uint8_t *data, size_t len, void *user_data);
+
+// Converts document in `buf` of `len` bytes from syntax `src_mt` into syntax `dst_mt`, handing converted document to `out_cb` in chunks, along with `user_data`.
+//
+// # Safety
+// `buf` must point to `len` readable bytes, unless `len` is 0. `src_mt`, and `dst_mt` must be valid nul terminated strings. `out_cb` must be safe to call with `user_data`.
+DynsynStatus dynsyn_convert(const uint8_t *buf,
+                            size_t len,
+                            const char *src_mt,
+                            const char *dst_mt,
+                            DynsynWriteCallback out_cb,
+                            void *user_data);
+
+// Returns [error code](crate::error_code) of last error on calling thread, or null if last conversion on it succeeded, or none is made. Returned string is valid until next dynsyn call on the thread.
+const char *dynsyn_last_error_code(void);
+
+// Returns message of last error on calling thread, or null if last conversion on it succeeded, or none is made. Returned string is valid until next dynsyn call on the thread.
+const char *dynsyn_last_error_message(void);
+
+#endif /* RDF_DYNSYN_H */
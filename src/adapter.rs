@@ -0,0 +1,305 @@
+//! This module provides adapters between triple-sources, and quad-sources, with same graph name policies, that dynsyn sources apply in adapting statements of one kind from documents of other kind. Unlike dynsyn sources, they can wrap any sophia source, like those from specialized sophia parsers, or from in-memory graphs and datasets.
+//!
+//! - [`TriplesAsQuads`] emits a quad for each triple, with graph name set to a configured term, like [`DynSynQuadSource`](crate::parser::quads::source::DynSynQuadSource) does for triple syntaxes.
+//! - [`QuadsFilteredToGraph`] emits a triple for each quad in a configured graph, and ignores quads of other graphs without copying any of their terms, like [`DynSynTripleSource`](crate::parser::triples::source::DynSynTripleSource) does for quad syntaxes.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::adapter::{QuadsFilteredToGraph, TriplesAsQuads};
+//! use sophia_api::{dataset::Dataset, graph::Graph, quad::stream::QuadSource, triple::stream::TripleSource};
+//! use sophia_inmem::{dataset::FastDataset, graph::FastGraph};
+//! use sophia_term::{BoxTerm, StaticTerm};
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let me = StaticTerm::new_iri("http://example.org/#me")?;
+//! let g = BoxTerm::new_iri("http://example.org/g")?;
+//! let graph = vec![[me, me, me]];
+//!
+//! let dataset: FastDataset =
+//!     TriplesAsQuads::new(graph.triples(), Some(g.clone())).collect_quads()?;
+//! assert_eq!(dataset.quads().count(), 1);
+//!
+//! let in_g: FastGraph =
+//!     QuadsFilteredToGraph::<_, BoxTerm>::new(dataset.quads(), Some(g)).collect_triples()?;
+//! assert_eq!(in_g.triples().count(), 1);
+//! let in_default: FastGraph =
+//!     QuadsFilteredToGraph::<_, BoxTerm>::new(dataset.quads(), None).collect_triples()?;
+//! assert_eq!(in_default.triples().count(), 0);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::error::Error;
+
+use sophia_api::{
+    quad::{
+        stream::QuadSource,
+        streaming_mode::{ByValue, StreamedQuad},
+        Quad,
+    },
+    term::{term_eq, CopyTerm, TTerm},
+    triple::{
+        stream::{StreamResult, TripleSource},
+        streaming_mode::{ByValue as TripleByValue, StreamedTriple},
+        Triple,
+    },
+};
+
+use crate::parser::{quads::source::TupleQuad, triples::source::SliceTriple};
+
+/// A [`QuadSource`], that emits a quad for each triple of underlying triple-source, with graph name set to configured `graph_name`, and remaining terms being copies of those of triple.
+pub struct TriplesAsQuads<TS, T> {
+    source: TS,
+    graph_name: Option<T>,
+}
+
+impl<TS, T> TriplesAsQuads<TS, T>
+where
+    TS: TripleSource,
+    T: CopyTerm + TTerm + Clone,
+{
+    /// Create a new adapter over given triple `source`, that puts triples into graph with given `graph_name`, or into default graph if it is `None`.
+    pub fn new(source: TS, graph_name: Option<T>) -> Self {
+        Self { source, graph_name }
+    }
+
+    /// Get graph name of emitted quads.
+    pub fn graph_name(&self) -> Option<&T> {
+        self.graph_name.as_ref()
+    }
+
+    /// Get back underlying triple-source.
+    pub fn into_inner(self) -> TS {
+        self.source
+    }
+}
+
+impl<TS, T> QuadSource for TriplesAsQuads<TS, T>
+where
+    TS: TripleSource,
+    T: CopyTerm + TTerm + Clone,
+{
+    type Error = TS::Error;
+
+    type Quad = ByValue<TupleQuad<T>>;
+
+    fn try_for_some_quad<F, E>(&mut self, f: &mut F) -> StreamResult<bool, Self::Error, E>
+    where
+        F: FnMut(StreamedQuad<Self::Quad>) -> Result<(), E>,
+        E: Error,
+    {
+        let graph_name = &self.graph_name;
+        self.source.try_for_some_triple(&mut |t| {
+            f(StreamedQuad::by_value((
+                [T::copy(t.s()), T::copy(t.p()), T::copy(t.o())],
+                graph_name.clone(),
+            )))
+        })
+    }
+
+    fn size_hint_quads(&self) -> (usize, Option<usize>) {
+        self.source.size_hint_triples()
+    }
+}
+
+/// A [`TripleSource`], that emits a triple for each quad of underlying quad-source, that is in graph with configured `graph_name`. Quads of other graphs are ignored, without copying any of their terms.
+pub struct QuadsFilteredToGraph<QS, T> {
+    source: QS,
+    graph_name: Option<T>,
+    dropped: usize,
+}
+
+impl<QS, T> QuadsFilteredToGraph<QS, T>
+where
+    QS: QuadSource,
+    T: CopyTerm + TTerm + Clone,
+{
+    /// Create a new adapter over given quad `source`, that emits triples of graph with given `graph_name`, or of default graph if it is `None`.
+    pub fn new(source: QS, graph_name: Option<T>) -> Self {
+        Self {
+            source,
+            graph_name,
+            dropped: 0,
+        }
+    }
+
+    /// Get graph name, whose triples are emitted.
+    pub fn graph_name(&self) -> Option<&T> {
+        self.graph_name.as_ref()
+    }
+
+    /// Get number of quads ignored so far, for being in other graphs.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Get back underlying quad-source.
+    pub fn into_inner(self) -> QS {
+        self.source
+    }
+}
+
+impl<QS, T> TripleSource for QuadsFilteredToGraph<QS, T>
+where
+    QS: QuadSource,
+    T: CopyTerm + TTerm + Clone,
+{
+    type Error = QS::Error;
+
+    type Triple = TripleByValue<SliceTriple<T>>;
+
+    fn try_for_some_triple<F, E>(&mut self, f: &mut F) -> StreamResult<bool, Self::Error, E>
+    where
+        F: FnMut(StreamedTriple<Self::Triple>) -> Result<(), E>,
+        E: Error,
+    {
+        let graph_name = &self.graph_name;
+        let dropped = &mut self.dropped;
+        self.source.try_for_some_quad(&mut |q| {
+            // Compare graph term before materializing any term, so that quads of other graphs
+            // are dropped cheaply.
+            let in_graph = match (q.g(), graph_name) {
+                (Some(gv), Some(target)) => term_eq(gv, target),
+                (None, None) => true,
+                _ => false,
+            };
+            if !in_graph {
+                *dropped += 1;
+                return Ok(());
+            }
+            f(StreamedTriple::by_value([
+                T::copy(q.s()),
+                T::copy(q.p()),
+                T::copy(q.o()),
+            ]))
+        })
+    }
+
+    fn size_hint_triples(&self) -> (usize, Option<usize>) {
+        (0, self.source.size_hint_quads().1)
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use once_cell::sync::Lazy;
+    use sophia_api::{
+        dataset::{Dataset, MutableDataset},
+        graph::Graph,
+        parser::QuadParser,
+        quad::{stream::QuadSource, Quad},
+        term::{term_eq, CopyTerm, TTerm},
+        triple::{stream::TripleSource, Triple},
+    };
+    use sophia_inmem::{dataset::FastDataset, graph::FastGraph};
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+
+    use crate::{
+        fixtures::TESTS_TRIG, parser::quads::DynSynQuadParserFactory, syntax, tests::TRACING,
+    };
+
+    use super::{QuadsFilteredToGraph, TriplesAsQuads};
+
+    fn parse_trig(doc: &str) -> FastDataset {
+        DynSynQuadParserFactory::default()
+            .try_new_parser(syntax::TRIG, None, None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(doc)
+            .collect_quads()
+            .unwrap()
+    }
+
+    #[test_case(None)]
+    #[test_case(Some("http://example.org/g"))]
+    pub fn puts_triples_into_configured_graph(graph_name: Option<&str>) {
+        Lazy::force(&TRACING);
+        let graph_name = graph_name.map(|g| BoxTerm::new_iri(g).unwrap());
+        let s = BoxTerm::new_iri("http://example.org/s").unwrap();
+        let graph = vec![[s.clone(), s.clone(), s.clone()], [s.clone(), s.clone(), s]];
+
+        let dataset: FastDataset = TriplesAsQuads::new(graph.triples(), graph_name.clone())
+            .collect_quads()
+            .unwrap();
+        assert_eq!(dataset.quads().count(), 1);
+        assert!(dataset.quads().all(|q| match (q.unwrap().g(), &graph_name) {
+            (Some(gv), Some(target)) => term_eq(gv, target),
+            (None, None) => true,
+            _ => false,
+        }));
+    }
+
+    #[test]
+    pub fn filters_quads_to_configured_graph() {
+        Lazy::force(&TRACING);
+        let dataset = parse_trig(TESTS_TRIG[1]);
+        let graph_names: HashSet<Option<BoxTerm>> = dataset
+            .quads()
+            .map(|q| q.unwrap().g().map(BoxTerm::copy))
+            .collect();
+
+        let mut total = 0;
+        for graph_name in graph_names {
+            let mut adapter = QuadsFilteredToGraph::new(dataset.quads(), graph_name.clone());
+            let mut emitted = 0;
+            adapter.for_each_triple(|_| emitted += 1).unwrap();
+            let expected = dataset
+                .quads()
+                .filter(|q| q.as_ref().unwrap().g().map(BoxTerm::copy) == graph_name)
+                .count();
+            assert_eq!(emitted, expected);
+            assert_eq!(adapter.dropped(), dataset.quads().count() - expected);
+            total += expected;
+        }
+        assert_eq!(total, dataset.quads().count());
+    }
+
+    #[test]
+    pub fn filters_parsed_quads_to_graph() {
+        Lazy::force(&TRACING);
+        let doc = r#"
+            <http://example.org/s> <http://example.org/p> "default" .
+            <http://example.org/s> <http://example.org/p> "in g" <http://example.org/g> .
+            <http://example.org/s> <http://example.org/p> "in h" <http://example.org/h> .
+        "#;
+        let quads = DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+            .unwrap()
+            .parse_str(doc);
+        let g = BoxTerm::new_iri("http://example.org/g").unwrap();
+        let mut adapter = QuadsFilteredToGraph::new(quads, Some(g));
+
+        let mut objects = Vec::new();
+        adapter
+            .for_each_triple(|t| objects.push(t.o().value().to_string()))
+            .unwrap();
+        assert_eq!(objects, vec!["in g".to_owned()]);
+        assert_eq!(adapter.dropped(), 2);
+    }
+
+    #[test]
+    pub fn roundtrips_graph_through_adapters() {
+        Lazy::force(&TRACING);
+        let g = BoxTerm::new_iri("http://example.org/g").unwrap();
+        let mut d1 = FastDataset::new();
+        let s = BoxTerm::new_iri("http://example.org/s").unwrap();
+        d1.insert(&s, &s, &s, Some(&g)).unwrap();
+        d1.insert(&s, &s, &s, None as Option<&BoxTerm>).unwrap();
+
+        let graph: FastGraph = QuadsFilteredToGraph::new(d1.quads(), Some(g.clone()))
+            .collect_triples()
+            .unwrap();
+        let d2: FastDataset = TriplesAsQuads::new(graph.triples(), Some(g))
+            .collect_quads()
+            .unwrap();
+        assert_eq!(d2.quads().count(), 1);
+    }
+}
@@ -0,0 +1,344 @@
+//! This module provides [`DatasetArchiveExporter`], that writes a dataset into an archive, with one file per graph in a chosen syntax.
+//!
+//! Graph files are named `default.<ext>` for default graph, and `graph-<n>.<ext>` for named graphs, with extension corresponding to chosen syntax. Named graphs are numbered in a stable order of their names. As each graph is a separate document, blank nodes are scoped to their graph file. Graph files in syntaxes that support quads retain their graph names too.
+//!
+//! Example:
+//!
+//! ```
+//! use std::io::Cursor;
+//!
+//! use rdf_dynsyn::{archive::{export::*, ArchiveFormat}, parser::quads::DynSynQuadParserFactory, syntax};
+//! use sophia_api::{parser::QuadParser, quad::stream::QuadSource};
+//! use sophia_inmem::dataset::FastDataset;
+//! use sophia_term::BoxTerm;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let dataset: FastDataset = DynSynQuadParserFactory::default()
+//!     .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)?
+//!     .parse_str(r#"
+//!         <http://example.org/a> <http://example.org/p> "a" .
+//!         <http://example.org/a> <http://example.org/p> "b" <http://example.org/g1> .
+//!     "#)
+//!     .collect_quads()?;
+//!
+//! let mut archive = Cursor::new(Vec::new());
+//! let manifest = DatasetArchiveExporter::new(ArchiveFormat::Zip, syntax::TURTLE)
+//!     .export(&dataset, &mut archive)?;
+//!
+//! let file_names: Vec<_> = manifest.entries.iter().map(|e| e.file_name.as_str()).collect();
+//! assert_eq!(file_names, vec!["default.ttl", "graph-1.ttl"]);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::{collections::HashSet, io};
+
+use sophia_api::{
+    dataset::Dataset,
+    quad::{stream::QuadSource, Quad},
+    serializer::QuadSerializer,
+    term::{CopyTerm, TTerm, TermKind},
+};
+use sophia_term::BoxTerm;
+
+use crate::{
+    correspondence::SYNTAX_TO_EXTENSION_CORRESPONDENCE,
+    serializer::{
+        nd_rdf_json::{write_json_str, write_term},
+        serializable::{DynSynSerializable, DynSynSerializeError, DynSynSerializerFactories},
+    },
+    syntax::{RdfSyntax, UnKnownSyntaxError},
+};
+
+use super::{ArchiveFormat, ArchiveWriter, MANIFEST_FILE_NAME};
+
+/// An entry in [`ArchiveManifest`], that records graph name of a graph file.
+#[derive(Debug, Clone)]
+pub struct ArchiveEntry {
+    pub file_name: String,
+    pub graph_name: Option<BoxTerm>,
+}
+
+/// Manifest of an archive, that records syntax of graph files, and graph name of each graph file.
+#[derive(Debug, Clone)]
+pub struct ArchiveManifest {
+    pub syntax: RdfSyntax,
+    pub entries: Vec<ArchiveEntry>,
+}
+
+impl ArchiveManifest {
+    /// Writes json representation of this manifest.
+    pub fn write_json<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(b"{\"syntax\":")?;
+        write_json_str(w, self.syntax.0, false)?;
+        w.write_all(b",\"entries\":[")?;
+        for (i, entry) in self.entries.iter().enumerate() {
+            if i > 0 {
+                w.write_all(b",")?;
+            }
+            w.write_all(b"{\"file\":")?;
+            write_json_str(w, &entry.file_name, false)?;
+            w.write_all(b",\"graph\":")?;
+            match &entry.graph_name {
+                Some(g) => write_term(w, g, false)?,
+                None => w.write_all(b"null")?,
+            }
+            w.write_all(b"}")?;
+        }
+        w.write_all(b"]}")
+    }
+}
+
+/// An exporter, that writes a dataset into an archive of given format, with each graph serialized into given syntax.
+pub struct DatasetArchiveExporter {
+    format: ArchiveFormat,
+    syntax_: RdfSyntax,
+    config: DynSynSerializerFactories,
+}
+
+impl DatasetArchiveExporter {
+    pub fn new(format: ArchiveFormat, syntax_: RdfSyntax) -> Self {
+        Self {
+            format,
+            syntax_,
+            config: DynSynSerializerFactories::default(),
+        }
+    }
+
+    /// Configures serializers, and hence formatting options, to be used for graph files.
+    pub fn with_config(mut self, config: DynSynSerializerFactories) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Exports given `dataset` into an archive, writing to `write`. Returns manifest of written archive.
+    ///
+    /// # Errors
+    /// returns [`DynSynSerializeError::UnKnownSyntax`] if configured syntax is not supported for serialization, or has no corresponding file extension.
+    pub fn export<D, W>(
+        &self,
+        dataset: &D,
+        write: W,
+    ) -> Result<ArchiveManifest, DynSynSerializeError<D::Error>>
+    where
+        D: Dataset,
+        W: io::Write + io::Seek,
+    {
+        let extension = SYNTAX_TO_EXTENSION_CORRESPONDENCE
+            .get(&self.syntax_)
+            .ok_or(UnKnownSyntaxError(self.syntax_))?
+            .value
+            .clone();
+
+        let mut has_default_graph = false;
+        let mut graph_names = HashSet::new();
+        for q in dataset.quads() {
+            let q = q.map_err(DynSynSerializeError::Source)?;
+            match q.g() {
+                Some(g) => {
+                    graph_names.insert(BoxTerm::copy(g));
+                }
+                None => has_default_graph = true,
+            }
+        }
+        let mut graph_names: Vec<BoxTerm> = graph_names.into_iter().collect();
+        graph_names.sort_by_key(|g| (g.kind() == TermKind::BlankNode, g.value().to_string()));
+
+        let mut entries = Vec::new();
+        if has_default_graph {
+            entries.push(ArchiveEntry {
+                file_name: format!("default.{}", extension),
+                graph_name: None,
+            });
+        }
+        for (i, g) in graph_names.into_iter().enumerate() {
+            entries.push(ArchiveEntry {
+                file_name: format!("graph-{}.{}", i + 1, extension),
+                graph_name: Some(g),
+            });
+        }
+        let manifest = ArchiveManifest {
+            syntax: self.syntax_,
+            entries,
+        };
+
+        let mut archive = ArchiveWriter::new(self.format, write);
+        let mut buf = Vec::new();
+        manifest
+            .write_json(&mut buf)
+            .and_then(|_| archive.append(MANIFEST_FILE_NAME, &buf))
+            .map_err(DynSynSerializeError::Io)?;
+
+        for entry in manifest.entries.iter() {
+            buf.clear();
+            let quads = dataset.quads_with_g(entry.graph_name.as_ref());
+            let quad_serializer_factory = &self.config.quad_serializer_factory;
+            if quad_serializer_factory.content_type(self.syntax_).is_ok() {
+                quad_serializer_factory
+                    .try_new_serializer(self.syntax_, &mut buf)
+                    .map_err(UnKnownSyntaxError::from)?
+                    .serialize_quads(quads)?;
+            } else {
+                quads
+                    .filter_map_quads(|q| {
+                        Some([
+                            BoxTerm::copy(q.s()),
+                            BoxTerm::copy(q.p()),
+                            BoxTerm::copy(q.o()),
+                        ])
+                    })
+                    .serialize_to(self.syntax_, &mut buf, &self.config)?;
+            }
+            archive
+                .append(&entry.file_name, &buf)
+                .map_err(DynSynSerializeError::Io)?;
+        }
+        archive.finish().map_err(DynSynSerializeError::Io)?;
+        Ok(manifest)
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read};
+
+    use once_cell::sync::Lazy;
+    use sophia_api::{
+        dataset::{isomorphic_datasets, MutableDataset},
+        parser::QuadParser,
+    };
+    use sophia_inmem::dataset::FastDataset;
+    use test_case::test_case;
+
+    use crate::{
+        error_code::{self, ErrorCode},
+        parser::quads::DynSynQuadParserFactory,
+        syntax,
+        tests::TRACING,
+    };
+
+    use super::*;
+
+    const DOC: &str = r#"
+        <http://example.org/a> <http://example.org/p> "a" .
+        <http://example.org/a> <http://example.org/p> _:b .
+        <http://example.org/a> <http://example.org/p> "b" <http://example.org/g2> .
+        <http://example.org/a> <http://example.org/p> "c" <http://example.org/g1> .
+        <http://example.org/a> <http://example.org/p> "d" _:g3 .
+    "#;
+
+    fn parse(doc: &[u8], syntax_: RdfSyntax, graph_name: Option<BoxTerm>) -> FastDataset {
+        DynSynQuadParserFactory::default()
+            .try_new_parser(syntax_, None, graph_name)
+            .unwrap()
+            .parse(doc)
+            .collect_quads()
+            .unwrap()
+    }
+
+    fn read_entries(format: ArchiveFormat, archive: Vec<u8>) -> Vec<(String, Vec<u8>)> {
+        let mut entries = Vec::new();
+        match format {
+            ArchiveFormat::Tar => {
+                let mut a = tar::Archive::new(Cursor::new(archive));
+                for e in a.entries().unwrap() {
+                    let mut e = e.unwrap();
+                    let name = e.path().unwrap().to_string_lossy().to_string();
+                    let mut data = Vec::new();
+                    e.read_to_end(&mut data).unwrap();
+                    entries.push((name, data));
+                }
+            }
+            ArchiveFormat::Zip => {
+                let mut a = zip::ZipArchive::new(Cursor::new(archive)).unwrap();
+                for i in 0..a.len() {
+                    let mut e = a.by_index(i).unwrap();
+                    let mut data = Vec::new();
+                    e.read_to_end(&mut data).unwrap();
+                    entries.push((e.name().to_string(), data));
+                }
+            }
+        }
+        entries
+    }
+
+    #[test_case(ArchiveFormat::Tar, syntax::TURTLE, "ttl")]
+    #[test_case(ArchiveFormat::Zip, syntax::TURTLE, "ttl")]
+    #[test_case(ArchiveFormat::Tar, syntax::N_TRIPLES, "nt")]
+    #[cfg_attr(feature = "rdf-xml", test_case(ArchiveFormat::Zip, syntax::RDF_XML, "rdf"))]
+    #[test_case(ArchiveFormat::Zip, syntax::N_QUADS, "nq")]
+    pub fn exports_one_file_per_graph(format: ArchiveFormat, syntax_: RdfSyntax, ext: &str) {
+        Lazy::force(&TRACING);
+        let dataset = parse(DOC.as_bytes(), syntax::N_QUADS, None);
+        let mut archive = Cursor::new(Vec::new());
+        let manifest = DatasetArchiveExporter::new(format, syntax_)
+            .export(&dataset, &mut archive)
+            .unwrap();
+
+        let entries = read_entries(format, archive.into_inner());
+        let names: Vec<_> = entries.iter().map(|(n, _)| n.clone()).collect();
+        assert_eq!(
+            names,
+            vec![
+                MANIFEST_FILE_NAME.to_string(),
+                format!("default.{}", ext),
+                format!("graph-1.{}", ext),
+                format!("graph-2.{}", ext),
+                format!("graph-3.{}", ext),
+            ]
+        );
+        assert_eq!(
+            manifest.entries[1]
+                .graph_name
+                .as_ref()
+                .unwrap()
+                .value()
+                .to_string(),
+            "http://example.org/g1"
+        );
+        assert_eq!(
+            manifest.entries[3].graph_name.as_ref().unwrap().kind(),
+            TermKind::BlankNode
+        );
+
+        let mut manifest_json = Vec::new();
+        manifest.write_json(&mut manifest_json).unwrap();
+        assert_eq!(entries[0].1, manifest_json);
+
+        let mut reassembled = FastDataset::new();
+        for (entry, (_, data)) in manifest.entries.iter().zip(entries[1..].iter()) {
+            let d = parse(data, syntax_, entry.graph_name.clone());
+            for q in d.quads() {
+                let q = q.unwrap();
+                reassembled.insert(q.s(), q.p(), q.o(), q.g()).unwrap();
+            }
+        }
+        assert!(isomorphic_datasets(&dataset, &reassembled).unwrap());
+    }
+
+    #[test]
+    pub fn omits_empty_default_graph() {
+        Lazy::force(&TRACING);
+        let dataset = parse(b"<tag:s> <tag:p> <tag:o> <tag:g> .", syntax::N_QUADS, None);
+        let manifest = DatasetArchiveExporter::new(ArchiveFormat::Tar, syntax::TURTLE)
+            .export(&dataset, Cursor::new(Vec::new()))
+            .unwrap();
+        assert_eq!(manifest.entries.len(), 1);
+        assert_eq!(manifest.entries[0].file_name, "graph-1.ttl");
+    }
+
+    #[test]
+    pub fn exporting_into_unsupported_syntax_will_error() {
+        Lazy::force(&TRACING);
+        let dataset = parse(DOC.as_bytes(), syntax::N_QUADS, None);
+        let e = DatasetArchiveExporter::new(ArchiveFormat::Tar, syntax::JSON_LD)
+            .export(&dataset, Cursor::new(Vec::new()))
+            .unwrap_err();
+        assert_eq!(e.code(), error_code::DYNSYN_UNKNOWN_SYNTAX);
+    }
+}
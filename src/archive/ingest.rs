@@ -0,0 +1,482 @@
+//! This module provides [`ArchiveIngester`], that parses rdf files out of tar/zip archives, complementing [`export`](super::export).
+//!
+//! Archive format is detected from the stream itself. Gzip-compressed tar archives are supported. Entries are read in a streaming fashion, hence archive stream need not be seekable.
+//!
+//! Syntax of each file is resolved from it's file extension. Files with extensions that don't correspond to any supported syntax are skipped, and are reported in [`ArchiveIngestSummary`]. If archive has a [manifest](super) as it's first entry, then triples of each graph file are put in graph recorded in manifest. Otherwise they are put in default graph.
+//!
+//! Example:
+//!
+//! ```
+//! use std::io::Cursor;
+//!
+//! use rdf_dynsyn::{archive::{export::*, ingest::*, ArchiveFormat}, parser::quads::DynSynQuadParserFactory, syntax};
+//! use sophia_api::{dataset::{isomorphic_datasets, Dataset}, parser::QuadParser, quad::stream::QuadSource};
+//! use sophia_inmem::dataset::FastDataset;
+//! use sophia_term::BoxTerm;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let dataset: FastDataset = DynSynQuadParserFactory::default()
+//!     .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)?
+//!     .parse_str(r#"
+//!         <http://example.org/a> <http://example.org/p> "a" .
+//!         <http://example.org/a> <http://example.org/p> "b" <http://example.org/g1> .
+//!     "#)
+//!     .collect_quads()?;
+//!
+//! let mut archive = Cursor::new(Vec::new());
+//! DatasetArchiveExporter::new(ArchiveFormat::Tar, syntax::TURTLE).export(&dataset, &mut archive)?;
+//!
+//! let mut ingested = FastDataset::new();
+//! let summary = ArchiveIngester::default().ingest_into(archive.into_inner().as_slice(), &mut ingested)?;
+//!
+//! assert_eq!(summary.ingested.len(), 2);
+//! assert!(isomorphic_datasets(&dataset, &ingested)?);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, BufReader, Read},
+};
+
+use flate2::read::MultiGzDecoder;
+use sophia_api::{
+    dataset::MutableDataset, parser::QuadParser, quad::stream::QuadSource,
+    triple::stream::StreamError,
+};
+use sophia_term::BoxTerm;
+
+use crate::{
+    correspondence::Correspondent,
+    error_code::{self, ErrorCode},
+    file_extension::FileExtension,
+    parser::{
+        errors::DynSynParseError,
+        quads::{source::DynSynQuadSource, DynSynQuadParserFactory},
+    },
+    syntax::RdfSyntax,
+};
+
+use super::MANIFEST_FILE_NAME;
+
+/// A quad source, that streams quads from a file in an archive.
+pub type ArchiveFileSource<'a> = DynSynQuadSource<BoxTerm, BufReader<&'a mut dyn Read>>;
+
+/// Details of a file in an archive, that is ingested.
+#[derive(Debug, Clone)]
+pub struct ArchiveFile {
+    pub file_name: String,
+    pub syntax: RdfSyntax,
+    /// Graph name, that triples of this file are put in. Quads in syntaxes that support quads retain their graph names.
+    pub graph_name: Option<BoxTerm>,
+}
+
+/// Summary of an archive ingestion.
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveIngestSummary {
+    /// Files, that are ingested.
+    pub ingested: Vec<ArchiveFile>,
+    /// Names of files, that are skipped, as their syntax couldn't be resolved or is not supported.
+    pub skipped: Vec<String>,
+}
+
+/// An error in ingesting an archive.
+#[derive(Debug, thiserror::Error)]
+pub enum ArchiveIngestError<E>
+where
+    E: std::error::Error + 'static,
+{
+    #[error("Error in reading archive: {0}")]
+    Io(#[from] io::Error),
+    #[error("Invalid archive manifest: {0}")]
+    InvalidManifest(String),
+    #[error("Error in parsing archive file {file_name}: {source}")]
+    Parse {
+        file_name: String,
+        #[source]
+        source: DynSynParseError,
+    },
+    #[error("Error in handling statements: {0}")]
+    Sink(#[source] E),
+}
+
+impl<E: std::error::Error + 'static> ErrorCode for ArchiveIngestError<E> {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::Io(_) => error_code::DYNSYN_PARSE_IO,
+            Self::InvalidManifest(_) => error_code::DYNSYN_ARCHIVE_MANIFEST,
+            Self::Parse { source, .. } => source.code(),
+            Self::Sink(_) => error_code::DYNSYN_STATEMENT_SINK,
+        }
+    }
+}
+
+/// An ingester, that parses rdf files out of archives.
+pub struct ArchiveIngester {
+    parser_factory: DynSynQuadParserFactory,
+}
+
+impl Default for ArchiveIngester {
+    fn default() -> Self {
+        Self::new(DynSynQuadParserFactory::default())
+    }
+}
+
+impl ArchiveIngester {
+    /// Instantiate an ingester, that parses archive files with parsers from given factory.
+    pub fn new(parser_factory: DynSynQuadParserFactory) -> Self {
+        Self { parser_factory }
+    }
+
+    /// Ingests archive from `read`, calling `f` with details, and quad source of each archive file, that has a supported syntax. Errors returned by `f` are wrapped in [`ArchiveIngestError::Sink`] variant.
+    pub fn ingest_with<R, F, E>(
+        &self,
+        read: R,
+        mut f: F,
+    ) -> Result<ArchiveIngestSummary, ArchiveIngestError<E>>
+    where
+        R: Read,
+        F: FnMut(&ArchiveFile, ArchiveFileSource<'_>) -> Result<(), E>,
+        E: std::error::Error + 'static,
+    {
+        let mut read = BufReader::new(read);
+        let magic = read.fill_buf()?;
+        let (is_gzip, is_zip) = (
+            magic.starts_with(&[0x1f, 0x8b]),
+            magic.starts_with(b"PK\x03\x04"),
+        );
+
+        let mut state = IngestState::default();
+        if is_zip {
+            while let Some(mut file) =
+                zip::read::read_zipfile_from_stream(&mut read).map_err(io::Error::from)?
+            {
+                if file.is_file() {
+                    let file_name = file.name().to_string();
+                    self.ingest_file(file_name, &mut file, &mut state, &mut f)?;
+                }
+            }
+        } else if is_gzip {
+            self.ingest_tar(MultiGzDecoder::new(read), &mut state, &mut f)?;
+        } else {
+            self.ingest_tar(read, &mut state, &mut f)?;
+        }
+        Ok(state.summary)
+    }
+
+    /// Ingests archive from `read`, inserting quads from all supported archive files into given `dataset`.
+    pub fn ingest_into<R, D>(
+        &self,
+        read: R,
+        dataset: &mut D,
+    ) -> Result<ArchiveIngestSummary, ArchiveIngestError<D::MutationError>>
+    where
+        R: Read,
+        D: MutableDataset,
+    {
+        let r = self.ingest_with(read, |file, source| {
+            source
+                .add_to_dataset(dataset)
+                .map(|_| ())
+                .map_err(|e| match e {
+                    StreamError::SourceError(source) => ArchiveIngestError::Parse {
+                        file_name: file.file_name.clone(),
+                        source,
+                    },
+                    StreamError::SinkError(e) => ArchiveIngestError::Sink(e),
+                })
+        });
+        r.map_err(|e| match e {
+            ArchiveIngestError::Io(e) => ArchiveIngestError::Io(e),
+            ArchiveIngestError::InvalidManifest(m) => ArchiveIngestError::InvalidManifest(m),
+            ArchiveIngestError::Parse { file_name, source } => {
+                ArchiveIngestError::Parse { file_name, source }
+            }
+            ArchiveIngestError::Sink(e) => e,
+        })
+    }
+
+    fn ingest_tar<R, F, E>(
+        &self,
+        read: R,
+        state: &mut IngestState,
+        f: &mut F,
+    ) -> Result<(), ArchiveIngestError<E>>
+    where
+        R: Read,
+        F: FnMut(&ArchiveFile, ArchiveFileSource<'_>) -> Result<(), E>,
+        E: std::error::Error + 'static,
+    {
+        let mut archive = tar::Archive::new(read);
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            if entry.header().entry_type().is_file() {
+                let file_name = entry.path()?.to_string_lossy().into_owned();
+                self.ingest_file(file_name, &mut entry, state, f)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn ingest_file<F, E>(
+        &self,
+        file_name: String,
+        data: &mut dyn Read,
+        state: &mut IngestState,
+        f: &mut F,
+    ) -> Result<(), ArchiveIngestError<E>>
+    where
+        F: FnMut(&ArchiveFile, ArchiveFileSource<'_>) -> Result<(), E>,
+        E: std::error::Error + 'static,
+    {
+        if file_name == MANIFEST_FILE_NAME {
+            state.graph_names = read_manifest_graph_names(data)?;
+            return Ok(());
+        }
+
+        let syntax = FileExtension::from_path_str(&file_name)
+            .and_then(|ext| Correspondent::<RdfSyntax>::try_from(&ext).ok())
+            .map(|c| c.value);
+        let graph_name = state.graph_names.get(&file_name).cloned().flatten();
+        let parser = syntax.and_then(|syntax| {
+            self.parser_factory
+                .try_new_parser::<BoxTerm>(syntax, None, graph_name.clone())
+                .ok()
+        });
+        let (syntax, parser) = match (syntax, parser) {
+            (Some(syntax), Some(parser)) => (syntax, parser),
+            _ => {
+                tracing::warn!("Skipping archive file {}", file_name);
+                state.summary.skipped.push(file_name);
+                return Ok(());
+            }
+        };
+
+        let file = ArchiveFile {
+            file_name,
+            syntax,
+            graph_name,
+        };
+        f(&file, parser.parse(BufReader::new(data))).map_err(ArchiveIngestError::Sink)?;
+        state.summary.ingested.push(file);
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct IngestState {
+    graph_names: HashMap<String, Option<BoxTerm>>,
+    summary: ArchiveIngestSummary,
+}
+
+/// Reads mapping from file names to graph names, from manifest.
+fn read_manifest_graph_names<E>(
+    data: &mut dyn Read,
+) -> Result<HashMap<String, Option<BoxTerm>>, ArchiveIngestError<E>>
+where
+    E: std::error::Error + 'static,
+{
+    let invalid = |m: &str| ArchiveIngestError::InvalidManifest(m.to_string());
+    let manifest: serde_json::Value =
+        serde_json::from_reader(data).map_err(|e| invalid(&e.to_string()))?;
+    let entries = manifest["entries"]
+        .as_array()
+        .ok_or_else(|| invalid("entries are missing"))?;
+
+    let mut graph_names = HashMap::new();
+    for entry in entries {
+        let file_name = entry["file"]
+            .as_str()
+            .ok_or_else(|| invalid("entry file is missing"))?;
+        let graph = &entry["graph"];
+        let graph_name = if graph.is_null() {
+            None
+        } else {
+            let value = graph["value"]
+                .as_str()
+                .ok_or_else(|| invalid("graph value is missing"))?;
+            let term = match graph["kind"].as_str() {
+                Some("iri") => BoxTerm::new_iri(value).ok(),
+                Some("bnode") => BoxTerm::new_bnode(value).ok(),
+                _ => None,
+            };
+            Some(term.ok_or_else(|| invalid("graph is not a valid iri or blank node"))?)
+        };
+        graph_names.insert(file_name.to_string(), graph_name);
+    }
+    Ok(graph_names)
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::Infallible, io::Cursor};
+
+    use flate2::{write::GzEncoder, Compression};
+    use once_cell::sync::Lazy;
+    use sophia_api::{
+        dataset::{isomorphic_datasets, Dataset},
+        quad::Quad,
+        term::TTerm,
+    };
+    use sophia_inmem::dataset::FastDataset;
+    use test_case::test_case;
+
+    use crate::{
+        archive::{export::DatasetArchiveExporter, ArchiveFormat, ArchiveWriter},
+        syntax,
+        tests::TRACING,
+    };
+
+    use super::*;
+
+    const DOC: &str = r#"
+        <http://example.org/a> <http://example.org/p> "a" .
+        <http://example.org/a> <http://example.org/p> _:b .
+        <http://example.org/a> <http://example.org/p> "b" <http://example.org/g2> .
+        <http://example.org/a> <http://example.org/p> "c" <http://example.org/g1> .
+        <http://example.org/a> <http://example.org/p> "d" _:g3 .
+    "#;
+
+    fn dataset() -> FastDataset {
+        DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+            .unwrap()
+            .parse_str(DOC)
+            .collect_quads()
+            .unwrap()
+    }
+
+    fn archive_of(format: ArchiveFormat, files: &[(&str, &str)]) -> Vec<u8> {
+        let mut archive = ArchiveWriter::new(format, Cursor::new(Vec::new()));
+        for (file_name, data) in files {
+            archive.append(file_name, data.as_bytes()).unwrap();
+        }
+        archive.finish().unwrap().into_inner()
+    }
+
+    fn gzipped(data: &[u8]) -> Vec<u8> {
+        let mut e = GzEncoder::new(Vec::new(), Compression::default());
+        io::Write::write_all(&mut e, data).unwrap();
+        e.finish().unwrap()
+    }
+
+    #[test_case(ArchiveFormat::Tar, syntax::TURTLE, false)]
+    #[cfg_attr(feature = "rdf-xml", test_case(ArchiveFormat::Tar, syntax::RDF_XML, true))]
+    #[test_case(ArchiveFormat::Zip, syntax::N_TRIPLES, false)]
+    #[test_case(ArchiveFormat::Zip, syntax::TRIG, false)]
+    pub fn ingests_exported_archives(format: ArchiveFormat, syntax_: RdfSyntax, gzip: bool) {
+        Lazy::force(&TRACING);
+        let dataset = dataset();
+        let mut archive = Cursor::new(Vec::new());
+        DatasetArchiveExporter::new(format, syntax_)
+            .export(&dataset, &mut archive)
+            .unwrap();
+        let mut archive = archive.into_inner();
+        if gzip {
+            archive = gzipped(&archive);
+        }
+
+        let mut ingested = FastDataset::new();
+        let summary = ArchiveIngester::default()
+            .ingest_into(archive.as_slice(), &mut ingested)
+            .unwrap();
+        assert_eq!(summary.ingested.len(), 4);
+        assert!(summary.ingested.iter().all(|f| f.syntax == syntax_));
+        assert!(summary.skipped.is_empty());
+        assert!(isomorphic_datasets(&dataset, &ingested).unwrap());
+    }
+
+    #[test_case(ArchiveFormat::Tar)]
+    #[test_case(ArchiveFormat::Zip)]
+    pub fn resolves_syntax_from_extension_and_skips_unknown_files(format: ArchiveFormat) {
+        Lazy::force(&TRACING);
+        let archive = archive_of(
+            format,
+            &[
+                ("README.md", "# data drop"),
+                ("data/a.ttl", "<tag:s> <tag:p> <tag:o1> ."),
+                ("data/b.nq", "<tag:s> <tag:p> <tag:o2> <tag:g> ."),
+                ("data/c.jsonld", "{}"),
+            ],
+        );
+
+        let mut files = Vec::new();
+        let summary = ArchiveIngester::default()
+            .ingest_with(archive.as_slice(), |file, mut source| {
+                let mut objects = Vec::new();
+                source
+                    .for_each_quad(|q| objects.push(q.o().value().to_string()))
+                    .unwrap();
+                files.push((file.file_name.clone(), file.syntax, objects));
+                Ok::<_, Infallible>(())
+            })
+            .unwrap();
+
+        assert_eq!(
+            files,
+            vec![
+                (
+                    "data/a.ttl".to_string(),
+                    syntax::TURTLE,
+                    vec!["tag:o1".to_string()]
+                ),
+                (
+                    "data/b.nq".to_string(),
+                    syntax::N_QUADS,
+                    vec!["tag:o2".to_string()]
+                ),
+            ]
+        );
+        assert_eq!(summary.skipped, vec!["README.md", "data/c.jsonld"]);
+    }
+
+    #[test]
+    pub fn reports_parse_errors_with_file_name() {
+        Lazy::force(&TRACING);
+        let archive = archive_of(ArchiveFormat::Tar, &[("bad.nt", "<tag:s> <tag:p> .")]);
+        let e = ArchiveIngester::default()
+            .ingest_into(archive.as_slice(), &mut FastDataset::new())
+            .unwrap_err();
+        assert!(matches!(&e, ArchiveIngestError::Parse { file_name, .. } if file_name == "bad.nt"));
+        assert_eq!(e.code(), error_code::DYNSYN_PARSE_SYNTAX);
+    }
+
+    #[test]
+    pub fn invalid_manifest_will_error() {
+        Lazy::force(&TRACING);
+        let archive = archive_of(
+            ArchiveFormat::Zip,
+            &[(MANIFEST_FILE_NAME, "{\"entries\":3}")],
+        );
+        let e = ArchiveIngester::default()
+            .ingest_into(archive.as_slice(), &mut FastDataset::new())
+            .unwrap_err();
+        assert_eq!(e.code(), error_code::DYNSYN_ARCHIVE_MANIFEST);
+    }
+
+    #[test]
+    pub fn puts_graph_files_in_graphs_from_manifest() {
+        Lazy::force(&TRACING);
+        let manifest =
+            r#"{"syntax":"x","entries":[{"file":"g.ttl","graph":{"kind":"iri","value":"tag:g"}}]}"#;
+        let archive = archive_of(
+            ArchiveFormat::Tar,
+            &[
+                (MANIFEST_FILE_NAME, manifest),
+                ("g.ttl", "<tag:s> <tag:p> <tag:o> ."),
+            ],
+        );
+        let mut ingested = FastDataset::new();
+        ArchiveIngester::default()
+            .ingest_into(archive.as_slice(), &mut ingested)
+            .unwrap();
+        let q = ingested.quads().next().unwrap().unwrap();
+        assert_eq!(q.g().unwrap().value().to_string(), "tag:g");
+    }
+}
@@ -0,0 +1,66 @@
+//! This module provides utilities to pack datasets into archives, with one file per graph, for distribution and backup, and to ingest rdf files out of archives.
+//!
+//! Each archive has a manifest file named [`MANIFEST_FILE_NAME`] as it's first entry. It is a json object that records syntax of graph files, and graph name of each graph file, like:
+//!
+//! ```text
+//! {"syntax":"http://www.w3.org/ns/formats/Turtle","entries":[{"file":"default.ttl","graph":null},{"file":"graph-1.ttl","graph":{"kind":"iri","value":"http://example.org/g1"}}]}
+//! ```
+//!
+//! Graph names are encoded same as terms in [`nd-rdf-json`](crate::serializer::nd_rdf_json) syntax.
+
+use std::io;
+
+pub mod export;
+pub mod ingest;
+
+/// Name of manifest file in archives.
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Archive formats, that are supported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Tar,
+    Zip,
+}
+
+/// A sum-type over writers of supported archive formats.
+pub(crate) enum ArchiveWriter<W: io::Write + io::Seek> {
+    Tar(tar::Builder<W>),
+    Zip(zip::ZipWriter<W>),
+}
+
+impl<W: io::Write + io::Seek> ArchiveWriter<W> {
+    pub(crate) fn new(format: ArchiveFormat, write: W) -> Self {
+        match format {
+            ArchiveFormat::Tar => Self::Tar(tar::Builder::new(write)),
+            ArchiveFormat::Zip => Self::Zip(zip::ZipWriter::new(write)),
+        }
+    }
+
+    /// Appends a file with given name, and content.
+    pub(crate) fn append(&mut self, file_name: &str, data: &[u8]) -> io::Result<()> {
+        match self {
+            Self::Tar(b) => {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(data.len() as u64);
+                header.set_mode(0o644);
+                header.set_cksum();
+                b.append_data(&mut header, file_name, data)
+            }
+            Self::Zip(z) => {
+                let options = zip::write::FileOptions::default()
+                    .compression_method(zip::CompressionMethod::Deflated);
+                z.start_file(file_name, options)?;
+                io::Write::write_all(z, data)
+            }
+        }
+    }
+
+    /// Writes trailing structures of archive, and returns underlying writer.
+    pub(crate) fn finish(self) -> io::Result<W> {
+        match self {
+            Self::Tar(b) => b.into_inner(),
+            Self::Zip(mut z) => Ok(z.finish()?),
+        }
+    }
+}
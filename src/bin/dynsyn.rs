@@ -0,0 +1,365 @@
+//! `dynsyn` command line tool, to convert rdf documents between syntaxes, to detect syntax of documents, and to validate them.
+//!
+//! ```text
+//! dynsyn convert -i data.ttl -o data.nq
+//! dynsyn convert --from turtle --to n-quads < data.ttl > data.nq
+//! dynsyn convert -i dump.nt -o dump.nq --keep-going
+//! dynsyn convert -i data.trig -o data.ttl --named-graphs merge
+//! dynsyn convert -i data/ -o converted/ --to n-quads --jobs 8
+//! dynsyn sniff data.rdf
+//! dynsyn validate data.trig
+//! ```
+//!
+//! Syntaxes can be given as their short names, like `turtle`, their media types, like `text/turtle`, or their spec iris. Otherwise syntax of a file is resolved from it's file extension, and syntax of input is sniffed from it's content as a last resort.
+//!
+//! If input of `convert` is a directory, it's files with rdf file extensions are converted concurrently into output directory, with same file stems. With `--keep-going`, malformed statements of n-triples, and n-quads documents are skipped, and reported with their line numbers, instead of failing conversion.
+
+use std::{
+    fs::{self, File},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    thread,
+};
+
+use clap::{Parser, Subcommand};
+use rdf_dynsyn::{
+    convert::{ConvertOptions, Converter},
+    correspondence::syntax_for_path,
+    error_code::{self, ErrorCode},
+    parser::{
+        quads::DynSynQuadParserFactory,
+        recovery::{ParserConfig, SkipSummary},
+        validate::DynSynValidator,
+    },
+    serializer::serializable::{DynSynSerializerFactories, NamedGraphAction},
+    syntax::RdfSyntax,
+    syntax_hint::sniff_syntax,
+};
+use type_map::concurrent::TypeMap;
+
+#[derive(Parser)]
+#[clap(
+    name = "dynsyn",
+    version,
+    about = "Convert, detect, and validate rdf documents in any supported syntax"
+)]
+struct Cli {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Convert a document, or files of a directory, from their syntax into another syntax.
+    Convert {
+        /// Input file, or directory. Reads from stdin, if not given.
+        #[clap(short, long)]
+        input: Option<PathBuf>,
+        /// Output file, or directory if input is a directory. Writes to stdout, if not given.
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+        /// Syntax of input. Resolved from input file extension, or sniffed from content, if not given. Syntaxes of files of a directory are always resolved from their extensions.
+        #[clap(long)]
+        from: Option<RdfSyntax>,
+        /// Syntax of output. Resolved from output file extension, if not given. Required, if input is a directory.
+        #[clap(long)]
+        to: Option<RdfSyntax>,
+        /// Base iri, against which relative iris in input are resolved. Relative iris in files of a directory are resolved against their `file:` iris.
+        #[clap(long)]
+        base: Option<String>,
+        /// Skip malformed statements, and keep going, where syntax allows to. Skipped statements are reported with their line numbers.
+        #[clap(long)]
+        keep_going: bool,
+        /// Action on statements in named graphs, if output syntax is triple-only: `error`, `drop`, or `merge` them into a single graph. Defaults to `error`.
+        #[clap(long, parse(try_from_str = parse_named_graph_action))]
+        named_graphs: Option<NamedGraphAction>,
+        /// Number of files of a directory to convert concurrently. Defaults to available parallelism.
+        #[clap(short, long)]
+        jobs: Option<usize>,
+    },
+    /// Detect syntax of a document, from it's file extension, or from it's content.
+    Sniff {
+        /// File to detect syntax of.
+        file: PathBuf,
+    },
+    /// Check, that a document parses without errors. Reports every malformed statement, where syntax allows to keep going.
+    Validate {
+        /// File to validate.
+        file: PathBuf,
+        /// Syntax of file. Resolved from file extension, or sniffed from content, if not given.
+        #[clap(long)]
+        syntax: Option<RdfSyntax>,
+    },
+}
+
+/// An error to report, with it's error code.
+struct CliError {
+    code: &'static str,
+    message: String,
+}
+
+impl<E: ErrorCode + std::fmt::Display> From<E> for CliError {
+    fn from(e: E) -> Self {
+        Self {
+            code: e.code(),
+            message: e.to_string(),
+        }
+    }
+}
+
+fn parse_named_graph_action(s: &str) -> Result<NamedGraphAction, String> {
+    match s {
+        "error" => Ok(NamedGraphAction::Error),
+        "drop" => Ok(NamedGraphAction::Drop),
+        "merge" => Ok(NamedGraphAction::Merge),
+        _ => Err(format!(
+            "Unknown action {:?}. Expected error, drop, or merge",
+            s
+        )),
+    }
+}
+
+fn io_error(path: Option<&Path>, e: io::Error) -> CliError {
+    CliError {
+        code: error_code::DYNSYN_CONVERT_IO,
+        message: match path {
+            Some(path) => format!("{}: {}", path.display(), e),
+            None => e.to_string(),
+        },
+    }
+}
+
+fn open_input(input: Option<&Path>) -> Result<Box<dyn BufRead>, CliError> {
+    Ok(match input {
+        Some(path) => Box::new(BufReader::new(
+            File::open(path).map_err(|e| io_error(Some(path), e))?,
+        )),
+        None => Box::new(BufReader::new(io::stdin())),
+    })
+}
+
+/// Resolve syntax of input, from explicit syntax, from file extension, or from start of it's content, in that order.
+fn input_syntax(
+    explicit: Option<RdfSyntax>,
+    path: Option<&Path>,
+    read: &mut dyn BufRead,
+) -> Result<RdfSyntax, CliError> {
+    if let Some(syntax_) = explicit.or_else(|| path.and_then(|p| syntax_for_path(p).ok())) {
+        return Ok(syntax_);
+    }
+    let head = read.fill_buf().map_err(|e| io_error(path, e))?;
+    sniff_syntax(head).ok_or_else(|| CliError {
+        code: error_code::DYNSYN_UNDETERMINED_SYNTAX,
+        message: "Syntax of input couldn't be determined. Specify it with --from, or --syntax"
+            .into(),
+    })
+}
+
+/// Report skipped statements of given document on stderr.
+fn report_skipped(doc: &str, summary: &SkipSummary) {
+    for skipped in &summary.skipped {
+        match skipped.line_number {
+            Some(line) => eprintln!("{}:{}: skipped: {}", doc, line, skipped.error),
+            None => eprintln!("{}: skipped: {}", doc, skipped.error),
+        }
+    }
+}
+
+fn undetermined_output_syntax() -> CliError {
+    CliError {
+        code: error_code::DYNSYN_UNDETERMINED_SYNTAX,
+        message: "Syntax of output couldn't be determined. Specify it with --to".into(),
+    }
+}
+
+/// Convert files of `in_dir`, that have rdf file extensions, into `out_dir`, with up to `jobs` files being converted concurrently. Every file is attempted, even if some of them fail.
+fn convert_dir(
+    in_dir: &Path,
+    out_dir: Option<&Path>,
+    syntax_out: Option<RdfSyntax>,
+    keep_going: bool,
+    named_graphs: NamedGraphAction,
+    jobs: Option<usize>,
+) -> Result<(), CliError> {
+    let syntax_out = syntax_out.ok_or_else(undetermined_output_syntax)?;
+    let out_dir = out_dir.ok_or_else(|| CliError {
+        code: error_code::DYNSYN_CONVERT_IO,
+        message: "Output directory must be given with --output, to convert a directory".into(),
+    })?;
+    let mut inputs = Vec::new();
+    for entry in fs::read_dir(in_dir).map_err(|e| io_error(Some(in_dir), e))? {
+        let path = entry.map_err(|e| io_error(Some(in_dir), e))?.path();
+        if path.is_file() && syntax_for_path(&path).is_ok() {
+            inputs.push(path);
+        }
+    }
+    inputs.sort();
+    fs::create_dir_all(out_dir).map_err(|e| io_error(Some(out_dir), e))?;
+
+    let mut parser_config_map = TypeMap::new();
+    parser_config_map.insert(ParserConfig {
+        lenient: keep_going,
+        ..Default::default()
+    });
+    let converter = Converter::new(
+        DynSynQuadParserFactory::new(Some(parser_config_map)),
+        DynSynSerializerFactories::default().with_named_graph_action(named_graphs),
+    );
+    let jobs = jobs.unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()));
+    let mut first_error = None;
+    let mut failed = 0;
+    for outcome in converter.convert_all(&inputs, out_dir, syntax_out, jobs) {
+        match outcome {
+            Ok(report) => {
+                report_skipped(&report.input.display().to_string(), &report.skip_summary);
+                eprintln!(
+                    "{} -> {}: {} statements",
+                    report.input.display(),
+                    report.output.display(),
+                    report.statements
+                );
+            }
+            Err(e) => {
+                let e = CliError::from(e);
+                eprintln!("error[{}]: {}", e.code, e.message);
+                failed += 1;
+                first_error.get_or_insert(e.code);
+            }
+        }
+    }
+    match first_error {
+        None => Ok(()),
+        Some(code) => Err(CliError {
+            code,
+            message: format!("{} of {} files couldn't be converted", failed, inputs.len()),
+        }),
+    }
+}
+
+fn run(command: Command) -> Result<(), CliError> {
+    match command {
+        Command::Convert {
+            input: Some(in_dir),
+            output,
+            to,
+            keep_going,
+            named_graphs,
+            jobs,
+            ..
+        } if in_dir.is_dir() => convert_dir(
+            &in_dir,
+            output.as_deref(),
+            to,
+            keep_going,
+            named_graphs.unwrap_or_default(),
+            jobs,
+        ),
+
+        Command::Convert {
+            input,
+            output,
+            from,
+            to,
+            base,
+            keep_going,
+            named_graphs,
+            ..
+        } => {
+            let mut read = open_input(input.as_deref())?;
+            let syntax_in = input_syntax(from, input.as_deref(), &mut read)?;
+            let syntax_out = match (to, &output) {
+                (Some(syntax_), _) => syntax_,
+                (None, Some(path)) => syntax_for_path(path)?,
+                (None, None) => return Err(undetermined_output_syntax()),
+            };
+            let write: Box<dyn Write> = match &output {
+                Some(path) => Box::new(File::create(path).map_err(|e| io_error(Some(path), e))?),
+                None => Box::new(io::stdout()),
+            };
+            let converter = Converter::new(
+                Default::default(),
+                DynSynSerializerFactories::default()
+                    .with_named_graph_action(named_graphs.unwrap_or_default()),
+            );
+            let stats = converter.convert(
+                read,
+                syntax_in,
+                BufWriter::new(write),
+                syntax_out,
+                &ConvertOptions {
+                    base_iri: base,
+                    keep_going,
+                },
+            )?;
+            let doc = input
+                .as_deref()
+                .map_or("<stdin>".into(), |p| p.display().to_string());
+            report_skipped(&doc, &stats.skip_summary);
+            match stats.skip_summary.skipped.len() {
+                0 => eprintln!("Converted {} statements", stats.statements),
+                n => eprintln!(
+                    "Converted {} statements, skipped {} malformed statements",
+                    stats.statements, n
+                ),
+            }
+            Ok(())
+        }
+
+        Command::Sniff { file } => {
+            if let Ok(syntax_) = syntax_for_path(&file) {
+                println!(
+                    "{}\t{}\tfile extension",
+                    syntax_.name().unwrap_or_default(),
+                    syntax_
+                );
+                return Ok(());
+            }
+            let mut read = open_input(Some(&file))?;
+            let syntax_ = input_syntax(None, None, &mut read)?;
+            println!(
+                "{}\t{}\tcontent",
+                syntax_.name().unwrap_or_default(),
+                syntax_
+            );
+            Ok(())
+        }
+
+        Command::Validate { file, syntax } => {
+            let mut read = open_input(Some(&file))?;
+            let syntax_ = input_syntax(syntax, Some(&file), &mut read)?;
+            let report = DynSynValidator::default().validate(read, syntax_, None)?;
+            for issue in &report.issues {
+                eprintln!("{}:{}", file.display(), issue);
+            }
+            match (report.issues.len(), report.complete) {
+                (0, _) => {
+                    println!("{}: ok, {} statements", file.display(), report.statements);
+                    Ok(())
+                }
+                (n, true) => Err(CliError {
+                    code: error_code::DYNSYN_PARSE_SYNTAX,
+                    message: format!("{}: {} malformed statements", file.display(), n),
+                }),
+                (n, false) => Err(CliError {
+                    code: error_code::DYNSYN_PARSE_SYNTAX,
+                    message: format!(
+                        "{}: {} malformed statements, before validation stopped",
+                        file.display(),
+                        n
+                    ),
+                }),
+            }
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse().command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error[{}]: {}", e.code, e.message);
+            ExitCode::FAILURE
+        }
+    }
+}
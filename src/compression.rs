@@ -0,0 +1,481 @@
+//! This module provides transparent decompression of parser inputs. Most public rdf dumps are compressed, like `wikidata.nt.gz`. Dynsyn parsers can parse them through [`parse_decompressed`](crate::parser::quads::DynSynQuadParser::parse_decompressed) methods, that wrap input reader in a decoder of it's [`Compression`] codec. Codec can be declared, like from file extension of the input through [`Compression::from_path`], or be detected from magic bytes of the input. It is available only when `compression` feature is enabled.
+//!
+//! Syntax of compressed files can be resolved from their paths as usual, as [`FileExtension::from_path`] strips compression suffixes.
+//!
+//! On write side, dynsyn serializer factories, whose config map has a [`CompressionConfig`], create serializers that compress their output, so that dumps like `data.nq.gz` can be exported without composing encoders manually.
+//!
+//! Example:
+//!
+//! ```
+//! use std::{io::Write, path::Path};
+//!
+//! use flate2::{write::GzEncoder, Compression as Level};
+//! use rdf_dynsyn::{compression::Compression, parser::quads::DynSynQuadParserFactory};
+//! use sophia_api::{dataset::Dataset, quad::stream::QuadSource};
+//! use sophia_inmem::dataset::FastDataset;
+//! use sophia_term::BoxTerm;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut encoder = GzEncoder::new(Vec::new(), Level::default());
+//! encoder.write_all(b"<http://e/s> <http://e/p> <http://e/o> .")?;
+//! let compressed = encoder.finish()?;
+//!
+//! let path = Path::new("dump.nt.gz");
+//! let parser = DynSynQuadParserFactory::default()
+//!     .try_new_parser_for_path::<BoxTerm>(path, None, None)?;
+//! let dataset: FastDataset = parser
+//!     .parse_decompressed(&compressed[..], Compression::from_path(path))
+//!     .collect_quads()?;
+//! assert_eq!(dataset.quads().count(), 1);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::{
+    fmt::Display,
+    io::{self, BufRead, BufReader, Read},
+    path::Path,
+};
+
+use sophia_api::{
+    parser::{QuadParser, TripleParser},
+    term::{CopyTerm, TTerm},
+};
+
+use crate::{
+    file_extension::{self as fextn, FileExtension},
+    parser::{
+        quads::{source::DynSynQuadSource, DynSynQuadParser},
+        triples::{source::DynSynTripleSource, DynSynTripleParser},
+    },
+};
+
+/// Compression codecs, that are supported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Compression {
+    Gzip,
+    Bzip2,
+    Xz,
+    Zstd,
+}
+
+impl Display for Compression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Gzip => "gzip",
+                Self::Bzip2 => "bzip2",
+                Self::Xz => "xz",
+                Self::Zstd => "zstd",
+            }
+        )
+    }
+}
+
+impl Compression {
+    /// Get codec corresponding to given compression file extension. Extension is matched case-insensitively.
+    pub fn from_extension(extension: &FileExtension) -> Option<Self> {
+        let extension = FileExtension::from(extension.to_ascii_lowercase());
+        match extension {
+            e if e == fextn::GZ => Some(Self::Gzip),
+            e if e == fextn::BZ2 => Some(Self::Bzip2),
+            e if e == fextn::XZ => Some(Self::Xz),
+            e if e == fextn::ZST => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Get codec corresponding to compression suffix of given `path`, like `.gz` of `data.ttl.gz`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        Self::from_extension(&FileExtension::compression_from_path(path)?)
+    }
+
+    /// Get codec, whose magic bytes given `prefix` of a stream starts with.
+    pub fn from_magic_bytes(prefix: &[u8]) -> Option<Self> {
+        if prefix.starts_with(&[0x1f, 0x8b]) {
+            Some(Self::Gzip)
+        } else if prefix.starts_with(b"BZh") {
+            Some(Self::Bzip2)
+        } else if prefix.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+            Some(Self::Xz)
+        } else if prefix.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Some(Self::Zstd)
+        } else {
+            None
+        }
+    }
+
+    /// Detect codec of given stream from it's magic bytes, without consuming them.
+    ///
+    /// Magic bytes are looked up in buffered data of `reader`, hence readers with tiny buffers may not be detected.
+    pub fn detect<R: BufRead>(reader: &mut R) -> io::Result<Option<Self>> {
+        Ok(Self::from_magic_bytes(reader.fill_buf()?))
+    }
+}
+
+/// Configuration of compression of serializer outputs. Serializers created by dynsyn serializer factories, whose config map has it, write their documents compressed with configured codec. Each serialized document is written as a complete gzip member, bzip2 stream, xz stream, or zstd frame. Stringifiers are never compressed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompressionConfig {
+    /// Codec to compress output with. Output is not compressed, if it is `None`.
+    pub compression: Option<Compression>,
+    /// Compression level. If it is `None`, default level of the codec is used.
+    pub level: Option<u32>,
+}
+
+impl CompressionConfig {
+    /// Create a new config to compress output with given codec, at it's default level.
+    pub fn new(compression: Compression) -> Self {
+        Self {
+            compression: Some(compression),
+            level: None,
+        }
+    }
+
+    /// Set compression level. Levels range from 0 to 9 for gzip, 1 to 9 for bzip2, 0 to 9 for xz, and 1 to 22 for zstd.
+    pub fn with_level(mut self, level: u32) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Get config to compress output written to given `path`, as per it's compression suffix, if any.
+    pub fn from_path(path: &Path) -> Self {
+        Self {
+            compression: Compression::from_path(path),
+            level: None,
+        }
+    }
+}
+
+/// A reader, that decompresses data of underlying reader, if it is compressed.
+pub struct DecompressedRead<R: BufRead>(Decoder<R>);
+
+enum Decoder<R: BufRead> {
+    Plain(R),
+    Gzip(BufReader<flate2::bufread::MultiGzDecoder<R>>),
+    Bzip2(BufReader<bzip2::bufread::MultiBzDecoder<R>>),
+    Xz(BufReader<xz2::bufread::XzDecoder<R>>),
+    Zstd(BufReader<zstd::stream::read::Decoder<'static, R>>),
+    /// A decoder, that couldn't be initialized. Error is reported on first read.
+    Failed(Compression, Option<io::Error>),
+}
+
+impl<R: BufRead> DecompressedRead<R> {
+    /// Wrap given reader in decoder of given `compression` codec. If codec is `None`, it is detected from magic bytes of the input, and input is read as is, if none is detected.
+    ///
+    /// Concatenated gzip members, bzip2 streams, xz streams, and zstd frames, as produced by parallel compressors, are decompressed in full.
+    pub fn new(mut read: R, compression: Option<Compression>) -> Self {
+        // An io error in detection will recur on parsing, and be reported then.
+        let compression = compression.or_else(|| Compression::detect(&mut read).ok().flatten());
+        Self(match compression {
+            None => Decoder::Plain(read),
+            Some(Compression::Gzip) => {
+                Decoder::Gzip(BufReader::new(flate2::bufread::MultiGzDecoder::new(read)))
+            }
+            Some(Compression::Bzip2) => {
+                Decoder::Bzip2(BufReader::new(bzip2::bufread::MultiBzDecoder::new(read)))
+            }
+            Some(Compression::Xz) => Decoder::Xz(BufReader::new(
+                xz2::bufread::XzDecoder::new_multi_decoder(read),
+            )),
+            Some(Compression::Zstd) => match zstd::stream::read::Decoder::with_buffer(read) {
+                Ok(decoder) => Decoder::Zstd(BufReader::new(decoder)),
+                Err(e) => Decoder::Failed(Compression::Zstd, Some(e)),
+            },
+        })
+    }
+
+    /// Returns codec, that data is being decompressed with, if any.
+    pub fn compression(&self) -> Option<Compression> {
+        match &self.0 {
+            Decoder::Plain(_) => None,
+            Decoder::Gzip(_) => Some(Compression::Gzip),
+            Decoder::Bzip2(_) => Some(Compression::Bzip2),
+            Decoder::Xz(_) => Some(Compression::Xz),
+            Decoder::Zstd(_) => Some(Compression::Zstd),
+            Decoder::Failed(c, _) => Some(*c),
+        }
+    }
+}
+
+fn failed(compression: Compression, error: &mut Option<io::Error>) -> io::Error {
+    error.take().unwrap_or_else(|| {
+        io::Error::other(format!("{} decoder couldn't be initialized", compression))
+    })
+}
+
+impl<R: BufRead> Read for DecompressedRead<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.0 {
+            Decoder::Plain(r) => r.read(buf),
+            Decoder::Gzip(r) => r.read(buf),
+            Decoder::Bzip2(r) => r.read(buf),
+            Decoder::Xz(r) => r.read(buf),
+            Decoder::Zstd(r) => r.read(buf),
+            Decoder::Failed(c, e) => Err(failed(*c, e)),
+        }
+    }
+}
+
+impl<R: BufRead> BufRead for DecompressedRead<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        match &mut self.0 {
+            Decoder::Plain(r) => r.fill_buf(),
+            Decoder::Gzip(r) => r.fill_buf(),
+            Decoder::Bzip2(r) => r.fill_buf(),
+            Decoder::Xz(r) => r.fill_buf(),
+            Decoder::Zstd(r) => r.fill_buf(),
+            Decoder::Failed(c, e) => Err(failed(*c, e)),
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        match &mut self.0 {
+            Decoder::Plain(r) => r.consume(amt),
+            Decoder::Gzip(r) => r.consume(amt),
+            Decoder::Bzip2(r) => r.consume(amt),
+            Decoder::Xz(r) => r.consume(amt),
+            Decoder::Zstd(r) => r.consume(amt),
+            Decoder::Failed(..) => {}
+        }
+    }
+}
+
+impl<T> DynSynQuadParser<T>
+where
+    T: TTerm + CopyTerm + Clone,
+{
+    /// Parses `data`, decompressing it with given `compression` codec. If codec is `None`, it is detected from magic bytes of the input, and uncompressed inputs are parsed as is.
+    pub fn parse_decompressed<R: BufRead>(
+        &self,
+        data: R,
+        compression: Option<Compression>,
+    ) -> DynSynQuadSource<T, DecompressedRead<R>> {
+        self.parse(DecompressedRead::new(data, compression))
+    }
+}
+
+impl<T> DynSynTripleParser<T>
+where
+    T: TTerm + CopyTerm + Clone,
+{
+    /// Parses `data`, decompressing it with given `compression` codec. If codec is `None`, it is detected from magic bytes of the input, and uncompressed inputs are parsed as is.
+    pub fn parse_decompressed<R: BufRead>(
+        &self,
+        data: R,
+        compression: Option<Compression>,
+    ) -> DynSynTripleSource<T, DecompressedRead<R>> {
+        self.parse(DecompressedRead::new(data, compression))
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        path::Path,
+    };
+
+    use once_cell::sync::Lazy;
+    use sophia_api::{
+        dataset::Dataset,
+        graph::{isomorphic_graphs, Graph},
+        parser::{QuadParser, TripleParser},
+        quad::stream::QuadSource,
+        serializer::{QuadSerializer, Stringifier, TripleSerializer},
+        triple::stream::TripleSource,
+    };
+    use sophia_inmem::{dataset::FastDataset, graph::FastGraph};
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+
+    use type_map::concurrent::TypeMap;
+
+    use super::{Compression, CompressionConfig, DecompressedRead};
+    use crate::{
+        error_code::{self, ErrorCode},
+        parser::{quads::DynSynQuadParserFactory, triples::DynSynTripleParserFactory},
+        serializer::{quads::DynSynQuadSerializerFactory, triples::DynSynTripleSerializerFactory},
+        syntax,
+        tests::TRACING,
+    };
+
+    static DOC: &str = "<tag:s> <tag:p> <tag:o1> .\n<tag:s> <tag:p> <tag:o2> .\n";
+
+    fn compress(compression: Option<Compression>, data: &[u8]) -> Vec<u8> {
+        match compression {
+            None => data.to_vec(),
+            Some(Compression::Gzip) => {
+                let mut e = flate2::write::GzEncoder::new(Vec::new(), Default::default());
+                e.write_all(data).unwrap();
+                e.finish().unwrap()
+            }
+            Some(Compression::Bzip2) => {
+                let mut e = bzip2::write::BzEncoder::new(Vec::new(), Default::default());
+                e.write_all(data).unwrap();
+                e.finish().unwrap()
+            }
+            Some(Compression::Xz) => {
+                let mut e = xz2::write::XzEncoder::new(Vec::new(), 6);
+                e.write_all(data).unwrap();
+                e.finish().unwrap()
+            }
+            Some(Compression::Zstd) => zstd::encode_all(data, 0).unwrap(),
+        }
+    }
+
+    #[test_case("dump.nt.gz", Some(Compression::Gzip))]
+    #[test_case("dump.ttl.BZ2", Some(Compression::Bzip2))]
+    #[test_case("dump.nq.xz", Some(Compression::Xz))]
+    #[test_case("dump.nq.zst", Some(Compression::Zstd))]
+    #[test_case("dump.nq.br", None)]
+    #[test_case("dump.nq", None)]
+    pub fn resolves_compression_from_path(path: &str, expected: Option<Compression>) {
+        Lazy::force(&TRACING);
+        assert_eq!(Compression::from_path(Path::new(path)), expected);
+    }
+
+    #[test_case(None)]
+    #[test_case(Some(Compression::Gzip))]
+    #[test_case(Some(Compression::Bzip2))]
+    #[test_case(Some(Compression::Xz))]
+    #[test_case(Some(Compression::Zstd))]
+    pub fn detects_compression_from_magic_bytes(compression: Option<Compression>) {
+        Lazy::force(&TRACING);
+        let data = compress(compression, DOC.as_bytes());
+        let read = DecompressedRead::new(&data[..], None);
+        assert_eq!(read.compression(), compression);
+    }
+
+    #[test_case(None, None)]
+    #[test_case(Some(Compression::Gzip), None)]
+    #[test_case(Some(Compression::Bzip2), Some(Compression::Bzip2))]
+    #[test_case(Some(Compression::Xz), Some(Compression::Xz))]
+    #[test_case(Some(Compression::Zstd), None)]
+    pub fn parses_decompressed_inputs(
+        compression: Option<Compression>,
+        declared: Option<Compression>,
+    ) {
+        Lazy::force(&TRACING);
+        let data = compress(compression, DOC.as_bytes());
+
+        let parser = DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::N_TRIPLES, None, None)
+            .unwrap();
+        let dataset: FastDataset = parser
+            .parse_decompressed(&data[..], declared)
+            .collect_quads()
+            .unwrap();
+        assert_eq!(dataset.quads().count(), 2);
+
+        let parser = DynSynTripleParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)
+            .unwrap();
+        let graph: FastGraph = parser
+            .parse_decompressed(&data[..], declared)
+            .collect_triples()
+            .unwrap();
+        assert_eq!(graph.triples().count(), 2);
+    }
+
+    #[test]
+    pub fn decompresses_concatenated_members() {
+        Lazy::force(&TRACING);
+        let (first, second) = DOC.split_at(DOC.find('\n').unwrap() + 1);
+        let mut data = compress(Some(Compression::Gzip), first.as_bytes());
+        data.extend(compress(Some(Compression::Gzip), second.as_bytes()));
+        let dataset: FastDataset = DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+            .unwrap()
+            .parse_decompressed(&data[..], None)
+            .collect_quads()
+            .unwrap();
+        assert_eq!(dataset.quads().count(), 2);
+    }
+
+    #[test]
+    pub fn corrupt_inputs_will_have_io_error_code() {
+        Lazy::force(&TRACING);
+        let mut data = compress(Some(Compression::Xz), DOC.as_bytes());
+        data.truncate(data.len() / 2);
+        let e = DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+            .unwrap()
+            .parse_decompressed(&data[..], None)
+            .collect_quads::<FastDataset>()
+            .map(|_| ())
+            .unwrap_err();
+        match e {
+            sophia_api::triple::stream::StreamError::SourceError(e) => {
+                assert_eq!(e.code(), error_code::DYNSYN_PARSE_IO)
+            }
+            _ => panic!("expected a source error"),
+        }
+    }
+
+    #[test_case(Compression::Gzip)]
+    #[test_case(Compression::Bzip2)]
+    #[test_case(Compression::Xz)]
+    #[test_case(Compression::Zstd)]
+    pub fn serializers_compress_each_document(compression: Compression) {
+        Lazy::force(&TRACING);
+        let dataset: FastDataset = DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+            .unwrap()
+            .parse_str(DOC)
+            .collect_quads()
+            .unwrap();
+        let mut config_map = TypeMap::new();
+        config_map.insert(CompressionConfig::new(compression).with_level(1));
+        let factory = DynSynQuadSerializerFactory::new(Some(config_map));
+
+        let mut serializer = factory
+            .try_new_serializer(syntax::N_QUADS, Vec::new())
+            .unwrap();
+        serializer.serialize_dataset(&dataset).unwrap();
+        serializer.serialize_dataset(&dataset).unwrap();
+        let data = serializer.as_utf8().to_vec();
+        assert_eq!(Compression::from_magic_bytes(&data), Some(compression));
+
+        // Stringifiers are never compressed.
+        let mut stringifier = factory.try_new_stringifier(syntax::N_QUADS).unwrap();
+        let doc = stringifier.serialize_dataset(&dataset).unwrap().as_str();
+        assert_eq!(doc.lines().count(), 2);
+
+        let mut decompressed = String::new();
+        DecompressedRead::new(&data[..], None)
+            .read_to_string(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, doc.repeat(2));
+    }
+
+    #[test]
+    pub fn triple_serializers_compress_output() {
+        Lazy::force(&TRACING);
+        let graph: FastGraph = DynSynTripleParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::N_TRIPLES, None, None)
+            .unwrap()
+            .parse_str(DOC)
+            .collect_triples()
+            .unwrap();
+        let mut config_map = TypeMap::new();
+        config_map.insert(CompressionConfig::from_path(Path::new("out.ttl.gz")));
+        let mut serializer = DynSynTripleSerializerFactory::new(Some(config_map))
+            .try_new_serializer(syntax::TURTLE, Vec::new())
+            .unwrap();
+        serializer.serialize_graph(&graph).unwrap();
+
+        let reparsed: FastGraph = DynSynTripleParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)
+            .unwrap()
+            .parse_decompressed(serializer.as_utf8(), None)
+            .collect_triples()
+            .unwrap();
+        assert!(isomorphic_graphs(&graph, &reparsed).unwrap());
+    }
+}
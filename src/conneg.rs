@@ -0,0 +1,176 @@
+//! This module provides content negotiation, that resolves best rdf syntax for an http `Accept` header, among syntaxes actually supported by dynsyn parsers, or serializers in this build. Servers, like LDP, or SPARQL endpoints can use it to choose response syntax, or to advertise accepted request syntaxes, without hand rolling it on top of correspondence maps.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{
+//!     conneg::{resolve_syntax_for_accept, ParserOrSerializer},
+//!     syntax,
+//! };
+//!
+//! assert_eq!(
+//!     resolve_syntax_for_accept(
+//!         "application/ld+json, text/turtle;q=0.8, */*;q=0.1",
+//!         ParserOrSerializer::Serializer
+//!     ),
+//!     Some(syntax::TURTLE)
+//! );
+//! assert_eq!(
+//!     resolve_syntax_for_accept("application/ld+json", ParserOrSerializer::Parser),
+//!     None
+//! );
+//! ```
+
+use mime::Mime;
+
+use crate::{
+    correspondence::{MEDIA_TYPE_TO_SYNTAX_CORRESPONDENCE, SYNTAX_TO_MEDIA_TYPE_CORRESPONDENCE},
+    support::SyntaxSupport,
+    syntax::{RdfSyntax, KNOWN_SYNTAXES},
+};
+
+/// Role of dynsyn component, for which syntax is being negotiated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParserOrSerializer {
+    /// Negotiate among syntaxes, that can be parsed.
+    Parser,
+    /// Negotiate among syntaxes, that can be serialized into.
+    Serializer,
+}
+
+impl ParserOrSerializer {
+    fn supports(&self, syntax_: RdfSyntax) -> bool {
+        let support = SyntaxSupport::of(syntax_);
+        match self {
+            Self::Parser => support.quad_parsing || support.triple_parsing,
+            Self::Serializer => support.quad_serialization || support.triple_serialization,
+        }
+    }
+}
+
+/// Resolve best syntax for value of an `Accept` header, among syntaxes supported for given `role` in this build.
+///
+/// Each supported syntax gets quality value of most specific media range, that matches it's canonical media type. Syntax with highest quality value is chosen, and ties are broken by order of [`KNOWN_SYNTAXES`]. Media ranges that can't be parsed are ignored. If request has no `Accept` header, `*/*` should be passed.
+///
+/// Returns `None`, if no supported syntax is acceptable.
+pub fn resolve_syntax_for_accept(accept: &str, role: ParserOrSerializer) -> Option<RdfSyntax> {
+    let candidates = KNOWN_SYNTAXES.into_iter().filter_map(|syntax_| {
+        let media_type = SYNTAX_TO_MEDIA_TYPE_CORRESPONDENCE.get(&syntax_)?.value;
+        role.supports(syntax_)
+            .then(|| (syntax_, media_type.clone()))
+    });
+    best_match(accept, candidates).map(|(syntax_, _)| syntax_)
+}
+
+/// Choose best match for value of an `Accept` header, among `candidates`, that pair syntaxes with their content types. Candidates are expected in order of preference, that breaks ties.
+pub(crate) fn best_match(
+    accept: &str,
+    candidates: impl IntoIterator<Item = (RdfSyntax, Mime)>,
+) -> Option<(RdfSyntax, Mime)> {
+    let ranges = parse_accept(accept);
+    let mut chosen: Option<((RdfSyntax, Mime), f32)> = None;
+    for (syntax_, content_type) in candidates {
+        let q = match quality(&ranges, syntax_, &content_type) {
+            Some(q) if q > 0.0 => q,
+            _ => continue,
+        };
+        if !matches!(&chosen, Some((_, chosen_q)) if *chosen_q >= q) {
+            chosen = Some(((syntax_, content_type), q));
+        }
+    }
+    chosen.map(|(candidate, _)| candidate)
+}
+
+/// Parse media ranges of an `Accept` header, along with their quality values.
+fn parse_accept(accept: &str) -> Vec<(Mime, f32)> {
+    accept
+        .split(',')
+        .filter_map(|range| {
+            let range: Mime = range.trim().parse().ok()?;
+            let q = match range.get_param("q") {
+                Some(q) => q
+                    .as_str()
+                    .parse()
+                    .ok()
+                    .filter(|q| (0.0..=1.0).contains(q))?,
+                None => 1.0,
+            };
+            Some((range, q))
+        })
+        .collect()
+}
+
+/// Get quality value of most specific range in `ranges`, that matches `content_type` of `syntax_`. Ranges, that correspond to `syntax_` exclusively, like aliases of it's media type, match it too.
+fn quality(ranges: &[(Mime, f32)], syntax_: RdfSyntax, content_type: &Mime) -> Option<f32> {
+    ranges
+        .iter()
+        .filter_map(|(range, q)| {
+            let specificity = if range.type_() == mime::STAR {
+                0
+            } else if range.type_() != content_type.type_() {
+                if !is_total_alias(range, syntax_) {
+                    return None;
+                }
+                2
+            } else if range.subtype() == mime::STAR {
+                1
+            } else if range.subtype() == content_type.subtype() || is_total_alias(range, syntax_) {
+                2
+            } else {
+                return None;
+            };
+            Some((specificity, *q))
+        })
+        .max_by_key(|(specificity, _)| *specificity)
+        .map(|(_, q)| q)
+}
+
+fn is_total_alias(range: &Mime, syntax_: RdfSyntax) -> bool {
+    range
+        .essence_str()
+        .parse::<Mime>()
+        .ok()
+        .and_then(|essence| MEDIA_TYPE_TO_SYNTAX_CORRESPONDENCE.get(&essence).cloned())
+        .is_some_and(|c| c.is_total && c.value == syntax_)
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use test_case::test_case;
+
+    use super::{resolve_syntax_for_accept, ParserOrSerializer};
+    use crate::{
+        syntax::{self, RdfSyntax},
+        tests::TRACING,
+    };
+
+    #[test_case("text/turtle", Some(syntax::TURTLE))]
+    #[test_case("*/*", Some(syntax::TURTLE))]
+    #[test_case("application/ld+json;q=1, application/trig;q=0.5", Some(syntax::TRIG))]
+    #[test_case("application/x-nd-rdf-json", Some(syntax::ND_RDF_JSON))]
+    #[test_case("text/html, image/*", None)]
+    #[test_case("*/*;q=0", None)]
+    pub fn resolves_syntax_for_serializer(accept: &str, expected: Option<RdfSyntax>) {
+        Lazy::force(&TRACING);
+        assert_eq!(
+            resolve_syntax_for_accept(accept, ParserOrSerializer::Serializer),
+            expected
+        );
+    }
+
+    #[test_case("application/n-triples;q=0.4, text/turtle;q=0.6", Some(syntax::TURTLE))]
+    #[test_case("application/trig", Some(syntax::TRIG))]
+    #[test_case("application/x-nd-rdf-json", None)]
+    #[test_case("application/ld+json", None)]
+    pub fn resolves_syntax_for_parser(accept: &str, expected: Option<RdfSyntax>) {
+        Lazy::force(&TRACING);
+        assert_eq!(
+            resolve_syntax_for_accept(accept, ParserOrSerializer::Parser),
+            expected
+        );
+    }
+}
@@ -0,0 +1,624 @@
+//! This module provides conversion of rdf documents from their syntax into another syntax. [`convert`] converts a single document from a reader into a writer, which is the most common end-to-end use of this crate. [`Converter::convert_all`] converts many files on disk concurrently, and reports outcome for each file, which suits directory conversions, and data pipelines.
+//!
+//! Documents are converted by piping a dynsyn parser into a dynsyn serializer, without materializing an intermediate dataset. Hence memory use doesn't grow with size of document, except for whatever serializers of pretty syntaxes buffer themselves.
+//!
+//! With [`keep_going`](ConvertOptions::keep_going) option, malformed statements of input document are skipped, instead of failing conversion, and are reported with their line numbers in [`ConversionStats::skip_summary`]. It is supported for line-based syntaxes, as described in [`recovery`](crate::parser::recovery) module.
+//!
+//! Syntax of each input file is resolved from it's file extension. Converted file is written into output directory, with same file stem as input file, and with canonical file extension of output syntax.
+//!
+//! Example:
+//!
+//! ```
+//! use std::fs;
+//!
+//! use rdf_dynsyn::{convert::Converter, syntax};
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let dir = std::env::temp_dir().join("rdf_dynsyn_convert_doc");
+//! fs::create_dir_all(&dir)?;
+//! let input = dir.join("alice.ttl");
+//! fs::write(&input, "<http://example.org/alice> <http://example.org/name> \"Alice\" .")?;
+//!
+//! let reports = Converter::default().convert_all(&[input], &dir, syntax::N_TRIPLES, 2);
+//! let report = reports[0].as_ref().unwrap();
+//! assert_eq!(report.output, dir.join("alice.nt"));
+//! assert_eq!(report.statements, 1);
+//! # fs::remove_dir_all(&dir)?;
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+//!
+//! Converting a single document in memory:
+//!
+//! ```
+//! use rdf_dynsyn::{
+//!     convert::{convert, ConvertOptions},
+//!     syntax,
+//! };
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let doc = "@prefix : <http://example.org/> . :alice :knows :bob, :carol .";
+//! let mut out = Vec::new();
+//! let stats = convert(
+//!     doc.as_bytes(),
+//!     syntax::TURTLE,
+//!     &mut out,
+//!     syntax::N_TRIPLES,
+//!     &ConvertOptions::default(),
+//! )?;
+//! assert_eq!(stats.statements, 2);
+//! assert_eq!(String::from_utf8(out)?.lines().count(), 2);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::{
+    fs::{self, File},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+use once_cell::sync::Lazy;
+use sophia_api::parser::QuadParser;
+use sophia_term::BoxTerm;
+
+use crate::{
+    correspondence::{output_path_for, Correspondent},
+    error_code::{self, ErrorCode},
+    file_extension::FileExtension,
+    parser::{errors::DynSynParseError, quads::DynSynQuadParserFactory, recovery::SkipSummary},
+    serializer::{
+        _inner::counted::Counted,
+        serializable::{DynSynSerializable, DynSynSerializeError, DynSynSerializerFactories},
+    },
+    syntax::{RdfSyntax, UnKnownSyntaxError},
+};
+
+/// Report of a converted file.
+#[derive(Debug)]
+pub struct ConversionReport {
+    /// Path of input file.
+    pub input: PathBuf,
+    /// Path of written output file.
+    pub output: PathBuf,
+    /// Syntax of input file.
+    pub syntax_in: RdfSyntax,
+    /// Number of statements read from input file. Statements in named graphs are counted, even if they are dropped from a triple-only output syntax, as per [`NamedGraphAction`](crate::serializer::serializable::NamedGraphAction).
+    pub statements: usize,
+    /// Summary of malformed statements, that are skipped, if parsers of converter are lenient.
+    pub skip_summary: SkipSummary,
+}
+
+/// Statistics of a converted document.
+#[derive(Debug, Default)]
+pub struct ConversionStats {
+    /// Number of statements read from input document. Statements in named graphs are counted, even if they are dropped from a triple-only output syntax, as per [`NamedGraphAction`](crate::serializer::serializable::NamedGraphAction).
+    pub statements: usize,
+    /// Summary of malformed statements, that are skipped in keep-going mode.
+    pub skip_summary: SkipSummary,
+}
+
+/// Options for converting a single document.
+#[derive(Debug, Clone, Default)]
+pub struct ConvertOptions {
+    /// Base iri, against which relative iris in input document are resolved.
+    pub base_iri: Option<String>,
+    /// If true, malformed statements are skipped, and conversion keeps going, in syntaxes that support it. Else conversion fails on first malformed statement.
+    pub keep_going: bool,
+}
+
+/// An error in converting a file, or a document.
+#[derive(Debug, thiserror::Error)]
+pub enum ConvertError {
+    #[error("Syntax of file {0:?} couldn't be determined from it's extension")]
+    UnDeterminedSyntax(PathBuf),
+    #[error(transparent)]
+    UnKnownSyntax(#[from] UnKnownSyntaxError),
+    #[error("Io error in converting file {0:?}: {1}")]
+    Io(PathBuf, #[source] io::Error),
+    #[error("Error in converting file {0:?}: {1}")]
+    Serialize(PathBuf, #[source] DynSynSerializeError<DynSynParseError>),
+    #[error("Error in converting document: {0}")]
+    Document(#[source] DynSynSerializeError<DynSynParseError>),
+}
+
+impl ErrorCode for ConvertError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::UnDeterminedSyntax(_) => error_code::DYNSYN_UNDETERMINED_SYNTAX,
+            Self::UnKnownSyntax(e) => e.code(),
+            Self::Io(_, _) => error_code::DYNSYN_CONVERT_IO,
+            Self::Serialize(_, DynSynSerializeError::Source(e)) => e.code(),
+            Self::Serialize(_, e) => e.code(),
+            Self::Document(DynSynSerializeError::Source(e)) => e.code(),
+            Self::Document(e) => e.code(),
+        }
+    }
+}
+
+/// Converts rdf files from their syntax into another syntax.
+#[derive(Default)]
+pub struct Converter {
+    parser_factory: DynSynQuadParserFactory,
+    serializer_factories: DynSynSerializerFactories,
+}
+
+impl Converter {
+    /// Create a new converter, with given parser factory, and serializer config.
+    pub fn new(
+        parser_factory: DynSynQuadParserFactory,
+        serializer_factories: DynSynSerializerFactories,
+    ) -> Self {
+        Self {
+            parser_factory,
+            serializer_factories,
+        }
+    }
+
+    /// Converts document in `syntax_in` from `data` into `syntax_out`, writing to `write`. Writer is flushed after conversion.
+    ///
+    /// # Errors
+    /// returns [`ConvertError`] if either of syntaxes is not supported, or if conversion fails. Output may have been partially written on failure.
+    pub fn convert<R, W>(
+        &self,
+        data: R,
+        syntax_in: RdfSyntax,
+        mut write: W,
+        syntax_out: RdfSyntax,
+        options: &ConvertOptions,
+    ) -> Result<ConversionStats, ConvertError>
+    where
+        R: BufRead,
+        W: Write,
+    {
+        self.serializer_factories.content_type(syntax_out)?;
+        let mut parser = self.parser_factory.try_new_parser::<BoxTerm>(
+            syntax_in,
+            options.base_iri.clone(),
+            None,
+        )?;
+        if options.keep_going {
+            parser.set_lenient(true);
+        }
+
+        let mut source = parser.parse(data);
+        let mut statements = 0;
+        Counted::new(&mut source, &mut statements)
+            .serialize_to(syntax_out, &mut write, &self.serializer_factories)
+            .and_then(|_| write.flush().map_err(DynSynSerializeError::Io))
+            .map_err(ConvertError::Document)?;
+        Ok(ConversionStats {
+            statements,
+            skip_summary: source.take_skip_summary(),
+        })
+    }
+
+    /// Converts file at `input` into `out_syntax`, writing into `out_dir`. Relative iris in input are resolved against it's `file:` iri. Partially written output file is removed on error. Malformed statements are skipped, and reported in [`ConversionReport::skip_summary`], if parser factory of converter is configured with a lenient [`ParserConfig`](crate::parser::recovery::ParserConfig).
+    ///
+    /// # Errors
+    /// returns [`ConvertError`] if syntax of input can't be determined, if output syntax is not supported, or if conversion fails.
+    pub fn convert_file(
+        &self,
+        input: &Path,
+        out_dir: &Path,
+        out_syntax: RdfSyntax,
+    ) -> Result<ConversionReport, ConvertError> {
+        let output = out_dir.join(
+            output_path_for(Path::new(input.file_name().unwrap_or_default()), out_syntax)
+                .ok_or(UnKnownSyntaxError(out_syntax))?,
+        );
+        self.serializer_factories.content_type(out_syntax)?;
+        let syntax_in = FileExtension::from_path(input)
+            .map(|extension| FileExtension::from(extension.to_ascii_lowercase()))
+            .and_then(|extension| Correspondent::<RdfSyntax>::try_from(&extension).ok())
+            .ok_or_else(|| ConvertError::UnDeterminedSyntax(input.to_owned()))?
+            .value;
+        let parser =
+            self.parser_factory
+                .try_new_parser::<BoxTerm>(syntax_in, file_iri(input), None)?;
+
+        let io_error = |e| ConvertError::Io(input.to_owned(), e);
+        let data = BufReader::new(File::open(input).map_err(io_error)?);
+        let mut write = BufWriter::new(File::create(&output).map_err(io_error)?);
+
+        let mut source = parser.parse(data);
+        let mut statements = 0;
+        let result = Counted::new(&mut source, &mut statements)
+            .serialize_to(out_syntax, &mut write, &self.serializer_factories)
+            .map_err(|e| ConvertError::Serialize(input.to_owned(), e))
+            .and_then(|_| write.flush().map_err(io_error));
+        if let Err(e) = result {
+            drop(write);
+            let _ = fs::remove_file(&output);
+            return Err(e);
+        }
+        Ok(ConversionReport {
+            input: input.to_owned(),
+            output,
+            syntax_in,
+            statements,
+            skip_summary: source.take_skip_summary(),
+        })
+    }
+
+    /// Converts files at `inputs` into `out_syntax`, writing into `out_dir`, with up to `parallelism` files being converted concurrently. Returns outcome for each input, in order of inputs. Failure to convert a file doesn't stop conversion of others.
+    ///
+    /// Inputs with same file stem are converted into same output file. Callers should ensure, that file stems are unique.
+    pub fn convert_all(
+        &self,
+        inputs: &[PathBuf],
+        out_dir: &Path,
+        out_syntax: RdfSyntax,
+        parallelism: usize,
+    ) -> Vec<Result<ConversionReport, ConvertError>> {
+        let next = AtomicUsize::new(0);
+        let outcomes = Mutex::new(Vec::from_iter(inputs.iter().map(|_| None)));
+        thread::scope(|scope| {
+            for _ in 0..parallelism.clamp(1, inputs.len().max(1)) {
+                scope.spawn(|| loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let input = match inputs.get(i) {
+                        Some(input) => input,
+                        None => break,
+                    };
+                    let outcome = self.convert_file(input, out_dir, out_syntax);
+                    if let Err(e) = &outcome {
+                        tracing::warn!("Couldn't convert file {:?}: {}", input, e);
+                    }
+                    outcomes.lock().expect("lock is never poisoned")[i] = Some(outcome);
+                });
+            }
+        });
+        outcomes
+            .into_inner()
+            .expect("lock is never poisoned")
+            .into_iter()
+            .map(|outcome| outcome.expect("every input is converted"))
+            .collect()
+    }
+}
+
+static DEFAULT_CONVERTER: Lazy<Converter> = Lazy::new(Converter::default);
+
+/// Converts document in `syntax_in` from `data` into `syntax_out`, writing to `write`, with default parser, and serializer configurations. Use [`Converter::convert`] to convert with custom configurations.
+///
+/// # Errors
+/// returns [`ConvertError`] if either of syntaxes is not supported, or if conversion fails. Output may have been partially written on failure.
+pub fn convert<R, W>(
+    data: R,
+    syntax_in: RdfSyntax,
+    write: W,
+    syntax_out: RdfSyntax,
+    options: &ConvertOptions,
+) -> Result<ConversionStats, ConvertError>
+where
+    R: BufRead,
+    W: Write,
+{
+    DEFAULT_CONVERTER.convert(data, syntax_in, write, syntax_out, options)
+}
+
+/// Get `file:` iri of given path, if it can be made absolute, and is valid unicode.
+pub(crate) fn file_iri(path: &Path) -> Option<String> {
+    let path = fs::canonicalize(path).ok()?;
+    let path = path.to_str()?.replace('\\', "/");
+    Some(match path.starts_with('/') {
+        true => format!("file://{}", path),
+        false => format!("file:///{}", path),
+    })
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use once_cell::sync::Lazy;
+    use sophia_api::{
+        dataset::{isomorphic_datasets, Dataset},
+        parser::QuadParser,
+        quad::stream::QuadSource,
+    };
+    use sophia_inmem::dataset::FastDataset;
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+    use type_map::concurrent::TypeMap;
+
+    use super::{convert, ConvertOptions, Converter};
+    use crate::{
+        error_code::{self, ErrorCode},
+        parser::{quads::DynSynQuadParserFactory, recovery::ParserConfig},
+        serializer::serializable::{DynSynSerializerFactories, NamedGraphAction},
+        syntax::{self, RdfSyntax},
+        tests::TRACING,
+    };
+
+    static TURTLE_DOC: &str = r#"
+        @prefix : <http://example.org/> .
+        :alice :name "Alice" ; :knows <bob> .
+    "#;
+
+    static TRIG_DOC: &str = r#"
+        @prefix : <http://example.org/> .
+        :a :p :b .
+        :g { :a :p :c . }
+    "#;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rdf_dynsyn_convert_{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("out")).unwrap();
+        dir
+    }
+
+    fn parse(syntax_: RdfSyntax, doc: &str) -> FastDataset {
+        DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax_, Some("http://example.org/".into()), None)
+            .unwrap()
+            .parse_str(doc)
+            .collect_quads()
+            .unwrap()
+    }
+
+    #[test_case(1)]
+    #[test_case(3)]
+    #[test_case(16)]
+    pub fn converts_all_files(parallelism: usize) {
+        Lazy::force(&TRACING);
+        let dir = test_dir(&format!("all_{}", parallelism));
+        let inputs: Vec<PathBuf> = (0..5)
+            .map(|i| {
+                let (name, doc) = match i % 2 {
+                    0 => (format!("f{}.ttl", i), TURTLE_DOC),
+                    _ => (format!("f{}.TriG", i), TRIG_DOC),
+                };
+                fs::write(dir.join(&name), doc).unwrap();
+                dir.join(name)
+            })
+            .collect();
+
+        let reports = Converter::default().convert_all(
+            &inputs,
+            &dir.join("out"),
+            syntax::N_QUADS,
+            parallelism,
+        );
+        assert_eq!(reports.len(), inputs.len());
+        for (i, report) in reports.into_iter().enumerate() {
+            let report = report.unwrap();
+            assert_eq!(report.input, inputs[i]);
+            assert_eq!(report.output, dir.join("out").join(format!("f{}.nq", i)));
+            let (syntax_in, statements) = match i % 2 {
+                0 => (syntax::TURTLE, 2),
+                _ => (syntax::TRIG, 2),
+            };
+            assert_eq!(
+                (report.syntax_in, report.statements),
+                (syntax_in, statements)
+            );
+            let converted = parse(
+                syntax::N_QUADS,
+                &fs::read_to_string(&report.output).unwrap(),
+            );
+            assert_eq!(converted.quads().count(), statements);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    pub fn converted_file_is_isomorphic_to_input() {
+        Lazy::force(&TRACING);
+        let dir = test_dir("isomorphic");
+        let input = dir.join("g.trig");
+        fs::write(&input, TRIG_DOC).unwrap();
+        let report = Converter::default()
+            .convert_file(&input, &dir.join("out"), syntax::TRIG)
+            .unwrap();
+        assert!(isomorphic_datasets(
+            &parse(syntax::TRIG, TRIG_DOC),
+            &parse(syntax::TRIG, &fs::read_to_string(report.output).unwrap())
+        )
+        .unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    pub fn reports_errors_per_file() {
+        Lazy::force(&TRACING);
+        let dir = test_dir("errors");
+        let inputs = vec![
+            dir.join("good.ttl"),
+            dir.join("bad.ttl"),
+            dir.join("unknown.txt"),
+            dir.join("missing.nt"),
+        ];
+        fs::write(&inputs[0], TURTLE_DOC).unwrap();
+        fs::write(
+            &inputs[1],
+            "<http://example.org/a> <http://example.org/p> .",
+        )
+        .unwrap();
+        fs::write(&inputs[2], TURTLE_DOC).unwrap();
+
+        let reports =
+            Converter::default().convert_all(&inputs, &dir.join("out"), syntax::TURTLE, 2);
+        let codes: Vec<_> = reports
+            .iter()
+            .map(|r| r.as_ref().map(|_| ()).map_err(|e| e.code()))
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                Ok(()),
+                Err(error_code::DYNSYN_PARSE_SYNTAX),
+                Err(error_code::DYNSYN_UNDETERMINED_SYNTAX),
+                Err(error_code::DYNSYN_CONVERT_IO),
+            ]
+        );
+        assert!(!dir.join("out").join("bad.ttl").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    pub fn lenient_converter_skips_malformed_statements_of_files() {
+        Lazy::force(&TRACING);
+        let dir = test_dir("lenient");
+        let input = dir.join("dirty.nt");
+        fs::write(
+            &input,
+            "<http://example.org/a> <http://example.org/p> .\n\
+            <http://example.org/a> <http://example.org/p> <http://example.org/b> .\n",
+        )
+        .unwrap();
+        let mut config_map = TypeMap::new();
+        config_map.insert(ParserConfig::lenient());
+        let converter = Converter::new(
+            DynSynQuadParserFactory::new(Some(config_map)),
+            Default::default(),
+        );
+        let report = converter
+            .convert_file(&input, &dir.join("out"), syntax::N_QUADS)
+            .unwrap();
+        assert_eq!(report.statements, 1);
+        assert_eq!(report.skip_summary.skipped.len(), 1);
+        assert_eq!(report.skip_summary.skipped[0].line_number, Some(1));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    pub fn converting_into_unsupported_syntax_will_error() {
+        Lazy::force(&TRACING);
+        let dir = test_dir("unsupported");
+        let input = dir.join("a.ttl");
+        fs::write(&input, TURTLE_DOC).unwrap();
+        let e = Converter::default()
+            .convert_file(&input, &dir.join("out"), syntax::JSON_LD)
+            .unwrap_err();
+        assert_eq!(e.code(), error_code::DYNSYN_UNKNOWN_SYNTAX);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test_case(syntax::TRIG, TRIG_DOC, syntax::N_QUADS, 2)]
+    #[test_case(syntax::TURTLE, TURTLE_DOC, syntax::TRIG, 2)]
+    pub fn converts_documents(
+        syntax_in: RdfSyntax,
+        doc: &str,
+        syntax_out: RdfSyntax,
+        statements: usize,
+    ) {
+        Lazy::force(&TRACING);
+        let options = ConvertOptions {
+            base_iri: Some("http://example.org/".into()),
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        let stats = convert(doc.as_bytes(), syntax_in, &mut out, syntax_out, &options).unwrap();
+        assert_eq!(stats.statements, statements);
+
+        let expected = parse(syntax_in, doc);
+        let converted = parse(syntax_out, std::str::from_utf8(&out).unwrap());
+        assert!(isomorphic_datasets(&expected, &converted).unwrap());
+    }
+
+    #[test_case(None, None)]
+    #[test_case(Some(NamedGraphAction::Drop), Some(1))]
+    #[test_case(Some(NamedGraphAction::Merge), Some(2))]
+    pub fn converts_named_graphs_into_triple_syntaxes_as_per_configured_action(
+        action: Option<NamedGraphAction>,
+        triples: Option<usize>,
+    ) {
+        Lazy::force(&TRACING);
+        let serializer_factories = DynSynSerializerFactories::default()
+            .with_named_graph_action(action.unwrap_or_default());
+        let converter = Converter::new(Default::default(), serializer_factories);
+        let options = ConvertOptions {
+            base_iri: Some("http://example.org/".into()),
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        let result = converter.convert(
+            TRIG_DOC.as_bytes(),
+            syntax::TRIG,
+            &mut out,
+            syntax::TURTLE,
+            &options,
+        );
+        match triples {
+            Some(triples) => {
+                assert_eq!(result.unwrap().statements, 2);
+                let converted = parse(syntax::TURTLE, std::str::from_utf8(&out).unwrap());
+                assert_eq!(converted.quads().count(), triples);
+            }
+            None => assert_eq!(
+                result.unwrap_err().code(),
+                error_code::DYNSYN_NAMED_GRAPH_IN_TRIPLE_SYNTAX
+            ),
+        }
+    }
+
+    #[test]
+    pub fn skips_malformed_statements_in_keep_going_mode() {
+        Lazy::force(&TRACING);
+        let doc = "<http://example.org/a> <http://example.org/p> <http://example.org/b> .\n\
+            <http://example.org/a> <http://example.org/p> .\n\
+            <http://example.org/a> <http://example.org/p> <http://example.org/c> .\n";
+        let options = ConvertOptions {
+            keep_going: true,
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        let stats = convert(
+            doc.as_bytes(),
+            syntax::N_TRIPLES,
+            &mut out,
+            syntax::N_QUADS,
+            &options,
+        )
+        .unwrap();
+        assert_eq!(stats.statements, 2);
+        assert_eq!(String::from_utf8(out).unwrap().lines().count(), 2);
+        let skipped = &stats.skip_summary.skipped;
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].line_number, Some(2));
+        assert_eq!(skipped[0].error.code(), error_code::DYNSYN_PARSE_SYNTAX);
+
+        let e = convert(
+            doc.as_bytes(),
+            syntax::N_TRIPLES,
+            Vec::new(),
+            syntax::N_QUADS,
+            &ConvertOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(e.code(), error_code::DYNSYN_PARSE_SYNTAX);
+    }
+
+    #[test_case(syntax::JSON_LD, syntax::TURTLE, error_code::DYNSYN_UNKNOWN_SYNTAX)]
+    #[test_case(syntax::TURTLE, syntax::JSON_LD, error_code::DYNSYN_UNKNOWN_SYNTAX)]
+    #[test_case(syntax::N_TRIPLES, syntax::TURTLE, error_code::DYNSYN_PARSE_SYNTAX)]
+    pub fn converting_documents_will_error(
+        syntax_in: RdfSyntax,
+        syntax_out: RdfSyntax,
+        expected_code: &str,
+    ) {
+        Lazy::force(&TRACING);
+        let e = convert(
+            TURTLE_DOC.as_bytes(),
+            syntax_in,
+            Vec::new(),
+            syntax_out,
+            &ConvertOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(e.code(), expected_code);
+    }
+}
@@ -1,14 +1,20 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use once_cell::sync::Lazy;
 
 use super::{
+    error_code::{self, ErrorCode},
     file_extension::{self as fextn, FileExtension},
-    media_type,
-    syntax::{self, RdfSyntax},
+    media_type, metrics,
+    serializer::errors::{NewSerializerError, SyntaxRoleMismatch},
+    syntax::{self, RdfSyntax, UnKnownSyntaxError},
 };
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// A struct that wraps a corresponding value for some other entity, and qualifies correspondence with exclusivity
 pub struct Correspondent<T> {
     /// correspondent value
@@ -32,12 +38,16 @@ pub static SYNTAX_TO_EXTENSION_CORRESPONDENCE: Lazy<
     let mut map: HashMap<RdfSyntax, Correspondent<FileExtension>> = HashMap::new();
     set_correspondence!(
         map;
+        syntax::HDT, fextn::HDT, true;
+
         syntax::HTML_RDFA, fextn::HTML, true;
 
         syntax::JSON_LD, fextn::JSONLD, true;
 
         syntax::N3, fextn::N3, true;
 
+        syntax::ND_RDF_JSON, fextn::NDJSON, true;
+
         syntax::N_QUADS, fextn::NQ, true;
 
         syntax::N_TRIPLES, fextn::NT, true;
@@ -50,6 +60,8 @@ pub static SYNTAX_TO_EXTENSION_CORRESPONDENCE: Lazy<
 
         syntax::TRIG, fextn::TRIG, true;
 
+        syntax::TRIX, fextn::TRIX, true;
+
         syntax::TURTLE, fextn::TTL, true;
 
         syntax::XHTML_RDFA, fextn::XHTML, true;
@@ -64,6 +76,8 @@ pub static EXTENSION_TO_SYNTAX_CORRESPONDENCE: Lazy<
     let mut map: HashMap<FileExtension, Correspondent<RdfSyntax>> = HashMap::new();
     set_correspondence!(
         map;
+        fextn::HDT, syntax::HDT, true;
+
         fextn::HTML, syntax::HTML_RDFA, false;
 
         fextn::JSONLD, syntax::JSON_LD, true;
@@ -72,6 +86,8 @@ pub static EXTENSION_TO_SYNTAX_CORRESPONDENCE: Lazy<
 
         fextn::N3, syntax::N3, true;
 
+        fextn::NDJSON, syntax::ND_RDF_JSON, false;
+
         fextn::NQ, syntax::N_QUADS, true;
 
         fextn::NQUADS, syntax::N_QUADS, true;
@@ -92,6 +108,8 @@ pub static EXTENSION_TO_SYNTAX_CORRESPONDENCE: Lazy<
 
         fextn::TRIG, syntax::TRIG, true;
 
+        fextn::TRIX, syntax::TRIX, true;
+
         fextn::TTL, syntax::TURTLE, true;
 
         fextn::TURTLE, syntax::TURTLE, true;
@@ -108,12 +126,16 @@ pub static SYNTAX_TO_MEDIA_TYPE_CORRESPONDENCE: Lazy<
     let mut map: HashMap<RdfSyntax, Correspondent<&'static mime::Mime>> = HashMap::new();
     set_correspondence!(
         map;
+        syntax::HDT, &media_type::APPLICATION_VND_HDT, true;
+
         syntax::HTML_RDFA, &media_type::TEXT_HTML, true;
 
         syntax::JSON_LD, &media_type::APPLICATION_JSON_LD, true;
 
         syntax::N3, &media_type::TEXT_N3, true;
 
+        syntax::ND_RDF_JSON, &media_type::APPLICATION_X_ND_RDF_JSON, true;
+
         syntax::N_QUADS, &media_type::APPLICATION_N_QUADS, true;
 
         syntax::N_TRIPLES, &media_type::APPLICATION_N_TRIPLES, true;
@@ -126,6 +148,8 @@ pub static SYNTAX_TO_MEDIA_TYPE_CORRESPONDENCE: Lazy<
 
         syntax::TRIG, &media_type::APPLICATION_TRIG, true;
 
+        syntax::TRIX, &media_type::APPLICATION_TRIX, true;
+
         syntax::TURTLE, &media_type::TEXT_TURTLE, true;
 
         syntax::XHTML_RDFA, &media_type::APPLICATION_XHTML_XML, true;
@@ -140,12 +164,16 @@ pub static MEDIA_TYPE_TO_SYNTAX_CORRESPONDENCE: Lazy<
     let mut map: HashMap<&'static mime::Mime, Correspondent<RdfSyntax>> = HashMap::new();
     set_correspondence!(
         map;
+        &media_type::APPLICATION_VND_HDT, syntax::HDT, true;
+
         &media_type::TEXT_HTML, syntax::HTML_RDFA, false;
 
         &media_type::APPLICATION_JSON_LD, syntax::JSON_LD, true;
 
         &media_type::TEXT_N3, syntax::N3, true;
 
+        &media_type::APPLICATION_X_ND_RDF_JSON, syntax::ND_RDF_JSON, true;
+
         &media_type::APPLICATION_N_QUADS, syntax::N_QUADS, true;
 
         &media_type::APPLICATION_N_TRIPLES, syntax::N_TRIPLES, true;
@@ -158,6 +186,8 @@ pub static MEDIA_TYPE_TO_SYNTAX_CORRESPONDENCE: Lazy<
 
         &media_type::APPLICATION_TRIG, syntax::TRIG, true;
 
+        &media_type::APPLICATION_TRIX, syntax::TRIX, true;
+
         &media_type::TEXT_TURTLE, syntax::TURTLE, true;
 
         &media_type::APPLICATION_XHTML_XML, syntax::XHTML_RDFA, false;
@@ -175,16 +205,116 @@ pub struct NonRdfMediaTypeError(mime::Mime);
 #[error("Specified file-extension {0} doesn't correspond to any rdf syntax")]
 pub struct NonRdfFileExtensionError(FileExtension);
 
+impl ErrorCode for NonRdfMediaTypeError {
+    fn code(&self) -> &'static str {
+        error_code::DYNSYN_NON_RDF_MEDIA_TYPE
+    }
+}
+
+impl ErrorCode for NonRdfFileExtensionError {
+    fn code(&self) -> &'static str {
+        error_code::DYNSYN_NON_RDF_FILE_EXTENSION
+    }
+}
+
+/// An error in creating a parser, or a serializer for a file, from it's path.
+#[derive(Debug, thiserror::Error)]
+pub enum ForPathError {
+    #[error("Path {0:?} has no file extension")]
+    NoFileExtension(PathBuf),
+    #[error(transparent)]
+    NonRdfFileExtension(#[from] NonRdfFileExtensionError),
+    #[error(transparent)]
+    UnKnownSyntax(#[from] UnKnownSyntaxError),
+    #[error(transparent)]
+    SyntaxRoleMismatch(#[from] SyntaxRoleMismatch),
+}
+
+impl From<NewSerializerError> for ForPathError {
+    fn from(e: NewSerializerError) -> Self {
+        match e {
+            NewSerializerError::UnKnownSyntax(e) => e.into(),
+            NewSerializerError::SyntaxRoleMismatch(e) => e.into(),
+        }
+    }
+}
+
+impl ErrorCode for ForPathError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::NoFileExtension(_) => error_code::DYNSYN_UNDETERMINED_SYNTAX,
+            Self::NonRdfFileExtension(e) => e.code(),
+            Self::UnKnownSyntax(e) => e.code(),
+            Self::SyntaxRoleMismatch(e) => e.code(),
+        }
+    }
+}
+
+/// Get preferred file extension for documents in given `syntax_`.
+pub fn preferred_extension_for(syntax_: RdfSyntax) -> Option<FileExtension> {
+    SYNTAX_TO_EXTENSION_CORRESPONDENCE
+        .get(&syntax_)
+        .map(|c| c.value.clone())
+}
+
+/// Compute path of output file, that a document at `input_path` should be converted into, for `out_syntax`. File extension of input is replaced with preferred extension of `out_syntax`, while compression suffix, if any, is preserved. For example, `data/alice.ttl.gz` becomes `data/alice.nt.gz` for [`n-triples`](syntax::N_TRIPLES).
+///
+/// Returns `None`, if `out_syntax` has no preferred extension.
+pub fn output_path_for(input_path: &Path, out_syntax: RdfSyntax) -> Option<PathBuf> {
+    let out_extension = preferred_extension_for(out_syntax)?;
+    let compression = FileExtension::compression_from_path(input_path);
+    let mut output = match &compression {
+        Some(_) => input_path.with_extension(""),
+        None => input_path.to_owned(),
+    };
+    output.set_extension(&*out_extension);
+    if let Some(compression) = compression {
+        let mut file_name = output.file_name().unwrap_or_default().to_owned();
+        file_name.push(".");
+        file_name.push(&*compression);
+        output.set_file_name(file_name);
+    }
+    Some(output)
+}
+
+/// Resolve syntax of file at given `path`, from it's file extension. File extension is matched case-insensitively.
+pub fn syntax_for_path(path: &Path) -> Result<RdfSyntax, ForPathError> {
+    let file_extension = FileExtension::from_path(path)
+        .ok_or_else(|| ForPathError::NoFileExtension(path.to_owned()))?;
+    let file_extension = FileExtension::from(file_extension.to_ascii_lowercase());
+    Ok(Correspondent::<RdfSyntax>::try_from(&file_extension)?.value)
+}
+
+/// Get syntax corresponding to given media type, matched on it's essence. Unlike [`Correspondent::try_from`], it isn't counted in [`metrics`], and is meant for crate's own lookups.
+pub(crate) fn correspondent_for_media_type(
+    media_type: &mime::Mime,
+) -> Option<&'static Correspondent<RdfSyntax>> {
+    media_type
+        .essence_str()
+        .parse::<mime::Mime>()
+        .ok()
+        .and_then(|essence| MEDIA_TYPE_TO_SYNTAX_CORRESPONDENCE.get(&essence))
+}
+
+/// Get syntax corresponding to given file extension. Unlike [`Correspondent::try_from`], it isn't counted in [`metrics`], and is meant for crate's own lookups.
+pub(crate) fn correspondent_for_file_extension(
+    file_extension: &FileExtension,
+) -> Option<&'static Correspondent<RdfSyntax>> {
+    EXTENSION_TO_SYNTAX_CORRESPONDENCE.get(file_extension)
+}
+
 impl TryFrom<&mime::Mime> for Correspondent<RdfSyntax> {
     type Error = NonRdfMediaTypeError;
 
-    /// For given SyntaxHint, tries to resolve corresponding syntax.
+    /// For given media type, tries to resolve corresponding syntax. Media type is matched on it's essence, ignoring parameters, like in `text/turtle; charset=utf-8`.
     #[tracing::instrument(
         name = "Resolving Syntax from media type",
         fields(media_type=%media_type)
     )]
     fn try_from(media_type: &mime::Mime) -> Result<Self, Self::Error> {
-        match MEDIA_TYPE_TO_SYNTAX_CORRESPONDENCE.get(media_type) {
+        let correspondent_syntax = correspondent_for_media_type(media_type);
+        metrics::record_media_type_lookup(correspondent_syntax.is_some());
+        match correspondent_syntax {
             Some(correspondent_syntax) => {
                 tracing::info!("media_type resolved to {}", &correspondent_syntax.value);
                 Ok(correspondent_syntax.clone())
@@ -197,6 +327,59 @@ impl TryFrom<&mime::Mime> for Correspondent<RdfSyntax> {
     }
 }
 
+/// Syntax resolved from a media type, along with parameters of the media type, like `charset`, or `profile` of json-ld documents. Callers can configure parsers for the document with these parameters.
+#[derive(Debug, Clone)]
+pub struct MediaTypeResolution {
+    /// syntax, that essence of the media type corresponds to
+    pub syntax: Correspondent<RdfSyntax>,
+    /// parameters of the media type, in their order. Parameter names are lowercased, and quoted values are unquoted.
+    pub parameters: Vec<(String, String)>,
+}
+
+impl MediaTypeResolution {
+    /// Get value of the parameter with given name, if any. Name is matched case-insensitively.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Get value of `charset` parameter, if any.
+    pub fn charset(&self) -> Option<&str> {
+        self.param("charset")
+    }
+
+    /// Get iris of `profile` parameter. Profile parameter is a space separated list of iris, as in json-ld media type.
+    pub fn profiles(&self) -> Vec<&str> {
+        self.param("profile")
+            .map(|v| v.split_ascii_whitespace().collect())
+            .unwrap_or_default()
+    }
+}
+
+impl TryFrom<&mime::Mime> for MediaTypeResolution {
+    type Error = NonRdfMediaTypeError;
+
+    /// For given media type, tries to resolve corresponding syntax, capturing it's parameters.
+    fn try_from(media_type: &mime::Mime) -> Result<Self, Self::Error> {
+        Ok(Self {
+            syntax: Correspondent::<RdfSyntax>::try_from(media_type)?,
+            parameters: media_type
+                .params()
+                .map(|(n, v)| {
+                    let v = v.as_str();
+                    let v = v
+                        .strip_prefix('"')
+                        .and_then(|v| v.strip_suffix('"'))
+                        .unwrap_or(v);
+                    (n.as_str().to_ascii_lowercase(), v.to_string())
+                })
+                .collect(),
+        })
+    }
+}
+
 impl TryFrom<&FileExtension> for Correspondent<RdfSyntax> {
     type Error = NonRdfFileExtensionError;
 
@@ -206,7 +389,9 @@ impl TryFrom<&FileExtension> for Correspondent<RdfSyntax> {
         fields(file_extension=%file_extension)
     )]
     fn try_from(file_extension: &FileExtension) -> Result<Self, Self::Error> {
-        match EXTENSION_TO_SYNTAX_CORRESPONDENCE.get(file_extension) {
+        let correspondent_syntax = correspondent_for_file_extension(file_extension);
+        metrics::record_file_extension_lookup(correspondent_syntax.is_some());
+        match correspondent_syntax {
             Some(correspondent_syntax) => {
                 tracing::info!("file_extension resolved to {}", &correspondent_syntax.value);
                 Ok(correspondent_syntax.clone())
@@ -225,16 +410,21 @@ impl TryFrom<&FileExtension> for Correspondent<RdfSyntax> {
 
 #[cfg(test)]
 mod tests {
+    use std::path::{Path, PathBuf};
 
-    use claim::{assert_err, assert_ok};
+    use claim::{assert_err, assert_ok, assert_some};
     use once_cell::sync::Lazy;
     use test_case::test_case;
 
     use crate::{
-        correspondence::Correspondent,
+        correspondence::{
+            output_path_for, preferred_extension_for, syntax_for_path, Correspondent,
+            MediaTypeResolution,
+        },
+        error_code::{self, ErrorCode},
         file_extension::{self, FileExtension},
         media_type,
-        syntax::RdfSyntax,
+        syntax::{self, RdfSyntax},
         tests::TRACING,
     };
 
@@ -247,12 +437,15 @@ mod tests {
     pub fn non_rdf_file_extensions_should_not_have_correspondent_syntax(extn_str: &'static str) {
         Lazy::force(&TRACING);
         let extn = FileExtension::from(extn_str);
-        assert_err!(Correspondent::<RdfSyntax>::try_from(&extn));
+        let e = assert_err!(Correspondent::<RdfSyntax>::try_from(&extn));
+        assert_eq!(e.code(), error_code::DYNSYN_NON_RDF_FILE_EXTENSION);
     }
 
+    #[test_case(&file_extension::HDT)]
     #[test_case(&file_extension::HTML)]
     #[test_case(&file_extension::JSON)]
     #[test_case(&file_extension::JSONLD)]
+    #[test_case(&file_extension::NDJSON)]
     #[test_case(&file_extension::NQ)]
     #[test_case(&file_extension::NQUADS)]
     #[test_case(&file_extension::NT)]
@@ -263,6 +456,7 @@ mod tests {
     #[test_case(&file_extension::RDF)]
     #[test_case(&file_extension::RDFXML)]
     #[test_case(&file_extension::TRIG)]
+    #[test_case(&file_extension::TRIX)]
     #[test_case(&file_extension::TTL)]
     #[test_case(&file_extension::TURTLE)]
     #[test_case(&file_extension::XHTML)]
@@ -271,10 +465,50 @@ mod tests {
         assert_ok!(Correspondent::<RdfSyntax>::try_from(extn));
     }
 
+    #[test_case("data/alice.ttl.gz", Some(syntax::TURTLE))]
+    #[test_case("alice.NQ.XZ", Some(syntax::N_QUADS))]
+    #[test_case("alice.nt.bz2", Some(syntax::N_TRIPLES))]
+    #[test_case("alice.gz", None)]
+    pub fn syntax_for_path_strips_compression_suffix(path: &str, expected: Option<RdfSyntax>) {
+        Lazy::force(&TRACING);
+        assert_eq!(syntax_for_path(Path::new(path)).ok(), expected);
+    }
+
+    #[test_case("data/alice.ttl", syntax::N_TRIPLES, "data/alice.nt")]
+    #[test_case("data/alice.ttl.gz", syntax::N_TRIPLES, "data/alice.nt.gz")]
+    #[test_case("alice.nq.ZST", syntax::TRIG, "alice.trig.ZST")]
+    #[test_case("alice.gz", syntax::TURTLE, "alice.ttl.gz")]
+    #[test_case("data.d/alice", syntax::N_QUADS, "data.d/alice.nq")]
+    pub fn output_path_swaps_extension_preserving_compression(
+        input: &str,
+        out_syntax: RdfSyntax,
+        expected: &str,
+    ) {
+        Lazy::force(&TRACING);
+        assert_eq!(
+            output_path_for(Path::new(input), out_syntax),
+            Some(PathBuf::from(expected))
+        );
+    }
+
+    #[test]
+    pub fn preferred_extension_is_known_for_known_syntaxes() {
+        Lazy::force(&TRACING);
+        for syntax_ in syntax::KNOWN_SYNTAXES {
+            let extension = assert_some!(preferred_extension_for(syntax_));
+            assert_eq!(
+                Correspondent::<RdfSyntax>::try_from(&extension).unwrap().value,
+                syntax_
+            );
+        }
+    }
+
     // For rdfa+html
     #[test_case(&file_extension::HTML)]
     // For json-ld
     #[test_case(&file_extension::JSON)]
+    // For nd-rdf-json
+    #[test_case(&file_extension::NDJSON)]
     // for rdfa+xhtml
     #[test_case(&file_extension::XHTML)]
     pub fn known_general_file_extensions_should_have_non_total_correspondence(
@@ -284,6 +518,7 @@ mod tests {
         assert!(!Correspondent::<RdfSyntax>::try_from(extn).unwrap().is_total);
     }
 
+    #[test_case(&file_extension::HDT)]
     #[test_case(&file_extension::JSONLD)]
     #[test_case(&file_extension::NQ)]
     #[test_case(&file_extension::NQUADS)]
@@ -295,6 +530,7 @@ mod tests {
     #[test_case(&file_extension::RDF)]
     #[test_case(&file_extension::RDFXML)]
     #[test_case(&file_extension::TRIG)]
+    #[test_case(&file_extension::TRIX)]
     #[test_case(&file_extension::TTL)]
     #[test_case(&file_extension::TURTLE)]
     pub fn known_rdf_specific_file_extensions_should_have_total_correspondence(
@@ -311,7 +547,42 @@ mod tests {
     #[test_case(&mime::TEXT_CSV)]
     pub fn non_rdf_media_types_should_not_have_correspondent_syntax(media_type: &mime::Mime) {
         Lazy::force(&TRACING);
-        assert_err!(Correspondent::<RdfSyntax>::try_from(media_type));
+        let e = assert_err!(Correspondent::<RdfSyntax>::try_from(media_type));
+        assert_eq!(e.code(), error_code::DYNSYN_NON_RDF_MEDIA_TYPE);
+    }
+
+    #[test_case("text/turtle; charset=utf-8", syntax::TURTLE)]
+    #[test_case("Application/N-Triples;charset=UTF-8", syntax::N_TRIPLES)]
+    #[test_case(r#"application/ld+json; profile="http://www.w3.org/ns/json-ld#expanded""#, syntax::JSON_LD)]
+    pub fn media_types_with_params_should_resolve_on_essence(media_type: &str, expected: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let media_type: mime::Mime = media_type.parse().unwrap();
+        assert_eq!(
+            Correspondent::<RdfSyntax>::try_from(&media_type).unwrap().value,
+            expected
+        );
+    }
+
+    #[test]
+    pub fn media_type_resolution_captures_params() {
+        Lazy::force(&TRACING);
+        let media_type: mime::Mime = r#"application/ld+json; charset=UTF-8; Profile="http://www.w3.org/ns/json-ld#compacted http://example.org/p""#
+            .parse()
+            .unwrap();
+        let resolution = MediaTypeResolution::try_from(&media_type).unwrap();
+        assert_eq!(resolution.syntax.value, syntax::JSON_LD);
+        assert!(resolution.syntax.is_total);
+        assert!(resolution.charset().unwrap().eq_ignore_ascii_case("utf-8"));
+        assert_eq!(
+            resolution.profiles(),
+            vec!["http://www.w3.org/ns/json-ld#compacted", "http://example.org/p"]
+        );
+        assert_eq!(resolution.param("PROFILE"), resolution.param("profile"));
+
+        let resolution = MediaTypeResolution::try_from(&*media_type::TEXT_TURTLE).unwrap();
+        assert!(resolution.parameters.is_empty());
+        assert!(resolution.profiles().is_empty());
+        assert_err!(MediaTypeResolution::try_from(&mime::TEXT_CSV_UTF_8));
     }
 
     #[test_case(&media_type::APPLICATION_JSON_LD)]
@@ -320,6 +591,9 @@ mod tests {
     #[test_case(&media_type::APPLICATION_OWL_XML)]
     #[test_case(&media_type::APPLICATION_RDF_XML)]
     #[test_case(&media_type::APPLICATION_TRIG)]
+    #[test_case(&media_type::APPLICATION_TRIX)]
+    #[test_case(&media_type::APPLICATION_VND_HDT)]
+    #[test_case(&media_type::APPLICATION_X_ND_RDF_JSON)]
     #[test_case(&media_type::APPLICATION_XHTML_XML)]
     #[test_case(&media_type::TEXT_HTML)]
     #[test_case(&media_type::TEXT_N3)]
@@ -349,6 +623,8 @@ mod tests {
     #[test_case(&media_type::APPLICATION_OWL_XML)]
     #[test_case(&media_type::APPLICATION_RDF_XML)]
     #[test_case(&media_type::APPLICATION_TRIG)]
+    #[test_case(&media_type::APPLICATION_TRIX)]
+    #[test_case(&media_type::APPLICATION_VND_HDT)]
     #[test_case(&media_type::TEXT_N3)]
     #[test_case(&media_type::TEXT_OWL_MANCHESTER)]
     #[test_case(&media_type::TEXT_TURTLE)]
@@ -362,4 +638,17 @@ mod tests {
                 .is_total
         );
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    pub fn correspondent_round_trips_through_serde() {
+        Lazy::force(&TRACING);
+        let c = Correspondent::<RdfSyntax>::try_from(&file_extension::JSON).unwrap();
+        let json = serde_json::to_string(&c).unwrap();
+        assert_eq!(json, r#"{"value":"json-ld","is_total":false}"#);
+        let c: Correspondent<FileExtension> =
+            serde_json::from_str(r#"{"value":"ttl","is_total":true}"#).unwrap();
+        assert_eq!(c.value, file_extension::TTL);
+        assert!(c.is_total);
+    }
 }
@@ -0,0 +1,447 @@
+//! This module provides utilities to compare rdf documents across syntaxes, like a turtle file, and it's regenerated rdf-xml counterpart. [`are_isomorphic`] checks, whether two documents encode same dataset, up to renaming of blank nodes. [`diff`] reports statements, that are added, and removed between two documents.
+//!
+//! Blank node labels are not stable across syntaxes, and serializations. Hence [`diff`] maps blank nodes of first document to those of second document by their structure: blank nodes are colored iteratively by statements they occur in, and by colors of their neighbouring blank nodes, and blank nodes with same colors are mapped. Blank nodes, whose colors differ due to changes around them, are then mapped greedily, such that most of their statements match. Statements are compared under this mapping. Reported statements keep their labels from their own documents, and duplicate statements in a document are counted once.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{diff::{are_isomorphic, diff}, syntax};
+//! use sophia_api::term::TTerm;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let turtle = r#"
+//!     @prefix : <http://example.org/> .
+//!     :alice :knows [ :name "Bob" ] ; :age 30 .
+//! "#;
+//! let n_triples = r#"
+//!     <http://example.org/alice> <http://example.org/knows> _:x .
+//!     _:x <http://example.org/name> "Bob" .
+//!     <http://example.org/alice> <http://example.org/age> "31"^^<http://www.w3.org/2001/XMLSchema#integer> .
+//! "#;
+//! assert!(!are_isomorphic(turtle.as_bytes(), syntax::TURTLE, n_triples.as_bytes(), syntax::N_TRIPLES)?);
+//!
+//! let d = diff(turtle.as_bytes(), syntax::TURTLE, n_triples.as_bytes(), syntax::N_TRIPLES)?;
+//! assert_eq!(d.removed.len(), 1);
+//! assert_eq!(d.removed[0].o.value().to_string(), "30");
+//! assert_eq!(d.added.len(), 1);
+//! assert_eq!(d.added[0].o.value().to_string(), "31");
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeSet, HashMap, HashSet},
+    hash::{Hash, Hasher},
+    io::BufRead,
+};
+
+use sophia_api::{
+    dataset::isomorphic_datasets,
+    parser::QuadParser,
+    quad::stream::QuadSource,
+    term::{TTerm, TermKind},
+};
+use sophia_term::BoxTerm;
+
+use crate::{
+    error_code::ErrorCode,
+    model::{cmp_terms, OwnedQuad},
+    parser::{errors::DynSynParseError, quads::DynSynQuadParserFactory},
+    syntax::{RdfSyntax, UnKnownSyntaxError},
+};
+
+/// An error in comparing documents.
+#[derive(Debug, thiserror::Error)]
+pub enum DiffError {
+    #[error(transparent)]
+    UnKnownSyntax(#[from] UnKnownSyntaxError),
+    #[error(transparent)]
+    Parse(#[from] DynSynParseError),
+}
+
+impl ErrorCode for DiffError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::UnKnownSyntax(e) => e.code(),
+            Self::Parse(e) => e.code(),
+        }
+    }
+}
+
+/// Difference between statements of two documents.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StatementDiff {
+    /// Statements of second document, that are not in first document, in sorted order.
+    pub added: Vec<OwnedQuad>,
+    /// Statements of first document, that are not in second document, in sorted order.
+    pub removed: Vec<OwnedQuad>,
+}
+
+impl StatementDiff {
+    /// Returns true, if no statement is added, or removed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Returns true, if document `doc_a` in `syntax_a`, and document `doc_b` in `syntax_b` encode same dataset, up to renaming of blank nodes. Triple syntaxes encode statements of default graph.
+///
+/// # Errors
+/// returns [`DiffError`] if either of syntaxes is not supported, or if either of documents fails to parse.
+pub fn are_isomorphic<A: BufRead, B: BufRead>(
+    doc_a: A,
+    syntax_a: RdfSyntax,
+    doc_b: B,
+    syntax_b: RdfSyntax,
+) -> Result<bool, DiffError> {
+    let statements_a: Vec<_> = statements(doc_a, syntax_a)?.into_iter().collect();
+    let statements_b: Vec<_> = statements(doc_b, syntax_b)?.into_iter().collect();
+    Ok(statements_a.len() == statements_b.len()
+        && matches!(isomorphic_datasets(&statements_a, &statements_b), Ok(true)))
+}
+
+/// Returns statements, that are added, and removed from document `doc_a` in `syntax_a` to document `doc_b` in `syntax_b`, matching blank nodes by their structure. See [module documentation](self).
+///
+/// # Errors
+/// returns [`DiffError`] if either of syntaxes is not supported, or if either of documents fails to parse.
+pub fn diff<A: BufRead, B: BufRead>(
+    doc_a: A,
+    syntax_a: RdfSyntax,
+    doc_b: B,
+    syntax_b: RdfSyntax,
+) -> Result<StatementDiff, DiffError> {
+    let statements_a = statements(doc_a, syntax_a)?;
+    let statements_b = statements(doc_b, syntax_b)?;
+    let mapping = bnode_mapping(&statements_a, &statements_b);
+
+    let mut matched_b = HashSet::new();
+    let mut removed = Vec::new();
+    for q in &statements_a {
+        match mapped(q, &mapping).filter(|m| statements_b.contains(m)) {
+            Some(m) => {
+                matched_b.insert(m);
+            }
+            None => removed.push(q.clone()),
+        }
+    }
+    let added = statements_b
+        .iter()
+        .filter(|q| !matched_b.contains(*q))
+        .cloned()
+        .collect();
+    Ok(StatementDiff { added, removed })
+}
+
+/// Parse document into it's distinct statements, in sorted order.
+fn statements<R: BufRead>(data: R, syntax_: RdfSyntax) -> Result<BTreeSet<OwnedQuad>, DiffError> {
+    let parser =
+        DynSynQuadParserFactory::default().try_new_parser::<BoxTerm>(syntax_, None, None)?;
+    let mut statements = BTreeSet::new();
+    parser.parse(data).for_each_quad(|q| {
+        statements.insert(OwnedQuad::from_quad(&q));
+    })?;
+    Ok(statements)
+}
+
+fn is_bnode(t: &BoxTerm) -> bool {
+    t.kind() == TermKind::BlankNode
+}
+
+fn terms(q: &OwnedQuad) -> impl Iterator<Item = &BoxTerm> {
+    [&q.s, &q.p, &q.o].into_iter().chain(q.g.as_ref())
+}
+
+/// Returns given statement with it's blank nodes mapped, if all of them are mapped.
+fn mapped(q: &OwnedQuad, mapping: &HashMap<&BoxTerm, &BoxTerm>) -> Option<OwnedQuad> {
+    let map = |t: &BoxTerm| match is_bnode(t) {
+        true => mapping.get(t).map(|m| (*m).clone()),
+        false => Some(t.clone()),
+    };
+    Some(OwnedQuad {
+        s: map(&q.s)?,
+        p: map(&q.p)?,
+        o: map(&q.o)?,
+        g: match &q.g {
+            Some(g) => Some(map(g)?),
+            None => None,
+        },
+    })
+}
+
+/// Map blank nodes of `statements_a` to blank nodes of `statements_b`. Blank nodes with same colors are mapped first. Remaining blank nodes are mapped greedily, pairing those, whose mapping matches most statements, until no pairing matches any more statements.
+fn bnode_mapping<'q>(
+    statements_a: &'q BTreeSet<OwnedQuad>,
+    statements_b: &'q BTreeSet<OwnedQuad>,
+) -> HashMap<&'q BoxTerm, &'q BoxTerm> {
+    let blank = |statements: &'q BTreeSet<OwnedQuad>| -> Vec<&'q OwnedQuad> {
+        statements
+            .iter()
+            .filter(|q| terms(q).any(is_bnode))
+            .collect()
+    };
+    let (blank_a, blank_b) = (blank(statements_a), blank(statements_b));
+    let (colors_a, colors_b) = bnode_colors(&blank_a, &blank_b);
+
+    let classes = |colors: &HashMap<&'q BoxTerm, u64>| {
+        let mut classes: HashMap<u64, Vec<&'q BoxTerm>> = HashMap::new();
+        for (t, color) in colors {
+            classes.entry(*color).or_default().push(*t);
+        }
+        for members in classes.values_mut() {
+            members.sort_by(|t1, t2| cmp_terms(*t1, *t2));
+        }
+        classes
+    };
+    let classes_b = classes(&colors_b);
+    let mut mapping = HashMap::new();
+    for (color, members_a) in classes(&colors_a) {
+        match classes_b.get(&color) {
+            Some(members_b) if members_b.len() == members_a.len() => {
+                mapping.extend(members_a.into_iter().zip(members_b.iter().copied()));
+            }
+            _ => {}
+        }
+    }
+
+    let mapped_b: HashSet<_> = mapping.values().copied().collect();
+    let mut unmapped_a: Vec<_> = colors_a
+        .keys()
+        .filter(|t| !mapping.contains_key(*t))
+        .copied()
+        .collect();
+    let mut unmapped_b: Vec<_> = colors_b
+        .keys()
+        .filter(|t| !mapped_b.contains(*t))
+        .copied()
+        .collect();
+    unmapped_a.sort_by(|t1, t2| cmp_terms(*t1, *t2));
+    unmapped_b.sort_by(|t1, t2| cmp_terms(*t1, *t2));
+    let mut occurrences_a: HashMap<&BoxTerm, Vec<&OwnedQuad>> = HashMap::new();
+    for q in &blank_a {
+        for t in terms(q).filter(|t| is_bnode(t)) {
+            occurrences_a.entry(t).or_default().push(*q);
+        }
+    }
+
+    loop {
+        let mut best: Option<(usize, usize, usize)> = None;
+        for (i, a) in unmapped_a.iter().enumerate() {
+            for (j, b) in unmapped_b.iter().enumerate() {
+                mapping.insert(*a, *b);
+                let score = occurrences_a[a]
+                    .iter()
+                    .filter(|q| matches!(mapped(q, &mapping), Some(m) if statements_b.contains(&m)))
+                    .count();
+                mapping.remove(a);
+                if score > best.map_or(0, |(best_score, _, _)| best_score) {
+                    best = Some((score, i, j));
+                }
+            }
+        }
+        match best {
+            Some((_, i, j)) => {
+                mapping.insert(unmapped_a.remove(i), unmapped_b.remove(j));
+            }
+            None => return mapping,
+        }
+    }
+}
+
+/// Hash of given statement, with blank nodes replaced by their colors. Occurrences of `focus` blank node are marked distinctly, so that it's position in statement is accounted for.
+fn statement_hash(q: &OwnedQuad, colors: &HashMap<&BoxTerm, u64>, focus: &BoxTerm) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    q.g.is_some().hash(&mut hasher);
+    for t in terms(q) {
+        match is_bnode(t) {
+            true if t == focus => 0u8.hash(&mut hasher),
+            true => (1u8, colors[t]).hash(&mut hasher),
+            false => (2u8, t).hash(&mut hasher),
+        }
+    }
+    hasher.finish()
+}
+
+/// Color blank nodes of both statement lists jointly, refining colors until number of distinct colors grows on neither side. Colors don't depend on blank node labels, hence corresponding blank nodes of isomorphic documents get same colors.
+fn bnode_colors<'q>(
+    blank_a: &[&'q OwnedQuad],
+    blank_b: &[&'q OwnedQuad],
+) -> (HashMap<&'q BoxTerm, u64>, HashMap<&'q BoxTerm, u64>) {
+    let initial = |statements: &[&'q OwnedQuad]| -> HashMap<&'q BoxTerm, u64> {
+        statements
+            .iter()
+            .flat_map(|q| terms(q))
+            .filter(|t| is_bnode(t))
+            .map(|t| (t, 0))
+            .collect()
+    };
+    let refined = |statements: &[&'q OwnedQuad], colors: &HashMap<&'q BoxTerm, u64>| {
+        let mut signatures: HashMap<&'q BoxTerm, Vec<u64>> = HashMap::new();
+        for q in statements {
+            for t in terms(q).filter(|t| is_bnode(t)) {
+                signatures
+                    .entry(t)
+                    .or_default()
+                    .push(statement_hash(q, colors, t));
+            }
+        }
+        signatures
+            .into_iter()
+            .map(|(t, mut signature)| {
+                signature.sort_unstable();
+                let mut hasher = DefaultHasher::new();
+                (colors[t], signature).hash(&mut hasher);
+                (t, hasher.finish())
+            })
+            .collect::<HashMap<_, _>>()
+    };
+    let distinct = |colors: &HashMap<&BoxTerm, u64>| colors.values().collect::<HashSet<_>>().len();
+
+    let (mut colors_a, mut colors_b) = (initial(blank_a), initial(blank_b));
+    loop {
+        let (next_a, next_b) = (refined(blank_a, &colors_a), refined(blank_b, &colors_b));
+        let grown =
+            distinct(&next_a) > distinct(&colors_a) || distinct(&next_b) > distinct(&colors_b);
+        colors_a = next_a;
+        colors_b = next_b;
+        if !grown {
+            return (colors_a, colors_b);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use sophia_api::term::TTerm;
+    use test_case::test_case;
+
+    use super::{are_isomorphic, diff};
+    use crate::{
+        error_code::{self, ErrorCode},
+        model::OwnedQuad,
+        syntax::{self, RdfSyntax},
+        tests::TRACING,
+    };
+
+    const TURTLE_DOC: &str = r#"
+        @prefix : <http://example.org/> .
+        :alice :knows [ :name "Bob" ; :knows [ :name "Carol" ] ] .
+        :alice :name "Alice" .
+    "#;
+
+    #[cfg(feature = "rdf-xml")]
+    const RDF_XML_DOC: &str = r#"<?xml version="1.0"?>
+    <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:ex="http://example.org/">
+      <rdf:Description rdf:about="http://example.org/alice">
+        <ex:name>Alice</ex:name>
+        <ex:knows>
+          <rdf:Description>
+            <ex:knows><rdf:Description><ex:name>Carol</ex:name></rdf:Description></ex:knows>
+            <ex:name>Bob</ex:name>
+          </rdf:Description>
+        </ex:knows>
+      </rdf:Description>
+    </rdf:RDF>"#;
+
+    const N_TRIPLES_DOC: &str = r#"
+        _:c <http://example.org/name> "Carol" .
+        <http://example.org/alice> <http://example.org/name> "Alice" .
+        _:b <http://example.org/knows> _:c .
+        <http://example.org/alice> <http://example.org/knows> _:b .
+        _:b <http://example.org/name> "Bob" .
+    "#;
+
+    #[test_case(N_TRIPLES_DOC, syntax::N_TRIPLES)]
+    #[cfg_attr(feature = "rdf-xml", test_case(RDF_XML_DOC, syntax::RDF_XML))]
+    pub fn documents_with_relabeled_bnodes_are_isomorphic(doc: &str, syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        assert!(are_isomorphic(
+            TURTLE_DOC.as_bytes(),
+            syntax::TURTLE,
+            doc.as_bytes(),
+            syntax_
+        )
+        .unwrap());
+        assert!(diff(
+            TURTLE_DOC.as_bytes(),
+            syntax::TURTLE,
+            doc.as_bytes(),
+            syntax_
+        )
+        .unwrap()
+        .is_empty());
+    }
+
+    #[test]
+    pub fn reports_added_and_removed_statements() {
+        Lazy::force(&TRACING);
+        let changed = N_TRIPLES_DOC.replace("\"Carol\"", "\"Caroline\"").replace(
+            "<http://example.org/alice> <http://example.org/name> \"Alice\" .",
+            "",
+        );
+        assert!(!are_isomorphic(
+            TURTLE_DOC.as_bytes(),
+            syntax::TURTLE,
+            changed.as_bytes(),
+            syntax::N_TRIPLES
+        )
+        .unwrap());
+
+        let d = diff(
+            TURTLE_DOC.as_bytes(),
+            syntax::TURTLE,
+            changed.as_bytes(),
+            syntax::N_TRIPLES,
+        )
+        .unwrap();
+        let objects = |qs: &[OwnedQuad]| -> Vec<String> {
+            let mut objects: Vec<_> = qs.iter().map(|q| q.o.value().to_string()).collect();
+            objects.sort();
+            objects
+        };
+        assert_eq!(objects(&d.added), vec!["Caroline"]);
+        assert_eq!(objects(&d.removed), vec!["Alice", "Carol"]);
+    }
+
+    #[test]
+    pub fn distinguishes_graphs_of_statements() {
+        Lazy::force(&TRACING);
+        let doc_a = "<http://ex.org/s> <http://ex.org/p> _:o <http://ex.org/g1> .";
+        let doc_b = "<http://ex.org/s> <http://ex.org/p> _:x <http://ex.org/g2> .";
+        let d = diff(
+            doc_a.as_bytes(),
+            syntax::N_QUADS,
+            doc_b.as_bytes(),
+            syntax::N_QUADS,
+        )
+        .unwrap();
+        assert_eq!(d.added.len(), 1);
+        assert_eq!(d.removed.len(), 1);
+        assert!(!are_isomorphic(
+            doc_a.as_bytes(),
+            syntax::N_QUADS,
+            doc_b.as_bytes(),
+            syntax::N_QUADS
+        )
+        .unwrap());
+    }
+
+    #[test_case(syntax::JSON_LD, error_code::DYNSYN_UNKNOWN_SYNTAX)]
+    #[test_case(syntax::TURTLE, error_code::DYNSYN_PARSE_SYNTAX)]
+    pub fn comparing_documents_will_error(syntax_: RdfSyntax, expected_code: &str) {
+        Lazy::force(&TRACING);
+        let e = diff(
+            "<http://ex.org/a> <http://ex.org/b> .".as_bytes(),
+            syntax_,
+            N_TRIPLES_DOC.as_bytes(),
+            syntax::N_TRIPLES,
+        )
+        .unwrap_err();
+        assert_eq!(e.code(), expected_code);
+    }
+}
@@ -0,0 +1,127 @@
+//! This module defines stable, machine-readable codes for errors of this crate. Services can map errors to problem-details documents, alerting rules, etc. using these codes, instead of matching against their `Display` output.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{error_code::{self, ErrorCode}, parser::triples::DynSynTripleParserFactory, syntax};
+//! use sophia_term::BoxTerm;
+//!
+//! let e = DynSynTripleParserFactory::default()
+//!     .try_new_parser::<BoxTerm>(syntax::JSON_LD, None, None)
+//!     .unwrap_err();
+//! assert_eq!(e.code(), error_code::DYNSYN_UNKNOWN_SYNTAX);
+//! ```
+
+/// A trait for errors, that have a stable string code.
+pub trait ErrorCode {
+    /// Returns stable code of the error. Codes will not change across releases, once published.
+    fn code(&self) -> &'static str;
+}
+
+/// Requested syntax is not known/supported in given context.
+pub const DYNSYN_UNKNOWN_SYNTAX: &str = "DYNSYN_UNKNOWN_SYNTAX";
+
+/// Media type doesn't correspond to any rdf syntax.
+pub const DYNSYN_NON_RDF_MEDIA_TYPE: &str = "DYNSYN_NON_RDF_MEDIA_TYPE";
+
+/// File extension doesn't correspond to any rdf syntax.
+pub const DYNSYN_NON_RDF_FILE_EXTENSION: &str = "DYNSYN_NON_RDF_FILE_EXTENSION";
+
+/// Source document is not well-formed in it's syntax.
+pub const DYNSYN_PARSE_SYNTAX: &str = "DYNSYN_PARSE_SYNTAX";
+
+/// An io error occurred while reading source document.
+pub const DYNSYN_PARSE_IO: &str = "DYNSYN_PARSE_IO";
+
+/// Input appears to be gzip-compressed, instead of being an rdf document.
+pub const DYNSYN_PARSE_GZIP_INPUT: &str = "DYNSYN_PARSE_GZIP_INPUT";
+
+/// Input looks like an html error page, instead of being an rdf document.
+pub const DYNSYN_PARSE_HTML_INPUT: &str = "DYNSYN_PARSE_HTML_INPUT";
+
+/// Input appears to be truncated mid-statement.
+pub const DYNSYN_PARSE_TRUNCATED: &str = "DYNSYN_PARSE_TRUNCATED";
+
+/// A configured parse limit is exceeded by the document.
+pub const DYNSYN_LIMIT_EXCEEDED: &str = "DYNSYN_LIMIT_EXCEEDED";
+
+/// A parsed statement references an iri, that is disallowed by configured iri policy.
+pub const DYNSYN_IRI_POLICY_VIOLATION: &str = "DYNSYN_IRI_POLICY_VIOLATION";
+
+/// Lexical form of a literal is not valid for it's datatype.
+pub const DYNSYN_LITERAL_DECODE: &str = "DYNSYN_LITERAL_DECODE";
+
+/// Manifest of an archive is not valid.
+pub const DYNSYN_ARCHIVE_MANIFEST: &str = "DYNSYN_ARCHIVE_MANIFEST";
+
+/// An error occurred in writing parsed statements into a sink.
+pub const DYNSYN_STATEMENT_SINK: &str = "DYNSYN_STATEMENT_SINK";
+
+/// An error occurred in reading statements from a source, that is being serialized.
+pub const DYNSYN_STATEMENT_SOURCE: &str = "DYNSYN_STATEMENT_SOURCE";
+
+/// None of supported syntaxes is acceptable by an `Accept` header.
+pub const DYNSYN_NOT_ACCEPTABLE: &str = "DYNSYN_NOT_ACCEPTABLE";
+
+/// An io error occurred while writing serialized document.
+pub const DYNSYN_SERIALIZE_IO: &str = "DYNSYN_SERIALIZE_IO";
+
+/// Dataset couldn't be serialized in round-trip check.
+pub const DYNSYN_ROUNDTRIP_SERIALIZE: &str = "DYNSYN_ROUNDTRIP_SERIALIZE";
+
+/// Serialized doc couldn't be parsed back in round-trip check.
+pub const DYNSYN_ROUNDTRIP_PARSE: &str = "DYNSYN_ROUNDTRIP_PARSE";
+
+/// Parsed back dataset is not isomorphic to original dataset in round-trip check.
+pub const DYNSYN_ROUNDTRIP_NOT_ISOMORPHIC: &str = "DYNSYN_ROUNDTRIP_NOT_ISOMORPHIC";
+
+/// An io error occurred while reading, or writing files being converted.
+pub const DYNSYN_CONVERT_IO: &str = "DYNSYN_CONVERT_IO";
+
+/// A document couldn't be fetched.
+pub const DYNSYN_FETCH: &str = "DYNSYN_FETCH";
+
+/// Syntax of a fetched document couldn't be determined from it's media type, or iri.
+pub const DYNSYN_UNDETERMINED_SYNTAX: &str = "DYNSYN_UNDETERMINED_SYNTAX";
+
+/// Value of a `Content-Type` header is not a valid media type.
+pub const DYNSYN_INVALID_CONTENT_TYPE: &str = "DYNSYN_INVALID_CONTENT_TYPE";
+
+/// Given iri is not valid.
+pub const DYNSYN_INVALID_IRI: &str = "DYNSYN_INVALID_IRI";
+
+/// Syntax is supported by serializers for other kind of statements, but not for requested kind.
+pub const DYNSYN_SYNTAX_ROLE_MISMATCH: &str = "DYNSYN_SYNTAX_ROLE_MISMATCH";
+
+/// Snapshot couldn't be serialized.
+pub const DYNSYN_SNAPSHOT_SERIALIZE: &str = "DYNSYN_SNAPSHOT_SERIALIZE";
+
+/// Golden file of a snapshot couldn't be read, or written.
+pub const DYNSYN_SNAPSHOT_IO: &str = "DYNSYN_SNAPSHOT_IO";
+
+/// Snapshot doesn't match it's golden file.
+pub const DYNSYN_SNAPSHOT_MISMATCH: &str = "DYNSYN_SNAPSHOT_MISMATCH";
+
+/// A parsed statement has a literal, that violates configured literal policy.
+pub const DYNSYN_LITERAL_POLICY_VIOLATION: &str = "DYNSYN_LITERAL_POLICY_VIOLATION";
+
+/// Statements to be pretty serialized have a blank node cycle, and serializer is configured to fail on it.
+pub const DYNSYN_BLANK_NODE_CYCLE: &str = "DYNSYN_BLANK_NODE_CYCLE";
+
+/// Canonicalization of statements to be serialized takes more steps than configured.
+pub const DYNSYN_CANON_LIMIT_EXCEEDED: &str = "DYNSYN_CANON_LIMIT_EXCEEDED";
+
+/// Indentation string of a pretty-printing config contains characters other than ascii whitespaces.
+pub const DYNSYN_INVALID_INDENT: &str = "DYNSYN_INVALID_INDENT";
+
+/// A quad in a named graph is serialized into a triple-only syntax, that can't represent graphs.
+pub const DYNSYN_NAMED_GRAPH_IN_TRIPLE_SYNTAX: &str = "DYNSYN_NAMED_GRAPH_IN_TRIPLE_SYNTAX";
+
+/// Parsed statements of a payload couldn't be collected into a dataset.
+pub const DYNSYN_SERVICE_DATASET: &str = "DYNSYN_SERVICE_DATASET";
+
+/// An argument of an ffi call is a null pointer, or not a valid utf-8 string.
+pub const DYNSYN_FFI_INVALID_ARGUMENT: &str = "DYNSYN_FFI_INVALID_ARGUMENT";
+
+/// An ffi call panicked. Panic is caught at the C abi boundary.
+pub const DYNSYN_FFI_PANICKED: &str = "DYNSYN_FFI_PANICKED";
@@ -0,0 +1,332 @@
+//! This module provides reader, and writer adaptors, that inject configurable faults, like short reads, interrupted calls, io errors, and invalid utf-8 at given offsets. Downstream services can test their error handling around dynsyn parsers, and serializers with them, without crafting corrupted fixture files. It is available only when `fault_injection` feature is enabled.
+//!
+//! Example:
+//!
+//! ```
+//! use std::io::BufReader;
+//!
+//! use rdf_dynsyn::{
+//!     error_code::{self, ErrorCode},
+//!     fault_injection::{FaultConfig, FaultyRead},
+//!     parser::triples::DynSynTripleParserFactory,
+//!     syntax,
+//! };
+//! use sophia_api::{parser::TripleParser, triple::stream::TripleSource};
+//! use sophia_term::BoxTerm;
+//!
+//! let doc = "<tag:s> <tag:p> \"é\" .\n".repeat(4);
+//! let read = FaultyRead::new(doc.as_bytes(), FaultConfig::new().with_invalid_utf8_at(50));
+//! let e = DynSynTripleParserFactory::default()
+//!     .try_new_parser::<BoxTerm>(syntax::N_TRIPLES, None, None)
+//!     .unwrap()
+//!     .parse(BufReader::new(read))
+//!     .for_each_triple(|_| {})
+//!     .unwrap_err();
+//! assert_eq!(e.code(), error_code::DYNSYN_PARSE_SYNTAX);
+//! ```
+
+use std::io::{self, Read, Write};
+
+/// Configuration of faults to inject. Offsets are counted in bytes, from start of the stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FaultConfig {
+    /// If set, each read, or write transfers at most these many bytes. It must not be zero.
+    pub max_chunk: Option<usize>,
+    /// If set, every n'th call fails with [`io::ErrorKind::Interrupted`], without transferring any bytes. It must not be zero.
+    pub interrupt_every: Option<usize>,
+    /// If set, calls fail with an [`io::ErrorKind::Other`] error, once these many bytes are transferred.
+    pub fail_at: Option<u64>,
+    /// If set, byte at this offset is replaced with `0xFF`, which is never valid in utf-8.
+    pub invalid_utf8_at: Option<u64>,
+}
+
+impl FaultConfig {
+    /// Create a new config, that injects no faults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set maximum number of bytes, that each call transfers.
+    ///
+    /// # Panics
+    /// Panics if `max_chunk` is zero.
+    pub fn with_max_chunk(mut self, max_chunk: usize) -> Self {
+        assert!(max_chunk > 0, "max_chunk must not be zero");
+        self.max_chunk = Some(max_chunk);
+        self
+    }
+
+    /// Set period of interrupted calls.
+    ///
+    /// # Panics
+    /// Panics if `interrupt_every` is zero.
+    pub fn with_interrupt_every(mut self, interrupt_every: usize) -> Self {
+        assert!(interrupt_every > 0, "interrupt_every must not be zero");
+        self.interrupt_every = Some(interrupt_every);
+        self
+    }
+
+    /// Set offset, at which calls start failing.
+    pub fn with_fail_at(mut self, fail_at: u64) -> Self {
+        self.fail_at = Some(fail_at);
+        self
+    }
+
+    /// Set offset of invalid utf-8 byte.
+    pub fn with_invalid_utf8_at(mut self, invalid_utf8_at: u64) -> Self {
+        self.invalid_utf8_at = Some(invalid_utf8_at);
+        self
+    }
+}
+
+/// State of fault injection, that is common to readers, and writers.
+#[derive(Debug)]
+struct Injector {
+    config: FaultConfig,
+    offset: u64,
+    calls: usize,
+}
+
+impl Injector {
+    fn new(config: FaultConfig) -> Self {
+        Self {
+            config,
+            offset: 0,
+            calls: 0,
+        }
+    }
+
+    /// Returns number of bytes, that current call can transfer out of `len` requested, or an injected error.
+    fn admit(&mut self, len: usize) -> io::Result<usize> {
+        self.calls += 1;
+        if matches!(self.config.interrupt_every, Some(n) if self.calls.is_multiple_of(n)) {
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "injected interruption"));
+        }
+        let mut len = len.min(self.config.max_chunk.unwrap_or(usize::MAX));
+        if let Some(fail_at) = self.config.fail_at {
+            if self.offset >= fail_at && len > 0 {
+                return Err(io::Error::other("injected io error"));
+            }
+            len = len.min((fail_at - self.offset).try_into().unwrap_or(usize::MAX));
+        }
+        Ok(len)
+    }
+
+    /// Corrupts bytes to be transferred from current offset, as configured.
+    fn corrupt(&self, buf: &mut [u8]) {
+        if let Some(at) = self.config.invalid_utf8_at {
+            if (self.offset..self.offset + buf.len() as u64).contains(&at) {
+                buf[(at - self.offset) as usize] = 0xFF;
+            }
+        }
+    }
+}
+
+/// A reader adaptor, that injects faults configured by [`FaultConfig`] into reads from underlying reader. Wrap it in a [`BufReader`](std::io::BufReader) to parse from it.
+#[derive(Debug)]
+pub struct FaultyRead<R> {
+    read: R,
+    injector: Injector,
+}
+
+impl<R: Read> FaultyRead<R> {
+    /// Create a new reader, that injects faults into reads from `read`.
+    pub fn new(read: R, config: FaultConfig) -> Self {
+        Self {
+            read,
+            injector: Injector::new(config),
+        }
+    }
+
+    /// Returns number of bytes read so far.
+    pub fn offset(&self) -> u64 {
+        self.injector.offset
+    }
+}
+
+impl<R: Read> Read for FaultyRead<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.injector.admit(buf.len())?;
+        let n = self.read.read(&mut buf[..len])?;
+        self.injector.corrupt(&mut buf[..n]);
+        self.injector.offset += n as u64;
+        Ok(n)
+    }
+}
+
+/// A writer adaptor, that injects faults configured by [`FaultConfig`] into writes to underlying writer.
+#[derive(Debug)]
+pub struct FaultyWrite<W> {
+    write: W,
+    injector: Injector,
+}
+
+impl<W: Write> FaultyWrite<W> {
+    /// Create a new writer, that injects faults into writes to `write`.
+    pub fn new(write: W, config: FaultConfig) -> Self {
+        Self {
+            write,
+            injector: Injector::new(config),
+        }
+    }
+
+    /// Returns number of bytes written so far.
+    pub fn offset(&self) -> u64 {
+        self.injector.offset
+    }
+
+    /// Get reference to underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.write
+    }
+
+    /// Unwrap underlying writer.
+    pub fn into_inner(self) -> W {
+        self.write
+    }
+}
+
+impl<W: Write> Write for FaultyWrite<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.injector.admit(buf.len())?;
+        let mut chunk = buf[..len].to_vec();
+        self.injector.corrupt(&mut chunk);
+        let n = self.write.write(&chunk)?;
+        self.injector.offset += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write.flush()
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Read, Write};
+
+    use once_cell::sync::Lazy;
+    use sophia_api::{
+        graph::isomorphic_graphs, parser::TripleParser, serializer::TripleSerializer,
+        triple::stream::{StreamError, TripleSource},
+    };
+    use sophia_inmem::graph::FastGraph;
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+
+    use super::{FaultConfig, FaultyRead, FaultyWrite};
+    use crate::{
+        error_code::{self, ErrorCode},
+        parser::triples::DynSynTripleParserFactory,
+        serializer::triples::DynSynTripleSerializerFactory,
+        syntax::{self, RdfSyntax},
+        tests::TRACING,
+    };
+
+    static DOC: &str = r#"
+        @prefix ex: <http://example.org/> .
+        ex:alice ex:name "Alice" ; ex:knows ex:bob .
+        ex:bob ex:name "Bøb" .
+    "#;
+
+    fn parse<R: Read>(read: R) -> Result<FastGraph, String> {
+        DynSynTripleParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)
+            .unwrap()
+            .parse(BufReader::new(read))
+            .collect_triples()
+            .map_err(|e| match e {
+                StreamError::SourceError(e) => e.code().to_string(),
+                StreamError::SinkError(e) => match e {},
+            })
+    }
+
+    #[test]
+    pub fn reads_are_faithful_with_short_reads() {
+        Lazy::force(&TRACING);
+        let expected = parse(DOC.as_bytes()).unwrap();
+        let mut read = FaultyRead::new(DOC.as_bytes(), FaultConfig::new().with_max_chunk(1));
+        let mut buf = [0; 16];
+        assert_eq!(read.read(&mut buf).unwrap(), 1);
+        let actual = parse(FaultyRead::new(
+            DOC.as_bytes(),
+            FaultConfig::new().with_max_chunk(3),
+        ))
+        .unwrap();
+        assert!(isomorphic_graphs(&expected, &actual).unwrap());
+    }
+
+    #[test_case(FaultConfig::new().with_fail_at(40), error_code::DYNSYN_PARSE_IO)]
+    #[test_case(FaultConfig::new().with_invalid_utf8_at(60), error_code::DYNSYN_PARSE_SYNTAX)]
+    pub fn injected_read_faults_surface_as_parse_errors(config: FaultConfig, code: &str) {
+        Lazy::force(&TRACING);
+        assert_eq!(parse(FaultyRead::new(DOC.as_bytes(), config)).err().unwrap(), code);
+    }
+
+    #[test]
+    pub fn reports_interrupted_reads() {
+        Lazy::force(&TRACING);
+        let mut read = FaultyRead::new(DOC.as_bytes(), FaultConfig::new().with_interrupt_every(2));
+        let mut buf = [0; 4];
+        assert!(read.read(&mut buf).is_ok());
+        assert_eq!(
+            read.read(&mut buf).unwrap_err().kind(),
+            std::io::ErrorKind::Interrupted
+        );
+        assert_eq!(read.offset(), 4);
+        // Interruptions are retried by `read_to_end`.
+        let mut doc = String::new();
+        read.read_to_string(&mut doc).unwrap();
+        assert_eq!(doc.len(), DOC.len() - 4);
+    }
+
+    #[test_case(syntax::N_TRIPLES)]
+    #[test_case(syntax::TURTLE)]
+    #[cfg_attr(feature = "rdf-xml", test_case(syntax::RDF_XML))]
+    pub fn serializes_faithfully_through_short_interrupted_writes(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let graph = parse(DOC.as_bytes()).unwrap();
+        let config = FaultConfig::new().with_max_chunk(5).with_interrupt_every(3);
+        let mut write = FaultyWrite::new(Vec::new(), config);
+        DynSynTripleSerializerFactory::default()
+            .try_new_serializer(syntax_, &mut write)
+            .unwrap()
+            .serialize_graph(&graph)
+            .unwrap();
+        write.flush().unwrap();
+        let out = write.into_inner();
+        let actual: FastGraph = DynSynTripleParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap()
+            .parse(&out[..])
+            .collect_triples()
+            .unwrap();
+        assert!(isomorphic_graphs(&graph, &actual).unwrap());
+    }
+
+    #[test]
+    pub fn injected_write_faults_fail_serialization() {
+        Lazy::force(&TRACING);
+        let graph = parse(DOC.as_bytes()).unwrap();
+        let mut write = FaultyWrite::new(Vec::new(), FaultConfig::new().with_fail_at(10));
+        assert!(DynSynTripleSerializerFactory::default()
+            .try_new_serializer(syntax::N_TRIPLES, &mut write)
+            .unwrap()
+            .serialize_graph(&graph)
+            .is_err());
+        assert_eq!(write.offset(), 10);
+        assert_eq!(write.get_ref().len(), 10);
+    }
+
+    #[test]
+    pub fn writes_invalid_utf8_at_offset() {
+        Lazy::force(&TRACING);
+        let mut write = FaultyWrite::new(Vec::new(), FaultConfig::new().with_invalid_utf8_at(3));
+        write.write_all(b"<a> <b> <c> .").unwrap();
+        assert_eq!(write.get_ref()[3], 0xFF);
+        assert!(String::from_utf8(write.into_inner()).is_err());
+    }
+}
@@ -0,0 +1,593 @@
+//! This module provides follow-your-nose helpers for linked data clients. [`Describer::describe`] dereferences an iri with content negotiation, parses fetched document in it's negotiated syntax, extracts [concise bounded description](crate::serializer::cbd) of the resource, and returns it serialized in requested syntax.
+//!
+//! Transport is pluggable through [`DocumentFetcher`] trait, so that applications can use their http client of choice. [`FileFetcher`] is provided for `file:` iris. With `reqwest` feature, `HttpFetcher` is provided for `http:`, and `https:` iris, along with `fetch_graph`, and `fetch_dataset` functions, that dereference an iri into a graph, or a dataset.
+//!
+//! Example:
+//!
+//! ```
+//! use std::io;
+//!
+//! use rdf_dynsyn::{fetch::{Describer, FetchedDocument}, syntax};
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! // A fetcher that serves a fixed document. Real applications would use an http client.
+//! let fetcher = |iri: &str, _accept: &str| -> io::Result<FetchedDocument> {
+//!     Ok(FetchedDocument {
+//!         iri: iri.to_owned(),
+//!         content_type: Some("text/turtle; charset=utf-8".parse().unwrap()),
+//!         body: br#"<#me> <http://xmlns.com/foaf/0.1/name> "Me" . <#you> <http://xmlns.com/foaf/0.1/name> "You" ."#.to_vec(),
+//!     })
+//! };
+//!
+//! let describer = Describer::new(fetcher);
+//! let description = describer.describe("http://example.org/people#me", syntax::N_TRIPLES)?;
+//! assert_eq!(
+//!     description,
+//!     "<http://example.org/people#me> <http://xmlns.com/foaf/0.1/name> \"Me\".\n"
+//! );
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::{
+    fs::{self, File},
+    io::{self, BufRead, BufReader},
+    path::Path,
+};
+
+use mime::Mime;
+use sophia_api::parser::TripleParser;
+use sophia_term::{BoxTerm, TermError};
+
+use crate::{
+    correspondence::{Correspondent, NonRdfMediaTypeError},
+    error_code::{self, ErrorCode},
+    file_extension::FileExtension,
+    media_type::accept_header_for_parsers,
+    parser::{errors::DynSynParseError, triples::DynSynTripleParserFactory},
+    serializer::{
+        cbd::serialize_cbd,
+        serializable::{DynSynSerializeError, DynSynSerializerFactories},
+    },
+    syntax::{RdfSyntax, UnKnownSyntaxError},
+};
+
+#[cfg(feature = "reqwest")]
+use sophia_api::{dataset::CollectibleDataset, graph::CollectibleGraph};
+
+#[cfg(feature = "reqwest")]
+use crate::load::{load_dataset_from_url, load_graph_from_url, LoadError};
+
+/// A fetched document.
+#[derive(Debug, Clone)]
+pub struct FetchedDocument {
+    /// Iri of the document, after following any redirects. It is used as base iri for parsing.
+    pub iri: String,
+    /// Media type of the document, if known.
+    pub content_type: Option<Mime>,
+    /// Content of the document.
+    pub body: Vec<u8>,
+}
+
+impl FetchedDocument {
+    /// Get syntax of the document, as determined from it's media type, or from file extension of it's iri, if media type is unknown. Returns `None`, if media type is unknown, and iri has no rdf file extension.
+    ///
+    /// # Errors
+    /// returns [`NonRdfMediaTypeError`] if media type of the document doesn't correspond to any rdf syntax.
+    pub fn syntax(&self) -> Result<Option<RdfSyntax>, NonRdfMediaTypeError> {
+        document_syntax(&self.iri, self.content_type.as_ref())
+    }
+}
+
+/// A fetched document, whose body is read as it is received, instead of being buffered whole.
+pub struct StreamedDocument {
+    /// Iri of the document, after following any redirects. It is used as base iri for parsing.
+    pub iri: String,
+    /// Media type of the document, if known.
+    pub content_type: Option<Mime>,
+    /// Reader of content of the document.
+    pub body: Box<dyn BufRead>,
+}
+
+impl StreamedDocument {
+    /// Get syntax of the document. See [`FetchedDocument::syntax`].
+    ///
+    /// # Errors
+    /// returns [`NonRdfMediaTypeError`] if media type of the document doesn't correspond to any rdf syntax.
+    pub fn syntax(&self) -> Result<Option<RdfSyntax>, NonRdfMediaTypeError> {
+        document_syntax(&self.iri, self.content_type.as_ref())
+    }
+}
+
+impl From<FetchedDocument> for StreamedDocument {
+    fn from(doc: FetchedDocument) -> Self {
+        Self {
+            iri: doc.iri,
+            content_type: doc.content_type,
+            body: Box::new(io::Cursor::new(doc.body)),
+        }
+    }
+}
+
+/// Get syntax of a document at `iri`, from it's media type, or from file extension of it's iri, if media type is unknown.
+fn document_syntax(
+    iri: &str,
+    content_type: Option<&Mime>,
+) -> Result<Option<RdfSyntax>, NonRdfMediaTypeError> {
+    match content_type {
+        Some(content_type) => Ok(Some(
+            Correspondent::<RdfSyntax>::try_from(content_type)?.value,
+        )),
+        None => Ok(FileExtension::from_path_str(iri)
+            .and_then(|extn| Correspondent::<RdfSyntax>::try_from(&extn).ok())
+            .map(|c| c.value)),
+    }
+}
+
+/// A trait for fetchers of documents, that back [`Describer`].
+///
+/// It is implemented for closures with matching signature.
+pub trait DocumentFetcher {
+    /// Fetches document at given `iri`, which has no fragment. `accept` is the value for `Accept` header to negotiate content with, that is [`accept_header_for_parsers`].
+    fn fetch(&self, iri: &str, accept: &str) -> io::Result<FetchedDocument>;
+
+    /// Fetches document at given `iri`, like [`fetch`](Self::fetch), but returns a reader of it's body, so that it can be parsed as it is received. Default impl reads whole body with [`fetch`](Self::fetch).
+    fn fetch_streamed(&self, iri: &str, accept: &str) -> io::Result<StreamedDocument> {
+        self.fetch(iri, accept).map(StreamedDocument::from)
+    }
+}
+
+impl<F> DocumentFetcher for F
+where
+    F: Fn(&str, &str) -> io::Result<FetchedDocument>,
+{
+    fn fetch(&self, iri: &str, accept: &str) -> io::Result<FetchedDocument> {
+        self(iri, accept)
+    }
+}
+
+/// A [`DocumentFetcher`] for `file:` iris with absolute paths. Media type of fetched documents is left unknown, so that their syntax is determined from file extension.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FileFetcher;
+
+impl FileFetcher {
+    fn path(iri: &str) -> io::Result<&Path> {
+        let path = iri.strip_prefix("file://").ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is not a file iri", iri),
+            )
+        })?;
+        Ok(Path::new(path))
+    }
+}
+
+impl DocumentFetcher for FileFetcher {
+    fn fetch(&self, iri: &str, _accept: &str) -> io::Result<FetchedDocument> {
+        Ok(FetchedDocument {
+            iri: iri.to_owned(),
+            content_type: None,
+            body: fs::read(Self::path(iri)?)?,
+        })
+    }
+
+    fn fetch_streamed(&self, iri: &str, _accept: &str) -> io::Result<StreamedDocument> {
+        Ok(StreamedDocument {
+            iri: iri.to_owned(),
+            content_type: None,
+            body: Box::new(BufReader::new(File::open(Self::path(iri)?)?)),
+        })
+    }
+}
+
+/// A [`DocumentFetcher`] for `http:`, and `https:` iris, backed by a blocking [`reqwest`] client. Redirects are followed, and fetched documents take iri of final response, and it's `Content-Type`.
+///
+/// With [`fetch_streamed`](DocumentFetcher::fetch_streamed), response is handed to parser as body reader, so that documents are parsed as they are received, without being buffered whole. Then errors in receiving body surface as io errors of parser.
+#[cfg(feature = "reqwest")]
+#[derive(Debug, Clone, Default)]
+pub struct HttpFetcher {
+    client: reqwest::blocking::Client,
+}
+
+#[cfg(feature = "reqwest")]
+impl HttpFetcher {
+    /// Create a new fetcher, that sends requests with given `client`.
+    pub fn new(client: reqwest::blocking::Client) -> Self {
+        Self { client }
+    }
+
+    /// Sends request for document at `iri`, and returns successful response, before it's body is read.
+    fn send(&self, iri: &str, accept: &str) -> io::Result<reqwest::blocking::Response> {
+        self.client
+            .get(iri)
+            .header(reqwest::header::ACCEPT, accept)
+            .send()
+            .and_then(|r| r.error_for_status())
+            .map_err(io::Error::other)
+    }
+
+    /// Get content type of given response, if it is a valid media type.
+    fn content_type(response: &reqwest::blocking::Response) -> Option<Mime> {
+        response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl DocumentFetcher for HttpFetcher {
+    fn fetch(&self, iri: &str, accept: &str) -> io::Result<FetchedDocument> {
+        let response = self.send(iri, accept)?;
+        Ok(FetchedDocument {
+            iri: response.url().to_string(),
+            content_type: Self::content_type(&response),
+            body: response.bytes().map_err(io::Error::other)?.to_vec(),
+        })
+    }
+
+    fn fetch_streamed(&self, iri: &str, accept: &str) -> io::Result<StreamedDocument> {
+        let response = self.send(iri, accept)?;
+        Ok(StreamedDocument {
+            iri: response.url().to_string(),
+            content_type: Self::content_type(&response),
+            body: Box::new(BufReader::new(response)),
+        })
+    }
+}
+
+/// Dereferences given `iri` over http, with content negotiation, and parses default graph of fetched document. Parser is picked from `Content-Type` of the response, or from file extension of it's iri, if it has no `Content-Type`.
+///
+/// # Errors
+/// returns [`LoadError`] if document couldn't be fetched, if it's syntax couldn't be resolved, or if it couldn't be parsed, or collected into the graph.
+#[cfg(feature = "reqwest")]
+pub fn fetch_graph<G>(iri: &str) -> Result<G, LoadError>
+where
+    G: CollectibleGraph,
+    G::Error: Send + Sync,
+{
+    load_graph_from_url(iri, &HttpFetcher::default())
+}
+
+/// Dereferences given `iri` over http, with content negotiation, and parses dataset of fetched document. See [`fetch_graph`].
+///
+/// # Errors
+/// returns [`LoadError`] if document couldn't be fetched, if it's syntax couldn't be resolved, or if it couldn't be parsed, or collected into the dataset.
+#[cfg(feature = "reqwest")]
+pub fn fetch_dataset<D>(iri: &str) -> Result<D, LoadError>
+where
+    D: CollectibleDataset,
+    D::Error: Send + Sync,
+{
+    load_dataset_from_url(iri, &HttpFetcher::default())
+}
+
+/// An error in describing a resource.
+#[derive(Debug, thiserror::Error)]
+pub enum DescribeError {
+    #[error("Invalid iri: {0}")]
+    InvalidIri(#[from] TermError),
+    #[error("Error in fetching document: {0}")]
+    Fetch(#[source] io::Error),
+    #[error(transparent)]
+    NonRdfMediaType(#[from] NonRdfMediaTypeError),
+    #[error("Syntax of document at {0} couldn't be determined")]
+    UnDeterminedSyntax(String),
+    #[error(transparent)]
+    UnKnownSyntax(#[from] UnKnownSyntaxError),
+    #[error(transparent)]
+    Serialize(#[from] DynSynSerializeError<DynSynParseError>),
+}
+
+impl ErrorCode for DescribeError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidIri(_) => error_code::DYNSYN_INVALID_IRI,
+            Self::Fetch(_) => error_code::DYNSYN_FETCH,
+            Self::NonRdfMediaType(e) => e.code(),
+            Self::UnDeterminedSyntax(_) => error_code::DYNSYN_UNDETERMINED_SYNTAX,
+            Self::UnKnownSyntax(e) => e.code(),
+            Self::Serialize(DynSynSerializeError::Source(e)) => e.code(),
+            Self::Serialize(e) => e.code(),
+        }
+    }
+}
+
+/// Describes linked data resources, by dereferencing their iris.
+pub struct Describer<F> {
+    fetcher: F,
+    parser_factory: DynSynTripleParserFactory,
+    serializer_factories: DynSynSerializerFactories,
+}
+
+impl<F: DocumentFetcher> Describer<F> {
+    /// Create a new describer, that fetches documents with given `fetcher`, and uses default parsers, and serializers.
+    pub fn new(fetcher: F) -> Self {
+        Self::new_with_config(
+            fetcher,
+            DynSynTripleParserFactory::default(),
+            DynSynSerializerFactories::default(),
+        )
+    }
+
+    /// Create a new describer, with given parser factory, and serializer config.
+    pub fn new_with_config(
+        fetcher: F,
+        parser_factory: DynSynTripleParserFactory,
+        serializer_factories: DynSynSerializerFactories,
+    ) -> Self {
+        Self {
+            fetcher,
+            parser_factory,
+            serializer_factories,
+        }
+    }
+
+    /// Fetches document of resource with given `iri`, and returns resource's concise bounded description from it's default graph, serialized into `syntax_out`.
+    ///
+    /// Document is fetched from `iri` without it's fragment. It's syntax is determined from it's media type, or from it's file extension, if media type is unknown.
+    ///
+    /// # Errors
+    /// returns [`DescribeError`] if iri is invalid, if document couldn't be fetched, or parsed, or if `syntax_out` is not supported.
+    pub fn describe(&self, iri: &str, syntax_out: RdfSyntax) -> Result<String, DescribeError> {
+        let subject = BoxTerm::new_iri(iri)?;
+        let doc_iri = iri.split('#').next().unwrap_or(iri);
+        let doc = self
+            .fetcher
+            .fetch_streamed(doc_iri, accept_header_for_parsers())
+            .map_err(DescribeError::Fetch)?;
+
+        let syntax_in = doc
+            .syntax()?
+            .ok_or_else(|| DescribeError::UnDeterminedSyntax(doc.iri.clone()))?;
+
+        let parser = self.parser_factory.try_new_parser(
+            syntax_in,
+            Some(doc.iri.clone()),
+            None as Option<BoxTerm>,
+        )?;
+
+        let mut out = Vec::new();
+        serialize_cbd(
+            parser.parse(doc.body),
+            &subject,
+            syntax_out,
+            &mut out,
+            &self.serializer_factories,
+        )?;
+        Ok(String::from_utf8(out).expect("Serializers always write valid utf-8"))
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, io};
+    #[cfg(feature = "reqwest")]
+    use std::{
+        io::{BufRead, Write},
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use once_cell::sync::Lazy;
+    use sophia_api::{
+        graph::isomorphic_graphs, parser::TripleParser, triple::stream::TripleSource,
+    };
+    use sophia_inmem::graph::FastGraph;
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+
+    #[cfg(feature = "reqwest")]
+    use super::HttpFetcher;
+    use super::{Describer, FetchedDocument, FileFetcher};
+    #[cfg(feature = "reqwest")]
+    use crate::load::load_graph_from_url;
+    use crate::{
+        error_code::{self, ErrorCode},
+        media_type::accept_header_for_parsers,
+        parser::triples::DynSynTripleParserFactory,
+        syntax::{self, RdfSyntax},
+        tests::TRACING,
+    };
+
+    static DOC: &str = r#"
+        @prefix foaf: <http://xmlns.com/foaf/0.1/> .
+        <#me> foaf:name "Me" ; foaf:based_near [ foaf:name "Paris" ] .
+        <#you> foaf:name "You" .
+    "#;
+
+    fn parse(doc: &str) -> FastGraph {
+        DynSynTripleParserFactory::default()
+            .try_new_parser(syntax::TURTLE, None, None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(doc)
+            .collect_triples()
+            .unwrap()
+    }
+
+    #[test_case(syntax::N_TRIPLES)]
+    #[test_case(syntax::TURTLE)]
+    #[cfg_attr(feature = "rdf-xml", test_case(syntax::RDF_XML))]
+    pub fn describes_resource_from_negotiated_document(syntax_out: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let requests = RefCell::new(Vec::new());
+        let fetcher = |iri: &str, accept: &str| -> io::Result<FetchedDocument> {
+            requests
+                .borrow_mut()
+                .push((iri.to_owned(), accept.to_owned()));
+            Ok(FetchedDocument {
+                iri: iri.to_owned(),
+                content_type: Some("text/turtle; charset=utf-8".parse().unwrap()),
+                body: DOC.as_bytes().to_vec(),
+            })
+        };
+        let out = Describer::new(fetcher)
+            .describe("http://example.org/people#me", syntax_out)
+            .unwrap();
+
+        assert_eq!(
+            requests.into_inner(),
+            vec![(
+                "http://example.org/people".to_owned(),
+                accept_header_for_parsers().to_owned()
+            )]
+        );
+        let actual: FastGraph = DynSynTripleParserFactory::default()
+            .try_new_parser(syntax_out, None, None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(&out)
+            .collect_triples()
+            .unwrap();
+        let expected = parse(
+            r#"
+            @prefix foaf: <http://xmlns.com/foaf/0.1/> .
+            <http://example.org/people#me> foaf:name "Me" ;
+                foaf:based_near [ foaf:name "Paris" ] .
+            "#,
+        );
+        assert!(isomorphic_graphs(&expected, &actual).unwrap());
+    }
+
+    #[test_case(Some("application/json"), error_code::DYNSYN_NON_RDF_MEDIA_TYPE)]
+    #[test_case(Some("application/ld+json"), error_code::DYNSYN_UNKNOWN_SYNTAX)]
+    #[test_case(None, error_code::DYNSYN_UNDETERMINED_SYNTAX)]
+    #[test_case(Some("application/n-triples"), error_code::DYNSYN_PARSE_SYNTAX)]
+    pub fn reports_undescribable_documents(content_type: Option<&'static str>, code: &str) {
+        Lazy::force(&TRACING);
+        let fetcher = |iri: &str, _: &str| -> io::Result<FetchedDocument> {
+            Ok(FetchedDocument {
+                iri: iri.to_owned(),
+                content_type: content_type.map(|c| c.parse().unwrap()),
+                body: DOC.as_bytes().to_vec(),
+            })
+        };
+        let e = Describer::new(fetcher)
+            .describe("http://example.org/people#me", syntax::TURTLE)
+            .unwrap_err();
+        assert_eq!(e.code(), code);
+    }
+
+    #[test]
+    pub fn describes_resource_from_file() {
+        Lazy::force(&TRACING);
+        let path = std::env::temp_dir().join(format!("dynsyn-describe-{}.ttl", std::process::id()));
+        std::fs::write(&path, DOC).unwrap();
+        let iri = format!("file://{}", path.display());
+
+        let out = Describer::new(FileFetcher)
+            .describe(&format!("{}#you", iri), syntax::N_TRIPLES)
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            out,
+            format!("<{}#you> <http://xmlns.com/foaf/0.1/name> \"You\".\n", iri)
+        );
+    }
+
+    #[test]
+    pub fn reports_fetch_errors() {
+        Lazy::force(&TRACING);
+        let e = Describer::new(FileFetcher)
+            .describe("http://example.org/people#me", syntax::TURTLE)
+            .unwrap_err();
+        assert_eq!(e.code(), error_code::DYNSYN_FETCH);
+    }
+
+    /// Serves a single request on a local port, with given body parts, and returns url of served document, along with a handle, that joins to head of received request. Parts after first one are sent only after they are released, or after a timeout.
+    #[cfg(feature = "reqwest")]
+    fn serve_once(
+        content_type: &'static str,
+        parts: Vec<&'static str>,
+        release: Option<mpsc::Receiver<()>>,
+    ) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/people", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut head = String::new();
+            let mut reader = io::BufReader::new(stream.try_clone().unwrap());
+            while reader.read_line(&mut head).unwrap() > 2 {}
+            let content_length: usize = parts.iter().map(|p| p.len()).sum();
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\n\r\n{}",
+                content_type, content_length, parts[0]
+            );
+            let _ = stream.flush();
+            if let Some(release) = release {
+                let _ = release.recv_timeout(Duration::from_secs(10));
+            }
+            for part in &parts[1..] {
+                let _ = stream.write_all(part.as_bytes());
+            }
+            head
+        });
+        (url, server)
+    }
+
+    #[cfg(feature = "reqwest")]
+    fn http_fetcher() -> HttpFetcher {
+        HttpFetcher::new(
+            reqwest::blocking::Client::builder()
+                .no_proxy()
+                .build()
+                .unwrap(),
+        )
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[test]
+    pub fn fetches_negotiated_document_over_http() {
+        Lazy::force(&TRACING);
+        let (url, server) = serve_once("text/turtle; charset=utf-8", vec![DOC], None);
+        let graph: FastGraph = load_graph_from_url(&url, &http_fetcher()).unwrap();
+
+        let head = server.join().unwrap();
+        let accept = head.lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.eq_ignore_ascii_case("accept").then(|| value.trim())
+        });
+        assert_eq!(accept, Some(accept_header_for_parsers()));
+        let expected: FastGraph = DynSynTripleParserFactory::default()
+            .try_new_parser(syntax::TURTLE, Some(url), None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(DOC)
+            .collect_triples()
+            .unwrap();
+        assert!(isomorphic_graphs(&expected, &graph).unwrap());
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[test]
+    pub fn parses_http_body_as_it_is_received() {
+        Lazy::force(&TRACING);
+        // Parser looks ahead past end of malformed line, so it is followed by another line.
+        let parts = vec![
+            concat!(
+                "<http://e/a> <http://e/p> <http://e/b> .\n",
+                "<http://e/a> <http://e/p> .\n",
+                "<http://e/a> <http://e/p> <http://e/c> .\n",
+            ),
+            "<http://e/a> <http://e/p> <http://e/d> .\n",
+        ];
+        let (release, held) = mpsc::channel();
+        let (url, server) = serve_once("application/n-triples", parts, Some(held));
+
+        // Malformed statement in first part is reported, while rest of the body is held back.
+        let start = Instant::now();
+        let e = load_graph_from_url::<FastGraph, _>(&url, &http_fetcher())
+            .err()
+            .unwrap();
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(e.code(), error_code::DYNSYN_PARSE_SYNTAX);
+        release.send(()).unwrap();
+        server.join().unwrap();
+    }
+}
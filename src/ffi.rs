@@ -0,0 +1,339 @@
+//! This module provides a C abi to convert rdf documents from their syntax into another syntax, so that non-rust applications, like C services, or python through ctypes, can use dynsyn parsers, and serializers. A C header for it is at `include/rdf_dynsyn.h`, which is generated with [cbindgen](https://github.com/eqrion/cbindgen) from `cbindgen.toml`.
+//!
+//! Syntaxes are given as nul terminated strings, which can be their media types, like `text/turtle`, their short names, like `turtle`, or their spec iris. Converted document is streamed into a callback in chunks, as it is serialized. Functions return a [`DynsynStatus`], and details of last error on a thread can be retrieved with [`dynsyn_last_error_message`], and [`dynsyn_last_error_code`]. Panics are caught at the abi boundary, and reported as [`DynsynStatus::Panicked`], instead of unwinding into caller.
+//!
+//! Example in C:
+//!
+//! ```c
+//! #include <stdio.h>
+//! #include <string.h>
+//! #include "rdf_dynsyn.h"
+//!
+//! static void write_out(const uint8_t *data, size_t len, void *user_data) {
+//!     fwrite(data, 1, len, (FILE *)user_data);
+//! }
+//!
+//! int main(void) {
+//!     const char *doc = "@prefix : <http://example.org/> . :alice :knows :bob .";
+//!     DynsynStatus status = dynsyn_convert(
+//!         (const uint8_t *)doc, strlen(doc), "text/turtle", "application/n-quads", write_out, stdout);
+//!     if (status != DYNSYN_STATUS_OK) {
+//!         fprintf(stderr, "%s: %s\n", dynsyn_last_error_code(), dynsyn_last_error_message());
+//!     }
+//!     return status;
+//! }
+//! ```
+
+use std::{
+    cell::RefCell,
+    ffi::{CStr, CString},
+    io::{self, Write},
+    os::raw::{c_char, c_void},
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+
+use crate::{
+    convert::{convert, ConvertOptions},
+    error_code::{self, ErrorCode},
+    syntax::RdfSyntax,
+};
+
+/// Status of a dynsyn ffi call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DynsynStatus {
+    /// Call succeeded.
+    Ok = 0,
+    /// An argument was a null pointer, or not a valid utf-8 string.
+    InvalidArgument = 1,
+    /// A syntax string doesn't identify any known syntax.
+    UnknownSyntax = 2,
+    /// Conversion failed. Output may have been partially written.
+    ConversionFailed = 3,
+    /// Conversion panicked. Output may have been partially written.
+    Panicked = 4,
+}
+
+/// A callback, that receives chunks of converted document. `data` is valid only for duration of the call.
+pub type DynsynWriteCallback = extern "C" fn(data: *const u8, len: usize, user_data: *mut c_void);
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<(CString, CString)>> = const { RefCell::new(None) };
+}
+
+fn clear_last_error() {
+    LAST_ERROR.with(|e| *e.borrow_mut() = None);
+}
+
+fn set_last_error(code: &str, message: String) {
+    let nul_free = |s: String| CString::new(s.replace('\0', "")).expect("nul bytes are removed");
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some((nul_free(code.into()), nul_free(message))));
+}
+
+fn fail(status: DynsynStatus, code: &str, message: String) -> DynsynStatus {
+    set_last_error(code, message);
+    status
+}
+
+/// A writer, that hands written bytes to a callback.
+struct CallbackWriter {
+    out_cb: DynsynWriteCallback,
+    user_data: *mut c_void,
+}
+
+impl Write for CallbackWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !buf.is_empty() {
+            (self.out_cb)(buf.as_ptr(), buf.len(), self.user_data);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Parse a syntax from given nul terminated string.
+///
+/// # Safety
+/// `s` must be null, or a valid nul terminated string.
+unsafe fn syntax_arg(s: *const c_char) -> Result<RdfSyntax, DynsynStatus> {
+    if s.is_null() {
+        return Err(fail(
+            DynsynStatus::InvalidArgument,
+            error_code::DYNSYN_FFI_INVALID_ARGUMENT,
+            "syntax argument is null".into(),
+        ));
+    }
+    let s = CStr::from_ptr(s).to_str().map_err(|_| {
+        fail(
+            DynsynStatus::InvalidArgument,
+            error_code::DYNSYN_FFI_INVALID_ARGUMENT,
+            "syntax argument is not valid utf-8".into(),
+        )
+    })?;
+    s.parse::<RdfSyntax>()
+        .map_err(|e| fail(DynsynStatus::UnknownSyntax, e.code(), e.to_string()))
+}
+
+/// Converts document in `buf` of `len` bytes from syntax `src_mt` into syntax `dst_mt`, handing converted document to `out_cb` in chunks, along with `user_data`.
+///
+/// # Safety
+/// `buf` must point to `len` readable bytes, unless `len` is 0. `src_mt`, and `dst_mt` must be valid nul terminated strings. `out_cb` must be safe to call with `user_data`.
+#[no_mangle]
+pub unsafe extern "C" fn dynsyn_convert(
+    buf: *const u8,
+    len: usize,
+    src_mt: *const c_char,
+    dst_mt: *const c_char,
+    out_cb: DynsynWriteCallback,
+    user_data: *mut c_void,
+) -> DynsynStatus {
+    catching_panics(|| convert_unguarded(buf, len, src_mt, dst_mt, out_cb, user_data))
+}
+
+/// Clears last error of calling thread, and calls `f`, catching it's panic, if any, as unwinding across the C abi is undefined behaviour.
+fn catching_panics<F: FnOnce() -> DynsynStatus>(f: F) -> DynsynStatus {
+    clear_last_error();
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = match (
+            payload.downcast_ref::<&str>(),
+            payload.downcast_ref::<String>(),
+        ) {
+            (Some(message), _) => (*message).to_owned(),
+            (_, Some(message)) => message.clone(),
+            _ => "conversion panicked".into(),
+        };
+        fail(
+            DynsynStatus::Panicked,
+            error_code::DYNSYN_FFI_PANICKED,
+            message,
+        )
+    })
+}
+
+/// Body of [`dynsyn_convert`], that may panic.
+///
+/// # Safety
+/// Same as of [`dynsyn_convert`].
+unsafe fn convert_unguarded(
+    buf: *const u8,
+    len: usize,
+    src_mt: *const c_char,
+    dst_mt: *const c_char,
+    out_cb: DynsynWriteCallback,
+    user_data: *mut c_void,
+) -> DynsynStatus {
+    if buf.is_null() && len > 0 {
+        return fail(
+            DynsynStatus::InvalidArgument,
+            error_code::DYNSYN_FFI_INVALID_ARGUMENT,
+            "document buffer is null".into(),
+        );
+    }
+    let doc = match len {
+        0 => &[][..],
+        _ => slice::from_raw_parts(buf, len),
+    };
+    let (syntax_in, syntax_out) = match (syntax_arg(src_mt), syntax_arg(dst_mt)) {
+        (Ok(syntax_in), Ok(syntax_out)) => (syntax_in, syntax_out),
+        (Err(status), _) | (_, Err(status)) => return status,
+    };
+    match convert(
+        doc,
+        syntax_in,
+        CallbackWriter { out_cb, user_data },
+        syntax_out,
+        &ConvertOptions::default(),
+    ) {
+        Ok(_) => DynsynStatus::Ok,
+        Err(e) => fail(DynsynStatus::ConversionFailed, e.code(), e.to_string()),
+    }
+}
+
+/// Returns message of last error on calling thread, or null if last conversion on it succeeded, or none is made. Returned string is valid until next dynsyn call on the thread.
+#[no_mangle]
+pub extern "C" fn dynsyn_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|e| match &*e.borrow() {
+        Some((_, message)) => message.as_ptr(),
+        None => ptr::null(),
+    })
+}
+
+/// Returns [error code](crate::error_code) of last error on calling thread, or null if last conversion on it succeeded, or none is made. Returned string is valid until next dynsyn call on the thread.
+#[no_mangle]
+pub extern "C" fn dynsyn_last_error_code() -> *const c_char {
+    LAST_ERROR.with(|e| match &*e.borrow() {
+        Some((code, _)) => code.as_ptr(),
+        None => ptr::null(),
+    })
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        ffi::{CStr, CString},
+        os::raw::c_void,
+        ptr, slice,
+    };
+
+    use once_cell::sync::Lazy;
+    use test_case::test_case;
+
+    use super::{
+        catching_panics, dynsyn_convert, dynsyn_last_error_code, dynsyn_last_error_message,
+        DynsynStatus,
+    };
+    use crate::{error_code, tests::TRACING};
+
+    extern "C" fn collect(data: *const u8, len: usize, user_data: *mut c_void) {
+        let out = unsafe { &mut *(user_data as *mut Vec<u8>) };
+        out.extend_from_slice(unsafe { slice::from_raw_parts(data, len) });
+    }
+
+    fn convert(doc: &str, src_mt: &str, dst_mt: &str) -> (DynsynStatus, String) {
+        let (src_mt, dst_mt) = (CString::new(src_mt).unwrap(), CString::new(dst_mt).unwrap());
+        let mut out = Vec::<u8>::new();
+        let status = unsafe {
+            dynsyn_convert(
+                doc.as_ptr(),
+                doc.len(),
+                src_mt.as_ptr(),
+                dst_mt.as_ptr(),
+                collect,
+                &mut out as *mut Vec<u8> as *mut c_void,
+            )
+        };
+        (status, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    pub fn converts_documents_into_callback() {
+        Lazy::force(&TRACING);
+        let (status, out) = convert(
+            "@prefix : <http://example.org/> . :alice :knows :bob .",
+            "text/turtle",
+            "application/n-quads",
+        );
+        assert_eq!(status, DynsynStatus::Ok);
+        assert!(dynsyn_last_error_code().is_null());
+        assert_eq!(
+            out,
+            "<http://example.org/alice> <http://example.org/knows> <http://example.org/bob>.\n"
+        );
+    }
+
+    #[test_case(
+        "text/turtle",
+        "application/ld+json",
+        DynsynStatus::ConversionFailed,
+        error_code::DYNSYN_UNKNOWN_SYNTAX
+    )]
+    #[test_case(
+        "text/plain",
+        "n-quads",
+        DynsynStatus::UnknownSyntax,
+        error_code::DYNSYN_UNKNOWN_SYNTAX
+    )]
+    #[test_case(
+        "text/turtle",
+        "n-quads",
+        DynsynStatus::ConversionFailed,
+        error_code::DYNSYN_PARSE_SYNTAX
+    )]
+    pub fn failed_conversions_record_last_error(
+        src_mt: &str,
+        dst_mt: &str,
+        expected_status: DynsynStatus,
+        expected_code: &str,
+    ) {
+        Lazy::force(&TRACING);
+        let (status, _) = convert("<http://ex.org/a> <http://ex.org/b> .", src_mt, dst_mt);
+        assert_eq!(status, expected_status);
+        let code = unsafe { CStr::from_ptr(dynsyn_last_error_code()) };
+        assert_eq!(code.to_str().unwrap(), expected_code);
+        assert!(!dynsyn_last_error_message().is_null());
+
+        let (status, _) = convert(
+            "<http://ex.org/a> <http://ex.org/b> <http://ex.org/c> .",
+            "n-triples",
+            "n-quads",
+        );
+        assert_eq!(status, DynsynStatus::Ok);
+        assert!(dynsyn_last_error_code().is_null());
+        assert!(dynsyn_last_error_message().is_null());
+    }
+
+    #[test]
+    pub fn null_arguments_are_rejected() {
+        Lazy::force(&TRACING);
+        let status = unsafe {
+            dynsyn_convert(
+                ptr::null(),
+                1,
+                ptr::null(),
+                ptr::null(),
+                collect,
+                ptr::null_mut(),
+            )
+        };
+        assert_eq!(status, DynsynStatus::InvalidArgument);
+    }
+
+    #[test]
+    pub fn panics_are_caught_and_recorded() {
+        Lazy::force(&TRACING);
+        let status = catching_panics(|| panic!("conversion blew up"));
+        assert_eq!(status, DynsynStatus::Panicked);
+        let code = unsafe { CStr::from_ptr(dynsyn_last_error_code()) };
+        assert_eq!(code.to_str().unwrap(), error_code::DYNSYN_FFI_PANICKED);
+        let message = unsafe { CStr::from_ptr(dynsyn_last_error_message()) };
+        assert_eq!(message.to_str().unwrap(), "conversion blew up");
+    }
+}
@@ -18,6 +18,20 @@ impl Display for FileExtension {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for FileExtension {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FileExtension {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::from(String::deserialize(deserializer)?))
+    }
+}
+
 impl<T: Into<Cow<'static, str>>> From<T> for FileExtension {
     fn from(v: T) -> Self {
         Self(v.into())
@@ -25,12 +39,26 @@ impl<T: Into<Cow<'static, str>>> From<T> for FileExtension {
 }
 
 impl FileExtension {
+    /// Get file extension of given `path`. Compression suffix, if any, is stripped, so that for `data.ttl.gz`, extension is `ttl`. Use [`compression_from_path`](Self::compression_from_path) to get compression suffix.
     pub fn from_path(path: &Path) -> Option<Self> {
+        let path = match Self::compression_from_path(path) {
+            Some(_) => Path::new(path.file_stem()?),
+            None => path,
+        };
         Some(Self::from(
             path.extension().and_then(OsStr::to_str)?.to_string(),
         ))
     }
 
+    /// Get compression suffix of given `path`, if it's file extension is one of [`COMPRESSION_EXTENSIONS`]. Returned extension is as in path, and is not case-normalized.
+    pub fn compression_from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension().and_then(OsStr::to_str)?;
+        COMPRESSION_EXTENSIONS
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(extension))
+            .then(|| Self::from(extension.to_string()))
+    }
+
     pub fn from_path_str(path_str: &str) -> Option<Self> {
         Self::from_path(Path::new(path_str))
     }
@@ -40,6 +68,14 @@ impl FileExtension {
     }
 }
 
+pub const BR: FileExtension = FileExtension::from_static("br");
+
+pub const BZ2: FileExtension = FileExtension::from_static("bz2");
+
+pub const GZ: FileExtension = FileExtension::from_static("gz");
+
+pub const HDT: FileExtension = FileExtension::from_static("hdt");
+
 pub const HTML: FileExtension = FileExtension::from_static("html");
 
 pub const JSON: FileExtension = FileExtension::from_static("json");
@@ -48,6 +84,8 @@ pub const JSONLD: FileExtension = FileExtension::from_static("jsonld");
 
 pub const N3: FileExtension = FileExtension::from_static("n3");
 
+pub const NDJSON: FileExtension = FileExtension::from_static("ndjson");
+
 pub const NQ: FileExtension = FileExtension::from_static("nq");
 
 pub const NQUADS: FileExtension = FileExtension::from_static("nquads");
@@ -68,8 +106,17 @@ pub const RDFXML: FileExtension = FileExtension::from_static("rdfxml");
 
 pub const TRIG: FileExtension = FileExtension::from_static("trig");
 
+pub const TRIX: FileExtension = FileExtension::from_static("trix");
+
 pub const TTL: FileExtension = FileExtension::from_static("ttl");
 
 pub const TURTLE: FileExtension = FileExtension::from_static("turtle");
 
 pub const XHTML: FileExtension = FileExtension::from_static("xhtml");
+
+pub const XZ: FileExtension = FileExtension::from_static("xz");
+
+pub const ZST: FileExtension = FileExtension::from_static("zst");
+
+/// File extensions of compression formats, that wrap documents in compound extensions, like `data.ttl.gz`.
+pub const COMPRESSION_EXTENSIONS: [FileExtension; 5] = [BR, BZ2, GZ, XZ, ZST];
@@ -0,0 +1,235 @@
+//! This module exports small rdf documents in each of supported syntaxes, that can be used as fixtures in tests. It is available only when `fixtures` feature is enabled.
+//!
+//! Valid documents are copied from sophia tests. Each `INVALID_*` fixture holds documents, that any conforming parser for corresponding syntax must reject.
+
+pub static DATASET_STR_NQUADS: &str = r#"
+    <http://localhost/ex#me> <http://example.org/ns/knows> _:b1.
+    _:b1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.org/ns/Person> <tag:g1>.
+    _:b1 <http://example.org/ns/name> "Alice" <tag:g1>.
+"#;
+
+pub static DATASET_STR_TRIG: &str = r#"
+    @prefix : <http://example.org/ns/> .
+    <#g1> {
+        <#me> :knows _:alice.
+    }
+    <#g2> {
+        _:alice a :Person ; :name "Alice".
+    }
+"#;
+
+pub static GRAPH_STR_TURTLE: &str = r#"
+    @prefix : <http://example.org/ns/> .
+    <#me> :knows [ a :Person ; :name "Alice" ].
+"#;
+
+pub static GRAPH_STR_NTRIPLES: &str = r#"
+    <http://localhost/ex#me> <http://example.org/ns/knows> _:b1.
+    _:b1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.org/ns/Person>.
+    _:b1 <http://example.org/ns/name> "Alice".
+"#;
+
+pub static GRAPH_STR_RDF_XML: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+            xmlns="http://example.org/ns/">
+    <rdf:Description rdf:about="http://localhost/ex#me">
+    <knows>
+        <Person>
+        <name>Alice</name>
+        </Person>
+    </knows>
+    </rdf:Description>
+</rdf:RDF>
+"#;
+
+/// Base iri against which relative iris in above documents are resolved.
+pub static BASE_IRI1: &str = "http://localhost/ex";
+pub static G1_IRI: &str = "http://localhost/ex#g1";
+pub static G2_IRI: &str = "http://localhost/ex#g2";
+
+// ---------------------------------------------------------------------------------
+//                              round-trip documents
+// ---------------------------------------------------------------------------------
+
+pub static TESTS_NQUADS: &[&str] = &[
+    r#"<http://champin.net/#pa> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://schema.org/Person>.
+<http://champin.net/#pa> <http://schema.org/name> "Pierre-Antoine" <http://champin.net/>.
+"#,
+];
+
+pub static TESTS_NTRIPLES: &[&str] = &[
+    r#"<http://champin.net/#pa> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://schema.org/Person>.
+<http://champin.net/#pa> <http://schema.org/name> "Pierre-Antoine".
+"#,
+];
+
+pub static TESTS_RDF_XML: &[&str] = &[r#"<?xml version="1.0" encoding="utf-8"?>
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+            xmlns="http://example.org/ns/">
+    <rdf:Description rdf:about="http://localhost/ex#me">
+    <knows>
+        <Person>
+        <name>Alice</name>
+        </Person>
+    </knows>
+    </rdf:Description>
+</rdf:RDF>
+"#];
+
+pub static TESTS_TRIG: &[&str] = &[
+    "#empty trig",
+    r#"# simple quads
+        PREFIX : <http://example.org/ns/>
+        :alice a :Person; :name "Alice"; :age 42.
+        GRAPH :g {
+            :bob a :Person, :Man; :nick "bob"@fr, "bobby"@en; :admin true.
+        }
+    "#,
+    r#"# lists
+        GRAPH <tag:g> { <tag:alice> <tag:likes> ( 1 2 ( 3 4 ) 5 6 ), ("a" "b"). }
+    "#,
+    r#"# subject lists
+        GRAPH <tag:g> { (1 2 3) a <tag:List>. }
+    "#,
+    r#"# blank node graph name
+        PREFIX : <http://example.org/ns/>
+        #:lois :belives _:b.
+        #GRAPH _:b1 { :clark a :Human }
+    "#,
+    r#"# list split over different graphs
+        PREFIX rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#>
+        _:a rdf:first 42; rdf:rest _:b.
+        GRAPH [] {
+            _:b rdf:first 43; rdf:rest ().
+        }
+    "#,
+];
+
+pub static TESTS_TURTLE: &[&str] = &[
+    "#empty ttl",
+    r#"# simple triple
+        PREFIX : <http://example.org/ns/>
+        :alice a :Person; :name "Alice"; :age 42.
+        :bob a :Person, :Man; :nick "bob"@fr, "bobby"@en; :admin true.
+    "#,
+    r#"# lists
+        <tag:alice> <tag:likes> ( 1 2 ( 3 4 ) 5 6 ), ("a" "b").
+    "#,
+    r#"# subject lists
+        (1 2 3) a <tag:List>.
+    "#,
+    r#"# malformed list
+        PREFIX rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#>
+        _:a rdf:first 42, 43; rdf:rest (44 45).
+        _:b rdf:first 42; rdf:rest (43), (44).
+    "#,
+    r#"# bnode cycles
+    PREFIX : <http://example.org/ns/>
+    _:a :n "a"; :p [ :q [ :r _:a ]].
+    _:b :n "b"; :s [ :s _:b ].
+    "#,
+];
+
+// ---------------------------------------------------------------------------------
+//                                empty documents
+// ---------------------------------------------------------------------------------
+
+/// Documents, that must be parsed into an empty graph/dataset in every supported syntax.
+pub static EMPTY_DOCS: &[&str] = &["", "  \n\t \r\n", "\u{feff}", "\u{feff}\n  \n"];
+
+// ---------------------------------------------------------------------------------
+//                               invalid documents
+// ---------------------------------------------------------------------------------
+
+pub static INVALID_NQUADS: &[&str] = &[
+    // missing object
+    "<http://localhost/ex#me> <http://example.org/ns/knows> .\n",
+    // literal as graph name
+    "<http://localhost/ex#me> <http://example.org/ns/knows> <tag:b> \"g\" .\n",
+    // unterminated iri
+    "<http://localhost/ex#me <http://example.org/ns/knows> <tag:b> .\n",
+];
+
+pub static INVALID_NTRIPLES: &[&str] = &[
+    // missing object
+    "<http://localhost/ex#me> <http://example.org/ns/knows> .\n",
+    // prefixed names are not allowed
+    "<http://localhost/ex#me> ex:knows <tag:b> .\n",
+    // missing terminating dot
+    "<http://localhost/ex#me> <http://example.org/ns/knows> <tag:b>\n",
+];
+
+pub static INVALID_TURTLE: &[&str] = &[
+    // undeclared prefix
+    "ex:alice ex:knows ex:bob .\n",
+    // unterminated blank node property list
+    "@prefix : <http://example.org/ns/> .\n:alice :knows [ :name \"Bob\" .\n",
+    // literal as subject
+    "\"alice\" <http://example.org/ns/knows> <tag:b> .\n",
+];
+
+pub static INVALID_TRIG: &[&str] = &[
+    // unterminated graph block
+    "@prefix : <http://example.org/ns/> .\n:g { :alice :knows :bob .\n",
+    // undeclared prefix
+    "ex:g { ex:alice ex:knows ex:bob . }\n",
+    // literal as graph name
+    "\"g\" { <tag:a> <tag:b> <tag:c> . }\n",
+];
+
+pub static INVALID_RDF_XML: &[&str] = &[
+    // unclosed element
+    r#"<?xml version="1.0" encoding="utf-8"?>
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+            xmlns="http://example.org/ns/">
+    <rdf:Description rdf:about="http://localhost/ex#me">
+    <knows rdf:resource="http://localhost/ex#you">
+</rdf:RDF>
+"#,
+    // mismatched end tag
+    r#"<?xml version="1.0" encoding="utf-8"?>
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+            xmlns="http://example.org/ns/">
+    <rdf:Description rdf:about="http://localhost/ex#me">
+        <knows rdf:resource="http://localhost/ex#you"/>
+    </knows>
+</rdf:RDF>
+"#,
+];
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use sophia_api::{parser::QuadParser, quad::stream::QuadSource};
+    use sophia_inmem::dataset::FastDataset;
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+
+    use crate::{
+        parser::quads::DynSynQuadParserFactory,
+        syntax::{self, RdfSyntax},
+        tests::TRACING,
+    };
+
+    use super::*;
+
+    #[test_case(syntax::N_QUADS, INVALID_NQUADS)]
+    #[test_case(syntax::N_TRIPLES, INVALID_NTRIPLES)]
+    #[test_case(syntax::TURTLE, INVALID_TURTLE)]
+    #[test_case(syntax::TRIG, INVALID_TRIG)]
+    #[cfg_attr(feature = "rdf-xml", test_case(syntax::RDF_XML, INVALID_RDF_XML))]
+    pub fn invalid_docs_will_be_rejected(syntax_: RdfSyntax, docs: &[&str]) {
+        Lazy::force(&TRACING);
+        let parser = DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax_, Some(BASE_IRI1.into()), None)
+            .unwrap();
+        for doc in docs {
+            let result: Result<FastDataset, _> = parser.parse_str(doc).collect_quads();
+            assert!(result.is_err());
+        }
+    }
+}
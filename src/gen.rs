@@ -0,0 +1,294 @@
+//! This module provides a deterministic pseudo-random dataset generator. It produces synthetic datasets of configurable size and shape as quad-sources, which can be used for benchmarking parsers/serializers, and for load-testing downstream stores.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{gen::*, serializer::quads::DynSynQuadSerializerFactory, syntax};
+//! use sophia_api::serializer::{QuadSerializer, Stringifier};
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = DatasetGenConfig::new(DatasetShape::ManyGraphs, 1000)
+//!     .with_seed(42)
+//!     .with_graph_count(10);
+//!
+//! let mut serializer = DynSynQuadSerializerFactory::new(None).try_new_stringifier(syntax::N_QUADS)?;
+//! let doc = serializer.serialize_quads(DatasetGenerator::new(config))?.as_str();
+//! assert_eq!(doc.lines().count(), 1000);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::convert::Infallible;
+
+use sophia_api::ns::xsd;
+use sophia_term::BoxTerm;
+
+use crate::parser::quads::source::TupleQuad;
+
+/// Namespace, in which generated iris are minted.
+pub static GEN_IRI_NS: &str = "http://example.org/gen/";
+
+/// Shape of a generated dataset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DatasetShape {
+    /// All statements share a single subject, with varying predicates and objects.
+    Star,
+    /// Each statement links a node to the next node in a single long chain.
+    Chain,
+    /// Subjects and objects are blank-nodes, randomly linked with each other.
+    BNodeHeavy,
+    /// Objects are plain, language-tagged, and typed literals of varying length.
+    LiteralHeavy,
+    /// Statements are spread over configured number of named graphs.
+    ManyGraphs,
+}
+
+/// Configuration for [`DatasetGenerator`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatasetGenConfig {
+    /// shape of the dataset
+    pub shape: DatasetShape,
+    /// number of quads to generate
+    pub size: usize,
+    /// seed of the pseudo-random generator. Same seed produces same dataset.
+    pub seed: u64,
+    /// number of named graphs, used only by [`DatasetShape::ManyGraphs`] shape
+    pub graph_count: usize,
+}
+
+impl Default for DatasetGenConfig {
+    fn default() -> Self {
+        Self::new(DatasetShape::Star, 100)
+    }
+}
+
+impl DatasetGenConfig {
+    pub fn new(shape: DatasetShape, size: usize) -> Self {
+        Self {
+            shape,
+            size,
+            seed: 0,
+            graph_count: 8,
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn with_graph_count(mut self, graph_count: usize) -> Self {
+        self.graph_count = graph_count.max(1);
+        self
+    }
+}
+
+/// A splitmix64 pseudo-random number generator. It is tiny, and stable across platforms and releases, which is all we need for reproducible datasets.
+#[derive(Debug, Clone)]
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound.max(1) as u64) as usize
+    }
+}
+
+static WORDS: &[&str] = &[
+    "alpha", "bravo", "charlie", "delta", "echo", "foxtrot", "golf", "hotel", "india", "juliet",
+    "kilo", "lima", "mike", "november", "oscar", "papa",
+];
+
+static LANGS: &[&str] = &["en", "fr", "de", "te", "hi"];
+
+/// A deterministic pseudo-random dataset generator. It is an iterator of infallible quads, and hence a [`QuadSource`](sophia_api::quad::stream::QuadSource), that can be fed to any serializer or store.
+#[derive(Debug, Clone)]
+pub struct DatasetGenerator {
+    config: DatasetGenConfig,
+    rng: SplitMix64,
+    emitted: usize,
+}
+
+impl DatasetGenerator {
+    pub fn new(config: DatasetGenConfig) -> Self {
+        Self {
+            rng: SplitMix64(config.seed),
+            config,
+            emitted: 0,
+        }
+    }
+
+    /// Returns the config, this generator is instantiated with.
+    pub fn config(&self) -> &DatasetGenConfig {
+        &self.config
+    }
+
+    fn iri(&self, prefix: &str, i: usize) -> BoxTerm {
+        BoxTerm::new_iri(format!("{}{}{}", GEN_IRI_NS, prefix, i)).unwrap()
+    }
+
+    fn bnode(&self, i: usize) -> BoxTerm {
+        BoxTerm::new_bnode(format!("b{}", i)).unwrap()
+    }
+
+    fn literal(&mut self) -> BoxTerm {
+        let word_count = 1 + self.rng.below(12);
+        let text = (0..word_count)
+            .map(|_| WORDS[self.rng.below(WORDS.len())])
+            .collect::<Vec<_>>()
+            .join(" ");
+        match self.rng.below(4) {
+            0 => BoxTerm::new_literal_dt(text, xsd::string).unwrap(),
+            1 => {
+                let lang = LANGS[self.rng.below(LANGS.len())];
+                BoxTerm::new_literal_lang(text, lang).unwrap()
+            }
+            2 => BoxTerm::new_literal_dt(self.rng.next_u64().to_string(), xsd::integer).unwrap(),
+            _ => {
+                BoxTerm::new_literal_dt((self.rng.below(2) == 0).to_string(), xsd::boolean).unwrap()
+            }
+        }
+    }
+
+    fn next_quad(&mut self, i: usize) -> TupleQuad<BoxTerm> {
+        let size = self.config.size;
+        match self.config.shape {
+            DatasetShape::Star => (
+                [
+                    self.iri("center", 0),
+                    self.iri("p", i % 16),
+                    self.iri("o", i),
+                ],
+                None,
+            ),
+            DatasetShape::Chain => (
+                [self.iri("n", i), self.iri("next", 0), self.iri("n", i + 1)],
+                None,
+            ),
+            DatasetShape::BNodeHeavy => {
+                let o = self.rng.below(size);
+                ([self.bnode(i), self.iri("p", i % 8), self.bnode(o)], None)
+            }
+            DatasetShape::LiteralHeavy => (
+                [self.iri("s", i / 8), self.iri("p", i % 8), self.literal()],
+                None,
+            ),
+            DatasetShape::ManyGraphs => {
+                let s = self.rng.below(size);
+                let o = self.rng.below(size);
+                let g = self.rng.below(self.config.graph_count);
+                (
+                    [self.iri("s", s), self.iri("p", i % 8), self.iri("s", o)],
+                    Some(self.iri("g", g)),
+                )
+            }
+        }
+    }
+}
+
+impl Iterator for DatasetGenerator {
+    type Item = Result<TupleQuad<BoxTerm>, Infallible>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.emitted >= self.config.size {
+            return None;
+        }
+        let q = self.next_quad(self.emitted);
+        self.emitted += 1;
+        Some(Ok(q))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.config.size - self.emitted;
+        (remaining, Some(remaining))
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use once_cell::sync::Lazy;
+    use sophia_api::{quad::Quad, term::TTerm, term::TermKind};
+    use test_case::test_case;
+
+    use crate::{proptest_support::roundtrips, syntax, tests::TRACING};
+
+    use super::*;
+
+    fn generate(config: DatasetGenConfig) -> Vec<TupleQuad<BoxTerm>> {
+        DatasetGenerator::new(config).map(Result::unwrap).collect()
+    }
+
+    #[test_case(DatasetShape::Star)]
+    #[test_case(DatasetShape::Chain)]
+    #[test_case(DatasetShape::BNodeHeavy)]
+    #[test_case(DatasetShape::LiteralHeavy)]
+    #[test_case(DatasetShape::ManyGraphs)]
+    pub fn generates_configured_number_of_quads(shape: DatasetShape) {
+        Lazy::force(&TRACING);
+        assert_eq!(generate(DatasetGenConfig::new(shape, 0)).len(), 0);
+        assert_eq!(generate(DatasetGenConfig::new(shape, 257)).len(), 257);
+    }
+
+    #[test_case(DatasetShape::BNodeHeavy)]
+    #[test_case(DatasetShape::LiteralHeavy)]
+    #[test_case(DatasetShape::ManyGraphs)]
+    pub fn same_seed_generates_same_dataset(shape: DatasetShape) {
+        Lazy::force(&TRACING);
+        let d1 = generate(DatasetGenConfig::new(shape, 100).with_seed(7));
+        let d2 = generate(DatasetGenConfig::new(shape, 100).with_seed(7));
+        let d3 = generate(DatasetGenConfig::new(shape, 100).with_seed(8));
+        assert_eq!(d1, d2);
+        assert_ne!(d1, d3);
+    }
+
+    #[test]
+    pub fn many_graphs_shape_spreads_quads_over_configured_graphs() {
+        Lazy::force(&TRACING);
+        let d = generate(DatasetGenConfig::new(DatasetShape::ManyGraphs, 500).with_graph_count(5));
+        let graphs: HashSet<_> = d.iter().map(|q| q.g().cloned()).collect();
+        assert_eq!(graphs.len(), 5);
+        assert!(graphs.iter().all(Option::is_some));
+    }
+
+    #[test]
+    pub fn bnode_heavy_shape_links_bnodes() {
+        Lazy::force(&TRACING);
+        let d = generate(DatasetGenConfig::new(DatasetShape::BNodeHeavy, 50));
+        assert!(d
+            .iter()
+            .all(|q| q.s().kind() == TermKind::BlankNode && q.o().kind() == TermKind::BlankNode));
+    }
+
+    #[test_case(DatasetShape::Star)]
+    #[test_case(DatasetShape::Chain)]
+    #[test_case(DatasetShape::BNodeHeavy)]
+    #[test_case(DatasetShape::LiteralHeavy)]
+    #[test_case(DatasetShape::ManyGraphs)]
+    pub fn generated_datasets_roundtrip(shape: DatasetShape) {
+        Lazy::force(&TRACING);
+        let d = generate(DatasetGenConfig::new(shape, 40).with_seed(3));
+        for syntax_ in [
+            syntax::N_QUADS,
+            syntax::TRIG,
+            syntax::TURTLE,
+            #[cfg(feature = "rdf-xml")]
+            syntax::RDF_XML,
+        ] {
+            roundtrips(syntax_, &d).unwrap();
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! This module defines [`IriResolver`] trait, an integration hook to plug custom iri resolution strategies, like resolving against a virtual base, or rewriting iris to intranet mirrors. Resolvers are configured by inserting a [`DynIriResolver`] into `parser_config_map` of parser factories, and are then used consistently by all parsers from that factory.
+//!
+//! Example:
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use rdf_dynsyn::{iri_resolver::*, parser::triples::DynSynTripleParserFactory, syntax};
+//! use sophia_api::{graph::Graph, parser::TripleParser, term::TTerm, triple::{stream::TripleSource, Triple}};
+//! use sophia_inmem::graph::FastGraph;
+//! use sophia_term::BoxTerm;
+//! use type_map::concurrent::TypeMap;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let resolver = RewriteMap::new()
+//!     .with_rule("http://www.w3.org/ns/", "http://mirror.intranet/w3c/")
+//!     .with_virtual_base("http://virtual.example/doc");
+//! let mut config_map = TypeMap::new();
+//! config_map.insert(DynIriResolver(Arc::new(resolver)));
+//!
+//! let parser = DynSynTripleParserFactory::new(Some(config_map))
+//!     .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)?;
+//! let graph: FastGraph = parser
+//!     .parse_str("<#me> a <http://www.w3.org/ns/prov#Agent> .")
+//!     .collect_triples()?;
+//!
+//! let t = graph.triples().next().unwrap()?;
+//! assert_eq!(t.s().value(), "http://virtual.example/doc#me");
+//! assert_eq!(t.o().value(), "http://mirror.intranet/w3c/prov#Agent");
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::{fmt::Debug, sync::Arc};
+
+use sophia_api::term::{term_eq, CopyTerm, TTerm, TermKind};
+use sophia_term::BoxTerm;
+
+/// A strategy for resolving iris in parsed documents.
+pub trait IriResolver: Debug + Send + Sync {
+    /// Returns base iri, against which relative iris of a document are resolved. `base_iri` is the base iri, that parser is configured, or called with.
+    ///
+    /// Default implementation returns `base_iri` as it is.
+    fn resolve_base(&self, base_iri: Option<&str>) -> Option<String> {
+        base_iri.map(String::from)
+    }
+
+    /// Rewrites an absolute iri, after it is resolved against base. Returns `None` to keep iri as it is.
+    ///
+    /// Default implementation keeps all iris.
+    fn rewrite(&self, _iri: &str) -> Option<String> {
+        None
+    }
+}
+
+/// A shareable, type-erased [`IriResolver`], that can be inserted into config maps.
+#[derive(Debug, Clone)]
+pub struct DynIriResolver(pub Arc<dyn IriResolver>);
+
+impl DynIriResolver {
+    /// Copies given term into a term of type `T`, rewriting it if it is an iri.
+    pub(crate) fn resolved<T, U>(resolver: Option<&Self>, term: &U) -> T
+    where
+        T: CopyTerm,
+        U: TTerm + ?Sized,
+    {
+        if let (Some(resolver), TermKind::Iri) = (resolver, term.kind()) {
+            if let Some(iri) = resolver.0.rewrite(&term.value()) {
+                if let Ok(t) = BoxTerm::new_iri(iri) {
+                    return T::copy(&t);
+                }
+            }
+        }
+        T::copy(term)
+    }
+
+    /// Returns true, if given term, once rewritten, is same as `target`. It doesn't copy the term, so that statements can be filtered cheaply before they are materialized.
+    pub(crate) fn resolves_to<U, V>(resolver: Option<&Self>, term: &U, target: &V) -> bool
+    where
+        U: TTerm + ?Sized,
+        V: TTerm + ?Sized,
+    {
+        if let (Some(resolver), TermKind::Iri) = (resolver, term.kind()) {
+            if let Some(iri) = resolver.0.rewrite(&term.value()) {
+                return target.kind() == TermKind::Iri && target.value() == iri.as_str();
+            }
+        }
+        term_eq(term, target)
+    }
+}
+
+/// An [`IriResolver`], that rewrites iris by longest matching prefix, and optionally resolves documents against a virtual base, ignoring configured base.
+#[derive(Debug, Clone, Default)]
+pub struct RewriteMap {
+    rules: Vec<(String, String)>,
+    virtual_base: Option<String>,
+}
+
+impl RewriteMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule to rewrite iris starting with `from` prefix, to start with `to` prefix instead.
+    pub fn with_rule(mut self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        self.rules.push((from.into(), to.into()));
+        self
+    }
+
+    /// Sets a virtual base, against which all documents will be resolved.
+    pub fn with_virtual_base(mut self, virtual_base: impl Into<String>) -> Self {
+        self.virtual_base = Some(virtual_base.into());
+        self
+    }
+}
+
+impl IriResolver for RewriteMap {
+    fn resolve_base(&self, base_iri: Option<&str>) -> Option<String> {
+        self.virtual_base
+            .clone()
+            .or_else(|| base_iri.map(String::from))
+    }
+
+    fn rewrite(&self, iri: &str) -> Option<String> {
+        self.rules
+            .iter()
+            .filter(|(from, _)| iri.starts_with(from.as_str()))
+            .max_by_key(|(from, _)| from.len())
+            .map(|(from, to)| format!("{}{}", to, &iri[from.len()..]))
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use test_case::test_case;
+
+    use crate::tests::TRACING;
+
+    use super::*;
+
+    static REWRITE_MAP: Lazy<RewriteMap> = Lazy::new(|| {
+        RewriteMap::new()
+            .with_rule("http://example.org/", "http://mirror.local/")
+            .with_rule("http://example.org/vocab/", "http://vocab.local/")
+    });
+
+    #[test_case("http://example.org/a", Some("http://mirror.local/a"))]
+    #[test_case("http://example.org/vocab/b", Some("http://vocab.local/b"))]
+    #[test_case("http://other.org/c", None)]
+    pub fn rewrite_map_rewrites_by_longest_prefix(iri: &str, expected: Option<&str>) {
+        Lazy::force(&TRACING);
+        assert_eq!(REWRITE_MAP.rewrite(iri).as_deref(), expected);
+    }
+
+    #[test]
+    pub fn virtual_base_overrides_configured_base() {
+        Lazy::force(&TRACING);
+        assert_eq!(
+            REWRITE_MAP.resolve_base(Some("http://a/")),
+            Some("http://a/".into())
+        );
+        let resolver = RewriteMap::new().with_virtual_base("http://v/");
+        assert_eq!(
+            resolver.resolve_base(Some("http://a/")),
+            Some("http://v/".into())
+        );
+        assert_eq!(resolver.resolve_base(None), Some("http://v/".into()));
+    }
+
+    #[test]
+    pub fn only_iri_terms_are_rewritten() {
+        Lazy::force(&TRACING);
+        let resolver = DynIriResolver(Arc::new(REWRITE_MAP.clone()));
+        let iri = BoxTerm::new_iri("http://example.org/a").unwrap();
+        let lit = BoxTerm::new_literal_dt(
+            "http://example.org/a",
+            BoxTerm::new_iri("http://example.org/dt").unwrap(),
+        )
+        .unwrap();
+        let t: BoxTerm = DynIriResolver::resolved(Some(&resolver), &iri);
+        assert_eq!(t.value(), "http://mirror.local/a");
+        let t: BoxTerm = DynIriResolver::resolved(Some(&resolver), &lit);
+        assert_eq!(t, lit);
+        let t: BoxTerm = DynIriResolver::resolved(None, &iri);
+        assert_eq!(t, iri);
+    }
+
+    #[test_case("http://example.org/a", "http://mirror.local/a", true)]
+    #[test_case("http://example.org/a", "http://example.org/a", false)]
+    #[test_case("http://other.org/c", "http://other.org/c", true)]
+    #[test_case("http://other.org/c", "http://mirror.local/c", false)]
+    pub fn compares_terms_as_resolved(iri: &str, target: &str, expected: bool) {
+        Lazy::force(&TRACING);
+        let resolver = DynIriResolver(Arc::new(REWRITE_MAP.clone()));
+        let iri = BoxTerm::new_iri(iri).unwrap();
+        let target = BoxTerm::new_iri(target).unwrap();
+        assert_eq!(
+            DynIriResolver::resolves_to(Some(&resolver), &iri, &target),
+            expected
+        );
+        let resolved: BoxTerm = DynIriResolver::resolved(Some(&resolver), &iri);
+        assert_eq!(resolved == target, expected);
+    }
+}
@@ -65,22 +65,92 @@
 //!     println!("The resulting graph\n{}", serialized_doc);
 //! #   Ok(())
 //! # }
-//! # fn main() {try_main().unwrap();}
+//! # fn main() {
+//! #     #[cfg(feature = "rdf-xml")]
+//! #     try_main().unwrap();
+//! # }
 //! ```
 //!
+//! # Features
+//!
+//! Turtle, trig, n-triples, n-quads, and nd-rdf-json syntaxes are always supported.
+//!
+//! - `core-syntaxes` (default): names the miniature build, that supports only turtle, trig, n-triples, n-quads, and nd-rdf-json syntaxes. Default feature set builds on it. Use it with `default-features = false`, to drop xml dependencies.
+//! - `rdf-xml` (default): enables parsing from, and serializing into [rdf-xml](syntax::RDF_XML) syntax.
+//! - `n3`: enables parsing from [n3](syntax::N3) syntax. Only turtle-compatible subset of n3 is supported for now. Documents using n3-only constructs, like formulae, and rules fail to parse with a syntax error.
+//! - `rdfa`: enables parsing from [html+rdfa](syntax::HTML_RDFA), and [xhtml+rdfa](syntax::XHTML_RDFA) syntaxes, with an rdfa 1.1 extractor in `parser::rdfa` module. It pulls triples out of html pages, like their schema.org annotations.
+//! - `trix` (default): enables parsing from, and serializing into [trix](syntax::TRIX) syntax, with parser, and serializer in `parser::trix`, and `serializer::trix` modules.
+//! - `hdt`: enables parsing from binary [hdt](syntax::HDT) files, with reader in `parser::hdt` module.
+//! - `fixtures`: exports `fixtures` module with small valid/invalid rdf documents in each supported syntax, to be used in downstream tests.
+//! - `archive`: exports `archive` module, to pack datasets into tar/zip archives, with one file per graph, and to ingest rdf files out of tar/zip archives.
+//! - `proptest`: exports `proptest_support` module with [proptest](https://docs.rs/proptest) strategies for random graphs/datasets, and round-trip property helpers.
+//! - `fault_injection`: exports `fault_injection` module with reader, and writer adaptors, that inject faults like short reads, interrupted writes, io errors, and invalid utf-8, to test error handling around dynsyn parsers, and serializers.
+//! - `snapshot`: exports `snapshot` module with golden-file snapshot helpers, that compare serializer output against stored golden files, with normalized blank nodes, to pin rdf output format across upgrades.
+//! - `async`: exports `parser::async_parser` module with parsers, that parse from tokio's async readers into streams of statements.
+//! - `http`: implements `service::HeaderSource` for `http::HeaderMap`, and conversion of `service::ResponsePayload` into `http::Response`, to use `service` module with frameworks built on `http` crate, like axum, and hyper.
+//! - `parallel`: exports `parser::parallel` module, to parse large n-quads, and n-triples documents in parallel chunks with [rayon](https://docs.rs/rayon).
+//! - `reqwest`: exports `fetch::HttpFetcher`, a [reqwest](https://docs.rs/reqwest) based document fetcher, and `fetch::fetch_graph`, and `fetch::fetch_dataset` functions, that dereference an iri with content negotiation, and parse fetched document in syntax of it's content type.
+//! - `serde`: implements `Serialize`, and `Deserialize` for [`RdfSyntax`](syntax::RdfSyntax), [`FileExtension`](file_extension::FileExtension), and [`Correspondent`](correspondence::Correspondent), so that they can be used in configuration files, and api payloads. Syntaxes are serialized as their short names, and can be deserialized from anything that they can be parsed from, like `"text/turtle"`.
+//! - `compression`: exports `compression` module, to parse gzip, bzip2, xz, and zstd compressed documents with transparent decompression, and to compress serializer outputs as configured by `compression::CompressionConfig`.
+//! - `ffi`: exports `ffi` module with a C abi, to convert documents from a syntax into another syntax from non-rust applications. A `cdylib`, or a `staticlib` for linking with them can be built with `cargo rustc --lib --features ffi --crate-type cdylib`, or `--crate-type staticlib`. C header is at `include/rdf_dynsyn.h`.
+//! - `python`: exports `python` module with [PyO3](https://pyo3.rs) bindings, to parse, and serialize documents from python. Build python extension with it, and `extension-module` features, like with `maturin build --features python,extension-module`.
+//! - `wasm`: exports `wasm` module with [wasm-bindgen](https://docs.rs/wasm-bindgen) bindings, that convert documents from a syntax into another syntax in browsers. Crate compiles to `wasm32-unknown-unknown` target, except with `compression`, and `reqwest` features, which depend on native libraries.
+//! - `cli`: builds `dynsyn` command line tool, that converts documents, or directories of them, between syntaxes with `dynsyn convert -i in.ttl -o out.nq`, optionally skipping malformed statements with `--keep-going`, detects syntax of a document with `dynsyn sniff file`, and reports malformed statements of a document with `dynsyn validate file`. Install it with `cargo install rdf_dynsyn --features cli`.
+//! - `canon`: exports `serializer::canon` module, with which n-quads serializers write canonical n-quads per [RDFC-1.0](https://www.w3.org/TR/rdf-canon/), with deterministic blank node labels, as configured by `serializer::canon::CanonConfig`.
+//!
+#[cfg(all(
+    target_arch = "wasm32",
+    target_os = "unknown",
+    any(feature = "compression", feature = "reqwest")
+))]
+compile_error!("`compression`, and `reqwest` features are not supported on wasm32-unknown-unknown target");
+
+pub mod adapter;
+#[cfg(any(test, feature = "archive"))]
+pub mod archive;
+#[cfg(any(test, feature = "compression"))]
+pub mod compression;
+pub mod conneg;
+pub mod convert;
 pub mod correspondence;
+pub mod diff;
+pub mod error_code;
+#[cfg(any(test, feature = "fault_injection"))]
+pub mod fault_injection;
+pub mod fetch;
+#[cfg(any(test, feature = "ffi"))]
+pub mod ffi;
 pub mod file_extension;
+#[cfg(any(test, feature = "fixtures"))]
+pub mod fixtures;
+pub mod gen;
+pub mod iri_resolver;
+pub mod literal_decode;
+pub mod load;
 pub mod media_type;
+pub mod metrics;
+pub mod model;
 pub mod parser;
+#[cfg(any(test, feature = "proptest"))]
+pub mod proptest_support;
+#[cfg(feature = "python")]
+pub mod python;
 pub mod serializer;
+pub mod service;
+#[cfg(any(test, feature = "snapshot"))]
+pub mod snapshot;
+pub mod support;
 pub mod syntax;
+pub mod syntax_hint;
+#[cfg(any(test, feature = "wasm"))]
+pub mod wasm;
 
 #[cfg(test)]
 mod tests {
     use once_cell::sync::Lazy;
 
     fn setup_simple_tracing() {
-        if !std::env::var("TEST_LOG").is_ok() {
+        if std::env::var("TEST_LOG").is_err() {
             return;
         }
         env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
@@ -0,0 +1,471 @@
+//! This module provides an optional post-parse layer, that decodes xsd numeric, boolean, and date/time literals into native rust values. Downstream analytics can then work on native values, without re-parsing lexical forms of literals repeatedly.
+//!
+//! Decoding is driven by a [`LiteralDecoder`], that can be configured to decode only selected families of datatypes. It can decode single terms, or visit a quad-source, passing each quad along with decoded value of it's object.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{literal_decode::*, parser::quads::DynSynQuadParserFactory, syntax};
+//! use sophia_api::parser::QuadParser;
+//! use sophia_term::BoxTerm;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let doc = r#"
+//!     <http://example.org/a> <http://example.org/age> "42"^^<http://www.w3.org/2001/XMLSchema#integer> .
+//!     <http://example.org/a> <http://example.org/name> "A" .
+//! "#;
+//! let parser = DynSynQuadParserFactory::default().try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)?;
+//!
+//! let mut total = 0;
+//! LiteralDecoder::default().visit_quads(parser.parse_str(doc), |_quad, value| {
+//!     if let Some(Ok(NativeValue::Integer(v))) = value {
+//!         total += v;
+//!     }
+//! })?;
+//! assert_eq!(total, 42);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::fmt::Display;
+
+use sophia_api::{
+    ns::xsd,
+    quad::{stream::QuadSource, streaming_mode::StreamedQuad, Quad},
+    term::{TTerm, TermKind},
+};
+
+use crate::error_code::{self, ErrorCode};
+
+/// A calendar date, as represented by `xsd:date`, and date part of `xsd:dateTime`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+}
+
+/// A time of day, as represented by `xsd:time`, and time part of `xsd:dateTime`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Time {
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    /// fractional seconds, in nanoseconds
+    pub nanosecond: u32,
+}
+
+/// Timezone offset of a date/time value, in minutes from UTC. `None` if value has no timezone.
+pub type TzOffset = Option<i16>;
+
+/// A native value decoded from a literal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NativeValue {
+    /// Value of `xsd:integer` and it's derived datatypes.
+    Integer(i64),
+    /// Value of `xsd:decimal`.
+    Decimal(f64),
+    /// Value of `xsd:double`.
+    Double(f64),
+    /// Value of `xsd:float`.
+    Float(f32),
+    /// Value of `xsd:boolean`.
+    Boolean(bool),
+    /// Value of `xsd:date`.
+    Date(Date, TzOffset),
+    /// Value of `xsd:time`.
+    Time(Time, TzOffset),
+    /// Value of `xsd:dateTime`.
+    DateTime(Date, Time, TzOffset),
+}
+
+/// Families of datatypes, that a [`LiteralDecoder`] can decode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LiteralDecodeConfig {
+    /// decode `xsd:integer` (and derived), `xsd:decimal`, `xsd:double`, and `xsd:float` literals.
+    pub numeric: bool,
+    /// decode `xsd:boolean` literals.
+    pub boolean: bool,
+    /// decode `xsd:date`, `xsd:time`, and `xsd:dateTime` literals.
+    pub date_time: bool,
+}
+
+impl Default for LiteralDecodeConfig {
+    fn default() -> Self {
+        Self {
+            numeric: true,
+            boolean: true,
+            date_time: true,
+        }
+    }
+}
+
+/// An error indicating, lexical form of a literal is not valid for it's datatype.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Invalid lexical form {lexical_form:?} for datatype <{datatype}>")]
+pub struct LiteralDecodeError {
+    pub lexical_form: String,
+    pub datatype: String,
+}
+
+impl ErrorCode for LiteralDecodeError {
+    fn code(&self) -> &'static str {
+        error_code::DYNSYN_LITERAL_DECODE
+    }
+}
+
+/// A decoder, that converts literals of supported datatypes into [`NativeValue`]s.
+#[derive(Debug, Clone, Default)]
+pub struct LiteralDecoder {
+    config: LiteralDecodeConfig,
+}
+
+impl LiteralDecoder {
+    pub fn new(config: LiteralDecodeConfig) -> Self {
+        Self { config }
+    }
+
+    /// Returns the config, this decoder is instantiated with.
+    pub fn config(&self) -> &LiteralDecodeConfig {
+        &self.config
+    }
+
+    /// Decodes given term.
+    ///
+    /// Returns `None` if term is not a literal, or if it's datatype is not enabled/supported for decoding. Returns `Some(Err(_))` if lexical form is not valid for it's datatype.
+    pub fn decode<T>(&self, term: &T) -> Option<Result<NativeValue, LiteralDecodeError>>
+    where
+        T: TTerm + ?Sized,
+    {
+        if term.kind() != TermKind::Literal {
+            return None;
+        }
+        let dt = term.datatype()?;
+        let lex = term.value();
+        let lex = lex.as_ref();
+
+        let decoded = if self.config.numeric && is_integer_dt(&dt) {
+            parse_integer(lex).map(NativeValue::Integer)
+        } else if self.config.numeric && xsd::decimal == dt {
+            parse_decimal(lex).map(NativeValue::Decimal)
+        } else if self.config.numeric && xsd::double == dt {
+            parse_float(lex).map(NativeValue::Double)
+        } else if self.config.numeric && xsd::float == dt {
+            parse_float(lex).map(|v| NativeValue::Float(v as f32))
+        } else if self.config.boolean && xsd::boolean == dt {
+            match lex {
+                "true" | "1" => Some(NativeValue::Boolean(true)),
+                "false" | "0" => Some(NativeValue::Boolean(false)),
+                _ => None,
+            }
+        } else if self.config.date_time && xsd::date == dt {
+            split_tz(lex).and_then(|(v, tz)| Some(NativeValue::Date(parse_date(v)?, tz)))
+        } else if self.config.date_time && xsd::time == dt {
+            split_tz(lex).and_then(|(v, tz)| Some(NativeValue::Time(parse_time(v)?, tz)))
+        } else if self.config.date_time && xsd::dateTime == dt {
+            split_tz(lex).and_then(|(v, tz)| {
+                let (d, t) = v.split_once('T')?;
+                Some(NativeValue::DateTime(parse_date(d)?, parse_time(t)?, tz))
+            })
+        } else {
+            return None;
+        };
+
+        Some(decoded.ok_or_else(|| LiteralDecodeError {
+            lexical_form: lex.to_owned(),
+            datatype: dt.value().to_string(),
+        }))
+    }
+
+    /// Visits each quad of `source`, passing it to `visitor` along with decoded value of it's object. See [`decode`](Self::decode) for meaning of decoded value.
+    pub fn visit_quads<QS, F>(&self, mut source: QS, mut visitor: F) -> Result<(), QS::Error>
+    where
+        QS: QuadSource,
+        F: FnMut(StreamedQuad<QS::Quad>, Option<Result<NativeValue, LiteralDecodeError>>),
+    {
+        source.for_each_quad(|q| {
+            let value = self.decode(q.o());
+            visitor(q, value);
+        })
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl Display for Time {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)?;
+        if self.nanosecond > 0 {
+            write!(f, ".{:09}", self.nanosecond)?;
+        }
+        Ok(())
+    }
+}
+
+fn is_integer_dt<T: TTerm + ?Sized>(dt: &T) -> bool {
+    [
+        xsd::integer,
+        xsd::long,
+        xsd::int,
+        xsd::short,
+        xsd::byte,
+        xsd::nonNegativeInteger,
+        xsd::nonPositiveInteger,
+        xsd::negativeInteger,
+        xsd::positiveInteger,
+        xsd::unsignedLong,
+        xsd::unsignedInt,
+        xsd::unsignedShort,
+        xsd::unsignedByte,
+    ]
+    .iter()
+    .any(|n| *n == *dt)
+}
+
+fn parse_integer(lex: &str) -> Option<i64> {
+    let digits = lex.strip_prefix(['+', '-']).unwrap_or(lex);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    lex.strip_prefix('+').unwrap_or(lex).parse().ok()
+}
+
+fn parse_decimal(lex: &str) -> Option<f64> {
+    let unsigned = lex.strip_prefix(['+', '-']).unwrap_or(lex);
+    let (int, frac) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    if int.is_empty() && frac.is_empty()
+        || !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    lex.parse().ok()
+}
+
+fn parse_float(lex: &str) -> Option<f64> {
+    match lex {
+        "INF" | "+INF" => Some(f64::INFINITY),
+        "-INF" => Some(f64::NEG_INFINITY),
+        "NaN" => Some(f64::NAN),
+        // rust accepts "inf", "infinity", "nan" in any case, which xsd doesn't.
+        _ if lex
+            .bytes()
+            .any(|b| b.is_ascii_alphabetic() && b != b'e' && b != b'E') =>
+        {
+            None
+        }
+        _ => lex.parse().ok(),
+    }
+}
+
+/// Splits optional timezone suffix (`Z`, or `(+|-)hh:mm`) from a date/time lexical form.
+fn split_tz(lex: &str) -> Option<(&str, TzOffset)> {
+    if let Some(v) = lex.strip_suffix('Z') {
+        return Some((v, Some(0)));
+    }
+    if lex.len() > 6 {
+        let (v, tz) = lex.split_at(lex.len() - 6);
+        let sign = match tz.as_bytes()[0] {
+            b'+' => 1,
+            b'-' => -1,
+            _ => return Some((lex, None)),
+        };
+        // A date's own trailing `-dd` cannot be confused with an offset, as offsets have a colon.
+        if tz.as_bytes()[3] == b':' {
+            let h: i16 = parse_fixed_digits(&tz[1..3])?;
+            let m: i16 = parse_fixed_digits(&tz[4..6])?;
+            if h > 14 || m > 59 || (h == 14 && m > 0) {
+                return None;
+            }
+            return Some((v, Some(sign * (h * 60 + m))));
+        }
+    }
+    Some((lex, None))
+}
+
+fn parse_fixed_digits<N: std::str::FromStr>(v: &str) -> Option<N> {
+    if v.is_empty() || !v.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    v.parse().ok()
+}
+
+fn parse_date(v: &str) -> Option<Date> {
+    let (neg, unsigned) = match v.strip_prefix('-') {
+        Some(u) => (true, u),
+        None => (false, v),
+    };
+    let mut parts = unsigned.splitn(3, '-');
+    let (y, m, d) = (parts.next()?, parts.next()?, parts.next()?);
+    if y.len() < 4 || m.len() != 2 || d.len() != 2 {
+        return None;
+    }
+    let year: i32 = parse_fixed_digits(y)?;
+    let year = if neg { -year } else { year };
+    let month: u8 = parse_fixed_digits(m)?;
+    let day: u8 = parse_fixed_digits(d)?;
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return None;
+    }
+    Some(Date { year, month, day })
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn parse_time(v: &str) -> Option<Time> {
+    let (hms, frac) = v.split_once('.').unwrap_or((v, ""));
+    let mut parts = hms.splitn(3, ':');
+    let (h, m, s) = (parts.next()?, parts.next()?, parts.next()?);
+    if h.len() != 2 || m.len() != 2 || s.len() != 2 || (v.contains('.') && frac.is_empty()) {
+        return None;
+    }
+    let hour: u8 = parse_fixed_digits(h)?;
+    let minute: u8 = parse_fixed_digits(m)?;
+    let second: u8 = parse_fixed_digits(s)?;
+    let nanosecond = if frac.is_empty() {
+        0
+    } else {
+        let digits: String = frac.chars().chain("000000000".chars()).take(9).collect();
+        if !frac.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        digits.parse().ok()?
+    };
+    // 24:00:00 is allowed as end of day.
+    if hour == 24 && (minute, second, nanosecond) == (0, 0, 0) {
+        return Some(Time {
+            hour,
+            minute,
+            second,
+            nanosecond,
+        });
+    }
+    if hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    Some(Time {
+        hour,
+        minute,
+        second,
+        nanosecond,
+    })
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use sophia_api::{ns::Namespace, parser::QuadParser};
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+
+    use crate::{parser::quads::DynSynQuadParserFactory, syntax, tests::TRACING};
+
+    use super::*;
+
+    fn literal(lex: &str, dt_local: &str) -> BoxTerm {
+        let xsd_ns = Namespace::new("http://www.w3.org/2001/XMLSchema#").unwrap();
+        BoxTerm::new_literal_dt(lex, xsd_ns.get(dt_local).unwrap()).unwrap()
+    }
+
+    #[test_case("42", "integer", NativeValue::Integer(42))]
+    #[test_case("+7", "int", NativeValue::Integer(7))]
+    #[test_case("-0012", "long", NativeValue::Integer(-12))]
+    #[test_case("3.25", "decimal", NativeValue::Decimal(3.25))]
+    #[test_case("-.5", "decimal", NativeValue::Decimal(-0.5))]
+    #[test_case("1.5E2", "double", NativeValue::Double(150.0))]
+    #[test_case("-INF", "double", NativeValue::Double(f64::NEG_INFINITY))]
+    #[test_case("2.5", "float", NativeValue::Float(2.5))]
+    #[test_case("1", "boolean", NativeValue::Boolean(true))]
+    #[test_case("false", "boolean", NativeValue::Boolean(false))]
+    #[test_case("2024-02-29", "date", NativeValue::Date(Date { year: 2024, month: 2, day: 29 }, None))]
+    #[test_case("2024-02-03-05:30", "date", NativeValue::Date(Date { year: 2024, month: 2, day: 3 }, Some(-330)))]
+    #[test_case("13:20:00.5Z", "time", NativeValue::Time(Time { hour: 13, minute: 20, second: 0, nanosecond: 500_000_000 }, Some(0)))]
+    #[test_case("2002-05-30T09:00:00+05:30", "dateTime", NativeValue::DateTime(Date { year: 2002, month: 5, day: 30 }, Time { hour: 9, minute: 0, second: 0, nanosecond: 0 }, Some(330)))]
+    pub fn decodes_valid_literals(lex: &str, dt_local: &str, expected: NativeValue) {
+        Lazy::force(&TRACING);
+        let decoded = LiteralDecoder::default().decode(&literal(lex, dt_local));
+        assert_eq!(decoded, Some(Ok(expected)));
+    }
+
+    #[test_case("4.2", "integer")]
+    #[test_case("", "integer")]
+    #[test_case("99999999999999999999", "integer")]
+    #[test_case("1,5", "decimal")]
+    #[test_case("inf", "double")]
+    #[test_case("yes", "boolean")]
+    #[test_case("2023-02-29", "date")]
+    #[test_case("2023-2-1", "date")]
+    #[test_case("25:00:00", "time")]
+    #[test_case("2002-05-30 09:00:00", "dateTime")]
+    #[test_case("2002-05-30T09:00:00+15:00", "dateTime")]
+    pub fn rejects_invalid_lexical_forms(lex: &str, dt_local: &str) {
+        Lazy::force(&TRACING);
+        let e = LiteralDecoder::default()
+            .decode(&literal(lex, dt_local))
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(e.lexical_form, lex);
+        assert_eq!(e.code(), error_code::DYNSYN_LITERAL_DECODE);
+    }
+
+    #[test]
+    pub fn skips_non_literals_and_unsupported_datatypes() {
+        Lazy::force(&TRACING);
+        let decoder = LiteralDecoder::default();
+        assert!(decoder
+            .decode(&BoxTerm::new_iri("http://example.org/").unwrap())
+            .is_none());
+        assert!(decoder.decode(&literal("abc", "string")).is_none());
+        assert!(decoder
+            .decode(&BoxTerm::new_literal_lang("1", "en").unwrap())
+            .is_none());
+    }
+
+    #[test]
+    pub fn skips_disabled_datatype_families() {
+        Lazy::force(&TRACING);
+        let decoder = LiteralDecoder::new(LiteralDecodeConfig {
+            numeric: false,
+            boolean: true,
+            date_time: false,
+        });
+        assert!(decoder.decode(&literal("1", "integer")).is_none());
+        assert!(decoder.decode(&literal("2020-01-01", "date")).is_none());
+        assert!(decoder.decode(&literal("true", "boolean")).is_some());
+    }
+
+    #[test]
+    pub fn visits_quads_with_decoded_objects() {
+        Lazy::force(&TRACING);
+        let doc = r#"
+            <http://ex.org/s> <http://ex.org/p> "1"^^<http://www.w3.org/2001/XMLSchema#integer> .
+            <http://ex.org/s> <http://ex.org/p> "x"^^<http://www.w3.org/2001/XMLSchema#integer> <http://ex.org/g> .
+            <http://ex.org/s> <http://ex.org/p> <http://ex.org/o> .
+        "#;
+        let parser = DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+            .unwrap();
+        let mut values = Vec::new();
+        LiteralDecoder::default()
+            .visit_quads(parser.parse_str(doc), |_, v| {
+                values.push(v.map(|r| r.is_ok()))
+            })
+            .unwrap();
+        assert_eq!(values, vec![Some(true), Some(false), None]);
+    }
+}
@@ -0,0 +1,348 @@
+//! This module provides convenience loaders, that resolve syntax of a document, decompress it if needed, parse it, and collect it's statements into a graph, or a dataset, in a single call. All failures are reported through a single [`LoadError`].
+//!
+//! Syntax of files is resolved from their file extension, after stripping compression suffixes, like `.gz`. Files are decompressed transparently when `compression` feature is enabled. Documents at urls are fetched through a [`DocumentFetcher`], and are parsed as their bodies are read from [`fetch_streamed`](DocumentFetcher::fetch_streamed). Their syntax is resolved from their media type, or from file extension of their iri, as by [`StreamedDocument::syntax`].
+//!
+//! Example:
+//!
+//! ```
+//! use std::fs;
+//!
+//! use rdf_dynsyn::load::{load_dataset_from_path, load_graph_from_path};
+//! use sophia_api::{dataset::Dataset, graph::Graph};
+//! use sophia_inmem::{dataset::FastDataset, graph::FastGraph};
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let path = std::env::temp_dir().join("rdf_dynsyn_load_doc.trig");
+//! fs::write(
+//!     &path,
+//!     "<http://e/a> <http://e/p> <http://e/b> . <http://e/g> { <http://e/a> <http://e/p> <http://e/c> . }",
+//! )?;
+//!
+//! let dataset: FastDataset = load_dataset_from_path(&path)?;
+//! assert_eq!(dataset.quads().count(), 2);
+//! let graph: FastGraph = load_graph_from_path(&path)?;
+//! assert_eq!(graph.triples().count(), 1);
+//! # fs::remove_file(&path)?;
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::{
+    error::Error,
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+
+use sophia_api::{
+    dataset::CollectibleDataset, graph::CollectibleGraph, triple::stream::StreamError,
+};
+use sophia_term::BoxTerm;
+
+use crate::{
+    correspondence::{syntax_for_path, ForPathError, NonRdfMediaTypeError},
+    error_code::{self, ErrorCode},
+    fetch::{DocumentFetcher, StreamedDocument},
+    media_type::accept_header_for_parsers,
+    parser::{
+        errors::DynSynParseError,
+        statements::{DynSynStatementParser, DynSynStatementParserFactory},
+    },
+    syntax::{RdfSyntax, UnKnownSyntaxError},
+};
+
+#[cfg(any(test, feature = "compression"))]
+use crate::compression::{Compression, DecompressedRead};
+
+/// An error in loading a graph, or a dataset.
+#[derive(Debug, thiserror::Error)]
+pub enum LoadError {
+    #[error(transparent)]
+    ForPath(#[from] ForPathError),
+    #[error(transparent)]
+    NonRdfMediaType(#[from] NonRdfMediaTypeError),
+    #[error("Syntax of document at {0} couldn't be determined")]
+    UnDeterminedSyntax(String),
+    #[error(transparent)]
+    UnKnownSyntax(#[from] UnKnownSyntaxError),
+    #[error("Io error in reading file {0:?}: {1}")]
+    Io(PathBuf, #[source] io::Error),
+    #[error("Error in fetching document: {0}")]
+    Fetch(#[source] io::Error),
+    #[error("Error in parsing document: {0}")]
+    Parse(#[source] DynSynParseError),
+    #[error("Error in collecting statements: {0}")]
+    Collect(#[source] Box<dyn Error + Send + Sync>),
+}
+
+impl ErrorCode for LoadError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::ForPath(e) => e.code(),
+            Self::NonRdfMediaType(e) => e.code(),
+            Self::UnDeterminedSyntax(_) => error_code::DYNSYN_UNDETERMINED_SYNTAX,
+            Self::UnKnownSyntax(e) => e.code(),
+            Self::Io(_, _) => error_code::DYNSYN_PARSE_IO,
+            Self::Fetch(_) => error_code::DYNSYN_FETCH,
+            Self::Parse(e) => e.code(),
+            Self::Collect(_) => error_code::DYNSYN_STATEMENT_SINK,
+        }
+    }
+}
+
+impl<E> From<StreamError<DynSynParseError, E>> for LoadError
+where
+    E: Error + Send + Sync + 'static,
+{
+    fn from(e: StreamError<DynSynParseError, E>) -> Self {
+        match e {
+            StreamError::SourceError(e) => Self::Parse(e),
+            StreamError::SinkError(e) => Self::Collect(Box::new(e)),
+        }
+    }
+}
+
+/// Loads graphs, and datasets from files, and urls, with parsers from it's parser factory.
+#[derive(Debug, Clone, Default)]
+pub struct Loader {
+    parser_factory: DynSynStatementParserFactory,
+}
+
+impl Loader {
+    /// Create a new loader, that parses documents with parsers from given `parser_factory`.
+    pub fn new(parser_factory: DynSynStatementParserFactory) -> Self {
+        Self { parser_factory }
+    }
+
+    /// Get parser for file at given `path`, along with a reader of it's decompressed content.
+    fn open(
+        &self,
+        path: &Path,
+    ) -> Result<(DynSynStatementParser<BoxTerm>, impl BufRead), LoadError> {
+        let syntax_ = syntax_for_path(path)?;
+        let parser = self.parser(syntax_, Some(base_iri_for(path)))?;
+        let read = BufReader::new(File::open(path).map_err(|e| LoadError::Io(path.into(), e))?);
+        #[cfg(any(test, feature = "compression"))]
+        let read = DecompressedRead::new(read, Compression::from_path(path));
+        Ok((parser, read))
+    }
+
+    fn parser(
+        &self,
+        syntax_: RdfSyntax,
+        base_iri: Option<String>,
+    ) -> Result<DynSynStatementParser<BoxTerm>, UnKnownSyntaxError> {
+        self.parser_factory.try_new_parser(syntax_, base_iri)
+    }
+
+    /// Fetch document at given `url` with given `fetcher`, and get parser for it.
+    fn fetch<F: DocumentFetcher>(
+        &self,
+        url: &str,
+        fetcher: &F,
+    ) -> Result<(DynSynStatementParser<BoxTerm>, StreamedDocument), LoadError> {
+        let doc = fetcher
+            .fetch_streamed(url, accept_header_for_parsers())
+            .map_err(LoadError::Fetch)?;
+        let syntax_ = doc
+            .syntax()?
+            .ok_or_else(|| LoadError::UnDeterminedSyntax(doc.iri.clone()))?;
+        Ok((self.parser(syntax_, Some(doc.iri.clone()))?, doc))
+    }
+
+    /// Load default graph of the document in file at given `path`. Quads in named graphs of documents in quad syntaxes are ignored.
+    ///
+    /// # Errors
+    /// returns [`LoadError`] if syntax of the file couldn't be resolved, or if it couldn't be read, parsed, or collected into the graph.
+    pub fn load_graph_from_path<G>(&self, path: impl AsRef<Path>) -> Result<G, LoadError>
+    where
+        G: CollectibleGraph,
+        G::Error: Send + Sync,
+    {
+        let (parser, read) = self.open(path.as_ref())?;
+        Ok(parser.parse_to_graph(read)?)
+    }
+
+    /// Load dataset of the document in file at given `path`. Triples of documents in triple syntaxes are loaded into default graph.
+    ///
+    /// # Errors
+    /// returns [`LoadError`] if syntax of the file couldn't be resolved, or if it couldn't be read, parsed, or collected into the dataset.
+    pub fn load_dataset_from_path<D>(&self, path: impl AsRef<Path>) -> Result<D, LoadError>
+    where
+        D: CollectibleDataset,
+        D::Error: Send + Sync,
+    {
+        let (parser, read) = self.open(path.as_ref())?;
+        Ok(parser.parse_to_dataset(read)?)
+    }
+
+    /// Load default graph of the document at given `url`, fetched with given `fetcher`. Relative iris are resolved against iri of fetched document.
+    ///
+    /// # Errors
+    /// returns [`LoadError`] if document couldn't be fetched, if it's syntax couldn't be resolved, or if it couldn't be parsed, or collected into the graph.
+    pub fn load_graph_from_url<G, F>(&self, url: &str, fetcher: &F) -> Result<G, LoadError>
+    where
+        G: CollectibleGraph,
+        G::Error: Send + Sync,
+        F: DocumentFetcher,
+    {
+        let (parser, doc) = self.fetch(url, fetcher)?;
+        Ok(parser.parse_to_graph(doc.body)?)
+    }
+
+    /// Load dataset of the document at given `url`, fetched with given `fetcher`. Relative iris are resolved against iri of fetched document.
+    ///
+    /// # Errors
+    /// returns [`LoadError`] if document couldn't be fetched, if it's syntax couldn't be resolved, or if it couldn't be parsed, or collected into the dataset.
+    pub fn load_dataset_from_url<D, F>(&self, url: &str, fetcher: &F) -> Result<D, LoadError>
+    where
+        D: CollectibleDataset,
+        D::Error: Send + Sync,
+        F: DocumentFetcher,
+    {
+        let (parser, doc) = self.fetch(url, fetcher)?;
+        Ok(parser.parse_to_dataset(doc.body)?)
+    }
+}
+
+/// Get `file:` iri of given path, to resolve relative iris of the file against.
+fn base_iri_for(path: &Path) -> String {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_owned());
+    format!("file://{}", path.display())
+}
+
+/// Load default graph of the document in file at given `path`, with default parsers. See [`Loader::load_graph_from_path`].
+pub fn load_graph_from_path<G>(path: impl AsRef<Path>) -> Result<G, LoadError>
+where
+    G: CollectibleGraph,
+    G::Error: Send + Sync,
+{
+    Loader::default().load_graph_from_path(path)
+}
+
+/// Load dataset of the document in file at given `path`, with default parsers. See [`Loader::load_dataset_from_path`].
+pub fn load_dataset_from_path<D>(path: impl AsRef<Path>) -> Result<D, LoadError>
+where
+    D: CollectibleDataset,
+    D::Error: Send + Sync,
+{
+    Loader::default().load_dataset_from_path(path)
+}
+
+/// Load default graph of the document at given `url`, fetched with given `fetcher`, with default parsers. See [`Loader::load_graph_from_url`].
+pub fn load_graph_from_url<G, F>(url: &str, fetcher: &F) -> Result<G, LoadError>
+where
+    G: CollectibleGraph,
+    G::Error: Send + Sync,
+    F: DocumentFetcher,
+{
+    Loader::default().load_graph_from_url(url, fetcher)
+}
+
+/// Load dataset of the document at given `url`, fetched with given `fetcher`, with default parsers. See [`Loader::load_dataset_from_url`].
+pub fn load_dataset_from_url<D, F>(url: &str, fetcher: &F) -> Result<D, LoadError>
+where
+    D: CollectibleDataset,
+    D::Error: Send + Sync,
+    F: DocumentFetcher,
+{
+    Loader::default().load_dataset_from_url(url, fetcher)
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        io::{self, Write},
+        path::PathBuf,
+    };
+
+    use flate2::{write::GzEncoder, Compression as Level};
+    use once_cell::sync::Lazy;
+    use sophia_api::{dataset::Dataset, graph::Graph};
+    use sophia_inmem::{dataset::FastDataset, graph::FastGraph};
+    use test_case::test_case;
+
+    use super::{
+        load_dataset_from_path, load_dataset_from_url, load_graph_from_path, load_graph_from_url,
+        LoadError,
+    };
+    use crate::{
+        error_code::{self, ErrorCode},
+        fetch::FetchedDocument,
+        tests::TRACING,
+    };
+
+    static DOC: &str = "<http://e/a> <http://e/p> <http://e/b> .\n<http://e/a> <http://e/p> <http://e/c> <http://e/g> .\n";
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("dynsyn-load-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    pub fn loads_from_plain_and_compressed_files() {
+        Lazy::force(&TRACING);
+        let plain = temp_path("data.nq");
+        fs::write(&plain, DOC).unwrap();
+        let compressed = temp_path("data.nq.gz");
+        let mut encoder = GzEncoder::new(Vec::new(), Level::default());
+        encoder.write_all(DOC.as_bytes()).unwrap();
+        fs::write(&compressed, encoder.finish().unwrap()).unwrap();
+
+        for path in [&plain, &compressed] {
+            let dataset: FastDataset = load_dataset_from_path(path).unwrap();
+            assert_eq!(dataset.quads().count(), 2);
+            let graph: FastGraph = load_graph_from_path(path).unwrap();
+            assert_eq!(graph.triples().count(), 1);
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test_case("data.nq", None, error_code::DYNSYN_PARSE_IO)]
+    #[test_case("data.png", Some(DOC), error_code::DYNSYN_NON_RDF_FILE_EXTENSION)]
+    #[test_case("data", Some(DOC), error_code::DYNSYN_UNDETERMINED_SYNTAX)]
+    #[test_case("data.nt", Some(DOC), error_code::DYNSYN_PARSE_SYNTAX)]
+    pub fn reports_load_errors_from_path(name: &str, content: Option<&str>, code: &str) {
+        Lazy::force(&TRACING);
+        let path = temp_path(&format!("errors-{}", name));
+        if let Some(content) = content {
+            fs::write(&path, content).unwrap();
+        }
+        let e: LoadError = load_dataset_from_path::<FastDataset>(&path).err().unwrap();
+        if content.is_some() {
+            fs::remove_file(&path).unwrap();
+        }
+        assert_eq!(e.code(), code);
+    }
+
+    #[test_case(Some("application/n-quads"), None)]
+    #[test_case(Some("application/json"), Some(error_code::DYNSYN_NON_RDF_MEDIA_TYPE))]
+    #[test_case(None, Some(error_code::DYNSYN_UNDETERMINED_SYNTAX))]
+    pub fn loads_from_url(content_type: Option<&'static str>, code: Option<&str>) {
+        Lazy::force(&TRACING);
+        let fetcher = |iri: &str, _: &str| -> io::Result<FetchedDocument> {
+            Ok(FetchedDocument {
+                iri: iri.to_owned(),
+                content_type: content_type.map(|c| c.parse().unwrap()),
+                body: DOC.as_bytes().to_vec(),
+            })
+        };
+        let url = "http://example.org/data";
+        match code {
+            None => {
+                let dataset: FastDataset = load_dataset_from_url(url, &fetcher).unwrap();
+                assert_eq!(dataset.quads().count(), 2);
+                let graph: FastGraph = load_graph_from_url(url, &fetcher).unwrap();
+                assert_eq!(graph.triples().count(), 1);
+            }
+            Some(code) => {
+                let e = load_dataset_from_url::<FastDataset, _>(url, &fetcher).err().unwrap();
+                assert_eq!(e.code(), code);
+            }
+        }
+    }
+}
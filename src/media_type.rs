@@ -1,6 +1,46 @@
+//! This module defines canonical media types of known rdf syntaxes, and helpers to build values of http `Content-Type`, and `Accept` headers from them. Http servers, and clients embedding dynsyn can use these values as is, instead of rebuilding them from media types. Values of `Content-Type` header are built with [`ContentTypeConfig`](crate::serializer::content_type::ContentTypeConfig), which attaches `charset` parameter only to media types, that [take it](takes_charset).
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{
+//!     media_type::{accept_header_for_parsers, content_type_for, takes_charset},
+//!     serializer::content_type::ContentTypeConfig,
+//!     syntax,
+//! };
+//!
+//! assert_eq!(
+//!     content_type_for(syntax::TURTLE, Some("utf-8")).as_deref(),
+//!     Some("text/turtle; charset=utf-8")
+//! );
+//! let config = ContentTypeConfig::new().with_charset_utf8(true);
+//! assert_eq!(
+//!     config.content_type(syntax::TURTLE).unwrap().to_string(),
+//!     "text/turtle; charset=utf-8"
+//! );
+//! // Json media types don't take a charset parameter.
+//! assert!(!takes_charset(syntax::JSON_LD));
+//! assert_eq!(
+//!     content_type_for(syntax::JSON_LD, Some("utf-8")).as_deref(),
+//!     Some("application/ld+json")
+//! );
+//! assert_eq!(
+//!     config.content_type(syntax::JSON_LD).unwrap().to_string(),
+//!     "application/ld+json"
+//! );
+//! assert!(accept_header_for_parsers().starts_with("text/turtle, "));
+//! ```
+
 use mime::Mime;
 use once_cell::sync::Lazy;
 
+use crate::{
+    correspondence::SYNTAX_TO_MEDIA_TYPE_CORRESPONDENCE,
+    serializer::content_type::ContentTypeConfig,
+    support::{parsable_quad_syntaxes, parsable_triple_syntaxes},
+    syntax::{self, RdfSyntax, KNOWN_SYNTAXES},
+};
+
 pub static APPLICATION_JSON_LD: Lazy<Mime> = Lazy::new(|| "application/ld+json".parse().unwrap());
 
 pub static APPLICATION_N_QUADS: Lazy<Mime> = Lazy::new(|| "application/n-quads".parse().unwrap());
@@ -14,6 +54,13 @@ pub static APPLICATION_RDF_XML: Lazy<Mime> = Lazy::new(|| "application/rdf+xml".
 
 pub static APPLICATION_TRIG: Lazy<Mime> = Lazy::new(|| "application/trig".parse().unwrap());
 
+pub static APPLICATION_TRIX: Lazy<Mime> = Lazy::new(|| "application/trix".parse().unwrap());
+
+pub static APPLICATION_VND_HDT: Lazy<Mime> = Lazy::new(|| "application/vnd.hdt".parse().unwrap());
+
+pub static APPLICATION_X_ND_RDF_JSON: Lazy<Mime> =
+    Lazy::new(|| "application/x-nd-rdf-json".parse().unwrap());
+
 pub static APPLICATION_XHTML_XML: Lazy<Mime> =
     Lazy::new(|| "application/xhtml+xml".parse().unwrap());
 
@@ -24,3 +71,145 @@ pub static TEXT_N3: Lazy<Mime> = Lazy::new(|| "text/n3".parse().unwrap());
 pub static TEXT_OWL_MANCHESTER: Lazy<Mime> = Lazy::new(|| "text/owl-manchester".parse().unwrap());
 
 pub static TEXT_TURTLE: Lazy<Mime> = Lazy::new(|| "text/turtle".parse().unwrap());
+
+/// Returns true, if media type of given syntax takes a `charset` parameter. Binary syntaxes, and json based syntaxes, which are always encoded in utf-8, don't take it.
+pub fn takes_charset(syntax_: RdfSyntax) -> bool {
+    !matches!(syntax_, syntax::HDT | syntax::JSON_LD | syntax::ND_RDF_JSON)
+}
+
+/// Get value of `Content-Type` header for documents in given syntax, with `charset` parameter attached, if it is given, and if media type of syntax takes it. Returns `None` if syntax has no known media type.
+///
+/// It is built with a [`ContentTypeConfig`], that configures given charset. See it for attaching other parameters.
+///
+/// # Panics
+/// Panics if `charset` is not a valid parameter value, as per [`ContentTypeConfig::with_param`].
+pub fn content_type_for(syntax_: RdfSyntax, charset: Option<&str>) -> Option<String> {
+    let config = match charset {
+        Some(charset) => ContentTypeConfig::new().with_param(syntax_, "charset", charset),
+        None => ContentTypeConfig::new(),
+    };
+    Some(config.content_type(syntax_)?.to_string())
+}
+
+/// Get quality value, with which media type of given parsable syntax is accepted. Syntaxes of rdf 1.1 turtle family are preferred, then xml based syntaxes, and then others, like embedded, and non-standard syntaxes.
+fn parser_preference(syntax_: RdfSyntax) -> f32 {
+    match syntax_ {
+        syntax::TURTLE | syntax::TRIG | syntax::N_TRIPLES | syntax::N_QUADS => 1.0,
+        syntax::RDF_XML | syntax::TRIX => 0.9,
+        _ => 0.5,
+    }
+}
+
+/// Format given quality value, as http allows, with at most three decimal digits, and without trailing zeros.
+fn format_qvalue(q: f32) -> String {
+    let q = format!("{:.3}", q.clamp(0.0, 1.0));
+    q.trim_end_matches('0').trim_end_matches('.').to_owned()
+}
+
+static ACCEPT_HEADER_FOR_PARSERS: Lazy<String> = Lazy::new(|| {
+    let mut ranges: Vec<(f32, &str)> = KNOWN_SYNTAXES
+        .into_iter()
+        .filter(|syntax_| {
+            parsable_quad_syntaxes().contains(syntax_)
+                || parsable_triple_syntaxes().contains(syntax_)
+        })
+        .filter_map(|syntax_| {
+            let media_type = SYNTAX_TO_MEDIA_TYPE_CORRESPONDENCE.get(&syntax_)?.value;
+            Some((parser_preference(syntax_), media_type.essence_str()))
+        })
+        .collect();
+    // Stable sort retains order of known syntaxes among equally preferred ones.
+    ranges.sort_by(|(q1, _), (q2, _)| q2.total_cmp(q1));
+    ranges
+        .into_iter()
+        .map(|(q, media_type)| match format_qvalue(q).as_str() {
+            "1" => media_type.to_owned(),
+            q => format!("{};q={}", media_type, q),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+});
+
+/// Get value of `Accept` header, that requests documents in any syntax, that can be parsed in this build, as per [support matrix](crate::support), with quality values weighted by preference. Syntaxes of rdf 1.1 turtle family have quality value of 1, xml based syntaxes have 0.9, and others have 0.5. It is the `Accept` header, that [`fetch`](crate::fetch), and [`load`](crate::load) modules negotiate documents with.
+pub fn accept_header_for_parsers() -> &'static str {
+    &ACCEPT_HEADER_FOR_PARSERS
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use test_case::test_case;
+
+    use super::{accept_header_for_parsers, content_type_for, format_qvalue, takes_charset};
+    use crate::{
+        conneg::{resolve_syntax_for_accept, ParserOrSerializer},
+        support::SyntaxSupport,
+        syntax::{self, RdfSyntax},
+        tests::TRACING,
+    };
+
+    #[test_case(syntax::TURTLE, true)]
+    #[test_case(syntax::RDF_XML, true)]
+    #[test_case(syntax::JSON_LD, false)]
+    #[test_case(syntax::ND_RDF_JSON, false)]
+    #[test_case(syntax::HDT, false)]
+    pub fn tells_whether_media_type_takes_charset(syntax_: RdfSyntax, expected: bool) {
+        Lazy::force(&TRACING);
+        assert_eq!(takes_charset(syntax_), expected);
+    }
+
+    #[test_case(syntax::TURTLE, None, Some("text/turtle"))]
+    #[test_case(
+        syntax::N_QUADS,
+        Some("utf-8"),
+        Some("application/n-quads; charset=utf-8")
+    )]
+    #[test_case(
+        syntax::RDF_XML,
+        Some("us-ascii"),
+        Some("application/rdf+xml; charset=us-ascii")
+    )]
+    #[test_case(syntax::JSON_LD, Some("utf-8"), Some("application/ld+json"))]
+    #[test_case(syntax::ND_RDF_JSON, Some("utf-8"), Some("application/x-nd-rdf-json"))]
+    #[test_case(syntax::HDT, Some("utf-8"), Some("application/vnd.hdt"))]
+    #[test_case(RdfSyntax("http://example.org/unknown"), Some("utf-8"), None)]
+    pub fn builds_content_type(syntax_: RdfSyntax, charset: Option<&str>, expected: Option<&str>) {
+        Lazy::force(&TRACING);
+        assert_eq!(content_type_for(syntax_, charset).as_deref(), expected);
+    }
+
+    #[test]
+    pub fn accept_header_lists_parsable_syntaxes_by_preference() {
+        Lazy::force(&TRACING);
+        let accept = accept_header_for_parsers();
+        assert!(accept.starts_with(
+            "text/turtle, application/n-triples, application/n-quads, application/trig"
+        ));
+        assert!(!accept.contains("application/ld+json"));
+        assert_eq!(
+            resolve_syntax_for_accept(accept, ParserOrSerializer::Parser),
+            Some(syntax::TURTLE)
+        );
+
+        for range in accept.split(", ") {
+            let media_type = range.split(';').next().unwrap();
+            let syntax_ = resolve_syntax_for_accept(media_type, ParserOrSerializer::Parser);
+            assert!(syntax_.is_some(), "{} is not parsable", media_type);
+            let support = SyntaxSupport::of(syntax_.unwrap());
+            assert!(support.quad_parsing || support.triple_parsing);
+        }
+    }
+
+    #[test_case(1.0, "1")]
+    #[test_case(0.9, "0.9")]
+    #[test_case(0.125, "0.125")]
+    #[test_case(0.0626, "0.063")]
+    #[test_case(0.0, "0")]
+    pub fn formats_qvalues(q: f32, expected: &str) {
+        Lazy::force(&TRACING);
+        assert_eq!(format_qvalue(q), expected);
+    }
+}
@@ -0,0 +1,195 @@
+//! This module exposes process wide counters on how syntaxes of documents get resolved, and how often content of documents contradicts their declared syntax. Operators of ingestion services can export them to their monitoring systems, to quantify how broken their upstream sources are.
+//!
+//! Counted events are:
+//!
+//! - Correspondence lookups of syntaxes for media types, and file extensions, and whether they resolved to a syntax. Only resolutions requested through public entry points are counted: [`Correspondent::try_from`](crate::correspondence::Correspondent), and functions resolving through it, like [`syntax_for_path`](crate::correspondence::syntax_for_path), [`SyntaxHint::resolve`](crate::syntax_hint::SyntaxHint::resolve), and `try_new_parser_for_media_type` methods of parser factories. Crate's own lookups, like in parsing syntax names with [`FromStr`](std::str::FromStr), are not counted.
+//! - Fallbacks to file extension by [`SyntaxHint::resolve`](crate::syntax_hint::SyntaxHint::resolve), when media type of an http message didn't correspond to a syntax exclusively.
+//! - Sniff resolutions: syntaxes resolved from content of documents by [`sniff_syntax`](crate::syntax_hint::sniff_syntax), including through [`SyntaxHint::resolve_or_sniff`](crate::syntax_hint::SyntaxHint::resolve_or_sniff), when no other hint resolved.
+//! - Sniff disagreements: dynsyn parsers sniff start of each input, and count inputs that look like gzip streams, or html pages, though they were declared to be in an rdf syntax.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{metrics, syntax_hint::SyntaxHint};
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let before = metrics::snapshot();
+//! SyntaxHint::from_http(Some("text/plain"), Some("/alice.ttl"))?.resolve()?;
+//! let after = metrics::snapshot();
+//! assert!(after.fallbacks > before.fallbacks);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Process wide counters.
+struct Counters {
+    media_type_hits: AtomicU64,
+    media_type_misses: AtomicU64,
+    file_extension_hits: AtomicU64,
+    file_extension_misses: AtomicU64,
+    fallbacks: AtomicU64,
+    sniff_resolutions: AtomicU64,
+    sniff_disagreements: AtomicU64,
+}
+
+static COUNTERS: Counters = Counters {
+    media_type_hits: AtomicU64::new(0),
+    media_type_misses: AtomicU64::new(0),
+    file_extension_hits: AtomicU64::new(0),
+    file_extension_misses: AtomicU64::new(0),
+    fallbacks: AtomicU64::new(0),
+    sniff_resolutions: AtomicU64::new(0),
+    sniff_disagreements: AtomicU64::new(0),
+};
+
+/// A point in time snapshot of counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    /// Number of media types, that resolved to a syntax.
+    pub media_type_hits: u64,
+    /// Number of media types, that didn't correspond to any syntax.
+    pub media_type_misses: u64,
+    /// Number of file extensions, that resolved to a syntax.
+    pub file_extension_hits: u64,
+    /// Number of file extensions, that didn't correspond to any syntax.
+    pub file_extension_misses: u64,
+    /// Number of times, syntax of an http message was resolved from it's file extension, because it's media type didn't correspond to a syntax exclusively.
+    pub fallbacks: u64,
+    /// Number of times, syntax of a document was resolved from it's content.
+    pub sniff_resolutions: u64,
+    /// Number of parsed inputs, that looked like gzip streams, or html pages, instead of documents in declared syntax.
+    pub sniff_disagreements: u64,
+}
+
+/// Get current values of counters.
+pub fn snapshot() -> MetricsSnapshot {
+    MetricsSnapshot {
+        media_type_hits: COUNTERS.media_type_hits.load(Ordering::Relaxed),
+        media_type_misses: COUNTERS.media_type_misses.load(Ordering::Relaxed),
+        file_extension_hits: COUNTERS.file_extension_hits.load(Ordering::Relaxed),
+        file_extension_misses: COUNTERS.file_extension_misses.load(Ordering::Relaxed),
+        fallbacks: COUNTERS.fallbacks.load(Ordering::Relaxed),
+        sniff_resolutions: COUNTERS.sniff_resolutions.load(Ordering::Relaxed),
+        sniff_disagreements: COUNTERS.sniff_disagreements.load(Ordering::Relaxed),
+    }
+}
+
+/// Reset all counters to zero, like at start of a reporting interval.
+pub fn reset() {
+    for counter in [
+        &COUNTERS.media_type_hits,
+        &COUNTERS.media_type_misses,
+        &COUNTERS.file_extension_hits,
+        &COUNTERS.file_extension_misses,
+        &COUNTERS.fallbacks,
+        &COUNTERS.sniff_resolutions,
+        &COUNTERS.sniff_disagreements,
+    ] {
+        counter.store(0, Ordering::Relaxed);
+    }
+}
+
+pub(crate) fn record_media_type_lookup(is_hit: bool) {
+    let counter = if is_hit {
+        &COUNTERS.media_type_hits
+    } else {
+        &COUNTERS.media_type_misses
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn record_file_extension_lookup(is_hit: bool) {
+    let counter = if is_hit {
+        &COUNTERS.file_extension_hits
+    } else {
+        &COUNTERS.file_extension_misses
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn record_fallback() {
+    COUNTERS.fallbacks.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn record_sniff_resolution() {
+    COUNTERS.sniff_resolutions.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn record_sniff_disagreement() {
+    COUNTERS.sniff_disagreements.fetch_add(1, Ordering::Relaxed);
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use sophia_api::{parser::TripleParser, triple::stream::TripleSource};
+    use sophia_term::BoxTerm;
+
+    use super::snapshot;
+    use crate::{
+        correspondence::Correspondent,
+        file_extension::FileExtension,
+        media_type,
+        parser::triples::DynSynTripleParserFactory,
+        syntax::{self, RdfSyntax},
+        syntax_hint::SyntaxHint,
+        tests::TRACING,
+    };
+
+    // Counters are process wide, and other tests run concurrently. Hence only lower bounds of increments are asserted.
+
+    #[test]
+    pub fn counts_correspondence_lookups() {
+        Lazy::force(&TRACING);
+        let before = snapshot();
+        assert!(Correspondent::<RdfSyntax>::try_from(&*media_type::TEXT_TURTLE).is_ok());
+        assert!(Correspondent::<RdfSyntax>::try_from(&mime::IMAGE_PNG).is_err());
+        assert!(Correspondent::<RdfSyntax>::try_from(&FileExtension::from("nt")).is_ok());
+        assert!(Correspondent::<RdfSyntax>::try_from(&FileExtension::from("png")).is_err());
+        let after = snapshot();
+        assert!(after.media_type_hits > before.media_type_hits);
+        assert!(after.media_type_misses > before.media_type_misses);
+        assert!(after.file_extension_hits > before.file_extension_hits);
+        assert!(after.file_extension_misses > before.file_extension_misses);
+    }
+
+    #[test]
+    pub fn counts_fallbacks_to_file_extension() {
+        Lazy::force(&TRACING);
+        let before = snapshot();
+        let hint = SyntaxHint::from_http(Some("text/html"), Some("/alice.ttl")).unwrap();
+        assert_eq!(hint.resolve().unwrap().value, syntax::TURTLE);
+        assert!(snapshot().fallbacks > before.fallbacks);
+    }
+
+    #[test]
+    pub fn counts_sniff_resolutions() {
+        Lazy::force(&TRACING);
+        let before = snapshot();
+        let syntax_ = SyntaxHint::Unknown
+            .resolve_or_sniff(Some(&b"@prefix ex: <http://ex.org/> ."[..]))
+            .unwrap();
+        assert_eq!(syntax_, syntax::TURTLE);
+        assert!(snapshot().sniff_resolutions > before.sniff_resolutions);
+    }
+
+    #[test]
+    pub fn counts_sniff_disagreements() {
+        Lazy::force(&TRACING);
+        let before = snapshot();
+        let parser = DynSynTripleParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)
+            .unwrap();
+        assert!(parser
+            .parse_str("<!DOCTYPE html><html></html>")
+            .for_each_triple(|_| {})
+            .is_err());
+        assert!(snapshot().sniff_disagreements > before.sniff_disagreements);
+    }
+}
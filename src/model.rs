@@ -0,0 +1,295 @@
+//! This module defines owned statement types, [`OwnedTriple`], and [`OwnedQuad`], that own their terms as [`BoxTerm`]s, or, for quads, as terms of any [`CopyTerm`] type. Statements streamed by sources borrow from the source, and are valid only for a callback. Owned statements can be buffered, sorted, deduplicated, and sent across threads, without fighting lifetimes.
+//!
+//! Owned statements implement sophia's [`Triple`], and [`Quad`] traits, and can be created from any statement implementing them. Hence collections of them can be serialized back with any serializer.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{model::OwnedTriple, parser::triples::DynSynTripleParserFactory, syntax};
+//! use sophia_api::{parser::TripleParser, term::TTerm, triple::{stream::TripleSource, Triple}};
+//! use sophia_term::BoxTerm;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let doc = r#"
+//!     <http://ex.org/b> <http://ex.org/p> "2" .
+//!     <http://ex.org/a> <http://ex.org/p> "1" .
+//! "#;
+//! let parser = DynSynTripleParserFactory::default()
+//!     .try_new_parser::<BoxTerm>(syntax::N_TRIPLES, None, None)?;
+//!
+//! let mut triples = Vec::new();
+//! parser
+//!     .parse_str(doc)
+//!     .for_each_triple(|t| triples.push(OwnedTriple::from_triple(&t)))?;
+//! triples.sort();
+//!
+//! let handle = std::thread::spawn(move || triples[0].s().value().to_string());
+//! assert_eq!(handle.join().unwrap(), "http://ex.org/a");
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::cmp::Ordering;
+
+use sophia_api::{
+    quad::Quad,
+    term::{CopyTerm, TTerm, TermKind},
+    triple::Triple,
+};
+use sophia_term::BoxTerm;
+
+/// A triple, that owns it's terms.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OwnedTriple {
+    /// subject of the triple
+    pub s: BoxTerm,
+    /// predicate of the triple
+    pub p: BoxTerm,
+    /// object of the triple
+    pub o: BoxTerm,
+}
+
+impl OwnedTriple {
+    /// Create an owned copy of given triple.
+    pub fn from_triple<T: Triple + ?Sized>(t: &T) -> Self {
+        Self {
+            s: BoxTerm::copy(t.s()),
+            p: BoxTerm::copy(t.p()),
+            o: BoxTerm::copy(t.o()),
+        }
+    }
+
+    /// Create an owned quad, with this triple in graph named `g`, or in default graph if it is `None`.
+    pub fn in_graph(self, g: Option<BoxTerm>) -> OwnedQuad {
+        OwnedQuad {
+            s: self.s,
+            p: self.p,
+            o: self.o,
+            g,
+        }
+    }
+}
+
+impl Triple for OwnedTriple {
+    type Term = BoxTerm;
+
+    fn s(&self) -> &Self::Term {
+        &self.s
+    }
+
+    fn p(&self) -> &Self::Term {
+        &self.p
+    }
+
+    fn o(&self) -> &Self::Term {
+        &self.o
+    }
+}
+
+impl From<[BoxTerm; 3]> for OwnedTriple {
+    fn from([s, p, o]: [BoxTerm; 3]) -> Self {
+        Self { s, p, o }
+    }
+}
+
+impl From<OwnedTriple> for [BoxTerm; 3] {
+    fn from(t: OwnedTriple) -> Self {
+        [t.s, t.p, t.o]
+    }
+}
+
+/// Orders triples by their subjects, predicates, and objects, in that order. See [`cmp_terms`] for order of terms.
+impl Ord for OwnedTriple {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_terms(&self.s, &other.s)
+            .then_with(|| cmp_terms(&self.p, &other.p))
+            .then_with(|| cmp_terms(&self.o, &other.o))
+    }
+}
+
+impl PartialOrd for OwnedTriple {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A quad, that owns it's terms. Terms are [`BoxTerm`]s by default, but quads of a [`DynSynQuadSource`](crate::parser::quads::source::DynSynQuadSource) own terms of source's term type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OwnedQuad<T = BoxTerm> {
+    /// subject of the quad
+    pub s: T,
+    /// predicate of the quad
+    pub p: T,
+    /// object of the quad
+    pub o: T,
+    /// graph name of the quad. It is `None` for quads in default graph.
+    pub g: Option<T>,
+}
+
+impl<T: CopyTerm + TTerm> OwnedQuad<T> {
+    /// Create an owned copy of given quad.
+    pub fn from_quad<Q: Quad + ?Sized>(q: &Q) -> Self {
+        Self {
+            s: T::copy(q.s()),
+            p: T::copy(q.p()),
+            o: T::copy(q.o()),
+            g: q.g().map(T::copy),
+        }
+    }
+}
+
+impl OwnedQuad {
+    /// Get triple of this quad, dropping it's graph name.
+    pub fn into_triple(self) -> OwnedTriple {
+        OwnedTriple {
+            s: self.s,
+            p: self.p,
+            o: self.o,
+        }
+    }
+}
+
+impl<T: TTerm> Quad for OwnedQuad<T> {
+    type Term = T;
+
+    fn s(&self) -> &Self::Term {
+        &self.s
+    }
+
+    fn p(&self) -> &Self::Term {
+        &self.p
+    }
+
+    fn o(&self) -> &Self::Term {
+        &self.o
+    }
+
+    fn g(&self) -> Option<&Self::Term> {
+        self.g.as_ref()
+    }
+}
+
+impl<T> From<([T; 3], Option<T>)> for OwnedQuad<T> {
+    fn from(([s, p, o], g): ([T; 3], Option<T>)) -> Self {
+        Self { s, p, o, g }
+    }
+}
+
+impl<T> From<OwnedQuad<T>> for ([T; 3], Option<T>) {
+    fn from(q: OwnedQuad<T>) -> Self {
+        ([q.s, q.p, q.o], q.g)
+    }
+}
+
+/// Orders quads by their graph names, with default graph first, and then by their subjects, predicates, and objects. Hence quads of a graph are contiguous, when sorted.
+impl<T: TTerm + Eq> Ord for OwnedQuad<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (&self.g, &other.g) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Less,
+            (Some(_), None) => Ordering::Greater,
+            (Some(g1), Some(g2)) => cmp_terms(g1, g2),
+        }
+        .then_with(|| cmp_terms(&self.s, &other.s))
+        .then_with(|| cmp_terms(&self.p, &other.p))
+        .then_with(|| cmp_terms(&self.o, &other.o))
+    }
+}
+
+impl<T: TTerm + Eq> PartialOrd for OwnedQuad<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compare given terms. Iris come first, then blank nodes, then literals, and then variables. Terms of same kind are ordered by their values, and literals are further ordered by their datatypes, and language tags.
+pub fn cmp_terms<T, U>(t1: &T, t2: &U) -> Ordering
+where
+    T: TTerm + ?Sized,
+    U: TTerm + ?Sized,
+{
+    let rank = |kind| match kind {
+        TermKind::Iri => 0,
+        TermKind::BlankNode => 1,
+        TermKind::Literal => 2,
+        TermKind::Variable => 3,
+    };
+    rank(t1.kind())
+        .cmp(&rank(t2.kind()))
+        .then_with(|| t1.value().cmp(&t2.value()))
+        .then_with(|| {
+            let d1 = t1.datatype().map(|d| d.value().to_string());
+            d1.cmp(&t2.datatype().map(|d| d.value().to_string()))
+        })
+        .then_with(|| t1.language().cmp(&t2.language()))
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use once_cell::sync::Lazy;
+    use sophia_api::{quad::Quad, triple::Triple};
+    use sophia_term::BoxTerm;
+
+    use super::{OwnedQuad, OwnedTriple};
+    use crate::tests::TRACING;
+
+    fn iri(v: &str) -> BoxTerm {
+        BoxTerm::new_iri(v).unwrap()
+    }
+
+    #[test]
+    pub fn converts_and_orders_triples() {
+        Lazy::force(&TRACING);
+        let streamed = [
+            iri("http://ex.org/b"),
+            iri("http://ex.org/p"),
+            iri("http://ex.org/o"),
+        ];
+        let b = OwnedTriple::from_triple(&streamed);
+        let a = OwnedTriple::from([
+            iri("http://ex.org/a"),
+            iri("http://ex.org/p"),
+            BoxTerm::new_literal_lang("o", "en").unwrap(),
+        ]);
+        let bnode = OwnedTriple::from([
+            BoxTerm::new_bnode("x").unwrap(),
+            iri("http://ex.org/p"),
+            iri("http://ex.org/o"),
+        ]);
+
+        let mut triples = vec![bnode.clone(), b.clone(), a.clone(), b.clone()];
+        triples.sort();
+        triples.dedup();
+        assert_eq!(triples, vec![a, b.clone(), bnode]);
+        assert_eq!(<[BoxTerm; 3]>::from(b.clone()), streamed);
+        assert_eq!(b.s(), &streamed[0]);
+    }
+
+    #[test]
+    pub fn converts_and_orders_quads() {
+        Lazy::force(&TRACING);
+        let t = OwnedTriple::from([
+            iri("http://ex.org/s"),
+            iri("http://ex.org/p"),
+            iri("http://ex.org/o"),
+        ]);
+        let in_g = t.clone().in_graph(Some(iri("http://ex.org/g")));
+        let in_default = t.clone().in_graph(None);
+        assert_eq!(in_g.g(), Some(&iri("http://ex.org/g")));
+        assert_eq!(OwnedQuad::from_quad(&in_g), in_g);
+
+        let mut quads = vec![in_g.clone(), in_default.clone()];
+        quads.sort();
+        assert_eq!(quads, vec![in_default.clone(), in_g.clone()]);
+        assert_eq!(in_g.into_triple(), t);
+        let distinct: HashSet<_> = [in_default.clone(), in_default].into_iter().collect();
+        assert_eq!(distinct.len(), 1);
+    }
+}
@@ -1,12 +1,164 @@
 //! This module defines sum-types over errors that arise from underlying parsers
 
+use std::{error::Error, io};
+
+use rio_api::parser::{LineBytePosition, ParseError};
 use rio_turtle::TurtleError;
+#[cfg(feature = "rdf-xml")]
 use rio_xml::RdfXmlError;
 
+#[cfg(feature = "hdt")]
+use crate::parser::hdt::HdtError;
+#[cfg(feature = "rdfa")]
+use crate::parser::rdfa::RdfaError;
+#[cfg(feature = "trix")]
+use crate::parser::trix::TrixError;
+
+use crate::parser::{
+    iri_policy::IriPolicyViolation,
+    limits::{BudgetResource, DocumentLimit},
+    literal_policy::LiteralPolicyViolation,
+    rdf_star::RdfStarError,
+};
+
+use super::source::InputSniff;
+
 /// This is a sum-type that wraps around different rdf-syntax-parse-errors, that arise from different sophia parsers.
+///
+/// It also has variants that wrap those errors with a diagnosis of common failure shapes of inputs.
 #[derive(Debug, thiserror::Error)]
-#[error(transparent)]
 pub enum InnerParseError {
+    #[error(transparent)]
     Turtle(#[from] TurtleError),
+    #[cfg(feature = "rdf-xml")]
+    #[error(transparent)]
     RdfXml(#[from] RdfXmlError),
+    #[cfg(feature = "rdfa")]
+    #[error(transparent)]
+    Rdfa(#[from] RdfaError),
+    #[cfg(feature = "trix")]
+    #[error(transparent)]
+    Trix(#[from] TrixError),
+    #[cfg(feature = "hdt")]
+    #[error(transparent)]
+    Hdt(#[from] HdtError),
+    #[error(transparent)]
+    RdfStar(RdfStarError),
+    #[error("input appears to be gzip-compressed")]
+    GzipCompressed(#[source] Box<InnerParseError>),
+    #[error("input looks like an HTML error page")]
+    HtmlPage(#[source] Box<InnerParseError>),
+    #[error("input appears to be truncated mid-statement: {0}")]
+    Truncated(#[source] Box<InnerParseError>),
+    #[error("document exceeds limit of {0} distinct named graphs")]
+    TooManyGraphs(usize),
+    #[error("session budget of {0} is exhausted")]
+    BudgetExhausted(BudgetResource),
+    #[error("document exceeds limit of {0}")]
+    DocumentLimitExceeded(DocumentLimit),
+    #[error("statement violates iri policy: {0}")]
+    IriPolicyViolation(IriPolicyViolation),
+    #[error("statement violates literal policy: {0}")]
+    LiteralPolicyViolation(LiteralPolicyViolation),
+    #[cfg_attr(not(feature = "parallel"), allow(dead_code))]
+    #[error("{error} (lines counted from line {first_line} of document)")]
+    InChunk {
+        first_line: u64,
+        #[source]
+        error: Box<InnerParseError>,
+    },
+}
+
+impl InnerParseError {
+    /// Wraps this error with diagnosis of the failure shape, using given sniff of the input. Io errors, limit errors, and policy errors are never re-diagnosed.
+    pub fn diagnosed(self, input_sniff: InputSniff) -> Self {
+        if self.is_io()
+            || matches!(
+                self,
+                Self::TooManyGraphs(_)
+                    | Self::BudgetExhausted(_)
+                    | Self::DocumentLimitExceeded(_)
+                    | Self::IriPolicyViolation(_)
+                    | Self::LiteralPolicyViolation(_)
+                    | Self::InChunk { .. }
+            )
+        {
+            return self;
+        }
+        match input_sniff {
+            InputSniff::Gzip => return Self::GzipCompressed(Box::new(self)),
+            InputSniff::Html => return Self::HtmlPage(Box::new(self)),
+            InputSniff::Unremarkable => {}
+        }
+        match &self {
+            // rio doesn't expose kind of turtle errors.
+            Self::Turtle(e) if e.to_string().starts_with("premature end of file") => {
+                Self::Truncated(Box::new(self))
+            }
+            #[cfg(feature = "trix")]
+            Self::Trix(TrixError::UnexpectedEof(_)) => Self::Truncated(Box::new(self)),
+            #[cfg(feature = "hdt")]
+            Self::Hdt(HdtError::UnexpectedEof(_)) => Self::Truncated(Box::new(self)),
+            _ => self,
+        }
+    }
+
+    /// Returns position of the error in source document, if known.
+    pub fn textual_position(&self) -> Option<LineBytePosition> {
+        match self {
+            Self::Turtle(e) => e.textual_position(),
+            #[cfg(feature = "rdf-xml")]
+            Self::RdfXml(e) => e.textual_position(),
+            #[cfg(feature = "rdfa")]
+            Self::Rdfa(e) => e.textual_position(),
+            #[cfg(feature = "trix")]
+            Self::Trix(e) => e.textual_position(),
+            #[cfg(feature = "hdt")]
+            Self::Hdt(e) => e.textual_position(),
+            Self::RdfStar(e) => e.textual_position(),
+            Self::GzipCompressed(e) | Self::HtmlPage(e) | Self::Truncated(e) => {
+                e.textual_position()
+            }
+            Self::InChunk { first_line, error } => error
+                .textual_position()
+                .map(|p| LineBytePosition::new(p.line_number() + first_line - 1, p.byte_number())),
+            Self::TooManyGraphs(_)
+            | Self::BudgetExhausted(_)
+            | Self::DocumentLimitExceeded(_)
+            | Self::IriPolicyViolation(_)
+            | Self::LiteralPolicyViolation(_) => None,
+        }
+    }
+
+    /// Returns this error, without offset of the chunk it arose in, if any.
+    pub fn unchunked(&self) -> &Self {
+        match self {
+            Self::InChunk { error, .. } => error.unchunked(),
+            _ => self,
+        }
+    }
+
+    /// Returns true if this error is caused by an io error from underlying reader, rather than by malformed input.
+    pub fn is_io(&self) -> bool {
+        let mut source = match self {
+            Self::Turtle(e) => e.source(),
+            #[cfg(feature = "rdf-xml")]
+            Self::RdfXml(e) => e.source(),
+            #[cfg(feature = "rdfa")]
+            Self::Rdfa(e) => return matches!(e, RdfaError::Io(_)),
+            #[cfg(feature = "trix")]
+            Self::Trix(e) => return matches!(e, TrixError::Io(_)),
+            #[cfg(feature = "hdt")]
+            Self::Hdt(e) => return matches!(e, HdtError::Io(_)),
+            Self::InChunk { error, .. } => return error.is_io(),
+            _ => return false,
+        };
+        while let Some(e) = source {
+            if e.is::<io::Error>() {
+                return true;
+            }
+            source = e.source();
+        }
+        false
+    }
 }
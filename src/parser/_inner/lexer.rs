@@ -0,0 +1,203 @@
+//! This module provides a lexer of turtle-family documents. It recognizes just enough of their grammar to track nesting, and directives of documents, as underlying parsers read them.
+
+use std::io;
+
+use crate::{
+    parser::{limits::NestingTracker, prefixes::PrefixTracker},
+    syntax::{self, RdfSyntax},
+};
+
+/// Returns true, if documents in given syntax are of turtle-family, that [`TurtleLexer`] can lex.
+pub fn is_turtle_family(syntax_: RdfSyntax) -> bool {
+    syntax_ == syntax::TURTLE || syntax_ == syntax::TRIG || syntax_ == syntax::N3
+}
+
+/// A lexeme of a turtle-family document, that trackers observe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lexeme<'a> {
+    /// `[`, or `(`.
+    Open,
+    /// `]`, or `)`.
+    Close,
+    /// A run of bytes, that are not delimiters, like a keyword, or a prefixed name.
+    Word(&'a [u8]),
+    /// An iri reference, without it's angle brackets.
+    Iri(&'a [u8]),
+}
+
+/// State of a [`TurtleLexer`].
+#[derive(Debug, Clone, Copy)]
+enum State {
+    Normal,
+    /// After an escaping backslash in a word.
+    Escape,
+    Iri,
+    Comment,
+    /// After `n` consecutive quotes in normal state.
+    Quotes {
+        quote: u8,
+        n: u8,
+    },
+    /// In a string, with `n` consecutive quotes seen just before.
+    Str {
+        quote: u8,
+        long: bool,
+        escape: bool,
+        n: u8,
+    },
+}
+
+/// A lexer of turtle-family documents. It skips strings, and comments, so that brackets, and keywords in them are not observed.
+#[derive(Debug)]
+pub struct TurtleLexer {
+    state: State,
+    token: Vec<u8>,
+}
+
+impl TurtleLexer {
+    pub fn new() -> Self {
+        Self {
+            state: State::Normal,
+            token: Vec::new(),
+        }
+    }
+
+    /// Feeds given bytes to the lexer, calling `on_lexeme` for each complete lexeme in them. Lexemes can span multiple feeds.
+    pub fn feed<F>(&mut self, bytes: &[u8], on_lexeme: &mut F) -> io::Result<()>
+    where
+        F: FnMut(Lexeme<'_>) -> io::Result<()>,
+    {
+        for &b in bytes {
+            self.state = match self.state {
+                State::Quotes { quote, n } if b == quote && n < 3 => {
+                    State::Quotes { quote, n: n + 1 }
+                }
+                State::Quotes { quote, n: 3 } => Self::in_string(quote, true, 0, b),
+                State::Quotes { quote, n: 1 } => Self::in_string(quote, false, 0, b),
+                // An empty string.
+                State::Quotes { .. } => self.in_normal(b, on_lexeme)?,
+                State::Str {
+                    escape: true,
+                    quote,
+                    long,
+                    ..
+                } => State::Str {
+                    quote,
+                    long,
+                    escape: false,
+                    n: 0,
+                },
+                State::Str { quote, long, n, .. } => Self::in_string(quote, long, n, b),
+                State::Iri if b == b'>' => {
+                    on_lexeme(Lexeme::Iri(&self.token))?;
+                    self.token.clear();
+                    State::Normal
+                }
+                State::Iri => {
+                    self.token.push(b);
+                    State::Iri
+                }
+                State::Comment if b == b'\n' || b == b'\r' => State::Normal,
+                State::Comment => State::Comment,
+                State::Escape => {
+                    self.token.push(b);
+                    State::Normal
+                }
+                State::Normal => self.in_normal(b, on_lexeme)?,
+            };
+        }
+        Ok(())
+    }
+
+    fn in_normal<F>(&mut self, b: u8, on_lexeme: &mut F) -> io::Result<State>
+    where
+        F: FnMut(Lexeme<'_>) -> io::Result<()>,
+    {
+        let is_delimiter = b.is_ascii_whitespace()
+            || matches!(
+                b,
+                b'<' | b'#' | b'"' | b'\'' | b'[' | b']' | b'(' | b')' | b'{' | b'}' | b';' | b','
+            );
+        if !is_delimiter {
+            self.token.push(b);
+            return Ok(if b == b'\\' {
+                State::Escape
+            } else {
+                State::Normal
+            });
+        }
+        if !self.token.is_empty() {
+            on_lexeme(Lexeme::Word(&self.token))?;
+            self.token.clear();
+        }
+        Ok(match b {
+            b'<' => State::Iri,
+            b'#' => State::Comment,
+            b'"' | b'\'' => State::Quotes { quote: b, n: 1 },
+            b'[' | b'(' => {
+                on_lexeme(Lexeme::Open)?;
+                State::Normal
+            }
+            b']' | b')' => {
+                on_lexeme(Lexeme::Close)?;
+                State::Normal
+            }
+            _ => State::Normal,
+        })
+    }
+
+    fn in_string(quote: u8, long: bool, n: u8, b: u8) -> State {
+        match b {
+            b'\\' => State::Str {
+                quote,
+                long,
+                escape: true,
+                n: 0,
+            },
+            b if b == quote && (!long || n == 2) => State::Normal,
+            b if b == quote => State::Str {
+                quote,
+                long,
+                escape: false,
+                n: n + 1,
+            },
+            _ => State::Str {
+                quote,
+                long,
+                escape: false,
+                n: 0,
+            },
+        }
+    }
+}
+
+/// A tracker of a turtle-family document, that feeds it's bytes to a [`TurtleLexer`], and lets configured trackers observe lexemes.
+#[derive(Debug)]
+pub struct DocumentTracker {
+    lexer: TurtleLexer,
+    nesting_tracker: Option<NestingTracker>,
+    prefix_tracker: PrefixTracker,
+}
+
+impl DocumentTracker {
+    pub fn new(nesting_tracker: Option<NestingTracker>, prefix_tracker: PrefixTracker) -> Self {
+        Self {
+            lexer: TurtleLexer::new(),
+            nesting_tracker,
+            prefix_tracker,
+        }
+    }
+
+    /// Tracks given consumed bytes. Returns an error, if they exceed a limit.
+    pub fn track(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let nesting_tracker = &mut self.nesting_tracker;
+        let prefix_tracker = &mut self.prefix_tracker;
+        self.lexer.feed(bytes, &mut |lexeme| {
+            prefix_tracker.observe(lexeme);
+            match nesting_tracker.as_mut() {
+                Some(tracker) => tracker.observe(lexeme),
+                None => Ok(()),
+            }
+        })
+    }
+}
@@ -1,14 +1,24 @@
 use sophia_turtle::parser::{
-    nq::NQuadsParser, nt::NTriplesParser, trig::TriGParser, turtle::TurtleParser,
+    gtrig::GTriGParser, nq::NQuadsParser, nt::NTriplesParser, trig::TriGParser,
+    turtle::TurtleParser,
 };
+#[cfg(feature = "rdf-xml")]
 use sophia_xml::parser::RdfXmlParser;
 
+#[cfg(feature = "hdt")]
+use crate::parser::hdt::HdtParser;
+#[cfg(feature = "rdfa")]
+use crate::parser::rdfa::{HostLanguage, RdfaParser};
+#[cfg(feature = "trix")]
+use crate::parser::trix::TrixParser;
 use crate::syntax::{self, RdfSyntax, UnKnownSyntaxError};
 
 pub mod source;
 
 pub mod errors;
 
+pub mod lexer;
+
 /// This is a sum-type that wraps around different rdf-syntax-parsers from sophia.
 #[derive(Debug)]
 pub enum InnerParser {
@@ -16,7 +26,19 @@ pub enum InnerParser {
     TriG(TriGParser),
     NTriples(NTriplesParser),
     Turtle(TurtleParser),
+    #[cfg(feature = "rdf-xml")]
     RdfXml(RdfXmlParser),
+    /// N3 documents are parsed with a turtle parser, hence only their turtle-compatible subset is supported.
+    #[cfg(feature = "n3")]
+    N3(TurtleParser),
+    #[cfg(feature = "rdfa")]
+    Rdfa(RdfaParser),
+    #[cfg(feature = "trix")]
+    Trix(TrixParser),
+    #[cfg(feature = "hdt")]
+    Hdt(HdtParser),
+    /// Documents of turtle family syntaxes are parsed with a generalized trig parser in generalized mode. It records syntax of the documents.
+    Generalized(GTriGParser, RdfSyntax),
 }
 
 impl From<NQuadsParser> for InnerParser {
@@ -43,12 +65,34 @@ impl From<TurtleParser> for InnerParser {
     }
 }
 
+#[cfg(feature = "rdf-xml")]
 impl From<RdfXmlParser> for InnerParser {
     fn from(p: RdfXmlParser) -> Self {
         Self::RdfXml(p)
     }
 }
 
+#[cfg(feature = "rdfa")]
+impl From<RdfaParser> for InnerParser {
+    fn from(p: RdfaParser) -> Self {
+        Self::Rdfa(p)
+    }
+}
+
+#[cfg(feature = "trix")]
+impl From<TrixParser> for InnerParser {
+    fn from(p: TrixParser) -> Self {
+        Self::Trix(p)
+    }
+}
+
+#[cfg(feature = "hdt")]
+impl From<HdtParser> for InnerParser {
+    fn from(p: HdtParser) -> Self {
+        Self::Hdt(p)
+    }
+}
+
 impl InnerParser {
     /// Try to create a sum-parser for given syntax.
     ///
@@ -61,10 +105,123 @@ impl InnerParser {
         match syntax_ {
             syntax::N_QUADS => Ok(NQuadsParser {}.into()),
             syntax::N_TRIPLES => Ok(NTriplesParser {}.into()),
+            #[cfg(feature = "rdf-xml")]
             syntax::RDF_XML => Ok(RdfXmlParser { base: base_iri }.into()),
             syntax::TRIG => Ok(TriGParser { base: base_iri }.into()),
             syntax::TURTLE => Ok(TurtleParser { base: base_iri }.into()),
+            #[cfg(feature = "n3")]
+            syntax::N3 => Ok(Self::N3(TurtleParser { base: base_iri })),
+            #[cfg(feature = "rdfa")]
+            syntax::HTML_RDFA => Ok(RdfaParser {
+                base: base_iri,
+                host_language: HostLanguage::Html,
+                ..Default::default()
+            }
+            .into()),
+            #[cfg(feature = "rdfa")]
+            syntax::XHTML_RDFA => Ok(RdfaParser {
+                base: base_iri,
+                host_language: HostLanguage::Xhtml,
+                ..Default::default()
+            }
+            .into()),
+            #[cfg(feature = "trix")]
+            syntax::TRIX => Ok(TrixParser {}.into()),
+            #[cfg(feature = "hdt")]
+            syntax::HDT => Ok(HdtParser {}.into()),
             _ => Err(UnKnownSyntaxError(syntax_)),
         }
     }
+
+    /// Try to create a generalized sum-parser for given syntax. Generalized mode is supported for [`turtle`](syntax::TURTLE), [`trig`](syntax::TRIG), and [`n-triples`](syntax::N_TRIPLES), which are subsets of generalized trig.
+    ///
+    /// #Errors
+    /// throws [`UnKnownSyntaxError`] if syntax is unknown/un-supported in generalized mode.
+    pub fn try_new_generalized(
+        syntax_: RdfSyntax,
+        base_iri: Option<String>,
+    ) -> Result<Self, UnKnownSyntaxError> {
+        match syntax_ {
+            syntax::TURTLE | syntax::TRIG | syntax::N_TRIPLES => {
+                Ok(Self::Generalized(GTriGParser { base: base_iri }, syntax_))
+            }
+            _ => Err(UnKnownSyntaxError(syntax_)),
+        }
+    }
+
+    /// Returns true, if this is a generalized parser.
+    pub fn is_generalized(&self) -> bool {
+        matches!(self, Self::Generalized(..))
+    }
+
+    /// Returns syntax of this parser.
+    pub fn syntax(&self) -> RdfSyntax {
+        match self {
+            Self::NQuads(_) => syntax::N_QUADS,
+            Self::TriG(_) => syntax::TRIG,
+            Self::NTriples(_) => syntax::N_TRIPLES,
+            Self::Turtle(_) => syntax::TURTLE,
+            #[cfg(feature = "rdf-xml")]
+            Self::RdfXml(_) => syntax::RDF_XML,
+            #[cfg(feature = "n3")]
+            Self::N3(_) => syntax::N3,
+            #[cfg(feature = "rdfa")]
+            Self::Rdfa(p) => match p.host_language {
+                HostLanguage::Html => syntax::HTML_RDFA,
+                HostLanguage::Xhtml => syntax::XHTML_RDFA,
+            },
+            #[cfg(feature = "trix")]
+            Self::Trix(_) => syntax::TRIX,
+            #[cfg(feature = "hdt")]
+            Self::Hdt(_) => syntax::HDT,
+            Self::Generalized(_, syntax_) => *syntax_,
+        }
+    }
+
+    /// Returns base iri of this parser. Syntaxes that don't resolve relative iris have no base iri.
+    pub fn base_iri(&self) -> Option<&str> {
+        match self {
+            Self::NQuads(_) | Self::NTriples(_) => None,
+            #[cfg(feature = "rdf-xml")]
+            Self::RdfXml(p) => p.base.as_deref(),
+            Self::TriG(p) => p.base.as_deref(),
+            Self::Turtle(p) => p.base.as_deref(),
+            #[cfg(feature = "n3")]
+            Self::N3(p) => p.base.as_deref(),
+            #[cfg(feature = "rdfa")]
+            Self::Rdfa(p) => p.base.as_deref(),
+            #[cfg(feature = "trix")]
+            Self::Trix(_) => None,
+            #[cfg(feature = "hdt")]
+            Self::Hdt(_) => None,
+            Self::Generalized(p, _) => p.base.as_deref(),
+        }
+    }
+
+    /// Returns a parser of same syntax, but with given base iri. Syntaxes that don't resolve relative iris ignore base iri.
+    pub fn with_base(&self, base_iri: Option<String>) -> Self {
+        match self {
+            Self::NQuads(_) => NQuadsParser {}.into(),
+            Self::NTriples(_) => NTriplesParser {}.into(),
+            #[cfg(feature = "rdf-xml")]
+            Self::RdfXml(_) => RdfXmlParser { base: base_iri }.into(),
+            Self::TriG(_) => TriGParser { base: base_iri }.into(),
+            Self::Turtle(_) => TurtleParser { base: base_iri }.into(),
+            #[cfg(feature = "n3")]
+            Self::N3(_) => Self::N3(TurtleParser { base: base_iri }),
+            #[cfg(feature = "rdfa")]
+            Self::Rdfa(p) => RdfaParser {
+                base: base_iri,
+                ..p.clone()
+            }
+            .into(),
+            #[cfg(feature = "trix")]
+            Self::Trix(p) => (*p).into(),
+            #[cfg(feature = "hdt")]
+            Self::Hdt(p) => (*p).into(),
+            Self::Generalized(_, syntax_) => {
+                Self::Generalized(GTriGParser { base: base_iri }, *syntax_)
+            }
+        }
+    }
 }
@@ -2,43 +2,166 @@
 
 use std::io::BufRead;
 
-use rio_turtle::{NQuadsParser, NTriplesParser, TriGParser, TurtleError, TurtleParser};
+use rio_turtle::{GTriGParser, NQuadsParser, NTriplesParser, TriGParser, TurtleParser};
+#[cfg(feature = "rdf-xml")]
 use rio_xml::{RdfXmlError, RdfXmlParser};
-use sophia_rio::parser::StrictRioSource;
+use sophia_rio::parser::{GeneralizedRioSource, StrictRioSource};
+
+#[cfg(feature = "hdt")]
+use crate::parser::hdt::{HdtError, HdtTriplesParser};
+#[cfg(feature = "rdfa")]
+use crate::parser::rdfa::{RdfaError, RdfaTriplesParser};
+#[cfg(feature = "trix")]
+use crate::parser::trix::{TrixError, TrixQuadsParser};
+use crate::{
+    metrics,
+    parser::rdf_star::{RdfStarAdapter, RdfStarError},
+    syntax::{self, RdfSyntax},
+};
 
 /// This is a sum-type that wraps around different rdf-streaming-sources (currently those, which implements  either [`QuadSource`](sophia_api::quad::stream::QuadSource) or [`TripleSource`](sophia_api::triple::stream::TripleSource) trait), that are normally produced by different sophia parsers.
 pub enum InnerStatementSource<R: BufRead> {
-    FNQuads(StrictRioSource<NQuadsParser<R>, TurtleError>),
-    FTriG(StrictRioSource<TriGParser<R>, TurtleError>),
-    FNTriples(StrictRioSource<NTriplesParser<R>, TurtleError>),
-    FTurtle(StrictRioSource<TurtleParser<R>, TurtleError>),
+    FNQuads(StrictRioSource<RdfStarAdapter<NQuadsParser<R>>, RdfStarError>),
+    FTriG(StrictRioSource<RdfStarAdapter<TriGParser<R>>, RdfStarError>),
+    FNTriples(StrictRioSource<RdfStarAdapter<NTriplesParser<R>>, RdfStarError>),
+    FTurtle(StrictRioSource<RdfStarAdapter<TurtleParser<R>>, RdfStarError>),
+    #[cfg(feature = "rdf-xml")]
     FRdfXml(StrictRioSource<RdfXmlParser<R>, RdfXmlError>),
+    #[cfg(feature = "rdfa")]
+    FRdfa(StrictRioSource<RdfaTriplesParser<R>, RdfaError>),
+    #[cfg(feature = "trix")]
+    FTrix(StrictRioSource<TrixQuadsParser<R>, TrixError>),
+    #[cfg(feature = "hdt")]
+    FHdt(StrictRioSource<HdtTriplesParser<R>, HdtError>),
+    FGTriG(GeneralizedRioSource<RdfStarAdapter<GTriGParser<R>>, RdfStarError>),
 }
 
-impl<R: BufRead> From<StrictRioSource<NQuadsParser<R>, TurtleError>> for InnerStatementSource<R> {
-    fn from(qs: StrictRioSource<NQuadsParser<R>, TurtleError>) -> Self {
+impl<R: BufRead> From<StrictRioSource<RdfStarAdapter<NQuadsParser<R>>, RdfStarError>>
+    for InnerStatementSource<R>
+{
+    fn from(qs: StrictRioSource<RdfStarAdapter<NQuadsParser<R>>, RdfStarError>) -> Self {
         Self::FNQuads(qs)
     }
 }
 
-impl<R: BufRead> From<StrictRioSource<TriGParser<R>, TurtleError>> for InnerStatementSource<R> {
-    fn from(qs: StrictRioSource<TriGParser<R>, TurtleError>) -> Self {
+impl<R: BufRead> From<StrictRioSource<RdfStarAdapter<TriGParser<R>>, RdfStarError>>
+    for InnerStatementSource<R>
+{
+    fn from(qs: StrictRioSource<RdfStarAdapter<TriGParser<R>>, RdfStarError>) -> Self {
         Self::FTriG(qs)
     }
 }
-impl<R: BufRead> From<StrictRioSource<NTriplesParser<R>, TurtleError>> for InnerStatementSource<R> {
-    fn from(ts: StrictRioSource<NTriplesParser<R>, TurtleError>) -> Self {
+impl<R: BufRead> From<StrictRioSource<RdfStarAdapter<NTriplesParser<R>>, RdfStarError>>
+    for InnerStatementSource<R>
+{
+    fn from(ts: StrictRioSource<RdfStarAdapter<NTriplesParser<R>>, RdfStarError>) -> Self {
         Self::FNTriples(ts)
     }
 }
-impl<R: BufRead> From<StrictRioSource<TurtleParser<R>, TurtleError>> for InnerStatementSource<R> {
-    fn from(ts: StrictRioSource<TurtleParser<R>, TurtleError>) -> Self {
+impl<R: BufRead> From<StrictRioSource<RdfStarAdapter<TurtleParser<R>>, RdfStarError>>
+    for InnerStatementSource<R>
+{
+    fn from(ts: StrictRioSource<RdfStarAdapter<TurtleParser<R>>, RdfStarError>) -> Self {
         Self::FTurtle(ts)
     }
 }
 
+impl<R: BufRead> From<GeneralizedRioSource<RdfStarAdapter<GTriGParser<R>>, RdfStarError>>
+    for InnerStatementSource<R>
+{
+    fn from(qs: GeneralizedRioSource<RdfStarAdapter<GTriGParser<R>>, RdfStarError>) -> Self {
+        Self::FGTriG(qs)
+    }
+}
+
+#[cfg(feature = "rdf-xml")]
 impl<R: BufRead> From<StrictRioSource<RdfXmlParser<R>, RdfXmlError>> for InnerStatementSource<R> {
     fn from(ts: StrictRioSource<RdfXmlParser<R>, RdfXmlError>) -> Self {
         Self::FRdfXml(ts)
     }
 }
+
+#[cfg(feature = "rdfa")]
+impl<R: BufRead> From<StrictRioSource<RdfaTriplesParser<R>, RdfaError>> for InnerStatementSource<R> {
+    fn from(ts: StrictRioSource<RdfaTriplesParser<R>, RdfaError>) -> Self {
+        Self::FRdfa(ts)
+    }
+}
+
+#[cfg(feature = "trix")]
+impl<R: BufRead> From<StrictRioSource<TrixQuadsParser<R>, TrixError>> for InnerStatementSource<R> {
+    fn from(qs: StrictRioSource<TrixQuadsParser<R>, TrixError>) -> Self {
+        Self::FTrix(qs)
+    }
+}
+
+#[cfg(feature = "hdt")]
+impl<R: BufRead> From<StrictRioSource<HdtTriplesParser<R>, HdtError>> for InnerStatementSource<R> {
+    fn from(ts: StrictRioSource<HdtTriplesParser<R>, HdtError>) -> Self {
+        Self::FHdt(ts)
+    }
+}
+
+/// Byte order mark in utf-8 encoding.
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Consume leading utf-8 byte order mark, if any, from given reader. Byte order mark is not part of any of supported syntaxes, but is often prepended to documents by editors and upload clients.
+///
+/// Read errors are ignored here, as they will be surfaced to the parser on next read.
+pub fn skip_bom<R: BufRead>(data: &mut R) {
+    if let Ok(buf) = data.fill_buf() {
+        if buf.starts_with(UTF8_BOM) {
+            data.consume(UTF8_BOM.len());
+        }
+    }
+}
+
+/// A sniff of the start of an input, that identifies common shapes of inputs, which are not rdf documents at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputSniff {
+    Unremarkable,
+    /// Input starts with gzip magic bytes.
+    Gzip,
+    /// Input starts with an html doctype or root element.
+    Html,
+}
+
+/// Magic bytes at the start of a gzip stream.
+const GZIP_MAGIC: &[u8] = b"\x1F\x8B";
+
+/// Sniff the start of given reader, without consuming it, for a document in given syntax. Html sniffs are unremarkable for html based syntaxes. Remarkable sniffs are counted as [sniff disagreements](crate::metrics::MetricsSnapshot::sniff_disagreements).
+pub fn sniff_input<R: BufRead>(data: &mut R, syntax_: RdfSyntax) -> InputSniff {
+    let sniff = match sniff(data) {
+        InputSniff::Html if syntax_ == syntax::HTML_RDFA || syntax_ == syntax::XHTML_RDFA => {
+            InputSniff::Unremarkable
+        }
+        sniff => sniff,
+    };
+    if sniff != InputSniff::Unremarkable {
+        metrics::record_sniff_disagreement();
+    }
+    sniff
+}
+
+fn sniff<R: BufRead>(data: &mut R) -> InputSniff {
+    let buf = match data.fill_buf() {
+        Ok(buf) => buf,
+        Err(_) => return InputSniff::Unremarkable,
+    };
+    if buf.starts_with(GZIP_MAGIC) {
+        return InputSniff::Gzip;
+    }
+    let start = buf
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(buf.len());
+    let head = &buf[start..buf.len().min(start + 14)];
+    if starts_with_ignore_case(head, b"<!doctype html") || starts_with_ignore_case(head, b"<html") {
+        return InputSniff::Html;
+    }
+    InputSniff::Unremarkable
+}
+
+fn starts_with_ignore_case(buf: &[u8], prefix: &[u8]) -> bool {
+    buf.len() >= prefix.len() && buf[..prefix.len()].eq_ignore_ascii_case(prefix)
+}
@@ -0,0 +1,439 @@
+//! This module provides [`DynSynAsyncQuadParser`], and [`DynSynAsyncTripleParser`], that parse documents from tokio's [`AsyncBufRead`] readers, and expose parsed statements as a [`Stream`]. Web services can parse request bodies with them, without spawning blocking tasks. It is available only when `async` feature is enabled.
+//!
+//! Underlying parsers pull from blocking readers. Hence input is read asynchronously into memory in full, before it is parsed from there. Memory use grows with size of input. If parser is configured with a [`SessionBudget`](super::limits::SessionBudget) with a byte limit, it is enforced while input is read, and stream fails with a limit error, as soon as buffered input would exceed remaining bytes of the budget. Untrusted inputs should be bounded that way, or by limiting request body size.
+//!
+//! Parsing never blocks on io, but it is done on the task, that polls the stream, a few statements per poll. It is not moved to a blocking thread, so that streams don't need a tokio runtime with blocking pool. Services, that parse large documents, and can't afford cpu-bound work on async workers, should parse with sync parsers in `tokio::task::spawn_blocking` instead.
+//!
+//! Example:
+//!
+//! ```
+//! use futures_core::Stream;
+//! use rdf_dynsyn::{
+//!     parser::{async_parser::DynSynAsyncQuadParser, quads::DynSynQuadParserFactory},
+//!     syntax,
+//! };
+//! use sophia_term::BoxTerm;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let parser: DynSynAsyncQuadParser<BoxTerm> = DynSynQuadParserFactory::default()
+//!     .try_new_parser(syntax::TRIG, None, None)?
+//!     .into();
+//! let doc = "<http://e/g> { <http://e/a> <http://e/p> <http://e/b> . }";
+//!
+//! let mut stream = parser.parse(doc.as_bytes());
+//! let count = tokio::runtime::Builder::new_current_thread()
+//!     .build()?
+//!     .block_on(async move {
+//!         let mut count = 0;
+//!         while let Some(quad) =
+//!             std::future::poll_fn(|cx| std::pin::Pin::new(&mut stream).poll_next(cx)).await
+//!         {
+//!             quad?;
+//!             count += 1;
+//!         }
+//!         Ok::<_, Box<dyn std::error::Error>>(count)
+//!     })?;
+//! assert_eq!(count, 1);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::{
+    collections::VecDeque,
+    convert::Infallible,
+    io::{self, Cursor},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+use rio_turtle::TurtleError;
+use sophia_api::{
+    parser::{QuadParser, TripleParser},
+    quad::{stream::QuadSource, Quad},
+    term::{CopyTerm, TTerm},
+    triple::{
+        stream::{StreamError, TripleSource},
+        Triple,
+    },
+};
+use tokio::io::AsyncBufRead;
+
+use super::{
+    errors::DynSynParseError,
+    quads::{source::TupleQuad, DynSynQuadParser},
+    triples::{source::SliceTriple, DynSynTripleParser},
+};
+use crate::{parser::limits::SessionBudget, syntax::RdfSyntax};
+
+/// State of reading input, and then parsing it.
+enum State<R, S> {
+    Reading(R, Vec<u8>),
+    Parsing(S),
+    Done,
+}
+
+impl<R: AsyncBufRead + Unpin, S> State<R, S> {
+    /// Poll for statement source over read input. Reads input, until it's end is reached, and then starts parsing it with `parse`. Returns `None` once done. Fails with a limit error, if read input exceeds byte limit of given `budget`.
+    fn poll_source(
+        &mut self,
+        cx: &mut Context<'_>,
+        budget: Option<&SessionBudget>,
+        parse: impl FnOnce(Cursor<Vec<u8>>) -> S,
+    ) -> Poll<Result<Option<&mut S>, DynSynParseError>> {
+        if let Self::Reading(reader, buf) = self {
+            loop {
+                let chunk = match Pin::new(&mut *reader).poll_fill_buf(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(Ok(chunk)) => chunk,
+                    Poll::Ready(Err(e)) => {
+                        *self = Self::Done;
+                        return Poll::Ready(Err(io_error(e)));
+                    }
+                };
+                if chunk.is_empty() {
+                    break;
+                }
+                let len = chunk.len();
+                let exhausted = budget.and_then(|b| b.exhausted_with_bytes(buf.len() + len));
+                if let Some(resource) = exhausted {
+                    *self = Self::Done;
+                    return Poll::Ready(Err(DynSynParseError::budget_exhausted(resource)));
+                }
+                buf.extend_from_slice(chunk);
+                Pin::new(&mut *reader).consume(len);
+            }
+            let data = std::mem::take(buf);
+            *self = Self::Parsing(parse(Cursor::new(data)));
+        }
+        Poll::Ready(Ok(match self {
+            Self::Parsing(source) => Some(source),
+            _ => None,
+        }))
+    }
+}
+
+fn io_error(e: io::Error) -> DynSynParseError {
+    TurtleError::from(e).into()
+}
+
+fn source_error(e: StreamError<DynSynParseError, Infallible>) -> DynSynParseError {
+    match e {
+        StreamError::SourceError(e) => e,
+        StreamError::SinkError(e) => match e {},
+    }
+}
+
+/// An async counterpart of [`DynSynQuadParser`]. We can get it's instance from a [`DynSynQuadParser`] through [`From`] conversion.
+#[derive(Debug)]
+pub struct DynSynAsyncQuadParser<T>
+where
+    T: TTerm + CopyTerm + Clone,
+{
+    parser: Arc<DynSynQuadParser<T>>,
+}
+
+impl<T> From<DynSynQuadParser<T>> for DynSynAsyncQuadParser<T>
+where
+    T: TTerm + CopyTerm + Clone,
+{
+    fn from(parser: DynSynQuadParser<T>) -> Self {
+        Self {
+            parser: Arc::new(parser),
+        }
+    }
+}
+
+impl<T> DynSynAsyncQuadParser<T>
+where
+    T: TTerm + CopyTerm + Clone,
+{
+    /// Returns syntax, this parser is targeted at.
+    pub fn syntax(&self) -> RdfSyntax {
+        self.parser.syntax()
+    }
+
+    /// Parse document from `data`, into a stream of quads. Stream ends after first error.
+    pub fn parse<R: AsyncBufRead + Unpin>(&self, data: R) -> DynSynAsyncQuadStream<T, R> {
+        DynSynAsyncQuadStream {
+            parser: self.parser.clone(),
+            state: State::Reading(data, Vec::new()),
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+/// A [`Stream`] of quads, parsed by a [`DynSynAsyncQuadParser`].
+pub struct DynSynAsyncQuadStream<T, R>
+where
+    T: TTerm + CopyTerm + Clone,
+{
+    parser: Arc<DynSynQuadParser<T>>,
+    state: State<R, <DynSynQuadParser<T> as QuadParser<Cursor<Vec<u8>>>>::Source>,
+    pending: VecDeque<Result<TupleQuad<T>, DynSynParseError>>,
+}
+
+impl<T, R> Stream for DynSynAsyncQuadStream<T, R>
+where
+    T: TTerm + CopyTerm + Clone + Unpin,
+    R: AsyncBufRead + Unpin,
+{
+    type Item = Result<TupleQuad<T>, DynSynParseError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(quad) = this.pending.pop_front() {
+                return Poll::Ready(Some(quad));
+            }
+            let parser = &this.parser;
+            let source = match this
+                .state
+                .poll_source(cx, parser.session_budget(), |data| parser.parse(data))
+            {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(e)) => {
+                    return Poll::Ready(Some(Err(e.with_syntax(this.parser.syntax()))))
+                }
+                Poll::Ready(Ok(None)) => return Poll::Ready(None),
+                Poll::Ready(Ok(Some(source))) => source,
+            };
+            let pending = &mut this.pending;
+            match source.try_for_some_quad(&mut |q| {
+                pending.push_back(Ok((
+                    [T::copy(q.s()), T::copy(q.p()), T::copy(q.o())],
+                    q.g().map(T::copy),
+                )));
+                Ok(())
+            }) {
+                Ok(true) => {}
+                Ok(false) => this.state = State::Done,
+                Err(e) => {
+                    // Statements parsed before error are still emitted.
+                    this.state = State::Done;
+                    this.pending.push_back(Err(source_error(e)));
+                }
+            }
+        }
+    }
+}
+
+/// An async counterpart of [`DynSynTripleParser`]. We can get it's instance from a [`DynSynTripleParser`] through [`From`] conversion.
+#[derive(Debug)]
+pub struct DynSynAsyncTripleParser<T>
+where
+    T: TTerm + CopyTerm + Clone,
+{
+    parser: Arc<DynSynTripleParser<T>>,
+}
+
+impl<T> From<DynSynTripleParser<T>> for DynSynAsyncTripleParser<T>
+where
+    T: TTerm + CopyTerm + Clone,
+{
+    fn from(parser: DynSynTripleParser<T>) -> Self {
+        Self {
+            parser: Arc::new(parser),
+        }
+    }
+}
+
+impl<T> DynSynAsyncTripleParser<T>
+where
+    T: TTerm + CopyTerm + Clone,
+{
+    /// Returns syntax, this parser is targeted at.
+    pub fn syntax(&self) -> RdfSyntax {
+        self.parser.syntax()
+    }
+
+    /// Parse document from `data`, into a stream of triples. Stream ends after first error.
+    pub fn parse<R: AsyncBufRead + Unpin>(&self, data: R) -> DynSynAsyncTripleStream<T, R> {
+        DynSynAsyncTripleStream {
+            parser: self.parser.clone(),
+            state: State::Reading(data, Vec::new()),
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+/// A [`Stream`] of triples, parsed by a [`DynSynAsyncTripleParser`].
+pub struct DynSynAsyncTripleStream<T, R>
+where
+    T: TTerm + CopyTerm + Clone,
+{
+    parser: Arc<DynSynTripleParser<T>>,
+    state: State<R, <DynSynTripleParser<T> as TripleParser<Cursor<Vec<u8>>>>::Source>,
+    pending: VecDeque<Result<SliceTriple<T>, DynSynParseError>>,
+}
+
+impl<T, R> Stream for DynSynAsyncTripleStream<T, R>
+where
+    T: TTerm + CopyTerm + Clone + Unpin,
+    R: AsyncBufRead + Unpin,
+{
+    type Item = Result<SliceTriple<T>, DynSynParseError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(triple) = this.pending.pop_front() {
+                return Poll::Ready(Some(triple));
+            }
+            let parser = &this.parser;
+            let source = match this
+                .state
+                .poll_source(cx, parser.session_budget(), |data| parser.parse(data))
+            {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(e)) => {
+                    return Poll::Ready(Some(Err(e.with_syntax(this.parser.syntax()))))
+                }
+                Poll::Ready(Ok(None)) => return Poll::Ready(None),
+                Poll::Ready(Ok(Some(source))) => source,
+            };
+            let pending = &mut this.pending;
+            match source.try_for_some_triple(&mut |t| {
+                pending.push_back(Ok([T::copy(t.s()), T::copy(t.p()), T::copy(t.o())]));
+                Ok(())
+            }) {
+                Ok(true) => {}
+                Ok(false) => this.state = State::Done,
+                Err(e) => {
+                    // Statements parsed before error are still emitted.
+                    this.state = State::Done;
+                    this.pending.push_back(Err(source_error(e)));
+                }
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use std::{future::poll_fn, pin::Pin};
+
+    use futures_core::Stream;
+    use once_cell::sync::Lazy;
+    use sophia_api::term::TTerm;
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+    use tokio::io::BufReader;
+    use type_map::concurrent::TypeMap;
+
+    use super::{DynSynAsyncQuadParser, DynSynAsyncTripleParser};
+    use crate::{
+        error_code::{self, ErrorCode},
+        parser::{
+            limits::SessionBudget, quads::DynSynQuadParserFactory,
+            triples::DynSynTripleParserFactory,
+        },
+        syntax::{self, RdfSyntax},
+        tests::TRACING,
+    };
+
+    static DOC: &str = r#"
+        @prefix : <http://example.org/> .
+        :a :p :b .
+        :g { :a :p :c, :d . }
+    "#;
+
+    /// Collect items of given stream, until it ends.
+    async fn collect<S: Stream + Unpin>(mut stream: S) -> Vec<S::Item> {
+        let mut items = Vec::new();
+        while let Some(item) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await {
+            items.push(item);
+        }
+        items
+    }
+
+    fn quad_parser(syntax_: RdfSyntax) -> DynSynAsyncQuadParser<BoxTerm> {
+        DynSynQuadParserFactory::default()
+            .try_new_parser(syntax_, None, None)
+            .unwrap()
+            .into()
+    }
+
+    fn triple_parser(syntax_: RdfSyntax) -> DynSynAsyncTripleParser<BoxTerm> {
+        DynSynTripleParserFactory::default()
+            .try_new_parser(syntax_, None, None)
+            .unwrap()
+            .into()
+    }
+
+    // Small buffer capacity makes input to be read in many chunks.
+    #[test_case(1)]
+    #[test_case(7)]
+    #[test_case(8192)]
+    #[tokio::test]
+    pub async fn parses_quads(capacity: usize) {
+        Lazy::force(&TRACING);
+        let parser = quad_parser(syntax::TRIG);
+        assert_eq!(parser.syntax(), syntax::TRIG);
+        let quads = collect(parser.parse(BufReader::with_capacity(capacity, DOC.as_bytes()))).await;
+        assert_eq!(quads.len(), 3);
+        let quads: Vec<_> = quads.into_iter().map(Result::unwrap).collect();
+        assert!(quads[0].1.is_none());
+        assert!(quads[1].1.is_some());
+    }
+
+    #[tokio::test]
+    pub async fn parses_triples() {
+        Lazy::force(&TRACING);
+        let parser = triple_parser(syntax::TRIG);
+        let triples = collect(parser.parse(DOC.as_bytes())).await;
+        assert_eq!(triples.len(), 1);
+        assert_eq!(
+            triples[0].as_ref().unwrap()[2].value().to_string(),
+            "http://example.org/b"
+        );
+    }
+
+    #[tokio::test]
+    pub async fn stream_ends_after_parse_error() {
+        Lazy::force(&TRACING);
+        let doc = "<http://e/a> <http://e/p> <http://e/b> .\n<http://e/a> <http://e/p> .\n<http://e/a> <http://e/p> <http://e/c> .\n";
+        let items = collect(triple_parser(syntax::N_TRIPLES).parse(doc.as_bytes())).await;
+        assert_eq!(items.len(), 2);
+        assert!(items[0].is_ok());
+        assert_eq!(
+            items[1].as_ref().unwrap_err().code(),
+            error_code::DYNSYN_PARSE_SYNTAX
+        );
+    }
+
+    #[test_case(1000, 1; "exceeding budget")]
+    #[test_case(100_000, 3; "within budget")]
+    #[tokio::test]
+    pub async fn enforces_byte_budget_while_reading(max_bytes: u64, expected_items: usize) {
+        Lazy::force(&TRACING);
+        let mut config_map = TypeMap::new();
+        config_map.insert(SessionBudget::new(None, Some(max_bytes), None));
+        let parser: DynSynAsyncQuadParser<BoxTerm> = DynSynQuadParserFactory::new(Some(config_map))
+            .try_new_parser(syntax::TRIG, None, None)
+            .unwrap()
+            .into();
+        let doc = format!("{}{}", DOC, "# padding\n".repeat(100));
+        let items = collect(parser.parse(BufReader::with_capacity(7, doc.as_bytes()))).await;
+        assert_eq!(items.len(), expected_items);
+        if expected_items == 1 {
+            assert_eq!(
+                items[0].as_ref().unwrap_err().code(),
+                error_code::DYNSYN_LIMIT_EXCEEDED
+            );
+        }
+    }
+
+    #[test]
+    pub fn streams_are_send() {
+        fn assert_send<S: Send>(_: &S) {}
+        let parser = quad_parser(syntax::N_QUADS);
+        assert_send(&parser.parse(&b""[..]));
+        assert_send(&triple_parser(syntax::TURTLE).parse(&b""[..]));
+    }
+}
@@ -0,0 +1,264 @@
+//! This module provides [`DynSynParserBuilder`], a builder to construct triple, and quad parsers with named, chained options, instead of positional optional arguments of factory methods.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{parser::builder::DynSynParserBuilder, syntax};
+//! use sophia_api::{dataset::Dataset, parser::QuadParser, quad::stream::QuadSource};
+//! use sophia_inmem::dataset::FastDataset;
+//! use sophia_term::BoxTerm;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let parser = DynSynParserBuilder::new()
+//!     .syntax(syntax::TURTLE)
+//!     .base_iri("http://e/")
+//!     .default_graph(BoxTerm::new_iri("http://e/g")?)
+//!     .lenient(false)
+//!     .build_quad_parser()?;
+//!
+//! let dataset: FastDataset = parser.parse_str("<a> <p> <b> .").collect_quads()?;
+//! assert_eq!(dataset.quads().count(), 1);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use sophia_api::term::{CopyTerm, TTerm};
+
+use crate::syntax::{RdfSyntax, UnKnownSyntaxError};
+
+use super::{
+    quads::DynSynQuadParser, statements::DynSynStatementParserFactory, triples::DynSynTripleParser,
+};
+
+/// An error in building a parser with [`DynSynParserBuilder`].
+#[derive(Debug, thiserror::Error)]
+pub enum ParserBuildError {
+    #[error("Syntax of parser is not specified")]
+    MissingSyntax,
+    #[error(transparent)]
+    UnKnownSyntax(#[from] UnKnownSyntaxError),
+}
+
+/// A builder to construct [`DynSynTripleParser`], and [`DynSynQuadParser`] instances. Parsers are created through factories of builder, which are default factories, unless set with [`factories`](Self::factories). Options set on builder override corresponding options from factory config.
+#[derive(Debug, Clone)]
+pub struct DynSynParserBuilder<T>
+where
+    T: TTerm + CopyTerm + Clone,
+{
+    factories: DynSynStatementParserFactory,
+    syntax: Option<RdfSyntax>,
+    base_iri: Option<String>,
+    default_graph: Option<T>,
+    lenient: Option<bool>,
+    reify_quoted_triples: Option<bool>,
+}
+
+impl<T> Default for DynSynParserBuilder<T>
+where
+    T: TTerm + CopyTerm + Clone,
+{
+    fn default() -> Self {
+        Self {
+            factories: Default::default(),
+            syntax: None,
+            base_iri: None,
+            default_graph: None,
+            lenient: None,
+            reify_quoted_triples: None,
+        }
+    }
+}
+
+impl<T> DynSynParserBuilder<T>
+where
+    T: TTerm + CopyTerm + Clone,
+{
+    /// Create a new builder, with default factories, and no options set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets factories, through which parsers are created. Their configuration is applied to built parsers.
+    pub fn factories(mut self, factories: DynSynStatementParserFactory) -> Self {
+        self.factories = factories;
+        self
+    }
+
+    /// Sets syntax of parser. It is required.
+    pub fn syntax(mut self, syntax_: RdfSyntax) -> Self {
+        self.syntax = Some(syntax_);
+        self
+    }
+
+    /// Sets base iri, against which relative iris of documents are resolved.
+    pub fn base_iri(mut self, base_iri: impl Into<String>) -> Self {
+        self.base_iri = Some(base_iri.into());
+        self
+    }
+
+    /// Sets default graph. Quad parsers put triples of documents in triple syntaxes into this graph. Triple parsers read triples of this graph from documents in quad syntaxes. If not set, default graph of dataset is used in both cases.
+    pub fn default_graph(mut self, graph_iri: T) -> Self {
+        self.default_graph = Some(graph_iri);
+        self
+    }
+
+    /// Makes parser lenient, or strict. See [`DynSynQuadParser::set_lenient`].
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = Some(lenient);
+        self
+    }
+
+    /// Enables, or disables reification of quoted triples by parser. See [`DynSynQuadParser::set_reify_quoted_triples`].
+    pub fn reify_quoted_triples(mut self, reify_quoted_triples: bool) -> Self {
+        self.reify_quoted_triples = Some(reify_quoted_triples);
+        self
+    }
+
+    /// Build a [`DynSynTripleParser`] with set options.
+    ///
+    /// # Errors
+    /// returns [`ParserBuildError`] if syntax is not set, or if it is not known/supported.
+    pub fn build_triple_parser(self) -> Result<DynSynTripleParser<T>, ParserBuildError> {
+        let syntax_ = self.syntax.ok_or(ParserBuildError::MissingSyntax)?;
+        let mut parser = self.factories.triple_parser_factory.try_new_parser(
+            syntax_,
+            self.base_iri,
+            self.default_graph,
+        )?;
+        if let Some(lenient) = self.lenient {
+            parser.set_lenient(lenient);
+        }
+        if let Some(reify_quoted_triples) = self.reify_quoted_triples {
+            parser.set_reify_quoted_triples(reify_quoted_triples);
+        }
+        Ok(parser)
+    }
+
+    /// Build a [`DynSynQuadParser`] with set options.
+    ///
+    /// # Errors
+    /// returns [`ParserBuildError`] if syntax is not set, or if it is not known/supported.
+    pub fn build_quad_parser(self) -> Result<DynSynQuadParser<T>, ParserBuildError> {
+        let syntax_ = self.syntax.ok_or(ParserBuildError::MissingSyntax)?;
+        let mut parser = self.factories.quad_parser_factory.try_new_parser(
+            syntax_,
+            self.base_iri,
+            self.default_graph,
+        )?;
+        if let Some(lenient) = self.lenient {
+            parser.set_lenient(lenient);
+        }
+        if let Some(reify_quoted_triples) = self.reify_quoted_triples {
+            parser.set_reify_quoted_triples(reify_quoted_triples);
+        }
+        Ok(parser)
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use claim::assert_matches;
+    use once_cell::sync::Lazy;
+    use sophia_api::{
+        dataset::Dataset,
+        graph::Graph,
+        parser::{QuadParser, TripleParser},
+        quad::{stream::QuadSource, Quad},
+        term::TTerm,
+        triple::stream::TripleSource,
+    };
+    use sophia_inmem::{dataset::FastDataset, graph::FastGraph};
+    use sophia_term::BoxTerm;
+    use type_map::concurrent::TypeMap;
+
+    use super::{DynSynParserBuilder, ParserBuildError};
+    use crate::{
+        parser::{
+            quads::DynSynQuadParserFactory, recovery::ParserConfig,
+            statements::DynSynStatementParserFactory, triples::DynSynTripleParserFactory,
+        },
+        syntax,
+        tests::TRACING,
+    };
+
+    #[test]
+    pub fn building_without_syntax_will_error() {
+        Lazy::force(&TRACING);
+        assert_matches!(
+            DynSynParserBuilder::<BoxTerm>::new().build_quad_parser(),
+            Err(ParserBuildError::MissingSyntax)
+        );
+        assert_matches!(
+            DynSynParserBuilder::<BoxTerm>::new().build_triple_parser(),
+            Err(ParserBuildError::MissingSyntax)
+        );
+    }
+
+    #[test]
+    pub fn building_for_unsupported_syntax_will_error() {
+        Lazy::force(&TRACING);
+        assert_matches!(
+            DynSynParserBuilder::<BoxTerm>::new()
+                .syntax(syntax::JSON_LD)
+                .build_quad_parser(),
+            Err(ParserBuildError::UnKnownSyntax(_))
+        );
+    }
+
+    #[test]
+    pub fn built_parsers_take_set_options() {
+        Lazy::force(&TRACING);
+        let g = BoxTerm::new_iri("http://e/g").unwrap();
+        let builder = DynSynParserBuilder::new()
+            .base_iri("http://e/")
+            .default_graph(g.clone())
+            .lenient(true);
+
+        let parser = builder
+            .clone()
+            .syntax(syntax::TURTLE)
+            .build_quad_parser()
+            .unwrap();
+        assert_eq!(parser.syntax(), syntax::TURTLE);
+        assert!(parser.is_lenient());
+        let dataset: FastDataset = parser.parse_str("<a> <p> <b> .").collect_quads().unwrap();
+        let q = dataset.quads().next().unwrap().unwrap();
+        assert_eq!(q.s().value(), "http://e/a");
+        assert_eq!(q.g().unwrap().value(), g.value());
+
+        let parser = builder
+            .syntax(syntax::N_QUADS)
+            .build_triple_parser()
+            .unwrap();
+        let graph: FastGraph = parser
+            .parse_str("<http://e/a> <http://e/p> <http://e/b> <http://e/g> .\n<http://e/a> <http://e/p> <http://e/c> .\n")
+            .collect_triples()
+            .unwrap();
+        assert_eq!(graph.triples().count(), 1);
+    }
+
+    #[test]
+    pub fn built_parsers_take_config_of_factories_unless_overridden() {
+        Lazy::force(&TRACING);
+        let mut config_map = TypeMap::new();
+        config_map.insert(ParserConfig::lenient());
+        let factories = DynSynStatementParserFactory::new(
+            DynSynTripleParserFactory::new(None),
+            DynSynQuadParserFactory::new(Some(config_map)),
+        );
+        let builder = DynSynParserBuilder::<BoxTerm>::new()
+            .factories(factories)
+            .syntax(syntax::TURTLE);
+
+        assert!(builder.clone().build_quad_parser().unwrap().is_lenient());
+        assert!(!builder
+            .lenient(false)
+            .build_quad_parser()
+            .unwrap()
+            .is_lenient());
+    }
+}
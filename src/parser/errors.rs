@@ -1,23 +1,227 @@
 use rio_turtle::TurtleError;
+#[cfg(feature = "rdf-xml")]
 use rio_xml::RdfXmlError;
 use sophia_api::triple::stream::{StreamError, StreamResult};
 
-use super::_inner::errors::InnerParseError;
+use crate::{
+    correspondence::NonRdfMediaTypeError,
+    error_code::{self, ErrorCode},
+    syntax::{self, RdfSyntax, UnKnownSyntaxError},
+    syntax_hint::SyntaxHintError,
+};
 
-#[derive(Debug, thiserror::Error)]
-#[error(transparent)]
-/// An error that abstracts over other syntax parsing errors. Currently it can be constructed from [`TurtleError`](TurtleError), and [`RdfXmlError`](RdfXmlError)
-pub struct DynSynParseError(InnerParseError);
+#[cfg(feature = "rdfa")]
+use super::rdfa::RdfaError;
+#[cfg(feature = "hdt")]
+use super::hdt::HdtError;
+#[cfg(feature = "trix")]
+use super::trix::TrixError;
+use super::{
+    _inner::{errors::InnerParseError, source::InputSniff},
+    iri_policy::IriPolicyViolation,
+    limits::{BudgetResource, DocumentLimit},
+    literal_policy::LiteralPolicyViolation,
+    rdf_star::RdfStarError,
+};
+
+/// Kinds of [`DynSynParseError`]. Each kind corresponds to an [error code](crate::error_code) of the error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParseErrorKind {
+    /// Document is malformed as per it's syntax.
+    Syntax,
+    /// Document couldn't be read from underlying reader.
+    Io,
+    /// Document is truncated mid-statement.
+    Truncated,
+    /// Input is gzip-compressed, instead of being a plain document.
+    GzipInput,
+    /// Input is an html page, instead of being an rdf document.
+    HtmlInput,
+    /// Document exceeds a configured limit, or session budget is exhausted.
+    LimitExceeded,
+    /// A statement violates configured iri policy.
+    IriPolicyViolation,
+    /// A statement violates configured literal policy.
+    LiteralPolicyViolation,
+}
+
+#[derive(Debug)]
+/// An error that abstracts over other syntax parsing errors. Currently it can be constructed from [`TurtleError`](TurtleError), [`RdfStarError`](super::rdf_star::RdfStarError), [`RdfXmlError`](RdfXmlError), [`RdfaError`](super::rdfa::RdfaError), [`TrixError`](super::trix::TrixError), and [`HdtError`](super::hdt::HdtError)
+///
+/// When input is recognized as gzip-compressed, as an html page, or as truncated mid-statement, error will describe that instead of generic parse error. Underlying parse error is still available through [`source`](std::error::Error::source).
+///
+/// Applications can report structured diagnostics with [`kind`](Self::kind), [`location`](Self::location), and [`syntax`](Self::syntax) of the error, instead of matching it's message.
+pub struct DynSynParseError(InnerParseError, RdfSyntax);
+
+impl std::fmt::Display for DynSynParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl std::error::Error for DynSynParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.source()
+    }
+}
+
+impl DynSynParseError {
+    pub(crate) fn diagnosed(self, input_sniff: InputSniff) -> Self {
+        Self(self.0.diagnosed(input_sniff), self.1)
+    }
+
+    /// Marks this error as arising from parsing a document of given syntax. Sources mark errors they raise with their syntax, hence errors constructed below are marked with a placeholder syntax.
+    pub(crate) fn with_syntax(self, syntax_: RdfSyntax) -> Self {
+        Self(self.0, syntax_)
+    }
+
+    pub(crate) fn too_many_graphs(max_named_graphs: usize) -> Self {
+        Self(
+            InnerParseError::TooManyGraphs(max_named_graphs),
+            syntax::TURTLE,
+        )
+    }
+
+    pub(crate) fn budget_exhausted(resource: BudgetResource) -> Self {
+        Self(InnerParseError::BudgetExhausted(resource), syntax::TURTLE)
+    }
+
+    pub(crate) fn document_limit_exceeded(limit: DocumentLimit) -> Self {
+        Self(
+            InnerParseError::DocumentLimitExceeded(limit),
+            syntax::TURTLE,
+        )
+    }
+
+    pub(crate) fn iri_policy_violation(violation: IriPolicyViolation) -> Self {
+        Self(
+            InnerParseError::IriPolicyViolation(violation),
+            syntax::TURTLE,
+        )
+    }
+
+    pub(crate) fn literal_policy_violation(violation: LiteralPolicyViolation) -> Self {
+        Self(
+            InnerParseError::LiteralPolicyViolation(violation),
+            syntax::TURTLE,
+        )
+    }
+
+    /// Marks this error as arising in a chunk of document, that starts from given line number. Line number of the error will be counted from start of document.
+    #[cfg(any(test, feature = "parallel"))]
+    pub(crate) fn in_chunk(self, first_line: u64) -> Self {
+        match self.0.textual_position() {
+            Some(_) if first_line > 1 => Self(
+                InnerParseError::InChunk {
+                    first_line,
+                    error: Box::new(self.0),
+                },
+                self.1,
+            ),
+            _ => self,
+        }
+    }
+
+    /// Returns line number of the error in source document, starting from 1, if known.
+    pub fn line_number(&self) -> Option<u64> {
+        self.0.textual_position().map(|p| p.line_number())
+    }
+
+    /// Returns location of the error in source document as `(line, column)`, if known. Both line, and column start from 1. Column is counted in bytes.
+    pub fn location(&self) -> Option<(u64, u64)> {
+        self.0
+            .textual_position()
+            .map(|p| (p.line_number(), p.byte_number()))
+    }
+
+    /// Returns syntax of the document, that failed to parse.
+    pub fn syntax(&self) -> RdfSyntax {
+        self.1
+    }
+
+    /// Returns kind of this error.
+    pub fn kind(&self) -> ParseErrorKind {
+        match self.0.unchunked() {
+            InnerParseError::GzipCompressed(_) => ParseErrorKind::GzipInput,
+            InnerParseError::HtmlPage(_) => ParseErrorKind::HtmlInput,
+            InnerParseError::Truncated(_) => ParseErrorKind::Truncated,
+            InnerParseError::TooManyGraphs(_)
+            | InnerParseError::BudgetExhausted(_)
+            | InnerParseError::DocumentLimitExceeded(_) => ParseErrorKind::LimitExceeded,
+            InnerParseError::IriPolicyViolation(_) => ParseErrorKind::IriPolicyViolation,
+            InnerParseError::LiteralPolicyViolation(_) => ParseErrorKind::LiteralPolicyViolation,
+            e if e.is_io() => ParseErrorKind::Io,
+            _ => ParseErrorKind::Syntax,
+        }
+    }
+
+    /// Returns true, if this error is confined to a single malformed statement, and parsing can be resumed after it. Io errors, limit errors, and errors from inputs that are diagnosed to be not rdf documents are not recoverable.
+    pub(crate) fn is_recoverable(&self) -> bool {
+        let is_syntax_error = match self.0.unchunked() {
+            InnerParseError::Turtle(_) | InnerParseError::RdfStar(_) => true,
+            #[cfg(feature = "rdf-xml")]
+            InnerParseError::RdfXml(_) => true,
+            _ => false,
+        };
+        is_syntax_error && !self.0.is_io()
+    }
+}
 
 impl From<TurtleError> for DynSynParseError {
     fn from(e: TurtleError) -> Self {
-        Self(e.into())
+        Self(e.into(), syntax::TURTLE)
     }
 }
 
+impl From<RdfStarError> for DynSynParseError {
+    fn from(e: RdfStarError) -> Self {
+        match e {
+            RdfStarError::Turtle(e) => e.into(),
+            e => Self(InnerParseError::RdfStar(e), syntax::TURTLE),
+        }
+    }
+}
+
+#[cfg(feature = "rdf-xml")]
 impl From<RdfXmlError> for DynSynParseError {
     fn from(e: RdfXmlError) -> Self {
-        Self(e.into())
+        Self(e.into(), syntax::RDF_XML)
+    }
+}
+
+#[cfg(feature = "rdfa")]
+impl From<RdfaError> for DynSynParseError {
+    fn from(e: RdfaError) -> Self {
+        Self(e.into(), syntax::HTML_RDFA)
+    }
+}
+
+#[cfg(feature = "trix")]
+impl From<TrixError> for DynSynParseError {
+    fn from(e: TrixError) -> Self {
+        Self(e.into(), syntax::TRIX)
+    }
+}
+
+#[cfg(feature = "hdt")]
+impl From<HdtError> for DynSynParseError {
+    fn from(e: HdtError) -> Self {
+        Self(e.into(), syntax::HDT)
+    }
+}
+
+impl ErrorCode for DynSynParseError {
+    fn code(&self) -> &'static str {
+        match self.kind() {
+            ParseErrorKind::Syntax => error_code::DYNSYN_PARSE_SYNTAX,
+            ParseErrorKind::Io => error_code::DYNSYN_PARSE_IO,
+            ParseErrorKind::Truncated => error_code::DYNSYN_PARSE_TRUNCATED,
+            ParseErrorKind::GzipInput => error_code::DYNSYN_PARSE_GZIP_INPUT,
+            ParseErrorKind::HtmlInput => error_code::DYNSYN_PARSE_HTML_INPUT,
+            ParseErrorKind::LimitExceeded => error_code::DYNSYN_LIMIT_EXCEEDED,
+            ParseErrorKind::IriPolicyViolation => error_code::DYNSYN_IRI_POLICY_VIOLATION,
+            ParseErrorKind::LiteralPolicyViolation => error_code::DYNSYN_LITERAL_POLICY_VIOLATION,
+        }
     }
 }
 
@@ -39,6 +243,34 @@ where
 
 pub type DynSynStreamResult<T, SinkErr> = StreamResult<T, DynSynParseError, SinkErr>;
 
+/// This function marks source error of given stream result, if any, as arising from parsing a document of given syntax.
+pub(crate) fn with_syntax_stream_result<T, SinkErr>(
+    r: DynSynStreamResult<T, SinkErr>,
+    syntax_: RdfSyntax,
+) -> DynSynStreamResult<T, SinkErr>
+where
+    SinkErr: std::error::Error,
+{
+    r.map_err(|e| match e {
+        StreamError::SourceError(ev) => StreamError::SourceError(ev.with_syntax(syntax_)),
+        StreamError::SinkError(ev) => StreamError::SinkError(ev),
+    })
+}
+
+/// This function diagnoses source error of given stream result, if any, using sniff of the input.
+pub(crate) fn diagnose_stream_result<T, SinkErr>(
+    r: DynSynStreamResult<T, SinkErr>,
+    input_sniff: InputSniff,
+) -> DynSynStreamResult<T, SinkErr>
+where
+    SinkErr: std::error::Error,
+{
+    r.map_err(|e| match e {
+        StreamError::SourceError(ev) => StreamError::SourceError(ev.diagnosed(input_sniff)),
+        StreamError::SinkError(ev) => StreamError::SinkError(ev),
+    })
+}
+
 pub fn adapt_stream_result<T, SourceErr, SinkErr>(
     r: StreamResult<T, SourceErr, SinkErr>,
 ) -> DynSynStreamResult<T, SinkErr>
@@ -51,3 +283,174 @@ where
         Err(e) => Err(adapt_quads_stream_error(e)),
     }
 }
+
+/// An error in creating a parser for a media type.
+#[derive(Debug, thiserror::Error)]
+pub enum ParserForMediaTypeError {
+    #[error(transparent)]
+    NonRdfMediaType(#[from] NonRdfMediaTypeError),
+    #[error(transparent)]
+    UnKnownSyntax(#[from] UnKnownSyntaxError),
+}
+
+impl ErrorCode for ParserForMediaTypeError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::NonRdfMediaType(e) => e.code(),
+            Self::UnKnownSyntax(e) => e.code(),
+        }
+    }
+}
+
+/// An error in creating a parser for a [`SyntaxHint`](crate::syntax_hint::SyntaxHint).
+#[derive(Debug, thiserror::Error)]
+pub enum ParserForHintError {
+    #[error(transparent)]
+    SyntaxHint(#[from] SyntaxHintError),
+    #[error(transparent)]
+    UnKnownSyntax(#[from] UnKnownSyntaxError),
+}
+
+impl ErrorCode for ParserForHintError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::SyntaxHint(e) => e.code(),
+            Self::UnKnownSyntax(e) => e.code(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        error::Error,
+        io::{self, BufReader, Read},
+    };
+
+    use claim::assert_err;
+    use once_cell::sync::Lazy;
+    use sophia_api::{parser::TripleParser, triple::stream::TripleSource};
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+
+    use super::ParseErrorKind;
+    use crate::{
+        error_code::{self, ErrorCode},
+        fixtures::INVALID_TURTLE,
+        parser::triples::DynSynTripleParserFactory,
+        syntax::{self, RdfSyntax},
+        tests::TRACING,
+    };
+
+    /// A reader that always fails.
+    struct FailingReader;
+
+    impl Read for FailingReader {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::new(
+                io::ErrorKind::ConnectionReset,
+                "connection reset",
+            ))
+        }
+    }
+
+    #[test_case(syntax::TURTLE, INVALID_TURTLE[0])]
+    #[cfg_attr(
+        feature = "rdf-xml",
+        test_case(syntax::RDF_XML, crate::fixtures::INVALID_RDF_XML[0])
+    )]
+    pub fn malformed_docs_will_have_syntax_error_code(syntax_: RdfSyntax, doc: &str) {
+        Lazy::force(&TRACING);
+        let parser = DynSynTripleParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap();
+        let e = assert_err!(parser.parse_str(doc).for_each_triple(|_| {}));
+        assert_eq!(e.code(), error_code::DYNSYN_PARSE_SYNTAX);
+    }
+
+    #[test_case(syntax::TURTLE)]
+    #[test_case(syntax::N_TRIPLES)]
+    #[cfg_attr(feature = "rdf-xml", test_case(syntax::RDF_XML))]
+    pub fn reader_failures_will_have_io_error_code(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let parser = DynSynTripleParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap();
+        let e = assert_err!(parser
+            .parse(BufReader::new(FailingReader))
+            .for_each_triple(|_| {}));
+        assert_eq!(e.code(), error_code::DYNSYN_PARSE_IO);
+    }
+
+    #[test_case(syntax::N_QUADS)]
+    #[test_case(syntax::TURTLE)]
+    #[cfg_attr(feature = "rdf-xml", test_case(syntax::RDF_XML))]
+    pub fn gzip_inputs_will_be_diagnosed(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let parser = DynSynTripleParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap();
+        let gzip_doc: &[u8] =
+            b"\x1F\x8B\x08\x00\x00\x00\x00\x00\x00\x03\xCB\x48\xCD\xC9\xC9\x07\x00";
+        let e = assert_err!(parser.parse(gzip_doc).for_each_triple(|_| {}));
+        assert_eq!(e.code(), error_code::DYNSYN_PARSE_GZIP_INPUT);
+        assert_eq!(e.to_string(), "input appears to be gzip-compressed");
+        assert!(e.source().is_some());
+    }
+
+    #[test_case(
+        syntax::N_TRIPLES,
+        "<!DOCTYPE html>\n<html><body>502 Bad Gateway</body></html>"
+    )]
+    #[test_case(
+        syntax::TURTLE,
+        "\n  <html>\n<head><title>Not Found</title></head></html>"
+    )]
+    #[cfg_attr(
+        feature = "rdf-xml",
+        test_case(syntax::RDF_XML, "<!doctype html><html><body><p>Oops</body></html>")
+    )]
+    pub fn html_inputs_will_be_diagnosed(syntax_: RdfSyntax, doc: &str) {
+        Lazy::force(&TRACING);
+        let parser = DynSynTripleParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap();
+        let e = assert_err!(parser.parse_str(doc).for_each_triple(|_| {}));
+        assert_eq!(e.code(), error_code::DYNSYN_PARSE_HTML_INPUT);
+    }
+
+    #[test_case(
+        syntax::TURTLE,
+        "@prefix : <http://example.org/ns/> .\n:alice :knows [ :name \"Bob\""
+    )]
+    #[test_case(syntax::TRIG, "<tag:g> { <tag:a> <tag:b> <tag:c> .\n")]
+    pub fn truncated_inputs_will_be_diagnosed(syntax_: RdfSyntax, doc: &str) {
+        Lazy::force(&TRACING);
+        let parser = DynSynTripleParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap();
+        let e = assert_err!(parser.parse_str(doc).for_each_triple(|_| {}));
+        assert_eq!(e.code(), error_code::DYNSYN_PARSE_TRUNCATED);
+    }
+
+    #[test_case(syntax::N_TRIPLES, "<tag:a> <tag:b> <tag:c> .\n<tag:a> <tag:b> .\n", (2, 17))]
+    #[test_case(syntax::TURTLE, "<tag:a> <tag:b> <tag:c> .\n\n  <tag:a> <tag:b> .", (3, 19))]
+    pub fn errors_will_have_location_and_syntax(
+        syntax_: RdfSyntax,
+        doc: &str,
+        location: (u64, u64),
+    ) {
+        Lazy::force(&TRACING);
+        let parser = DynSynTripleParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap();
+        let e = assert_err!(parser.parse_str(doc).for_each_triple(|_| {}));
+        assert_eq!(e.kind(), ParseErrorKind::Syntax);
+        assert_eq!(e.syntax(), syntax_);
+        assert_eq!(e.location(), Some(location));
+    }
+}
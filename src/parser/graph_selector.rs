@@ -0,0 +1,182 @@
+//! This module defines [`GraphSelector`], that selects graphs, whose triples a [`DynSynTripleParser`](super::triples::DynSynTripleParser) reads from documents in quad syntaxes, like trig, and n-quads. Quads of graphs, that are not selected, are ignored without copying any of their terms.
+//!
+//! By default, only default graph of the dataset is selected. Selecting [`UnionOfAllGraphs`](GraphSelector::UnionOfAllGraphs) flattens whole dataset into it's union graph. A subset of graphs can be selected by their names with [`AnyOf`](GraphSelector::AnyOf), or by a user-supplied predicate over graph names with [`Matching`](GraphSelector::Matching), like when datasets partition data by provenance graphs. Triples, that are in more than one selected graph, are emitted once per graph, and are deduplicated by graphs they are collected into.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{
+//!     parser::{graph_selector::GraphSelector, triples::DynSynTripleParserFactory},
+//!     syntax,
+//! };
+//! use sophia_api::{graph::Graph, parser::TripleParser, triple::stream::TripleSource};
+//! use sophia_inmem::graph::FastGraph;
+//! use sophia_term::BoxTerm;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let doc = r#"
+//!     <http://example.org/s> <http://example.org/p> "in default graph" .
+//!     <http://example.org/g1> { <http://example.org/s> <http://example.org/p> "in g1" . }
+//!     <http://example.org/g2> { <http://example.org/s> <http://example.org/p> "in g2" . }
+//! "#;
+//! let mut parser = DynSynTripleParserFactory::default()
+//!     .try_new_parser::<BoxTerm>(syntax::TRIG, None, None)?;
+//!
+//! let default_graph: FastGraph = parser.parse_str(doc).collect_triples()?;
+//! assert_eq!(default_graph.triples().count(), 1);
+//!
+//! parser.set_graph_selector(GraphSelector::UnionOfAllGraphs);
+//! let union_graph: FastGraph = parser.parse_str(doc).collect_triples()?;
+//! assert_eq!(union_graph.triples().count(), 3);
+//!
+//! parser.set_graph_selector(GraphSelector::matching(|g| {
+//!     g.map_or(false, |g| g.value().ends_with("2"))
+//! }));
+//! let g2: FastGraph = parser.parse_str(doc).collect_triples()?;
+//! assert_eq!(g2.triples().count(), 1);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::sync::Arc;
+
+use sophia_api::term::TTerm;
+use sophia_term::BoxTerm;
+
+use crate::iri_resolver::DynIriResolver;
+
+/// A predicate over graph names, that selects graphs for which it returns true. Graph name is `None` for default graph.
+pub type GraphPredicate = Arc<dyn Fn(Option<&dyn TTerm>) -> bool + Send + Sync>;
+
+/// Selection of graphs of a dataset, whose triples are read by triple parsers.
+#[derive(Clone, Default)]
+pub enum GraphSelector<T> {
+    /// Selects only default graph.
+    #[default]
+    Default,
+    /// Selects only graph with given name.
+    Named(T),
+    /// Selects all graphs, including default graph.
+    UnionOfAllGraphs,
+    /// Selects graphs with any of given names. `None` stands for default graph.
+    AnyOf(Vec<Option<T>>),
+    /// Selects graphs, for which given predicate returns true.
+    Matching(GraphPredicate),
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for GraphSelector<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Default => f.debug_tuple("Default").finish(),
+            Self::Named(g) => f.debug_tuple("Named").field(g).finish(),
+            Self::UnionOfAllGraphs => f.debug_tuple("UnionOfAllGraphs").finish(),
+            Self::AnyOf(gs) => f.debug_tuple("AnyOf").field(gs).finish(),
+            Self::Matching(_) => f.debug_tuple("Matching").finish(),
+        }
+    }
+}
+
+impl<T> From<Option<T>> for GraphSelector<T> {
+    /// Selects graph with given name, or default graph if it is `None`.
+    fn from(graph_name: Option<T>) -> Self {
+        match graph_name {
+            Some(graph_name) => Self::Named(graph_name),
+            None => Self::Default,
+        }
+    }
+}
+
+impl<T> GraphSelector<T> {
+    /// Create a selector, that selects graphs for which given `predicate` returns true.
+    pub fn matching<F>(predicate: F) -> Self
+    where
+        F: Fn(Option<&dyn TTerm>) -> bool + Send + Sync + 'static,
+    {
+        Self::Matching(Arc::new(predicate))
+    }
+}
+
+impl<T: TTerm> GraphSelector<T> {
+    /// Returns true, if graph with given name is selected. Graph name is rewritten with given resolver, if any, before matching, without being copied.
+    pub(crate) fn selects<G>(
+        &self,
+        graph_name: Option<&G>,
+        resolver: Option<&DynIriResolver>,
+    ) -> bool
+    where
+        G: TTerm + ?Sized,
+    {
+        match (self, graph_name) {
+            (Self::UnionOfAllGraphs, _) => true,
+            (Self::Default, None) => true,
+            (Self::Named(target), Some(gv)) => DynIriResolver::resolves_to(resolver, gv, target),
+            (Self::AnyOf(targets), graph_name) => {
+                targets.iter().any(|target| match (graph_name, target) {
+                    (Some(gv), Some(target)) => DynIriResolver::resolves_to(resolver, gv, target),
+                    (None, None) => true,
+                    _ => false,
+                })
+            }
+            (Self::Matching(predicate), Some(gv)) if resolver.is_some() => {
+                // Graph name is copied only if it has to be rewritten.
+                let gv: BoxTerm = DynIriResolver::resolved(resolver, gv);
+                predicate(Some(gv.as_dyn()))
+            }
+            (Self::Matching(predicate), graph_name) => predicate(graph_name.map(TTerm::as_dyn)),
+            _ => false,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+
+    use super::GraphSelector;
+    use crate::tests::TRACING;
+
+    fn named(graph_name: &str) -> GraphSelector<BoxTerm> {
+        GraphSelector::Named(BoxTerm::new_iri(graph_name).unwrap())
+    }
+
+    fn any_of(graph_names: &[Option<&str>]) -> GraphSelector<BoxTerm> {
+        GraphSelector::AnyOf(
+            graph_names
+                .iter()
+                .map(|g| g.map(|g| BoxTerm::new_iri(g).unwrap()))
+                .collect(),
+        )
+    }
+
+    #[test_case(GraphSelector::Default, None, true)]
+    #[test_case(GraphSelector::Default, Some("http://example.org/g1"), false)]
+    #[test_case(named("http://example.org/g1"), None, false)]
+    #[test_case(named("http://example.org/g1"), Some("http://example.org/g1"), true)]
+    #[test_case(named("http://example.org/g1"), Some("http://example.org/g2"), false)]
+    #[test_case(GraphSelector::UnionOfAllGraphs, None, true)]
+    #[test_case(GraphSelector::UnionOfAllGraphs, Some("http://example.org/g1"), true)]
+    #[test_case(any_of(&[None, Some("http://example.org/g1")]), None, true)]
+    #[test_case(any_of(&[None, Some("http://example.org/g1")]), Some("http://example.org/g1"), true)]
+    #[test_case(any_of(&[Some("http://example.org/g1")]), None, false)]
+    #[test_case(any_of(&[Some("http://example.org/g1")]), Some("http://example.org/g2"), false)]
+    #[test_case(any_of(&[]), None, false)]
+    #[test_case(GraphSelector::matching(|g| g.is_none()), None, true)]
+    #[test_case(GraphSelector::matching(|g| g.is_none()), Some("http://example.org/g1"), false)]
+    #[test_case(GraphSelector::matching(|g| g.is_some_and(|g| g.value().ends_with("g2"))), Some("http://example.org/g2"), true)]
+    pub fn selects_graphs_correctly(
+        selector: GraphSelector<BoxTerm>,
+        graph_name: Option<&str>,
+        expected: bool,
+    ) {
+        Lazy::force(&TRACING);
+        let graph_name = graph_name.map(|g| BoxTerm::new_iri(g).unwrap());
+        assert_eq!(selector.selects(graph_name.as_ref(), None), expected);
+    }
+}
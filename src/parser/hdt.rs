@@ -0,0 +1,869 @@
+//! This module provides a reader for [hdt](crate::syntax::HDT) files. Dynsyn parsers use it for that syntax with `hdt` feature.
+//!
+//! Hdt is a binary format, that is laid out for random access, rather than for streaming. Hence dictionary, and triples sections of a file are read into memory on first step, in their compressed form, and then a triple is decoded on each step. Triples are emitted in the order they are stored in, that is subject-predicate-object order of their dictionary ids.
+//!
+//! Reader supports files with a four-section dictionary of plain front coded sections, and bitmap triples in `SPO` order, which is what hdt-cpp, and hdt-java write by default. Header section is skipped. Empty, and whitespace-only inputs are read as an empty graph.
+//!
+//! Checksums of control information, and sections are skipped, and not verified. Hence a corrupted file, whose structure is still well formed, is read into wrong triples, instead of failing. Reader is tested against files laid out as per [hdt spec](https://www.rdfhdt.org/hdt-binary-format/), but not yet against files written by hdt-cpp, or hdt-java.
+//!
+//! Example:
+//!
+//! ```no_run
+//! use std::{fs::File, io::BufReader};
+//!
+//! use rdf_dynsyn::parser::hdt::HdtParser;
+//! use sophia_api::{parser::TripleParser, triple::stream::TripleSource};
+//! use sophia_inmem::graph::FastGraph;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let file = BufReader::new(File::open("dbpedia.hdt")?);
+//! let graph: FastGraph = HdtParser::default().parse(file).collect_triples()?;
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::io::{self, BufRead, Read};
+
+use rio_api::{
+    model::{BlankNode, Literal, NamedNode, Subject, Term as RioTerm, Triple as RioTriple},
+    parser::{LineBytePosition, ParseError, TriplesParser},
+};
+use sophia_api::parser::TripleParser;
+use sophia_rio::parser::StrictRioSource;
+
+/// Cookie at the start of each control information block.
+const COOKIE: &[u8] = b"$HDT";
+
+/// Types of control information blocks.
+const CI_GLOBAL: u8 = 1;
+const CI_HEADER: u8 = 2;
+const CI_DICTIONARY: u8 = 3;
+const CI_TRIPLES: u8 = 4;
+
+const DICTIONARY_FOUR: &str = "<http://purl.org/HDT/hdt#dictionaryFour>";
+const TRIPLES_BITMAP: &str = "<http://purl.org/HDT/hdt#triplesBitmap>";
+
+/// Type tags of serialized structures.
+const TYPE_PFC: u8 = 2;
+const TYPE_SEQ_LOG: u8 = 1;
+const TYPE_BITMAP_PLAIN: u8 = 1;
+
+/// Value of `order` property of triples section, for `SPO` order.
+const ORDER_SPO: &str = "1";
+
+/// An hdt parser.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HdtParser {}
+
+impl<B: BufRead> TripleParser<B> for HdtParser {
+    type Source = StrictRioSource<HdtTriplesParser<B>, HdtError>;
+
+    fn parse(&self, data: B) -> Self::Source {
+        StrictRioSource::Parser(HdtTriplesParser {
+            data: Some(data),
+            hdt: None,
+            cursor: Cursor::default(),
+            terms: Default::default(),
+        })
+    }
+}
+
+/// A rio triples parser for hdt files. It reads dictionary, and triples sections on first step, and then decodes a triple on each step.
+pub struct HdtTriplesParser<R> {
+    data: Option<R>,
+    hdt: Option<Box<Hdt>>,
+    cursor: Cursor,
+    /// Ids, and decoded strings of terms of last triple. Consecutive triples often share subject, and predicate.
+    terms: [(u64, String); 3],
+}
+
+/// Position of next triple in bitmap triples.
+#[derive(Debug, Default)]
+struct Cursor {
+    pos_y: u64,
+    pos_z: u64,
+    subject: u64,
+}
+
+impl<R: BufRead> HdtTriplesParser<R> {
+    /// Decode next triple into `terms`. Returns `false` if there are no more triples.
+    fn decode_next(&mut self) -> Result<bool, HdtError> {
+        let hdt = match &self.hdt {
+            Some(hdt) => hdt,
+            None => return Ok(false),
+        };
+        let c = &mut self.cursor;
+        if c.pos_z >= hdt.seq_z.len {
+            return Ok(false);
+        }
+        if c.pos_y >= hdt.seq_y.len {
+            return Err(HdtError::InvalidTriples(
+                "objects outnumber predicate lists".into(),
+            ));
+        }
+        let ids = [
+            c.subject + 1,
+            hdt.seq_y.get(c.pos_y),
+            hdt.seq_z.get(c.pos_z),
+        ];
+        // Advance to next triple.
+        if hdt.bitmap_z.get(c.pos_z) {
+            if hdt.bitmap_y.get(c.pos_y) {
+                c.subject += 1;
+            }
+            c.pos_y += 1;
+        }
+        c.pos_z += 1;
+
+        for (i, id) in ids.into_iter().enumerate() {
+            if self.terms[i].0 != id {
+                self.terms[i] = (id, hdt.dictionary.term(i, id)?);
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl<R: BufRead> TriplesParser for HdtTriplesParser<R> {
+    type Error = HdtError;
+
+    fn parse_step<E: From<Self::Error>>(
+        &mut self,
+        on_triple: &mut impl FnMut(RioTriple<'_>) -> Result<(), E>,
+    ) -> Result<(), E> {
+        if let Some(data) = self.data.take() {
+            self.hdt = Hdt::read(&mut Input {
+                inner: data,
+                offset: 0,
+            })?;
+            return Ok(());
+        }
+        match self.decode_next() {
+            Ok(true) => {}
+            Ok(false) => return Ok(()),
+            Err(e) => {
+                self.hdt = None;
+                return Err(e.into());
+            }
+        }
+        let subject = match rio_term(&self.terms[0].1)? {
+            RioTerm::NamedNode(n) => Subject::NamedNode(n),
+            RioTerm::BlankNode(b) => Subject::BlankNode(b),
+            _ => {
+                return Err(
+                    HdtError::InvalidTriples("literal as subject of a triple".into()).into(),
+                )
+            }
+        };
+        let predicate = match rio_term(&self.terms[1].1)? {
+            RioTerm::NamedNode(n) => n,
+            _ => {
+                return Err(
+                    HdtError::InvalidTriples("predicate of a triple is not an iri".into()).into(),
+                )
+            }
+        };
+        on_triple(RioTriple {
+            subject,
+            predicate,
+            object: rio_term(&self.terms[2].1)?,
+        })
+    }
+
+    fn is_end(&self) -> bool {
+        self.data.is_none()
+            && !matches!(&self.hdt, Some(hdt) if self.cursor.pos_z < hdt.seq_z.len)
+    }
+}
+
+/// Get rio term of a term, as it is encoded in hdt dictionary. Iris are encoded bare, blank nodes with `_:` prefix, and literals as in n-triples, but with their lexical form unescaped.
+fn rio_term(term: &str) -> Result<RioTerm<'_>, HdtError> {
+    if let Some(id) = term.strip_prefix("_:") {
+        return Ok(RioTerm::BlankNode(BlankNode { id }));
+    }
+    let quoted = match term.strip_prefix('"') {
+        Some(quoted) => quoted,
+        None => return Ok(RioTerm::NamedNode(NamedNode { iri: term })),
+    };
+    let invalid = || HdtError::InvalidTerm(term.to_owned());
+    let end = quoted.rfind('"').ok_or_else(invalid)?;
+    let (value, suffix) = (&quoted[..end], &quoted[end + 1..]);
+    Ok(RioTerm::Literal(if suffix.is_empty() {
+        Literal::Simple { value }
+    } else if let Some(language) = suffix.strip_prefix('@') {
+        Literal::LanguageTaggedString { value, language }
+    } else if let Some(iri) = suffix
+        .strip_prefix("^^<")
+        .and_then(|dt| dt.strip_suffix('>'))
+    {
+        Literal::Typed {
+            value,
+            datatype: NamedNode { iri },
+        }
+    } else {
+        return Err(invalid());
+    }))
+}
+
+/// Dictionary, and triples sections of an hdt file.
+struct Hdt {
+    dictionary: Dictionary,
+    bitmap_y: Bitmap,
+    bitmap_z: Bitmap,
+    seq_y: LogArray,
+    seq_z: LogArray,
+}
+
+impl Hdt {
+    /// Read an hdt file. Returns `None` for empty, or whitespace-only input.
+    fn read<R: BufRead>(input: &mut Input<R>) -> Result<Option<Box<Self>>, HdtError> {
+        if input.skip_whitespace()? {
+            return Ok(None);
+        }
+        input.read_control(CI_GLOBAL)?;
+
+        let header = input.read_control(CI_HEADER)?;
+        let header_length = header
+            .property("length")
+            .and_then(|l| l.parse().ok())
+            .ok_or_else(|| input.invalid("header without a valid length"))?;
+        input.read_bytes(header_length)?;
+
+        let dictionary = input.read_control(CI_DICTIONARY)?;
+        if dictionary.format != DICTIONARY_FOUR {
+            return Err(input.invalid(format!("unsupported dictionary type {}", dictionary.format)));
+        }
+        let dictionary = Dictionary {
+            shared: input.read_pfc()?,
+            subjects: input.read_pfc()?,
+            predicates: input.read_pfc()?,
+            objects: input.read_pfc()?,
+        };
+
+        let triples = input.read_control(CI_TRIPLES)?;
+        if triples.format != TRIPLES_BITMAP {
+            return Err(input.invalid(format!("unsupported triples type {}", triples.format)));
+        }
+        if triples.property("order") != Some(ORDER_SPO) {
+            return Err(input.invalid("unsupported triples order, only SPO is supported"));
+        }
+        let hdt = Self {
+            dictionary,
+            bitmap_y: input.read_bitmap()?,
+            bitmap_z: input.read_bitmap()?,
+            seq_y: input.read_log_array()?,
+            seq_z: input.read_log_array()?,
+        };
+        if hdt.bitmap_y.len != hdt.seq_y.len || hdt.bitmap_z.len != hdt.seq_z.len {
+            return Err(input.invalid("bitmaps, and sequences of triples differ in length"));
+        }
+        Ok(Some(Box::new(hdt)))
+    }
+}
+
+/// A four-section dictionary. Shared terms have ids from 1, and ids of subject-only, and object-only terms follow them. Predicates have ids from 1.
+struct Dictionary {
+    shared: Pfc,
+    subjects: Pfc,
+    predicates: Pfc,
+    objects: Pfc,
+}
+
+impl Dictionary {
+    /// Get term with given id at given position of a triple.
+    fn term(&self, position: usize, id: u64) -> Result<String, HdtError> {
+        let index = id.checked_sub(1).ok_or(HdtError::InvalidId(id))?;
+        let (section, index) = match position {
+            1 => (&self.predicates, index),
+            _ if index < self.shared.len => (&self.shared, index),
+            0 => (&self.subjects, index - self.shared.len),
+            _ => (&self.objects, index - self.shared.len),
+        };
+        section.get(index).unwrap_or(Err(HdtError::InvalidId(id)))
+    }
+}
+
+/// A plain front coded dictionary section. Strings are sorted, and stored in blocks. First string of a block is stored in full, and others as length of prefix they share with previous string, and rest of them.
+struct Pfc {
+    len: u64,
+    block_size: u64,
+    /// Offsets of blocks in `data`.
+    blocks: LogArray,
+    data: Vec<u8>,
+}
+
+impl Pfc {
+    /// Get string at given index. Returns `None` if index is out of bounds.
+    fn get(&self, index: u64) -> Option<Result<String, HdtError>> {
+        if index >= self.len {
+            return None;
+        }
+        Some(match self.decode(index) {
+            Some(bytes) => String::from_utf8(bytes).map_err(|e| {
+                HdtError::InvalidTerm(String::from_utf8_lossy(e.as_bytes()).into_owned())
+            }),
+            None => Err(HdtError::InvalidTerm(format!(
+                "corrupt dictionary entry {}",
+                index
+            ))),
+        })
+    }
+
+    fn decode(&self, index: u64) -> Option<Vec<u8>> {
+        let mut pos = usize::try_from(self.blocks.get(index / self.block_size)).ok()?;
+        let mut string = Vec::new();
+        for i in 0..=index % self.block_size {
+            if i > 0 {
+                let (prefix, len) = vbyte_at(self.data.get(pos..)?)?;
+                pos += len;
+                string.truncate(
+                    usize::try_from(prefix)
+                        .ok()
+                        .filter(|&p| p <= string.len())?,
+                );
+            }
+            let suffix = self.data.get(pos..)?;
+            let end = suffix.iter().position(|&b| b == 0)?;
+            string.extend_from_slice(&suffix[..end]);
+            pos += end + 1;
+        }
+        Some(string)
+    }
+}
+
+/// An array of unsigned integers, that are packed with fixed number of bits each.
+struct LogArray {
+    bits: u8,
+    len: u64,
+    data: Vec<u8>,
+}
+
+impl LogArray {
+    fn get(&self, index: u64) -> u64 {
+        if self.bits == 0 {
+            return 0;
+        }
+        let start = index * self.bits as u64;
+        let mut word = 0u128;
+        for (i, &b) in self.data[(start / 8) as usize..].iter().take(9).enumerate() {
+            word |= (b as u128) << (8 * i);
+        }
+        ((word >> (start % 8)) & ((1u128 << self.bits) - 1)) as u64
+    }
+}
+
+/// A plain bitmap.
+struct Bitmap {
+    len: u64,
+    data: Vec<u8>,
+}
+
+impl Bitmap {
+    fn get(&self, index: u64) -> bool {
+        self.data[(index / 8) as usize] & (1 << (index % 8)) != 0
+    }
+}
+
+/// A control information block, that precedes each section.
+struct Control {
+    format: String,
+    properties: String,
+}
+
+impl Control {
+    fn property(&self, key: &str) -> Option<&str> {
+        self.properties
+            .split(';')
+            .filter_map(|p| p.split_once('='))
+            .find_map(|(k, v)| (k == key).then_some(v))
+    }
+}
+
+/// Decode a variable length integer at start of given bytes. Hdt vbytes store 7 bits in each byte, least significant first, and set high bit of last byte. Returns value, and length of encoding.
+fn vbyte_at(bytes: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0u64;
+    for (i, &b) in bytes.iter().enumerate().take(10) {
+        value |= ((b & 0x7F) as u64).checked_shl(7 * i as u32)?;
+        if b & 0x80 != 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+/// An input adaptor, that tracks offset of read bytes, to locate errors.
+struct Input<R> {
+    inner: R,
+    offset: u64,
+}
+
+impl<R: BufRead> Input<R> {
+    fn invalid(&self, message: impl Into<String>) -> HdtError {
+        HdtError::Invalid {
+            message: message.into(),
+            offset: self.offset,
+        }
+    }
+
+    /// Skip leading ascii whitespace. Returns `true` if input ends after it.
+    fn skip_whitespace(&mut self) -> Result<bool, HdtError> {
+        loop {
+            let buf = self.inner.fill_buf()?;
+            if buf.is_empty() {
+                return Ok(true);
+            }
+            let n = buf.iter().take_while(|b| b.is_ascii_whitespace()).count();
+            let done = n < buf.len();
+            self.inner.consume(n);
+            self.offset += n as u64;
+            if done {
+                return Ok(false);
+            }
+        }
+    }
+
+    fn read_bytes(&mut self, len: u64) -> Result<Vec<u8>, HdtError> {
+        let mut bytes = Vec::new();
+        (&mut self.inner).take(len).read_to_end(&mut bytes)?;
+        self.offset += bytes.len() as u64;
+        if (bytes.len() as u64) < len {
+            return Err(HdtError::UnexpectedEof(self.offset));
+        }
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, HdtError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_vbyte(&mut self) -> Result<u64, HdtError> {
+        let mut bytes = Vec::new();
+        loop {
+            let b = self.read_u8()?;
+            bytes.push(b);
+            if b & 0x80 != 0 {
+                break;
+            }
+        }
+        vbyte_at(&bytes)
+            .map(|(v, _)| v)
+            .ok_or_else(|| self.invalid("variable length integer overflows"))
+    }
+
+    fn read_cstr(&mut self) -> Result<String, HdtError> {
+        let mut bytes = Vec::new();
+        self.inner.read_until(0, &mut bytes)?;
+        self.offset += bytes.len() as u64;
+        if bytes.pop() != Some(0) {
+            return Err(HdtError::UnexpectedEof(self.offset));
+        }
+        String::from_utf8(bytes).map_err(|_| self.invalid("control string is not valid utf-8"))
+    }
+
+    fn read_control(&mut self, type_: u8) -> Result<Control, HdtError> {
+        if self.read_bytes(COOKIE.len() as u64)? != COOKIE {
+            return Err(self.invalid("missing $HDT cookie"));
+        }
+        let actual = self.read_u8()?;
+        if actual != type_ {
+            return Err(self.invalid(format!(
+                "expected control information of type {}, found {}",
+                type_, actual
+            )));
+        }
+        let control = Control {
+            format: self.read_cstr()?,
+            properties: self.read_cstr()?,
+        };
+        // crc16
+        self.read_bytes(2)?;
+        Ok(control)
+    }
+
+    fn read_pfc(&mut self) -> Result<Pfc, HdtError> {
+        let type_ = self.read_u8()?;
+        if type_ != TYPE_PFC {
+            return Err(self.invalid(format!("unsupported dictionary section type {}", type_)));
+        }
+        let len = self.read_vbyte()?;
+        let size = self.read_vbyte()?;
+        let block_size = self.read_vbyte()?;
+        // crc8
+        self.read_u8()?;
+        if block_size == 0 {
+            return Err(self.invalid("dictionary section with zero block size"));
+        }
+        let blocks = self.read_log_array()?;
+        if blocks.len < len.div_ceil(block_size) {
+            return Err(self.invalid("dictionary section has too few blocks"));
+        }
+        let data = self.read_bytes(size)?;
+        // crc32
+        self.read_bytes(4)?;
+        Ok(Pfc {
+            len,
+            block_size,
+            blocks,
+            data,
+        })
+    }
+
+    fn read_log_array(&mut self) -> Result<LogArray, HdtError> {
+        let type_ = self.read_u8()?;
+        if type_ != TYPE_SEQ_LOG {
+            return Err(self.invalid(format!("unsupported sequence type {}", type_)));
+        }
+        let bits = self.read_u8()?;
+        if bits > 64 {
+            return Err(self.invalid(format!("sequence with {} bits per entry", bits)));
+        }
+        let len = self.read_vbyte()?;
+        // crc8
+        self.read_u8()?;
+        let size = len
+            .checked_mul(bits as u64)
+            .map(|b| b.div_ceil(8))
+            .ok_or_else(|| self.invalid("sequence is too large"))?;
+        let data = self.read_bytes(size)?;
+        // crc32c
+        self.read_bytes(4)?;
+        Ok(LogArray { bits, len, data })
+    }
+
+    fn read_bitmap(&mut self) -> Result<Bitmap, HdtError> {
+        let type_ = self.read_u8()?;
+        if type_ != TYPE_BITMAP_PLAIN {
+            return Err(self.invalid(format!("unsupported bitmap type {}", type_)));
+        }
+        let len = self.read_vbyte()?;
+        // crc8
+        self.read_u8()?;
+        let data = self.read_bytes(len.div_ceil(8))?;
+        // crc32c
+        self.read_bytes(4)?;
+        Ok(Bitmap { len, data })
+    }
+}
+
+/// An error in reading an hdt file.
+#[derive(Debug, thiserror::Error)]
+pub enum HdtError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("invalid hdt file: {message}, at byte {offset}")]
+    Invalid { message: String, offset: u64 },
+    #[error("unexpected end of hdt file, at byte {0}")]
+    UnexpectedEof(u64),
+    #[error("invalid triples in hdt file: {0}")]
+    InvalidTriples(String),
+    #[error("hdt triple refers to unknown dictionary id {0}")]
+    InvalidId(u64),
+    #[error("invalid term in hdt dictionary: {0}")]
+    InvalidTerm(String),
+}
+
+impl ParseError for HdtError {
+    /// Hdt files are binary, and have no textual positions. Errors in reading sections are located by their byte offset instead.
+    fn textual_position(&self) -> Option<LineBytePosition> {
+        None
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use claim::{assert_matches, assert_ok};
+    use once_cell::sync::Lazy;
+    use sophia_api::{
+        graph::{isomorphic_graphs, Graph},
+        parser::TripleParser,
+        triple::stream::{StreamError, TripleSource},
+    };
+    use sophia_inmem::graph::FastGraph;
+    use sophia_turtle::parser::turtle;
+    use test_case::test_case;
+
+    use super::{HdtError, HdtParser, DICTIONARY_FOUR, TRIPLES_BITMAP};
+    use crate::tests::TRACING;
+
+    fn vbyte(out: &mut Vec<u8>, mut v: u64) {
+        while v > 127 {
+            out.push((v & 127) as u8);
+            v >>= 7;
+        }
+        out.push(v as u8 | 0x80);
+    }
+
+    fn control(out: &mut Vec<u8>, type_: u8, format: &str, properties: &str) {
+        out.extend_from_slice(b"$HDT");
+        out.push(type_);
+        for s in [format, properties] {
+            out.extend_from_slice(s.as_bytes());
+            out.push(0);
+        }
+        out.extend_from_slice(&[0; 2]);
+    }
+
+    fn log_array(out: &mut Vec<u8>, values: &[u64]) {
+        let bits = values
+            .iter()
+            .max()
+            .map_or(1, |&m| 64 - m.leading_zeros().min(63)) as usize;
+        out.extend_from_slice(&[1, bits as u8]);
+        vbyte(out, values.len() as u64);
+        out.push(0);
+        let mut data = vec![0u8; (bits * values.len()).div_ceil(8)];
+        for (i, v) in values.iter().enumerate() {
+            for b in 0..bits {
+                if v >> b & 1 == 1 {
+                    let pos = i * bits + b;
+                    data[pos / 8] |= 1 << (pos % 8);
+                }
+            }
+        }
+        out.extend_from_slice(&data);
+        out.extend_from_slice(&[0; 4]);
+    }
+
+    fn bitmap(out: &mut Vec<u8>, bits: &[bool]) {
+        out.push(1);
+        vbyte(out, bits.len() as u64);
+        out.push(0);
+        let mut data = vec![0u8; bits.len().div_ceil(8)];
+        for (i, _) in bits.iter().enumerate().filter(|(_, &b)| b) {
+            data[i / 8] |= 1 << (i % 8);
+        }
+        out.extend_from_slice(&data);
+        out.extend_from_slice(&[0; 4]);
+    }
+
+    fn pfc(out: &mut Vec<u8>, strings: &[&str], block_size: usize) {
+        let mut blocks = Vec::new();
+        let mut data = Vec::new();
+        for (i, s) in strings.iter().enumerate() {
+            if i % block_size == 0 {
+                blocks.push(data.len() as u64);
+                data.extend_from_slice(s.as_bytes());
+            } else {
+                let prev = strings[i - 1].as_bytes();
+                let prefix = prev
+                    .iter()
+                    .zip(s.as_bytes())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                vbyte(&mut data, prefix as u64);
+                data.extend_from_slice(&s.as_bytes()[prefix..]);
+            }
+            data.push(0);
+        }
+        blocks.push(data.len() as u64);
+        out.push(2);
+        vbyte(out, strings.len() as u64);
+        vbyte(out, data.len() as u64);
+        vbyte(out, block_size as u64);
+        out.push(0);
+        log_array(out, &blocks);
+        out.extend_from_slice(&data);
+        out.extend_from_slice(&[0; 4]);
+    }
+
+    /// Write an hdt file with given triples, whose terms are encoded as in hdt dictionaries.
+    fn write_hdt(triples: &[[&str; 3]]) -> Vec<u8> {
+        let subjects: BTreeSet<&str> = triples.iter().map(|t| t[0]).collect();
+        let predicates: BTreeSet<&str> = triples.iter().map(|t| t[1]).collect();
+        let objects: BTreeSet<&str> = triples.iter().map(|t| t[2]).collect();
+        let shared: Vec<&str> = subjects.intersection(&objects).copied().collect();
+        let subjects: Vec<&str> = subjects.difference(&objects).copied().collect();
+        let objects: Vec<&str> = objects
+            .iter()
+            .filter(|o| !shared.contains(o))
+            .copied()
+            .collect();
+        let predicates: Vec<&str> = predicates.into_iter().collect();
+        let so_id = |t: &str, section: &[&str]| match shared.iter().position(|s| *s == t) {
+            Some(i) => i as u64 + 1,
+            None => (shared.len() + section.iter().position(|s| *s == t).unwrap()) as u64 + 1,
+        };
+        let ids: BTreeSet<(u64, u64, u64)> = triples
+            .iter()
+            .map(|t| {
+                (
+                    so_id(t[0], &subjects),
+                    predicates.iter().position(|p| *p == t[1]).unwrap() as u64 + 1,
+                    so_id(t[2], &objects),
+                )
+            })
+            .collect();
+        let ids: Vec<_> = ids.into_iter().collect();
+
+        let (mut seq_y, mut bitmap_y, mut seq_z, mut bitmap_z) = (vec![], vec![], vec![], vec![]);
+        for (i, &(s, p, o)) in ids.iter().enumerate() {
+            let next = ids.get(i + 1);
+            if i == 0 || ids[i - 1].0 != s || ids[i - 1].1 != p {
+                seq_y.push(p);
+            }
+            let last_of_pair = !matches!(next, Some(n) if n.0 == s && n.1 == p);
+            seq_z.push(o);
+            bitmap_z.push(last_of_pair);
+            if last_of_pair {
+                bitmap_y.push(!matches!(next, Some(n) if n.0 == s));
+            }
+        }
+
+        let mut out = Vec::new();
+        control(&mut out, 1, "<http://purl.org/HDT/hdt#HDTv1>", "");
+        let header = "<http://example.org/dataset> <http://rdfs.org/ns/void#triples> \"1\" .\n";
+        control(
+            &mut out,
+            2,
+            "ntriples",
+            &format!("length={};", header.len()),
+        );
+        out.extend_from_slice(header.as_bytes());
+        control(&mut out, 3, DICTIONARY_FOUR, "mapping=1;sizeStrings=0;");
+        for section in [&shared, &subjects, &predicates, &objects] {
+            pfc(&mut out, section, 2);
+        }
+        control(
+            &mut out,
+            4,
+            TRIPLES_BITMAP,
+            &format!("order=1;numTriples={};", ids.len()),
+        );
+        bitmap(&mut out, &bitmap_y);
+        bitmap(&mut out, &bitmap_z);
+        log_array(&mut out, &seq_y);
+        log_array(&mut out, &seq_z);
+        out
+    }
+
+    static TRIPLES: &[[&str; 3]] = &[
+        [
+            "http://example.org/alice",
+            "http://xmlns.com/foaf/0.1/knows",
+            "_:b1",
+        ],
+        [
+            "http://example.org/alice",
+            "http://xmlns.com/foaf/0.1/knows",
+            "http://example.org/bob",
+        ],
+        [
+            "http://example.org/alice",
+            "http://xmlns.com/foaf/0.1/name",
+            "\"Alice \"A\"\"@en",
+        ],
+        [
+            "http://example.org/bob",
+            "http://xmlns.com/foaf/0.1/age",
+            "\"42\"^^<http://www.w3.org/2001/XMLSchema#integer>",
+        ],
+        [
+            "http://example.org/bob",
+            "http://xmlns.com/foaf/0.1/name",
+            "\"Bob\"",
+        ],
+        ["_:b1", "http://xmlns.com/foaf/0.1/name", "\"Café\""],
+        ["_:b1", "http://xmlns.com/foaf/0.1/nick", "\"Cafè\""],
+    ];
+
+    static TURTLE_DOC: &str = r#"
+        @prefix foaf: <http://xmlns.com/foaf/0.1/> .
+        <http://example.org/alice> foaf:knows _:b1, <http://example.org/bob> ;
+            foaf:name "Alice \"A\""@en .
+        <http://example.org/bob> foaf:age 42 ; foaf:name "Bob" .
+        _:b1 foaf:name "Café" ; foaf:nick "Cafè" .
+    "#;
+
+    #[test]
+    pub fn reads_triples_of_hdt_files() {
+        Lazy::force(&TRACING);
+        let hdt = write_hdt(TRIPLES);
+        let g1: FastGraph = HdtParser::default()
+            .parse(&hdt[..])
+            .collect_triples()
+            .unwrap();
+        let g2: FastGraph = turtle::parse_str(TURTLE_DOC).collect_triples().unwrap();
+        assert_eq!(g1.triples().count(), TRIPLES.len());
+        assert!(isomorphic_graphs(&g1, &g2).unwrap());
+    }
+
+    #[cfg(feature = "hdt")]
+    #[test]
+    pub fn dynsyn_parsers_read_hdt_files() {
+        use sophia_term::BoxTerm;
+
+        use crate::{parser::triples::DynSynTripleParserFactory, syntax};
+
+        Lazy::force(&TRACING);
+        let hdt = write_hdt(TRIPLES);
+        let parser = DynSynTripleParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::HDT, None, None)
+            .unwrap();
+        let g: FastGraph = parser.parse(&hdt[..]).collect_triples().unwrap();
+        assert_eq!(g.triples().count(), TRIPLES.len());
+    }
+
+    #[test_case(b"" ; "empty")]
+    #[test_case(b" \n\t" ; "whitespace")]
+    pub fn empty_inputs_have_no_triples(data: &[u8]) {
+        Lazy::force(&TRACING);
+        let g: FastGraph = HdtParser::default().parse(data).collect_triples().unwrap();
+        assert_eq!(g.triples().count(), 0);
+    }
+
+    #[test]
+    pub fn files_without_triples_have_no_triples() {
+        Lazy::force(&TRACING);
+        let hdt = write_hdt(&[]);
+        let g: FastGraph = assert_ok!(HdtParser::default().parse(&hdt[..]).collect_triples());
+        assert_eq!(g.triples().count(), 0);
+    }
+
+    fn parse_error(data: &[u8]) -> HdtError {
+        match HdtParser::default()
+            .parse(data)
+            .collect_triples::<FastGraph>()
+        {
+            Err(StreamError::SourceError(e)) => e,
+            r => panic!("expected a source error, found {:?}", r.map(|_| ())),
+        }
+    }
+
+    #[test_case(10)]
+    #[test_case(60)]
+    #[test_case(200)]
+    #[test_case(1)]
+    pub fn truncated_files_will_error(cut: usize) {
+        Lazy::force(&TRACING);
+        let hdt = write_hdt(TRIPLES);
+        assert_matches!(
+            parse_error(&hdt[..hdt.len() - cut]),
+            HdtError::UnexpectedEof(_)
+        );
+    }
+
+    #[test_case("$HDT", "$HDX", "missing $HDT cookie, at byte 4")]
+    #[test_case(
+        DICTIONARY_FOUR,
+        "<http://purl.org/HDT/hdt#dictionaryFourPsfc>",
+        "unsupported dictionary type"
+    )]
+    #[test_case("order=1", "order=4", "unsupported triples order")]
+    pub fn unsupported_files_will_error(needle: &str, replacement: &str, message: &str) {
+        Lazy::force(&TRACING);
+        let mut hdt = write_hdt(TRIPLES);
+        let start = hdt
+            .windows(needle.len())
+            .position(|w| w == needle.as_bytes())
+            .unwrap();
+        hdt.splice(start..start + needle.len(), replacement.bytes());
+        let e = parse_error(&hdt);
+        assert!(e.to_string().contains(message), "{}", e);
+    }
+}
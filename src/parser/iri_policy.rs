@@ -0,0 +1,365 @@
+//! This module defines an iri policy, that parsers can be configured with, to reject statements referencing disallowed iri schemes, or hosts, like `file:`, `jar:`, or internal host names. It protects services, that dereference iris of parsed untrusted content, from being steered at local, or internal resources.
+//!
+//! Policy is configured by inserting an [`IriPolicy`] into `parser_config_map` of parser factories. It is checked against iri terms of each statement, after they are resolved by configured [`DynIriResolver`](crate::iri_resolver::DynIriResolver), if any. Datatype iris of literals are not checked.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{
+//!     error_code::{self, ErrorCode},
+//!     parser::{iri_policy::IriPolicy, limits::LimitAction, triples::DynSynTripleParserFactory},
+//!     syntax,
+//! };
+//! use sophia_api::{parser::TripleParser, triple::stream::TripleSource};
+//! use sophia_term::BoxTerm;
+//! use type_map::concurrent::TypeMap;
+//!
+//! let mut config_map = TypeMap::new();
+//! config_map.insert(IriPolicy::untrusted(LimitAction::Error).with_denied_host("internal.example"));
+//! let parser = DynSynTripleParserFactory::new(Some(config_map))
+//!     .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)
+//!     .unwrap();
+//!
+//! let doc = "<http://example.org/a> <http://example.org/p> <http://db.internal.example/x> .";
+//! let e = parser.parse_str(doc).for_each_triple(|_| {}).unwrap_err();
+//! assert_eq!(e.code(), error_code::DYNSYN_IRI_POLICY_VIOLATION);
+//! ```
+
+use std::{collections::HashSet, fmt::Display};
+
+use sophia_api::term::{TTerm, TermKind};
+
+use super::limits::LimitAction;
+
+/// A policy of iri schemes, and hosts, that statements of parsed documents can reference.
+///
+/// Schemes, and hosts are matched case-insensitively. A host entry matches that host, and all of it's subdomains. Iris without an authority, like `urn:` iris, are checked against scheme lists only.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IriPolicy {
+    /// If set, only iris with these schemes are allowed.
+    pub allowed_schemes: Option<HashSet<String>>,
+    /// Iris with these schemes are denied.
+    pub denied_schemes: HashSet<String>,
+    /// If set, only iris with authority, whose host matches one of these hosts are allowed.
+    pub allowed_hosts: Option<HashSet<String>>,
+    /// Iris with authority, whose host matches one of these hosts are denied.
+    pub denied_hosts: HashSet<String>,
+    /// Action to take, when a statement violates the policy.
+    pub on_violation: LimitAction,
+}
+
+impl IriPolicy {
+    /// Create a new policy, that allows all iris.
+    pub fn new(on_violation: LimitAction) -> Self {
+        Self {
+            allowed_schemes: None,
+            denied_schemes: HashSet::new(),
+            allowed_hosts: None,
+            denied_hosts: HashSet::new(),
+            on_violation,
+        }
+    }
+
+    /// Create a new policy for parsing untrusted content. It denies `file`, and `jar` schemes, and `localhost` host.
+    pub fn untrusted(on_violation: LimitAction) -> Self {
+        Self::new(on_violation)
+            .with_denied_scheme("file")
+            .with_denied_scheme("jar")
+            .with_denied_host("localhost")
+    }
+
+    /// Add a scheme to allow list. Once a scheme is allowed, all schemes, that are not in allow list are denied.
+    pub fn with_allowed_scheme(mut self, scheme: &str) -> Self {
+        self.allowed_schemes
+            .get_or_insert_with(HashSet::new)
+            .insert(scheme.to_ascii_lowercase());
+        self
+    }
+
+    /// Add a scheme to deny list.
+    pub fn with_denied_scheme(mut self, scheme: &str) -> Self {
+        self.denied_schemes.insert(scheme.to_ascii_lowercase());
+        self
+    }
+
+    /// Add a host to allow list. Once a host is allowed, iris with authority, whose host doesn't match any of allow list are denied.
+    pub fn with_allowed_host(mut self, host: &str) -> Self {
+        self.allowed_hosts
+            .get_or_insert_with(HashSet::new)
+            .insert(host.to_ascii_lowercase());
+        self
+    }
+
+    /// Add a host to deny list.
+    pub fn with_denied_host(mut self, host: &str) -> Self {
+        self.denied_hosts.insert(host.to_ascii_lowercase());
+        self
+    }
+
+    /// Check given iri against this policy.
+    ///
+    /// # Errors
+    /// returns [`IriPolicyViolation`], if iri references a disallowed scheme, or host.
+    pub fn check(&self, iri: &str) -> Result<(), IriPolicyViolation> {
+        let violation = |reason| IriPolicyViolation {
+            iri: iri.to_string(),
+            reason,
+        };
+        let (scheme, rest) = match iri.split_once(':') {
+            Some((scheme, rest)) => (scheme.to_ascii_lowercase(), rest),
+            // Relative iris can't reference a resource by themselves.
+            None => return Ok(()),
+        };
+        if self.denied_schemes.contains(&scheme)
+            || matches!(&self.allowed_schemes, Some(allowed) if !allowed.contains(&scheme))
+        {
+            return Err(violation(IriPolicyViolationReason::Scheme(scheme)));
+        }
+        let host = match host_of(rest) {
+            Some(host) => host,
+            None => return Ok(()),
+        };
+        if self.denied_hosts.iter().any(|h| host_matches(&host, h))
+            || matches!(
+                &self.allowed_hosts,
+                Some(allowed) if !allowed.iter().any(|h| host_matches(&host, h))
+            )
+        {
+            return Err(violation(IriPolicyViolationReason::Host(host)));
+        }
+        Ok(())
+    }
+}
+
+/// Get lowercased host of given hierarchical part of an iri, if it has an authority.
+fn host_of(hier_part: &str) -> Option<String> {
+    let authority = hier_part.strip_prefix("//")?;
+    let authority = authority
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default();
+    let host_port = authority.rsplit('@').next().unwrap_or_default();
+    let host = match host_port.strip_prefix('[') {
+        // Ip literal
+        Some(literal) => literal.split(']').next().unwrap_or_default(),
+        None => host_port.split(':').next().unwrap_or_default(),
+    };
+    Some(host.trim_end_matches('.').to_ascii_lowercase())
+}
+
+/// Returns true, if `host` is same as `entry`, or is a subdomain of it.
+fn host_matches(host: &str, entry: &str) -> bool {
+    host == entry
+        || (host.len() > entry.len()
+            && host.ends_with(entry)
+            && host.as_bytes()[host.len() - entry.len() - 1] == b'.')
+}
+
+/// Reason of an [`IriPolicyViolation`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum IriPolicyViolationReason {
+    /// Iri has disallowed scheme.
+    Scheme(String),
+    /// Iri has disallowed host.
+    Host(String),
+}
+
+/// A violation of an [`IriPolicy`] by an iri.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IriPolicyViolation {
+    /// Violating iri.
+    pub iri: String,
+    /// Reason of violation.
+    pub reason: IriPolicyViolationReason,
+}
+
+impl Display for IriPolicyViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.reason {
+            IriPolicyViolationReason::Scheme(scheme) => {
+                write!(f, "iri <{}> has disallowed scheme `{}`", self.iri, scheme)
+            }
+            IriPolicyViolationReason::Host(host) => {
+                write!(f, "iri <{}> has disallowed host `{}`", self.iri, host)
+            }
+        }
+    }
+}
+
+/// A guard that checks statements in a stream against an [`IriPolicy`].
+#[derive(Debug)]
+pub(crate) struct IriPolicyGuard {
+    policy: IriPolicy,
+    violation: Option<IriPolicyViolation>,
+}
+
+impl IriPolicyGuard {
+    pub fn new(policy: IriPolicy) -> Self {
+        Self {
+            policy,
+            violation: None,
+        }
+    }
+
+    /// Returns true, if a statement with given terms can be admitted into stream. Returns false only if statement violates policy with [`LimitAction::Error`] action, or if such a violation has already occurred.
+    pub fn admit<'t, T, I>(&mut self, terms: I) -> bool
+    where
+        T: TTerm + ?Sized + 't,
+        I: IntoIterator<Item = &'t T>,
+    {
+        if self.must_fail() {
+            return false;
+        }
+        let violation = terms
+            .into_iter()
+            .filter(|t| t.kind() == TermKind::Iri)
+            .find_map(|t| self.policy.check(&t.value()).err());
+        let violation = match violation {
+            Some(v) => v,
+            None => return true,
+        };
+        match self.policy.on_violation {
+            LimitAction::Error => {
+                self.violation = Some(violation);
+                false
+            }
+            LimitAction::Warn => {
+                if self.violation.is_none() {
+                    tracing::warn!("Parsed statement violates iri policy: {}", violation);
+                    self.violation = Some(violation);
+                }
+                true
+            }
+        }
+    }
+
+    /// Returns true, if stream must be failed for a violation.
+    pub fn must_fail(&self) -> bool {
+        self.violation.is_some() && self.policy.on_violation == LimitAction::Error
+    }
+
+    /// Returns first violation, if any.
+    pub fn violation(&self) -> Option<&IriPolicyViolation> {
+        self.violation.as_ref()
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use claim::{assert_err, assert_ok};
+    use once_cell::sync::Lazy;
+    use sophia_api::{
+        parser::{QuadParser, TripleParser},
+        quad::stream::QuadSource,
+        triple::stream::TripleSource,
+    };
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+    use type_map::concurrent::TypeMap;
+
+    use super::{IriPolicy, IriPolicyViolation, IriPolicyViolationReason};
+    use crate::{
+        error_code::{self, ErrorCode},
+        parser::{
+            limits::LimitAction, quads::DynSynQuadParserFactory,
+            triples::DynSynTripleParserFactory,
+        },
+        syntax,
+        tests::TRACING,
+    };
+
+    static DOC: &str = r#"
+        @prefix : <http://example.org/> .
+        :a :p :b .
+        :a :p <file:///etc/passwd> .
+        :a :p :c .
+    "#;
+
+    #[test_case("http://example.org/a", true)]
+    #[test_case("FILE:///etc/passwd", false)]
+    #[test_case("jar:file:///x.jar!/a", false)]
+    #[test_case("http://localhost:8080/admin", false)]
+    #[test_case("http://user@LocalHost./admin", false)]
+    #[test_case("http://db.internal.example/x", false)]
+    #[test_case("http://notinternal.example/x", true)]
+    #[test_case("urn:uuid:6e8bc430-9c3a-11d9-9669-0800200c9a66", true)]
+    #[test_case("relative/path", true)]
+    pub fn checks_untrusted_policy(iri: &str, allowed: bool) {
+        Lazy::force(&TRACING);
+        let policy = IriPolicy::untrusted(LimitAction::Error).with_denied_host("internal.example");
+        assert_eq!(policy.check(iri).is_ok(), allowed);
+    }
+
+    #[test]
+    pub fn checks_allow_lists() {
+        Lazy::force(&TRACING);
+        let policy = IriPolicy::new(LimitAction::Error)
+            .with_allowed_scheme("https")
+            .with_allowed_scheme("urn")
+            .with_allowed_host("example.org");
+        assert_ok!(policy.check("https://example.org/a"));
+        assert_ok!(policy.check("https://data.example.org/a"));
+        assert_ok!(policy.check("urn:isbn:0451450523"));
+        assert_eq!(
+            policy.check("http://example.org/a"),
+            Err(IriPolicyViolation {
+                iri: "http://example.org/a".into(),
+                reason: IriPolicyViolationReason::Scheme("http".into()),
+            })
+        );
+        assert_eq!(
+            policy.check("https://[::1]:80/a").unwrap_err().reason,
+            IriPolicyViolationReason::Host("::1".into())
+        );
+        assert_err!(policy.check("https://10.0.0.1/a"));
+    }
+
+    #[test]
+    pub fn violations_fail_parse_with_error_action() {
+        Lazy::force(&TRACING);
+        let mut config_map = TypeMap::new();
+        config_map.insert(IriPolicy::untrusted(LimitAction::Error));
+        let mut count = 0;
+        let e = DynSynTripleParserFactory::new(Some(config_map))
+            .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)
+            .unwrap()
+            .parse_str(DOC)
+            .for_each_triple(|_| count += 1)
+            .unwrap_err();
+        assert_eq!(e.code(), error_code::DYNSYN_IRI_POLICY_VIOLATION);
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    pub fn violations_are_warned_with_warn_action() {
+        Lazy::force(&TRACING);
+        let mut config_map = TypeMap::new();
+        config_map.insert(IriPolicy::untrusted(LimitAction::Warn));
+        let mut count = 0;
+        DynSynQuadParserFactory::new(Some(config_map))
+            .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)
+            .unwrap()
+            .parse_str(DOC)
+            .for_each_quad(|_| count += 1)
+            .unwrap();
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    pub fn checks_graph_names_of_quads() {
+        Lazy::force(&TRACING);
+        let mut config_map = TypeMap::new();
+        config_map.insert(IriPolicy::new(LimitAction::Error).with_denied_scheme("file"));
+        let e = DynSynQuadParserFactory::new(Some(config_map))
+            .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+            .unwrap()
+            .parse_str("<tag:s> <tag:p> <tag:o> <file:///g> .\n")
+            .for_each_quad(|_| {})
+            .unwrap_err();
+        assert_eq!(e.code(), error_code::DYNSYN_IRI_POLICY_VIOLATION);
+        assert!(e.to_string().contains("file:///g"));
+    }
+}
@@ -0,0 +1,322 @@
+//! This module defines iri validation modes of dynsyn parsers, for line-based syntaxes, [`n-triples`](crate::syntax::N_TRIPLES), and [`n-quads`](crate::syntax::N_QUADS). Their underlying parsers reject relative iris. Hence dynsyn parsers resolve relative iri references of such documents against base iri of the parser, as lines are read, before underlying parser reads them, and validate parsed iris as per configured [`IriValidation`] mode. Byte positions of parse errors refer to lines with their iri references resolved.
+//!
+//! Mode can be configured with a [`ParserConfig`](super::recovery::ParserConfig) in factory's config map, or with `set_iri_validation` method of parsers. In [`Strict`](IriValidation::Strict) mode, parsed statements are guaranteed to have only absolute, valid iris, and statements with other iris fail with an error. In [`Lax`](IriValidation::Lax) mode, which is the default, iris that can't be resolved are passed to underlying parser as they are, which still rejects those that are not absolute. Turtle family parsers always resolve, and validate iris themselves.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{
+//!     parser::{iri_validation::IriValidation, triples::DynSynTripleParserFactory},
+//!     syntax,
+//! };
+//! use sophia_api::{graph::Graph, parser::TripleParser, term::TTerm, triple::{stream::TripleSource, Triple}};
+//! use sophia_inmem::graph::FastGraph;
+//! use sophia_term::BoxTerm;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let doc = "<#me> <http://xmlns.com/foaf/0.1/name> \"Alice\" .";
+//! let mut parser = DynSynTripleParserFactory::default()
+//!     .try_new_parser::<BoxTerm>(syntax::N_TRIPLES, Some("http://example.org/doc".into()), None)?;
+//! parser.set_iri_validation(IriValidation::Strict);
+//!
+//! let graph: FastGraph = parser.parse_str(doc).collect_triples()?;
+//! let t = graph.triples().next().unwrap()?;
+//! assert_eq!(t.s().value(), "http://example.org/doc#me");
+//!
+//! // Without a base, relative iris can't be resolved.
+//! assert!(parser.parse_with_base(doc.as_bytes(), None).for_each_triple(|_| {}).is_err());
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::borrow::Cow;
+
+use rio_api::model::{GraphName, Literal, NamedNode, Quad, Subject, Term, Triple};
+use sophia_iri::{
+    is_absolute_iri_ref, is_relative_iri_ref,
+    resolve::{IriParsed, Resolve},
+};
+
+use super::rdf_star::RdfStarError;
+
+/// Mode of validating iris of parsed statements.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum IriValidation {
+    /// Relative iris are resolved against base iri, if any. Iris that can't be resolved, or are invalid are kept as they are.
+    #[default]
+    Lax,
+    /// Relative iris are resolved against base iri. Statements with iris, that are not absolute, valid iris after resolution are rejected with an error.
+    Strict,
+}
+
+/// Resolves, and validates iris of statements of a single document.
+#[derive(Debug, Clone)]
+pub(crate) struct IriNormalizer {
+    mode: IriValidation,
+    base_iri: Option<String>,
+}
+
+impl IriNormalizer {
+    pub(crate) fn new(mode: IriValidation, base_iri: Option<String>) -> Self {
+        Self { mode, base_iri }
+    }
+
+    /// Returns given iri, resolved against base iri if it is relative. Returns `None` if it is not an absolute, valid iri after resolution in strict mode.
+    fn normalized<'a>(&self, iri: &'a str) -> Option<Cow<'a, str>> {
+        if is_absolute_iri_ref(iri) {
+            return Some(Cow::Borrowed(iri));
+        }
+        let resolved = self
+            .base_iri
+            .as_deref()
+            .filter(|_| is_relative_iri_ref(iri))
+            .and_then(|base| IriParsed::new(base).ok())
+            .and_then(|base| {
+                let resolved: Result<_, _> = base.resolve(iri);
+                resolved.ok().map(|iri| iri.to_string())
+            })
+            .filter(|resolved| is_absolute_iri_ref(resolved));
+        match (resolved, self.mode) {
+            (Some(resolved), _) => Some(Cow::Owned(resolved)),
+            (None, IriValidation::Lax) => Some(Cow::Borrowed(iri)),
+            (None, IriValidation::Strict) => None,
+        }
+    }
+
+    /// Calls `emit` with given triple, with it's iris normalized. Triple is passed through as it is, if `normalizer` is `None`.
+    pub(crate) fn normalize_triple<E>(
+        normalizer: Option<&Self>,
+        t: Triple<'_>,
+        emit: &mut impl FnMut(Triple<'_>) -> Result<(), E>,
+    ) -> Result<(), E>
+    where
+        E: From<RdfStarError>,
+    {
+        let normalizer = match normalizer {
+            Some(normalizer) => normalizer,
+            None => return emit(t),
+        };
+        let normalized = |iri| {
+            normalizer
+                .normalized(iri)
+                .ok_or_else(|| RdfStarError::InvalidIri(t.to_string()))
+        };
+        let s = match t.subject {
+            Subject::NamedNode(n) => Some(normalized(n.iri)?),
+            _ => None,
+        };
+        let p = normalized(t.predicate.iri)?;
+        let o = match t.object {
+            Term::NamedNode(NamedNode { iri })
+            | Term::Literal(Literal::Typed {
+                datatype: NamedNode { iri },
+                ..
+            }) => Some(normalized(iri)?),
+            _ => None,
+        };
+        emit(Triple {
+            subject: match (t.subject, &s) {
+                (Subject::NamedNode(_), Some(iri)) => Subject::NamedNode(NamedNode { iri }),
+                (subject, _) => subject,
+            },
+            predicate: NamedNode { iri: &p },
+            object: match (t.object, &o) {
+                (Term::NamedNode(_), Some(iri)) => Term::NamedNode(NamedNode { iri }),
+                (Term::Literal(Literal::Typed { value, .. }), Some(iri)) => {
+                    Term::Literal(Literal::Typed {
+                        value,
+                        datatype: NamedNode { iri },
+                    })
+                }
+                (object, _) => object,
+            },
+        })
+    }
+
+    /// Calls `emit` with given quad, with it's graph name normalized. Quad is passed through as it is, if `normalizer` is `None`.
+    pub(crate) fn normalize_graph_name<E>(
+        normalizer: Option<&Self>,
+        q: Quad<'_>,
+        emit: &mut impl FnMut(Quad<'_>) -> Result<(), E>,
+    ) -> Result<(), E>
+    where
+        E: From<RdfStarError>,
+    {
+        let g = match (normalizer, q.graph_name) {
+            (Some(normalizer), Some(GraphName::NamedNode(n))) => Some(
+                normalizer
+                    .normalized(n.iri)
+                    .ok_or_else(|| RdfStarError::InvalidIri(q.to_string()))?,
+            ),
+            _ => None,
+        };
+        emit(Quad {
+            graph_name: match &g {
+                Some(iri) => Some(GraphName::NamedNode(NamedNode { iri })),
+                None => q.graph_name,
+            },
+            ..q
+        })
+    }
+}
+
+/// Resolves relative iri references of lines of a line-based document against a base iri, before they are read by underlying parser, which rejects relative iris.
+#[derive(Debug, Clone)]
+pub(crate) struct LineIriResolver {
+    normalizer: IriNormalizer,
+    /// Current line, with it's relative iri references resolved.
+    line: Vec<u8>,
+    /// Position of next unread byte of current line.
+    pos: usize,
+}
+
+impl LineIriResolver {
+    pub(crate) fn new(base_iri: String) -> Self {
+        Self {
+            normalizer: IriNormalizer::new(IriValidation::Lax, Some(base_iri)),
+            line: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Returns unread bytes of current line.
+    pub(crate) fn pending(&self) -> &[u8] {
+        &self.line[self.pos..]
+    }
+
+    /// Marks given number of bytes of current line as read.
+    pub(crate) fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.line.len());
+    }
+
+    /// Sets current line, with relative iri references of given raw line resolved. Iri references are recognized outside of string literals, and comments.
+    pub(crate) fn set_line(&mut self, raw: &[u8]) {
+        self.line.clear();
+        self.pos = 0;
+        let mut i = 0;
+        while i < raw.len() {
+            match raw[i] {
+                b'#' => {
+                    self.line.extend_from_slice(&raw[i..]);
+                    return;
+                }
+                b'"' => {
+                    let end = Self::string_end(raw, i + 1);
+                    self.line.extend_from_slice(&raw[i..end]);
+                    i = end;
+                }
+                b'<' if raw.get(i + 1) == Some(&b'<') => {
+                    self.line.extend_from_slice(b"<<");
+                    i += 2;
+                }
+                b'<' => match raw[i + 1..].iter().position(|b| *b == b'>') {
+                    Some(len) => {
+                        let iri = &raw[i + 1..i + 1 + len];
+                        self.line.push(b'<');
+                        match std::str::from_utf8(iri)
+                            .ok()
+                            .and_then(|iri| self.normalizer.normalized(iri))
+                        {
+                            Some(resolved) => self.line.extend_from_slice(resolved.as_bytes()),
+                            None => self.line.extend_from_slice(iri),
+                        }
+                        self.line.push(b'>');
+                        i += len + 2;
+                    }
+                    None => {
+                        self.line.extend_from_slice(&raw[i..]);
+                        return;
+                    }
+                },
+                b => {
+                    self.line.push(b);
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    /// Returns position after closing quote of a string literal, whose content starts at `start`, or end of line, if it is not closed.
+    fn string_end(raw: &[u8], start: usize) -> usize {
+        let mut i = start;
+        while i < raw.len() {
+            match raw[i] {
+                b'\\' => i += 2,
+                b'"' => return i + 1,
+                _ => i += 1,
+            }
+        }
+        raw.len()
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use test_case::test_case;
+
+    use super::{IriNormalizer, IriValidation, LineIriResolver};
+    use crate::tests::TRACING;
+
+    #[test_case(IriValidation::Lax, None, "http://ex.org/a", Some("http://ex.org/a"))]
+    #[test_case(
+        IriValidation::Strict,
+        None,
+        "http://ex.org/a",
+        Some("http://ex.org/a")
+    )]
+    #[test_case(
+        IriValidation::Lax,
+        Some("http://ex.org/doc"),
+        "#a",
+        Some("http://ex.org/doc#a")
+    )]
+    #[test_case(
+        IriValidation::Strict,
+        Some("http://ex.org/doc"),
+        "#a",
+        Some("http://ex.org/doc#a")
+    )]
+    #[test_case(IriValidation::Lax, None, "#a", Some("#a"))]
+    #[test_case(IriValidation::Strict, None, "#a", None)]
+    #[test_case(
+        IriValidation::Lax,
+        Some("http://ex.org/doc"),
+        "http://ex.org/a b",
+        Some("http://ex.org/a b")
+    )]
+    #[test_case(
+        IriValidation::Strict,
+        Some("http://ex.org/doc"),
+        "http://ex.org/a b",
+        None
+    )]
+    pub fn normalizes_iris_as_per_mode(
+        mode: IriValidation,
+        base_iri: Option<&str>,
+        iri: &str,
+        expected: Option<&str>,
+    ) {
+        Lazy::force(&TRACING);
+        let normalizer = IriNormalizer::new(mode, base_iri.map(String::from));
+        assert_eq!(normalizer.normalized(iri).as_deref(), expected);
+    }
+
+    #[test_case("<#s> <http://ex.org/p> \"v\"^^<#dt> <#g> .\n", "<http://ex.org/doc#s> <http://ex.org/p> \"v\"^^<http://ex.org/doc#dt> <http://ex.org/doc#g> .\n"; "iri references")]
+    #[test_case("<#s> <#p> \"<#not-an-iri> \\\" <#x>\" . # <#comment>\n", "<http://ex.org/doc#s> <http://ex.org/doc#p> \"<#not-an-iri> \\\" <#x>\" . # <#comment>\n"; "literals and comments")]
+    #[test_case("<< <#s> <#p> <#o> >> <#q> <#r> .", "<< <http://ex.org/doc#s> <http://ex.org/doc#p> <http://ex.org/doc#o> >> <http://ex.org/doc#q> <http://ex.org/doc#r> ."; "quoted triples")]
+    #[test_case("<#s> <#p", "<http://ex.org/doc#s> <#p"; "unclosed iri reference")]
+    pub fn resolves_iri_references_of_lines(raw: &str, expected: &str) {
+        Lazy::force(&TRACING);
+        let mut resolver = LineIriResolver::new("http://ex.org/doc".into());
+        resolver.set_line(raw.as_bytes());
+        assert_eq!(std::str::from_utf8(resolver.pending()).unwrap(), expected);
+        resolver.consume(5);
+        assert_eq!(resolver.pending(), &expected.as_bytes()[5..]);
+    }
+}
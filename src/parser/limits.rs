@@ -0,0 +1,510 @@
+//! This module defines limits, that parsers can be configured with to protect downstream consumers from pathological inputs. Limits are configured by inserting them into `parser_config_map` of parser factories.
+//!
+//! [`DocumentLimits`] guard each parsed document against too many statements, too long literals, and too deeply nested blank nodes, or collections, that services parsing untrusted uploads must be protected from.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{
+//!     error_code::{self, ErrorCode},
+//!     parser::{limits::*, quads::DynSynQuadParserFactory},
+//!     syntax,
+//! };
+//! use sophia_api::{parser::QuadParser, quad::stream::QuadSource};
+//! use sophia_term::BoxTerm;
+//! use type_map::concurrent::TypeMap;
+//!
+//! let mut config_map = TypeMap::new();
+//! config_map.insert(GraphCardinalityLimit::new(1, LimitAction::Error));
+//! let parser = DynSynQuadParserFactory::new(Some(config_map))
+//!     .try_new_parser::<BoxTerm>(syntax::TRIG, None, None)
+//!     .unwrap();
+//!
+//! let doc = "<tag:g1> { <tag:s> <tag:p> <tag:o> . } <tag:g2> { <tag:s> <tag:p> <tag:o> . }";
+//! let e = parser.parse_str(doc).for_each_quad(|_| {}).unwrap_err();
+//! assert_eq!(e.code(), error_code::DYNSYN_LIMIT_EXCEEDED);
+//! ```
+
+use std::{
+    collections::HashSet,
+    fmt::Display,
+    io::{self, BufRead, Read},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+// `std::time::Instant` panics on wasm32-unknown-unknown, as it has no clock.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::Instant;
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use web_time::Instant;
+
+use sophia_api::term::{CopyTerm, TTerm, TermKind};
+use sophia_term::BoxTerm;
+
+use super::{
+    _inner::lexer::{DocumentTracker, Lexeme},
+    iri_validation::LineIriResolver,
+};
+
+/// Action to take, when a parse limit is exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LimitAction {
+    /// Fail the parse with an error.
+    Error,
+    /// Log a warning once, and continue parsing.
+    Warn,
+}
+
+/// A limit on number of distinct named graphs in a parsed dataset. It protects stores with per-graph overhead from pathological inputs, like trig documents that create a graph per statement.
+///
+/// It applies to quad parsers only. Default graph is not counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GraphCardinalityLimit {
+    /// maximum number of distinct named graphs allowed
+    pub max_named_graphs: usize,
+    /// action to take, when limit is exceeded
+    pub on_exceed: LimitAction,
+}
+
+impl GraphCardinalityLimit {
+    pub fn new(max_named_graphs: usize, on_exceed: LimitAction) -> Self {
+        Self {
+            max_named_graphs,
+            on_exceed,
+        }
+    }
+}
+
+/// A guard that tracks distinct graph names in a stream against a [`GraphCardinalityLimit`].
+#[derive(Debug)]
+pub(crate) struct GraphCardinalityGuard {
+    limit: GraphCardinalityLimit,
+    seen: HashSet<BoxTerm>,
+    exceeded: bool,
+}
+
+impl GraphCardinalityGuard {
+    pub fn new(limit: GraphCardinalityLimit) -> Self {
+        Self {
+            limit,
+            seen: HashSet::new(),
+            exceeded: false,
+        }
+    }
+
+    pub fn limit(&self) -> GraphCardinalityLimit {
+        self.limit
+    }
+
+    /// Returns true, if a statement in given graph can be admitted into stream. Returns false only if limit is exceeded with [`LimitAction::Error`] action.
+    pub fn admit<T: TTerm + ?Sized>(&mut self, graph_name: Option<&T>) -> bool {
+        if self.exceeded && self.limit.on_exceed == LimitAction::Error {
+            return false;
+        }
+        let g = match graph_name {
+            Some(g) => BoxTerm::copy(g),
+            None => return true,
+        };
+        if self.seen.contains(&g) || self.exceeded {
+            return true;
+        }
+        if self.seen.len() < self.limit.max_named_graphs {
+            self.seen.insert(g);
+            return true;
+        }
+        self.exceeded = true;
+        match self.limit.on_exceed {
+            LimitAction::Error => false,
+            LimitAction::Warn => {
+                tracing::warn!(
+                    "Parsed dataset exceeds limit of {} distinct named graphs",
+                    self.limit.max_named_graphs
+                );
+                true
+            }
+        }
+    }
+
+    /// Returns true, if stream must be failed for exceeding the limit.
+    pub fn must_fail(&self) -> bool {
+        self.exceeded && self.limit.on_exceed == LimitAction::Error
+    }
+}
+
+/// Limits on a single parsed document. A `None` limit is unlimited. Parsing a document fails with a limit error, once it exceeds any of them.
+///
+/// Nesting depth is checked for syntaxes with nestable blank nodes, and collections, i.e. [turtle](crate::syntax::TURTLE), [trig](crate::syntax::TRIG), and [n3](crate::syntax::N3). It is checked as input is read, before nested statements are parsed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct DocumentLimits {
+    /// maximum number of statements in the document
+    pub max_statements: Option<u64>,
+    /// maximum length of lexical form of literals in bytes
+    pub max_literal_len: Option<usize>,
+    /// maximum depth of nested blank node property lists, and collections
+    pub max_nesting_depth: Option<usize>,
+}
+
+impl DocumentLimits {
+    pub fn new(
+        max_statements: Option<u64>,
+        max_literal_len: Option<usize>,
+        max_nesting_depth: Option<usize>,
+    ) -> Self {
+        Self {
+            max_statements,
+            max_literal_len,
+            max_nesting_depth,
+        }
+    }
+}
+
+/// A limit of [`DocumentLimits`], that a document exceeds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DocumentLimit {
+    /// Number of statements.
+    Statements(u64),
+    /// Length of a literal in bytes.
+    LiteralLen(usize),
+    /// Nesting depth of blank nodes, and collections.
+    NestingDepth(usize),
+}
+
+impl Display for DocumentLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Statements(max) => write!(f, "{} statements", max),
+            Self::LiteralLen(max) => write!(f, "{} bytes per literal", max),
+            Self::NestingDepth(max) => write!(f, "nesting depth of {}", max),
+        }
+    }
+}
+
+/// A guard that tracks statements of a document against [`DocumentLimits`].
+#[derive(Debug)]
+pub(crate) struct DocumentLimitGuard {
+    limits: DocumentLimits,
+    statements: u64,
+    exceeded: Option<DocumentLimit>,
+    /// Set by [`NestingTracker`] of the document's reader.
+    nesting_exceeded: Arc<AtomicBool>,
+}
+
+impl DocumentLimitGuard {
+    pub fn new(limits: DocumentLimits) -> Self {
+        Self {
+            limits,
+            statements: 0,
+            exceeded: None,
+            nesting_exceeded: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Returns a tracker of nesting depth, that must be installed on reader of the document, if nesting depth is limited.
+    pub fn nesting_tracker(&self) -> Option<NestingTracker> {
+        self.limits
+            .max_nesting_depth
+            .map(|max_depth| NestingTracker {
+                max_depth,
+                depth: 0,
+                exceeded: self.nesting_exceeded.clone(),
+            })
+    }
+
+    /// Returns true, if a statement with given object can be admitted into stream.
+    pub fn admit<T: TTerm + ?Sized>(&mut self, object: &T) -> bool {
+        if self.exceeded().is_some() {
+            return false;
+        }
+        if let Some(max) = self.limits.max_literal_len {
+            if object.kind() == TermKind::Literal && object.value_raw().len() > max {
+                self.exceeded = Some(DocumentLimit::LiteralLen(max));
+                return false;
+            }
+        }
+        if let Some(max) = self.limits.max_statements {
+            if self.statements >= max {
+                self.exceeded = Some(DocumentLimit::Statements(max));
+                return false;
+            }
+        }
+        self.statements += 1;
+        true
+    }
+
+    /// Returns exceeded limit, if any.
+    pub fn exceeded(&self) -> Option<DocumentLimit> {
+        match self.limits.max_nesting_depth {
+            Some(max) if self.nesting_exceeded.load(Ordering::Relaxed) => {
+                Some(DocumentLimit::NestingDepth(max))
+            }
+            _ => self.exceeded,
+        }
+    }
+}
+
+/// A tracker of nesting depth of blank node property lists, and collections in turtle-family documents.
+#[derive(Debug)]
+pub(crate) struct NestingTracker {
+    max_depth: usize,
+    depth: usize,
+    exceeded: Arc<AtomicBool>,
+}
+
+impl NestingTracker {
+    /// Observes given lexeme. Returns an error, if it exceeds maximum depth.
+    pub fn observe(&mut self, lexeme: Lexeme<'_>) -> io::Result<()> {
+        match lexeme {
+            Lexeme::Open => {
+                self.depth += 1;
+                if self.depth > self.max_depth {
+                    self.exceeded.store(true, Ordering::Relaxed);
+                    return Err(io::Error::other(format!(
+                        "document exceeds nesting depth of {}",
+                        self.max_depth
+                    )));
+                }
+            }
+            Lexeme::Close => self.depth = self.depth.saturating_sub(1),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// A resource of a [`SessionBudget`], along with it's limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BudgetResource {
+    /// Total number of parsed statements.
+    Statements(u64),
+    /// Total number of read bytes.
+    Bytes(u64),
+    /// Wall time since creation of the budget.
+    WallTime(Duration),
+}
+
+impl Display for BudgetResource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Statements(max) => write!(f, "{} statements", max),
+            Self::Bytes(max) => write!(f, "{} bytes", max),
+            Self::WallTime(max) => write!(f, "wall time of {:?}", max),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct BudgetState {
+    max_statements: Option<u64>,
+    max_bytes: Option<u64>,
+    max_wall_time: Option<Duration>,
+    started_at: Instant,
+    statements: AtomicU64,
+    bytes: AtomicU64,
+}
+
+/// A budget of statements, bytes, and wall time, that is shared by multiple parsers, which parse many documents on behalf of a single session, like an imports closure, or a federated fetch. Each parser decrements it cooperatively, and fails with a limit error, once any of it's resources is exhausted.
+///
+/// Cloning a budget gives another handle to the same shared budget. Parsers are configured with it by inserting it into `parser_config_map` of parser factories. Same budget can be inserted into config maps of both triple, and quad parser factories.
+///
+/// Bytes are charged as parsers consume them from underlying reader, which may be ahead of emitted statements by a buffer.
+#[derive(Debug, Clone)]
+pub struct SessionBudget(Arc<BudgetState>);
+
+impl SessionBudget {
+    /// Create a new budget with given limits. A `None` limit is unlimited. Wall time is measured from now.
+    pub fn new(
+        max_statements: Option<u64>,
+        max_bytes: Option<u64>,
+        max_wall_time: Option<Duration>,
+    ) -> Self {
+        Self(Arc::new(BudgetState {
+            max_statements,
+            max_bytes,
+            max_wall_time,
+            started_at: Instant::now(),
+            statements: AtomicU64::new(0),
+            bytes: AtomicU64::new(0),
+        }))
+    }
+
+    /// Returns number of statements parsed against this budget so far.
+    pub fn statements_used(&self) -> u64 {
+        self.0.statements.load(Ordering::Relaxed)
+    }
+
+    /// Returns number of bytes read against this budget so far.
+    pub fn bytes_used(&self) -> u64 {
+        self.0.bytes.load(Ordering::Relaxed)
+    }
+
+    /// Returns exhausted resource of this budget, if any.
+    pub fn exhausted(&self) -> Option<BudgetResource> {
+        let state = &self.0;
+        match (state.max_statements, state.max_bytes, state.max_wall_time) {
+            (Some(max), _, _) if self.statements_used() > max => {
+                Some(BudgetResource::Statements(max))
+            }
+            (_, Some(max), _) if self.bytes_used() > max => Some(BudgetResource::Bytes(max)),
+            (_, _, Some(max)) if state.started_at.elapsed() > max => {
+                Some(BudgetResource::WallTime(max))
+            }
+            _ => None,
+        }
+    }
+
+    /// Charges a statement against this budget. Returns true, if statement can be admitted into stream.
+    pub(crate) fn admit_statement(&self) -> bool {
+        if self.exhausted().is_some() {
+            return false;
+        }
+        let used = self.0.statements.fetch_add(1, Ordering::Relaxed) + 1;
+        !matches!(self.0.max_statements, Some(max) if used > max)
+    }
+
+    /// Returns resource of this budget, that would be exhausted once given number of bytes more are charged against it, if any.
+    #[cfg(any(test, feature = "async"))]
+    pub(crate) fn exhausted_with_bytes(&self, n: usize) -> Option<BudgetResource> {
+        match self.0.max_bytes {
+            Some(max) if self.bytes_used() + n as u64 > max => Some(BudgetResource::Bytes(max)),
+            _ => self.exhausted(),
+        }
+    }
+
+    fn charge_bytes(&self, n: usize) {
+        self.0.bytes.fetch_add(n as u64, Ordering::Relaxed);
+    }
+}
+
+/// A reader, that charges bytes consumed from underlying reader against a [`SessionBudget`], if any, and tracks them with a [`DocumentTracker`], if any.
+pub(crate) struct BudgetedRead<R> {
+    read: R,
+    budget: Option<SessionBudget>,
+    document_tracker: Option<DocumentTracker>,
+    /// Set once a tracked limit is exceeded. Subsequent reads fail.
+    tracked_limit_exceeded: bool,
+    /// Count of bytes read so far, shared with progress tracker of the source, if any.
+    bytes_read: Arc<AtomicU64>,
+    /// Resolves relative iri references of lines read, if any.
+    line_iri_resolver: Option<LineIriResolver>,
+}
+
+impl<R> BudgetedRead<R> {
+    pub fn new(read: R, budget: Option<SessionBudget>) -> Self {
+        Self {
+            read,
+            budget,
+            document_tracker: None,
+            tracked_limit_exceeded: false,
+            bytes_read: Arc::default(),
+            line_iri_resolver: None,
+        }
+    }
+
+    /// Returns a shared counter of bytes read so far.
+    pub fn bytes_read(&self) -> Arc<AtomicU64> {
+        self.bytes_read.clone()
+    }
+
+    pub fn with_document_tracker(mut self, document_tracker: Option<DocumentTracker>) -> Self {
+        self.document_tracker = document_tracker;
+        self
+    }
+
+    /// Read line by line, resolving relative iri references of each line with given resolver. It is meant for line-based syntaxes, whose underlying parsers reject relative iris.
+    pub fn with_line_iri_resolver(mut self, line_iri_resolver: Option<LineIriResolver>) -> Self {
+        self.line_iri_resolver = line_iri_resolver;
+        self
+    }
+
+    /// Charge given bytes, read from underlying reader, against budget, and track them.
+    fn charge(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.bytes_read
+            .fetch_add(bytes.len() as u64, Ordering::Relaxed);
+        if let Some(budget) = &self.budget {
+            budget.charge_bytes(bytes.len());
+        }
+        if let Some(tracker) = self.document_tracker.as_mut() {
+            if let Err(e) = tracker.track(bytes) {
+                self.tracked_limit_exceeded = true;
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    fn check_tracked_limit(&self) -> io::Result<()> {
+        if self.tracked_limit_exceeded {
+            return Err(io::Error::other("document exceeds a tracked limit"));
+        }
+        Ok(())
+    }
+}
+
+impl<R: BufRead> BudgetedRead<R> {
+    /// Read next line from underlying reader into line iri resolver, if it's current line is read completely.
+    fn fill_line(&mut self) -> io::Result<()> {
+        if !matches!(&self.line_iri_resolver, Some(resolver) if resolver.pending().is_empty()) {
+            return Ok(());
+        }
+        let mut raw = Vec::new();
+        self.read.read_until(b'\n', &mut raw)?;
+        self.charge(&raw)?;
+        if let Some(resolver) = self.line_iri_resolver.as_mut() {
+            resolver.set_line(&raw);
+        }
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Read for BudgetedRead<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.check_tracked_limit()?;
+        if self.line_iri_resolver.is_some() {
+            let n = {
+                let line = self.fill_buf()?;
+                let n = line.len().min(buf.len());
+                buf[..n].copy_from_slice(&line[..n]);
+                n
+            };
+            self.consume(n);
+            return Ok(n);
+        }
+        let n = self.read.read(buf)?;
+        self.charge(&buf[..n])?;
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for BudgetedRead<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.check_tracked_limit()?;
+        self.fill_line()?;
+        match &self.line_iri_resolver {
+            Some(resolver) => Ok(resolver.pending()),
+            None => self.read.fill_buf(),
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if let Some(resolver) = self.line_iri_resolver.as_mut() {
+            // Bytes of the line are charged, as it is read from underlying reader.
+            resolver.consume(amt);
+            return;
+        }
+        self.bytes_read.fetch_add(amt as u64, Ordering::Relaxed);
+        if let Some(budget) = &self.budget {
+            budget.charge_bytes(amt);
+        }
+        if let (Some(tracker), Ok(buf)) = (self.document_tracker.as_mut(), self.read.fill_buf()) {
+            // Exceeded limit is surfaced from next read.
+            if tracker.track(&buf[..amt.min(buf.len())]).is_err() {
+                self.tracked_limit_exceeded = true;
+            }
+        }
+        self.read.consume(amt)
+    }
+}
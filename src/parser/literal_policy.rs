@@ -0,0 +1,376 @@
+//! This module defines a literal policy, that parsers can be configured with, to keep, warn about, or reject statements with literals of unknown datatypes, or with ill-typed literals. Pipelines that load into strict stores can fail early, while lenient pipelines can just log such literals.
+//!
+//! Policy is configured by inserting a [`LiteralPolicy`] into `parser_config_map` of parser factories. It is checked against object terms of each statement. A datatype is known, if it is a builtin xsd datatype, one of `rdf:langString`, `rdf:HTML`, `rdf:XMLLiteral`, `rdf:JSON`, or is added to the policy through [`with_known_datatype`](LiteralPolicy::with_known_datatype). Well-typedness is checked only for datatypes, that [`LiteralDecoder`] can decode.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{
+//!     error_code::{self, ErrorCode},
+//!     parser::{
+//!         literal_policy::{LiteralAction, LiteralPolicy},
+//!         triples::DynSynTripleParserFactory,
+//!     },
+//!     syntax,
+//! };
+//! use sophia_api::{parser::TripleParser, triple::stream::TripleSource};
+//! use sophia_term::BoxTerm;
+//! use type_map::concurrent::TypeMap;
+//!
+//! let mut config_map = TypeMap::new();
+//! config_map.insert(LiteralPolicy::new(LiteralAction::Keep, LiteralAction::Error));
+//! let parser = DynSynTripleParserFactory::new(Some(config_map))
+//!     .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)
+//!     .unwrap();
+//!
+//! let doc = r#"<http://example.org/a> <http://example.org/age> "forty"^^<http://www.w3.org/2001/XMLSchema#integer> ."#;
+//! let e = parser.parse_str(doc).for_each_triple(|_| {}).unwrap_err();
+//! assert_eq!(e.code(), error_code::DYNSYN_LITERAL_POLICY_VIOLATION);
+//! ```
+
+use std::{collections::HashSet, fmt::Display};
+
+use sophia_api::{
+    ns::{rdf, xsd},
+    term::{TTerm, TermKind},
+};
+
+use crate::literal_decode::{LiteralDecodeError, LiteralDecoder};
+
+/// Local names of builtin xsd datatypes.
+const XSD_DATATYPES: [&str; 45] = [
+    "anyURI",
+    "base64Binary",
+    "boolean",
+    "byte",
+    "date",
+    "dateTime",
+    "dateTimeStamp",
+    "dayTimeDuration",
+    "decimal",
+    "double",
+    "duration",
+    "float",
+    "gDay",
+    "gMonth",
+    "gMonthDay",
+    "gYear",
+    "gYearMonth",
+    "hexBinary",
+    "int",
+    "integer",
+    "language",
+    "long",
+    "Name",
+    "NCName",
+    "negativeInteger",
+    "NMTOKEN",
+    "nonNegativeInteger",
+    "nonPositiveInteger",
+    "normalizedString",
+    "positiveInteger",
+    "short",
+    "string",
+    "time",
+    "token",
+    "unsignedByte",
+    "unsignedInt",
+    "unsignedLong",
+    "unsignedShort",
+    "yearMonthDuration",
+    "ENTITY",
+    "ENTITIES",
+    "ID",
+    "IDREF",
+    "IDREFS",
+    "NMTOKENS",
+];
+
+/// Iri of `rdf:JSON` datatype, that sophia's rdf namespace doesn't define.
+const RDF_JSON: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#JSON";
+
+/// Action to take, when a literal violates a [`LiteralPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LiteralAction {
+    /// Keep the statement as is, silently.
+    Keep,
+    /// Keep the statement, and log a warning for first violation.
+    Warn,
+    /// Fail the stream.
+    Error,
+}
+
+/// A policy of how to treat literals of unknown datatypes, and ill-typed literals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiteralPolicy {
+    /// Action to take on literals, whose datatype is not known.
+    pub on_unknown_datatype: LiteralAction,
+    /// Action to take on literals, whose lexical form is not valid for their datatype.
+    pub on_ill_typed: LiteralAction,
+    /// Datatype iris, that are known in addition to builtin ones.
+    pub extra_known_datatypes: HashSet<String>,
+}
+
+impl Default for LiteralPolicy {
+    fn default() -> Self {
+        Self::new(LiteralAction::Keep, LiteralAction::Keep)
+    }
+}
+
+impl LiteralPolicy {
+    pub fn new(on_unknown_datatype: LiteralAction, on_ill_typed: LiteralAction) -> Self {
+        Self {
+            on_unknown_datatype,
+            on_ill_typed,
+            extra_known_datatypes: HashSet::new(),
+        }
+    }
+
+    /// Add a datatype iri to known datatypes.
+    pub fn with_known_datatype(mut self, datatype: &str) -> Self {
+        self.extra_known_datatypes.insert(datatype.to_owned());
+        self
+    }
+
+    /// Returns true, if given datatype iri is known to this policy.
+    pub fn is_known_datatype(&self, datatype: &str) -> bool {
+        if let Some(local_name) = datatype.strip_prefix(xsd::PREFIX) {
+            if XSD_DATATYPES.contains(&local_name) {
+                return true;
+            }
+        }
+        [rdf::langString, rdf::HTML, rdf::XMLLiteral]
+            .iter()
+            .any(|dt| dt.value() == datatype)
+            || datatype == RDF_JSON
+            || self.extra_known_datatypes.contains(datatype)
+    }
+
+    /// Check given term against this policy. Terms, that are not literals always pass.
+    ///
+    /// Returns violation along with action configured for it, if term violates the policy, and that action is not [`LiteralAction::Keep`].
+    pub fn check<T>(&self, term: &T) -> Option<(LiteralPolicyViolation, LiteralAction)>
+    where
+        T: TTerm + ?Sized,
+    {
+        if term.kind() != TermKind::Literal {
+            return None;
+        }
+        if self.on_unknown_datatype != LiteralAction::Keep {
+            let datatype = term.datatype()?.value().to_string();
+            if !self.is_known_datatype(&datatype) {
+                return Some((
+                    LiteralPolicyViolation::UnknownDatatype(datatype),
+                    self.on_unknown_datatype,
+                ));
+            }
+        }
+        if self.on_ill_typed != LiteralAction::Keep {
+            if let Some(Err(e)) = LiteralDecoder::default().decode(term) {
+                return Some((LiteralPolicyViolation::IllTyped(e), self.on_ill_typed));
+            }
+        }
+        None
+    }
+}
+
+/// A violation of a [`LiteralPolicy`] by a literal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LiteralPolicyViolation {
+    /// Literal has datatype with given iri, that is not known.
+    UnknownDatatype(String),
+    /// Lexical form of literal is not valid for it's datatype.
+    IllTyped(LiteralDecodeError),
+}
+
+impl Display for LiteralPolicyViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownDatatype(datatype) => {
+                write!(f, "literal has unknown datatype <{}>", datatype)
+            }
+            Self::IllTyped(e) => write!(f, "literal is ill-typed: {}", e),
+        }
+    }
+}
+
+/// A guard that checks statements in a stream against a [`LiteralPolicy`].
+#[derive(Debug)]
+pub(crate) struct LiteralPolicyGuard {
+    policy: LiteralPolicy,
+    violation: Option<LiteralPolicyViolation>,
+    must_fail: bool,
+    has_warned: bool,
+}
+
+impl LiteralPolicyGuard {
+    pub fn new(policy: LiteralPolicy) -> Self {
+        Self {
+            policy,
+            violation: None,
+            must_fail: false,
+            has_warned: false,
+        }
+    }
+
+    /// Returns true, if a statement with given object can be admitted into stream. Returns false only if object violates policy with [`LiteralAction::Error`] action, or if such a violation has already occurred.
+    pub fn admit<T>(&mut self, object: &T) -> bool
+    where
+        T: TTerm + ?Sized,
+    {
+        if self.must_fail {
+            return false;
+        }
+        let (violation, action) = match self.policy.check(object) {
+            Some(v) => v,
+            None => return true,
+        };
+        match action {
+            LiteralAction::Error => {
+                self.violation = Some(violation);
+                self.must_fail = true;
+                false
+            }
+            LiteralAction::Warn => {
+                if !self.has_warned {
+                    tracing::warn!("Parsed statement violates literal policy: {}", violation);
+                    self.has_warned = true;
+                }
+                self.violation.get_or_insert(violation);
+                true
+            }
+            LiteralAction::Keep => true,
+        }
+    }
+
+    /// Returns true, if stream must be failed for a violation.
+    pub fn must_fail(&self) -> bool {
+        self.must_fail
+    }
+
+    /// Returns violation, that fails the stream, or else first warned violation, if any.
+    pub fn violation(&self) -> Option<&LiteralPolicyViolation> {
+        self.violation.as_ref()
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use sophia_api::{
+        parser::{QuadParser, TripleParser},
+        quad::stream::QuadSource,
+        triple::stream::TripleSource,
+    };
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+    use type_map::concurrent::TypeMap;
+
+    use super::{LiteralAction, LiteralPolicy, LiteralPolicyViolation};
+    use crate::{
+        error_code::{self, ErrorCode},
+        parser::{quads::DynSynQuadParserFactory, triples::DynSynTripleParserFactory},
+        syntax,
+        tests::TRACING,
+    };
+
+    static DOC: &str = r#"
+        @prefix : <http://example.org/> .
+        @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+        :a :p "1"^^xsd:integer .
+        :a :p "x"^^:myType .
+        :a :p "one"^^xsd:integer .
+        :a :p "b"@en .
+    "#;
+
+    #[test_case("http://www.w3.org/2001/XMLSchema#gYearMonth", true)]
+    #[test_case("http://www.w3.org/1999/02/22-rdf-syntax-ns#langString", true)]
+    #[test_case("http://www.w3.org/1999/02/22-rdf-syntax-ns#JSON", true)]
+    #[test_case("http://www.w3.org/2001/XMLSchema#integr", false)]
+    #[test_case("http://example.org/myType", false)]
+    pub fn knows_builtin_datatypes(datatype: &str, known: bool) {
+        Lazy::force(&TRACING);
+        assert_eq!(LiteralPolicy::default().is_known_datatype(datatype), known);
+    }
+
+    #[test]
+    pub fn knows_added_datatypes() {
+        Lazy::force(&TRACING);
+        let policy = LiteralPolicy::default().with_known_datatype("http://example.org/myType");
+        assert!(policy.is_known_datatype("http://example.org/myType"));
+    }
+
+    #[test_case(LiteralAction::Error, LiteralAction::Keep, 1, "myType")]
+    #[test_case(LiteralAction::Keep, LiteralAction::Error, 2, "one")]
+    pub fn violations_fail_parse_with_error_action(
+        on_unknown_datatype: LiteralAction,
+        on_ill_typed: LiteralAction,
+        expected_count: usize,
+        expected_in_message: &str,
+    ) {
+        Lazy::force(&TRACING);
+        let mut config_map = TypeMap::new();
+        config_map.insert(LiteralPolicy::new(on_unknown_datatype, on_ill_typed));
+        let mut count = 0;
+        let e = DynSynTripleParserFactory::new(Some(config_map))
+            .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)
+            .unwrap()
+            .parse_str(DOC)
+            .for_each_triple(|_| count += 1)
+            .unwrap_err();
+        assert_eq!(e.code(), error_code::DYNSYN_LITERAL_POLICY_VIOLATION);
+        assert!(e.to_string().contains(expected_in_message));
+        assert_eq!(count, expected_count);
+    }
+
+    #[test_case(LiteralAction::Warn)]
+    #[test_case(LiteralAction::Keep)]
+    pub fn violations_are_kept_with_lenient_actions(action: LiteralAction) {
+        Lazy::force(&TRACING);
+        let mut config_map = TypeMap::new();
+        config_map.insert(LiteralPolicy::new(action, action));
+        let mut count = 0;
+        DynSynQuadParserFactory::new(Some(config_map))
+            .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)
+            .unwrap()
+            .parse_str(DOC)
+            .for_each_quad(|_| count += 1)
+            .unwrap();
+        assert_eq!(count, 4);
+    }
+
+    #[test]
+    pub fn checks_objects_of_quads() {
+        Lazy::force(&TRACING);
+        let mut config_map = TypeMap::new();
+        config_map.insert(LiteralPolicy::new(
+            LiteralAction::Error,
+            LiteralAction::Error,
+        ));
+        let e = DynSynQuadParserFactory::new(Some(config_map))
+            .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+            .unwrap()
+            .parse_str("<tag:s> <tag:p> \"x\"^^<tag:dt> <tag:g> .\n")
+            .for_each_quad(|_| {})
+            .unwrap_err();
+        assert_eq!(e.code(), error_code::DYNSYN_LITERAL_POLICY_VIOLATION);
+    }
+
+    #[test]
+    pub fn reports_violations() {
+        Lazy::force(&TRACING);
+        let policy = LiteralPolicy::new(LiteralAction::Warn, LiteralAction::Keep);
+        let term = BoxTerm::new_literal_dt("x", BoxTerm::new_iri("tag:dt").unwrap()).unwrap();
+        assert_eq!(
+            policy.check(&term),
+            Some((
+                LiteralPolicyViolation::UnknownDatatype("tag:dt".into()),
+                LiteralAction::Warn
+            ))
+        );
+        assert_eq!(policy.check(&BoxTerm::new_iri("tag:x").unwrap()), None);
+    }
+}
@@ -0,0 +1,196 @@
+//! This module documents order, in which dynsyn sources emit statements, for each syntax they can parse. Code that depends on order, like patch application, can query it through [`statement_order`], or [`SyntaxSupport::parse_order`](crate::support::SyntaxSupport::parse_order), instead of assuming it.
+//!
+//! Dynsyn sources never reorder statements. Sources that adapt statements across triple/quad boundary keep relative order of statements they emit. Limits, and keep-going recovery only drop statements, and keep order of the rest.
+
+use crate::syntax::{self, RdfSyntax};
+
+/// Order, in which statements are emitted by dynsyn sources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatementOrder {
+    /// Statements are emitted in order, in which they appear in the document. Each statement is written at a single place in syntaxes with this order.
+    Document,
+    /// Statements are emitted in order, in which they are completed in the document, i.e. once their object is read in full. Hence statements nested in blank-node property lists, collections, and nested node elements are emitted before their enclosing statement. Other statements are emitted in order, in which they appear in the document.
+    Completion,
+    /// Statements are emitted in order, in which an extractor completes them, while walking elements of a host document in document order. A statement can be assembled from attributes of several elements. Hence statements of an element precede those of it's descendants, except statements with literal objects taken from element content, and statements of lists, which follow them.
+    Extraction,
+}
+
+/// Get order, in which dynsyn sources emit statements parsed from a document in given syntax. Returns `None` if syntax can't be parsed.
+pub fn statement_order(syntax_: RdfSyntax) -> Option<StatementOrder> {
+    match syntax_ {
+        syntax::N_TRIPLES | syntax::N_QUADS => Some(StatementOrder::Document),
+        #[cfg(feature = "trix")]
+        syntax::TRIX => Some(StatementOrder::Document),
+        #[cfg(feature = "hdt")]
+        syntax::HDT => Some(StatementOrder::Document),
+        syntax::TURTLE | syntax::TRIG => Some(StatementOrder::Completion),
+        #[cfg(feature = "rdf-xml")]
+        syntax::RDF_XML => Some(StatementOrder::Completion),
+        #[cfg(feature = "n3")]
+        syntax::N3 => Some(StatementOrder::Completion),
+        #[cfg(feature = "rdfa")]
+        syntax::HTML_RDFA | syntax::XHTML_RDFA => Some(StatementOrder::Extraction),
+        _ => None,
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use sophia_api::{
+        parser::{QuadParser, TripleParser},
+        quad::{stream::QuadSource, Quad},
+        term::TTerm,
+        triple::{stream::TripleSource, Triple},
+    };
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+
+    use super::{statement_order, StatementOrder};
+    use crate::{
+        parser::{quads::DynSynQuadParserFactory, triples::DynSynTripleParserFactory},
+        support::SyntaxSupport,
+        syntax::{self, RdfSyntax, KNOWN_SYNTAXES},
+        tests::TRACING,
+    };
+
+    static NT_DOC: &str = r#"
+        <http://e/b> <http://e/p> <http://e/c> .
+        <http://e/a> <http://e/p> <http://e/x> .
+        <http://e/a> <http://e/p> "y" .
+    "#;
+
+    static NQ_DOC: &str = r#"
+        <http://e/b> <http://e/p> <http://e/c> <http://e/g> .
+        <http://e/a> <http://e/p> <http://e/x> .
+        <http://e/a> <http://e/p> "y" <http://e/g> .
+    "#;
+
+    static TURTLE_DOC: &str = r#"
+        @prefix : <http://e/> .
+        :b :p :c .
+        :a :p1 :x ; :p2 [ :q :y ] ; :p3 ( :l ) ; :p4 :w .
+    "#;
+
+    static TRIG_DOC: &str = r#"
+        @prefix : <http://e/> .
+        :g { :b :p :c . }
+        :a :p1 :x ; :p2 [ :q :y ] ; :p3 ( :l ) ; :p4 :w .
+    "#;
+
+    #[cfg(feature = "rdf-xml")]
+    static RDF_XML_DOC: &str = r#"<?xml version="1.0"?>
+        <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:e="http://e/">
+            <rdf:Description rdf:about="http://e/b"><e:p rdf:resource="http://e/c"/></rdf:Description>
+            <rdf:Description rdf:about="http://e/a">
+                <e:p1 rdf:resource="http://e/x"/>
+                <e:p2><rdf:Description><e:q rdf:resource="http://e/y"/></rdf:Description></e:p2>
+                <e:p4 rdf:resource="http://e/w"/>
+            </rdf:Description>
+        </rdf:RDF>
+    "#;
+
+    #[cfg(feature = "rdfa")]
+    static HTML_RDFA_DOC: &str = r#"
+        <div prefix="e: http://e/">
+            <p about="e:b" rel="e:p" resource="e:c"></p>
+            <div about="e:a">
+                <span rel="e:p1" resource="e:x"></span>
+                <div rel="e:p2"><span property="e:q" resource="e:y"></span></div>
+                <span property="e:p4">w</span>
+            </div>
+        </div>
+    "#;
+
+    /// Predicates of statements, in order of emission.
+    fn triple_predicates(syntax_: RdfSyntax, doc: &str) -> Vec<String> {
+        let mut predicates = Vec::new();
+        DynSynTripleParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap()
+            .parse_str(doc)
+            .for_each_triple(|t| predicates.push(local_name(t.p())))
+            .unwrap();
+        predicates
+    }
+
+    fn quad_predicates(syntax_: RdfSyntax, doc: &str) -> Vec<String> {
+        let mut predicates = Vec::new();
+        DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap()
+            .parse_str(doc)
+            .for_each_quad(|q| predicates.push(local_name(q.p())))
+            .unwrap();
+        predicates
+    }
+
+    fn local_name<T: TTerm + ?Sized>(t: &T) -> String {
+        let v = t.value().to_string();
+        v.rsplit(['/', '#']).next().unwrap().to_string()
+    }
+
+    #[test_case(syntax::N_TRIPLES, NT_DOC, &["p", "p", "p"])]
+    #[test_case(syntax::N_QUADS, NQ_DOC, &["p", "p", "p"])]
+    #[test_case(
+        syntax::TURTLE,
+        TURTLE_DOC,
+        &["p", "p1", "q", "p2", "first", "rest", "p3", "p4"]
+    )]
+    #[test_case(
+        syntax::TRIG,
+        TRIG_DOC,
+        &["p", "p1", "q", "p2", "first", "rest", "p3", "p4"]
+    )]
+    #[cfg_attr(
+        feature = "rdf-xml",
+        test_case(syntax::RDF_XML, RDF_XML_DOC, &["p", "p1", "q", "p2", "p4"])
+    )]
+    #[cfg_attr(
+        feature = "rdfa",
+        test_case(syntax::HTML_RDFA, HTML_RDFA_DOC, &["p", "p1", "p2", "q", "p4"])
+    )]
+    pub fn quad_sources_emit_statements_in_documented_order(
+        syntax_: RdfSyntax,
+        doc: &str,
+        expected: &[&str],
+    ) {
+        Lazy::force(&TRACING);
+        assert!(statement_order(syntax_).is_some());
+        assert_eq!(quad_predicates(syntax_, doc), expected);
+    }
+
+    #[test]
+    pub fn triple_sources_keep_relative_order_of_adapted_statements() {
+        Lazy::force(&TRACING);
+        assert_eq!(triple_predicates(syntax::N_QUADS, NQ_DOC), vec!["p"]);
+        assert_eq!(
+            triple_predicates(syntax::TRIG, TRIG_DOC),
+            vec!["p1", "q", "p2", "first", "rest", "p3", "p4"]
+        );
+        assert_eq!(
+            triple_predicates(syntax::TURTLE, TURTLE_DOC),
+            quad_predicates(syntax::TURTLE, TURTLE_DOC)
+        );
+    }
+
+    #[test]
+    pub fn order_is_documented_for_every_parsable_syntax() {
+        Lazy::force(&TRACING);
+        for syntax_ in KNOWN_SYNTAXES {
+            let support = SyntaxSupport::of(syntax_);
+            assert_eq!(
+                support.quad_parsing || support.triple_parsing,
+                statement_order(syntax_).is_some()
+            );
+            assert_eq!(support.parse_order, statement_order(syntax_));
+        }
+        assert_eq!(
+            statement_order(syntax::N_QUADS),
+            Some(StatementOrder::Document)
+        );
+    }
+}
@@ -0,0 +1,353 @@
+//! This module provides parallel parsing of line-based syntaxes, [n-quads](crate::syntax::N_QUADS), and [n-triples](crate::syntax::N_TRIPLES), with [`DynSynQuadParser::parse_parallel`]. It is available only when `parallel` feature is enabled.
+//!
+//! In line-based syntaxes, each statement is on it's own line, and blank node labels are scoped to the document. Hence input can be split into chunks at line boundaries, and each chunk can be parsed independently. Chunks are parsed concurrently on rayon's global thread pool, and their quads are merged in document order. It gives a speedup proportional to number of cores on large dumps.
+//!
+//! Parser's configuration, like iri policy, literal policy, and session budget, applies to each chunk. Progress configured by a [`ProgressConfig`](super::progress::ProgressConfig) is reported as parsed chunks are merged, in document order, with totals across whole document. Documents in other syntaxes are parsed sequentially. So are documents parsed with state, that is scoped to whole document, and can't be split across chunks, that is with a [`GraphCardinalityLimit`](super::limits::GraphCardinalityLimit), or a statement limit of [`DocumentLimits`](super::limits::DocumentLimits), which count across whole document, with [`Skolemization`](super::skolem::Skolemization), which maps each blank node label of document to a single iri, with [reification of quoted triples](super::rdf_star), which maps each quoted triple of document to a single blank node, or in [lenient mode](super::recovery), which locates skipped statements in document.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{
+//!     parser::{parallel::DEFAULT_CHUNK_BYTES, quads::DynSynQuadParserFactory},
+//!     syntax,
+//! };
+//! use sophia_term::BoxTerm;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let parser = DynSynQuadParserFactory::default()
+//!     .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)?;
+//! let doc = (0..1000)
+//!     .map(|i| format!("<http://e/s{}> <http://e/p> \"{}\" <http://e/g> .\n", i, i))
+//!     .collect::<String>();
+//!
+//! let quads = parser.parse_parallel(doc.as_bytes(), DEFAULT_CHUNK_BYTES)?;
+//! assert_eq!(quads.len(), 1000);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::{
+    io::{BufRead, Cursor},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use rayon::prelude::*;
+use rio_turtle::TurtleError;
+use sophia_api::{
+    parser::QuadParser,
+    term::{CopyTerm, TTerm},
+};
+
+use super::{
+    errors::DynSynParseError,
+    progress::ProgressTracker,
+    quads::{
+        source::{DynSynQuadSource, TupleQuad},
+        DynSynQuadParser,
+    },
+};
+use crate::syntax::{self, RdfSyntax};
+
+/// Default approximate size of chunks, that documents are split into.
+pub const DEFAULT_CHUNK_BYTES: usize = 4 * 1024 * 1024;
+
+/// A chunk of document, along with line number of it's first line.
+struct Chunk {
+    first_line: u64,
+    data: Vec<u8>,
+}
+
+/// Read next chunk of at least `chunk_bytes` bytes, ending at a line boundary, unless document ends before.
+fn read_chunk<R: BufRead>(
+    data: &mut R,
+    chunk_bytes: usize,
+    next_line: &mut u64,
+    syntax_: RdfSyntax,
+) -> Result<Option<Chunk>, DynSynParseError> {
+    let mut chunk = Chunk {
+        first_line: *next_line,
+        data: Vec::new(),
+    };
+    while chunk.data.len() < chunk_bytes {
+        match data.read_until(b'\n', &mut chunk.data) {
+            Ok(0) => break,
+            Ok(_) => *next_line += 1,
+            Err(e) => return Err(DynSynParseError::from(TurtleError::from(e)).with_syntax(syntax_)),
+        }
+    }
+    Ok((!chunk.data.is_empty()).then_some(chunk))
+}
+
+fn is_line_based(syntax_: RdfSyntax) -> bool {
+    syntax_ == syntax::N_QUADS || syntax_ == syntax::N_TRIPLES
+}
+
+impl<T> DynSynQuadParser<T>
+where
+    T: TTerm + CopyTerm + Clone + Send + Sync,
+{
+    /// Parses `data` into quads, splitting it into chunks of approximately `chunk_bytes` bytes at line boundaries, and parsing chunks in parallel, if this parser targets a line-based syntax. Quads are returned in document order.
+    ///
+    /// As many chunks are held in memory at a time, as there are threads in rayon's global pool. Progress is reported from calling thread, after each batch of chunks is merged.
+    ///
+    /// # Errors
+    /// returns first [`DynSynParseError`] in document order, if any chunk couldn't be parsed. Line number of the error is counted from start of the document.
+    pub fn parse_parallel<R: BufRead>(
+        &self,
+        mut data: R,
+        chunk_bytes: usize,
+    ) -> Result<Vec<TupleQuad<T>>, DynSynParseError> {
+        let counts_statements = self
+            .document_limits()
+            .is_some_and(|l| l.max_statements.is_some());
+        let has_document_state = self.graph_cardinality_limit().is_some()
+            || counts_statements
+            || self.skolemization().is_some()
+            || self.reifies_quoted_triples()
+            || self.is_lenient();
+        if !is_line_based(self.syntax()) || has_document_state {
+            return Self::collect(QuadParser::parse(self, data));
+        }
+        // Chunks are parsed without tracking progress. Progress of whole document is tracked
+        // here instead, as chunks are merged.
+        let bytes_read = Arc::new(AtomicU64::new(0));
+        let mut progress_tracker = self
+            .progress()
+            .cloned()
+            .map(|config| ProgressTracker::new(config, bytes_read.clone()));
+        let batch_size = rayon::current_num_threads();
+        let mut next_line = 1;
+        let mut quads = Vec::new();
+        loop {
+            let mut batch = Vec::with_capacity(batch_size);
+            while batch.len() < batch_size {
+                match read_chunk(&mut data, chunk_bytes.max(1), &mut next_line, self.syntax())? {
+                    Some(chunk) => batch.push(chunk),
+                    None => break,
+                }
+            }
+            if batch.is_empty() {
+                if let Some(tracker) = progress_tracker.as_mut() {
+                    tracker.on_end();
+                }
+                return Ok(quads);
+            }
+            let parsed: Vec<_> = batch
+                .into_par_iter()
+                .map(|chunk| {
+                    let chunk_bytes = chunk.data.len() as u64;
+                    let source = QuadParser::parse(self, Cursor::new(chunk.data))
+                        .with_progress_tracker(None);
+                    Self::collect(source)
+                        .map(|chunk_quads| (chunk_bytes, chunk_quads))
+                        .map_err(|e| e.in_chunk(chunk.first_line))
+                })
+                .collect();
+            for chunk in parsed {
+                let (chunk_bytes, chunk_quads) = chunk?;
+                bytes_read.fetch_add(chunk_bytes, Ordering::Relaxed);
+                if let Some(tracker) = progress_tracker.as_mut() {
+                    tracker.on_statements(chunk_quads.len());
+                }
+                quads.extend(chunk_quads);
+            }
+        }
+    }
+
+    fn collect<R: BufRead>(
+        source: DynSynQuadSource<T, R>,
+    ) -> Result<Vec<TupleQuad<T>>, DynSynParseError> {
+        source.into_iter().map(|q| q.map(Into::into)).collect()
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashSet,
+        sync::{Arc, Mutex},
+    };
+
+    use once_cell::sync::Lazy;
+    use sophia_api::{parser::QuadParser, term::TTerm};
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+    use type_map::concurrent::TypeMap;
+
+    use crate::{
+        error_code::{self, ErrorCode},
+        parser::{
+            limits::{GraphCardinalityLimit, LimitAction},
+            progress::{Progress, ProgressConfig},
+            quads::{source::TupleQuad, DynSynQuadParserFactory},
+            skolem::Skolemization,
+        },
+        syntax::{self, RdfSyntax},
+        tests::TRACING,
+    };
+
+    fn doc(syntax_: RdfSyntax, lines: usize) -> String {
+        (0..lines)
+            .map(|i| match syntax_ {
+                s if s == syntax::N_QUADS => {
+                    format!("<tag:s{}> <tag:p> _:b{} <tag:g{}> .\n", i, i % 7, i % 3)
+                }
+                _ => format!("<tag:s{}> <tag:p> \"{}\" .\n", i, i),
+            })
+            .collect()
+    }
+
+    #[test_case(syntax::N_QUADS, 1)]
+    #[test_case(syntax::N_QUADS, 100)]
+    #[test_case(syntax::N_TRIPLES, 64)]
+    #[test_case(syntax::TRIG, 64)]
+    pub fn parses_in_document_order(syntax_: RdfSyntax, chunk_bytes: usize) {
+        Lazy::force(&TRACING);
+        let parser = DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap();
+        let doc = doc(syntax_, 500);
+        let expected: Vec<TupleQuad<BoxTerm>> = parser
+            .parse_str(&doc)
+            .into_iter()
+            .map(|q| q.unwrap().into())
+            .collect();
+        let quads = parser.parse_parallel(doc.as_bytes(), chunk_bytes).unwrap();
+        assert_eq!(quads.len(), 500);
+        assert_eq!(quads, expected);
+    }
+
+    #[test]
+    pub fn reports_first_error_with_document_line_number() {
+        Lazy::force(&TRACING);
+        let parser = DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+            .unwrap();
+        let mut lines: Vec<_> = doc(syntax::N_QUADS, 500)
+            .lines()
+            .map(String::from)
+            .collect();
+        lines[299] = "<tag:s> <tag:p> .".into();
+        lines[399] = "<tag:s> <tag:p> .".into();
+        let doc = lines.join("\n");
+        let sequential = parser
+            .parse_str(&doc)
+            .into_iter()
+            .find_map(Result::err)
+            .unwrap();
+
+        let e = parser.parse_parallel(doc.as_bytes(), 100).unwrap_err();
+        assert_eq!(e.code(), error_code::DYNSYN_PARSE_SYNTAX);
+        assert_eq!(e.line_number(), Some(300));
+        assert_eq!(e.line_number(), sequential.line_number());
+    }
+
+    #[test]
+    pub fn reports_progress_of_whole_document_monotonically() {
+        Lazy::force(&TRACING);
+        let reports = Arc::new(Mutex::new(Vec::<Progress>::new()));
+        let sink = reports.clone();
+        let mut config_map = TypeMap::new();
+        config_map.insert(ProgressConfig::new(50, move |p| {
+            sink.lock().unwrap().push(p)
+        }));
+        let parser = DynSynQuadParserFactory::new(Some(config_map))
+            .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+            .unwrap();
+        let doc = doc(syntax::N_QUADS, 500);
+
+        parser.parse_parallel(doc.as_bytes(), 100).unwrap();
+        let reports = reports.lock().unwrap();
+        assert!(reports.len() > 2);
+        assert!(reports
+            .windows(2)
+            .all(|w| w[0].statements <= w[1].statements
+                && w[0].bytes_read <= w[1].bytes_read
+                && !w[0].finished));
+        let last = reports.last().unwrap();
+        assert_eq!(
+            (last.statements, last.bytes_read, last.finished),
+            (500, doc.len() as u64, true)
+        );
+    }
+
+    #[test]
+    pub fn counts_graphs_across_whole_document() {
+        Lazy::force(&TRACING);
+        let mut config_map = TypeMap::new();
+        config_map.insert(GraphCardinalityLimit::new(2, LimitAction::Error));
+        let parser = DynSynQuadParserFactory::new(Some(config_map))
+            .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+            .unwrap();
+        let e = parser
+            .parse_parallel(doc(syntax::N_QUADS, 500).as_bytes(), 100)
+            .unwrap_err();
+        assert_eq!(e.code(), error_code::DYNSYN_LIMIT_EXCEEDED);
+    }
+
+    #[test]
+    pub fn adapts_triples_into_configured_graph() {
+        Lazy::force(&TRACING);
+        let parser = DynSynQuadParserFactory::default()
+            .try_new_parser(
+                syntax::N_TRIPLES,
+                None,
+                Some(BoxTerm::new_iri("tag:g").unwrap()),
+            )
+            .unwrap();
+        let quads = parser
+            .parse_parallel(doc(syntax::N_TRIPLES, 100).as_bytes(), 100)
+            .unwrap();
+        assert!(quads
+            .iter()
+            .all(|(_, g)| g.as_ref().map(|g| g.value().to_string()).as_deref() == Some("tag:g")));
+    }
+
+    #[test_case(true, false; "skolemization")]
+    #[test_case(false, true; "quoted triple reification")]
+    pub fn maps_terms_reused_across_chunks_consistently(
+        skolemize: bool,
+        reify_quoted_triples: bool,
+    ) {
+        Lazy::force(&TRACING);
+        let mut config_map = TypeMap::new();
+        if skolemize {
+            config_map.insert(Skolemization::try_new("https://data.example").unwrap());
+        }
+        let mut parser = DynSynQuadParserFactory::new(Some(config_map))
+            .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+            .unwrap();
+        parser.set_reify_quoted_triples(reify_quoted_triples);
+        // Same blank node, or quoted triple is on every line, and hence in every chunk.
+        let doc: String = (0..200)
+            .map(|i| match reify_quoted_triples {
+                true => format!("<< <tag:a> <tag:b> <tag:c> >> <tag:p> \"{}\" .\n", i),
+                false => format!("<tag:s{}> <tag:p> _:b .\n", i),
+            })
+            .collect();
+        let sequential = parser.parse_str(&doc).into_iter().count();
+
+        let quads = parser.parse_parallel(doc.as_bytes(), 100).unwrap();
+        assert_eq!(quads.len(), sequential);
+        let p = BoxTerm::new_iri("tag:p").unwrap();
+        let nodes: HashSet<_> = quads
+            .iter()
+            .filter(|([_, qp, _], _)| qp == &p)
+            .map(|([s, _, o], _)| {
+                if reify_quoted_triples {
+                    s.clone()
+                } else {
+                    o.clone()
+                }
+            })
+            .collect();
+        assert_eq!(nodes.len(), 1);
+    }
+}
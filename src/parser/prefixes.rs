@@ -0,0 +1,226 @@
+//! This module defines [`DocumentPrefixes`], prefix bindings, and base iri of a parsed document. They are retrieved from sources with [`DynSynQuadSource::prefixes`](super::quads::source::DynSynQuadSource::prefixes), and [`DynSynTripleSource::prefixes`](super::triples::source::DynSynTripleSource::prefixes), so that converters can re-emit same prefixes on serialization.
+//!
+//! Prefixes are tracked for turtle-family syntaxes, i.e. [turtle](crate::syntax::TURTLE), [trig](crate::syntax::TRIG), and [n3](crate::syntax::N3), from both `@prefix`, and sparql style `PREFIX` directives. Relative iris in directives are resolved against base iri in effect.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{parser::triples::DynSynTripleParserFactory, syntax};
+//! use sophia_api::{parser::TripleParser, triple::stream::TripleSource};
+//! use sophia_term::BoxTerm;
+//! use sophia_turtle::serializer::turtle::TurtleConfig;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let parser = DynSynTripleParserFactory::default()
+//!     .try_new_parser::<BoxTerm>(syntax::TURTLE, Some("http://example.org/".into()), None)?;
+//! let doc = r#"
+//!     @prefix foaf: <http://xmlns.com/foaf/0.1/> .
+//!     @base <people/> .
+//!     PREFIX me: <me#>
+//!     me:i foaf:name "Alice" .
+//! "#;
+//!
+//! let mut source = parser.parse_str(doc);
+//! source.for_each_triple(|_| {})?;
+//! let prefixes = source.prefixes();
+//! assert_eq!(prefixes.base_iri(), Some("http://example.org/people/"));
+//! assert_eq!(prefixes.prefix_map().len(), 2);
+//! assert_eq!(&prefixes.prefix_map()[1].1[..], "http://example.org/people/me#");
+//!
+//! let config = TurtleConfig::new().with_own_prefix_map(prefixes.prefix_map().to_vec());
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use sophia_api::prefix::PrefixBox;
+use sophia_iri::{resolve::IriParsed, resolve::Resolve, IriBox};
+
+use super::_inner::lexer::Lexeme;
+
+/// Prefix bindings, and base iri of a parsed document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocumentPrefixes {
+    base_iri: Option<String>,
+    prefix_map: Vec<(PrefixBox, IriBox)>,
+}
+
+impl DocumentPrefixes {
+    pub(crate) fn new(base_iri: Option<String>) -> Self {
+        Self {
+            base_iri,
+            prefix_map: Vec::new(),
+        }
+    }
+
+    /// Returns base iri of the document, that is either given to parser, or set by last base directive of the document.
+    pub fn base_iri(&self) -> Option<&str> {
+        self.base_iri.as_deref()
+    }
+
+    /// Returns prefix bindings of the document, in order of their first declaration. A redeclared prefix is bound to it's last iri.
+    pub fn prefix_map(&self) -> &[(PrefixBox, IriBox)] {
+        &self.prefix_map
+    }
+
+    fn resolved(&self, iri: &[u8]) -> Option<String> {
+        let iri = std::str::from_utf8(iri).ok()?;
+        match &self.base_iri {
+            Some(base) => {
+                let base = IriParsed::new(base).ok()?;
+                let resolved: Result<_, _> = base.resolve(iri);
+                resolved.ok().map(|iri| iri.to_string())
+            }
+            None => Some(iri.to_owned()),
+        }
+    }
+
+    fn bind(&mut self, prefix: &[u8], iri: &[u8]) {
+        let prefix = match std::str::from_utf8(prefix).map(|p| PrefixBox::new(p.into())) {
+            Ok(Ok(prefix)) => prefix,
+            _ => return,
+        };
+        let iri = match self.resolved(iri).map(|iri| IriBox::new(iri.into())) {
+            Some(Ok(iri)) => iri,
+            _ => return,
+        };
+        match self.prefix_map.iter_mut().find(|(p, _)| *p == prefix) {
+            Some(binding) => binding.1 = iri,
+            None => self.prefix_map.push((prefix, iri)),
+        }
+    }
+
+    fn rebase(&mut self, iri: &[u8]) {
+        if let Some(iri) = self.resolved(iri) {
+            self.base_iri = Some(iri);
+        }
+    }
+}
+
+/// State of a [`PrefixTracker`] in a directive.
+#[derive(Debug)]
+enum DirectiveState {
+    Idle,
+    Prefix,
+    PrefixName(Vec<u8>),
+    Base,
+}
+
+/// A tracker of prefix, and base directives of turtle-family documents.
+#[derive(Debug)]
+pub(crate) struct PrefixTracker {
+    state: DirectiveState,
+    prefixes: Arc<Mutex<DocumentPrefixes>>,
+}
+
+impl PrefixTracker {
+    pub fn new(prefixes: Arc<Mutex<DocumentPrefixes>>) -> Self {
+        Self {
+            state: DirectiveState::Idle,
+            prefixes,
+        }
+    }
+
+    /// Observes given lexeme.
+    pub fn observe(&mut self, lexeme: Lexeme<'_>) {
+        let state = std::mem::replace(&mut self.state, DirectiveState::Idle);
+        self.state = match (state, lexeme) {
+            (_, Lexeme::Word(w)) if w == b"@prefix" || w.eq_ignore_ascii_case(b"prefix") => {
+                DirectiveState::Prefix
+            }
+            (_, Lexeme::Word(w)) if w == b"@base" || w.eq_ignore_ascii_case(b"base") => {
+                DirectiveState::Base
+            }
+            (DirectiveState::Prefix, Lexeme::Word(w)) if w.ends_with(b":") => {
+                DirectiveState::PrefixName(w[..w.len() - 1].to_vec())
+            }
+            (DirectiveState::PrefixName(prefix), Lexeme::Iri(iri)) => {
+                lock(&self.prefixes).bind(&prefix, iri);
+                DirectiveState::Idle
+            }
+            (DirectiveState::Base, Lexeme::Iri(iri)) => {
+                lock(&self.prefixes).rebase(iri);
+                DirectiveState::Idle
+            }
+            _ => DirectiveState::Idle,
+        };
+    }
+}
+
+/// Locks given prefixes. Prefixes are always left consistent, hence a poisoned lock is recovered.
+pub(crate) fn lock(
+    prefixes: &Mutex<DocumentPrefixes>,
+) -> std::sync::MutexGuard<'_, DocumentPrefixes> {
+    prefixes.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use sophia_api::{parser::QuadParser, quad::stream::QuadSource};
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+
+    use crate::{
+        parser::quads::DynSynQuadParserFactory,
+        syntax::{self, RdfSyntax},
+        tests::TRACING,
+    };
+
+    #[test_case(
+        syntax::TURTLE,
+        "@prefix ex: <http://a/> .\n@prefix : <http://b/>.\n<tag:s> <tag:p> ex:o .",
+        &[("ex", "http://a/"), ("", "http://b/")]
+    )]
+    #[test_case(
+        syntax::TURTLE,
+        "@prefix ex: <http://a/> .\n@prefix ex: <http://c/> .\n<tag:s> <tag:p> ex:o .",
+        &[("ex", "http://c/")]
+    )]
+    #[test_case(
+        syntax::TURTLE,
+        "# @prefix no: <http://n/> .\n<tag:s> <tag:p> \"@prefix no: <http://n/> .\", '''\nprefix no: <http://n/>''' .",
+        &[]
+    )]
+    #[test_case(
+        syntax::TRIG,
+        "PREFIX ex: <http://a/>\n<tag:g> { <tag:s> <tag:p> ex:o . }",
+        &[("ex", "http://a/")]
+    )]
+    #[test_case(syntax::N_QUADS, "<tag:s> <tag:p> <tag:o> .", &[])]
+    pub fn prefixes_will_be_tracked(syntax_: RdfSyntax, doc: &str, expected: &[(&str, &str)]) {
+        Lazy::force(&TRACING);
+        let parser = DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap();
+        let mut source = parser.parse_str(doc);
+        source.for_each_quad(|_| {}).unwrap();
+        let prefixes = source.prefixes();
+        let bindings: Vec<_> = prefixes
+            .prefix_map()
+            .iter()
+            .map(|(p, iri)| (&p[..], &iri[..]))
+            .collect();
+        assert_eq!(bindings, expected);
+        assert_eq!(prefixes.base_iri(), None);
+    }
+
+    #[test]
+    pub fn base_directives_will_rebase_document() {
+        Lazy::force(&TRACING);
+        let parser = DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::TURTLE, Some("http://e/a/b".into()), None)
+            .unwrap();
+        let mut source =
+            parser.parse_str("@base <c/> .\nBASE <../d/>\n@prefix x: <y#> .\n<s> <p> <o> .");
+        source.for_each_quad(|_| {}).unwrap();
+        let prefixes = source.prefixes();
+        assert_eq!(prefixes.base_iri(), Some("http://e/a/d/"));
+        assert_eq!(&prefixes.prefix_map()[0].1[..], "http://e/a/d/y#");
+    }
+}
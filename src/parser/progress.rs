@@ -0,0 +1,183 @@
+//! This module defines progress reporting of dynsyn sources. Long-running conversions can show progress bars, as sources know both bytes read from the document, and statements emitted so far.
+//!
+//! Progress reporting is configured by inserting a [`ProgressConfig`] into `parser_config_map` of parser factories, or with `set_progress` method of parsers. Sources call configured callback after every `every_n_statements` emitted statements, and once more after the document is exhausted. Bytes are counted as they are consumed by underlying parser, which may be ahead of emitted statements by a buffer.
+//!
+//! Example:
+//!
+//! ```
+//! use std::sync::{Arc, Mutex};
+//!
+//! use rdf_dynsyn::{
+//!     parser::{progress::ProgressConfig, triples::DynSynTripleParserFactory},
+//!     syntax,
+//! };
+//! use sophia_api::{parser::TripleParser, triple::stream::TripleSource};
+//! use sophia_term::BoxTerm;
+//! use type_map::concurrent::TypeMap;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let reports = Arc::new(Mutex::new(Vec::new()));
+//! let sink = reports.clone();
+//! let mut config_map = TypeMap::new();
+//! config_map.insert(ProgressConfig::new(2, move |progress| {
+//!     sink.lock().unwrap().push(progress.statements);
+//! }));
+//! let parser = DynSynTripleParserFactory::new(Some(config_map))
+//!     .try_new_parser::<BoxTerm>(syntax::N_TRIPLES, None, None)?;
+//!
+//! let doc = "<tag:s> <tag:p> \"1\" .\n<tag:s> <tag:p> \"2\" .\n<tag:s> <tag:p> \"3\" .\n";
+//! parser.parse_str(doc).for_each_triple(|_| {})?;
+//! assert_eq!(*reports.lock().unwrap(), vec![2, 3]);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// Progress of a source, as reported to a [`ProgressCallback`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Progress {
+    /// number of bytes of the document read so far
+    pub bytes_read: u64,
+    /// number of statements emitted so far
+    pub statements: usize,
+    /// true, if the document is exhausted
+    pub finished: bool,
+}
+
+/// A callback, that sources call to report their progress.
+pub type ProgressCallback = Arc<dyn Fn(Progress) + Send + Sync>;
+
+/// Configuration of progress reporting of sources.
+#[derive(Clone)]
+pub struct ProgressConfig {
+    /// Progress is reported after every these many emitted statements.
+    pub every_n_statements: usize,
+    /// Callback to report progress to.
+    pub callback: ProgressCallback,
+}
+
+impl std::fmt::Debug for ProgressConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProgressConfig")
+            .field("every_n_statements", &self.every_n_statements)
+            .finish()
+    }
+}
+
+impl ProgressConfig {
+    /// Create a new config, that reports progress to given `callback` after every `every_n_statements` emitted statements. Zero is treated as one.
+    pub fn new<F>(every_n_statements: usize, callback: F) -> Self
+    where
+        F: Fn(Progress) + Send + Sync + 'static,
+    {
+        Self {
+            every_n_statements,
+            callback: Arc::new(callback),
+        }
+    }
+}
+
+/// Tracks progress of a single source, and reports it as per config.
+pub(crate) struct ProgressTracker {
+    config: ProgressConfig,
+    bytes_read: Arc<AtomicU64>,
+    statements: usize,
+    /// Statements emitted since last report.
+    unreported: usize,
+    finished: bool,
+}
+
+impl ProgressTracker {
+    pub(crate) fn new(config: ProgressConfig, bytes_read: Arc<AtomicU64>) -> Self {
+        Self {
+            config,
+            bytes_read,
+            statements: 0,
+            unreported: 0,
+            finished: false,
+        }
+    }
+
+    fn report(&self) {
+        (self.config.callback)(Progress {
+            bytes_read: self.bytes_read.load(Ordering::Relaxed),
+            statements: self.statements,
+            finished: self.finished,
+        });
+    }
+
+    /// Records an emitted statement, and reports progress if it is due.
+    pub(crate) fn on_statement(&mut self) {
+        self.statements += 1;
+        self.unreported += 1;
+        if self.unreported >= self.config.every_n_statements {
+            self.unreported = 0;
+            self.report();
+        }
+    }
+
+    /// Records `n` emitted statements at once, and reports progress once, if it is due.
+    #[cfg(any(test, feature = "parallel"))]
+    pub(crate) fn on_statements(&mut self, n: usize) {
+        self.statements += n;
+        self.unreported += n;
+        if self.unreported >= self.config.every_n_statements {
+            self.unreported %= self.config.every_n_statements.max(1);
+            self.report();
+        }
+    }
+
+    /// Reports final progress, once document is exhausted.
+    pub(crate) fn on_end(&mut self) {
+        if !self.finished {
+            self.finished = true;
+            self.report();
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    };
+
+    use once_cell::sync::Lazy;
+
+    use super::{Progress, ProgressConfig, ProgressTracker};
+    use crate::tests::TRACING;
+
+    #[test]
+    pub fn reports_every_n_statements_and_once_at_end() {
+        Lazy::force(&TRACING);
+        let reports = Arc::new(Mutex::new(Vec::<Progress>::new()));
+        let sink = reports.clone();
+        let bytes_read = Arc::new(AtomicU64::new(0));
+        let mut tracker = ProgressTracker::new(
+            ProgressConfig::new(3, move |p| sink.lock().unwrap().push(p)),
+            bytes_read.clone(),
+        );
+        for i in 0..7 {
+            bytes_read.store(10 * (i + 1), Ordering::Relaxed);
+            tracker.on_statement();
+        }
+        tracker.on_end();
+        tracker.on_end();
+
+        let reports = reports.lock().unwrap();
+        assert_eq!(
+            reports.iter().map(|p| (p.statements, p.bytes_read, p.finished)).collect::<Vec<_>>(),
+            vec![(3, 30, false), (6, 60, false), (7, 70, true)]
+        );
+    }
+}
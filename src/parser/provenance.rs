@@ -0,0 +1,21 @@
+//! This module defines types to report provenance of statements streamed through dynsyn sources. Auditing pipelines can use them to distinguish statements, as they are asserted in a document, from those adapted across triple/quad boundary, like quads with injected graph names.
+
+/// Provenance of statements emitted by a dynsyn source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatementProvenance {
+    /// Statements are emitted, as they are asserted in the document.
+    Native,
+    /// Statements are adapted across triple/quad boundary. Either quads are synthesized from triples with configured graph name, or triples are extracted from quads of configured graph.
+    Adapted,
+}
+
+/// A running summary of statements streamed through a dynsyn source.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProvenanceSummary {
+    /// number of statements emitted, as they are asserted in the document
+    pub native: usize,
+    /// number of statements emitted after adaptation across triple/quad boundary
+    pub adapted: usize,
+    /// number of underlying quads dropped in adaptation, as they are not in configured graph
+    pub dropped: usize,
+}
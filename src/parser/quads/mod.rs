@@ -1,25 +1,56 @@
-use std::io::BufRead;
+use std::{
+    io::BufRead,
+    path::Path,
+    sync::{Arc, Mutex},
+};
 
+use mime::Mime;
 use sophia_api::{
     parser::{QuadParser, TripleParser},
     term::{CopyTerm, TTerm},
 };
 use type_map::concurrent::TypeMap;
 
-use crate::syntax::{RdfSyntax, UnKnownSyntaxError};
+use crate::{
+    correspondence::{syntax_for_path, Correspondent, ForPathError},
+    iri_resolver::DynIriResolver,
+    syntax::{RdfSyntax, UnKnownSyntaxError},
+    syntax_hint::SyntaxHint,
+};
 
 use self::source::DynSynQuadSource;
 
-use super::_inner::InnerParser;
+use super::{
+    _inner::{
+        lexer::{is_turtle_family, DocumentTracker},
+        source::{skip_bom, sniff_input},
+        InnerParser,
+    },
+    errors::{ParserForHintError, ParserForMediaTypeError},
+    iri_policy::{IriPolicy, IriPolicyGuard},
+    iri_validation::{IriNormalizer, IriValidation, LineIriResolver},
+    limits::{
+        BudgetedRead, DocumentLimitGuard, DocumentLimits, GraphCardinalityGuard,
+        GraphCardinalityLimit, SessionBudget,
+    },
+    literal_policy::{LiteralPolicy, LiteralPolicyGuard},
+    prefixes::{DocumentPrefixes, PrefixTracker},
+    progress::{ProgressConfig, ProgressTracker},
+    rdf_star,
+    recovery::ParserConfig,
+    skolem::{Skolemization, Skolemizer},
+};
 
 pub mod source;
 
 /// This parser implements [`sophia_api::parser::QuadParser`] trait, and can be instantiated at runtime against any of supported syntaxes using [`DynSynQuadParserFactory`] factory. It is generic over type of terms in quads it produces.
 ///
-/// It can currently parse quads from documents in any of concrete_syntaxes: [`n-quads`](crate::syntax::N_QUADS), [`trig`](crate::syntax::TRIG), [`turtle`](crate::syntax::TURTLE), [`n-triples`](crate::syntax::N_TRIPLES), [rdf-xml](crate::syntax::RDF_XML). For docs in any of these syntaxes, this parser will stream quads through [`DynSynQuadSource`] instance.
+/// It can currently parse quads from documents in any of concrete_syntaxes: [`n-quads`](crate::syntax::N_QUADS), [`trig`](crate::syntax::TRIG), [`trix`](crate::syntax::TRIX) with `trix` feature, [`turtle`](crate::syntax::TURTLE), [`n-triples`](crate::syntax::N_TRIPLES), [rdf-xml](crate::syntax::RDF_XML), turtle-compatible subset of [`n3`](crate::syntax::N3) with `n3` feature, [`html+rdfa`](crate::syntax::HTML_RDFA), and [`xhtml+rdfa`](crate::syntax::XHTML_RDFA) with `rdfa` feature, and binary [`hdt`](crate::syntax::HDT) with `hdt` feature. For docs in any of these syntaxes, this parser will stream quads through [`DynSynQuadSource`] instance.
 ///
 /// For syntaxes that doesn't support quads, like [`turtle`](crate::syntax::TURTLE), [`n-triples`](crate::syntax::N_TRIPLES), [rdf-xml](crate::syntax::RDF_XML), etc.. This parser can be configured with preferred graph_name term for quads that are adapted from underlying triples.
 ///
+/// Empty and whitespace-only documents are parsed into an empty dataset without error in every syntax. A leading utf-8 byte order mark is skipped.
+///
 /// Example:
 ///
 /// ```
@@ -74,7 +105,20 @@ where
     T: TTerm + CopyTerm + Clone,
 {
     inner_parser: InnerParser,
+    base_iri: Option<String>,
     triple_source_adapted_graph_iri: Option<T>,
+    iri_resolver: Option<DynIriResolver>,
+    graph_cardinality_limit: Option<GraphCardinalityLimit>,
+    session_budget: Option<SessionBudget>,
+    iri_policy: Option<IriPolicy>,
+    literal_policy: Option<LiteralPolicy>,
+    document_limits: Option<DocumentLimits>,
+    skolemization: Option<Skolemization>,
+    progress: Option<ProgressConfig>,
+    lenient: bool,
+    reify_quoted_triples: bool,
+    iri_validation: IriValidation,
+    preserve_bnode_labels: bool,
 }
 
 impl<T> DynSynQuadParser<T>
@@ -86,11 +130,301 @@ where
         base_iri: Option<String>,
         triple_source_adapted_graph_iri: Option<T>,
     ) -> Result<Self, UnKnownSyntaxError> {
-        let inner_parser = InnerParser::try_new(syntax_, base_iri)?;
-        Ok(Self {
+        let inner_parser = InnerParser::try_new(syntax_, base_iri.clone())?;
+        Ok(Self::new_for(
+            inner_parser,
+            base_iri,
+            triple_source_adapted_graph_iri,
+        ))
+    }
+
+    /// Try to create a parser in generalized mode, for given `syntax_`. See [`try_new_generalized_parser`](DynSynQuadParserFactory::try_new_generalized_parser).
+    pub(crate) fn try_new_generalized(
+        syntax_: RdfSyntax,
+        base_iri: Option<String>,
+        triple_source_adapted_graph_iri: Option<T>,
+    ) -> Result<Self, UnKnownSyntaxError> {
+        let inner_parser = InnerParser::try_new_generalized(syntax_, base_iri.clone())?;
+        Ok(Self::new_for(
+            inner_parser,
+            base_iri,
+            triple_source_adapted_graph_iri,
+        ))
+    }
+
+    fn new_for(
+        inner_parser: InnerParser,
+        base_iri: Option<String>,
+        triple_source_adapted_graph_iri: Option<T>,
+    ) -> Self {
+        Self {
             inner_parser,
+            base_iri,
             triple_source_adapted_graph_iri,
-        })
+            iri_resolver: None,
+            graph_cardinality_limit: None,
+            session_budget: None,
+            iri_policy: None,
+            literal_policy: None,
+            document_limits: None,
+            skolemization: None,
+            progress: None,
+            lenient: false,
+            reify_quoted_triples: false,
+            iri_validation: IriValidation::default(),
+            preserve_bnode_labels: false,
+        }
+    }
+
+    /// Returns syntax, this parser is currently targeted at.
+    pub fn syntax(&self) -> RdfSyntax {
+        self.inner_parser.syntax()
+    }
+
+    /// Retargets this parser to given `syntax_`, keeping rest of it's configuration, like base iri, and adapted graph iri. It allows a single long-lived parser to handle documents of whatever syntax each request carries.
+    ///
+    /// # Errors
+    /// returns [`UnKnownSyntaxError`] if requested syntax is not known/supported, or is not supported in generalized mode, if parser is in it. Parser is left unchanged in that case.
+    pub fn retarget(&mut self, syntax_: RdfSyntax) -> Result<(), UnKnownSyntaxError> {
+        let base_iri = self.resolved_base(self.base_iri.as_deref());
+        self.inner_parser = if self.is_generalized() {
+            InnerParser::try_new_generalized(syntax_, base_iri)?
+        } else {
+            InnerParser::try_new(syntax_, base_iri)?
+        };
+        Ok(())
+    }
+
+    /// Returns true, if this parser is in generalized mode. See [`try_new_generalized_parser`](DynSynQuadParserFactory::try_new_generalized_parser).
+    pub fn is_generalized(&self) -> bool {
+        self.inner_parser.is_generalized()
+    }
+
+    /// Returns true, if this parser is lenient. See [`recovery`](super::recovery) module.
+    pub fn is_lenient(&self) -> bool {
+        self.lenient
+    }
+
+    /// Makes this parser lenient, or strict, overriding [`ParserConfig`] from factory config, if any. Sources of a lenient parser skip malformed statements, and record them in their [`skip_summary`](source::DynSynQuadSource::skip_summary).
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.lenient = lenient;
+    }
+
+    /// Returns true, if this parser reifies quoted triples of rdf-star documents. See [`rdf_star`](super::rdf_star) module.
+    pub fn reifies_quoted_triples(&self) -> bool {
+        self.reify_quoted_triples
+    }
+
+    /// Enables, or disables reification of quoted triples by this parser, overriding [`ParserConfig`] from factory config, if any. If enabled, quoted triples of turtle family documents are read as blank nodes, and their rdf reification statements are asserted along with statements of document. Otherwise documents with quoted triples are rejected.
+    pub fn set_reify_quoted_triples(&mut self, reify_quoted_triples: bool) {
+        self.reify_quoted_triples = reify_quoted_triples;
+    }
+
+    /// Returns true, if sources of this parser preserve blank node labels of documents.
+    pub fn preserves_bnode_labels(&self) -> bool {
+        self.preserve_bnode_labels
+    }
+
+    /// Makes sources of this parser preserve blank node labels of documents, or not, overriding [`ParserConfig`] from factory config, if any. If enabled, rdfa documents keep their labels, instead of having them mapped to generated ids, and blank nodes are not skolemized, even if a [`Skolemization`] is configured. Other syntaxes always keep labels, except that turtle family parsers suffix labels like `riog00000001`, which they could have generated, with `d`.
+    pub fn set_preserve_bnode_labels(&mut self, preserve_bnode_labels: bool) {
+        self.preserve_bnode_labels = preserve_bnode_labels;
+    }
+
+    /// Sets progress reporting config of sources of this parser, overriding [`ProgressConfig`] from factory config, if any. See [`progress`](super::progress) module.
+    pub fn set_progress(&mut self, progress: Option<ProgressConfig>) {
+        self.progress = progress;
+    }
+
+    /// Returns mode, in which this parser validates iris of n-triples, and n-quads documents. See [`iri_validation`](super::iri_validation) module.
+    pub fn iri_validation(&self) -> IriValidation {
+        self.iri_validation
+    }
+
+    /// Sets mode, in which this parser validates iris of n-triples, and n-quads documents, overriding [`ParserConfig`] from factory config, if any.
+    pub fn set_iri_validation(&mut self, iri_validation: IriValidation) {
+        self.iri_validation = iri_validation;
+    }
+
+    /// Configures this parser with given iri resolver.
+    pub(crate) fn with_iri_resolver(mut self, iri_resolver: Option<DynIriResolver>) -> Self {
+        self.iri_resolver = iri_resolver;
+        self.inner_parser = self
+            .inner_parser
+            .with_base(self.resolved_base(self.base_iri.as_deref()));
+        self
+    }
+
+    /// Configures this parser with given session budget, replacing one from factory config, if any.
+    pub(crate) fn with_session_budget(mut self, session_budget: Option<SessionBudget>) -> Self {
+        self.session_budget = session_budget;
+        self
+    }
+
+    /// Returns graph cardinality limit, this parser is configured with.
+    #[cfg(any(test, feature = "parallel"))]
+    pub(crate) fn graph_cardinality_limit(&self) -> Option<GraphCardinalityLimit> {
+        self.graph_cardinality_limit
+    }
+
+    /// Returns session budget, this parser is configured with.
+    #[cfg(any(test, feature = "async"))]
+    pub(crate) fn session_budget(&self) -> Option<&SessionBudget> {
+        self.session_budget.as_ref()
+    }
+
+    /// Returns document limits, this parser is configured with.
+    #[cfg(any(test, feature = "parallel"))]
+    pub(crate) fn document_limits(&self) -> Option<DocumentLimits> {
+        self.document_limits
+    }
+
+    /// Returns progress reporting config, this parser is configured with.
+    #[cfg(any(test, feature = "parallel"))]
+    pub(crate) fn progress(&self) -> Option<&ProgressConfig> {
+        self.progress.as_ref()
+    }
+
+    /// Returns skolemization config, this parser is configured with.
+    #[cfg(any(test, feature = "parallel"))]
+    pub(crate) fn skolemization(&self) -> Option<&Skolemization> {
+        self.skolemization.as_ref()
+    }
+
+    fn resolved_base(&self, base_iri: Option<&str>) -> Option<String> {
+        match &self.iri_resolver {
+            Some(resolver) => resolver.0.resolve_base(base_iri),
+            None => base_iri.map(String::from),
+        }
+    }
+
+    /// Parses `data` like [`QuadParser::parse`], but resolves relative iris against given `base_iri`, instead of base iri this parser was constructed with. It allows a single parser instance to serve many documents with different bases.
+    ///
+    /// Relative iris of syntaxes that don't support them, like n-triples, and n-quads are resolved against `base_iri` as per parser's [`IriValidation`] mode.
+    pub fn parse_with_base<R: BufRead>(
+        &self,
+        data: R,
+        base_iri: Option<String>,
+    ) -> DynSynQuadSource<T, R> {
+        let base_iri = self.resolved_base(base_iri.as_deref());
+        self.parse_with_inner(&self.inner_parser.with_base(base_iri.clone()), base_iri, data)
+    }
+
+    fn parse_with_inner<R: BufRead>(
+        &self,
+        inner_parser: &InnerParser,
+        base_iri: Option<String>,
+        mut data: R,
+    ) -> DynSynQuadSource<T, R> {
+        skip_bom(&mut data);
+        let syntax_ = inner_parser.syntax();
+        let sniff = sniff_input(&mut data, syntax_);
+        let document_limit_guard = self.document_limits.map(DocumentLimitGuard::new);
+        let prefixes = Arc::new(Mutex::new(DocumentPrefixes::new(
+            inner_parser.base_iri().map(String::from),
+        )));
+        let document_tracker = is_turtle_family(syntax_).then(|| {
+            DocumentTracker::new(
+                document_limit_guard
+                    .as_ref()
+                    .and_then(DocumentLimitGuard::nesting_tracker),
+                PrefixTracker::new(prefixes.clone()),
+            )
+        });
+        let is_line_based = matches!(
+            inner_parser,
+            InnerParser::NTriples(_) | InnerParser::NQuads(_)
+        );
+        let line_iri_resolver = base_iri
+            .clone()
+            .filter(|_| is_line_based)
+            .map(LineIriResolver::new);
+        let data = BudgetedRead::new(data, self.session_budget.clone())
+            .with_document_tracker(document_tracker)
+            .with_line_iri_resolver(line_iri_resolver);
+        let progress_tracker = self
+            .progress
+            .clone()
+            .map(|config| ProgressTracker::new(config, data.bytes_read()));
+        let tsg_iri = self.triple_source_adapted_graph_iri.clone();
+        let iri_normalizer = Some(IriNormalizer::new(self.iri_validation, base_iri));
+        // TODO may have to abstract over literal repetition
+        let source = match inner_parser {
+            InnerParser::NQuads(p) => DynSynQuadSource::new_for(
+                rdf_star::adapt(p.parse(data), self.reify_quoted_triples, iri_normalizer).into(),
+                tsg_iri,
+                sniff,
+                syntax_,
+            ),
+            InnerParser::TriG(p) => DynSynQuadSource::new_for(
+                rdf_star::adapt(p.parse(data), self.reify_quoted_triples, None).into(),
+                tsg_iri,
+                sniff,
+                syntax_,
+            ),
+            InnerParser::NTriples(p) => DynSynQuadSource::new_for(
+                rdf_star::adapt(p.parse(data), self.reify_quoted_triples, iri_normalizer).into(),
+                tsg_iri,
+                sniff,
+                syntax_,
+            ),
+            InnerParser::Turtle(p) => DynSynQuadSource::new_for(
+                rdf_star::adapt(p.parse(data), self.reify_quoted_triples, None).into(),
+                tsg_iri,
+                sniff,
+                syntax_,
+            ),
+            InnerParser::Generalized(p, _) => DynSynQuadSource::new_for(
+                rdf_star::adapt_generalized(p.parse(data)).into(),
+                tsg_iri,
+                sniff,
+                syntax_,
+            ),
+            #[cfg(feature = "rdf-xml")]
+            InnerParser::RdfXml(p) => {
+                DynSynQuadSource::new_for(p.parse(data).into(), tsg_iri, sniff, syntax_)
+            }
+            #[cfg(feature = "n3")]
+            InnerParser::N3(p) => DynSynQuadSource::new_for(
+                rdf_star::adapt(p.parse(data), self.reify_quoted_triples, None).into(),
+                tsg_iri,
+                sniff,
+                syntax_,
+            ),
+            #[cfg(feature = "rdfa")]
+            InnerParser::Rdfa(p) => {
+                let p = super::rdfa::RdfaParser {
+                    preserve_bnode_labels: self.preserve_bnode_labels,
+                    ..p.clone()
+                };
+                DynSynQuadSource::new_for(p.parse(data).into(), tsg_iri, sniff, syntax_)
+            }
+            #[cfg(feature = "trix")]
+            InnerParser::Trix(p) => {
+                DynSynQuadSource::new_for(p.parse(data).into(), tsg_iri, sniff, syntax_)
+            }
+            #[cfg(feature = "hdt")]
+            InnerParser::Hdt(p) => {
+                DynSynQuadSource::new_for(p.parse(data).into(), tsg_iri, sniff, syntax_)
+            }
+        };
+        source
+            .with_graph_cardinality_guard(
+                self.graph_cardinality_limit.map(GraphCardinalityGuard::new),
+            )
+            .with_iri_resolver(self.iri_resolver.clone())
+            .with_session_budget(self.session_budget.clone())
+            .with_iri_policy_guard(self.iri_policy.clone().map(IriPolicyGuard::new))
+            .with_literal_policy_guard(self.literal_policy.clone().map(LiteralPolicyGuard::new))
+            .with_document_limit_guard(document_limit_guard)
+            .with_skolemizer(
+                self.skolemization
+                    .as_ref()
+                    .filter(|_| !self.preserve_bnode_labels)
+                    .map(Skolemizer::new),
+            )
+            .with_progress_tracker(progress_tracker)
+            .with_prefixes(prefixes)
+            .with_lenient(self.lenient)
     }
 }
 
@@ -102,34 +436,27 @@ where
     type Source = DynSynQuadSource<T, R>;
 
     fn parse(&self, data: R) -> Self::Source {
-        let tsg_iri = self.triple_source_adapted_graph_iri.clone();
-        // TODO may have to abstract over literal repetition
-        match &self.inner_parser {
-            InnerParser::NQuads(p) => DynSynQuadSource::new_for(p.parse(data).into(), tsg_iri),
-            InnerParser::TriG(p) => DynSynQuadSource::new_for(p.parse(data).into(), tsg_iri),
-            InnerParser::NTriples(p) => DynSynQuadSource::new_for(p.parse(data).into(), tsg_iri),
-            InnerParser::Turtle(p) => DynSynQuadSource::new_for(p.parse(data).into(), tsg_iri),
-            InnerParser::RdfXml(p) => DynSynQuadSource::new_for(p.parse(data).into(), tsg_iri),
-        }
+        self.parse_with_inner(
+            &self.inner_parser,
+            self.resolved_base(self.base_iri.as_deref()),
+            data,
+        )
     }
 }
 
 /// A factory to instantiate [`DynSynQuadParser`].
+///
+/// Factory is `Send + Sync`, and keeps its config map behind an [`Arc`]. Hence it is cheap to clone, and clones can be shared across threads, like in application state of web servers.
+#[derive(Debug, Clone)]
 pub struct DynSynQuadParserFactory {
-    _parser_config_map: TypeMap
+    parser_config_map: Arc<TypeMap>,
 }
 
 impl DynSynQuadParserFactory {
-    /// Instantiate a factory. It takes a `parser_config_map`, an optional [`TypeMap`], which can be populated with configuration structures corresponding to supported syntaxes.
+    /// Instantiate a factory. It takes a `parser_config_map`, an optional [`TypeMap`], which can be populated with configuration structures corresponding to supported syntaxes, with parse limits from [`limits`](super::limits) module, like [`GraphCardinalityLimit`], [`DocumentLimits`], and [`SessionBudget`], with an [`IriPolicy`], with a [`LiteralPolicy`], with a [`Skolemization`], with a [`ProgressConfig`], with a [`ParserConfig`], and with a [`DynIriResolver`].
     pub fn new(parser_config_map: Option<TypeMap>) -> Self {
-        let parser_config_map = if let Some(v) = parser_config_map {
-            v
-        } else {
-            TypeMap::new()
-        };
-        Self {
-            _parser_config_map: parser_config_map,
-        }
+        let parser_config_map = Arc::new(parser_config_map.unwrap_or_default());
+        Self { parser_config_map }
     }
 
     /// Try to create new [`DynSynQuadParser`] instance, for given `syntax_`, `base_iri`, and  `triple_source_adapted_graph_iri`.
@@ -145,7 +472,112 @@ impl DynSynQuadParserFactory {
     where
         T: TTerm + CopyTerm + Clone,
     {
-        DynSynQuadParser::try_new(syntax_, base_iri, triple_source_adapted_graph_iri)
+        Ok(self.configured(DynSynQuadParser::try_new(
+            syntax_,
+            base_iri,
+            triple_source_adapted_graph_iri,
+        )?))
+    }
+
+    /// Try to create new [`DynSynQuadParser`] instance in generalized mode, for given `syntax_`, `base_iri`, and `triple_source_adapted_graph_iri`. It is otherwise configured like [`try_new_parser`](Self::try_new_parser).
+    ///
+    /// Generalized parsers read documents of generalized rdf, in which any kind of term can be in any position of a statement, and terms can be sparql variables, like `?name`. It allows sparql related tooling to parse documents with variables. Generalized mode is supported for [`turtle`](crate::syntax::TURTLE), [`trig`](crate::syntax::TRIG), and [`n-triples`](crate::syntax::N_TRIPLES) syntaxes, which are parsed as subsets of generalized trig. Quoted triples are not supported in generalized mode.
+    ///
+    /// # Errors
+    /// returns [`UnKnownSyntaxError`](crate::syntax::UnKnownSyntaxError) if requested syntax is not known, or is not supported in generalized mode.
+    pub fn try_new_generalized_parser<T>(
+        &self,
+        syntax_: RdfSyntax,
+        base_iri: Option<String>,
+        triple_source_adapted_graph_iri: Option<T>,
+    ) -> Result<DynSynQuadParser<T>, UnKnownSyntaxError>
+    where
+        T: TTerm + CopyTerm + Clone,
+    {
+        Ok(self.configured(DynSynQuadParser::try_new_generalized(
+            syntax_,
+            base_iri,
+            triple_source_adapted_graph_iri,
+        )?))
+    }
+
+    /// Configures given parser with configuration from config map.
+    fn configured<T>(&self, mut parser: DynSynQuadParser<T>) -> DynSynQuadParser<T>
+    where
+        T: TTerm + CopyTerm + Clone,
+    {
+        parser.graph_cardinality_limit = self
+            .parser_config_map
+            .get::<GraphCardinalityLimit>()
+            .cloned();
+        parser.session_budget = self.parser_config_map.get::<SessionBudget>().cloned();
+        parser.iri_policy = self.parser_config_map.get::<IriPolicy>().cloned();
+        parser.literal_policy = self.parser_config_map.get::<LiteralPolicy>().cloned();
+        parser.document_limits = self.parser_config_map.get::<DocumentLimits>().cloned();
+        parser.skolemization = self.parser_config_map.get::<Skolemization>().cloned();
+        parser.progress = self.parser_config_map.get::<ProgressConfig>().cloned();
+        let config = self
+            .parser_config_map
+            .get::<ParserConfig>()
+            .copied()
+            .unwrap_or_default();
+        parser.lenient = config.lenient;
+        parser.reify_quoted_triples = config.reify_quoted_triples;
+        parser.iri_validation = config.iri_validation;
+        parser.preserve_bnode_labels = config.preserve_bnode_labels;
+        parser.with_iri_resolver(self.parser_config_map.get::<DynIriResolver>().cloned())
+    }
+
+    /// Try to create new [`DynSynQuadParser`] instance, for syntax corresponding to given `media_type`. Media type parameters, like charset are ignored. Use [`MediaTypeResolution`](crate::correspondence::MediaTypeResolution) to get them. It saves resolving syntax of http messages separately.
+    ///
+    /// # Errors
+    /// returns [`ParserForMediaTypeError`] if media type doesn't correspond to any rdf syntax, or if corresponding syntax is not supported.
+    pub fn try_new_parser_for_media_type<T>(
+        &self,
+        media_type: &Mime,
+        base_iri: Option<String>,
+        triple_source_adapted_graph_iri: Option<T>,
+    ) -> Result<DynSynQuadParser<T>, ParserForMediaTypeError>
+    where
+        T: TTerm + CopyTerm + Clone,
+    {
+        let syntax_ = Correspondent::<RdfSyntax>::try_from(media_type)?.value;
+        Ok(self.try_new_parser(syntax_, base_iri, triple_source_adapted_graph_iri)?)
+    }
+
+    /// Try to create new [`DynSynQuadParser`] instance, for syntax corresponding to file extension of given `path`. File extension is matched case-insensitively, and compression suffix, like `.gz` of `data.ttl.gz`, is skipped. It saves resolving syntax of files on disk separately.
+    ///
+    /// # Errors
+    /// returns [`ForPathError`] if path has no file extension, or if it doesn't correspond to any rdf syntax, or if corresponding syntax is not supported.
+    pub fn try_new_parser_for_path<T>(
+        &self,
+        path: &Path,
+        base_iri: Option<String>,
+        triple_source_adapted_graph_iri: Option<T>,
+    ) -> Result<DynSynQuadParser<T>, ForPathError>
+    where
+        T: TTerm + CopyTerm + Clone,
+    {
+        let syntax_ = syntax_for_path(path)?;
+        Ok(self.try_new_parser(syntax_, base_iri, triple_source_adapted_graph_iri)?)
+    }
+
+    /// Try to create new [`DynSynQuadParser`] instance, for syntax resolved from given `hint`. If hint doesn't resolve, syntax is sniffed from `content_head`, the start of the document, like from [`BufRead::fill_buf`], if given.
+    ///
+    /// # Errors
+    /// returns [`ParserForHintError`] if syntax can't be resolved, or sniffed, or if resolved syntax is not supported.
+    pub fn try_new_parser_for_hint<T>(
+        &self,
+        hint: &SyntaxHint,
+        content_head: Option<&[u8]>,
+        base_iri: Option<String>,
+        triple_source_adapted_graph_iri: Option<T>,
+    ) -> Result<DynSynQuadParser<T>, ParserForHintError>
+    where
+        T: TTerm + CopyTerm + Clone,
+    {
+        let syntax_ = hint.resolve_or_sniff(content_head)?;
+        Ok(self.try_new_parser(syntax_, base_iri, triple_source_adapted_graph_iri)?)
     }
 }
 
@@ -159,40 +591,57 @@ impl Default for DynSynQuadParserFactory {
 
 #[cfg(test)]
 mod tests {
+    use std::{collections::HashSet, convert::Infallible, sync::Arc, time::Duration};
+
     use claim::{assert_err, assert_ok};
     use once_cell::sync::Lazy;
     use sophia_api::{
-        dataset::{isomorphic_datasets, Dataset},
+        dataset::{isomorphic_datasets, Dataset, MutableDataset},
         graph::Graph,
         parser::{IntoParsable, QuadParser, TripleParser},
         quad::{stream::QuadSource, Quad},
-        term::{term_eq, CopyTerm, TTerm},
-        triple::stream::TripleSource,
+        term::{term_eq, CopyTerm, TTerm, TermKind},
+        triple::stream::{StreamError, TripleSource},
     };
     use sophia_inmem::{dataset::FastDataset, graph::FastGraph};
-    use sophia_term::{iri::Iri, BoxTerm};
+    use sophia_term::{iri::Iri, BoxTerm, RcTerm};
     use sophia_turtle::parser::{
         nq::NQuadsParser, nt::NTriplesParser, trig::TriGParser, turtle::TurtleParser,
     };
+    #[cfg(feature = "rdf-xml")]
     use sophia_xml::parser::RdfXmlParser;
     use test_case::test_case;
 
+    use type_map::concurrent::TypeMap;
+
     use crate::{
+        error_code::{self, ErrorCode},
+        parser::{
+            limits::{BudgetResource, GraphCardinalityLimit, LimitAction, SessionBudget},
+            provenance::StatementProvenance,
+            recovery::ParserConfig,
+            scoped::DEFAULT_BATCH_SIZE,
+            skolem::Skolemization,
+            triples::DynSynTripleParserFactory,
+        },
         syntax::{self, RdfSyntax},
         tests::TRACING,
     };
 
     use super::DynSynQuadParserFactory;
-    use crate::parser::test_data::*;
+    use crate::fixtures::*;
+    use crate::iri_resolver::{DynIriResolver, RewriteMap};
 
     static DYNSYN_QUAD_PARSER_FACTORY: Lazy<DynSynQuadParserFactory> =
-        Lazy::new(|| DynSynQuadParserFactory::default());
+        Lazy::new(DynSynQuadParserFactory::default);
 
     #[test_case(syntax::JSON_LD)]
-    #[test_case(syntax::HTML_RDFA)]
-    #[test_case(syntax::N3)]
+    #[cfg_attr(not(feature = "hdt"), test_case(syntax::HDT))]
+    #[cfg_attr(not(feature = "rdfa"), test_case(syntax::HTML_RDFA))]
+    #[cfg_attr(not(feature = "n3"), test_case(syntax::N3))]
     #[test_case(syntax::OWL2_XML)]
-    #[test_case(syntax::XHTML_RDFA)]
+    #[cfg_attr(not(feature = "trix"), test_case(syntax::TRIX))]
+    #[cfg_attr(not(feature = "rdfa"), test_case(syntax::XHTML_RDFA))]
     pub fn creating_parser_for_un_supported_syntax_will_error(syntax_: RdfSyntax) {
         Lazy::force(&TRACING);
         assert_err!(&DYNSYN_QUAD_PARSER_FACTORY.try_new_parser::<BoxTerm>(syntax_, None, None));
@@ -200,14 +649,475 @@ mod tests {
 
     #[test_case(syntax::N_QUADS)]
     #[test_case(syntax::N_TRIPLES)]
-    #[test_case(syntax::RDF_XML)]
+    #[cfg_attr(feature = "rdf-xml", test_case(syntax::RDF_XML))]
     #[test_case(syntax::TRIG)]
     #[test_case(syntax::TURTLE)]
+    #[cfg_attr(feature = "n3", test_case(syntax::N3))]
+    #[cfg_attr(feature = "rdfa", test_case(syntax::HTML_RDFA))]
+    #[cfg_attr(feature = "rdfa", test_case(syntax::XHTML_RDFA))]
+    #[cfg_attr(feature = "trix", test_case(syntax::TRIX))]
+    #[cfg_attr(feature = "hdt", test_case(syntax::HDT))]
     pub fn creating_parser_for_supported_syntax_will_succeed(syntax_: RdfSyntax) {
         Lazy::force(&TRACING);
         assert_ok!(&DYNSYN_QUAD_PARSER_FACTORY.try_new_parser::<BoxTerm>(syntax_, None, None));
     }
 
+    #[test_case("application/trig; charset=utf-8", Ok(syntax::TRIG))]
+    #[test_case("text/turtle", Ok(syntax::TURTLE))]
+    #[test_case("image/png", Err(error_code::DYNSYN_NON_RDF_MEDIA_TYPE))]
+    #[test_case("application/ld+json", Err(error_code::DYNSYN_UNKNOWN_SYNTAX))]
+    pub fn creating_parser_for_media_type_works_correctly(
+        media_type: &str,
+        expected: Result<RdfSyntax, &str>,
+    ) {
+        Lazy::force(&TRACING);
+        let media_type: mime::Mime = media_type.parse().unwrap();
+        let actual = DYNSYN_QUAD_PARSER_FACTORY
+            .try_new_parser_for_media_type::<BoxTerm>(&media_type, None, None)
+            .map(|p| p.syntax())
+            .map_err(|e| e.code());
+        assert_eq!(actual, expected);
+    }
+
+    #[test_case(syntax::N_QUADS)]
+    #[test_case(syntax::N_TRIPLES)]
+    #[cfg_attr(feature = "rdf-xml", test_case(syntax::RDF_XML))]
+    #[test_case(syntax::TRIG)]
+    #[test_case(syntax::TURTLE)]
+    pub fn parses_empty_docs_into_empty_dataset(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let parser = DYNSYN_QUAD_PARSER_FACTORY
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap();
+        for doc in EMPTY_DOCS {
+            let d: FastDataset = parser.parse_str(doc).collect_quads().unwrap();
+            assert_eq!(d.quads().count(), 0);
+        }
+    }
+
+    #[test_case(syntax::TRIG)]
+    #[test_case(syntax::TURTLE)]
+    #[cfg_attr(feature = "rdf-xml", test_case(syntax::RDF_XML))]
+    pub fn parse_with_base_overrides_constructed_base(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let doc = if syntax_ == syntax::RDF_XML {
+            r##"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"><rdf:Description rdf:about="#me"><rdf:value rdf:resource="#o"/></rdf:Description></rdf:RDF>"##
+        } else {
+            "<#me> <http://www.w3.org/1999/02/22-rdf-syntax-ns#value> <#o> ."
+        };
+        let parser = DYNSYN_QUAD_PARSER_FACTORY
+            .try_new_parser::<BoxTerm>(syntax_, Some(BASE_IRI1.into()), None)
+            .unwrap();
+        for base in ["http://a.example/doc", "http://b.example/doc"] {
+            let d: FastDataset = parser
+                .parse_with_base(doc.as_bytes(), Some(base.into()))
+                .collect_quads()
+                .unwrap();
+            let q = d.quads().next().unwrap().unwrap();
+            assert_eq!(q.s().value().to_string(), format!("{}#me", base));
+        }
+        // Constructed base is still used by `parse`.
+        let d: FastDataset = parser.parse_str(doc).collect_quads().unwrap();
+        let q = d.quads().next().unwrap().unwrap();
+        assert_eq!(q.s().value().to_string(), format!("{}#me", BASE_IRI1));
+    }
+
+    #[test]
+    pub fn retargeted_parser_keeps_configuration() {
+        Lazy::force(&TRACING);
+        let g1 = BoxTerm::new_iri(G1_IRI).unwrap();
+        let mut parser = DYNSYN_QUAD_PARSER_FACTORY
+            .try_new_parser(syntax::N_QUADS, Some(BASE_IRI1.into()), Some(g1.clone()))
+            .unwrap();
+
+        assert_ok!(parser.retarget(syntax::TURTLE));
+        assert_eq!(parser.syntax(), syntax::TURTLE);
+        let d: FastDataset = parser
+            .parse_str("<#me> <http://www.w3.org/1999/02/22-rdf-syntax-ns#value> <#o> .")
+            .collect_quads()
+            .unwrap();
+        let q = d.quads().next().unwrap().unwrap();
+        assert_eq!(q.s().value().to_string(), format!("{}#me", BASE_IRI1));
+        assert!(term_eq(q.g().unwrap(), &g1));
+
+        assert_err!(parser.retarget(syntax::JSON_LD));
+        assert_eq!(parser.syntax(), syntax::TURTLE);
+    }
+
+    #[test_case(syntax::TRIG, "<#g> { ?s ?p \"o\" . \"l\" _:p ?o . }")]
+    #[test_case(syntax::TURTLE, "?s ?p \"o\" . \"l\" _:p ?o .")]
+    pub fn generalized_parsers_read_variables_and_generalized_terms(
+        syntax_: RdfSyntax,
+        doc: &str,
+    ) {
+        Lazy::force(&TRACING);
+        let parser = DYNSYN_QUAD_PARSER_FACTORY
+            .try_new_generalized_parser::<BoxTerm>(syntax_, Some(BASE_IRI1.into()), None)
+            .unwrap();
+        assert!(parser.is_generalized());
+        let mut kinds = Vec::new();
+        parser
+            .parse_str(doc)
+            .for_each_quad(|q| kinds.push([q.s().kind(), q.p().kind(), q.o().kind()]))
+            .unwrap();
+        assert_eq!(
+            kinds,
+            [
+                [TermKind::Variable, TermKind::Variable, TermKind::Literal],
+                [TermKind::Literal, TermKind::BlankNode, TermKind::Variable],
+            ]
+        );
+    }
+
+    #[test_case(syntax::N_QUADS)]
+    #[test_case(syntax::JSON_LD)]
+    pub fn generalized_parsers_are_not_supported_for_other_syntaxes(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        assert_err!(DYNSYN_QUAD_PARSER_FACTORY
+            .try_new_generalized_parser::<BoxTerm>(syntax_, None, None));
+    }
+
+    #[test]
+    pub fn retargeted_generalized_parser_stays_generalized() {
+        Lazy::force(&TRACING);
+        let mut parser = DYNSYN_QUAD_PARSER_FACTORY
+            .try_new_generalized_parser::<BoxTerm>(syntax::TRIG, None, None)
+            .unwrap();
+        assert_ok!(parser.retarget(syntax::N_TRIPLES));
+        assert!(parser.is_generalized());
+        assert_eq!(parser.syntax(), syntax::N_TRIPLES);
+        assert_err!(parser.retarget(syntax::N_QUADS));
+        assert_eq!(parser.syntax(), syntax::N_TRIPLES);
+    }
+
+    #[test_case("<http://ex.org/a b> ?p ?o .")]
+    #[test_case("<< <http://ex.org/s> <http://ex.org/p> <http://ex.org/o> >> ?p ?o .")]
+    pub fn generalized_parsers_reject_invalid_iris(doc: &str) {
+        Lazy::force(&TRACING);
+        let parser = DYNSYN_QUAD_PARSER_FACTORY
+            .try_new_generalized_parser::<BoxTerm>(syntax::TURTLE, None, None)
+            .unwrap();
+        let r = parser.parse_str(doc).for_each_quad(|_| {});
+        assert_err!(&r);
+        assert!(r.unwrap_err().to_string().contains("invalid iri"));
+    }
+
+    #[test]
+    pub fn reports_provenance_of_quads() {
+        Lazy::force(&TRACING);
+        let g1 = BoxTerm::new_iri(G1_IRI).unwrap();
+        let parser = DYNSYN_QUAD_PARSER_FACTORY
+            .try_new_parser(syntax::N_TRIPLES, None, Some(g1))
+            .unwrap();
+        let mut source = parser.parse_str(GRAPH_STR_NTRIPLES);
+        assert!(source.is_adapted());
+        source.for_each_quad(|_| {}).unwrap();
+        let summary = source.provenance_summary();
+        assert!(summary.adapted > 0);
+        assert_eq!((summary.native, summary.dropped), (0, 0));
+
+        let parser = DYNSYN_QUAD_PARSER_FACTORY
+            .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+            .unwrap();
+        let mut source = parser.parse_str(DATASET_STR_NQUADS);
+        assert_eq!(source.provenance(), StatementProvenance::Native);
+        source.for_each_quad(|_| {}).unwrap();
+        let summary = source.provenance_summary();
+        assert!(summary.native > 0);
+        assert_eq!((summary.adapted, summary.dropped), (0, 0));
+    }
+
+    #[test_case(LimitAction::Error, 2, true)]
+    #[test_case(LimitAction::Error, 3, false)]
+    #[test_case(LimitAction::Warn, 2, false)]
+    pub fn guards_graph_name_cardinality(on_exceed: LimitAction, max: usize, must_fail: bool) {
+        Lazy::force(&TRACING);
+        let doc = r#"
+            <tag:s> <tag:p> <tag:o> .
+            <tag:s> <tag:p> <tag:o> <tag:g1> .
+            <tag:s> <tag:p> <tag:o> <tag:g2> .
+            <tag:s> <tag:p> <tag:o2> <tag:g1> .
+            <tag:s> <tag:p> <tag:o> _:g3 .
+        "#;
+        let mut config_map = TypeMap::new();
+        config_map.insert(GraphCardinalityLimit::new(max, on_exceed));
+        let parser = DynSynQuadParserFactory::new(Some(config_map))
+            .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+            .unwrap();
+        let mut d = FastDataset::new();
+        let r = parser.parse_str(doc).add_to_dataset(&mut d);
+        match r {
+            Ok(c) => {
+                assert!(!must_fail);
+                assert_eq!(c, 5);
+            }
+            Err(StreamError::SourceError(e)) => {
+                assert!(must_fail);
+                assert_eq!(e.code(), error_code::DYNSYN_LIMIT_EXCEEDED);
+            }
+            Err(e) => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test_case(SessionBudget::new(Some(3), None, None), Some(BudgetResource::Statements(3)))]
+    #[test_case(SessionBudget::new(None, Some(60), None), Some(BudgetResource::Bytes(60)))]
+    #[test_case(
+        SessionBudget::new(None, None, Some(Duration::ZERO)),
+        Some(BudgetResource::WallTime(Duration::ZERO))
+    )]
+    #[test_case(SessionBudget::new(Some(4), Some(1000), Some(Duration::from_secs(60))), None)]
+    pub fn shares_session_budget_across_parsers(
+        budget: SessionBudget,
+        exhausted: Option<BudgetResource>,
+    ) {
+        Lazy::force(&TRACING);
+        let doc = "<tag:s> <tag:p> <tag:o1> .\n<tag:s> <tag:p> <tag:o2> .\n";
+        std::thread::sleep(Duration::from_millis(1));
+        let mut config_map = TypeMap::new();
+        config_map.insert(budget.clone());
+        let quad_parser = DynSynQuadParserFactory::new(Some(config_map))
+            .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+            .unwrap();
+        let mut config_map = TypeMap::new();
+        config_map.insert(budget.clone());
+        let triple_parser = DynSynTripleParserFactory::new(Some(config_map))
+            .try_new_parser::<BoxTerm>(syntax::N_TRIPLES, None, None)
+            .unwrap();
+
+        let mut count = 0;
+        let r1 = quad_parser.parse_str(doc).for_each_quad(|_| count += 1);
+        let r2 = r1.and_then(|_| {
+            triple_parser
+                .parse_str(doc)
+                .for_each_triple(|_| count += 1)
+        });
+        assert_eq!(budget.exhausted(), exhausted);
+        match r2 {
+            Ok(_) => {
+                assert!(exhausted.is_none());
+                assert_eq!(count, 4);
+                assert_eq!(budget.statements_used(), 4);
+                assert_eq!(budget.bytes_used(), 2 * doc.len() as u64);
+            }
+            Err(e) => {
+                assert!(exhausted.is_some());
+                assert!(count <= 3);
+                assert_eq!(e.code(), error_code::DYNSYN_LIMIT_EXCEEDED);
+            }
+        }
+    }
+
+    #[test]
+    pub fn keeps_going_past_malformed_statements() {
+        Lazy::force(&TRACING);
+        let doc = [
+            "<tag:s> <tag:p> <tag:o1> <tag:g1> .",
+            "<tag:s> <tag:p> <tag:o2> <tag:g2> <tag:x> .",
+            "<tag:s> <tag:p> <tag:o3> <tag:g3> .",
+        ]
+        .join("\n");
+        let parser = DYNSYN_QUAD_PARSER_FACTORY
+            .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+            .unwrap();
+        let mut d = FastDataset::new();
+        let summary = parser
+            .parse_str(&doc)
+            .try_for_each_quad_keep_going(|q| d.insert(q.s(), q.p(), q.o(), q.g()).map(|_| ()))
+            .unwrap();
+        assert_eq!(d.quads().count(), 2);
+        assert_eq!(summary.skipped.len(), 1);
+        assert_eq!(summary.skipped[0].line_number, Some(2));
+        assert_eq!(summary.skipped[0].error.code(), error_code::DYNSYN_PARSE_SYNTAX);
+
+        // Limit errors are not confined to a statement.
+        let mut config_map = TypeMap::new();
+        config_map.insert(GraphCardinalityLimit::new(1, LimitAction::Error));
+        let parser = DynSynQuadParserFactory::new(Some(config_map))
+            .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+            .unwrap();
+        let e = parser
+            .parse_str(&doc)
+            .try_for_each_quad_keep_going(|_| Ok::<_, Infallible>(()))
+            .unwrap_err();
+        assert!(matches!(
+            e,
+            StreamError::SourceError(e) if e.code() == error_code::DYNSYN_LIMIT_EXCEEDED
+        ));
+    }
+
+    #[test]
+    pub fn iterates_over_quads() {
+        Lazy::force(&TRACING);
+        let parser = DYNSYN_QUAD_PARSER_FACTORY
+            .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+            .unwrap();
+        let doc = [
+            "<tag:s> <tag:p> <tag:o1> <tag:g1> .",
+            "<tag:s> <tag:p> <tag:o2> .",
+        ]
+        .join("\n");
+        let quads = parser
+            .parse_str(&doc)
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(quads.len(), 2);
+        assert_eq!(quads[0].g().unwrap().value().to_string(), "tag:g1");
+        assert!(quads[1].g().is_none());
+
+        // Quads before an error are yielded first.
+        let doc = doc + "\n<tag:s> <tag:p> .\n<tag:s> <tag:p> <tag:o3> .";
+        let mut iter = parser.parse_str(&doc).into_iter();
+        assert_eq!(iter.by_ref().take_while(Result::is_ok).count(), 2);
+        assert!(iter.source().is_none());
+        assert!(iter.next().is_none());
+
+        let mut iter = parser.parse_str(&doc).into_iter();
+        let e = iter.nth(2).unwrap().unwrap_err();
+        assert_eq!(e.code(), error_code::DYNSYN_PARSE_SYNTAX);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    pub fn resolves_iris_with_configured_resolver() {
+        Lazy::force(&TRACING);
+        let resolver = RewriteMap::new()
+            .with_rule("http://example.org/", "http://mirror.local/")
+            .with_virtual_base("http://virtual.local/doc");
+        let mut config_map = TypeMap::new();
+        config_map.insert(DynIriResolver(Arc::new(resolver)));
+        let parser = DynSynQuadParserFactory::new(Some(config_map))
+            .try_new_parser::<BoxTerm>(syntax::TRIG, Some(BASE_IRI1.into()), None)
+            .unwrap();
+        let doc = r#"<http://example.org/g> { <#s> <http://example.org/p> "http://example.org/o" . }"#;
+        let d: FastDataset = parser.parse_str(doc).collect_quads()
+            .unwrap();
+        let q = d.quads().next().unwrap().unwrap();
+        assert_eq!(q.s().value().to_string(), "http://virtual.local/doc#s");
+        assert_eq!(q.p().value().to_string(), "http://mirror.local/p");
+        assert_eq!(q.o().value().to_string(), "http://example.org/o");
+        assert_eq!(q.g().unwrap().value().to_string(), "http://mirror.local/g");
+    }
+
+    #[test_case(syntax::TRIG, DATASET_STR_TRIG, None)]
+    #[test_case(syntax::TURTLE, GRAPH_STR_TURTLE, Some(G1_IRI))]
+    pub fn scoped_quads_agree_with_copied_quads(
+        syntax_: RdfSyntax,
+        doc: &str,
+        triple_source_graph_iri: Option<&str>,
+    ) {
+        Lazy::force(&TRACING);
+        let parser = DYNSYN_QUAD_PARSER_FACTORY
+            .try_new_parser(
+                syntax_,
+                Some(BASE_IRI1.into()),
+                triple_source_graph_iri.map(|v| BoxTerm::new_iri(v).unwrap()),
+            )
+            .unwrap();
+        let d1: FastDataset = parser.parse_str(doc).collect_quads().unwrap();
+
+        let mut d2 = FastDataset::new();
+        parser
+            .parse_str(doc)
+            .for_each_quad_scoped(|([s, p, o], g)| {
+                d2.insert(s, p, o, g).unwrap();
+            })
+            .unwrap();
+
+        assert!(isomorphic_datasets(&d1, &d2).unwrap());
+    }
+
+    #[test_case(1)]
+    #[test_case(2)]
+    #[test_case(DEFAULT_BATCH_SIZE)]
+    pub fn pulls_quads_in_batches_of_configured_size(batch_size: usize) {
+        Lazy::force(&TRACING);
+        let parser = DYNSYN_QUAD_PARSER_FACTORY
+            .try_new_parser(
+                syntax::N_TRIPLES,
+                None,
+                Some(BoxTerm::new_iri(G1_IRI).unwrap()),
+            )
+            .unwrap();
+        let doc: String = (0..5)
+            .map(|i| format!("<http://ex.org/s{}> <http://ex.org/p> <http://ex.org/o> .\n", i))
+            .collect();
+        let mut source = parser.parse_str(&doc);
+        source.set_batch_size(batch_size);
+        assert_eq!(source.batch_size(), batch_size);
+
+        let mut batches = Vec::new();
+        loop {
+            let mut count = 0;
+            let more = source
+                .try_for_some_quad(&mut |q| {
+                    assert_eq!(q.g().unwrap().value().to_string(), G1_IRI);
+                    count += 1;
+                    Ok::<_, Infallible>(())
+                })
+                .unwrap();
+            batches.push(count);
+            if !more {
+                break;
+            }
+        }
+        assert_eq!(batches.iter().sum::<usize>(), 5);
+        assert!(batches.iter().all(|count| *count <= batch_size));
+        // A pull from underlying parser may yield no statement, so batches may be short.
+        assert!(batches.len() > 5 / batch_size);
+    }
+
+    #[test]
+    pub fn shares_adapted_graph_term_across_quads() {
+        Lazy::force(&TRACING);
+        let doc: String = (0..5)
+            .map(|i| format!("<http://ex.org/s{}> <http://ex.org/p> <http://ex.org/o> .\n", i))
+            .collect();
+        let data_ptr = |g: &dyn TTerm| g.value_raw().0.as_ptr() as usize;
+
+        // By value, graph names of quads are clones of configured `RcTerm`, sharing it's data.
+        let g1 = RcTerm::new_iri(G1_IRI).unwrap();
+        let mut source = DYNSYN_QUAD_PARSER_FACTORY
+            .try_new_parser(syntax::N_TRIPLES, None, Some(g1.clone()))
+            .unwrap()
+            .parse_str(&doc);
+        source.set_batch_size(2);
+        let mut ptrs = HashSet::new();
+        source
+            .try_for_each_quad(|q| {
+                ptrs.insert(data_ptr(q.g().unwrap()));
+                Ok::<_, Infallible>(())
+            })
+            .unwrap();
+        assert_eq!(ptrs, HashSet::from([data_ptr(&g1)]));
+
+        // Iterated, terms of by-value quads are moved into yielded quads, instead of being copied.
+        let ptrs: HashSet<_> = DYNSYN_QUAD_PARSER_FACTORY
+            .try_new_parser(syntax::N_TRIPLES, None, Some(g1.clone()))
+            .unwrap()
+            .parse_str(&doc)
+            .into_iter()
+            .map(|q| data_ptr(q.unwrap().g.as_ref().unwrap()))
+            .collect();
+        assert_eq!(ptrs, HashSet::from([data_ptr(&g1)]));
+
+        // Scoped, graph names of quads are references to configured term, for any term type.
+        let mut ptrs = HashSet::new();
+        DYNSYN_QUAD_PARSER_FACTORY
+            .try_new_parser(
+                syntax::N_TRIPLES,
+                None,
+                Some(BoxTerm::new_iri(G1_IRI).unwrap()),
+            )
+            .unwrap()
+            .parse_str(&doc)
+            .for_each_quad_scoped(|(_, g)| {
+                ptrs.insert(data_ptr(g.unwrap()));
+            })
+            .unwrap();
+        assert_eq!(ptrs.len(), 1);
+    }
+
     fn check_dataset_parse_isomorphism<'b, B, P1, P2>(p1: &P1, p2: &P2, qs: &'b str)
     where
         P1: QuadParser<B>,
@@ -284,7 +1194,7 @@ mod tests {
     pub fn correctly_parses_turtle(triple_source_graph_iri: Option<&str>) {
         Lazy::force(&TRACING);
         let triple_source_graph_iri = triple_source_graph_iri
-            .and_then(|v| Some(BoxTerm::Iri(Iri::new(Box::from(v)).unwrap())));
+            .map(|v| BoxTerm::Iri(Iri::new(Box::from(v)).unwrap()));
         check_graph_parse_isomorphism(
             &TurtleParser {
                 base: Some(BASE_IRI1.into()),
@@ -307,7 +1217,7 @@ mod tests {
     pub fn correctly_parses_ntriples(triple_source_graph_iri: Option<&str>) {
         Lazy::force(&TRACING);
         let triple_source_graph_iri = triple_source_graph_iri
-            .and_then(|v| Some(BoxTerm::Iri(Iri::new(Box::from(v)).unwrap())));
+            .map(|v| BoxTerm::Iri(Iri::new(Box::from(v)).unwrap()));
         check_graph_parse_isomorphism(
             &NTriplesParser {},
             &DYNSYN_QUAD_PARSER_FACTORY
@@ -322,13 +1232,14 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "rdf-xml")]
     #[test_case(Some(G1_IRI))]
     #[test_case(Some(G2_IRI))]
     #[test_case(None)]
     pub fn correctly_parses_rdf_xml(triple_source_graph_iri: Option<&str>) {
         Lazy::force(&TRACING);
         let triple_source_graph_iri = triple_source_graph_iri
-            .and_then(|v| Some(BoxTerm::Iri(Iri::new(Box::from(v)).unwrap())));
+            .map(|v| BoxTerm::Iri(Iri::new(Box::from(v)).unwrap()));
         check_graph_parse_isomorphism(
             &RdfXmlParser {
                 base: Some(BASE_IRI1.into()),
@@ -344,4 +1255,84 @@ mod tests {
             triple_source_graph_iri.as_ref(),
         );
     }
+
+    #[test]
+    pub fn cloned_factories_share_config_across_threads() {
+        Lazy::force(&TRACING);
+        let mut config_map = TypeMap::new();
+        config_map.insert(ParserConfig::reify_quoted_triples());
+        let factory = DynSynQuadParserFactory::new(Some(config_map));
+        let cloned = factory.clone();
+        assert!(Arc::ptr_eq(
+            &factory.parser_config_map,
+            &cloned.parser_config_map
+        ));
+
+        let handle = std::thread::spawn(move || {
+            let d: FastDataset = cloned
+                .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+                .unwrap()
+                .parse_str("<< <http://ex.org/s> <http://ex.org/p> <http://ex.org/o> >> <http://ex.org/q> \"v\" .")
+                .collect_quads()
+                .unwrap();
+            d.quads().count()
+        });
+        assert_eq!(handle.join().unwrap(), 5);
+    }
+
+    #[test_case(syntax::TRIG)]
+    #[test_case(syntax::N_QUADS)]
+    pub fn skolemizes_blank_nodes_when_configured(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let doc = if syntax_ == syntax::TRIG {
+            "_:g { _:a <http://ex.org/p> _:b . _:b <http://ex.org/p> _:a . }"
+        } else {
+            "_:a <http://ex.org/p> _:b _:g .\n_:b <http://ex.org/p> _:a _:g .\n"
+        };
+        let skolemization = Skolemization::try_new("https://data.example").unwrap();
+        let mut config_map = TypeMap::new();
+        config_map.insert(skolemization.clone());
+        let parser = DynSynQuadParserFactory::new(Some(config_map))
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap();
+
+        let d: FastDataset = parser.parse_str(doc).collect_quads().unwrap();
+        let mut terms = std::collections::HashSet::new();
+        for q in d.quads() {
+            let q = q.unwrap();
+            for t in [q.s(), q.o(), q.g().unwrap()] {
+                assert_eq!(t.kind(), TermKind::Iri);
+                assert!(skolemization.is_skolem_iri(&t.value()));
+                terms.insert(BoxTerm::copy(t));
+            }
+        }
+        assert_eq!(d.quads().count(), 2);
+        assert_eq!(terms.len(), 3);
+    }
+
+    #[test_case(syntax::N_QUADS, "_:alice <http://ex.org/knows> _:bob _:g .\n")]
+    #[test_case(syntax::TRIG, "_:g { _:alice <http://ex.org/knows> _:bob . }")]
+    #[cfg_attr(
+        feature = "rdfa",
+        test_case(
+            syntax::HTML_RDFA,
+            r##"<div about="_:alice" rel="http://ex.org/knows" resource="_:bob"></div>"##
+        )
+    )]
+    pub fn preserves_bnode_labels_when_configured(syntax_: RdfSyntax, doc: &str) {
+        Lazy::force(&TRACING);
+        let mut config_map = TypeMap::new();
+        config_map.insert(Skolemization::try_new("https://data.example").unwrap());
+        config_map.insert(ParserConfig::preserve_bnode_labels());
+        let parser = DynSynQuadParserFactory::new(Some(config_map))
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap();
+        assert!(parser.preserves_bnode_labels());
+
+        let d: FastDataset = parser.parse_str(doc).collect_quads().unwrap();
+        let q = d.quads().next().unwrap().unwrap();
+        assert_eq!(q.s().kind(), TermKind::BlankNode);
+        assert_eq!(q.s().value().as_ref(), "alice");
+        assert_eq!(q.o().value().as_ref(), "bob");
+    }
 }
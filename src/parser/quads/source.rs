@@ -1,6 +1,12 @@
-use std::{error::Error, io::BufRead};
+use std::{
+    collections::VecDeque,
+    convert::Infallible,
+    error::Error,
+    io::BufRead,
+    sync::{Arc, Mutex},
+};
 
-use rio_api::parser::{QuadsParser, TriplesParser};
+use rio_api::parser::TriplesParser;
 use sophia_api::{
     quad::{
         self,
@@ -8,17 +14,35 @@ use sophia_api::{
         streaming_mode::{ByValue, StreamedQuad},
         Quad,
     },
-    term::{CopiableTerm, CopyTerm, TTerm},
+    term::{CopyTerm, TTerm},
     triple::{
-        stream::{StreamResult, TripleSource},
+        stream::{StreamError, StreamResult, TripleSource},
         Triple,
     },
 };
 use sophia_rio::parser::StrictRioSource;
 
-use crate::parser::{
-    _inner::source::InnerStatementSource,
-    errors::{adapt_stream_result, DynSynParseError},
+use crate::{
+    iri_resolver::DynIriResolver,
+    model::OwnedQuad,
+    parser::{
+        _inner::source::{InnerStatementSource, InputSniff},
+        errors::{
+            adapt_stream_result, diagnose_stream_result, with_syntax_stream_result,
+            DynSynParseError,
+        },
+        iri_policy::IriPolicyGuard,
+        limits::{BudgetedRead, DocumentLimitGuard, GraphCardinalityGuard, SessionBudget},
+        literal_policy::LiteralPolicyGuard,
+        prefixes::{self, DocumentPrefixes},
+        progress::ProgressTracker,
+        provenance::{ProvenanceSummary, StatementProvenance},
+        recovery::SkipSummary,
+        scoped::{scoped_term, ScopedQuad, StatementGates, DEFAULT_BATCH_SIZE},
+        skolem::Skolemizer,
+        stats::{StatementStats, StatsCollector},
+    },
+    syntax::RdfSyntax,
 };
 
 pub type TupleQuad<T> = ([T; 3], Option<T>);
@@ -28,38 +52,86 @@ pub type TupleQuad<T> = ([T; 3], Option<T>);
 /// If underlying statement source is a quad-source, then it will emit equivalent quads.
 ///
 /// If underlying statement source is a triple-source, then it will emit quads corresponding to each triple, with graph_name term set to configured `triple_source_graph_iri`  field value, and remaining terms  being equivalent to those of triple.
+///
+/// Whether emitted quads are adapted can be known through [`provenance`](Self::provenance), and a running count of emitted quads through [`provenance_summary`](Self::provenance_summary).
+///
+/// Statements are emitted in order documented in [`ordering`](crate::parser::ordering) module.
+///
+/// Besides push-style [`QuadSource`] api, quads can also be pulled through [`DynSynQuadIter`], that it converts into with [`IntoIterator`].
+///
+/// Graph name of adapted quads emitted by value is cloned from configured term, which doesn't allocate for terms with shared data, like `RcTerm`, and `ArcTerm`, but copies whole term for `BoxTerm`. Scoped apis, like [`for_each_quad_scoped`](Self::for_each_quad_scoped), lend configured term by reference, and never clone it, for any term type.
 pub struct DynSynQuadSource<T: CopyTerm + TTerm, R: BufRead> {
-    inner_source: InnerStatementSource<R>,
+    inner_source: InnerStatementSource<BudgetedRead<R>>,
     triple_source_graph_iri: Option<T>,
+    input_sniff: InputSniff,
+    syntax_: RdfSyntax,
+    provenance_summary: ProvenanceSummary,
+    graph_cardinality_guard: Option<GraphCardinalityGuard>,
+    iri_resolver: Option<DynIriResolver>,
+    session_budget: Option<SessionBudget>,
+    iri_policy_guard: Option<IriPolicyGuard>,
+    literal_policy_guard: Option<LiteralPolicyGuard>,
+    document_limit_guard: Option<DocumentLimitGuard>,
+    skolemizer: Option<Skolemizer>,
+    stats: Option<StatsCollector>,
+    progress_tracker: Option<ProgressTracker>,
+    prefixes: Arc<Mutex<DocumentPrefixes>>,
+    lenient: bool,
+    skip_summary: SkipSummary,
+    batch_size: usize,
 }
 
 impl<T: CopyTerm + TTerm + Clone, R: BufRead> DynSynQuadSource<T, R> {
-    /// Call `f` for at least one adapted-quad (if any) that is adapted from underlying rio quad source.
+    /// Call `f` for at least one scoped quad (if any) that is adapted from underlying rio quad source.
     ///
     /// Return false if no more quads can be adapted from underlying source.
     ///
     /// If underlying fallible quad-source returns a parse error, then that error will be wrapped in enum [`DynSynParseError`] as an appropriate variant.
-    fn try_for_some_quad_adapted_from_rio_quad_source<Parser, PErr, SinkErr, F>(
+    fn try_for_some_quad_adapted_from_rio_quad_source<QS, PErr, SinkErr, F>(
         // underlying quad source
-        qs: &mut StrictRioSource<Parser, PErr>,
+        qs: &mut QS,
         mut f: F,
+        summary: &mut ProvenanceSummary,
+        guard: &mut Option<GraphCardinalityGuard>,
+        resolver: Option<&DynIriResolver>,
+        skolemizer: Option<&Skolemizer>,
     ) -> StreamResult<bool, DynSynParseError, SinkErr>
     where
-        Parser: QuadsParser<Error = PErr>,
+        QS: QuadSource<Error = PErr>,
         PErr: Error + 'static + Into<DynSynParseError>,
         SinkErr: Error,
-        F: FnMut(StreamedQuad<ByValue<TupleQuad<T>>>) -> Result<(), SinkErr>,
+        F: FnMut(ScopedQuad<'_>) -> Result<(), SinkErr>,
     {
-        adapt_stream_result(qs.try_for_some_quad(&mut |q| {
-            let tq: TupleQuad<T> = (
-                [q.s().copied(), q.p().copied(), q.o().copied()],
-                q.g().map(|gv| gv.copied()),
-            );
-            f(StreamedQuad::by_value(tq))
-        }))
+        let r = adapt_stream_result(qs.try_for_some_quad(&mut |q| {
+            if let Some(guard) = guard.as_mut() {
+                if !guard.admit(q.g()) {
+                    return Ok(());
+                }
+            }
+            let (mut bs, mut bp, mut bo, mut bg) = (None, None, None, None);
+            let g = q
+                .g()
+                .map(|gv| scoped_term(skolemizer, resolver, gv, &mut bg));
+            f((
+                [
+                    scoped_term(skolemizer, resolver, q.s(), &mut bs),
+                    scoped_term(skolemizer, resolver, q.p(), &mut bp),
+                    scoped_term(skolemizer, resolver, q.o(), &mut bo),
+                ],
+                g,
+            ))?;
+            summary.native += 1;
+            Ok(())
+        }));
+        match guard {
+            Some(guard) if guard.must_fail() => Err(StreamError::SourceError(
+                DynSynParseError::too_many_graphs(guard.limit().max_named_graphs),
+            )),
+            _ => r,
+        }
     }
 
-    /// Call `f` for at least one adapted-quad (if any) that is adapted from underlying rio triple source.
+    /// Call `f` for at least one scoped quad (if any) that is adapted from underlying rio triple source.
     ///
     /// Return false if no more quads can be adapted from underlying source.
     ///
@@ -70,32 +142,489 @@ impl<T: CopyTerm + TTerm + Clone, R: BufRead> DynSynQuadSource<T, R> {
     fn try_for_some_quad_adapted_from_rio_triple_source<Parser, PErr, SinkErr, F>(
         ts: &mut StrictRioSource<Parser, PErr>,
         mut f: F,
-        triple_source_graph_iri: &Option<T>,
+        triple_source_graph_iri: Option<&T>,
+        summary: &mut ProvenanceSummary,
+        resolver: Option<&DynIriResolver>,
+        skolemizer: Option<&Skolemizer>,
     ) -> StreamResult<bool, DynSynParseError, SinkErr>
     where
         Parser: TriplesParser<Error = PErr>,
         PErr: Error + 'static + Into<DynSynParseError>,
         SinkErr: Error,
-        F: FnMut(StreamedQuad<ByValue<TupleQuad<T>>>) -> Result<(), SinkErr>,
+        F: FnMut(ScopedQuad<'_>) -> Result<(), SinkErr>,
     {
         adapt_stream_result(ts.try_for_some_triple(&mut |t| {
-            let tq: TupleQuad<T> = (
-                [t.s().copied(), t.p().copied(), t.o().copied()],
-                triple_source_graph_iri.clone(),
-            );
-            f(StreamedQuad::by_value(tq))
+            let (mut bs, mut bp, mut bo) = (None, None, None);
+            f((
+                [
+                    scoped_term(skolemizer, resolver, t.s(), &mut bs),
+                    scoped_term(skolemizer, resolver, t.p(), &mut bp),
+                    scoped_term(skolemizer, resolver, t.o(), &mut bo),
+                ],
+                triple_source_graph_iri.map(TTerm::as_dyn),
+            ))?;
+            summary.adapted += 1;
+            Ok(())
         }))
     }
 
     pub(crate) fn new_for(
-        inner_source: InnerStatementSource<R>,
+        inner_source: InnerStatementSource<BudgetedRead<R>>,
         triple_source_graph_iri: Option<T>,
+        input_sniff: InputSniff,
+        syntax_: RdfSyntax,
     ) -> Self {
         Self {
             inner_source,
             triple_source_graph_iri,
+            input_sniff,
+            syntax_,
+            provenance_summary: ProvenanceSummary::default(),
+            graph_cardinality_guard: None,
+            iri_resolver: None,
+            session_budget: None,
+            iri_policy_guard: None,
+            literal_policy_guard: None,
+            document_limit_guard: None,
+            skolemizer: None,
+            stats: None,
+            progress_tracker: None,
+            prefixes: Arc::default(),
+            lenient: false,
+            skip_summary: SkipSummary::default(),
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    pub(crate) fn with_graph_cardinality_guard(
+        mut self,
+        guard: Option<GraphCardinalityGuard>,
+    ) -> Self {
+        self.graph_cardinality_guard = guard;
+        self
+    }
+
+    pub(crate) fn with_iri_resolver(mut self, iri_resolver: Option<DynIriResolver>) -> Self {
+        self.iri_resolver = iri_resolver;
+        self
+    }
+
+    pub(crate) fn with_session_budget(mut self, session_budget: Option<SessionBudget>) -> Self {
+        self.session_budget = session_budget;
+        self
+    }
+
+    pub(crate) fn with_iri_policy_guard(mut self, guard: Option<IriPolicyGuard>) -> Self {
+        self.iri_policy_guard = guard;
+        self
+    }
+
+    pub(crate) fn with_literal_policy_guard(mut self, guard: Option<LiteralPolicyGuard>) -> Self {
+        self.literal_policy_guard = guard;
+        self
+    }
+
+    pub(crate) fn with_document_limit_guard(mut self, guard: Option<DocumentLimitGuard>) -> Self {
+        self.document_limit_guard = guard;
+        self
+    }
+
+    pub(crate) fn with_skolemizer(mut self, skolemizer: Option<Skolemizer>) -> Self {
+        self.skolemizer = skolemizer;
+        self
+    }
+
+    pub(crate) fn with_progress_tracker(mut self, tracker: Option<ProgressTracker>) -> Self {
+        self.progress_tracker = tracker;
+        self
+    }
+
+    pub(crate) fn with_prefixes(mut self, prefixes: Arc<Mutex<DocumentPrefixes>>) -> Self {
+        self.prefixes = prefixes;
+        self
+    }
+
+    pub(crate) fn with_lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Returns provenance of quads emitted by this source. Quads are [`Adapted`](StatementProvenance::Adapted), if underlying statement source is a triple-source.
+    pub fn provenance(&self) -> StatementProvenance {
+        match &self.inner_source {
+            InnerStatementSource::FNQuads(_)
+            | InnerStatementSource::FTriG(_)
+            | InnerStatementSource::FGTriG(_) => StatementProvenance::Native,
+            #[cfg(feature = "trix")]
+            InnerStatementSource::FTrix(_) => StatementProvenance::Native,
+            _ => StatementProvenance::Adapted,
+        }
+    }
+
+    /// Returns true, if quads emitted by this source are synthesized from underlying triples.
+    pub fn is_adapted(&self) -> bool {
+        self.provenance() == StatementProvenance::Adapted
+    }
+
+    /// Returns summary of quads emitted by this source so far. After source is exhausted, it summarizes whole document.
+    pub fn provenance_summary(&self) -> ProvenanceSummary {
+        self.provenance_summary
+    }
+
+    /// Call `f` for some owned quads, like [`try_for_some_quad`](QuadSource::try_for_some_quad), but without marking raised errors with syntax of the document.
+    fn try_for_some_owned_quad_untagged<F, E>(
+        &mut self,
+        f: &mut F,
+    ) -> StreamResult<bool, DynSynParseError, E>
+    where
+        F: FnMut(TupleQuad<T>) -> Result<(), E>,
+        E: std::error::Error,
+    {
+        // Graph name of adapted quads is cloned from configured term, instead of being copied
+        // from scoped term. It shares data of configured term, for terms like `RcTerm`.
+        let adapted_graph = self
+            .is_adapted()
+            .then(|| self.triple_source_graph_iri.clone());
+        self.try_for_some_scoped_quad_untagged(&mut |([s, p, o], g): ScopedQuad<'_>| {
+            let g = match &adapted_graph {
+                Some(graph) => graph.clone(),
+                None => g.map(T::copy),
+            };
+            f(([T::copy(s), T::copy(p), T::copy(o)], g))
+        })
+    }
+
+    /// Call `f` for some owned quads, like [`try_for_some_quad`](QuadSource::try_for_some_quad), but passes quads by value, so that their terms can be moved out.
+    fn try_for_some_owned_quad<F, E>(
+        &mut self,
+        f: &mut F,
+    ) -> StreamResult<bool, DynSynParseError, E>
+    where
+        F: FnMut(TupleQuad<T>) -> Result<(), E>,
+        E: Error,
+    {
+        let syntax_ = self.syntax_;
+        match with_syntax_stream_result(self.try_for_some_owned_quad_untagged(f), syntax_) {
+            Err(StreamError::SourceError(e))
+                if self.lenient && self.supports_keep_going() && e.is_recoverable() =>
+            {
+                tracing::warn!("Skipping malformed statement: {}", e);
+                self.skip_summary.skipped.push(e.into());
+                Ok(true)
+            }
+            r => r,
+        }
+    }
+
+    /// Call `f` for some scoped quads, without marking raised errors with syntax of the document. Quads, that are not admitted by parse limits, and policies are skipped, before any of their terms is copied.
+    fn try_for_some_scoped_quad_untagged<F, E>(
+        &mut self,
+        f: &mut F,
+    ) -> StreamResult<bool, DynSynParseError, E>
+    where
+        F: FnMut(ScopedQuad<'_>) -> Result<(), E>,
+        E: std::error::Error,
+    {
+        if let Some(resource) = self
+            .session_budget
+            .as_ref()
+            .and_then(SessionBudget::exhausted)
+        {
+            return Err(StreamError::SourceError(
+                DynSynParseError::budget_exhausted(resource),
+            ));
+        }
+        let mut gates = StatementGates {
+            budget: self.session_budget.as_ref(),
+            iri_policy_guard: &mut self.iri_policy_guard,
+            literal_policy_guard: &mut self.literal_policy_guard,
+            document_limit_guard: &mut self.document_limit_guard,
+            stats: &mut self.stats,
+            progress_tracker: &mut self.progress_tracker,
+        };
+        let mut r = Ok(true);
+        // Pull a batch of statements per call, so that per call checks, and dispatch on
+        // underlying parser, are amortized over the batch.
+        for _ in 0..self.batch_size {
+            if gates.tripped() {
+                break;
+            }
+            let mut gated_f = |quad: ScopedQuad<'_>| {
+                if gates.admit_quad(quad.0, quad.1) {
+                    f(quad)
+                } else {
+                    Ok(())
+                }
+            };
+            r = match &mut self.inner_source {
+                InnerStatementSource::FNQuads(qs) => {
+                    Self::try_for_some_quad_adapted_from_rio_quad_source(
+                        qs,
+                        &mut gated_f,
+                        &mut self.provenance_summary,
+                        &mut self.graph_cardinality_guard,
+                        self.iri_resolver.as_ref(),
+                        self.skolemizer.as_ref(),
+                    )
+                }
+
+                InnerStatementSource::FTriG(qs) => {
+                    Self::try_for_some_quad_adapted_from_rio_quad_source(
+                        qs,
+                        &mut gated_f,
+                        &mut self.provenance_summary,
+                        &mut self.graph_cardinality_guard,
+                        self.iri_resolver.as_ref(),
+                        self.skolemizer.as_ref(),
+                    )
+                }
+
+                InnerStatementSource::FGTriG(qs) => {
+                    Self::try_for_some_quad_adapted_from_rio_quad_source(
+                        qs,
+                        &mut gated_f,
+                        &mut self.provenance_summary,
+                        &mut self.graph_cardinality_guard,
+                        self.iri_resolver.as_ref(),
+                        self.skolemizer.as_ref(),
+                    )
+                }
+
+                #[cfg(feature = "trix")]
+                InnerStatementSource::FTrix(qs) => {
+                    Self::try_for_some_quad_adapted_from_rio_quad_source(
+                        qs,
+                        &mut gated_f,
+                        &mut self.provenance_summary,
+                        &mut self.graph_cardinality_guard,
+                        self.iri_resolver.as_ref(),
+                        self.skolemizer.as_ref(),
+                    )
+                }
+
+                InnerStatementSource::FNTriples(ts) => {
+                    Self::try_for_some_quad_adapted_from_rio_triple_source(
+                        ts,
+                        &mut gated_f,
+                        self.triple_source_graph_iri.as_ref(),
+                        &mut self.provenance_summary,
+                        self.iri_resolver.as_ref(),
+                        self.skolemizer.as_ref(),
+                    )
+                }
+
+                InnerStatementSource::FTurtle(ts) => {
+                    Self::try_for_some_quad_adapted_from_rio_triple_source(
+                        ts,
+                        &mut gated_f,
+                        self.triple_source_graph_iri.as_ref(),
+                        &mut self.provenance_summary,
+                        self.iri_resolver.as_ref(),
+                        self.skolemizer.as_ref(),
+                    )
+                }
+
+                #[cfg(feature = "rdf-xml")]
+                InnerStatementSource::FRdfXml(ts) => {
+                    Self::try_for_some_quad_adapted_from_rio_triple_source(
+                        ts,
+                        &mut gated_f,
+                        self.triple_source_graph_iri.as_ref(),
+                        &mut self.provenance_summary,
+                        self.iri_resolver.as_ref(),
+                        self.skolemizer.as_ref(),
+                    )
+                }
+
+                #[cfg(feature = "rdfa")]
+                InnerStatementSource::FRdfa(ts) => {
+                    Self::try_for_some_quad_adapted_from_rio_triple_source(
+                        ts,
+                        &mut gated_f,
+                        self.triple_source_graph_iri.as_ref(),
+                        &mut self.provenance_summary,
+                        self.iri_resolver.as_ref(),
+                        self.skolemizer.as_ref(),
+                    )
+                }
+
+                #[cfg(feature = "hdt")]
+                InnerStatementSource::FHdt(ts) => {
+                    Self::try_for_some_quad_adapted_from_rio_triple_source(
+                        ts,
+                        &mut gated_f,
+                        self.triple_source_graph_iri.as_ref(),
+                        &mut self.provenance_summary,
+                        self.iri_resolver.as_ref(),
+                        self.skolemizer.as_ref(),
+                    )
+                }
+            };
+            if !matches!(r, Ok(true)) {
+                break;
+            }
+        }
+        if let Some(limit) = self
+            .document_limit_guard
+            .as_ref()
+            .and_then(DocumentLimitGuard::exceeded)
+        {
+            return Err(StreamError::SourceError(
+                DynSynParseError::document_limit_exceeded(limit),
+            ));
+        }
+        if let Some(guard) = self.iri_policy_guard.as_ref().filter(|g| g.must_fail()) {
+            let violation = guard
+                .violation()
+                .cloned()
+                .expect("must_fail implies a violation");
+            return Err(StreamError::SourceError(
+                DynSynParseError::iri_policy_violation(violation),
+            ));
+        }
+        if let Some(guard) = self.literal_policy_guard.as_ref().filter(|g| g.must_fail()) {
+            let violation = guard
+                .violation()
+                .cloned()
+                .expect("must_fail implies a violation");
+            return Err(StreamError::SourceError(
+                DynSynParseError::literal_policy_violation(violation),
+            ));
+        }
+        if let (Ok(false), Some(tracker)) = (&r, self.progress_tracker.as_mut()) {
+            tracker.on_end();
+        }
+        match self
+            .session_budget
+            .as_ref()
+            .and_then(SessionBudget::exhausted)
+        {
+            Some(resource) => Err(StreamError::SourceError(
+                DynSynParseError::budget_exhausted(resource),
+            )),
+            None => diagnose_stream_result(r, self.input_sniff),
         }
     }
+
+    /// Enables collection of [`StatementStats`] of quads emitted by this source from now on. See [`stats`](crate::parser::stats) module.
+    pub fn enable_stats(&mut self) {
+        self.stats.get_or_insert_with(StatsCollector::default);
+    }
+
+    /// Returns stats of quads emitted so far, if their collection is enabled. After source is exhausted, they summarize whole document.
+    pub fn stats(&self) -> Option<StatementStats> {
+        self.stats.as_ref().map(StatsCollector::stats)
+    }
+
+    /// Returns prefix bindings, and base iri of the document, that are declared so far. Directives are tracked as they are read, which may be ahead of emitted statements by a buffer. Hence they are complete only after the source is exhausted. See [`prefixes`](crate::parser::prefixes) module.
+    pub fn prefixes(&self) -> DocumentPrefixes {
+        prefixes::lock(&self.prefixes).clone()
+    }
+
+    /// Returns summary of statements, that are skipped so far, if this source is from a lenient parser. See [`recovery`](crate::parser::recovery) module.
+    pub fn skip_summary(&self) -> &SkipSummary {
+        &self.skip_summary
+    }
+
+    /// Takes summary of statements, that are skipped so far, leaving an empty summary in it's place.
+    pub(crate) fn take_skip_summary(&mut self) -> SkipSummary {
+        std::mem::take(&mut self.skip_summary)
+    }
+
+    /// Returns max number of quads, that this source pulls from underlying parser per `try_for_some_*` call.
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// Sets max number of quads, that this source pulls from underlying parser per `try_for_some_*` call. Larger batches amortize per call checks, and dispatch over more statements, while smaller batches return control to caller sooner. It defaults to [`DEFAULT_BATCH_SIZE`], and is at least 1.
+    pub fn set_batch_size(&mut self, batch_size: usize) {
+        self.batch_size = batch_size.max(1);
+    }
+
+    /// Returns true, if this source can skip malformed statements, and keep going. See [`recovery`](crate::parser::recovery) module.
+    pub fn supports_keep_going(&self) -> bool {
+        matches!(
+            &self.inner_source,
+            InnerStatementSource::FNQuads(_) | InnerStatementSource::FNTriples(_)
+        )
+    }
+
+    /// Calls `f` for each quad, like [`try_for_each_quad`](QuadSource::try_for_each_quad), but skips statements that can't be parsed, and keeps going. Returns summary of skipped statements.
+    ///
+    /// If this source doesn't [support keep-going](Self::supports_keep_going), or if error is not confined to a statement, then it returns on first parse error. If this source is from a lenient parser, statements are recorded in it's [`skip_summary`](Self::skip_summary) instead.
+    pub fn try_for_each_quad_keep_going<F, E>(
+        &mut self,
+        mut f: F,
+    ) -> StreamResult<SkipSummary, DynSynParseError, E>
+    where
+        F: FnMut(StreamedQuad<ByValue<TupleQuad<T>>>) -> Result<(), E>,
+        E: Error,
+    {
+        let mut summary = SkipSummary::default();
+        loop {
+            match self.try_for_some_quad(&mut f) {
+                Ok(true) => {}
+                Ok(false) => return Ok(summary),
+                Err(StreamError::SourceError(e))
+                    if self.supports_keep_going() && e.is_recoverable() =>
+                {
+                    tracing::warn!("Skipping malformed statement: {}", e);
+                    summary.skipped.push(e.into());
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Calls `f` for some scoped quads, like [`try_for_some_quad`](QuadSource::try_for_some_quad), but without copying terms of quads. Return false if source is exhausted. See [`scoped`](crate::parser::scoped) module.
+    pub fn try_for_some_quad_scoped<F, E>(
+        &mut self,
+        f: &mut F,
+    ) -> StreamResult<bool, DynSynParseError, E>
+    where
+        F: FnMut(ScopedQuad<'_>) -> Result<(), E>,
+        E: Error,
+    {
+        let syntax_ = self.syntax_;
+        match with_syntax_stream_result(self.try_for_some_scoped_quad_untagged(f), syntax_) {
+            Err(StreamError::SourceError(e))
+                if self.lenient && self.supports_keep_going() && e.is_recoverable() =>
+            {
+                tracing::warn!("Skipping malformed statement: {}", e);
+                self.skip_summary.skipped.push(e.into());
+                Ok(true)
+            }
+            r => r,
+        }
+    }
+
+    /// Calls `f` for each scoped quad, like [`try_for_each_quad`](QuadSource::try_for_each_quad), but without copying terms of quads. See [`scoped`](crate::parser::scoped) module.
+    pub fn try_for_each_quad_scoped<F, E>(
+        &mut self,
+        mut f: F,
+    ) -> StreamResult<(), DynSynParseError, E>
+    where
+        F: FnMut(ScopedQuad<'_>) -> Result<(), E>,
+        E: Error,
+    {
+        while self.try_for_some_quad_scoped(&mut f)? {}
+        Ok(())
+    }
+
+    /// Calls `f` for each scoped quad, like [`for_each_quad`](QuadSource::for_each_quad), but without copying terms of quads. See [`scoped`](crate::parser::scoped) module.
+    pub fn for_each_quad_scoped<F>(&mut self, mut f: F) -> Result<(), DynSynParseError>
+    where
+        F: FnMut(ScopedQuad<'_>),
+    {
+        self.try_for_each_quad_scoped(|q| {
+            f(q);
+            Ok::<_, Infallible>(())
+        })
+        .map_err(|e| match e {
+            StreamError::SourceError(e) => e,
+            StreamError::SinkError(e) => match e {},
+        })
+    }
 }
 
 impl<T, R> quad::stream::QuadSource for DynSynQuadSource<T, R>
@@ -110,39 +639,75 @@ where
     fn try_for_some_quad<F, E>(&mut self, f: &mut F) -> StreamResult<bool, Self::Error, E>
     where
         F: FnMut(StreamedQuad<Self::Quad>) -> Result<(), E>,
-        E: std::error::Error,
+        E: Error,
     {
-        match &mut self.inner_source {
-            InnerStatementSource::FNQuads(qs) => {
-                Self::try_for_some_quad_adapted_from_rio_quad_source(qs, f)
-            }
+        self.try_for_some_owned_quad(&mut |q| f(StreamedQuad::by_value(q)))
+    }
+}
 
-            InnerStatementSource::FTriG(qs) => {
-                Self::try_for_some_quad_adapted_from_rio_quad_source(qs, f)
-            }
+impl<T, R> IntoIterator for DynSynQuadSource<T, R>
+where
+    T: CopyTerm + TTerm + Clone,
+    R: BufRead,
+{
+    type Item = Result<OwnedQuad<T>, DynSynParseError>;
 
-            InnerStatementSource::FNTriples(ts) => {
-                Self::try_for_some_quad_adapted_from_rio_triple_source(
-                    ts,
-                    f,
-                    &self.triple_source_graph_iri,
-                )
-            }
+    type IntoIter = DynSynQuadIter<T, R>;
 
-            InnerStatementSource::FTurtle(ts) => {
-                Self::try_for_some_quad_adapted_from_rio_triple_source(
-                    ts,
-                    f,
-                    &self.triple_source_graph_iri,
-                )
-            }
+    fn into_iter(self) -> Self::IntoIter {
+        DynSynQuadIter {
+            source: Some(self),
+            pending: VecDeque::new(),
+        }
+    }
+}
 
-            InnerStatementSource::FRdfXml(ts) => {
-                Self::try_for_some_quad_adapted_from_rio_triple_source(
-                    ts,
-                    f,
-                    &self.triple_source_graph_iri,
-                )
+/// An [`Iterator`] over quads of a [`DynSynQuadSource`], that can be consumed with `for` loops, iterator combinators, and `collect`. We can get it's instance through [`IntoIterator`] impl of [`DynSynQuadSource`].
+///
+/// It yields [`OwnedQuad`]s, into which terms of parsed quads are moved, without being copied again. Quads are pulled from source in chunks, as they are parsed. On a parse error, quads parsed before it are yielded first, then the error, and then iteration ends.
+pub struct DynSynQuadIter<T: CopyTerm + TTerm, R: BufRead> {
+    source: Option<DynSynQuadSource<T, R>>,
+    pending: VecDeque<Result<OwnedQuad<T>, DynSynParseError>>,
+}
+
+impl<T, R> DynSynQuadIter<T, R>
+where
+    T: CopyTerm + TTerm + Clone,
+    R: BufRead,
+{
+    /// Returns underlying source, if iteration is not yet ended. It can be used to query [`provenance_summary`](DynSynQuadSource::provenance_summary) so far.
+    pub fn source(&self) -> Option<&DynSynQuadSource<T, R>> {
+        self.source.as_ref()
+    }
+}
+
+impl<T, R> Iterator for DynSynQuadIter<T, R>
+where
+    T: CopyTerm + TTerm + Clone,
+    R: BufRead,
+{
+    type Item = Result<OwnedQuad<T>, DynSynParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.pending.pop_front() {
+                return Some(item);
+            }
+            let source = self.source.as_mut()?;
+            let pending = &mut self.pending;
+            match source.try_for_some_owned_quad(&mut |q| {
+                pending.push_back(Ok(OwnedQuad::from(q)));
+                Ok::<_, Infallible>(())
+            }) {
+                Ok(true) => {}
+                Ok(false) => self.source = None,
+                Err(e) => {
+                    self.source = None;
+                    self.pending.push_back(Err(match e {
+                        StreamError::SourceError(e) => e,
+                        StreamError::SinkError(e) => match e {},
+                    }));
+                }
             }
         }
     }
@@ -0,0 +1,562 @@
+//! This module provides reification of quoted triples by dynsyn parsers. It is an explicit opt-in, as it asserts statements, that are not in the document. It can be enabled with [`ParserConfig::reify_quoted_triples`](super::recovery::ParserConfig::reify_quoted_triples) in factory's config map, or with `set_reify_quoted_triples` method of parsers.
+//!
+//! With it, [`turtle`](crate::syntax::TURTLE), [`trig`](crate::syntax::TRIG), [`n-triples`](crate::syntax::N_TRIPLES), [`n-quads`](crate::syntax::N_QUADS), and [`n3`](crate::syntax::N3) with `n3` feature documents can have quoted triples `<< s p o >>` as subjects, and objects of statements, as in their turtle-star, trig-star, n-triples-star, and n-quads-star variants. Without it, such documents fail to parse with an error.
+//!
+//! Sophia terms can't be triples. Hence each distinct quoted triple of a document is read as a blank node, that is described with asserted rdf reification statements, and statements use that blank node in place of quoted triple. Reification statements of a quoted triple are emitted once, just before the first statement, that uses it. Quoted triples are nested the same way. For example, document
+//!
+//! ```text
+//! << <http://ex.org/alice> <http://ex.org/age> 23 >> <http://ex.org/certainty> 0.9 .
+//! ```
+//!
+//! is read as
+//!
+//! ```text
+//! _:rdfstar-qt1b4e28ba2fa1411d9e5b6b4d0e1f7c2a-0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://www.w3.org/1999/02/22-rdf-syntax-ns#Statement> .
+//! _:rdfstar-qt1b4e28ba2fa1411d9e5b6b4d0e1f7c2a-0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#subject> <http://ex.org/alice> .
+//! _:rdfstar-qt1b4e28ba2fa1411d9e5b6b4d0e1f7c2a-0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#predicate> <http://ex.org/age> .
+//! _:rdfstar-qt1b4e28ba2fa1411d9e5b6b4d0e1f7c2a-0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#object> 23 .
+//! _:rdfstar-qt1b4e28ba2fa1411d9e5b6b4d0e1f7c2a-0 <http://ex.org/certainty> 0.9 .
+//! ```
+//!
+//! Blank nodes of quoted triples are labelled with [`QUOTED_TRIPLE_BNODE_PREFIX`], followed by a random id of the document, and a number, that is counted per document. Hence they don't collide with blank node labels of the document itself, nor with those of other documents. Sources keep n-triples-star form of each distinct quoted triple they have read, to map it to same blank node.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{
+//!     parser::{recovery::ParserConfig, triples::DynSynTripleParserFactory},
+//!     syntax,
+//! };
+//! use sophia_api::{graph::Graph, parser::TripleParser, triple::stream::TripleSource};
+//! use sophia_inmem::graph::FastGraph;
+//! use sophia_term::BoxTerm;
+//! use type_map::concurrent::TypeMap;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut config_map = TypeMap::new();
+//! config_map.insert(ParserConfig::reify_quoted_triples());
+//! let parser = DynSynTripleParserFactory::new(Some(config_map))
+//!     .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)?;
+//! let graph: FastGraph = parser
+//!     .parse_str("<< <http://ex.org/alice> <http://ex.org/age> 23 >> <http://ex.org/certainty> 0.9 .")
+//!     .collect_triples()?;
+//! assert_eq!(graph.triples().count(), 5);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::collections::{hash_map::Entry, HashMap};
+
+use rio_api::{
+    model::{
+        BlankNode, GeneralizedQuad, GeneralizedTerm, Literal, NamedNode, Quad, Subject, Term, Triple,
+    },
+    parser::{GeneralizedQuadsParser, LineBytePosition, ParseError, QuadsParser, TriplesParser},
+};
+use rio_turtle::TurtleError;
+use sophia_iri::is_valid_iri_ref;
+use sophia_rio::parser::{GeneralizedRioSource, StrictRioSource};
+use uuid::Uuid;
+
+use super::iri_validation::IriNormalizer;
+
+/// Prefix of labels of blank nodes, that stand for quoted triples. It is followed by a random id of the document, and number of quoted triple in the document.
+pub const QUOTED_TRIPLE_BNODE_PREFIX: &str = "rdfstar-qt";
+
+const RDF_TYPE: NamedNode<'static> = NamedNode {
+    iri: "http://www.w3.org/1999/02/22-rdf-syntax-ns#type",
+};
+const RDF_STATEMENT: NamedNode<'static> = NamedNode {
+    iri: "http://www.w3.org/1999/02/22-rdf-syntax-ns#Statement",
+};
+const RDF_SUBJECT: NamedNode<'static> = NamedNode {
+    iri: "http://www.w3.org/1999/02/22-rdf-syntax-ns#subject",
+};
+const RDF_PREDICATE: NamedNode<'static> = NamedNode {
+    iri: "http://www.w3.org/1999/02/22-rdf-syntax-ns#predicate",
+};
+const RDF_OBJECT: NamedNode<'static> = NamedNode {
+    iri: "http://www.w3.org/1999/02/22-rdf-syntax-ns#object",
+};
+
+/// A rio parser adaptor for turtle family parsers, that reads quoted triples as reified blank nodes if enabled, and rejects them otherwise.
+pub struct RdfStarAdapter<P> {
+    inner: P,
+    unstar: Unstar,
+    iri_normalizer: Option<IriNormalizer>,
+}
+
+/// Adapt given source of a turtle family parser, to read quoted triples as per given mode. Iris of statements are normalized with given `iri_normalizer`, if any, after quoted triples are read. See [`iri_validation`](super::iri_validation) module.
+pub(crate) fn adapt<P>(
+    source: StrictRioSource<P, TurtleError>,
+    reify_quoted_triples: bool,
+    iri_normalizer: Option<IriNormalizer>,
+) -> StrictRioSource<RdfStarAdapter<P>, RdfStarError> {
+    match source {
+        StrictRioSource::Parser(inner) => StrictRioSource::Parser(RdfStarAdapter {
+            inner,
+            unstar: Unstar::new(reify_quoted_triples),
+            iri_normalizer,
+        }),
+        StrictRioSource::Error(e) => StrictRioSource::Error(e.map(RdfStarError::from)),
+    }
+}
+
+/// Adapt given source of a generalized turtle family parser, to reject quoted triples, and invalid iris. Quoted triples are not reified in generalized mode.
+pub(crate) fn adapt_generalized<P>(
+    source: GeneralizedRioSource<P, TurtleError>,
+) -> GeneralizedRioSource<RdfStarAdapter<P>, RdfStarError> {
+    match source {
+        GeneralizedRioSource::Parser(inner) => GeneralizedRioSource::Parser(RdfStarAdapter {
+            inner,
+            unstar: Unstar::new(false),
+            iri_normalizer: None,
+        }),
+        GeneralizedRioSource::Error(e) => GeneralizedRioSource::Error(e.map(RdfStarError::from)),
+    }
+}
+
+/// State of the mapping of quoted triples to blank nodes.
+struct Unstar {
+    enabled: bool,
+    /// Numbers of blank nodes of quoted triples read so far, keyed by their n-triples-star form.
+    quoted: HashMap<String, usize>,
+    /// Prefix of labels of blank nodes of quoted triples of the document.
+    label_prefix: String,
+}
+
+impl Unstar {
+    fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            quoted: HashMap::new(),
+            label_prefix: format!("{}{}-", QUOTED_TRIPLE_BNODE_PREFIX, Uuid::new_v4().simple()),
+        }
+    }
+
+    /// Call `emit` with reification triples of quoted triples in given triple, that are not read before, and then with the triple itself, with it's quoted triples replaced by their blank nodes.
+    fn unstar<E>(
+        &mut self,
+        triple: Triple<'_>,
+        emit: &mut impl FnMut(Triple<'_>) -> Result<(), E>,
+    ) -> Result<(), E>
+    where
+        E: From<RdfStarError>,
+    {
+        let mut quoted = Vec::new();
+        collect_quoted(&triple, &mut quoted);
+        if quoted.is_empty() {
+            return emit(triple);
+        }
+        if !self.enabled {
+            return Err(RdfStarError::QuotedTriple(triple.to_string()).into());
+        }
+        let nodes: Vec<(String, bool)> = quoted
+            .iter()
+            .map(|q| {
+                let next = self.quoted.len();
+                let (n, is_new) = match self.quoted.entry(q.to_string()) {
+                    Entry::Occupied(e) => (*e.get(), false),
+                    Entry::Vacant(e) => (*e.insert(next), true),
+                };
+                (format!("{}{}", self.label_prefix, n), is_new)
+            })
+            .collect();
+        let replaced = |term| replace_quoted(term, &quoted, &nodes);
+
+        for (q, (id, is_new)) in quoted.iter().zip(&nodes) {
+            if !is_new {
+                continue;
+            }
+            let subject = Subject::BlankNode(BlankNode { id });
+            for (predicate, object) in [
+                (RDF_TYPE, Term::NamedNode(RDF_STATEMENT)),
+                (RDF_SUBJECT, replaced(q.subject.into())),
+                (RDF_PREDICATE, Term::NamedNode(q.predicate)),
+                (RDF_OBJECT, replaced(q.object)),
+            ] {
+                emit(Triple {
+                    subject,
+                    predicate,
+                    object,
+                })?;
+            }
+        }
+        emit(Triple {
+            subject: match replaced(triple.subject.into()) {
+                Term::NamedNode(n) => Subject::NamedNode(n),
+                Term::BlankNode(b) => Subject::BlankNode(b),
+                _ => unreachable!("subjects are never literals"),
+            },
+            predicate: triple.predicate,
+            object: replaced(triple.object),
+        })
+    }
+}
+
+/// Replace given term with blank node of the quoted triple it is, if any.
+fn replace_quoted<'x>(
+    term: Term<'x>,
+    quoted: &[&'x Triple<'x>],
+    nodes: &'x [(String, bool)],
+) -> Term<'x> {
+    match term {
+        Term::Triple(q) => {
+            let i = quoted
+                .iter()
+                .position(|x| std::ptr::eq(*x, q))
+                .expect("all quoted triples are collected");
+            Term::BlankNode(BlankNode { id: &nodes[i].0 })
+        }
+        term => term,
+    }
+}
+
+/// Collect quoted triples in given triple, nested ones before those that contain them.
+fn collect_quoted<'a>(triple: &Triple<'a>, quoted: &mut Vec<&'a Triple<'a>>) {
+    if let Subject::Triple(q) = triple.subject {
+        collect_quoted(q, quoted);
+        quoted.push(q);
+    }
+    if let Term::Triple(q) = triple.object {
+        collect_quoted(q, quoted);
+        quoted.push(q);
+    }
+}
+
+/// An error sink, that lets handlers of inner parser return errors of rdf-star adaptor.
+struct Sink<E>(E);
+
+impl<E: From<RdfStarError>> From<TurtleError> for Sink<E> {
+    fn from(e: TurtleError) -> Self {
+        Self(RdfStarError::from(e).into())
+    }
+}
+
+impl<E: From<RdfStarError>> From<RdfStarError> for Sink<E> {
+    fn from(e: RdfStarError) -> Self {
+        Self(e.into())
+    }
+}
+
+impl<P> TriplesParser for RdfStarAdapter<P>
+where
+    P: TriplesParser<Error = TurtleError>,
+{
+    type Error = RdfStarError;
+
+    fn parse_step<E: From<Self::Error>>(
+        &mut self,
+        on_triple: &mut impl FnMut(Triple<'_>) -> Result<(), E>,
+    ) -> Result<(), E> {
+        let unstar = &mut self.unstar;
+        let iri_normalizer = self.iri_normalizer.as_ref();
+        self.inner
+            .parse_step(&mut |t| {
+                unstar.unstar(t, &mut |t| {
+                    IriNormalizer::normalize_triple(iri_normalizer, t, &mut |t| {
+                        on_triple(t).map_err(Sink)
+                    })
+                })
+            })
+            .map_err(|Sink(e)| e)
+    }
+
+    fn is_end(&self) -> bool {
+        self.inner.is_end()
+    }
+}
+
+impl<P> QuadsParser for RdfStarAdapter<P>
+where
+    P: QuadsParser<Error = TurtleError>,
+{
+    type Error = RdfStarError;
+
+    fn parse_step<E: From<Self::Error>>(
+        &mut self,
+        on_quad: &mut impl FnMut(Quad<'_>) -> Result<(), E>,
+    ) -> Result<(), E> {
+        let unstar = &mut self.unstar;
+        let iri_normalizer = self.iri_normalizer.as_ref();
+        self.inner
+            .parse_step(&mut |q| {
+                IriNormalizer::normalize_graph_name(iri_normalizer, q, &mut |q| {
+                    let triple = Triple {
+                        subject: q.subject,
+                        predicate: q.predicate,
+                        object: q.object,
+                    };
+                    unstar.unstar(triple, &mut |t| {
+                        IriNormalizer::normalize_triple(iri_normalizer, t, &mut |t| {
+                            on_quad(Quad {
+                                subject: t.subject,
+                                predicate: t.predicate,
+                                object: t.object,
+                                graph_name: q.graph_name,
+                            })
+                            .map_err(Sink)
+                        })
+                    })
+                })
+            })
+            .map_err(|Sink(e)| e)
+    }
+
+    fn is_end(&self) -> bool {
+        self.inner.is_end()
+    }
+}
+
+impl<P> GeneralizedQuadsParser for RdfStarAdapter<P>
+where
+    P: GeneralizedQuadsParser<Error = TurtleError>,
+{
+    type Error = RdfStarError;
+
+    fn parse_step<E: From<Self::Error>>(
+        &mut self,
+        on_quad: &mut impl FnMut(GeneralizedQuad<'_>) -> Result<(), E>,
+    ) -> Result<(), E> {
+        self.inner
+            .parse_step(&mut |q| {
+                let terms = [q.subject, q.predicate, q.object];
+                let mut terms = terms.iter().chain(q.graph_name.as_ref());
+                if terms.clone().any(|t| matches!(t, GeneralizedTerm::Triple(_))) {
+                    return Err(Sink::from(RdfStarError::GeneralizedQuotedTriple(
+                        q.to_string(),
+                    )));
+                }
+                // Generalized parser doesn't validate iris.
+                if terms.any(|t| match t {
+                    GeneralizedTerm::NamedNode(n) => !is_valid_iri_ref(n.iri),
+                    GeneralizedTerm::Literal(Literal::Typed { datatype, .. }) => {
+                        !is_valid_iri_ref(datatype.iri)
+                    }
+                    _ => false,
+                }) {
+                    return Err(Sink::from(RdfStarError::InvalidIri(q.to_string())));
+                }
+                on_quad(q).map_err(Sink)
+            })
+            .map_err(|Sink(e)| e)
+    }
+
+    fn is_end(&self) -> bool {
+        self.inner.is_end()
+    }
+}
+
+/// An error in parsing a turtle family document, with or without reification of quoted triples.
+#[derive(Debug, thiserror::Error)]
+pub enum RdfStarError {
+    #[error(transparent)]
+    Turtle(#[from] TurtleError),
+    #[error("statement {0} has a quoted triple, but reification of quoted triples is not enabled")]
+    QuotedTriple(String),
+    #[error("statement {0} has a quoted triple, which is not supported in generalized mode")]
+    GeneralizedQuotedTriple(String),
+    #[error("statement {0} has an invalid iri")]
+    InvalidIri(String),
+}
+
+impl ParseError for RdfStarError {
+    fn textual_position(&self) -> Option<LineBytePosition> {
+        match self {
+            Self::Turtle(e) => e.textual_position(),
+            Self::QuotedTriple(_) | Self::GeneralizedQuotedTriple(_) | Self::InvalidIri(_) => None,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use claim::assert_matches;
+    use once_cell::sync::Lazy;
+    use sophia_api::{
+        dataset::Dataset,
+        graph::isomorphic_graphs,
+        parser::{QuadParser, TripleParser},
+        quad::{stream::QuadSource, Quad},
+        term::TTerm,
+        triple::{
+            stream::{StreamError, TripleSource},
+            Triple,
+        },
+    };
+    use sophia_inmem::{dataset::FastDataset, graph::FastGraph};
+    use sophia_term::BoxTerm;
+    use sophia_turtle::parser::turtle;
+    use test_case::test_case;
+    use type_map::concurrent::TypeMap;
+
+    use super::QUOTED_TRIPLE_BNODE_PREFIX;
+    use crate::{
+        parser::{
+            errors::ParseErrorKind, quads::DynSynQuadParserFactory, recovery::ParserConfig,
+            triples::DynSynTripleParserFactory,
+        },
+        syntax::{self, RdfSyntax},
+        tests::TRACING,
+    };
+
+    static STAR_FACTORY: Lazy<DynSynTripleParserFactory> = Lazy::new(|| {
+        let mut config_map = TypeMap::new();
+        config_map.insert(ParserConfig::reify_quoted_triples());
+        DynSynTripleParserFactory::new(Some(config_map))
+    });
+
+    static STAR_TURTLE_DOC: &str = r#"
+        @prefix ex: <http://ex.org/> .
+        << ex:alice ex:age 23 >> ex:certainty 0.9 .
+        ex:bob ex:says << ex:alice ex:age 23 >> .
+        << << ex:alice ex:knows _:b >> ex:source ex:web >> ex:retrieved "2021" .
+    "#;
+
+    static UNSTARRED_TURTLE_DOC: &str = r#"
+        @prefix ex: <http://ex.org/> .
+        @prefix rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> .
+        _:q0 a rdf:Statement ; rdf:subject ex:alice ; rdf:predicate ex:age ; rdf:object 23 ;
+            ex:certainty 0.9 .
+        ex:bob ex:says _:q0 .
+        _:q1 a rdf:Statement ; rdf:subject ex:alice ; rdf:predicate ex:knows ; rdf:object _:b .
+        _:q2 a rdf:Statement ; rdf:subject _:q1 ; rdf:predicate ex:source ; rdf:object ex:web ;
+            ex:retrieved "2021" .
+    "#;
+
+    #[test]
+    pub fn reads_quoted_triples_as_reified_blank_nodes() {
+        Lazy::force(&TRACING);
+        let g1: FastGraph = STAR_FACTORY
+            .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)
+            .unwrap()
+            .parse_str(STAR_TURTLE_DOC)
+            .collect_triples()
+            .unwrap();
+        let g2: FastGraph = turtle::parse_str(UNSTARRED_TURTLE_DOC)
+            .collect_triples()
+            .unwrap();
+        assert!(isomorphic_graphs(&g1, &g2).unwrap());
+    }
+
+    #[test]
+    pub fn emits_reification_of_a_quoted_triple_once() {
+        Lazy::force(&TRACING);
+        let mut source = STAR_FACTORY
+            .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)
+            .unwrap()
+            .parse_str(STAR_TURTLE_DOC);
+        let mut subjects = Vec::new();
+        source
+            .for_each_triple(|t| subjects.push(t.s().value().to_string()))
+            .unwrap();
+        assert_eq!(subjects.len(), 15);
+        assert!(subjects[0].starts_with(QUOTED_TRIPLE_BNODE_PREFIX));
+        assert!(subjects[0].ends_with("-0"));
+        assert!(subjects[..5].iter().all(|s| s == &subjects[0]));
+        assert_eq!(subjects[5], "http://ex.org/bob");
+    }
+
+    #[test]
+    pub fn labels_of_quoted_triples_dont_collide_with_document_labels() {
+        Lazy::force(&TRACING);
+        let parser = STAR_FACTORY
+            .try_new_parser::<BoxTerm>(syntax::N_TRIPLES, None, None)
+            .unwrap();
+        let doc = "_:rdfstar-qt0 <http://ex.org/q> \"own\" .\n<< <http://ex.org/s> <http://ex.org/p> <http://ex.org/o> >> <http://ex.org/q> \"quoted\" .\n";
+        let subjects = |doc: &str| {
+            let mut subjects = Vec::new();
+            parser
+                .parse_str(doc)
+                .for_each_triple(|t| subjects.push(t.s().value().to_string()))
+                .unwrap();
+            subjects
+        };
+        let (s1, s2) = (subjects(doc), subjects(doc));
+        assert_eq!(s1.len(), 6);
+        assert_ne!(s1[0], s1[1]);
+        // Each document gets it's own labels.
+        assert_ne!(s1[1], s2[1]);
+    }
+
+    #[test_case(
+        syntax::N_TRIPLES,
+        "<< <http://ex.org/s> <http://ex.org/p> <http://ex.org/o> >> <http://ex.org/q> \"v\" ."
+    )]
+    #[test_case(syntax::N_QUADS, "<http://ex.org/s> <http://ex.org/q> << <http://ex.org/s> <http://ex.org/p> \"o\"@en >> <http://ex.org/g> .")]
+    #[test_case(
+        syntax::TRIG,
+        "<http://ex.org/g> { << <http://ex.org/s> <http://ex.org/p> _:o >> <http://ex.org/q> 1 . }"
+    )]
+    pub fn reads_star_variants_of_syntaxes_into_datasets(syntax_: RdfSyntax, doc: &str) {
+        Lazy::force(&TRACING);
+        let mut config_map = TypeMap::new();
+        config_map.insert(ParserConfig::reify_quoted_triples());
+        let d: FastDataset = DynSynQuadParserFactory::new(Some(config_map))
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap()
+            .parse_str(doc)
+            .collect_quads()
+            .unwrap();
+        let quads: Vec<_> = d.quads().map(|q| q.unwrap()).collect();
+        assert_eq!(quads.len(), 5);
+        // Reification statements are in same graph as the statement, that quotes the triple.
+        let g = quads[4].g().map(|g| g.value().to_string());
+        assert!(quads
+            .iter()
+            .all(|q| q.g().map(|g| g.value().to_string()) == g));
+    }
+
+    #[test_case(syntax::TURTLE)]
+    #[test_case(syntax::N_TRIPLES)]
+    pub fn quoted_triples_are_rejected_without_reification(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let parser = DynSynTripleParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap();
+        assert!(!parser.reifies_quoted_triples());
+        let r = parser
+            .parse_str("<< <http://ex.org/s> <http://ex.org/p> <http://ex.org/o> >> <http://ex.org/q> <http://ex.org/o> .")
+            .collect_triples::<FastGraph>()
+            .map(|_| ());
+        assert_matches!(&r, Err(StreamError::SourceError(e)) if e.kind() == ParseErrorKind::Syntax);
+        assert!(r
+            .unwrap_err()
+            .to_string()
+            .contains("reification of quoted triples is not enabled"));
+    }
+
+    #[test]
+    pub fn lenient_parsers_skip_quoted_triples_without_reification() {
+        Lazy::force(&TRACING);
+        let mut parser = DynSynTripleParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::N_TRIPLES, None, None)
+            .unwrap();
+        parser.set_lenient(true);
+        let mut source = parser.parse_str(
+            "<http://ex.org/s> <http://ex.org/p> <http://ex.org/o> .\n<< <http://ex.org/s> <http://ex.org/p> <http://ex.org/o> >> <http://ex.org/q> <http://ex.org/o> .\n",
+        );
+        let mut count = 0;
+        source.for_each_triple(|_| count += 1).unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(source.skip_summary().skipped.len(), 1);
+    }
+
+    #[test]
+    pub fn set_reify_quoted_triples_overrides_factory_config() {
+        Lazy::force(&TRACING);
+        let mut parser = STAR_FACTORY
+            .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)
+            .unwrap();
+        assert!(parser.reifies_quoted_triples());
+        parser.set_reify_quoted_triples(false);
+        assert!(parser
+            .parse_str(STAR_TURTLE_DOC)
+            .collect_triples::<FastGraph>()
+            .is_err());
+    }
+}
@@ -0,0 +1,470 @@
+//! This module provides a lenient html tokenizer, and tree builder. It recognizes just enough of html, and xhtml to extract rdfa from real world pages, which are often not well-formed.
+//!
+//! Unlike html5 tree construction, it doesn't reparent misnested elements. End tags close nearest open element of same name, and stray end tags are ignored.
+
+use std::ops::Range;
+
+/// Elements that never have content.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Elements, whose content is text, that is not tokenized. Character references are decoded only in escapable ones.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title"];
+
+/// Raw text elements, whose content can have character references.
+const ESCAPABLE_RAW_TEXT_ELEMENTS: &[&str] = &["textarea", "title"];
+
+/// Groups of elements, whose open element is implicitly closed by start tag of any element in same group.
+const IMPLICITLY_CLOSED_GROUPS: &[&[&str]] = &[
+    &["li"],
+    &["dt", "dd"],
+    &["p"],
+    &["option"],
+    &["tr"],
+    &["td", "th"],
+];
+
+/// A node of an html document tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    Element(Element),
+    Text(String),
+}
+
+/// An element of an html document tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Element {
+    /// Name of the element, in lower case.
+    pub name: String,
+    /// Attributes of the element, with names in lower case, in order of their appearance. Duplicate attributes are dropped.
+    pub attrs: Vec<(String, String)>,
+    pub children: Vec<Node>,
+    /// Byte range of markup of the element's content in the document.
+    pub content: Range<usize>,
+}
+
+impl Element {
+    fn new(name: String, attrs: Vec<(String, String)>, content_start: usize) -> Self {
+        Self {
+            name,
+            attrs,
+            children: Vec::new(),
+            content: content_start..content_start,
+        }
+    }
+
+    /// Returns value of given attribute, if present.
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Returns true if given attribute is present.
+    pub fn has_attr(&self, name: &str) -> bool {
+        self.attr(name).is_some()
+    }
+
+    /// Returns text content of the element, i.e. concatenation of all descendant text nodes.
+    pub fn text_content(&self) -> String {
+        let mut text = String::new();
+        self.push_text_content(&mut text);
+        text
+    }
+
+    fn push_text_content(&self, text: &mut String) {
+        for child in &self.children {
+            match child {
+                Node::Element(e) => e.push_text_content(text),
+                Node::Text(t) => text.push_str(t),
+            }
+        }
+    }
+}
+
+/// Parses given html document into a tree. Returned element is a synthetic document element named `#document`, whose children are top level nodes of the document.
+pub fn parse_document(doc: &str) -> Element {
+    let mut stack = vec![Element::new("#document".into(), Vec::new(), 0)];
+    let bytes = doc.as_bytes();
+    let mut pos = 0;
+    let mut text_start = 0;
+    while let Some(offset) = memchr(b'<', &bytes[pos..]) {
+        let lt = pos + offset;
+        let rest = &doc[lt..];
+        let (markup, end) = if rest.starts_with("<!--") {
+            (Markup::Skipped, find_or_end(doc, lt + 4, "-->"))
+        } else if rest.starts_with("<![CDATA[") {
+            let end = find_or_end(doc, lt + 9, "]]>");
+            let text_end = end.saturating_sub(3).max(lt + 9);
+            (Markup::CData(lt + 9..text_end), end)
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            (Markup::Skipped, find_or_end(doc, lt, ">"))
+        } else if rest.starts_with("</") && starts_with_name(&bytes[lt + 2..]) {
+            let (name, name_end) = read_name(doc, lt + 2);
+            (Markup::EndTag(name), find_or_end(doc, name_end, ">"))
+        } else if starts_with_name(&bytes[lt + 1..]) {
+            let (name, name_end) = read_name(doc, lt + 1);
+            let (attrs, self_closing, end) = read_attrs(doc, name_end);
+            (
+                Markup::StartTag {
+                    name,
+                    attrs,
+                    self_closing,
+                },
+                end,
+            )
+        } else {
+            // A literal `<` in text.
+            pos = lt + 1;
+            continue;
+        };
+
+        push_text(&mut stack, &doc[text_start..lt]);
+        match markup {
+            Markup::Skipped => {}
+            Markup::CData(range) => push_raw_text(&mut stack, &doc[range]),
+            Markup::EndTag(name) => {
+                if let Some(i) = stack.iter().rposition(|e| e.name == name) {
+                    if i > 0 {
+                        close_elements(&mut stack, i, lt);
+                    }
+                }
+            }
+            Markup::StartTag {
+                name,
+                attrs,
+                self_closing,
+            } => {
+                if let Some(group) = IMPLICITLY_CLOSED_GROUPS
+                    .iter()
+                    .find(|g| g.contains(&&*name))
+                {
+                    let top = stack.len() - 1;
+                    if top > 0 && group.contains(&&*stack[top].name) {
+                        close_elements(&mut stack, top, lt);
+                    }
+                }
+                let is_void = VOID_ELEMENTS.contains(&&*name);
+                let is_raw = RAW_TEXT_ELEMENTS.contains(&&*name);
+                let mut element = Element::new(name, attrs, end);
+                if is_void || self_closing {
+                    push_node(&mut stack, Node::Element(element));
+                } else if is_raw {
+                    let close_tag = format!("</{}", element.name);
+                    let text_end = find_ignore_case(doc, end, &close_tag).unwrap_or(doc.len());
+                    let text = &doc[end..text_end];
+                    let text = if ESCAPABLE_RAW_TEXT_ELEMENTS.contains(&&*element.name) {
+                        decode_entities(text)
+                    } else {
+                        text.to_owned()
+                    };
+                    element.children.push(Node::Text(text));
+                    element.content = end..text_end;
+                    push_node(&mut stack, Node::Element(element));
+                    let end = find_or_end(doc, text_end, ">");
+                    pos = end;
+                    text_start = end;
+                    continue;
+                } else {
+                    stack.push(element);
+                }
+            }
+        }
+        pos = end;
+        text_start = end;
+    }
+    push_text(&mut stack, &doc[text_start..]);
+    if stack.len() > 1 {
+        close_elements(&mut stack, 1, doc.len());
+    }
+    let mut document = stack.pop().expect("document element is never closed");
+    document.content = 0..doc.len();
+    document
+}
+
+/// A markup construct of an html document.
+enum Markup {
+    /// Comments, doctypes, processing instructions.
+    Skipped,
+    CData(Range<usize>),
+    EndTag(String),
+    StartTag {
+        name: String,
+        attrs: Vec<(String, String)>,
+        self_closing: bool,
+    },
+}
+
+/// Closes open elements from given index of the stack to it's top, with their content ending at `content_end`.
+fn close_elements(stack: &mut Vec<Element>, from: usize, content_end: usize) {
+    while stack.len() > from {
+        let mut element = stack.pop().expect("stack is longer than from");
+        element.content.end = content_end.max(element.content.start);
+        push_node(stack, Node::Element(element));
+    }
+}
+
+fn push_node(stack: &mut [Element], node: Node) {
+    stack
+        .last_mut()
+        .expect("document element is never closed")
+        .children
+        .push(node);
+}
+
+fn push_text(stack: &mut [Element], text: &str) {
+    if !text.is_empty() {
+        push_raw_text(stack, &decode_entities(text));
+    }
+}
+
+fn push_raw_text(stack: &mut [Element], text: &str) {
+    let parent = stack.last_mut().expect("document element is never closed");
+    match parent.children.last_mut() {
+        Some(Node::Text(t)) => t.push_str(text),
+        _ => parent.children.push(Node::Text(text.to_owned())),
+    }
+}
+
+fn memchr(needle: u8, haystack: &[u8]) -> Option<usize> {
+    haystack.iter().position(|&b| b == needle)
+}
+
+/// Returns offset just after first occurrence of `pat` at or after `from`, or end of document.
+fn find_or_end(doc: &str, from: usize, pat: &str) -> usize {
+    doc[from..]
+        .find(pat)
+        .map_or(doc.len(), |i| from + i + pat.len())
+}
+
+/// Returns offset of first ascii case-insensitive occurrence of `pat` at or after `from`.
+fn find_ignore_case(doc: &str, from: usize, pat: &str) -> Option<usize> {
+    let pat = pat.as_bytes();
+    doc.as_bytes()[from..]
+        .windows(pat.len())
+        .position(|w| w.eq_ignore_ascii_case(pat))
+        .map(|i| from + i)
+}
+
+fn starts_with_name(bytes: &[u8]) -> bool {
+    bytes.first().is_some_and(|b| b.is_ascii_alphabetic())
+}
+
+fn is_name_end(b: u8) -> bool {
+    b.is_ascii_whitespace() || b == b'>' || b == b'/'
+}
+
+/// Reads a tag name starting at `from`. Returns it in lower case, along with offset after it.
+fn read_name(doc: &str, from: usize) -> (String, usize) {
+    let end = doc.as_bytes()[from..]
+        .iter()
+        .position(|&b| is_name_end(b))
+        .map_or(doc.len(), |i| from + i);
+    (doc[from..end].to_ascii_lowercase(), end)
+}
+
+/// Reads attributes of a start tag starting at `from`. Returns them along with whether tag is self-closing, and offset after the tag.
+fn read_attrs(doc: &str, from: usize) -> (Vec<(String, String)>, bool, usize) {
+    let bytes = doc.as_bytes();
+    let mut attrs: Vec<(String, String)> = Vec::new();
+    let mut pos = from;
+    loop {
+        while pos < bytes.len() && (bytes[pos].is_ascii_whitespace() || bytes[pos] == b'/') {
+            pos += 1;
+        }
+        if pos >= bytes.len() {
+            return (attrs, false, bytes.len());
+        }
+        if bytes[pos] == b'>' {
+            return (attrs, bytes[pos - 1] == b'/', pos + 1);
+        }
+        let name_start = pos;
+        while pos < bytes.len() && !is_name_end(bytes[pos]) && bytes[pos] != b'=' {
+            pos += 1;
+        }
+        let name = doc[name_start..pos].to_ascii_lowercase();
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        let mut value = String::new();
+        if pos < bytes.len() && bytes[pos] == b'=' {
+            pos += 1;
+            while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+                pos += 1;
+            }
+            let (raw, end) = match bytes.get(pos) {
+                Some(&q) if q == b'"' || q == b'\'' => {
+                    let end = memchr(q, &bytes[pos + 1..]).map_or(bytes.len(), |i| pos + 1 + i);
+                    (&doc[pos + 1..end], (end + 1).min(bytes.len()))
+                }
+                _ => {
+                    let end = bytes[pos..]
+                        .iter()
+                        .position(|&b| b.is_ascii_whitespace() || b == b'>')
+                        .map_or(bytes.len(), |i| pos + i);
+                    (&doc[pos..end], end)
+                }
+            };
+            value = decode_entities(raw);
+            pos = end;
+        }
+        if !attrs.iter().any(|(n, _)| *n == name) {
+            attrs.push((name, value));
+        }
+    }
+}
+
+/// Named character references, that are decoded. Others are left as they are.
+const NAMED_REFERENCES: &[(&str, &str)] = &[
+    ("amp", "&"),
+    ("lt", "<"),
+    ("gt", ">"),
+    ("quot", "\""),
+    ("apos", "'"),
+    ("nbsp", "\u{A0}"),
+    ("copy", "\u{A9}"),
+    ("reg", "\u{AE}"),
+    ("trade", "\u{2122}"),
+    ("hellip", "\u{2026}"),
+    ("ndash", "\u{2013}"),
+    ("mdash", "\u{2014}"),
+    ("lsquo", "\u{2018}"),
+    ("rsquo", "\u{2019}"),
+    ("ldquo", "\u{201C}"),
+    ("rdquo", "\u{201D}"),
+    ("euro", "\u{20AC}"),
+];
+
+/// Decodes character references in given text.
+pub fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_owned();
+    }
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(i) = rest.find('&') {
+        decoded.push_str(&rest[..i]);
+        rest = &rest[i..];
+        match decode_reference(rest) {
+            Some((c, len)) => {
+                decoded.push_str(&c);
+                rest = &rest[len..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Decodes character reference at start of given text. Returns decoded text, and length of the reference.
+fn decode_reference(text: &str) -> Option<(String, usize)> {
+    let semi = text.bytes().take(12).position(|b| b == b';')?;
+    let body = &text[1..semi];
+    let decoded = if let Some(num) = body.strip_prefix('#') {
+        let code = match num.strip_prefix('x').or_else(|| num.strip_prefix('X')) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => num.parse().ok()?,
+        };
+        char::from_u32(code)
+            .unwrap_or(char::REPLACEMENT_CHARACTER)
+            .to_string()
+    } else {
+        NAMED_REFERENCES
+            .iter()
+            .find(|(name, _)| *name == body)
+            .map(|(_, c)| (*c).to_owned())?
+    };
+    Some((decoded, semi + 1))
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use test_case::test_case;
+
+    use super::{decode_entities, parse_document, Element, Node};
+    use crate::tests::TRACING;
+
+    fn outline(element: &Element) -> String {
+        let children: Vec<_> = element
+            .children
+            .iter()
+            .map(|c| match c {
+                Node::Element(e) => outline(e),
+                Node::Text(t) => format!("{:?}", t),
+            })
+            .collect();
+        format!("{}({})", element.name, children.join(" "))
+    }
+
+    #[test_case("<p>a<b>b</b></p>", "#document(p(\"a\" b(\"b\")))")]
+    #[test_case("<ul><li>a<li>b</ul>", "#document(ul(li(\"a\") li(\"b\")))")]
+    #[test_case("<p>a<br>b<img src=x/>c", "#document(p(\"a\" br() \"b\" img() \"c\"))")]
+    #[test_case("<div>a</span>b</div>", "#document(div(\"ab\"))")]
+    #[test_case("<!DOCTYPE html><!-- <p> --><p>&lt;a&gt;</p>", "#document(p(\"<a>\"))")]
+    #[test_case(
+        "<script>if (a < b) { x = '</p>'; }</script>",
+        "#document(script(\"if (a < b) { x = '</p>'; }\"))"
+    )]
+    #[test_case("<p><![CDATA[a<b]]></p>", "#document(p(\"a<b\"))")]
+    #[test_case("a < b", "#document(\"a < b\")")]
+    pub fn builds_lenient_tree(doc: &str, expected: &str) {
+        Lazy::force(&TRACING);
+        assert_eq!(outline(&parse_document(doc)), expected);
+    }
+
+    #[test]
+    pub fn reads_attributes() {
+        Lazy::force(&TRACING);
+        let document = parse_document(
+            "<DIV Property=\"a&amp;b\" typeof='x' hidden data-x=y about=\"1\" about=\"2\">c</DIV>",
+        );
+        let div = match &document.children[0] {
+            Node::Element(e) => e,
+            _ => panic!("expected an element"),
+        };
+        assert_eq!(div.name, "div");
+        assert_eq!(
+            div.attrs,
+            [
+                ("property", "a&b"),
+                ("typeof", "x"),
+                ("hidden", ""),
+                ("data-x", "y"),
+                ("about", "1")
+            ]
+            .map(|(n, v)| (n.to_owned(), v.to_owned()))
+        );
+    }
+
+    #[test]
+    pub fn records_content_markup() {
+        Lazy::force(&TRACING);
+        let doc = "<div><p>a <b>b</b></p></div>";
+        let document = parse_document(doc);
+        let div = match &document.children[0] {
+            Node::Element(e) => e,
+            _ => panic!("expected an element"),
+        };
+        assert_eq!(&doc[div.content.clone()], "<p>a <b>b</b></p>");
+        assert_eq!(div.text_content(), "a b");
+    }
+
+    #[test_case("a &amp; b", "a & b")]
+    #[test_case("&#65;&#x42;&#X43;", "ABC")]
+    #[test_case("&unknown; & &amp", "&unknown; & &amp")]
+    pub fn decodes_entities(text: &str, expected: &str) {
+        Lazy::force(&TRACING);
+        assert_eq!(decode_entities(text), expected);
+    }
+}
@@ -0,0 +1,352 @@
+//! This module provides an [rdfa 1.1](https://www.w3.org/TR/rdfa-core/) extractor, that parses triples from [html+rdfa](crate::syntax::HTML_RDFA), and [xhtml+rdfa](crate::syntax::XHTML_RDFA) documents. Dynsyn parsers use it for those syntaxes with `rdfa` feature.
+//!
+//! Html is parsed leniently, as real world pages are often not well-formed. Whole document is read, before triples are extracted. Documents must be encoded in utf-8.
+//!
+//! Base iri given to the parser is the document iri, which can be overridden by a `<base href>` element of the document. Without a base iri, relative iris in the document are kept as they are.
+//!
+//! Blank node labels of the document are mapped to generated ids by default, so that they don't clash with blank nodes, that extractor generates. With [`preserve_bnode_labels`](RdfaParser::preserve_bnode_labels) option, labels are kept as they are, except for labels that could have been generated, which are suffixed with `d`, like rio parsers do.
+//!
+//! Extractor follows processing sequence of rdfa core, and html specific rules of html+rdfa, like typing of `@datetime` values. It doesn't perform vocabulary expansion, or `rdfa:copy` pattern expansion.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::parser::rdfa::RdfaParser;
+//! use sophia_api::{graph::Graph, parser::TripleParser, triple::stream::TripleSource};
+//! use sophia_inmem::graph::FastGraph;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let doc = r#"
+//!     <div vocab="http://schema.org/" typeof="Person" resource="http://example.org/alice">
+//!         <span property="name">Alice</span>
+//!     </div>
+//! "#;
+//! let parser = RdfaParser {
+//!     base: Some("http://example.org/page".into()),
+//!     ..Default::default()
+//! };
+//! let graph: FastGraph = parser.parse_str(doc).collect_triples()?;
+//! // `rdfa:usesVocabulary`, `rdf:type`, and `schema:name` statements.
+//! assert_eq!(graph.triples().count(), 3);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::io::{self, BufRead};
+
+use rio_api::{
+    model::{BlankNode, Literal, NamedNode, Subject, Term as RioTerm, Triple as RioTriple},
+    parser::{LineBytePosition, ParseError, TriplesParser},
+};
+use sophia_api::parser::TripleParser;
+use sophia_rio::parser::StrictRioSource;
+
+use self::processor::{Processor, Term, Triple};
+
+mod html;
+mod processor;
+
+/// Host language of rdfa documents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum HostLanguage {
+    /// Html, as per [html+rdfa](https://www.w3.org/TR/html-rdfa/).
+    #[default]
+    Html,
+    /// Xhtml, as per [xhtml+rdfa](https://www.w3.org/TR/xhtml-rdfa/). It's initial context has terms of xhtml vocabulary, like `next`, and `license`.
+    Xhtml,
+}
+
+/// An rdfa parser, that extracts triples from html, or xhtml documents.
+#[derive(Debug, Clone, Default)]
+pub struct RdfaParser {
+    /// Iri of the document, against which relative iris are resolved.
+    pub base: Option<String>,
+    pub host_language: HostLanguage,
+    /// If true, blank nodes are labeled with their labels in the document, instead of generated ids.
+    pub preserve_bnode_labels: bool,
+}
+
+impl<B: BufRead> TripleParser<B> for RdfaParser {
+    type Source = StrictRioSource<RdfaTriplesParser<B>, RdfaError>;
+
+    fn parse(&self, data: B) -> Self::Source {
+        StrictRioSource::Parser(RdfaTriplesParser {
+            data: Some(data),
+            parser: self.clone(),
+            triples: Vec::new().into_iter(),
+        })
+    }
+}
+
+/// A rio triples parser, that extracts triples from an rdfa document. It reads, and processes whole document on first step, and then emits a triple on each step.
+pub struct RdfaTriplesParser<R> {
+    data: Option<R>,
+    parser: RdfaParser,
+    triples: std::vec::IntoIter<Triple>,
+}
+
+impl<R: BufRead> RdfaTriplesParser<R> {
+    fn extract(&self, mut data: R) -> Result<Vec<Triple>, RdfaError> {
+        let mut bytes = Vec::new();
+        data.read_to_end(&mut bytes)?;
+        let doc = std::str::from_utf8(&bytes).map_err(|e| {
+            let valid = &bytes[..e.valid_up_to()];
+            let line = valid.iter().filter(|&&b| b == b'\n').count() as u64;
+            let line_start = valid.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+            RdfaError::InvalidUtf8(LineBytePosition::new(
+                line + 1,
+                (valid.len() - line_start) as u64 + 1,
+            ))
+        })?;
+        let document = html::parse_document(doc);
+        let base = self.parser.base.clone().unwrap_or_default();
+        Ok(Processor::new(doc, self.parser.host_language, base)
+            .with_preserve_bnode_labels(self.parser.preserve_bnode_labels)
+            .process(&document))
+    }
+}
+
+impl<R: BufRead> TriplesParser for RdfaTriplesParser<R> {
+    type Error = RdfaError;
+
+    fn parse_step<E: From<Self::Error>>(
+        &mut self,
+        on_triple: &mut impl FnMut(RioTriple<'_>) -> Result<(), E>,
+    ) -> Result<(), E> {
+        if let Some(data) = self.data.take() {
+            self.triples = self.extract(data)?.into_iter();
+            return Ok(());
+        }
+        match self.triples.next() {
+            Some(triple) => on_triple(rio_triple(&triple)),
+            None => Ok(()),
+        }
+    }
+
+    fn is_end(&self) -> bool {
+        self.data.is_none() && self.triples.len() == 0
+    }
+}
+
+fn rio_triple(triple: &Triple) -> RioTriple<'_> {
+    let subject = match rio_term(&triple.s) {
+        RioTerm::BlankNode(b) => Subject::BlankNode(b),
+        RioTerm::NamedNode(n) => Subject::NamedNode(n),
+        _ => unreachable!("rdfa subjects are never literals"),
+    };
+    RioTriple {
+        subject,
+        predicate: NamedNode { iri: &triple.p },
+        object: rio_term(&triple.o),
+    }
+}
+
+fn rio_term(term: &Term) -> RioTerm<'_> {
+    match term {
+        Term::Iri(iri) => RioTerm::NamedNode(NamedNode { iri }),
+        Term::BNode(id) => RioTerm::BlankNode(BlankNode { id }),
+        Term::Literal {
+            value,
+            datatype: Some(iri),
+            ..
+        } => RioTerm::Literal(Literal::Typed {
+            value,
+            datatype: NamedNode { iri },
+        }),
+        Term::Literal {
+            value,
+            lang: Some(language),
+            ..
+        } => RioTerm::Literal(Literal::LanguageTaggedString { value, language }),
+        Term::Literal { value, .. } => RioTerm::Literal(Literal::Simple { value }),
+    }
+}
+
+/// An error in extracting triples from an rdfa document.
+#[derive(Debug, thiserror::Error)]
+pub enum RdfaError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("document is not valid utf-8, at line {} byte {}", .0.line_number(), .0.byte_number())]
+    InvalidUtf8(LineBytePosition),
+}
+
+impl ParseError for RdfaError {
+    fn textual_position(&self) -> Option<LineBytePosition> {
+        match self {
+            Self::Io(_) => None,
+            Self::InvalidUtf8(position) => Some(*position),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use once_cell::sync::Lazy;
+    use sophia_api::{
+        graph::{isomorphic_graphs, Graph},
+        parser::TripleParser,
+        term::{TTerm, TermKind},
+        triple::{stream::TripleSource, Triple},
+    };
+    use sophia_inmem::graph::FastGraph;
+    use sophia_turtle::parser::turtle;
+    use test_case::test_case;
+
+    use super::{HostLanguage, RdfaParser};
+    use crate::tests::TRACING;
+
+    static BASE: &str = "http://example.org/page";
+
+    fn extract(host_language: HostLanguage, doc: &str) -> FastGraph {
+        RdfaParser {
+            base: Some(BASE.into()),
+            host_language,
+            ..Default::default()
+        }
+        .parse_str(doc)
+        .collect_triples()
+        .unwrap()
+    }
+
+    fn assert_extracts(host_language: HostLanguage, doc: &str, expected_ttl: &str) {
+        let g1 = extract(host_language, doc);
+        let g2: FastGraph = turtle::parse_str(&format!(
+            "@base <{}> .\n@prefix schema: <http://schema.org/> .\n@prefix foaf: <http://xmlns.com/foaf/0.1/> .\n@prefix rdfa: <http://www.w3.org/ns/rdfa#> .\n@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .\n@prefix rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> .\n{}",
+            BASE, expected_ttl
+        ))
+        .collect_triples()
+        .unwrap();
+        let dump = |g: &FastGraph| {
+            g.triples()
+                .map(|t| {
+                    let t = t.unwrap();
+                    format!("{} {} {}", t.s(), t.p(), t.o())
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        assert!(
+            isomorphic_graphs(&g1, &g2).unwrap(),
+            "extracted:\n{}\nexpected:\n{}",
+            dump(&g1),
+            dump(&g2)
+        );
+    }
+
+    #[test_case(
+        r##"<html><head><title>T</title></head><body>
+            <div vocab="http://schema.org/" typeof="Person" resource="#alice">
+                <span property="name">Alice</span>
+                <a property="url" href="http://alice.example/">home</a>
+                <div property="address" typeof="PostalAddress"><span property="addressLocality">Paris</span></div>
+            </div>
+        </body></html>"##,
+        r##"<> rdfa:usesVocabulary schema: .
+        <#alice> a schema:Person ; schema:name "Alice" ; schema:url <http://alice.example/> ;
+            schema:address [ a schema:PostalAddress ; schema:addressLocality "Paris" ] ."##;
+        "schema.org annotations"
+    )]
+    #[test_case(
+        r##"<div prefix="ex: http://e.org/ns#"><p about="ex:a" rel="ex:knows"><span about="ex:b"></span><span resource="ex:c"></span></p></div>"##,
+        "<http://e.org/ns#a> <http://e.org/ns#knows> <http://e.org/ns#b>, <http://e.org/ns#c> .";
+        "hanging rels"
+    )]
+    #[test_case(
+        r##"<div about="[_:x]" rev="foaf:made" resource="/doc"></div><p about="_:x" property="foaf:name" content="X" lang="en"></p>"##,
+        r##"</doc> foaf:made _:x . _:x foaf:name "X"@en ."##;
+        "reverse links, and bnode labels"
+    )]
+    #[test_case(
+        r##"<p about="/s" lang="fr"><span property="foaf:name">Chat</span><span property="foaf:age" datatype="xsd:integer">3</span><time property="schema:birthDate" datetime="2020-01-02">jan</time></p>"##,
+        r##"</s> foaf:name "Chat"@fr ; foaf:age "3"^^xsd:integer ; schema:birthDate "2020-01-02"^^xsd:date ."##;
+        "literals"
+    )]
+    #[test_case(
+        r##"<p about="/s"><span property="foaf:knows" inlist="" resource="/a"></span><span property="foaf:knows" inlist="" resource="/b"></span><span rel="foaf:made" inlist=""></span></p>"##,
+        r##"</s> foaf:knows ( </a> </b> ) ; foaf:made () ."##;
+        "lists"
+    )]
+    #[test_case(
+        r##"<p about="/s" property="foaf:bio" datatype="rdf:HTML">a <b>b</b></p>"##,
+        r##"</s> foaf:bio "a <b>b</b>"^^rdf:HTML ."##;
+        "html literals"
+    )]
+    #[test_case(
+        r##"<html><head><base href="http://other.org/"><link rel="stylesheet" href="s.css"><link rel="foaf:img" href="i.png"></head></html>"##,
+        r##"<http://other.org/> foaf:img <http://other.org/i.png> ."##;
+        "base element, and terms without vocabulary"
+    )]
+    pub fn extracts_rdfa_from_html(doc: &str, expected_ttl: &str) {
+        Lazy::force(&TRACING);
+        assert_extracts(HostLanguage::Html, doc, expected_ttl);
+    }
+
+    #[test]
+    pub fn xhtml_initial_context_has_xhtml_terms() {
+        Lazy::force(&TRACING);
+        let doc = r##"<html xmlns="http://www.w3.org/1999/xhtml"><head><link rel="next" href="/p2"/></head></html>"##;
+        assert_extracts(
+            HostLanguage::Xhtml,
+            doc,
+            "<> <http://www.w3.org/1999/xhtml/vocab#next> </p2> .",
+        );
+        assert_extracts(HostLanguage::Html, doc, "");
+    }
+
+    #[test]
+    pub fn invalid_utf8_will_error() {
+        Lazy::force(&TRACING);
+        let doc: &[u8] = b"<p>\n\xFF</p>";
+        let r: Result<FastGraph, _> = RdfaParser::default().parse(doc).collect_triples();
+        let e = r.err().expect("invalid utf-8 must error");
+        assert!(e.to_string().contains("line 2 byte 1"), "{}", e);
+    }
+
+    #[test]
+    pub fn blank_node_labels_do_not_clash_with_generated_ones() {
+        Lazy::force(&TRACING);
+        let g = extract(
+            HostLanguage::Html,
+            r##"<div about="_:b1" property="foaf:name" content="x"></div><div about="/s" typeof="foaf:Person"></div><div typeof="foaf:Agent"></div>"##,
+        );
+        let subjects: HashSet<String> = g
+            .triples()
+            .map(|t| t.unwrap().s().value().to_string())
+            .collect();
+        assert_eq!(subjects.len(), 3);
+    }
+
+    #[test]
+    pub fn preserves_blank_node_labels_when_configured() {
+        Lazy::force(&TRACING);
+        let g: FastGraph = RdfaParser {
+            preserve_bnode_labels: true,
+            ..Default::default()
+        }
+        .parse_str(
+            r##"<div about="_:alice" property="foaf:knows" resource="_:b1"></div><div about="/s" rel="foaf:knows"><span typeof="foaf:Agent"></span></div>"##,
+        )
+        .collect_triples()
+        .unwrap();
+        let mut bnodes = HashSet::new();
+        for t in g.triples() {
+            let t = t.unwrap();
+            for term in [t.s(), t.o()] {
+                if term.kind() == TermKind::BlankNode {
+                    bnodes.insert(term.value().to_string());
+                }
+            }
+        }
+        // Label `b1` could have been generated, hence it is suffixed.
+        assert!(bnodes.contains("alice"));
+        assert!(bnodes.contains("b1d"));
+        assert_eq!(bnodes.len(), 3);
+    }
+}
@@ -0,0 +1,802 @@
+//! This module implements rdfa 1.1 processing sequence, over html document trees.
+//!
+//! It follows [processing sequence](https://www.w3.org/TR/rdfa-core/#s_sequence) of rdfa core, along with html specific rules of [html+rdfa](https://www.w3.org/TR/html-rdfa/#extensions-to-rdfa-core-1-1-processing-rules). Vocabulary expansion, and `rdfa:copy` patterns are not performed.
+
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use sophia_iri::resolve::{IriParsed, Resolve};
+
+use super::{
+    html::{Element, Node},
+    HostLanguage,
+};
+
+const RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const XSD: &str = "http://www.w3.org/2001/XMLSchema#";
+const XHV: &str = "http://www.w3.org/1999/xhtml/vocab#";
+const RDFA_USES_VOCABULARY: &str = "http://www.w3.org/ns/rdfa#usesVocabulary";
+
+/// Prefixes of [rdfa 1.1 initial context](https://www.w3.org/2011/rdfa-context/rdfa-1.1).
+const INITIAL_PREFIXES: &[(&str, &str)] = &[
+    ("as", "https://www.w3.org/ns/activitystreams#"),
+    ("cc", "http://creativecommons.org/ns#"),
+    ("ctag", "http://commontag.org/ns#"),
+    ("dc", "http://purl.org/dc/terms/"),
+    ("dc11", "http://purl.org/dc/elements/1.1/"),
+    ("dcat", "http://www.w3.org/ns/dcat#"),
+    ("dcterms", "http://purl.org/dc/terms/"),
+    ("dqv", "http://www.w3.org/ns/dqv#"),
+    ("duv", "https://www.w3.org/ns/duv#"),
+    ("foaf", "http://xmlns.com/foaf/0.1/"),
+    ("gr", "http://purl.org/goodrelations/v1#"),
+    ("grddl", "http://www.w3.org/2003/g/data-view#"),
+    ("ical", "http://www.w3.org/2002/12/cal/icaltzd#"),
+    ("jsonld", "http://www.w3.org/ns/json-ld#"),
+    ("ldp", "http://www.w3.org/ns/ldp#"),
+    ("ma", "http://www.w3.org/ns/ma-ont#"),
+    ("oa", "http://www.w3.org/ns/oa#"),
+    ("odrl", "http://www.w3.org/ns/odrl/2/"),
+    ("og", "http://ogp.me/ns#"),
+    ("org", "http://www.w3.org/ns/org#"),
+    ("owl", "http://www.w3.org/2002/07/owl#"),
+    ("prov", "http://www.w3.org/ns/prov#"),
+    ("qb", "http://purl.org/linked-data/cube#"),
+    ("rdf", RDF),
+    ("rdfa", "http://www.w3.org/ns/rdfa#"),
+    ("rdfs", "http://www.w3.org/2000/01/rdf-schema#"),
+    ("rev", "http://purl.org/stuff/rev#"),
+    ("rif", "http://www.w3.org/2007/rif#"),
+    ("rr", "http://www.w3.org/ns/r2rml#"),
+    ("schema", "http://schema.org/"),
+    ("sd", "http://www.w3.org/ns/sparql-service-description#"),
+    ("sioc", "http://rdfs.org/sioc/ns#"),
+    ("skos", "http://www.w3.org/2004/02/skos/core#"),
+    ("skosxl", "http://www.w3.org/2008/05/skos-xl#"),
+    ("v", "http://rdf.data-vocabulary.org/#"),
+    ("vcard", "http://www.w3.org/2006/vcard/ns#"),
+    ("void", "http://rdfs.org/ns/void#"),
+    ("wdr", "http://www.w3.org/2007/05/powder#"),
+    ("wdrs", "http://www.w3.org/2007/05/powder-s#"),
+    ("xhv", XHV),
+    ("xml", "http://www.w3.org/XML/1998/namespace"),
+    ("xsd", XSD),
+];
+
+/// Terms of rdfa 1.1 initial context.
+const INITIAL_TERMS: &[(&str, &str)] = &[
+    (
+        "describedby",
+        "http://www.w3.org/2007/05/powder-s#describedby",
+    ),
+    ("license", "http://www.w3.org/1999/xhtml/vocab#license"),
+    ("role", "http://www.w3.org/1999/xhtml/vocab#role"),
+];
+
+/// Terms of [xhtml+rdfa 1.1 initial context](https://www.w3.org/2011/rdfa-context/xhtml-rdfa-1.1), in `xhv` vocabulary.
+const XHTML_TERMS: &[&str] = &[
+    "alternate",
+    "appendix",
+    "bookmark",
+    "cite",
+    "chapter",
+    "contents",
+    "copyright",
+    "glossary",
+    "help",
+    "icon",
+    "index",
+    "meta",
+    "next",
+    "p3pv1",
+    "prev",
+    "previous",
+    "section",
+    "start",
+    "stylesheet",
+    "subsection",
+    "top",
+    "up",
+];
+
+/// An rdf term, produced by rdfa processing.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Term {
+    Iri(String),
+    BNode(String),
+    Literal {
+        value: String,
+        datatype: Option<String>,
+        lang: Option<String>,
+    },
+}
+
+/// A triple, produced by rdfa processing.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Triple {
+    pub s: Term,
+    pub p: String,
+    pub o: Term,
+}
+
+/// Lists of an element, keyed by their predicate, in order of their creation.
+type ListMapping = Rc<RefCell<Vec<(String, Vec<Term>)>>>;
+
+fn list_entry<'a>(lists: &'a mut Vec<(String, Vec<Term>)>, predicate: &str) -> &'a mut Vec<Term> {
+    let i = match lists.iter().position(|(p, _)| p == predicate) {
+        Some(i) => i,
+        None => {
+            lists.push((predicate.to_owned(), Vec::new()));
+            lists.len() - 1
+        }
+    };
+    &mut lists[i].1
+}
+
+/// Direction of an incomplete triple.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Direction {
+    Forward,
+    Reverse,
+    List,
+}
+
+/// A triple, that is pending discovery of it's object, or subject.
+#[derive(Debug, Clone)]
+struct IncompleteTriple {
+    predicate: String,
+    direction: Direction,
+}
+
+/// Evaluation context of rdfa processing.
+#[derive(Debug, Clone)]
+struct Context {
+    parent_subject: Option<Term>,
+    parent_object: Option<Term>,
+    incomplete_triples: Vec<IncompleteTriple>,
+    list_mapping: ListMapping,
+    lang: Option<String>,
+    prefixes: Rc<HashMap<String, String>>,
+    vocab: Option<String>,
+}
+
+/// A processor, that extracts triples from an html document tree.
+pub struct Processor<'d> {
+    doc: &'d str,
+    host_language: HostLanguage,
+    base: String,
+    terms: HashMap<String, String>,
+    bnode_labels: HashMap<String, String>,
+    bnode_count: usize,
+    preserve_bnode_labels: bool,
+    triples: Vec<Triple>,
+}
+
+impl<'d> Processor<'d> {
+    /// Creates a new processor, for given document source, and it's base iri. If base iri is empty, relative iris are not resolved.
+    pub fn new(doc: &'d str, host_language: HostLanguage, base: String) -> Self {
+        let mut terms: HashMap<String, String> = INITIAL_TERMS
+            .iter()
+            .map(|(t, iri)| (t.to_string(), iri.to_string()))
+            .collect();
+        if host_language == HostLanguage::Xhtml {
+            terms.extend(
+                XHTML_TERMS
+                    .iter()
+                    .map(|t| (t.to_string(), format!("{}{}", XHV, t))),
+            );
+        }
+        Self {
+            doc,
+            host_language,
+            base,
+            terms,
+            bnode_labels: HashMap::new(),
+            bnode_count: 0,
+            preserve_bnode_labels: false,
+            triples: Vec::new(),
+        }
+    }
+
+    /// Makes this processor keep blank node labels of the document, instead of mapping them to generated ids.
+    pub fn with_preserve_bnode_labels(mut self, preserve_bnode_labels: bool) -> Self {
+        self.preserve_bnode_labels = preserve_bnode_labels;
+        self
+    }
+
+    /// Processes given document tree, as returned by [`parse_document`](super::html::parse_document), and returns extracted triples.
+    pub fn process(mut self, document: &Element) -> Vec<Triple> {
+        if let Some(href) = find_base_href(document) {
+            self.base = self.resolve(href);
+        }
+        let base = Term::Iri(self.base.clone());
+        let context = Context {
+            parent_subject: Some(base),
+            parent_object: None,
+            incomplete_triples: Vec::new(),
+            list_mapping: Rc::default(),
+            lang: None,
+            prefixes: Rc::new(
+                INITIAL_PREFIXES
+                    .iter()
+                    .map(|(p, iri)| (p.to_string(), iri.to_string()))
+                    .collect(),
+            ),
+            vocab: None,
+        };
+        for child in &document.children {
+            if let Node::Element(element) = child {
+                self.process_element(element, &context, true);
+            }
+        }
+        self.triples
+    }
+
+    fn emit(&mut self, s: Term, p: String, o: Term) {
+        self.triples.push(Triple { s, p, o });
+    }
+
+    fn new_bnode(&mut self) -> Term {
+        self.bnode_count += 1;
+        Term::BNode(format!("b{}", self.bnode_count))
+    }
+
+    /// Returns blank node for given label in the document. Labels are mapped to generated ids, so that they don't clash with generated blank nodes. If labels are preserved, they are kept as they are, except for those that could have been generated, which are suffixed with `d`. Empty label is always mapped to a generated id.
+    fn labeled_bnode(&mut self, label: &str) -> Term {
+        if self.preserve_bnode_labels && !label.is_empty() {
+            let rest = label.strip_prefix('b').unwrap_or_default();
+            let count = rest.trim_end_matches('d');
+            if !count.is_empty() && count.bytes().all(|b| b.is_ascii_digit()) {
+                return Term::BNode(format!("{}d", label));
+            }
+            return Term::BNode(label.to_owned());
+        }
+        if let Some(id) = self.bnode_labels.get(label) {
+            return Term::BNode(id.clone());
+        }
+        let bnode = self.new_bnode();
+        if let Term::BNode(id) = &bnode {
+            self.bnode_labels.insert(label.to_owned(), id.clone());
+        }
+        bnode
+    }
+
+    fn resolve(&self, iri: &str) -> String {
+        if self.base.is_empty() {
+            return iri.to_owned();
+        }
+        IriParsed::new(&self.base)
+            .ok()
+            .and_then(|base| {
+                let resolved: Result<_, _> = base.resolve(iri);
+                resolved.ok().map(|iri| iri.to_string())
+            })
+            .unwrap_or_else(|| iri.to_owned())
+    }
+
+    /// Expands given curie, if it's prefix is bound, or if it is a blank node.
+    fn expand_curie(&mut self, context: &Context, curie: &str) -> Option<Term> {
+        if let Some(label) = curie.strip_prefix("_:") {
+            return Some(self.labeled_bnode(label));
+        }
+        let (prefix, reference) = curie.split_once(':')?;
+        if reference.starts_with("//") {
+            return None;
+        }
+        if prefix.is_empty() {
+            return Some(Term::Iri(format!("{}{}", XHV, reference)));
+        }
+        context
+            .prefixes
+            .get(&prefix.to_ascii_lowercase())
+            .map(|ns| Term::Iri(format!("{}{}", ns, reference)))
+    }
+
+    /// Resolves value of `@about`, or `@resource`, which can be a safe curie, a curie, or an iri.
+    fn resource(&mut self, context: &Context, value: &str) -> Option<Term> {
+        if let Some(safe_curie) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            return self.expand_curie(context, safe_curie);
+        }
+        match self.expand_curie(context, value) {
+            Some(term) => Some(term),
+            None => Some(Term::Iri(self.resolve(value))),
+        }
+    }
+
+    /// Resolves value of `@typeof`, `@property`, `@rel`, `@rev`, or `@datatype`, which are space separated terms, curies, or absolute iris. Values that can't be resolved are dropped.
+    fn iris(&mut self, context: &Context, value: &str) -> Vec<String> {
+        value
+            .split_ascii_whitespace()
+            .filter_map(|token| self.iri(context, token))
+            .collect()
+    }
+
+    fn iri(&mut self, context: &Context, token: &str) -> Option<String> {
+        if !token.contains(':') {
+            return match &context.vocab {
+                Some(vocab) => Some(format!("{}{}", vocab, token)),
+                None => self
+                    .terms
+                    .get(token)
+                    .or_else(|| self.terms.get(&token.to_ascii_lowercase()))
+                    .cloned(),
+            };
+        }
+        if token.starts_with("_:") {
+            return None;
+        }
+        match self.expand_curie(context, token) {
+            Some(Term::Iri(iri)) => Some(iri),
+            _ if is_absolute_iri(token) => Some(token.to_owned()),
+            _ => None,
+        }
+    }
+
+    /// Returns value of `@rel`, or `@rev` attribute of given element. In html, if element has `@property`, terms are dropped from them, and attributes with only terms are treated as absent.
+    fn link_attr<'e>(&self, element: &'e Element, name: &str) -> Option<&'e str> {
+        let value = element.attr(name)?;
+        if self.host_language == HostLanguage::Html
+            && element.has_attr("property")
+            && value.split_ascii_whitespace().all(|t| !t.contains(':'))
+        {
+            return None;
+        }
+        Some(value)
+    }
+
+    fn link_iris(&mut self, context: &Context, element: &Element, value: &str) -> Vec<String> {
+        let drop_terms = self.host_language == HostLanguage::Html && element.has_attr("property");
+        value
+            .split_ascii_whitespace()
+            .filter(|t| !drop_terms || t.contains(':'))
+            .filter_map(|t| self.iri(context, t))
+            .collect()
+    }
+
+    fn lang(&self, element: &Element) -> Option<Option<String>> {
+        element
+            .attr("xml:lang")
+            .or_else(|| element.attr("lang"))
+            .map(|lang| Some(lang.to_owned()).filter(|l| !l.is_empty()))
+    }
+
+    /// Returns prefix mappings in scope of given element.
+    fn prefixes(&self, context: &Context, element: &Element) -> Rc<HashMap<String, String>> {
+        let xmlns: Vec<_> = element
+            .attrs
+            .iter()
+            .filter_map(|(name, iri)| Some((name.strip_prefix("xmlns:")?, iri.as_str())))
+            .collect();
+        let prefix_attr = element.attr("prefix");
+        if xmlns.is_empty() && prefix_attr.is_none() {
+            return context.prefixes.clone();
+        }
+        let mut prefixes = (*context.prefixes).clone();
+        for (prefix, iri) in xmlns {
+            if prefix != "_" {
+                prefixes.insert(prefix.to_ascii_lowercase(), iri.to_owned());
+            }
+        }
+        let mut tokens = prefix_attr.unwrap_or_default().split_ascii_whitespace();
+        while let Some(token) = tokens.next() {
+            let prefix = match token.strip_suffix(':') {
+                Some(prefix) => prefix,
+                None => continue,
+            };
+            match tokens.next() {
+                Some(iri) if prefix != "_" && !prefix.is_empty() => {
+                    prefixes.insert(prefix.to_ascii_lowercase(), iri.to_owned());
+                }
+                Some(_) => {}
+                None => break,
+            }
+        }
+        Rc::new(prefixes)
+    }
+
+    fn process_element(&mut self, element: &Element, context: &Context, is_root: bool) {
+        // Step 2.
+        let vocab = match element.attr("vocab") {
+            Some("") => None,
+            Some(vocab) => {
+                let vocab = self.resolve(vocab);
+                self.emit(
+                    Term::Iri(self.base.clone()),
+                    RDFA_USES_VOCABULARY.to_owned(),
+                    Term::Iri(vocab.clone()),
+                );
+                Some(vocab)
+            }
+            None => context.vocab.clone(),
+        };
+        // Steps 3, and 4.
+        let local = Context {
+            prefixes: self.prefixes(context, element),
+            lang: self.lang(element).unwrap_or_else(|| context.lang.clone()),
+            vocab,
+            ..context.clone()
+        };
+
+        let rel = self.link_attr(element, "rel");
+        let rev = self.link_attr(element, "rev");
+        let property = element.attr("property");
+        let type_of = element.attr("typeof");
+        // Attributes, that don't resolve to a resource are treated as absent.
+        let about = match element.attr("about") {
+            Some(about) => self.resource(&local, about),
+            None => None,
+        };
+        let has_about = about.is_some();
+        let is_head_or_body = matches!(element.name.as_str(), "head" | "body");
+        let resource = match element.attr("resource") {
+            Some(resource) => self.resource(&local, resource),
+            None => None,
+        }
+        .or_else(|| element.attr("href").map(|v| Term::Iri(self.resolve(v))))
+        .or_else(|| element.attr("src").map(|v| Term::Iri(self.resolve(v))));
+
+        let mut skip = false;
+        let mut new_subject;
+        let mut current_object = None;
+        let mut typed_resource = None;
+
+        if rel.is_none() && rev.is_none() {
+            // Step 5.
+            if property.is_some() && !element.has_attr("content") && !element.has_attr("datatype") {
+                new_subject = if has_about {
+                    about.clone()
+                } else if is_root {
+                    Some(Term::Iri(self.base.clone()))
+                } else {
+                    context.parent_object.clone()
+                };
+                if type_of.is_some() {
+                    typed_resource = if has_about {
+                        about
+                    } else if is_root {
+                        Some(Term::Iri(self.base.clone()))
+                    } else {
+                        Some(match resource.clone() {
+                            Some(resource) => resource,
+                            None => self.new_bnode(),
+                        })
+                    };
+                    current_object = typed_resource.clone();
+                }
+            } else {
+                new_subject = if has_about { about } else { resource.clone() };
+                if new_subject.is_none() {
+                    if is_root {
+                        new_subject = Some(Term::Iri(self.base.clone()));
+                    } else if is_head_or_body {
+                        new_subject = context.parent_object.clone();
+                    } else if type_of.is_some() {
+                        new_subject = Some(self.new_bnode());
+                    } else if context.parent_object.is_some() {
+                        new_subject = context.parent_object.clone();
+                        skip = property.is_none();
+                    }
+                }
+                if type_of.is_some() {
+                    typed_resource = new_subject.clone();
+                }
+            }
+        } else {
+            // Step 6.
+            new_subject = if has_about {
+                about
+            } else if is_root {
+                Some(Term::Iri(self.base.clone()))
+            } else {
+                context.parent_object.clone()
+            };
+            if type_of.is_some() && has_about {
+                typed_resource = new_subject.clone();
+            }
+            current_object = resource.clone();
+            if current_object.is_none() && type_of.is_some() && !has_about {
+                current_object = Some(self.new_bnode());
+            }
+            if type_of.is_some() && !has_about {
+                typed_resource = current_object.clone();
+            }
+        }
+
+        // Step 7.
+        if let (Some(typed_resource), Some(type_of)) = (&typed_resource, type_of) {
+            for type_ in self.iris(&local, type_of) {
+                self.emit(
+                    typed_resource.clone(),
+                    format!("{}type", RDF),
+                    Term::Iri(type_),
+                );
+            }
+        }
+
+        // Step 8.
+        let list_mapping = match &new_subject {
+            Some(subject) if Some(subject) != context.parent_object.as_ref() => Rc::default(),
+            _ => context.list_mapping.clone(),
+        };
+        let inlist = element.has_attr("inlist");
+        let rels = match rel {
+            Some(rel) => self.link_iris(&local, element, rel),
+            None => Vec::new(),
+        };
+        let revs = match rev {
+            Some(rev) => self.link_iris(&local, element, rev),
+            None => Vec::new(),
+        };
+
+        let mut incomplete_triples = Vec::new();
+        match (&current_object, &new_subject) {
+            // Step 9.
+            (Some(object), Some(subject)) => {
+                for predicate in rels {
+                    if inlist {
+                        list_entry(&mut list_mapping.borrow_mut(), &predicate).push(object.clone());
+                    } else {
+                        self.emit(subject.clone(), predicate, object.clone());
+                    }
+                }
+                for predicate in revs {
+                    self.emit(object.clone(), predicate, subject.clone());
+                }
+            }
+            // Step 10.
+            (None, _) if !rels.is_empty() || !revs.is_empty() => {
+                for predicate in rels {
+                    let direction = if inlist {
+                        list_entry(&mut list_mapping.borrow_mut(), &predicate);
+                        Direction::List
+                    } else {
+                        Direction::Forward
+                    };
+                    incomplete_triples.push(IncompleteTriple {
+                        predicate,
+                        direction,
+                    });
+                }
+                for predicate in revs {
+                    incomplete_triples.push(IncompleteTriple {
+                        predicate,
+                        direction: Direction::Reverse,
+                    });
+                }
+                current_object = Some(self.new_bnode());
+            }
+            _ => {}
+        }
+
+        // Step 11. Statements of properties are emitted after those of descendants, when their content is complete.
+        let mut property_triples = Vec::new();
+        if let (Some(property), Some(subject)) = (property, &new_subject) {
+            let linked_resource = match (rel, rev) {
+                (None, None) => resource,
+                _ => None,
+            };
+            let typed_object = typed_resource.filter(|_| !has_about);
+            let value = self.property_value(&local, element, linked_resource, typed_object);
+            for predicate in self.iris(&local, property) {
+                if inlist {
+                    list_entry(&mut list_mapping.borrow_mut(), &predicate).push(value.clone());
+                } else {
+                    property_triples.push((subject.clone(), predicate, value.clone()));
+                }
+            }
+        }
+
+        // Step 12.
+        if let (false, Some(subject)) = (skip, &new_subject) {
+            for incomplete in &context.incomplete_triples {
+                match incomplete.direction {
+                    Direction::List => {
+                        list_entry(
+                            &mut context.list_mapping.borrow_mut(),
+                            &incomplete.predicate,
+                        )
+                        .push(subject.clone());
+                    }
+                    Direction::Forward => {
+                        if let Some(parent_subject) = &context.parent_subject {
+                            self.emit(
+                                parent_subject.clone(),
+                                incomplete.predicate.clone(),
+                                subject.clone(),
+                            );
+                        }
+                    }
+                    Direction::Reverse => {
+                        if let Some(parent_subject) = &context.parent_subject {
+                            self.emit(
+                                subject.clone(),
+                                incomplete.predicate.clone(),
+                                parent_subject.clone(),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        // Step 13.
+        let child_context = if skip {
+            Context {
+                prefixes: local.prefixes,
+                lang: local.lang,
+                vocab: local.vocab,
+                ..context.clone()
+            }
+        } else {
+            let parent_subject = new_subject
+                .clone()
+                .or_else(|| context.parent_subject.clone());
+            Context {
+                parent_object: current_object
+                    .clone()
+                    .or_else(|| new_subject.clone())
+                    .or_else(|| parent_subject.clone()),
+                parent_subject,
+                incomplete_triples,
+                list_mapping: list_mapping.clone(),
+                ..local
+            }
+        };
+        for child in &element.children {
+            if let Node::Element(child) = child {
+                self.process_element(child, &child_context, false);
+            }
+        }
+        for (s, p, o) in property_triples {
+            self.emit(s, p, o);
+        }
+
+        // Step 14.
+        if !Rc::ptr_eq(&list_mapping, &context.list_mapping) {
+            if let Some(subject) = new_subject {
+                let lists = std::mem::take(&mut *list_mapping.borrow_mut());
+                for (predicate, items) in lists {
+                    let head = self.emit_list(items);
+                    self.emit(subject.clone(), predicate, head);
+                }
+            }
+        }
+    }
+
+    /// Emits statements of an rdf list of given items, and returns it's head.
+    fn emit_list(&mut self, items: Vec<Term>) -> Term {
+        let nodes: Vec<_> = items.iter().map(|_| self.new_bnode()).collect();
+        for (i, item) in items.into_iter().enumerate() {
+            self.emit(nodes[i].clone(), format!("{}first", RDF), item);
+            let rest = nodes
+                .get(i + 1)
+                .cloned()
+                .unwrap_or_else(|| Term::Iri(format!("{}nil", RDF)));
+            self.emit(nodes[i].clone(), format!("{}rest", RDF), rest);
+        }
+        nodes
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| Term::Iri(format!("{}nil", RDF)))
+    }
+
+    /// Returns current property value of given element. `linked_resource` is resource of the element, if it has no `@rel`, or `@rev`. `typed_object` is it's typed resource, if it has no `@about`.
+    fn property_value(
+        &mut self,
+        context: &Context,
+        element: &Element,
+        linked_resource: Option<Term>,
+        typed_object: Option<Term>,
+    ) -> Term {
+        let content = element.attr("content");
+        let datatype = element
+            .attr("datatype")
+            .map(|d| self.iri(context, d.trim()).unwrap_or_default());
+        let lang = context.lang.clone();
+        match datatype {
+            Some(datatype)
+                if datatype == format!("{}XMLLiteral", RDF)
+                    || datatype == format!("{}HTML", RDF) =>
+            {
+                Term::Literal {
+                    value: self.doc[element.content.clone()].to_owned(),
+                    datatype: Some(datatype),
+                    lang: None,
+                }
+            }
+            Some(datatype) if !datatype.is_empty() => Term::Literal {
+                value: content.map_or_else(|| element.text_content(), String::from),
+                datatype: Some(datatype),
+                lang: None,
+            },
+            Some(_) => Term::Literal {
+                value: content.map_or_else(|| element.text_content(), String::from),
+                datatype: None,
+                lang,
+            },
+            None if content.is_some() => Term::Literal {
+                value: content.unwrap_or_default().to_owned(),
+                datatype: None,
+                lang,
+            },
+            None if self.host_language == HostLanguage::Html
+                && (element.has_attr("datetime") || element.name == "time") =>
+            {
+                let value = element
+                    .attr("datetime")
+                    .map_or_else(|| element.text_content(), String::from);
+                let datatype = temporal_datatype(&value).map(|d| format!("{}{}", XSD, d));
+                let lang = if datatype.is_some() { None } else { lang };
+                Term::Literal {
+                    value,
+                    datatype,
+                    lang,
+                }
+            }
+            None => match linked_resource.or(typed_object) {
+                Some(resource) => resource,
+                None => Term::Literal {
+                    value: element.text_content(),
+                    datatype: None,
+                    lang,
+                },
+            },
+        }
+    }
+}
+
+/// Returns `href` of first `base` element in given document tree, if any.
+fn find_base_href(element: &Element) -> Option<&str> {
+    if element.name == "base" {
+        if let Some(href) = element.attr("href") {
+            return Some(href);
+        }
+    }
+    element.children.iter().find_map(|child| match child {
+        Node::Element(e) => find_base_href(e),
+        Node::Text(_) => None,
+    })
+}
+
+/// Returns true if given value starts with an iri scheme.
+fn is_absolute_iri(value: &str) -> bool {
+    match value.split_once(':') {
+        Some((scheme, _)) => {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => false,
+    }
+}
+
+/// Returns local name of xsd datatype of given temporal value, as per html+rdfa rules for `@datetime`.
+fn temporal_datatype(value: &str) -> Option<&'static str> {
+    let b = value.as_bytes();
+    let digits = |range: std::ops::Range<usize>| {
+        b.get(range)
+            .is_some_and(|d| d.iter().all(u8::is_ascii_digit))
+    };
+    let is_date = b.len() >= 10
+        && digits(0..4)
+        && b[4] == b'-'
+        && digits(5..7)
+        && b[7] == b'-'
+        && digits(8..10);
+    let is_time = |t: &[u8]| t.len() >= 5 && t[..2].iter().all(u8::is_ascii_digit) && t[2] == b':';
+    if value.starts_with('P') || value.starts_with("-P") {
+        Some("duration")
+    } else if is_date && b.len() > 10 && b[10] == b'T' && is_time(&b[11..]) {
+        Some("dateTime")
+    } else if is_date {
+        Some("date")
+    } else if is_time(b) {
+        Some("time")
+    } else if b.len() == 7 && digits(0..4) && b[4] == b'-' && digits(5..7) {
+        Some("gYearMonth")
+    } else if b.len() == 4 && digits(0..4) {
+        Some("gYear")
+    } else {
+        None
+    }
+}
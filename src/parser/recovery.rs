@@ -0,0 +1,85 @@
+//! This module defines types to report statements, that are skipped while parsing in keep-going mode. See [`DynSynQuadSource::try_for_each_quad_keep_going`](super::quads::source::DynSynQuadSource::try_for_each_quad_keep_going), and [`DynSynTripleSource::try_for_each_triple_keep_going`](super::triples::source::DynSynTripleSource::try_for_each_triple_keep_going).
+//!
+//! Parsers can also be made lenient with a [`ParserConfig`] in factory's config map, or with `set_lenient` method of parsers. Sources of lenient parsers skip malformed statements in every traversal, and record them in a [`SkipSummary`], accessible through their `skip_summary` method after parsing.
+//!
+//! Keep-going mode is supported for line-based syntaxes, [`n-quads`](crate::syntax::N_QUADS), and [`n-triples`](crate::syntax::N_TRIPLES), as their parsers can resume at next line after a malformed statement.
+
+use super::{errors::DynSynParseError, iri_validation::IriValidation};
+
+/// A statement, that is skipped as it couldn't be parsed.
+#[derive(Debug)]
+pub struct SkippedStatement {
+    /// Line number of the statement, starting from 1, if known.
+    pub line_number: Option<u64>,
+    /// Error in parsing the statement.
+    pub error: DynSynParseError,
+}
+
+impl From<DynSynParseError> for SkippedStatement {
+    fn from(error: DynSynParseError) -> Self {
+        Self {
+            line_number: error.line_number(),
+            error,
+        }
+    }
+}
+
+/// Summary of statements, that are skipped in keep-going mode.
+#[derive(Debug, Default)]
+pub struct SkipSummary {
+    pub skipped: Vec<SkippedStatement>,
+}
+
+impl SkipSummary {
+    /// Returns true, if no statement is skipped.
+    pub fn is_clean(&self) -> bool {
+        self.skipped.is_empty()
+    }
+}
+
+/// Configuration of parsers, that can be put in config map of parser factories.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParserConfig {
+    /// If true, sources skip malformed statements in syntaxes that support keep-going mode, instead of failing on them.
+    pub lenient: bool,
+    /// If true, turtle family parsers read quoted triples of rdf-star documents as blank nodes, and assert their rdf reification statements. Off by default, as reification statements are not part of document. See [`rdf_star`](super::rdf_star) module.
+    pub reify_quoted_triples: bool,
+    /// Mode of validating iris of n-triples, and n-quads documents. See [`iri_validation`](super::iri_validation) module.
+    pub iri_validation: IriValidation,
+    /// If true, sources hand out blank nodes with labels of the document, in syntaxes whose parsers would otherwise relabel them, and don't skolemize them, even if a [`Skolemization`](super::skolem::Skolemization) is configured. See [`DynSynSerializerConfig`](crate::serializer::config::DynSynSerializerConfig) for serializer side of preserving labels.
+    pub preserve_bnode_labels: bool,
+}
+
+impl ParserConfig {
+    /// Returns config for lenient parsers.
+    pub fn lenient() -> Self {
+        Self {
+            lenient: true,
+            ..Default::default()
+        }
+    }
+
+    /// Returns config for parsers, that reify quoted triples.
+    pub fn reify_quoted_triples() -> Self {
+        Self {
+            reify_quoted_triples: true,
+            ..Default::default()
+        }
+    }
+
+    /// Returns config for parsers, that validate iris strictly.
+    pub fn strict_iris() -> Self {
+        Self {
+            iri_validation: IriValidation::Strict,
+            ..Default::default()
+        }
+    }
+
+    /// Returns config for parsers, that preserve blank node labels of documents.
+    pub fn preserve_bnode_labels() -> Self {
+        Self {
+            preserve_bnode_labels: true,
+            ..Default::default()
+        }
+    }
+}
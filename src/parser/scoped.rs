@@ -0,0 +1,145 @@
+//! This module defines scoped statements, that dynsyn sources hand out to visitors of their `for_each_triple_scoped`, and `for_each_quad_scoped` methods. Terms of scoped statements are borrowed from underlying parser, and are valid only for duration of the callback, like in rio. Hence no term is allocated per statement, which is a large win for counting, filtering, and streaming conversions, that never store terms.
+//!
+//! Parse limits, policies, stats, and progress apply to scoped statements, like to statements emitted through [`TripleSource`](sophia_api::triple::stream::TripleSource), and [`QuadSource`](sophia_api::quad::stream::QuadSource) apis. Terms are copied only if they have to be rewritten, like by a configured [`DynIriResolver`](crate::iri_resolver::DynIriResolver), or a [`Skolemization`](super::skolem::Skolemization). Copy terms, that are to be stored, with [`OwnedTriple`](crate::model::OwnedTriple), or [`OwnedQuad`](crate::model::OwnedQuad).
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{parser::triples::DynSynTripleParserFactory, syntax};
+//! use sophia_api::{parser::TripleParser, term::TermKind};
+//! use sophia_term::BoxTerm;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let doc = r#"
+//!     <http://ex.org/alice> <http://ex.org/name> "Alice" .
+//!     <http://ex.org/alice> <http://ex.org/knows> <http://ex.org/bob> .
+//! "#;
+//! let parser = DynSynTripleParserFactory::default()
+//!     .try_new_parser::<BoxTerm>(syntax::N_TRIPLES, None, None)?;
+//!
+//! let mut literals = 0;
+//! parser.parse_str(doc).for_each_triple_scoped(|[_, _, o]| {
+//!     if o.kind() == TermKind::Literal {
+//!         literals += 1;
+//!     }
+//! })?;
+//! assert_eq!(literals, 1);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use sophia_api::term::TTerm;
+use sophia_term::BoxTerm;
+
+use crate::iri_resolver::DynIriResolver;
+
+use super::{
+    iri_policy::IriPolicyGuard,
+    limits::{DocumentLimitGuard, SessionBudget},
+    literal_policy::LiteralPolicyGuard,
+    progress::ProgressTracker,
+    skolem::Skolemizer,
+    stats::StatsCollector,
+};
+
+/// A triple, whose terms are borrowed for duration of a callback.
+pub type ScopedTriple<'a> = [&'a dyn TTerm; 3];
+
+/// A quad, whose terms are borrowed for duration of a callback. Graph name is `None` for quads in default graph.
+pub type ScopedQuad<'a> = ([&'a dyn TTerm; 3], Option<&'a dyn TTerm>);
+
+/// Returns given term as a scoped term. If it has to be rewritten by `skolemizer`, or `resolver`, it is copied into `buf`, and rewritten copy is returned.
+pub(crate) fn scoped_term<'t, U>(
+    skolemizer: Option<&Skolemizer>,
+    resolver: Option<&DynIriResolver>,
+    term: &'t U,
+    buf: &'t mut Option<BoxTerm>,
+) -> &'t dyn TTerm
+where
+    U: TTerm + ?Sized,
+{
+    if skolemizer.is_none() && resolver.is_none() {
+        return term.as_dyn();
+    }
+    buf.insert(Skolemizer::copied(skolemizer, resolver, term))
+        .as_dyn()
+}
+
+/// Default max number of statements, that a source pulls from it's underlying parser in one `try_for_some_*` call.
+pub const DEFAULT_BATCH_SIZE: usize = 64;
+
+/// Per statement gates of a source: parse limits, and policies, that decide whether a statement is emitted, and stats, and progress, that record emitted statements.
+pub(crate) struct StatementGates<'s> {
+    pub(crate) budget: Option<&'s SessionBudget>,
+    pub(crate) iri_policy_guard: &'s mut Option<IriPolicyGuard>,
+    pub(crate) literal_policy_guard: &'s mut Option<LiteralPolicyGuard>,
+    pub(crate) document_limit_guard: &'s mut Option<DocumentLimitGuard>,
+    pub(crate) stats: &'s mut Option<StatsCollector>,
+    pub(crate) progress_tracker: &'s mut Option<ProgressTracker>,
+}
+
+impl StatementGates<'_> {
+    /// Returns true, if a limit, or policy has tripped, such that source must stop, and raise an error.
+    pub(crate) fn tripped(&self) -> bool {
+        matches!(self.budget, Some(budget) if budget.exhausted().is_some())
+            || matches!(self.document_limit_guard.as_ref(), Some(guard) if guard.exceeded().is_some())
+            || matches!(self.iri_policy_guard.as_ref(), Some(guard) if guard.must_fail())
+            || matches!(self.literal_policy_guard.as_ref(), Some(guard) if guard.must_fail())
+    }
+
+    /// Returns true, if triple with given terms is to be emitted, after recording it.
+    pub(crate) fn admit_triple<U>(&mut self, [s, p, o]: [&U; 3]) -> bool
+    where
+        U: TTerm + ?Sized,
+    {
+        if let Some(guard) = self.iri_policy_guard.as_mut() {
+            if !guard.admit([s, p, o]) {
+                return false;
+            }
+        }
+        self.admit_statement([s, p, o], None)
+    }
+
+    /// Returns true, if quad with given terms is to be emitted, after recording it.
+    pub(crate) fn admit_quad<U>(&mut self, [s, p, o]: [&U; 3], g: Option<&U>) -> bool
+    where
+        U: TTerm + ?Sized,
+    {
+        if let Some(guard) = self.iri_policy_guard.as_mut() {
+            if !guard.admit([s, p, o].into_iter().chain(g)) {
+                return false;
+            }
+        }
+        self.admit_statement([s, p, o], Some(g))
+    }
+
+    fn admit_statement<U>(&mut self, [s, p, o]: [&U; 3], g: Option<Option<&U>>) -> bool
+    where
+        U: TTerm + ?Sized,
+    {
+        if let Some(guard) = self.literal_policy_guard.as_mut() {
+            if !guard.admit(o) {
+                return false;
+            }
+        }
+        if let Some(guard) = self.document_limit_guard.as_mut() {
+            if !guard.admit(o) {
+                return false;
+            }
+        }
+        if matches!(self.budget, Some(budget) if !budget.admit_statement()) {
+            return false;
+        }
+        if let Some(stats) = self.stats.as_mut() {
+            stats.record_triple(s, p, o);
+            if let Some(g) = g {
+                stats.record_graph(g);
+            }
+        }
+        if let Some(tracker) = self.progress_tracker.as_mut() {
+            tracker.on_statement();
+        }
+        true
+    }
+}
@@ -0,0 +1,155 @@
+//! This module defines skolemization config, that parsers can be configured with, to replace blank nodes with well-known skolem iris, as statements stream through. Services that need stable identities of nodes across requests can then store, and reference parsed nodes, without post-processing whole graphs.
+//!
+//! Skolemization is configured by inserting a [`Skolemization`] into `parser_config_map` of parser factories. Each blank node is replaced with an iri of form `{authority}/.well-known/genid/{uuid}`, as recommended by [rdf 1.1 concepts](https://www.w3.org/TR/rdf11-concepts/#section-skolemization). All occurrences of a blank node label in a document get same iri, while same label in different documents, or in different parses of same document get different iris. Skolem iris are not rewritten by configured [`DynIriResolver`], but are checked against configured [`IriPolicy`](super::iri_policy::IriPolicy), like other iris.
+//!
+//! Graph names are skolemized too. But [`GraphSelector`](super::graph_selector::GraphSelector)s of triple parsers match graph names as they are in the document.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{parser::{skolem::Skolemization, triples::DynSynTripleParserFactory}, syntax};
+//! use sophia_api::{graph::Graph, parser::TripleParser, term::{TTerm, TermKind}, triple::{stream::TripleSource, Triple}};
+//! use sophia_inmem::graph::FastGraph;
+//! use sophia_term::BoxTerm;
+//! use type_map::concurrent::TypeMap;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut config_map = TypeMap::new();
+//! config_map.insert(Skolemization::try_new("https://data.example")?);
+//! let parser = DynSynTripleParserFactory::new(Some(config_map))
+//!     .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)?;
+//! let graph: FastGraph = parser
+//!     .parse_str("_:alice <http://xmlns.com/foaf/0.1/knows> _:bob .")
+//!     .collect_triples()?;
+//!
+//! let t = graph.triples().next().unwrap()?;
+//! assert_eq!(t.s().kind(), TermKind::Iri);
+//! assert!(t.s().value().starts_with("https://data.example/.well-known/genid/"));
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use sophia_api::term::{CopyTerm, TTerm, TermKind};
+use sophia_term::{BoxTerm, TermError};
+use uuid::Uuid;
+
+use crate::iri_resolver::DynIriResolver;
+
+/// Path of well-known skolem iris, relative to their authority.
+pub const GENID_PATH: &str = "/.well-known/genid/";
+
+/// Configuration to skolemize blank nodes of parsed documents, into well-known iris under an authority.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Skolemization {
+    genid_base: String,
+}
+
+impl Skolemization {
+    /// Try to create a new skolemization config, that skolemizes blank nodes into iris under given `authority`, like `https://data.example`. A trailing slash of `authority` is ignored.
+    ///
+    /// # Errors
+    /// returns [`TermError`] if `authority` doesn't make valid iris.
+    pub fn try_new(authority: &str) -> Result<Self, TermError> {
+        let genid_base = format!("{}{}", authority.trim_end_matches('/'), GENID_PATH);
+        BoxTerm::new_iri(genid_base.as_str())?;
+        Ok(Self { genid_base })
+    }
+
+    /// Returns base of skolem iris, like `https://data.example/.well-known/genid/`.
+    pub fn genid_base(&self) -> &str {
+        &self.genid_base
+    }
+
+    /// Returns true, if given iri is a skolem iri under configured authority.
+    pub fn is_skolem_iri(&self, iri: &str) -> bool {
+        iri.len() > self.genid_base.len() && iri.starts_with(&self.genid_base)
+    }
+}
+
+/// Skolemizes blank nodes of a single document.
+#[derive(Debug, Clone)]
+pub(crate) struct Skolemizer {
+    genid_base: String,
+    document_id: Uuid,
+}
+
+impl Skolemizer {
+    pub(crate) fn new(config: &Skolemization) -> Self {
+        Self {
+            genid_base: config.genid_base.clone(),
+            document_id: Uuid::new_v4(),
+        }
+    }
+
+    /// Returns skolem iri for blank node with given label. It is derived from label, and document id, so that no mapping has to be kept for the document.
+    fn skolem_iri(&self, label: &str) -> String {
+        format!(
+            "{}{}",
+            self.genid_base,
+            Uuid::new_v5(&self.document_id, label.as_bytes())
+        )
+    }
+
+    /// Copies given term into a term of type `T`, skolemizing it if it is a blank node, or else rewriting it with `resolver`, if it is an iri.
+    pub(crate) fn copied<T, U>(
+        skolemizer: Option<&Self>,
+        resolver: Option<&DynIriResolver>,
+        term: &U,
+    ) -> T
+    where
+        T: CopyTerm,
+        U: TTerm + ?Sized,
+    {
+        if let (Some(skolemizer), TermKind::BlankNode) = (skolemizer, term.kind()) {
+            if let Ok(t) = BoxTerm::new_iri(skolemizer.skolem_iri(&term.value())) {
+                return T::copy(&t);
+            }
+        }
+        DynIriResolver::resolved(resolver, term)
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use sophia_api::term::{TTerm, TermKind};
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+
+    use super::{Skolemization, Skolemizer};
+    use crate::tests::TRACING;
+
+    #[test_case("https://data.example", true; "authority")]
+    #[test_case("https://data.example/", true; "authority with trailing slash")]
+    #[test_case("not an authority", false; "non iri")]
+    pub fn validates_authority(authority: &str, expected: bool) {
+        Lazy::force(&TRACING);
+        assert_eq!(Skolemization::try_new(authority).is_ok(), expected);
+    }
+
+    #[test]
+    pub fn skolemizes_only_blank_nodes_consistently_per_document() {
+        Lazy::force(&TRACING);
+        let config = Skolemization::try_new("https://data.example/").unwrap();
+        assert_eq!(
+            config.genid_base(),
+            "https://data.example/.well-known/genid/"
+        );
+        let (s1, s2) = (Skolemizer::new(&config), Skolemizer::new(&config));
+        let b = BoxTerm::new_bnode("b0").unwrap();
+        let iri = BoxTerm::new_iri("http://example.org/a").unwrap();
+
+        let b1: BoxTerm = Skolemizer::copied(Some(&s1), None, &b);
+        assert_eq!(b1.kind(), TermKind::Iri);
+        assert!(config.is_skolem_iri(&b1.value()));
+        assert_eq!(b1, Skolemizer::copied::<BoxTerm, _>(Some(&s1), None, &b));
+        assert_ne!(b1, Skolemizer::copied::<BoxTerm, _>(Some(&s2), None, &b));
+        assert_eq!(iri, Skolemizer::copied::<BoxTerm, _>(Some(&s1), None, &iri));
+        assert_eq!(b, Skolemizer::copied::<BoxTerm, _>(None, None, &b));
+    }
+}
@@ -0,0 +1,208 @@
+//! This module provides [`DynSynStatementParser`], that parses documents in any supported syntax into either a dataset, or a graph, through a single entry point. Applications converting arbitrary documents don't have to care, whether a syntax encodes triples, or quads. It delegates to [`DynSynTripleParser`], or [`DynSynQuadParser`], as appropriate.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{parser::statements::DynSynStatementParserFactory, syntax};
+//! use sophia_api::{dataset::Dataset, graph::Graph};
+//! use sophia_inmem::{dataset::FastDataset, graph::FastGraph};
+//! use sophia_term::BoxTerm;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let factory = DynSynStatementParserFactory::default();
+//! let doc = "<http://e/a> <http://e/p> <http://e/b> <http://e/g> .\n<http://e/a> <http://e/p> <http://e/c> .";
+//!
+//! let parser = factory.try_new_parser::<BoxTerm>(syntax::N_QUADS, None)?;
+//! let dataset: FastDataset = parser.parse_to_dataset(doc.as_bytes())?;
+//! assert_eq!(dataset.quads().count(), 2);
+//!
+//! let graph: FastGraph = parser.parse_to_graph(doc.as_bytes())?;
+//! assert_eq!(graph.triples().count(), 1);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::io::BufRead;
+
+use sophia_api::{
+    dataset::CollectibleDataset,
+    graph::CollectibleGraph,
+    parser::{QuadParser, TripleParser},
+    quad::stream::QuadSource,
+    term::{CopyTerm, TTerm},
+    triple::stream::{StreamResult, TripleSource},
+};
+
+use crate::syntax::{self, RdfSyntax, UnKnownSyntaxError};
+
+use super::{
+    errors::DynSynParseError,
+    quads::{DynSynQuadParser, DynSynQuadParserFactory},
+    triples::{DynSynTripleParser, DynSynTripleParserFactory},
+};
+
+/// A parser, that can parse documents in it's syntax into either a dataset, or a graph. We can get it's instance from [`DynSynStatementParserFactory::try_new_parser`] factory method.
+///
+/// Triples of documents in triple syntaxes are parsed into default graph of datasets. Only default graph of documents in quad syntaxes is parsed into graphs.
+#[derive(Debug)]
+pub struct DynSynStatementParser<T>
+where
+    T: TTerm + CopyTerm + Clone,
+{
+    triple_parser: DynSynTripleParser<T>,
+    quad_parser: DynSynQuadParser<T>,
+}
+
+impl<T> DynSynStatementParser<T>
+where
+    T: TTerm + CopyTerm + Clone,
+{
+    /// Returns syntax, this parser is targeted at.
+    pub fn syntax(&self) -> RdfSyntax {
+        self.quad_parser.syntax()
+    }
+
+    /// Returns true, if syntax of this parser encodes quads, instead of triples.
+    pub fn is_quad_syntax(&self) -> bool {
+        matches!(self.syntax(), syntax::N_QUADS | syntax::TRIG | syntax::TRIX)
+    }
+
+    /// Returns underlying triple parser.
+    pub fn triple_parser(&self) -> &DynSynTripleParser<T> {
+        &self.triple_parser
+    }
+
+    /// Returns underlying quad parser.
+    pub fn quad_parser(&self) -> &DynSynQuadParser<T> {
+        &self.quad_parser
+    }
+
+    /// Parse document from `data` into a dataset.
+    ///
+    /// # Errors
+    /// returns [`StreamError`](sophia_api::triple::stream::StreamError), if document couldn't be parsed, or if dataset couldn't be populated.
+    pub fn parse_to_dataset<D, R>(&self, data: R) -> StreamResult<D, DynSynParseError, D::Error>
+    where
+        D: CollectibleDataset,
+        R: BufRead,
+    {
+        self.quad_parser.parse(data).collect_quads()
+    }
+
+    /// Parse document from `data` into a graph. Quads in named graphs of documents in quad syntaxes are ignored.
+    ///
+    /// # Errors
+    /// returns [`StreamError`](sophia_api::triple::stream::StreamError), if document couldn't be parsed, or if graph couldn't be populated.
+    pub fn parse_to_graph<G, R>(&self, data: R) -> StreamResult<G, DynSynParseError, G::Error>
+    where
+        G: CollectibleGraph,
+        R: BufRead,
+    {
+        self.triple_parser.parse(data).collect_triples()
+    }
+}
+
+/// A factory to instantiate [`DynSynStatementParser`]. It pairs triple, and quad parser factories, whose configurations underlying parsers take.
+#[derive(Debug, Clone, Default)]
+pub struct DynSynStatementParserFactory {
+    pub triple_parser_factory: DynSynTripleParserFactory,
+    pub quad_parser_factory: DynSynQuadParserFactory,
+}
+
+impl DynSynStatementParserFactory {
+    pub fn new(
+        triple_parser_factory: DynSynTripleParserFactory,
+        quad_parser_factory: DynSynQuadParserFactory,
+    ) -> Self {
+        Self {
+            triple_parser_factory,
+            quad_parser_factory,
+        }
+    }
+
+    /// Try to create new [`DynSynStatementParser`] instance, for given `syntax_`, and `base_iri`.
+    ///
+    /// # Errors
+    /// returns [`UnKnownSyntaxError`] if requested syntax is not known/supported.
+    pub fn try_new_parser<T>(
+        &self,
+        syntax_: RdfSyntax,
+        base_iri: Option<String>,
+    ) -> Result<DynSynStatementParser<T>, UnKnownSyntaxError>
+    where
+        T: TTerm + CopyTerm + Clone,
+    {
+        Ok(DynSynStatementParser {
+            triple_parser: self.triple_parser_factory.try_new_parser(
+                syntax_,
+                base_iri.clone(),
+                None,
+            )?,
+            quad_parser: self
+                .quad_parser_factory
+                .try_new_parser(syntax_, base_iri, None)?,
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use claim::assert_err;
+    use once_cell::sync::Lazy;
+    use sophia_api::{dataset::Dataset, graph::Graph, term::TTerm, triple::Triple};
+    use sophia_inmem::{dataset::FastDataset, graph::FastGraph};
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+
+    use super::DynSynStatementParserFactory;
+    use crate::{
+        syntax::{self, RdfSyntax},
+        tests::TRACING,
+    };
+
+    static FACTORY: Lazy<DynSynStatementParserFactory> =
+        Lazy::new(DynSynStatementParserFactory::default);
+
+    #[test_case(syntax::N_TRIPLES, "<x:a> <x:p> <x:b> .", 1, 1, false)]
+    #[test_case(syntax::TURTLE, "<x:a> <x:p> <x:b>, <x:c> .", 2, 2, false)]
+    #[test_case(syntax::N_QUADS, "<x:a> <x:p> <x:b> <x:g>.\n<x:a> <x:p> <x:b>.", 2, 1, true)]
+    #[test_case(syntax::TRIG, "<x:g> { <x:a> <x:p> <x:b> . }", 1, 0, true)]
+    pub fn parses_into_either_shape(
+        syntax_: RdfSyntax,
+        doc: &str,
+        quads: usize,
+        triples: usize,
+        is_quad_syntax: bool,
+    ) {
+        Lazy::force(&TRACING);
+        let parser = FACTORY.try_new_parser::<BoxTerm>(syntax_, None).unwrap();
+        assert_eq!(parser.syntax(), syntax_);
+        assert_eq!(parser.is_quad_syntax(), is_quad_syntax);
+
+        let dataset: FastDataset = parser.parse_to_dataset(doc.as_bytes()).unwrap();
+        assert_eq!(dataset.quads().count(), quads);
+        let graph: FastGraph = parser.parse_to_graph(doc.as_bytes()).unwrap();
+        assert_eq!(graph.triples().count(), triples);
+    }
+
+    #[test]
+    pub fn resolves_relative_iris_against_base() {
+        Lazy::force(&TRACING);
+        let parser = FACTORY
+            .try_new_parser::<BoxTerm>(syntax::TURTLE, Some("http://e/".into()))
+            .unwrap();
+        let graph: FastGraph = parser.parse_to_graph("<a> <p> <b> .".as_bytes()).unwrap();
+        let t = graph.triples().next().unwrap().unwrap();
+        assert_eq!(t.s().value().to_string(), "http://e/a");
+    }
+
+    #[test]
+    pub fn creating_parser_for_unsupported_syntax_will_error() {
+        Lazy::force(&TRACING);
+        assert_err!(FACTORY.try_new_parser::<BoxTerm>(syntax::JSON_LD, None));
+    }
+}
@@ -0,0 +1,139 @@
+//! This module defines statistics of statements streamed through dynsyn sources. Dump-analysis tools can enable collection of stats on a source with it's `enable_stats` method, and retrieve them after the stream ends, without a second pass over the document, or custom sinks.
+//!
+//! Only statements, that are emitted by a source are counted. Statements skipped by parse limits, or policies are not. Distinct terms are tracked in memory, which grows with number of distinct subjects, predicates, and graphs of the document.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{parser::quads::DynSynQuadParserFactory, syntax};
+//! use sophia_api::{parser::QuadParser, quad::stream::QuadSource};
+//! use sophia_term::BoxTerm;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let doc = r#"
+//!     <http://ex.org/alice> <http://ex.org/age> "23"^^<http://www.w3.org/2001/XMLSchema#integer> <http://ex.org/g1> .
+//!     <http://ex.org/alice> <http://ex.org/name> "Alice" <http://ex.org/g2> .
+//!     <http://ex.org/bob> <http://ex.org/age> "31"^^<http://www.w3.org/2001/XMLSchema#integer> .
+//! "#;
+//! let parser = DynSynQuadParserFactory::default()
+//!     .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)?;
+//! let mut source = parser.parse_str(doc);
+//! source.enable_stats();
+//! source.for_each_quad(|_| {})?;
+//!
+//! let stats = source.stats().unwrap();
+//! assert_eq!(stats.statements, 3);
+//! assert_eq!(stats.distinct_subjects, 2);
+//! assert_eq!(stats.distinct_predicates, 2);
+//! assert_eq!(stats.distinct_graphs, 3);
+//! assert_eq!(stats.datatypes["http://www.w3.org/2001/XMLSchema#integer"], 2);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use sophia_api::term::{CopyTerm, TTerm};
+use sophia_term::BoxTerm;
+
+/// Statistics of statements streamed through a dynsyn source.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StatementStats {
+    /// number of statements emitted
+    pub statements: usize,
+    /// number of distinct subjects of emitted statements
+    pub distinct_subjects: usize,
+    /// number of distinct predicates of emitted statements
+    pub distinct_predicates: usize,
+    /// number of distinct graphs of emitted quads, counting default graph as one. It is always zero for triple sources.
+    pub distinct_graphs: usize,
+    /// number of literal objects of emitted statements, by their datatype iri
+    pub datatypes: HashMap<String, usize>,
+}
+
+/// Collects [`StatementStats`] of statements, as they are emitted by a source.
+#[derive(Debug, Default)]
+pub(crate) struct StatsCollector {
+    statements: usize,
+    subjects: HashSet<BoxTerm>,
+    predicates: HashSet<BoxTerm>,
+    graphs: HashSet<Option<BoxTerm>>,
+    datatypes: HashMap<String, usize>,
+}
+
+impl StatsCollector {
+    /// Records a statement with given terms.
+    pub(crate) fn record_triple<U>(&mut self, s: &U, p: &U, o: &U)
+    where
+        U: TTerm + ?Sized,
+    {
+        self.statements += 1;
+        self.subjects.insert(BoxTerm::copy(s));
+        self.predicates.insert(BoxTerm::copy(p));
+        if let Some(datatype) = o.datatype() {
+            *self
+                .datatypes
+                .entry(datatype.value().to_string())
+                .or_default() += 1;
+        }
+    }
+
+    /// Records graph of a quad.
+    pub(crate) fn record_graph<U>(&mut self, g: Option<&U>)
+    where
+        U: TTerm + ?Sized,
+    {
+        self.graphs.insert(g.map(BoxTerm::copy));
+    }
+
+    /// Returns stats of statements recorded so far.
+    pub(crate) fn stats(&self) -> StatementStats {
+        StatementStats {
+            statements: self.statements,
+            distinct_subjects: self.subjects.len(),
+            distinct_predicates: self.predicates.len(),
+            distinct_graphs: self.graphs.len(),
+            datatypes: self.datatypes.clone(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use sophia_term::BoxTerm;
+
+    use super::StatsCollector;
+    use crate::tests::TRACING;
+
+    #[test]
+    pub fn counts_distinct_terms_and_datatypes() {
+        Lazy::force(&TRACING);
+        let iri = |v: &str| BoxTerm::new_iri(v).unwrap();
+        let (a, b, p) = (iri("http://ex.org/a"), iri("http://ex.org/b"), iri("http://ex.org/p"));
+        let lit = BoxTerm::new_literal_lang("v", "en").unwrap();
+
+        let mut collector = StatsCollector::default();
+        collector.record_triple(&a, &p, &b);
+        collector.record_triple(&a, &p, &lit);
+        collector.record_triple(&b, &p, &lit);
+        collector.record_graph(None::<&BoxTerm>);
+        collector.record_graph(Some(&a));
+        collector.record_graph(None::<&BoxTerm>);
+
+        let stats = collector.stats();
+        assert_eq!(stats.statements, 3);
+        assert_eq!(stats.distinct_subjects, 2);
+        assert_eq!(stats.distinct_predicates, 1);
+        assert_eq!(stats.distinct_graphs, 2);
+        assert_eq!(
+            stats.datatypes["http://www.w3.org/1999/02/22-rdf-syntax-ns#langString"],
+            2
+        );
+    }
+}
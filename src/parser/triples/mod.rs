@@ -1,24 +1,53 @@
-use std::io::BufRead;
+use std::{
+    io::BufRead,
+    path::Path,
+    sync::{Arc, Mutex},
+};
 
+use mime::Mime;
 use sophia_api::{
     parser::{QuadParser, TripleParser},
     term::{CopyTerm, TTerm},
 };
 use type_map::concurrent::TypeMap;
 
-use crate::syntax::{RdfSyntax, UnKnownSyntaxError};
+use crate::{
+    correspondence::{syntax_for_path, Correspondent, ForPathError},
+    iri_resolver::DynIriResolver,
+    syntax::{RdfSyntax, UnKnownSyntaxError},
+    syntax_hint::SyntaxHint,
+};
 
 use self::source::DynSynTripleSource;
 
-use super::_inner::InnerParser;
+use super::{
+    _inner::{
+        lexer::{is_turtle_family, DocumentTracker},
+        source::{skip_bom, sniff_input},
+        InnerParser,
+    },
+    errors::{ParserForHintError, ParserForMediaTypeError},
+    graph_selector::GraphSelector,
+    iri_policy::{IriPolicy, IriPolicyGuard},
+    iri_validation::{IriNormalizer, IriValidation, LineIriResolver},
+    limits::{BudgetedRead, DocumentLimitGuard, DocumentLimits, SessionBudget},
+    literal_policy::{LiteralPolicy, LiteralPolicyGuard},
+    prefixes::{DocumentPrefixes, PrefixTracker},
+    progress::{ProgressConfig, ProgressTracker},
+    rdf_star,
+    recovery::ParserConfig,
+    skolem::{Skolemization, Skolemizer},
+};
 
 pub mod source;
 
 /// This parser implements [`sophia_api::parser::TripleParser`] trait, and can be instantiated at runtime against any of supported syntaxes using [`DynSynTripleParserFactory] factory.. It is generic over type of terms in triples it produces.
 ///
-/// It can currently parse triples from documents in any of concrete_syntaxes: [`turtle`](crate::syntax::TURTLE), [`n-triples`](crate::syntax::N_TRIPLES), [rdf-xml](crate::syntax::RDF_XML), [`n-quads`](crate::syntax::N_QUADS), [`trig`](crate::syntax::TRIG). For docs in any of these syntaxes, this parser will stream quads through [`DynSynTripleSource`] instance.
+/// It can currently parse triples from documents in any of concrete_syntaxes: [`turtle`](crate::syntax::TURTLE), [`n-triples`](crate::syntax::N_TRIPLES), [rdf-xml](crate::syntax::RDF_XML), [`n-quads`](crate::syntax::N_QUADS), [`trig`](crate::syntax::TRIG), [`trix`](crate::syntax::TRIX) with `trix` feature, turtle-compatible subset of [`n3`](crate::syntax::N3) with `n3` feature, [`html+rdfa`](crate::syntax::HTML_RDFA), and [`xhtml+rdfa`](crate::syntax::XHTML_RDFA) with `rdfa` feature, and binary [`hdt`](crate::syntax::HDT) with `hdt` feature. For docs in any of these syntaxes, this parser will stream quads through [`DynSynTripleSource`] instance.
+///
+/// For syntaxes that encodes quads instead of triples, like [`trig`](crate::syntax::TRIG), [`n-quads`](crate::syntax::N_QUADS), [`trix`](crate::syntax::TRIX), etc.. This parser can be configured with preferred graph_name term, to stream adapted triples from quads with specified graph_name. In that case, remaining underlying quads with different graph_name term will be ignored. It can also be configured with a [`GraphSelector`], to stream triples of union graph of whole dataset instead.
 ///
-/// For syntaxes that encodes quads instead of triples, like [`trig`](crate::syntax::TRIG), [`n-quads`](crate::syntax::N_QUADS), etc.. This parser can be configured with preferred graph_name term, to stream adapted triples from quads with specified graph_name. In that case, remaining underlying quads with different graph_name term will be ignored
+/// Empty and whitespace-only documents are parsed into an empty graph without error in every syntax. A leading utf-8 byte order mark is skipped.
 ///
 /// Example:
 ///
@@ -69,7 +98,19 @@ where
     T: TTerm + CopyTerm + Clone,
 {
     inner_parser: InnerParser,
-    quad_source_adapted_graph_iri: Option<T>,
+    base_iri: Option<String>,
+    graph_selector: GraphSelector<T>,
+    iri_resolver: Option<DynIriResolver>,
+    session_budget: Option<SessionBudget>,
+    iri_policy: Option<IriPolicy>,
+    literal_policy: Option<LiteralPolicy>,
+    document_limits: Option<DocumentLimits>,
+    skolemization: Option<Skolemization>,
+    progress: Option<ProgressConfig>,
+    lenient: bool,
+    reify_quoted_triples: bool,
+    iri_validation: IriValidation,
+    preserve_bnode_labels: bool,
 }
 
 impl<T> DynSynTripleParser<T>
@@ -81,11 +122,277 @@ where
         base_iri: Option<String>,
         quad_source_adapted_graph_iri: Option<T>,
     ) -> Result<Self, UnKnownSyntaxError> {
-        let inner_parser = InnerParser::try_new(syntax_, base_iri)?;
-        Ok(Self {
+        let inner_parser = InnerParser::try_new(syntax_, base_iri.clone())?;
+        Ok(Self::new_for(
             inner_parser,
+            base_iri,
             quad_source_adapted_graph_iri,
-        })
+        ))
+    }
+
+    /// Try to create a parser in generalized mode, for given `syntax_`. See [`try_new_generalized_parser`](DynSynTripleParserFactory::try_new_generalized_parser).
+    pub fn try_new_generalized(
+        syntax_: RdfSyntax,
+        base_iri: Option<String>,
+        quad_source_adapted_graph_iri: Option<T>,
+    ) -> Result<Self, UnKnownSyntaxError> {
+        let inner_parser = InnerParser::try_new_generalized(syntax_, base_iri.clone())?;
+        Ok(Self::new_for(
+            inner_parser,
+            base_iri,
+            quad_source_adapted_graph_iri,
+        ))
+    }
+
+    fn new_for(
+        inner_parser: InnerParser,
+        base_iri: Option<String>,
+        quad_source_adapted_graph_iri: Option<T>,
+    ) -> Self {
+        Self {
+            inner_parser,
+            base_iri,
+            graph_selector: quad_source_adapted_graph_iri.into(),
+            iri_resolver: None,
+            session_budget: None,
+            iri_policy: None,
+            literal_policy: None,
+            document_limits: None,
+            skolemization: None,
+            progress: None,
+            lenient: false,
+            reify_quoted_triples: false,
+            iri_validation: IriValidation::default(),
+            preserve_bnode_labels: false,
+        }
+    }
+
+    /// Returns syntax, this parser is currently targeted at.
+    pub fn syntax(&self) -> RdfSyntax {
+        self.inner_parser.syntax()
+    }
+
+    /// Retargets this parser to given `syntax_`, keeping rest of it's configuration, like base iri, and adapted graph iri. It allows a single long-lived parser to handle documents of whatever syntax each request carries.
+    ///
+    /// # Errors
+    /// returns [`UnKnownSyntaxError`] if requested syntax is not known/supported, or is not supported in generalized mode, if parser is in it. Parser is left unchanged in that case.
+    pub fn retarget(&mut self, syntax_: RdfSyntax) -> Result<(), UnKnownSyntaxError> {
+        let base_iri = self.resolved_base(self.base_iri.as_deref());
+        self.inner_parser = if self.is_generalized() {
+            InnerParser::try_new_generalized(syntax_, base_iri)?
+        } else {
+            InnerParser::try_new(syntax_, base_iri)?
+        };
+        Ok(())
+    }
+
+    /// Returns true, if this parser is in generalized mode. See [`try_new_generalized_parser`](DynSynTripleParserFactory::try_new_generalized_parser).
+    pub fn is_generalized(&self) -> bool {
+        self.inner_parser.is_generalized()
+    }
+
+    /// Returns selector of graphs, whose triples this parser reads from documents in quad syntaxes.
+    pub fn graph_selector(&self) -> &GraphSelector<T> {
+        &self.graph_selector
+    }
+
+    /// Sets selector of graphs, whose triples this parser reads from documents in quad syntaxes, overriding `quad_source_adapted_graph_iri` the parser is constructed with. See [`graph_selector`](super::graph_selector) module.
+    pub fn set_graph_selector(&mut self, graph_selector: GraphSelector<T>) {
+        self.graph_selector = graph_selector;
+    }
+
+    /// Returns true, if this parser is lenient. See [`recovery`](super::recovery) module.
+    pub fn is_lenient(&self) -> bool {
+        self.lenient
+    }
+
+    /// Makes this parser lenient, or strict, overriding [`ParserConfig`] from factory config, if any. Sources of a lenient parser skip malformed statements, and record them in their [`skip_summary`](source::DynSynTripleSource::skip_summary).
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.lenient = lenient;
+    }
+
+    /// Returns true, if this parser reifies quoted triples of rdf-star documents. See [`rdf_star`](super::rdf_star) module.
+    pub fn reifies_quoted_triples(&self) -> bool {
+        self.reify_quoted_triples
+    }
+
+    /// Enables, or disables reification of quoted triples by this parser, overriding [`ParserConfig`] from factory config, if any. If enabled, quoted triples of turtle family documents are read as blank nodes, and their rdf reification statements are asserted along with statements of document. Otherwise documents with quoted triples are rejected.
+    pub fn set_reify_quoted_triples(&mut self, reify_quoted_triples: bool) {
+        self.reify_quoted_triples = reify_quoted_triples;
+    }
+
+    /// Returns true, if sources of this parser preserve blank node labels of documents.
+    pub fn preserves_bnode_labels(&self) -> bool {
+        self.preserve_bnode_labels
+    }
+
+    /// Makes sources of this parser preserve blank node labels of documents, or not, overriding [`ParserConfig`] from factory config, if any. If enabled, rdfa documents keep their labels, instead of having them mapped to generated ids, and blank nodes are not skolemized, even if a [`Skolemization`] is configured. Other syntaxes always keep labels, except that turtle family parsers suffix labels like `riog00000001`, which they could have generated, with `d`.
+    pub fn set_preserve_bnode_labels(&mut self, preserve_bnode_labels: bool) {
+        self.preserve_bnode_labels = preserve_bnode_labels;
+    }
+
+    /// Sets progress reporting config of sources of this parser, overriding [`ProgressConfig`] from factory config, if any. See [`progress`](super::progress) module.
+    pub fn set_progress(&mut self, progress: Option<ProgressConfig>) {
+        self.progress = progress;
+    }
+
+    /// Returns mode, in which this parser validates iris of n-triples, and n-quads documents. See [`iri_validation`](super::iri_validation) module.
+    pub fn iri_validation(&self) -> IriValidation {
+        self.iri_validation
+    }
+
+    /// Sets mode, in which this parser validates iris of n-triples, and n-quads documents, overriding [`ParserConfig`] from factory config, if any.
+    pub fn set_iri_validation(&mut self, iri_validation: IriValidation) {
+        self.iri_validation = iri_validation;
+    }
+
+    /// Configures this parser with given iri resolver.
+    pub(crate) fn with_iri_resolver(mut self, iri_resolver: Option<DynIriResolver>) -> Self {
+        self.iri_resolver = iri_resolver;
+        self.inner_parser = self
+            .inner_parser
+            .with_base(self.resolved_base(self.base_iri.as_deref()));
+        self
+    }
+
+    /// Returns session budget, this parser is configured with.
+    #[cfg(any(test, feature = "async"))]
+    pub(crate) fn session_budget(&self) -> Option<&SessionBudget> {
+        self.session_budget.as_ref()
+    }
+
+    fn resolved_base(&self, base_iri: Option<&str>) -> Option<String> {
+        match &self.iri_resolver {
+            Some(resolver) => resolver.0.resolve_base(base_iri),
+            None => base_iri.map(String::from),
+        }
+    }
+
+    /// Parses `data` like [`TripleParser::parse`], but resolves relative iris against given `base_iri`, instead of base iri this parser was constructed with. It allows a single parser instance to serve many documents with different bases.
+    ///
+    /// Relative iris of syntaxes that don't support them, like n-triples, and n-quads are resolved against `base_iri` as per parser's [`IriValidation`] mode.
+    pub fn parse_with_base<R: BufRead>(
+        &self,
+        data: R,
+        base_iri: Option<String>,
+    ) -> DynSynTripleSource<T, R> {
+        let base_iri = self.resolved_base(base_iri.as_deref());
+        self.parse_with_inner(&self.inner_parser.with_base(base_iri.clone()), base_iri, data)
+    }
+
+    fn parse_with_inner<R: BufRead>(
+        &self,
+        inner_parser: &InnerParser,
+        base_iri: Option<String>,
+        mut data: R,
+    ) -> DynSynTripleSource<T, R> {
+        skip_bom(&mut data);
+        let syntax_ = inner_parser.syntax();
+        let sniff = sniff_input(&mut data, syntax_);
+        let document_limit_guard = self.document_limits.map(DocumentLimitGuard::new);
+        let prefixes = Arc::new(Mutex::new(DocumentPrefixes::new(
+            inner_parser.base_iri().map(String::from),
+        )));
+        let document_tracker = is_turtle_family(syntax_).then(|| {
+            DocumentTracker::new(
+                document_limit_guard
+                    .as_ref()
+                    .and_then(DocumentLimitGuard::nesting_tracker),
+                PrefixTracker::new(prefixes.clone()),
+            )
+        });
+        let is_line_based = matches!(
+            inner_parser,
+            InnerParser::NTriples(_) | InnerParser::NQuads(_)
+        );
+        let line_iri_resolver = base_iri
+            .clone()
+            .filter(|_| is_line_based)
+            .map(LineIriResolver::new);
+        let data = BudgetedRead::new(data, self.session_budget.clone())
+            .with_document_tracker(document_tracker)
+            .with_line_iri_resolver(line_iri_resolver);
+        let progress_tracker = self
+            .progress
+            .clone()
+            .map(|config| ProgressTracker::new(config, data.bytes_read()));
+        let graph_selector = self.graph_selector.clone();
+        let iri_normalizer = Some(IriNormalizer::new(self.iri_validation, base_iri));
+        // TODO may be abstract over literal repetition
+        let source = match inner_parser {
+            InnerParser::NQuads(p) => DynSynTripleSource::new_for(
+                rdf_star::adapt(p.parse(data), self.reify_quoted_triples, iri_normalizer).into(),
+                graph_selector,
+                sniff,
+                syntax_,
+            ),
+            InnerParser::TriG(p) => DynSynTripleSource::new_for(
+                rdf_star::adapt(p.parse(data), self.reify_quoted_triples, None).into(),
+                graph_selector,
+                sniff,
+                syntax_,
+            ),
+            InnerParser::NTriples(p) => DynSynTripleSource::new_for(
+                rdf_star::adapt(p.parse(data), self.reify_quoted_triples, iri_normalizer).into(),
+                graph_selector,
+                sniff,
+                syntax_,
+            ),
+            InnerParser::Turtle(p) => DynSynTripleSource::new_for(
+                rdf_star::adapt(p.parse(data), self.reify_quoted_triples, None).into(),
+                graph_selector,
+                sniff,
+                syntax_,
+            ),
+            InnerParser::Generalized(p, _) => DynSynTripleSource::new_for(
+                rdf_star::adapt_generalized(p.parse(data)).into(),
+                graph_selector,
+                sniff,
+                syntax_,
+            ),
+            #[cfg(feature = "rdf-xml")]
+            InnerParser::RdfXml(p) => {
+                DynSynTripleSource::new_for(p.parse(data).into(), graph_selector, sniff, syntax_)
+            }
+            #[cfg(feature = "n3")]
+            InnerParser::N3(p) => DynSynTripleSource::new_for(
+                rdf_star::adapt(p.parse(data), self.reify_quoted_triples, None).into(),
+                graph_selector,
+                sniff,
+                syntax_,
+            ),
+            #[cfg(feature = "rdfa")]
+            InnerParser::Rdfa(p) => {
+                let p = super::rdfa::RdfaParser {
+                    preserve_bnode_labels: self.preserve_bnode_labels,
+                    ..p.clone()
+                };
+                DynSynTripleSource::new_for(p.parse(data).into(), graph_selector, sniff, syntax_)
+            }
+            #[cfg(feature = "trix")]
+            InnerParser::Trix(p) => {
+                DynSynTripleSource::new_for(p.parse(data).into(), graph_selector, sniff, syntax_)
+            }
+            #[cfg(feature = "hdt")]
+            InnerParser::Hdt(p) => {
+                DynSynTripleSource::new_for(p.parse(data).into(), graph_selector, sniff, syntax_)
+            }
+        };
+        source
+            .with_iri_resolver(self.iri_resolver.clone())
+            .with_session_budget(self.session_budget.clone())
+            .with_iri_policy_guard(self.iri_policy.clone().map(IriPolicyGuard::new))
+            .with_literal_policy_guard(self.literal_policy.clone().map(LiteralPolicyGuard::new))
+            .with_document_limit_guard(document_limit_guard)
+            .with_skolemizer(
+                self.skolemization
+                    .as_ref()
+                    .filter(|_| !self.preserve_bnode_labels)
+                    .map(Skolemizer::new),
+            )
+            .with_progress_tracker(progress_tracker)
+            .with_prefixes(prefixes)
+            .with_lenient(self.lenient)
     }
 }
 
@@ -97,34 +404,27 @@ where
     type Source = DynSynTripleSource<T, R>;
 
     fn parse(&self, data: R) -> Self::Source {
-        let tsg_iri = self.quad_source_adapted_graph_iri.clone();
-        // TODO may be abstract over literal repetition
-        match &self.inner_parser {
-            InnerParser::NQuads(p) => DynSynTripleSource::new_for(p.parse(data).into(), tsg_iri),
-            InnerParser::TriG(p) => DynSynTripleSource::new_for(p.parse(data).into(), tsg_iri),
-            InnerParser::NTriples(p) => DynSynTripleSource::new_for(p.parse(data).into(), tsg_iri),
-            InnerParser::Turtle(p) => DynSynTripleSource::new_for(p.parse(data).into(), tsg_iri),
-            InnerParser::RdfXml(p) => DynSynTripleSource::new_for(p.parse(data).into(), tsg_iri),
-        }
+        self.parse_with_inner(
+            &self.inner_parser,
+            self.resolved_base(self.base_iri.as_deref()),
+            data,
+        )
     }
 }
 
 /// A factory to instantiate [`DynSynTripleParser`].
+///
+/// Factory is `Send + Sync`, and keeps its config map behind an [`Arc`]. Hence it is cheap to clone, and clones can be shared across threads, like in application state of web servers.
+#[derive(Debug, Clone)]
 pub struct DynSynTripleParserFactory {
-    _parser_config_map: TypeMap
+    parser_config_map: Arc<TypeMap>,
 }
 
 impl DynSynTripleParserFactory {
-    /// Instantiate a factory. It takes a `parser_config_map`, an optional [`TypeMap`], which can be populated with configuration structures corresponding to supported syntaxes.
+    /// Instantiate a factory. It takes a `parser_config_map`, an optional [`TypeMap`], which can be populated with configuration structures corresponding to supported syntaxes, with a [`SessionBudget`](super::limits::SessionBudget), with [`DocumentLimits`], with an [`IriPolicy`], with a [`LiteralPolicy`], with a [`Skolemization`], with a [`ProgressConfig`], with a [`ParserConfig`], and with a [`DynIriResolver`].
     pub fn new(parser_config_map: Option<TypeMap>) -> Self {
-        let parser_config_map = if let Some(v) = parser_config_map {
-            v
-        } else {
-            TypeMap::new()
-        };
-        Self {
-            _parser_config_map: parser_config_map,
-        }
+        let parser_config_map = Arc::new(parser_config_map.unwrap_or_default());
+        Self { parser_config_map }
     }
 
     /// Try to create new [`DynSynTripleParser`] instance, for given `syntax_`, `base_iri`, and  `quad_source_adapted_graph_iri`.
@@ -140,7 +440,108 @@ impl DynSynTripleParserFactory {
     where
         T: TTerm + CopyTerm + Clone,
     {
-        DynSynTripleParser::try_new(syntax_, base_iri, quad_source_adapted_graph_iri)
+        Ok(self.configured(DynSynTripleParser::try_new(
+            syntax_,
+            base_iri,
+            quad_source_adapted_graph_iri,
+        )?))
+    }
+
+    /// Try to create new [`DynSynTripleParser`] instance in generalized mode, for given `syntax_`, `base_iri`, and `quad_source_adapted_graph_iri`. It is otherwise configured like [`try_new_parser`](Self::try_new_parser).
+    ///
+    /// Generalized parsers read documents of generalized rdf, in which any kind of term can be in any position of a statement, and terms can be sparql variables, like `?name`. It allows sparql related tooling to parse documents with variables. Generalized mode is supported for [`turtle`](crate::syntax::TURTLE), [`trig`](crate::syntax::TRIG), and [`n-triples`](crate::syntax::N_TRIPLES) syntaxes, which are parsed as subsets of generalized trig. Quoted triples are not supported in generalized mode.
+    ///
+    /// # Errors
+    /// returns [`UnKnownSyntaxError`](crate::syntax::UnKnownSyntaxError) if requested syntax is not known, or is not supported in generalized mode.
+    pub fn try_new_generalized_parser<T>(
+        &self,
+        syntax_: RdfSyntax,
+        base_iri: Option<String>,
+        quad_source_adapted_graph_iri: Option<T>,
+    ) -> Result<DynSynTripleParser<T>, UnKnownSyntaxError>
+    where
+        T: TTerm + CopyTerm + Clone,
+    {
+        Ok(self.configured(DynSynTripleParser::try_new_generalized(
+            syntax_,
+            base_iri,
+            quad_source_adapted_graph_iri,
+        )?))
+    }
+
+    /// Configures given parser with configuration from config map.
+    fn configured<T>(&self, mut parser: DynSynTripleParser<T>) -> DynSynTripleParser<T>
+    where
+        T: TTerm + CopyTerm + Clone,
+    {
+        parser.session_budget = self.parser_config_map.get::<SessionBudget>().cloned();
+        parser.iri_policy = self.parser_config_map.get::<IriPolicy>().cloned();
+        parser.literal_policy = self.parser_config_map.get::<LiteralPolicy>().cloned();
+        parser.document_limits = self.parser_config_map.get::<DocumentLimits>().cloned();
+        parser.skolemization = self.parser_config_map.get::<Skolemization>().cloned();
+        parser.progress = self.parser_config_map.get::<ProgressConfig>().cloned();
+        let config = self
+            .parser_config_map
+            .get::<ParserConfig>()
+            .copied()
+            .unwrap_or_default();
+        parser.lenient = config.lenient;
+        parser.reify_quoted_triples = config.reify_quoted_triples;
+        parser.iri_validation = config.iri_validation;
+        parser.preserve_bnode_labels = config.preserve_bnode_labels;
+        parser.with_iri_resolver(self.parser_config_map.get::<DynIriResolver>().cloned())
+    }
+
+    /// Try to create new [`DynSynTripleParser`] instance, for syntax corresponding to given `media_type`. Media type parameters, like charset are ignored. Use [`MediaTypeResolution`](crate::correspondence::MediaTypeResolution) to get them. It saves resolving syntax of http messages separately.
+    ///
+    /// # Errors
+    /// returns [`ParserForMediaTypeError`] if media type doesn't correspond to any rdf syntax, or if corresponding syntax is not supported.
+    pub fn try_new_parser_for_media_type<T>(
+        &self,
+        media_type: &Mime,
+        base_iri: Option<String>,
+        quad_source_adapted_graph_iri: Option<T>,
+    ) -> Result<DynSynTripleParser<T>, ParserForMediaTypeError>
+    where
+        T: TTerm + CopyTerm + Clone,
+    {
+        let syntax_ = Correspondent::<RdfSyntax>::try_from(media_type)?.value;
+        Ok(self.try_new_parser(syntax_, base_iri, quad_source_adapted_graph_iri)?)
+    }
+
+    /// Try to create new [`DynSynTripleParser`] instance, for syntax corresponding to file extension of given `path`. File extension is matched case-insensitively, and compression suffix, like `.gz` of `data.ttl.gz`, is skipped. It saves resolving syntax of files on disk separately.
+    ///
+    /// # Errors
+    /// returns [`ForPathError`] if path has no file extension, or if it doesn't correspond to any rdf syntax, or if corresponding syntax is not supported.
+    pub fn try_new_parser_for_path<T>(
+        &self,
+        path: &Path,
+        base_iri: Option<String>,
+        quad_source_adapted_graph_iri: Option<T>,
+    ) -> Result<DynSynTripleParser<T>, ForPathError>
+    where
+        T: TTerm + CopyTerm + Clone,
+    {
+        let syntax_ = syntax_for_path(path)?;
+        Ok(self.try_new_parser(syntax_, base_iri, quad_source_adapted_graph_iri)?)
+    }
+
+    /// Try to create new [`DynSynTripleParser`] instance, for syntax resolved from given `hint`. If hint doesn't resolve, syntax is sniffed from `content_head`, the start of the document, like from [`BufRead::fill_buf`], if given.
+    ///
+    /// # Errors
+    /// returns [`ParserForHintError`] if syntax can't be resolved, or sniffed, or if resolved syntax is not supported.
+    pub fn try_new_parser_for_hint<T>(
+        &self,
+        hint: &SyntaxHint,
+        content_head: Option<&[u8]>,
+        base_iri: Option<String>,
+        quad_source_adapted_graph_iri: Option<T>,
+    ) -> Result<DynSynTripleParser<T>, ParserForHintError>
+    where
+        T: TTerm + CopyTerm + Clone,
+    {
+        let syntax_ = hint.resolve_or_sniff(content_head)?;
+        Ok(self.try_new_parser(syntax_, base_iri, quad_source_adapted_graph_iri)?)
     }
 }
 
@@ -150,61 +551,372 @@ impl Default for DynSynTripleParserFactory {
     }
 }
 
-/// ---------------------------------------------------------------------------------
-///                                      tests
-/// ---------------------------------------------------------------------------------
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
 
 #[cfg(test)]
 mod tests {
+    use std::convert::Infallible;
+
     use claim::{assert_err, assert_ok};
     use once_cell::sync::Lazy;
     use sophia_api::{
         dataset::Dataset,
-        graph::isomorphic_graphs,
+        graph::{isomorphic_graphs, Graph, MutableGraph},
         parser::{IntoParsable, QuadParser, TripleParser},
-        quad::stream::QuadSource,
-        term::{CopyTerm, TTerm},
-        triple::stream::TripleSource,
+        quad::{stream::QuadSource, Quad},
+        term::{CopyTerm, TTerm, TermKind},
+        triple::{stream::TripleSource, Triple},
     };
     use sophia_inmem::{dataset::FastDataset, graph::FastGraph};
     use sophia_term::{iri::Iri, BoxTerm};
     use sophia_turtle::parser::{
         nq::NQuadsParser, nt::NTriplesParser, trig::TriGParser, turtle::TurtleParser,
     };
+    #[cfg(feature = "rdf-xml")]
     use sophia_xml::parser::RdfXmlParser;
     use test_case::test_case;
 
     use crate::{
+        error_code::{self, ErrorCode},
+        parser::{
+            graph_selector::GraphSelector, iri_validation::IriValidation,
+            limits::DocumentLimits, provenance::ProvenanceSummary,
+            quads::DynSynQuadParserFactory,
+        },
         syntax::{self, RdfSyntax},
+        syntax_hint::SyntaxHint,
         tests::TRACING,
     };
 
     use super::DynSynTripleParserFactory;
-    use crate::parser::test_data::*;
+    use crate::fixtures::*;
 
     static DYNSYN_TRIPLE_PARSER_FACTORY: Lazy<DynSynTripleParserFactory> =
-        Lazy::new(|| DynSynTripleParserFactory::default());
+        Lazy::new(DynSynTripleParserFactory::default);
 
     #[test_case(syntax::JSON_LD)]
-    #[test_case(syntax::HTML_RDFA)]
-    #[test_case(syntax::N3)]
+    #[cfg_attr(not(feature = "hdt"), test_case(syntax::HDT))]
+    #[cfg_attr(not(feature = "rdfa"), test_case(syntax::HTML_RDFA))]
+    #[cfg_attr(not(feature = "n3"), test_case(syntax::N3))]
     #[test_case(syntax::OWL2_XML)]
-    #[test_case(syntax::XHTML_RDFA)]
+    #[cfg_attr(not(feature = "trix"), test_case(syntax::TRIX))]
+    #[cfg_attr(not(feature = "rdfa"), test_case(syntax::XHTML_RDFA))]
     pub fn creating_parser_for_un_supported_syntax_will_error(syntax_: RdfSyntax) {
         Lazy::force(&TRACING);
         assert_err!(&DYNSYN_TRIPLE_PARSER_FACTORY.try_new_parser::<BoxTerm>(syntax_, None, None));
     }
 
+    #[test]
+    pub fn generalized_parsers_read_triples_of_default_graph() {
+        Lazy::force(&TRACING);
+        let parser = DYNSYN_TRIPLE_PARSER_FACTORY
+            .try_new_generalized_parser::<BoxTerm>(syntax::TRIG, None, None)
+            .unwrap();
+        assert!(parser.is_generalized());
+        let mut source = parser.parse_str("?s ?p ?o . <http://ex.org/g> { ?s ?p 1 . }");
+        let mut triples = Vec::new();
+        source
+            .for_each_triple(|t| triples.push([t.s().kind(), t.p().kind(), t.o().kind()]))
+            .unwrap();
+        assert_eq!(triples, [[TermKind::Variable; 3]]);
+        assert_eq!(source.provenance_summary().dropped, 1);
+    }
+
     #[test_case(syntax::N_QUADS)]
     #[test_case(syntax::N_TRIPLES)]
-    #[test_case(syntax::RDF_XML)]
+    #[cfg_attr(feature = "rdf-xml", test_case(syntax::RDF_XML))]
     #[test_case(syntax::TRIG)]
     #[test_case(syntax::TURTLE)]
+    #[cfg_attr(feature = "n3", test_case(syntax::N3))]
+    #[cfg_attr(feature = "rdfa", test_case(syntax::HTML_RDFA))]
+    #[cfg_attr(feature = "rdfa", test_case(syntax::XHTML_RDFA))]
+    #[cfg_attr(feature = "trix", test_case(syntax::TRIX))]
+    #[cfg_attr(feature = "hdt", test_case(syntax::HDT))]
     pub fn creating_parser_for_supported_syntax_will_succeed(syntax_: RdfSyntax) {
         Lazy::force(&TRACING);
         assert_ok!(&DYNSYN_TRIPLE_PARSER_FACTORY.try_new_parser::<BoxTerm>(syntax_, None, None));
     }
 
+    #[test_case("application/trig; charset=utf-8", Ok(syntax::TRIG))]
+    #[test_case("text/turtle", Ok(syntax::TURTLE))]
+    #[test_case("image/png", Err(error_code::DYNSYN_NON_RDF_MEDIA_TYPE))]
+    #[test_case("application/ld+json", Err(error_code::DYNSYN_UNKNOWN_SYNTAX))]
+    pub fn creating_parser_for_media_type_works_correctly(
+        media_type: &str,
+        expected: Result<RdfSyntax, &str>,
+    ) {
+        Lazy::force(&TRACING);
+        let media_type: mime::Mime = media_type.parse().unwrap();
+        let actual = DYNSYN_TRIPLE_PARSER_FACTORY
+            .try_new_parser_for_media_type::<BoxTerm>(&media_type, None, None)
+            .map(|p| p.syntax())
+            .map_err(|e| e.code());
+        assert_eq!(actual, expected);
+    }
+
+    #[test_case("data/alice.ttl", Ok(syntax::TURTLE))]
+    #[test_case("data/ALICE.NT", Ok(syntax::N_TRIPLES))]
+    #[test_case("data/alice.jsonld", Err(error_code::DYNSYN_UNKNOWN_SYNTAX))]
+    #[test_case("data/alice.png", Err(error_code::DYNSYN_NON_RDF_FILE_EXTENSION))]
+    #[test_case("data.d/alice", Err(error_code::DYNSYN_UNDETERMINED_SYNTAX))]
+    pub fn creating_parser_for_path_works_correctly(path: &str, expected: Result<RdfSyntax, &str>) {
+        Lazy::force(&TRACING);
+        let actual = DYNSYN_TRIPLE_PARSER_FACTORY
+            .try_new_parser_for_path::<BoxTerm>(std::path::Path::new(path), None, None)
+            .map(|p| p.syntax())
+            .map_err(|e| e.code());
+        assert_eq!(actual, expected);
+    }
+
+    #[test_case(SyntaxHint::Explicit(syntax::TRIG), None, Ok(syntax::TRIG))]
+    #[test_case(SyntaxHint::MediaType(mime::TEXT_PLAIN), Some("<a> <b> <c> ."), Ok(syntax::N_TRIPLES))]
+    #[test_case(SyntaxHint::Unknown, Some("@prefix ex: <http://ex.org/> ."), Ok(syntax::TURTLE))]
+    #[test_case(SyntaxHint::Unknown, Some("{}"), Err(error_code::DYNSYN_UNKNOWN_SYNTAX))]
+    #[test_case(SyntaxHint::Unknown, None, Err(error_code::DYNSYN_UNDETERMINED_SYNTAX))]
+    pub fn creating_parser_for_hint_works_correctly(
+        hint: SyntaxHint,
+        content_head: Option<&str>,
+        expected: Result<RdfSyntax, &str>,
+    ) {
+        Lazy::force(&TRACING);
+        let actual = DYNSYN_TRIPLE_PARSER_FACTORY
+            .try_new_parser_for_hint::<BoxTerm>(
+                &hint,
+                content_head.map(str::as_bytes),
+                None,
+                None,
+            )
+            .map(|p| p.syntax())
+            .map_err(|e| e.code());
+        assert_eq!(actual, expected);
+    }
+
+    #[test_case(syntax::N_QUADS)]
+    #[test_case(syntax::N_TRIPLES)]
+    #[cfg_attr(feature = "rdf-xml", test_case(syntax::RDF_XML))]
+    #[test_case(syntax::TRIG)]
+    #[test_case(syntax::TURTLE)]
+    pub fn parses_empty_docs_into_empty_graph(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let parser = DYNSYN_TRIPLE_PARSER_FACTORY
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap();
+        for doc in EMPTY_DOCS {
+            let g: FastGraph = parser.parse_str(doc).collect_triples().unwrap();
+            assert_eq!(g.triples().count(), 0);
+        }
+    }
+
+    #[test_case(syntax::TRIG)]
+    #[test_case(syntax::TURTLE)]
+    pub fn parse_with_base_overrides_constructed_base(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let doc = "<#me> <http://www.w3.org/1999/02/22-rdf-syntax-ns#value> <#o> .";
+        let parser = DYNSYN_TRIPLE_PARSER_FACTORY
+            .try_new_parser::<BoxTerm>(syntax_, Some(BASE_IRI1.into()), None)
+            .unwrap();
+        for base in ["http://a.example/doc", "http://b.example/doc"] {
+            let g: FastGraph = parser
+                .parse_with_base(doc.as_bytes(), Some(base.into()))
+                .collect_triples()
+                .unwrap();
+            let t = g.triples().next().unwrap().unwrap();
+            assert_eq!(t.s().value().to_string(), format!("{}#me", base));
+        }
+    }
+
+    #[test]
+    pub fn retargeted_parser_keeps_configuration() {
+        Lazy::force(&TRACING);
+        let mut parser = DYNSYN_TRIPLE_PARSER_FACTORY
+            .try_new_parser::<BoxTerm>(syntax::N_TRIPLES, Some(BASE_IRI1.into()), None)
+            .unwrap();
+
+        assert_ok!(parser.retarget(syntax::TURTLE));
+        assert_eq!(parser.syntax(), syntax::TURTLE);
+        let g: FastGraph = parser
+            .parse_str("<#me> <http://www.w3.org/1999/02/22-rdf-syntax-ns#value> <#o> .")
+            .collect_triples()
+            .unwrap();
+        let t = g.triples().next().unwrap().unwrap();
+        assert_eq!(t.s().value().to_string(), format!("{}#me", BASE_IRI1));
+
+        assert_err!(parser.retarget(syntax::JSON_LD));
+        assert_eq!(parser.syntax(), syntax::TURTLE);
+    }
+
+    #[test]
+    pub fn reports_provenance_of_triples() {
+        Lazy::force(&TRACING);
+        let doc = r#"
+            <http://ex.org/s> <http://ex.org/p> <http://ex.org/o> .
+            <http://ex.org/s> <http://ex.org/p> <http://ex.org/o> <http://ex.org/g> .
+            <http://ex.org/s> <http://ex.org/p> <http://ex.org/o2> <http://ex.org/g> .
+        "#;
+        let parser = DYNSYN_TRIPLE_PARSER_FACTORY
+            .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+            .unwrap();
+        let mut source = parser.parse_str(doc);
+        assert!(source.is_adapted());
+        source.for_each_triple(|_| {}).unwrap();
+        assert_eq!(
+            source.provenance_summary(),
+            ProvenanceSummary {
+                native: 0,
+                adapted: 1,
+                dropped: 2
+            }
+        );
+
+        let parser = DYNSYN_TRIPLE_PARSER_FACTORY
+            .try_new_parser::<BoxTerm>(syntax::TURTLE, Some(BASE_IRI1.into()), None)
+            .unwrap();
+        let mut source = parser.parse_str(GRAPH_STR_TURTLE);
+        assert!(!source.is_adapted());
+        source.for_each_triple(|_| {}).unwrap();
+        assert!(source.provenance_summary().native > 0);
+    }
+
+    #[test]
+    pub fn keeps_going_past_malformed_statements() {
+        Lazy::force(&TRACING);
+        let doc = [
+            "<tag:s> <tag:p> <tag:o1> .",
+            "<tag:s> <tag:p> .",
+            "<tag:s> <tag:p> <tag:o2> .",
+            "<tag:s> <tag:p> \"x .",
+            "<tag:s> <tag:p> <tag:o3> .",
+        ]
+        .join("\n");
+        let parser = DYNSYN_TRIPLE_PARSER_FACTORY
+            .try_new_parser::<BoxTerm>(syntax::N_TRIPLES, None, None)
+            .unwrap();
+        let mut source = parser.parse_str(&doc);
+        assert!(source.supports_keep_going());
+        let mut objects = Vec::new();
+        let summary = source
+            .try_for_each_triple_keep_going(|t| {
+                objects.push(t.o().value().to_string());
+                Ok::<_, Infallible>(())
+            })
+            .unwrap();
+        assert_eq!(objects, vec!["tag:o1", "tag:o2", "tag:o3"]);
+        let lines: Vec<_> = summary.skipped.iter().map(|s| s.line_number).collect();
+        assert_eq!(lines, vec![Some(2), Some(4)]);
+
+        let parser = DYNSYN_TRIPLE_PARSER_FACTORY
+            .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)
+            .unwrap();
+        let mut source = parser.parse_str(&doc);
+        assert!(!source.supports_keep_going());
+        assert!(source
+            .try_for_each_triple_keep_going(|_| Ok::<_, Infallible>(()))
+            .is_err());
+    }
+
+    #[test]
+    pub fn lenient_parsers_will_skip_malformed_statements() {
+        Lazy::force(&TRACING);
+        let doc = [
+            "<tag:s> <tag:p> <tag:o1> .",
+            "<tag:s> <tag:p> .",
+            "<tag:s> <tag:p> <tag:o2> .",
+        ]
+        .join("\n");
+        let mut config_map = type_map::concurrent::TypeMap::new();
+        config_map.insert(crate::parser::recovery::ParserConfig::lenient());
+        let mut parser = DynSynTripleParserFactory::new(Some(config_map))
+            .try_new_parser::<BoxTerm>(syntax::N_TRIPLES, None, None)
+            .unwrap();
+        assert!(parser.is_lenient());
+
+        let mut source = parser.parse_str(&doc);
+        let mut objects = Vec::new();
+        source
+            .for_each_triple(|t| objects.push(t.o().value().to_string()))
+            .unwrap();
+        assert_eq!(objects, vec!["tag:o1", "tag:o2"]);
+        let lines: Vec<_> = source
+            .skip_summary()
+            .skipped
+            .iter()
+            .map(|s| s.line_number)
+            .collect();
+        assert_eq!(lines, vec![Some(2)]);
+
+        parser.set_lenient(false);
+        assert_err!(parser.parse_str(&doc).for_each_triple(|_| {}));
+
+        // Syntaxes without keep-going support are parsed strictly.
+        parser.set_lenient(true);
+        parser.retarget(syntax::TURTLE).unwrap();
+        assert_err!(parser.parse_str(&doc).for_each_triple(|_| {}));
+    }
+
+    #[test_case(
+        syntax::N_TRIPLES,
+        "<tag:s> <tag:p> <tag:o1> .\n<tag:s> <tag:p> <tag:o2> .",
+        DocumentLimits::new(Some(1), None, None),
+        Some(1)
+    )]
+    #[test_case(
+        syntax::N_TRIPLES,
+        "<tag:s> <tag:p> <tag:o1> .\n<tag:s> <tag:p> <tag:o2> .",
+        DocumentLimits::new(Some(2), None, None),
+        None
+    )]
+    #[test_case(
+        syntax::TURTLE,
+        "<tag:s> <tag:p> \"abc\", \"abcdef\" .",
+        DocumentLimits::new(None, Some(5), None),
+        Some(1)
+    )]
+    #[test_case(
+        syntax::TURTLE,
+        "<tag:s> <tag:p> [ <tag:p> [ <tag:p> ( ( 1 ) ) ] ] .",
+        DocumentLimits::new(None, None, Some(3)),
+        Some(0)
+    )]
+    #[test_case(
+        syntax::TURTLE,
+        "<tag:s> <tag:p> [ <tag:p> [ <tag:p> ( ( 1 ) ) ] ] .",
+        DocumentLimits::new(None, None, Some(4)),
+        None
+    )]
+    #[test_case(
+        syntax::TRIG,
+        "<tag:g> { <tag:s> <tag:p> \"[[[(\", '''((\\'''[[''' . } # [[[[\n<tag:s> <tag:p> [] .",
+        DocumentLimits::new(None, None, Some(1)),
+        None
+    )]
+    pub fn document_limits_will_be_enforced(
+        syntax_: RdfSyntax,
+        doc: &str,
+        limits: DocumentLimits,
+        failing_after: Option<usize>,
+    ) {
+        Lazy::force(&TRACING);
+        let mut config_map = type_map::concurrent::TypeMap::new();
+        config_map.insert(limits);
+        let parser = DynSynTripleParserFactory::new(Some(config_map))
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap();
+        let mut count = 0;
+        let r = parser.parse_str(doc).for_each_triple(|_| count += 1);
+        match failing_after {
+            Some(n) => {
+                let e = assert_err!(r);
+                assert_eq!(e.code(), error_code::DYNSYN_LIMIT_EXCEEDED);
+                assert_eq!(count, n);
+            }
+            None => {
+                assert_ok!(r);
+            }
+        }
+    }
+
     fn check_graph_parse_isomorphism<'b, B, P1, P2>(p1: &P1, p2: &P2, qs: &'b str)
     where
         P1: TripleParser<B>,
@@ -275,6 +987,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "rdf-xml")]
     #[test]
     pub fn correctly_parses_rdf_xml() {
         Lazy::force(&TRACING);
@@ -293,13 +1006,65 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "n3")]
+    #[test]
+    pub fn correctly_parses_turtle_compatible_n3() {
+        Lazy::force(&TRACING);
+        check_graph_parse_isomorphism(
+            &TurtleParser {
+                base: Some(BASE_IRI1.into()),
+            },
+            &DYNSYN_TRIPLE_PARSER_FACTORY
+                .try_new_parser(syntax::N3, Some(BASE_IRI1.into()), None as Option<BoxTerm>)
+                .unwrap(),
+            GRAPH_STR_TURTLE,
+        );
+    }
+
+    #[cfg(feature = "n3")]
+    #[test]
+    pub fn n3_only_constructs_will_error() {
+        Lazy::force(&TRACING);
+        let parser = DYNSYN_TRIPLE_PARSER_FACTORY
+            .try_new_parser::<BoxTerm>(syntax::N3, None, None)
+            .unwrap();
+        assert_eq!(parser.syntax(), syntax::N3);
+        assert_err!(parser
+            .parse_str("@prefix : <http://e/> . { :a :p :b } => { :a :q :b } .")
+            .for_each_triple(|_| {}));
+    }
+
+    #[cfg(feature = "rdfa")]
+    #[test]
+    pub fn extracts_rdfa_from_html_pages() {
+        Lazy::force(&TRACING);
+        let parser = DYNSYN_TRIPLE_PARSER_FACTORY
+            .try_new_parser_for_media_type::<BoxTerm>(
+                &"text/html; charset=utf-8".parse().unwrap(),
+                Some("http://example.org/page".into()),
+                None,
+            )
+            .unwrap();
+        assert_eq!(parser.syntax(), syntax::HTML_RDFA);
+        let mut source = parser.parse_str(
+            r#"<!DOCTYPE html>
+            <html><body vocab="http://schema.org/">
+                <article typeof="BlogPosting"><h1 property="headline">Hello</h1></article>
+            </body></html>"#,
+        );
+        let mut count = 0;
+        source.for_each_triple(|_| count += 1).unwrap();
+        assert_eq!(count, 3);
+        assert!(!source.is_adapted());
+    }
+
     #[test_case(Some(G1_IRI))]
     #[test_case(Some(G2_IRI))]
     #[test_case(None)]
     pub fn correctly_parses_nquads(quad_source_virtual_graph_iri: Option<&str>) {
         Lazy::force(&TRACING);
         let quad_source_virtual_graph_iri = quad_source_virtual_graph_iri
-            .and_then(|v| Some(BoxTerm::Iri(Iri::new(Box::from(v)).unwrap())));
+            .map(|v| BoxTerm::Iri(Iri::new(Box::from(v)).unwrap()));
         check_dataset_parse_isomorphism(
             &NQuadsParser {},
             &DYNSYN_TRIPLE_PARSER_FACTORY
@@ -320,7 +1085,7 @@ mod tests {
     pub fn correctly_parses_trig(quad_source_virtual_graph_iri: Option<&str>) {
         Lazy::force(&TRACING);
         let quad_source_virtual_graph_iri = quad_source_virtual_graph_iri
-            .and_then(|v| Some(BoxTerm::Iri(Iri::new(Box::from(v)).unwrap())));
+            .map(|v| BoxTerm::Iri(Iri::new(Box::from(v)).unwrap()));
         check_dataset_parse_isomorphism(
             &TriGParser {
                 base: Some(BASE_IRI1.into()),
@@ -336,4 +1101,121 @@ mod tests {
             quad_source_virtual_graph_iri.as_ref(),
         );
     }
+
+    #[test_case(syntax::N_QUADS, DATASET_STR_NQUADS)]
+    #[test_case(syntax::TRIG, DATASET_STR_TRIG)]
+    pub fn parses_union_of_all_graphs_when_selected(syntax_: RdfSyntax, doc: &str) {
+        Lazy::force(&TRACING);
+        let d: FastDataset = DynSynQuadParserFactory::default()
+            .try_new_parser(syntax_, Some(BASE_IRI1.into()), None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(doc)
+            .collect_quads()
+            .unwrap();
+        let mut g1 = FastGraph::new();
+        for q in d.quads() {
+            let q = q.unwrap();
+            g1.insert(q.s(), q.p(), q.o()).unwrap();
+        }
+
+        let mut parser = DYNSYN_TRIPLE_PARSER_FACTORY
+            .try_new_parser(syntax_, Some(BASE_IRI1.into()), None as Option<BoxTerm>)
+            .unwrap();
+        parser.set_graph_selector(GraphSelector::UnionOfAllGraphs);
+        let mut source = parser.parse_str(doc);
+        let mut g2 = FastGraph::new();
+        source
+            .for_each_triple(|t| {
+                g2.insert(t.s(), t.p(), t.o()).unwrap();
+            })
+            .unwrap();
+
+        assert!(isomorphic_graphs(&g1, &g2).unwrap());
+        assert_eq!(source.provenance_summary().dropped, 0);
+    }
+
+    #[test_case(syntax::TRIG, DATASET_STR_TRIG)]
+    #[test_case(syntax::N_TRIPLES, "<#s> <http://ex.org/p> \"v\"^^<#dt> .\n")]
+    pub fn scoped_triples_agree_with_copied_triples(syntax_: RdfSyntax, doc: &str) {
+        Lazy::force(&TRACING);
+        let mut parser = DYNSYN_TRIPLE_PARSER_FACTORY
+            .try_new_parser(syntax_, Some(BASE_IRI1.into()), None as Option<BoxTerm>)
+            .unwrap();
+        parser.set_graph_selector(GraphSelector::UnionOfAllGraphs);
+        let g1: FastGraph = parser.parse_str(doc).collect_triples().unwrap();
+
+        let mut source = parser.parse_str(doc);
+        let mut g2 = FastGraph::new();
+        source
+            .for_each_triple_scoped(|[s, p, o]| {
+                g2.insert(s, p, o).unwrap();
+            })
+            .unwrap();
+
+        assert!(isomorphic_graphs(&g1, &g2).unwrap());
+        assert_eq!(source.provenance_summary().dropped, 0);
+    }
+
+    #[test_case(syntax::N_TRIPLES, "<#s> <http://ex.org/p> \"v\"^^<#dt> .\n", IriValidation::Lax)]
+    #[test_case(syntax::N_TRIPLES, "<#s> <http://ex.org/p> \"v\"^^<#dt> .\n", IriValidation::Strict)]
+    #[test_case(syntax::N_QUADS, "<#s> <http://ex.org/p> \"v\"^^<#dt> .\n", IriValidation::Strict)]
+    #[test_case(syntax::N_QUADS, "<#s> <http://ex.org/p> \"v\"^^<#dt> <#g> .\n", IriValidation::Strict)]
+    pub fn resolves_relative_iris_of_line_based_syntaxes(
+        syntax_: RdfSyntax,
+        doc: &str,
+        iri_validation: IriValidation,
+    ) {
+        Lazy::force(&TRACING);
+        let mut parser = DYNSYN_TRIPLE_PARSER_FACTORY
+            .try_new_parser::<BoxTerm>(syntax_, Some(BASE_IRI1.into()), None)
+            .unwrap();
+        parser.set_iri_validation(iri_validation);
+        parser.set_graph_selector(GraphSelector::UnionOfAllGraphs);
+
+        let g: FastGraph = parser.parse_str(doc).collect_triples().unwrap();
+        let t = g.triples().next().unwrap().unwrap();
+        assert_eq!(t.s().value().to_string(), format!("{}#s", BASE_IRI1));
+        assert_eq!(
+            t.o().datatype().unwrap().value().to_string(),
+            format!("{}#dt", BASE_IRI1)
+        );
+    }
+
+    #[test_case(syntax::N_TRIPLES, "<#s> <http://ex.org/p> <http://ex.org/o> .\n")]
+    #[test_case(syntax::N_QUADS, "<http://ex.org/s> <http://ex.org/p> <http://ex.org/o> <#g> .\n")]
+    pub fn strict_iri_validation_rejects_unresolvable_iris(syntax_: RdfSyntax, doc: &str) {
+        Lazy::force(&TRACING);
+        let mut parser = DYNSYN_TRIPLE_PARSER_FACTORY
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap();
+        parser.set_iri_validation(IriValidation::Strict);
+        parser.set_graph_selector(GraphSelector::UnionOfAllGraphs);
+        assert_err!(parser.parse_str(doc).for_each_triple(|_| {}));
+    }
+
+    #[test_case(syntax::TURTLE)]
+    #[test_case(syntax::TRIG)]
+    pub fn collects_stats_of_emitted_triples_when_enabled(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let doc = r#"
+            @prefix ex: <http://ex.org/> .
+            ex:alice ex:age 23 ; ex:name "Alice" .
+            ex:bob ex:age 31 .
+        "#;
+        let parser = DYNSYN_TRIPLE_PARSER_FACTORY
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap();
+
+        let mut source = parser.parse_str(doc);
+        assert!(source.stats().is_none());
+        source.enable_stats();
+        source.for_each_triple(|_| {}).unwrap();
+        let stats = source.stats().unwrap();
+        assert_eq!(stats.statements, 3);
+        assert_eq!(stats.distinct_subjects, 2);
+        assert_eq!(stats.distinct_predicates, 2);
+        assert_eq!(stats.distinct_graphs, 0);
+        assert_eq!(stats.datatypes["http://www.w3.org/2001/XMLSchema#integer"], 2);
+        assert_eq!(stats.datatypes["http://www.w3.org/2001/XMLSchema#string"], 1);
+    }
 }
@@ -1,21 +1,44 @@
-use std::{error::Error, io::BufRead};
+use std::{
+    convert::Infallible,
+    error::Error,
+    io::BufRead,
+    sync::{Arc, Mutex},
+};
 
-use rio_api::parser::{QuadsParser, TriplesParser};
+use rio_api::parser::TriplesParser;
 use sophia_api::{
     quad::{stream::QuadSource, Quad},
-    term::{term_eq, CopiableTerm, CopyTerm, TTerm},
+    term::{CopyTerm, TTerm},
     triple::{
         self,
-        stream::{StreamResult, TripleSource},
+        stream::{StreamError, StreamResult, TripleSource},
         streaming_mode::{ByValue, StreamedTriple},
         Triple,
     },
 };
 use sophia_rio::parser::StrictRioSource;
 
-use crate::parser::{
-    _inner::source::InnerStatementSource,
-    errors::{adapt_stream_result, DynSynParseError},
+use crate::{
+    iri_resolver::DynIriResolver,
+    parser::{
+        _inner::source::{InnerStatementSource, InputSniff},
+        errors::{
+            adapt_stream_result, diagnose_stream_result, with_syntax_stream_result,
+            DynSynParseError,
+        },
+        graph_selector::GraphSelector,
+        iri_policy::IriPolicyGuard,
+        literal_policy::LiteralPolicyGuard,
+        prefixes::{self, DocumentPrefixes},
+        progress::ProgressTracker,
+        limits::{BudgetedRead, DocumentLimitGuard, SessionBudget},
+        provenance::{ProvenanceSummary, StatementProvenance},
+        recovery::SkipSummary,
+        scoped::{scoped_term, ScopedTriple, StatementGates, DEFAULT_BATCH_SIZE},
+        skolem::Skolemizer,
+        stats::{StatementStats, StatsCollector},
+    },
+    syntax::RdfSyntax,
 };
 
 pub type SliceTriple<T> = [T; 3];
@@ -24,46 +47,72 @@ pub type SliceTriple<T> = [T; 3];
 ///
 /// If underlying statement source is a triple-source, then it will emit equivalent triples.
 ///
-/// If underlying statement source is a quad-source, then it will emit triples corresponding to each quad that have  graph_name term set to configured `quad_source_adapted_graph_iri`  field value. quads that have different graph_name term will be ignored in such case.
+/// If underlying statement source is a quad-source, then it will emit triples corresponding to each quad, that is in a graph selected by configured [`GraphSelector`]. quads of other graphs will be ignored in such case, without copying any of their terms. Hence parsing just default graph of a large dataset is cheap.
+///
+/// Whether emitted triples are adapted can be known through [`provenance`](Self::provenance), and a running count of emitted triples, and ignored quads through [`provenance_summary`](Self::provenance_summary).
+///
+/// Statements are emitted in order documented in [`ordering`](crate::parser::ordering) module.
 pub struct DynSynTripleSource<T: CopyTerm + TTerm, R: BufRead> {
-    inner_source: InnerStatementSource<R>,
-    quad_source_adapted_graph_iri: Option<T>,
+    inner_source: InnerStatementSource<BudgetedRead<R>>,
+    graph_selector: GraphSelector<T>,
+    input_sniff: InputSniff,
+    syntax_: RdfSyntax,
+    provenance_summary: ProvenanceSummary,
+    iri_resolver: Option<DynIriResolver>,
+    session_budget: Option<SessionBudget>,
+    iri_policy_guard: Option<IriPolicyGuard>,
+    literal_policy_guard: Option<LiteralPolicyGuard>,
+    document_limit_guard: Option<DocumentLimitGuard>,
+    skolemizer: Option<Skolemizer>,
+    stats: Option<StatsCollector>,
+    progress_tracker: Option<ProgressTracker>,
+    prefixes: Arc<Mutex<DocumentPrefixes>>,
+    lenient: bool,
+    skip_summary: SkipSummary,
+    batch_size: usize,
 }
 
 impl<T: CopyTerm + TTerm + Clone, R: BufRead> DynSynTripleSource<T, R> {
-    /// Call `f` for at least one adapted-triple (if any) that is adapted from underlying rio quad source.
+    /// Call `f` for at least one scoped triple (if any) that is adapted from underlying rio quad source.
     ///
     /// Return false if no more triple can be adapted from underlying source.
     ///
     /// If underlying fallible quad-source returns a parse error, then that error will be wrapped in enum [`DynSynParseError`] as an appropriate variant.
     ///
     /// # Quad to Triple adaptation:
-    ///  Each quad from underlying quad-source, which has it's graph_name term same as `quad_source_adapted_graph_iri`  will be adapted into a triple. Quads with any other graph_name term will be ignored.
-    fn try_for_some_triple_adapted_from_rio_quad_source<Parser, PErr, SinkErr, F>(
-        qs: &mut StrictRioSource<Parser, PErr>,
+    ///  Each quad from underlying quad-source, which is in a graph selected by `graph_selector` will be adapted into a triple. Quads of other graphs will be ignored, without copying any of their terms.
+    fn try_for_some_triple_adapted_from_rio_quad_source<QS, PErr, SinkErr, F>(
+        qs: &mut QS,
         mut f: F,
-        quad_source_adapted_graph_iri: &Option<T>,
+        graph_selector: &GraphSelector<T>,
+        summary: &mut ProvenanceSummary,
+        resolver: Option<&DynIriResolver>,
+        skolemizer: Option<&Skolemizer>,
     ) -> StreamResult<bool, DynSynParseError, SinkErr>
     where
-        Parser: QuadsParser<Error = PErr>,
+        QS: QuadSource<Error = PErr>,
         PErr: Error + 'static + Into<DynSynParseError>,
         SinkErr: Error,
-        F: FnMut(StreamedTriple<ByValue<SliceTriple<T>>>) -> Result<(), SinkErr>,
+        F: FnMut(ScopedTriple<'_>) -> Result<(), SinkErr>,
     {
         adapt_stream_result(qs.try_for_some_quad(&mut |q| {
-            let in_graph = match (q.g(), quad_source_adapted_graph_iri) {
-                (Some(a), Some(b)) => term_eq(a, b),
-                (None, None) => true,
-                _ => false,
-            };
-            if !in_graph {
+            // Compare graph term before materializing any term, so that quads of other graphs
+            // are dropped cheaply.
+            if !graph_selector.selects(q.g(), resolver) {
+                summary.dropped += 1;
                 return Ok(());
             }
-            let tq: SliceTriple<T> = [q.s().copied(), q.p().copied(), q.o().copied()];
-            f(StreamedTriple::by_value(tq))
+            let (mut bs, mut bp, mut bo) = (None, None, None);
+            f([
+                scoped_term(skolemizer, resolver, q.s(), &mut bs),
+                scoped_term(skolemizer, resolver, q.p(), &mut bp),
+                scoped_term(skolemizer, resolver, q.o(), &mut bo),
+            ])?;
+            summary.adapted += 1;
+            Ok(())
         }))
     }
-    /// Call `f` for at least one adapted-triple (if any) that is adapted from underlying rio triple source.
+    /// Call `f` for at least one scoped triple (if any) that is adapted from underlying rio triple source.
     ///
     /// Return false if no more triples can be adapted from underlying source.
     ///
@@ -71,28 +120,421 @@ impl<T: CopyTerm + TTerm + Clone, R: BufRead> DynSynTripleSource<T, R> {
     fn try_for_some_triple_adapted_from_rio_triple_source<Parser, PErr, SinkErr, F>(
         ts: &mut StrictRioSource<Parser, PErr>,
         mut f: F,
+        summary: &mut ProvenanceSummary,
+        resolver: Option<&DynIriResolver>,
+        skolemizer: Option<&Skolemizer>,
     ) -> StreamResult<bool, DynSynParseError, SinkErr>
     where
         Parser: TriplesParser<Error = PErr>,
         PErr: Error + 'static + Into<DynSynParseError>,
         SinkErr: Error,
-        F: FnMut(StreamedTriple<ByValue<SliceTriple<T>>>) -> Result<(), SinkErr>,
+        F: FnMut(ScopedTriple<'_>) -> Result<(), SinkErr>,
     {
         adapt_stream_result(ts.try_for_some_triple(&mut |t| {
-            let tq: SliceTriple<T> = [t.s().copied(), t.p().copied(), t.o().copied()];
-            f(StreamedTriple::by_value(tq))
+            let (mut bs, mut bp, mut bo) = (None, None, None);
+            f([
+                scoped_term(skolemizer, resolver, t.s(), &mut bs),
+                scoped_term(skolemizer, resolver, t.p(), &mut bp),
+                scoped_term(skolemizer, resolver, t.o(), &mut bo),
+            ])?;
+            summary.native += 1;
+            Ok(())
         }))
     }
 
     pub(crate) fn new_for(
-        inner_source: InnerStatementSource<R>,
-        quad_source_virtual_default_graph_iri: Option<T>,
+        inner_source: InnerStatementSource<BudgetedRead<R>>,
+        graph_selector: GraphSelector<T>,
+        input_sniff: InputSniff,
+        syntax_: RdfSyntax,
     ) -> Self {
         Self {
             inner_source,
-            quad_source_adapted_graph_iri: quad_source_virtual_default_graph_iri,
+            graph_selector,
+            input_sniff,
+            syntax_,
+            provenance_summary: ProvenanceSummary::default(),
+            iri_resolver: None,
+            session_budget: None,
+            iri_policy_guard: None,
+            literal_policy_guard: None,
+            document_limit_guard: None,
+            skolemizer: None,
+            stats: None,
+            progress_tracker: None,
+            prefixes: Arc::default(),
+            lenient: false,
+            skip_summary: SkipSummary::default(),
+            batch_size: DEFAULT_BATCH_SIZE,
         }
     }
+
+    pub(crate) fn with_iri_resolver(mut self, iri_resolver: Option<DynIriResolver>) -> Self {
+        self.iri_resolver = iri_resolver;
+        self
+    }
+
+    pub(crate) fn with_session_budget(mut self, session_budget: Option<SessionBudget>) -> Self {
+        self.session_budget = session_budget;
+        self
+    }
+
+    pub(crate) fn with_iri_policy_guard(mut self, guard: Option<IriPolicyGuard>) -> Self {
+        self.iri_policy_guard = guard;
+        self
+    }
+
+    pub(crate) fn with_literal_policy_guard(mut self, guard: Option<LiteralPolicyGuard>) -> Self {
+        self.literal_policy_guard = guard;
+        self
+    }
+
+    pub(crate) fn with_document_limit_guard(mut self, guard: Option<DocumentLimitGuard>) -> Self {
+        self.document_limit_guard = guard;
+        self
+    }
+
+    pub(crate) fn with_skolemizer(mut self, skolemizer: Option<Skolemizer>) -> Self {
+        self.skolemizer = skolemizer;
+        self
+    }
+
+    pub(crate) fn with_progress_tracker(mut self, tracker: Option<ProgressTracker>) -> Self {
+        self.progress_tracker = tracker;
+        self
+    }
+
+    pub(crate) fn with_prefixes(mut self, prefixes: Arc<Mutex<DocumentPrefixes>>) -> Self {
+        self.prefixes = prefixes;
+        self
+    }
+
+    pub(crate) fn with_lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Returns provenance of triples emitted by this source. Triples are [`Adapted`](StatementProvenance::Adapted), if underlying statement source is a quad-source.
+    pub fn provenance(&self) -> StatementProvenance {
+        match &self.inner_source {
+            InnerStatementSource::FNQuads(_)
+            | InnerStatementSource::FTriG(_)
+            | InnerStatementSource::FGTriG(_) => StatementProvenance::Adapted,
+            #[cfg(feature = "trix")]
+            InnerStatementSource::FTrix(_) => StatementProvenance::Adapted,
+            _ => StatementProvenance::Native,
+        }
+    }
+
+    /// Returns true, if triples emitted by this source are extracted from underlying quads.
+    pub fn is_adapted(&self) -> bool {
+        self.provenance() == StatementProvenance::Adapted
+    }
+
+    /// Returns summary of triples emitted by this source so far, along with count of underlying quads ignored for being in other graphs. After source is exhausted, it summarizes whole document.
+    pub fn provenance_summary(&self) -> ProvenanceSummary {
+        self.provenance_summary
+    }
+
+    /// Call `f` for some statements, like [`try_for_some_triple`](TripleSource::try_for_some_triple), but without marking raised errors with syntax of the document.
+    fn try_for_some_triple_untagged<F, E>(
+        &mut self,
+        f: &mut F,
+    ) -> StreamResult<bool, DynSynParseError, E>
+    where
+        F: FnMut(StreamedTriple<ByValue<SliceTriple<T>>>) -> Result<(), E>,
+        E: Error,
+    {
+        self.try_for_some_scoped_triple_untagged(&mut |[s, p, o]: ScopedTriple<'_>| {
+            f(StreamedTriple::by_value([T::copy(s), T::copy(p), T::copy(o)]))
+        })
+    }
+
+    /// Call `f` for some scoped triples, without marking raised errors with syntax of the document. Triples, that are not admitted by parse limits, and policies are skipped, before any of their terms is copied.
+    fn try_for_some_scoped_triple_untagged<F, E>(
+        &mut self,
+        f: &mut F,
+    ) -> StreamResult<bool, DynSynParseError, E>
+    where
+        F: FnMut(ScopedTriple<'_>) -> Result<(), E>,
+        E: Error,
+    {
+        if let Some(resource) = self.session_budget.as_ref().and_then(SessionBudget::exhausted) {
+            return Err(StreamError::SourceError(
+                DynSynParseError::budget_exhausted(resource),
+            ));
+        }
+        let mut gates = StatementGates {
+            budget: self.session_budget.as_ref(),
+            iri_policy_guard: &mut self.iri_policy_guard,
+            literal_policy_guard: &mut self.literal_policy_guard,
+            document_limit_guard: &mut self.document_limit_guard,
+            stats: &mut self.stats,
+            progress_tracker: &mut self.progress_tracker,
+        };
+        let mut r = Ok(true);
+        // Pull a batch of statements per call, so that per call checks, and dispatch on
+        // underlying parser, are amortized over the batch.
+        for _ in 0..self.batch_size {
+            if gates.tripped() {
+                break;
+            }
+            let mut gated_f = |triple: ScopedTriple<'_>| {
+                if gates.admit_triple(triple) {
+                    f(triple)
+                } else {
+                    Ok(())
+                }
+            };
+            r = match &mut self.inner_source {
+                InnerStatementSource::FNQuads(qs) => {
+                    Self::try_for_some_triple_adapted_from_rio_quad_source(
+                        qs,
+                        &mut gated_f,
+                        &self.graph_selector,
+                        &mut self.provenance_summary,
+                        self.iri_resolver.as_ref(),
+                        self.skolemizer.as_ref(),
+                    )
+                }
+
+                InnerStatementSource::FTriG(qs) => {
+                    Self::try_for_some_triple_adapted_from_rio_quad_source(
+                        qs,
+                        &mut gated_f,
+                        &self.graph_selector,
+                        &mut self.provenance_summary,
+                        self.iri_resolver.as_ref(),
+                        self.skolemizer.as_ref(),
+                    )
+                }
+
+                InnerStatementSource::FGTriG(qs) => {
+                    Self::try_for_some_triple_adapted_from_rio_quad_source(
+                        qs,
+                        &mut gated_f,
+                        &self.graph_selector,
+                        &mut self.provenance_summary,
+                        self.iri_resolver.as_ref(),
+                        self.skolemizer.as_ref(),
+                    )
+                }
+
+                #[cfg(feature = "trix")]
+                InnerStatementSource::FTrix(qs) => {
+                    Self::try_for_some_triple_adapted_from_rio_quad_source(
+                        qs,
+                        &mut gated_f,
+                        &self.graph_selector,
+                        &mut self.provenance_summary,
+                        self.iri_resolver.as_ref(),
+                        self.skolemizer.as_ref(),
+                    )
+                }
+
+                InnerStatementSource::FNTriples(ts) => {
+                    Self::try_for_some_triple_adapted_from_rio_triple_source(
+                        ts,
+                        &mut gated_f,
+                        &mut self.provenance_summary,
+                        self.iri_resolver.as_ref(),
+                        self.skolemizer.as_ref(),
+                    )
+                }
+
+                InnerStatementSource::FTurtle(ts) => {
+                    Self::try_for_some_triple_adapted_from_rio_triple_source(
+                        ts,
+                        &mut gated_f,
+                        &mut self.provenance_summary,
+                        self.iri_resolver.as_ref(),
+                        self.skolemizer.as_ref(),
+                    )
+                }
+
+                #[cfg(feature = "rdf-xml")]
+                InnerStatementSource::FRdfXml(ts) => {
+                    Self::try_for_some_triple_adapted_from_rio_triple_source(
+                        ts,
+                        &mut gated_f,
+                        &mut self.provenance_summary,
+                        self.iri_resolver.as_ref(),
+                        self.skolemizer.as_ref(),
+                    )
+                }
+
+                #[cfg(feature = "rdfa")]
+                InnerStatementSource::FRdfa(ts) => {
+                    Self::try_for_some_triple_adapted_from_rio_triple_source(
+                        ts,
+                        &mut gated_f,
+                        &mut self.provenance_summary,
+                        self.iri_resolver.as_ref(),
+                        self.skolemizer.as_ref(),
+                    )
+                }
+
+                #[cfg(feature = "hdt")]
+                InnerStatementSource::FHdt(ts) => {
+                    Self::try_for_some_triple_adapted_from_rio_triple_source(
+                        ts,
+                        &mut gated_f,
+                        &mut self.provenance_summary,
+                        self.iri_resolver.as_ref(),
+                        self.skolemizer.as_ref(),
+                    )
+                }
+            };
+            if !matches!(r, Ok(true)) {
+                break;
+            }
+        }
+        if let Some(limit) = self
+            .document_limit_guard
+            .as_ref()
+            .and_then(DocumentLimitGuard::exceeded)
+        {
+            return Err(StreamError::SourceError(
+                DynSynParseError::document_limit_exceeded(limit),
+            ));
+        }
+        if let Some(guard) = self.iri_policy_guard.as_ref().filter(|g| g.must_fail()) {
+            let violation = guard.violation().cloned().expect("must_fail implies a violation");
+            return Err(StreamError::SourceError(
+                DynSynParseError::iri_policy_violation(violation),
+            ));
+        }
+        if let Some(guard) = self.literal_policy_guard.as_ref().filter(|g| g.must_fail()) {
+            let violation = guard.violation().cloned().expect("must_fail implies a violation");
+            return Err(StreamError::SourceError(
+                DynSynParseError::literal_policy_violation(violation),
+            ));
+        }
+        if let (Ok(false), Some(tracker)) = (&r, self.progress_tracker.as_mut()) {
+            tracker.on_end();
+        }
+        match self.session_budget.as_ref().and_then(SessionBudget::exhausted) {
+            Some(resource) => Err(StreamError::SourceError(
+                DynSynParseError::budget_exhausted(resource),
+            )),
+            None => diagnose_stream_result(r, self.input_sniff),
+        }
+    }
+
+    /// Enables collection of [`StatementStats`] of triples emitted by this source from now on. See [`stats`](crate::parser::stats) module.
+    pub fn enable_stats(&mut self) {
+        self.stats.get_or_insert_with(StatsCollector::default);
+    }
+
+    /// Returns stats of triples emitted so far, if their collection is enabled. After source is exhausted, they summarize whole document.
+    pub fn stats(&self) -> Option<StatementStats> {
+        self.stats.as_ref().map(StatsCollector::stats)
+    }
+
+    /// Returns prefix bindings, and base iri of the document, that are declared so far. Directives are tracked as they are read, which may be ahead of emitted statements by a buffer. Hence they are complete only after the source is exhausted. See [`prefixes`](crate::parser::prefixes) module.
+    pub fn prefixes(&self) -> DocumentPrefixes {
+        prefixes::lock(&self.prefixes).clone()
+    }
+
+    /// Returns summary of statements, that are skipped so far, if this source is from a lenient parser. See [`recovery`](crate::parser::recovery) module.
+    pub fn skip_summary(&self) -> &SkipSummary {
+        &self.skip_summary
+    }
+
+    /// Returns max number of triples, that this source pulls from underlying parser per `try_for_some_*` call.
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// Sets max number of triples, that this source pulls from underlying parser per `try_for_some_*` call. Larger batches amortize per call checks, and dispatch over more statements, while smaller batches return control to caller sooner. It defaults to [`DEFAULT_BATCH_SIZE`], and is at least 1.
+    pub fn set_batch_size(&mut self, batch_size: usize) {
+        self.batch_size = batch_size.max(1);
+    }
+
+    /// Returns true, if this source can skip malformed statements, and keep going. See [`recovery`](crate::parser::recovery) module.
+    pub fn supports_keep_going(&self) -> bool {
+        matches!(
+            &self.inner_source,
+            InnerStatementSource::FNQuads(_) | InnerStatementSource::FNTriples(_)
+        )
+    }
+
+    /// Calls `f` for each triple, like [`try_for_each_triple`](TripleSource::try_for_each_triple), but skips statements that can't be parsed, and keeps going. Returns summary of skipped statements.
+    ///
+    /// If this source doesn't [support keep-going](Self::supports_keep_going), or if error is not confined to a statement, then it returns on first parse error. If this source is from a lenient parser, statements are recorded in it's [`skip_summary`](Self::skip_summary) instead.
+    pub fn try_for_each_triple_keep_going<F, E>(
+        &mut self,
+        mut f: F,
+    ) -> StreamResult<SkipSummary, DynSynParseError, E>
+    where
+        F: FnMut(StreamedTriple<ByValue<SliceTriple<T>>>) -> Result<(), E>,
+        E: Error,
+    {
+        let mut summary = SkipSummary::default();
+        loop {
+            match self.try_for_some_triple(&mut f) {
+                Ok(true) => {}
+                Ok(false) => return Ok(summary),
+                Err(StreamError::SourceError(e))
+                    if self.supports_keep_going() && e.is_recoverable() =>
+                {
+                    tracing::warn!("Skipping malformed statement: {}", e);
+                    summary.skipped.push(e.into());
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Calls `f` for some scoped triples, like [`try_for_some_triple`](TripleSource::try_for_some_triple), but without copying terms of triples. Return false if source is exhausted. See [`scoped`](crate::parser::scoped) module.
+    pub fn try_for_some_triple_scoped<F, E>(
+        &mut self,
+        f: &mut F,
+    ) -> StreamResult<bool, DynSynParseError, E>
+    where
+        F: FnMut(ScopedTriple<'_>) -> Result<(), E>,
+        E: Error,
+    {
+        let syntax_ = self.syntax_;
+        match with_syntax_stream_result(self.try_for_some_scoped_triple_untagged(f), syntax_) {
+            Err(StreamError::SourceError(e))
+                if self.lenient && self.supports_keep_going() && e.is_recoverable() =>
+            {
+                tracing::warn!("Skipping malformed statement: {}", e);
+                self.skip_summary.skipped.push(e.into());
+                Ok(true)
+            }
+            r => r,
+        }
+    }
+
+    /// Calls `f` for each scoped triple, like [`try_for_each_triple`](TripleSource::try_for_each_triple), but without copying terms of triples. See [`scoped`](crate::parser::scoped) module.
+    pub fn try_for_each_triple_scoped<F, E>(
+        &mut self,
+        mut f: F,
+    ) -> StreamResult<(), DynSynParseError, E>
+    where
+        F: FnMut(ScopedTriple<'_>) -> Result<(), E>,
+        E: Error,
+    {
+        while self.try_for_some_triple_scoped(&mut f)? {}
+        Ok(())
+    }
+
+    /// Calls `f` for each scoped triple, like [`for_each_triple`](TripleSource::for_each_triple), but without copying terms of triples. See [`scoped`](crate::parser::scoped) module.
+    pub fn for_each_triple_scoped<F>(&mut self, mut f: F) -> Result<(), DynSynParseError>
+    where
+        F: FnMut(ScopedTriple<'_>),
+    {
+        self.try_for_each_triple_scoped(|t| {
+            f(t);
+            Ok::<_, Infallible>(())
+        })
+        .map_err(|e| match e {
+            StreamError::SourceError(e) => e,
+            StreamError::SinkError(e) => match e {},
+        })
+    }
 }
 
 impl<T, R> triple::stream::TripleSource for DynSynTripleSource<T, R>
@@ -109,34 +551,16 @@ where
         F: FnMut(StreamedTriple<Self::Triple>) -> Result<(), E>,
         E: Error,
     {
-        match &mut self.inner_source {
-            InnerStatementSource::FNQuads(qs) => {
-                Self::try_for_some_triple_adapted_from_rio_quad_source(
-                    qs,
-                    f,
-                    &self.quad_source_adapted_graph_iri,
-                )
-            }
-
-            InnerStatementSource::FTriG(qs) => {
-                Self::try_for_some_triple_adapted_from_rio_quad_source(
-                    qs,
-                    f,
-                    &self.quad_source_adapted_graph_iri,
-                )
-            }
-
-            InnerStatementSource::FNTriples(ts) => {
-                Self::try_for_some_triple_adapted_from_rio_triple_source(ts, f)
-            }
-
-            InnerStatementSource::FTurtle(ts) => {
-                Self::try_for_some_triple_adapted_from_rio_triple_source(ts, f)
-            }
-
-            InnerStatementSource::FRdfXml(ts) => {
-                Self::try_for_some_triple_adapted_from_rio_triple_source(ts, f)
+        let syntax_ = self.syntax_;
+        match with_syntax_stream_result(self.try_for_some_triple_untagged(f), syntax_) {
+            Err(StreamError::SourceError(e))
+                if self.lenient && self.supports_keep_going() && e.is_recoverable() =>
+            {
+                tracing::warn!("Skipping malformed statement: {}", e);
+                self.skip_summary.skipped.push(e.into());
+                Ok(true)
             }
+            r => r,
         }
     }
 }
@@ -0,0 +1,602 @@
+//! This module provides a streaming parser for [trix](crate::syntax::TRIX) documents. Dynsyn parsers use it for that syntax with `trix` feature.
+//!
+//! Each `<graph>` element of a document is read as a graph, that is named by it's leading `<uri>`, or `<id>` element, if any. Graphs without a name are read into default graph. Triples are emitted as soon as their `</triple>` end tag is read.
+//!
+//! Elements are matched by their local names. Relative iris are not resolved, as trix has no notion of base iri. Literals of `rdf:XMLLiteral` datatype are expected to have their markup escaped as text, as trix writers like that of rdf4j do.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::parser::trix::TrixParser;
+//! use sophia_api::{dataset::Dataset, parser::QuadParser, quad::stream::QuadSource};
+//! use sophia_inmem::dataset::FastDataset;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let doc = r#"<?xml version="1.0" encoding="utf-8"?>
+//! <TriX xmlns="http://www.w3.org/2004/03/trix/trix-1/">
+//!   <graph>
+//!     <uri>http://example.org/g</uri>
+//!     <triple>
+//!       <uri>http://example.org/alice</uri>
+//!       <uri>http://xmlns.com/foaf/0.1/name</uri>
+//!       <plainLiteral xml:lang="en">Alice</plainLiteral>
+//!     </triple>
+//!   </graph>
+//! </TriX>
+//! "#;
+//! let dataset: FastDataset = TrixParser::default().parse_str(doc).collect_quads()?;
+//! assert_eq!(dataset.quads().count(), 1);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::{
+    io::{self, BufRead},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use quick_xml::{events::Event, Reader};
+use rio_api::{
+    model::{BlankNode, GraphName, Literal, NamedNode, Quad, Subject, Term as RioTerm},
+    parser::{LineBytePosition, ParseError, QuadsParser},
+};
+use sophia_api::parser::QuadParser;
+use sophia_rio::parser::StrictRioSource;
+
+/// A trix parser.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TrixParser {}
+
+impl<B: BufRead> QuadParser<B> for TrixParser {
+    type Source = StrictRioSource<TrixQuadsParser<B>, TrixError>;
+
+    fn parse(&self, data: B) -> Self::Source {
+        StrictRioSource::Parser(TrixQuadsParser::new(data))
+    }
+}
+
+/// A rio quads parser for trix documents. It reads input as it goes, and emits quads of a triple on each step, that reads one.
+pub struct TrixQuadsParser<R: BufRead> {
+    reader: Reader<PositionedRead<R>>,
+    position: Arc<Position>,
+    buf: Vec<u8>,
+    state: State,
+    graph_name: Option<Term>,
+}
+
+/// Position of parser in element tree of a trix document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Before root `<TriX>` element.
+    Prolog,
+    /// Inside root element, between graphs.
+    Document,
+    /// Inside a `<graph>` element, before any of it's triples.
+    GraphStart,
+    /// Inside a `<graph>` element, after it's name, or a triple.
+    Graph,
+    /// After root element, or after an error.
+    End,
+}
+
+/// A term, as read from a term element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Term {
+    Iri(String),
+    BNode(String),
+    Literal {
+        value: String,
+        datatype: Option<String>,
+        lang: Option<String>,
+    },
+}
+
+/// An xml event, that is relevant to trix structure.
+enum XmlEvent {
+    Start {
+        name: String,
+        lang: Option<String>,
+        datatype: Option<String>,
+    },
+    End,
+    Text(String),
+    Eof,
+}
+
+impl<R: BufRead> TrixQuadsParser<R> {
+    /// Create a new parser, that reads from given `data`.
+    pub fn new(data: R) -> Self {
+        let position = Arc::new(Position::default());
+        let mut reader = Reader::from_reader(PositionedRead {
+            inner: data,
+            position: position.clone(),
+        });
+        reader.expand_empty_elements(true);
+        Self {
+            reader,
+            position,
+            buf: Vec::new(),
+            state: State::Prolog,
+            graph_name: None,
+        }
+    }
+
+    fn position(&self) -> LineBytePosition {
+        self.position.get()
+    }
+
+    fn syntax_error(&self, message: String) -> TrixError {
+        TrixError::Syntax {
+            message,
+            position: self.position(),
+        }
+    }
+
+    /// Read next event, that is relevant to trix structure. Declarations, comments, processing instructions, and doctype are skipped.
+    fn next_event(&mut self) -> Result<XmlEvent, TrixError> {
+        let mut buf = std::mem::take(&mut self.buf);
+        let event = self.read_event(&mut buf);
+        buf.clear();
+        self.buf = buf;
+        event
+    }
+
+    fn read_event(&mut self, buf: &mut Vec<u8>) -> Result<XmlEvent, TrixError> {
+        loop {
+            buf.clear();
+            return Ok(match self.reader.read_event(buf) {
+                Ok(Event::Start(e)) => {
+                    let mut lang = None;
+                    let mut datatype = None;
+                    for attr in e.attributes() {
+                        let attr = attr.map_err(|e| self.xml_error(e))?;
+                        let value = attr
+                            .unescape_and_decode_value(&self.reader)
+                            .map_err(|e| self.xml_error(e))?;
+                        match attr.key {
+                            b"xml:lang" => lang = Some(value),
+                            b"datatype" => datatype = Some(value),
+                            _ => {}
+                        }
+                    }
+                    XmlEvent::Start {
+                        name: String::from_utf8_lossy(e.local_name()).into_owned(),
+                        lang,
+                        datatype,
+                    }
+                }
+                Ok(Event::End(_)) => XmlEvent::End,
+                // Cdata content is escaped by the reader.
+                Ok(Event::Text(e)) | Ok(Event::CData(e)) => XmlEvent::Text(
+                    e.unescape_and_decode(&self.reader)
+                        .map_err(|e| self.xml_error(e))?,
+                ),
+                Ok(Event::Eof) => XmlEvent::Eof,
+                Ok(_) => continue,
+                Err(e) => return Err(self.xml_error(e)),
+            });
+        }
+    }
+
+    fn xml_error(&self, e: quick_xml::Error) -> TrixError {
+        match e {
+            quick_xml::Error::Io(e) => TrixError::Io(e),
+            quick_xml::Error::UnexpectedEof(_) => self.unexpected_eof(),
+            e => self.syntax_error(e.to_string()),
+        }
+    }
+
+    /// Read text content of a term element, whose start tag is just read.
+    fn read_text(&mut self, name: &str) -> Result<String, TrixError> {
+        let mut text = String::new();
+        loop {
+            match self.next_event()? {
+                XmlEvent::Text(t) => text.push_str(&t),
+                XmlEvent::End => return Ok(text),
+                XmlEvent::Start { name: child, .. } => {
+                    return Err(
+                        self.syntax_error(format!("unexpected element <{}> in <{}>", child, name))
+                    )
+                }
+                XmlEvent::Eof => return Err(self.unexpected_eof()),
+            }
+        }
+    }
+
+    /// Read a term element, whose start tag is just read.
+    fn read_term(
+        &mut self,
+        name: String,
+        lang: Option<String>,
+        datatype: Option<String>,
+    ) -> Result<Term, TrixError> {
+        match name.as_str() {
+            "uri" => Ok(Term::Iri(self.read_text(&name)?)),
+            "id" => Ok(Term::BNode(self.read_text(&name)?)),
+            "plainLiteral" => Ok(Term::Literal {
+                value: self.read_text(&name)?,
+                datatype: None,
+                lang,
+            }),
+            "typedLiteral" => match datatype {
+                Some(datatype) => Ok(Term::Literal {
+                    value: self.read_text(&name)?,
+                    datatype: Some(datatype),
+                    lang: None,
+                }),
+                None => Err(self.syntax_error("<typedLiteral> without datatype".into())),
+            },
+            _ => Err(self.unexpected_element(&name)),
+        }
+    }
+
+    /// Read terms of a triple element, whose start tag is just read.
+    fn read_triple(&mut self) -> Result<[Term; 3], TrixError> {
+        let mut terms = Vec::with_capacity(3);
+        loop {
+            match self.next_event()? {
+                XmlEvent::Start {
+                    name,
+                    lang,
+                    datatype,
+                } => terms.push(self.read_term(name, lang, datatype)?),
+                XmlEvent::End => break,
+                XmlEvent::Text(t) => self.skip_whitespace(&t)?,
+                XmlEvent::Eof => return Err(self.unexpected_eof()),
+            }
+        }
+        let terms: [Term; 3] = terms.try_into().map_err(|terms: Vec<Term>| {
+            self.syntax_error(format!("<triple> has {} terms, instead of 3", terms.len()))
+        })?;
+        if matches!(terms[0], Term::Literal { .. }) {
+            return Err(self.syntax_error("literal as subject of a triple".into()));
+        }
+        if !matches!(terms[1], Term::Iri(_)) {
+            return Err(self.syntax_error("predicate of a triple is not an iri".into()));
+        }
+        Ok(terms)
+    }
+
+    fn skip_whitespace(&self, text: &str) -> Result<(), TrixError> {
+        if text.trim().is_empty() {
+            Ok(())
+        } else {
+            Err(self.syntax_error(format!("unexpected text {:?}", text.trim())))
+        }
+    }
+
+    fn unexpected_element(&self, name: &str) -> TrixError {
+        self.syntax_error(format!("unexpected element <{}>", name))
+    }
+
+    fn unexpected_eof(&self) -> TrixError {
+        TrixError::UnexpectedEof(self.position())
+    }
+
+    /// Read events, until a triple is read, or document ends.
+    fn read_step(&mut self) -> Result<Option<[Term; 3]>, TrixError> {
+        loop {
+            match (self.state, self.next_event()?) {
+                (_, XmlEvent::Text(t)) => self.skip_whitespace(&t)?,
+                (State::Prolog, XmlEvent::Start { name, .. }) if name == "TriX" => {
+                    self.state = State::Document
+                }
+                (State::Document, XmlEvent::Start { name, .. }) if name == "graph" => {
+                    self.graph_name = None;
+                    self.state = State::GraphStart;
+                }
+                (State::Document, XmlEvent::End) => {
+                    self.state = State::End;
+                    return Ok(None);
+                }
+                (State::GraphStart, XmlEvent::Start { name, .. })
+                    if name == "uri" || name == "id" =>
+                {
+                    self.graph_name = Some(self.read_term(name, None, None)?);
+                    self.state = State::Graph;
+                }
+                (State::GraphStart | State::Graph, XmlEvent::Start { name, .. })
+                    if name == "triple" =>
+                {
+                    self.state = State::Graph;
+                    return self.read_triple().map(Some);
+                }
+                (State::GraphStart | State::Graph, XmlEvent::End) => self.state = State::Document,
+                (State::Prolog, XmlEvent::Eof) | (State::End, XmlEvent::Eof) => {
+                    self.state = State::End;
+                    return Ok(None);
+                }
+                (_, XmlEvent::Start { name, .. }) => return Err(self.unexpected_element(&name)),
+                (_, XmlEvent::Eof) => return Err(self.unexpected_eof()),
+                (_, XmlEvent::End) => return Err(self.syntax_error("unexpected end tag".into())),
+            }
+        }
+    }
+}
+
+impl<R: BufRead> QuadsParser for TrixQuadsParser<R> {
+    type Error = TrixError;
+
+    fn parse_step<E: From<Self::Error>>(
+        &mut self,
+        on_quad: &mut impl FnMut(Quad<'_>) -> Result<(), E>,
+    ) -> Result<(), E> {
+        let terms = match self.read_step() {
+            Ok(Some(terms)) => terms,
+            Ok(None) => {
+                // Consume trailing misc, so that content after root element is reported.
+                if let Err(e) = self.read_step() {
+                    self.state = State::End;
+                    return Err(e.into());
+                }
+                self.state = State::End;
+                self.buf.clear();
+                return Ok(());
+            }
+            Err(e) => {
+                self.state = State::End;
+                return Err(e.into());
+            }
+        };
+        let subject = match rio_term(&terms[0]) {
+            RioTerm::NamedNode(n) => Subject::NamedNode(n),
+            RioTerm::BlankNode(b) => Subject::BlankNode(b),
+            _ => unreachable!("literal subjects are rejected on read"),
+        };
+        let predicate = match rio_term(&terms[1]) {
+            RioTerm::NamedNode(n) => n,
+            _ => unreachable!("non-iri predicates are rejected on read"),
+        };
+        let graph_name = self.graph_name.as_ref().map(|g| match rio_term(g) {
+            RioTerm::BlankNode(b) => GraphName::BlankNode(b),
+            RioTerm::NamedNode(n) => GraphName::NamedNode(n),
+            _ => unreachable!("graph names are read only from uri, and id elements"),
+        });
+        on_quad(Quad {
+            subject,
+            predicate,
+            object: rio_term(&terms[2]),
+            graph_name,
+        })
+    }
+
+    fn is_end(&self) -> bool {
+        self.state == State::End
+    }
+}
+
+fn rio_term(term: &Term) -> RioTerm<'_> {
+    match term {
+        Term::Iri(iri) => RioTerm::NamedNode(NamedNode { iri }),
+        Term::BNode(id) => RioTerm::BlankNode(BlankNode { id }),
+        Term::Literal {
+            value,
+            datatype: Some(iri),
+            ..
+        } => RioTerm::Literal(Literal::Typed {
+            value,
+            datatype: NamedNode { iri },
+        }),
+        Term::Literal {
+            value,
+            lang: Some(language),
+            ..
+        } => RioTerm::Literal(Literal::LanguageTaggedString { value, language }),
+        Term::Literal { value, .. } => RioTerm::Literal(Literal::Simple { value }),
+    }
+}
+
+/// Line, and byte position of consumed input.
+#[derive(Debug, Default)]
+struct Position {
+    line: AtomicU64,
+    line_start: AtomicU64,
+    consumed: AtomicU64,
+}
+
+impl Position {
+    fn get(&self) -> LineBytePosition {
+        LineBytePosition::new(
+            self.line.load(Ordering::Relaxed) + 1,
+            self.consumed.load(Ordering::Relaxed) - self.line_start.load(Ordering::Relaxed) + 1,
+        )
+    }
+}
+
+/// A reader adaptor, that tracks position of consumed input, to locate errors.
+struct PositionedRead<R> {
+    inner: R,
+    position: Arc<Position>,
+}
+
+impl<R: io::Read> io::Read for PositionedRead<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl<R: BufRead> BufRead for PositionedRead<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        let consumed = self
+            .position
+            .consumed
+            .fetch_add(amt as u64, Ordering::Relaxed);
+        if let Ok(buf) = self.inner.fill_buf() {
+            for (i, &b) in buf[..amt.min(buf.len())].iter().enumerate() {
+                if b == b'\n' {
+                    self.position.line.fetch_add(1, Ordering::Relaxed);
+                    self.position
+                        .line_start
+                        .store(consumed + i as u64 + 1, Ordering::Relaxed);
+                }
+            }
+        }
+        self.inner.consume(amt);
+    }
+}
+
+/// An error in parsing a trix document.
+#[derive(Debug, thiserror::Error)]
+pub enum TrixError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("{message}, at line {} byte {}", .position.line_number(), .position.byte_number())]
+    Syntax {
+        message: String,
+        position: LineBytePosition,
+    },
+    #[error("unexpected end of document, at line {} byte {}", .0.line_number(), .0.byte_number())]
+    UnexpectedEof(LineBytePosition),
+}
+
+impl ParseError for TrixError {
+    fn textual_position(&self) -> Option<LineBytePosition> {
+        match self {
+            Self::Io(_) => None,
+            Self::Syntax { position, .. } | Self::UnexpectedEof(position) => Some(*position),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use rio_api::parser::ParseError;
+    use sophia_api::{
+        dataset::{isomorphic_datasets, Dataset},
+        parser::QuadParser,
+        quad::stream::QuadSource,
+    };
+    use sophia_inmem::dataset::FastDataset;
+    use sophia_turtle::parser::trig;
+    use test_case::test_case;
+
+    use super::TrixParser;
+    use crate::tests::TRACING;
+
+    static TRIX_DOC: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<!-- a comment -->
+<TriX xmlns="http://www.w3.org/2004/03/trix/trix-1/">
+  <graph>
+    <triple>
+      <uri>http://example.org/alice</uri>
+      <uri>http://xmlns.com/foaf/0.1/knows</uri>
+      <id>bob</id>
+    </triple>
+  </graph>
+  <graph>
+    <uri>http://example.org/g</uri>
+    <triple>
+      <id>bob</id>
+      <uri>http://xmlns.com/foaf/0.1/name</uri>
+      <plainLiteral xml:lang="en">Bob &amp; co</plainLiteral>
+    </triple>
+    <triple>
+      <id>bob</id>
+      <uri>http://xmlns.com/foaf/0.1/age</uri>
+      <typedLiteral datatype="http://www.w3.org/2001/XMLSchema#integer">42</typedLiteral>
+    </triple>
+    <triple>
+      <id>bob</id>
+      <uri>http://xmlns.com/foaf/0.1/nick</uri>
+      <plainLiteral/>
+    </triple>
+  </graph>
+  <graph>
+    <id>g2</id>
+    <triple>
+      <uri>http://example.org/alice</uri>
+      <uri>http://xmlns.com/foaf/0.1/nick</uri>
+      <plainLiteral><![CDATA[<al>]]></plainLiteral>
+    </triple>
+  </graph>
+</TriX>
+"#;
+
+    static TRIG_DOC: &str = r#"
+        @prefix foaf: <http://xmlns.com/foaf/0.1/> .
+        <http://example.org/alice> foaf:knows _:bob .
+        <http://example.org/g> {
+            _:bob foaf:name "Bob & co"@en ; foaf:age 42 ; foaf:nick "" .
+        }
+        _:g2 { <http://example.org/alice> foaf:nick "<al>" . }
+    "#;
+
+    #[test]
+    pub fn parses_graphs_and_terms() {
+        Lazy::force(&TRACING);
+        let d1: FastDataset = TrixParser::default()
+            .parse_str(TRIX_DOC)
+            .collect_quads()
+            .unwrap();
+        let d2: FastDataset = trig::parse_str(TRIG_DOC).collect_quads().unwrap();
+        assert_eq!(d1.quads().count(), 5);
+        assert!(isomorphic_datasets(&d1, &d2).unwrap());
+    }
+
+    #[test_case(
+        "<TriX><graph><triple><uri>tag:a</uri><uri>tag:b</uri></triple></graph></TriX>",
+        1,
+        63;
+        "missing term"
+    )]
+    #[test_case(
+        "<TriX><graph><triple><plainLiteral>a</plainLiteral><uri>tag:b</uri><uri>tag:c</uri></triple></graph></TriX>",
+        1,
+        93;
+        "literal subject"
+    )]
+    #[test_case(
+        "<TriX><graph><triple><uri>tag:a</uri><id>b</id><uri>tag:c</uri></triple></graph></TriX>",
+        1,
+        73;
+        "bnode predicate"
+    )]
+    #[test_case(
+        "<TriX>\n<graph>\n<triple><uri>tag:a</uri><uri>tag:b</uri><typedLiteral>1</typedLiteral>",
+        3,
+        55;
+        "typed literal without datatype"
+    )]
+    #[test_case("<TriX>\n<graph>\n<uri>tag:g</graph>", 3, 19; "mismatched end tag")]
+    #[test_case("<TriX>\n<graph>\n", 3, 1; "truncated document")]
+    #[test_case("<TriX>\n<graph>text</graph></TriX>", 2, 13; "stray text")]
+    #[test_case("<RDF></RDF>", 1, 6; "non trix root")]
+    #[test_case("<TriX></TriX>\n<TriX></TriX>", 2, 7; "multiple roots")]
+    pub fn malformed_docs_will_error(doc: &str, line: u64, byte: u64) {
+        Lazy::force(&TRACING);
+        let result: Result<FastDataset, _> = TrixParser::default().parse_str(doc).collect_quads();
+        let e = result
+            .err()
+            .expect("malformed doc must be rejected")
+            .unwrap_source_error();
+        let position = e.textual_position().expect("syntax errors have position");
+        assert_eq!(
+            (position.line_number(), position.byte_number()),
+            (line, byte)
+        );
+    }
+
+    #[test_case(""; "empty document")]
+    #[test_case("<TriX/>"; "empty root")]
+    #[test_case("<?xml version=\"1.0\"?>\n<TriX>\n  <graph/>\n</TriX>\n"; "empty graph")]
+    pub fn documents_without_triples_have_no_quads(doc: &str) {
+        Lazy::force(&TRACING);
+        let d: FastDataset = TrixParser::default()
+            .parse_str(doc)
+            .collect_quads()
+            .unwrap();
+        assert_eq!(d.quads().count(), 0);
+    }
+}
@@ -0,0 +1,281 @@
+//! This module defines [`DynSynValidator`], that parses documents purely to validate them, like in ci checks over ontology, and data files. Unlike parsing with a source, validation doesn't stop at first error. It collects every malformed statement with it's location into a [`ValidationReport`].
+//!
+//! Validation recovers from malformed statements in syntaxes, that support keep-going mode, like [`n-quads`](crate::syntax::N_QUADS), and [`n-triples`](crate::syntax::N_TRIPLES). See [`recovery`](super::recovery) module. In other syntaxes, and on errors that are not confined to a statement, like io errors, validation stops at the error, and report is marked as incomplete. Parse limits, and policies configured in factory's config map apply to validation too.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{parser::validate::DynSynValidator, syntax};
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let doc = r#"
+//! <http://ex.org/alice> <http://ex.org/knows> <http://ex.org/bob> .
+//! <http://ex.org/alice> <http://ex.org/name> "Alice" <http://ex.org/x> .
+//! <http://ex.org/bob> <http://ex.org/knows> <http://ex.org/alice> .
+//! <http://ex.org/bob> <http://ex.org/name> .
+//! "#;
+//! let report = DynSynValidator::default().validate(doc.as_bytes(), syntax::N_TRIPLES, None)?;
+//!
+//! assert!(!report.is_valid());
+//! assert!(report.complete);
+//! assert_eq!(report.statements, 2);
+//! let lines: Vec<_> = report.issues.iter().map(|issue| issue.line_number()).collect();
+//! assert_eq!(lines, vec![Some(3), Some(5)]);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::{
+    convert::Infallible,
+    fmt,
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+
+use sophia_api::{parser::QuadParser, triple::stream::StreamError};
+use sophia_term::BoxTerm;
+
+use super::{errors::DynSynParseError, quads::DynSynQuadParserFactory};
+use crate::{
+    convert::file_iri,
+    correspondence::{syntax_for_path, ForPathError},
+    error_code::{self, ErrorCode},
+    syntax::{RdfSyntax, UnKnownSyntaxError},
+};
+
+/// An error found in validating a document.
+#[derive(Debug)]
+pub struct ValidationIssue {
+    /// Error in parsing the document.
+    pub error: DynSynParseError,
+    /// True, if validation stopped at this error.
+    pub fatal: bool,
+}
+
+impl ValidationIssue {
+    /// Returns line number of the issue, starting from 1, if known.
+    pub fn line_number(&self) -> Option<u64> {
+        self.error.line_number()
+    }
+
+    /// Returns location of the issue as `(line, column)`, if known. See [`DynSynParseError::location`].
+    pub fn location(&self) -> Option<(u64, u64)> {
+        self.error.location()
+    }
+}
+
+impl ErrorCode for ValidationIssue {
+    fn code(&self) -> &'static str {
+        self.error.code()
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((line, column)) = self.location() {
+            write!(f, "{}:{}: ", line, column)?;
+        }
+        write!(f, "error[{}]: {}", self.code(), self.error)
+    }
+}
+
+/// Report of validating a document.
+#[derive(Debug)]
+pub struct ValidationReport {
+    /// Syntax, in which document is validated.
+    pub syntax: RdfSyntax,
+    /// Number of well-formed statements in the document.
+    pub statements: usize,
+    /// Errors found in the document, in document order.
+    pub issues: Vec<ValidationIssue>,
+    /// True, if whole document is validated. It is false, if validation stopped at a fatal issue, in which case later errors are not reported.
+    pub complete: bool,
+}
+
+impl ValidationReport {
+    /// Returns true, if no issue is found in the document.
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// An error in validating a file.
+#[derive(Debug, thiserror::Error)]
+pub enum ValidateFileError {
+    #[error(transparent)]
+    ForPath(#[from] ForPathError),
+    #[error("Io error in validating file {0:?}: {1}")]
+    Io(PathBuf, #[source] io::Error),
+}
+
+impl ErrorCode for ValidateFileError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::ForPath(e) => e.code(),
+            Self::Io(_, _) => error_code::DYNSYN_PARSE_IO,
+        }
+    }
+}
+
+/// Validates documents in any supported syntax, collecting all recoverable errors into a [`ValidationReport`].
+#[derive(Debug, Clone, Default)]
+pub struct DynSynValidator {
+    parser_factory: DynSynQuadParserFactory,
+}
+
+impl DynSynValidator {
+    /// Create a new validator, that parses documents with parsers from given factory.
+    pub fn new(parser_factory: DynSynQuadParserFactory) -> Self {
+        Self { parser_factory }
+    }
+
+    /// Validates document in `syntax_` from `data`. Relative iris in document are resolved against `base_iri`, if it is given.
+    ///
+    /// # Errors
+    /// returns [`UnKnownSyntaxError`] if syntax is not supported. Errors in the document are reported in returned report.
+    pub fn validate<R: BufRead>(
+        &self,
+        data: R,
+        syntax_: RdfSyntax,
+        base_iri: Option<String>,
+    ) -> Result<ValidationReport, UnKnownSyntaxError> {
+        let mut parser = self
+            .parser_factory
+            .try_new_parser::<BoxTerm>(syntax_, base_iri, None)?;
+        // Recover from malformed statements here, instead of in source, so that errors are owned by report.
+        parser.set_lenient(false);
+        let mut source = parser.parse(data);
+
+        let mut statements = 0;
+        let mut issues = Vec::new();
+        let mut complete = true;
+        loop {
+            match source.try_for_some_quad_scoped(&mut |_| {
+                statements += 1;
+                Ok::<_, Infallible>(())
+            }) {
+                Ok(true) => {}
+                Ok(false) => break,
+                Err(StreamError::SourceError(error)) => {
+                    let fatal = !(source.supports_keep_going() && error.is_recoverable());
+                    issues.push(ValidationIssue { error, fatal });
+                    if fatal {
+                        complete = false;
+                        break;
+                    }
+                }
+                Err(StreamError::SinkError(e)) => match e {},
+            }
+        }
+        Ok(ValidationReport {
+            syntax: syntax_,
+            statements,
+            issues,
+            complete,
+        })
+    }
+
+    /// Validates file at `path`, in syntax corresponding to it's file extension. Relative iris in file are resolved against it's `file:` iri.
+    ///
+    /// # Errors
+    /// returns [`ValidateFileError`] if syntax of file can't be determined, or if file can't be opened.
+    pub fn validate_file(&self, path: &Path) -> Result<ValidationReport, ValidateFileError> {
+        let syntax_ = syntax_for_path(path)?;
+        let data = File::open(path).map_err(|e| ValidateFileError::Io(path.to_owned(), e))?;
+        self.validate(BufReader::new(data), syntax_, file_iri(path))
+            .map_err(|e| ValidateFileError::ForPath(e.into()))
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use test_case::test_case;
+    use type_map::concurrent::TypeMap;
+
+    use super::DynSynValidator;
+    use crate::{
+        error_code::{self, ErrorCode},
+        parser::{quads::DynSynQuadParserFactory, recovery::ParserConfig},
+        syntax::{self, RdfSyntax},
+        tests::TRACING,
+    };
+
+    const MALFORMED_NQ: &str = r#"<http://ex.org/s> <http://ex.org/p> <http://ex.org/o> <http://ex.org/g> .
+<http://ex.org/s> <http://ex.org/p> <http://ex.org/o1> <http://ex.org/g> <http://ex.org/x> .
+<http://ex.org/s> <http://ex.org/p> <http://ex.org/o2> .
+<http://ex.org/s> <http://ex.org/p> .
+<http://ex.org/s> <http://ex.org/p> <http://ex.org/o3> .
+"#;
+
+    #[test_case(syntax::N_QUADS)]
+    #[test_case(syntax::TRIG)]
+    pub fn valid_documents_have_clean_reports(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let doc = "<http://ex.org/s> <http://ex.org/p> <http://ex.org/o> <http://ex.org/g> .\n";
+        let doc = match syntax_ {
+            syntax::TRIG => {
+                "<http://ex.org/g> { <http://ex.org/s> <http://ex.org/p> <http://ex.org/o> . }"
+            }
+            _ => doc,
+        };
+        let report = DynSynValidator::default()
+            .validate(doc.as_bytes(), syntax_, None)
+            .unwrap();
+        assert!(report.is_valid());
+        assert!(report.complete);
+        assert_eq!(report.statements, 1);
+    }
+
+    #[test_case(ParserConfig::default())]
+    #[test_case(ParserConfig::lenient())]
+    pub fn collects_all_recoverable_errors(config: ParserConfig) {
+        Lazy::force(&TRACING);
+        let mut config_map = TypeMap::new();
+        config_map.insert(config);
+        let report = DynSynValidator::new(DynSynQuadParserFactory::new(Some(config_map)))
+            .validate(MALFORMED_NQ.as_bytes(), syntax::N_QUADS, None)
+            .unwrap();
+
+        assert!(report.complete);
+        assert_eq!(report.statements, 3);
+        let lines: Vec<_> = report.issues.iter().map(|i| i.line_number()).collect();
+        assert_eq!(lines, vec![Some(2), Some(4)]);
+        assert!(report.issues.iter().all(|i| !i.fatal));
+        assert!(report
+            .issues
+            .iter()
+            .all(|i| i.code() == error_code::DYNSYN_PARSE_SYNTAX));
+        assert!(report.issues[0].to_string().starts_with("2:"));
+    }
+
+    #[test]
+    pub fn stops_at_first_error_in_syntaxes_without_keep_going() {
+        Lazy::force(&TRACING);
+        let doc = "@prefix : <http://ex.org/> .\n:s :p :o .\n:s :p .\n:s :p :o2 .\n";
+        let report = DynSynValidator::default()
+            .validate(doc.as_bytes(), syntax::TURTLE, None)
+            .unwrap();
+
+        assert!(!report.is_valid());
+        assert!(!report.complete);
+        assert_eq!(report.issues.len(), 1);
+        assert!(report.issues[0].fatal);
+        assert_eq!(report.issues[0].line_number(), Some(3));
+    }
+
+    #[test]
+    pub fn validating_in_unsupported_syntax_will_error() {
+        Lazy::force(&TRACING);
+        let e = DynSynValidator::default()
+            .validate(&b""[..], syntax::JSON_LD, None)
+            .unwrap_err();
+        assert_eq!(e.code(), error_code::DYNSYN_UNKNOWN_SYNTAX);
+    }
+}
@@ -0,0 +1,252 @@
+//! This module provides [`proptest`] strategies for generating random graphs/datasets, and a [`roundtrips`] property helper, that checks whether a dataset survives serialization into, and parsing back from given syntax. It is available only when `proptest` feature is enabled.
+//!
+//! Example:
+//!
+//! ```ignore
+//! use proptest::prelude::*;
+//! use rdf_dynsyn::{proptest_support::*, syntax};
+//!
+//! proptest! {
+//!     #[test]
+//!     fn trig_roundtrips(dataset in arb_dataset(16)) {
+//!         prop_assert!(roundtrips(syntax::TRIG, &dataset).is_ok());
+//!     }
+//! }
+//! ```
+
+use std::collections::HashSet;
+
+use proptest::{collection, option, prelude::*};
+use sophia_api::{
+    dataset::{isomorphic_datasets, Dataset},
+    graph::isomorphic_graphs,
+    ns::xsd,
+    parser::{QuadParser, TripleParser},
+    quad::stream::QuadSource,
+    serializer::{QuadSerializer, Stringifier, TripleSerializer},
+    triple::stream::TripleSource,
+};
+use sophia_term::BoxTerm;
+
+use crate::{
+    parser::{
+        quads::{source::TupleQuad, DynSynQuadParserFactory},
+        triples::{source::SliceTriple, DynSynTripleParserFactory},
+    },
+    serializer::{quads::DynSynQuadSerializerFactory, triples::DynSynTripleSerializerFactory},
+    error_code::{self, ErrorCode},
+    syntax::{RdfSyntax, UnKnownSyntaxError},
+};
+
+/// Namespace, in which generated iris are minted.
+pub static ARB_IRI_NS: &str = "http://example.org/ns/";
+
+/// A strategy that generates iri terms from a small vocabulary in [`ARB_IRI_NS`] namespace. Local names always start with a letter, so that generated iris can be abbreviated by all supported syntaxes.
+pub fn arb_iri() -> impl Strategy<Value = BoxTerm> {
+    "[a-z][a-z0-9]{0,3}"
+        .prop_map(|name| BoxTerm::new_iri(format!("{}{}", ARB_IRI_NS, name)).unwrap())
+}
+
+/// A strategy that generates blank-node terms from a small pool of labels, so that generated statements share blank-nodes often.
+pub fn arb_bnode() -> impl Strategy<Value = BoxTerm> {
+    (0..8_u8).prop_map(|i| BoxTerm::new_bnode(format!("b{}", i)).unwrap())
+}
+
+/// A strategy that generates plain, language-tagged, and `xsd:integer` typed literal terms. Lexical forms of string literals never start with whitespace, as rdf-xml parser doesn't preserve whitespace-only literals.
+pub fn arb_literal() -> impl Strategy<Value = BoxTerm> {
+    prop_oneof![
+        "([a-zA-Z0-9][a-zA-Z0-9 ]{0,11})?"
+            .prop_map(|v| BoxTerm::new_literal_dt(v, xsd::string).unwrap()),
+        ("([a-zA-Z0-9][a-zA-Z0-9 ]{0,11})?", "[a-z]{2}")
+            .prop_map(|(v, lang)| BoxTerm::new_literal_lang(v.as_str(), lang.as_str()).unwrap()),
+        any::<i32>().prop_map(|v| BoxTerm::new_literal_dt(v.to_string(), xsd::integer).unwrap()),
+    ]
+}
+
+/// A strategy that generates subject terms.
+pub fn arb_subject() -> impl Strategy<Value = BoxTerm> {
+    prop_oneof![arb_iri(), arb_bnode()]
+}
+
+/// A strategy that generates object terms.
+pub fn arb_object() -> impl Strategy<Value = BoxTerm> {
+    prop_oneof![arb_iri(), arb_bnode(), arb_literal()]
+}
+
+/// A strategy that generates triples.
+pub fn arb_triple() -> impl Strategy<Value = SliceTriple<BoxTerm>> {
+    (arb_subject(), arb_iri(), arb_object()).prop_map(|(s, p, o)| [s, p, o])
+}
+
+/// A strategy that generates quads. Graph name of generated quad will be either `None`, or an iri.
+pub fn arb_quad() -> impl Strategy<Value = TupleQuad<BoxTerm>> {
+    (arb_triple(), option::of(arb_iri()))
+}
+
+/// A strategy that generates graphs with at most `max_size` triples.
+pub fn arb_graph(max_size: usize) -> impl Strategy<Value = Vec<SliceTriple<BoxTerm>>> {
+    collection::vec(arb_triple(), 0..=max_size)
+}
+
+/// A strategy that generates datasets with at most `max_size` quads.
+pub fn arb_dataset(max_size: usize) -> impl Strategy<Value = Vec<TupleQuad<BoxTerm>>> {
+    collection::vec(arb_quad(), 0..=max_size)
+}
+
+/// An error that describes why a dataset failed to roundtrip.
+#[derive(Debug, thiserror::Error)]
+pub enum RoundtripError {
+    #[error(transparent)]
+    UnKnownSyntax(#[from] UnKnownSyntaxError),
+    #[error("Error in serializing dataset: {0}")]
+    Serialize(String),
+    #[error("Error in parsing back serialized doc: {0}\n{1}")]
+    Parse(String, String),
+    #[error("Parsed back dataset is not isomorphic to original dataset. serialized doc:\n{0}")]
+    NotIsomorphic(String),
+}
+
+impl ErrorCode for RoundtripError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::UnKnownSyntax(e) => e.code(),
+            Self::Serialize(_) => error_code::DYNSYN_ROUNDTRIP_SERIALIZE,
+            Self::Parse(_, _) => error_code::DYNSYN_ROUNDTRIP_PARSE,
+            Self::NotIsomorphic(_) => error_code::DYNSYN_ROUNDTRIP_NOT_ISOMORPHIC,
+        }
+    }
+}
+
+/// Checks whether `dataset` survives serialization into `syntax_` and parsing back, with default serializer configurations. See [`roundtrips_with`].
+pub fn roundtrips<D>(syntax_: RdfSyntax, dataset: &D) -> Result<(), RoundtripError>
+where
+    D: Dataset,
+{
+    roundtrips_with(
+        syntax_,
+        dataset,
+        &DynSynTripleSerializerFactory::new(None),
+        &DynSynQuadSerializerFactory::new(None),
+    )
+}
+
+/// Checks whether `dataset` survives serialization into `syntax_` using serializers from given factories, and parsing back. Parsed back dataset must be isomorphic to the original one.
+///
+/// If `syntax_` can represent quads, whole dataset will be checked. Otherwise only default graph of the dataset will be checked.
+pub fn roundtrips_with<D>(
+    syntax_: RdfSyntax,
+    dataset: &D,
+    triple_serializer_factory: &DynSynTripleSerializerFactory,
+    quad_serializer_factory: &DynSynQuadSerializerFactory,
+) -> Result<(), RoundtripError>
+where
+    D: Dataset,
+{
+    let d1: HashSet<TupleQuad<BoxTerm>> = dataset
+        .quads()
+        .collect_quads()
+        .map_err(|e| RoundtripError::Serialize(e.to_string()))?;
+
+    if let Ok(mut serializer) = quad_serializer_factory.try_new_stringifier(syntax_) {
+        let doc = serializer
+            .serialize_dataset(&d1)
+            .map_err(|e| RoundtripError::Serialize(e.to_string()))?
+            .to_string();
+        let d2: HashSet<TupleQuad<BoxTerm>> = DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax_, None, None)?
+            .parse_str(&doc)
+            .collect_quads()
+            .map_err(|e| RoundtripError::Parse(e.to_string(), doc.clone()))?;
+        return match isomorphic_datasets(&d1, &d2) {
+            Ok(true) => Ok(()),
+            _ => Err(RoundtripError::NotIsomorphic(doc)),
+        };
+    }
+
+    let g1 = d1.graph(None::<&BoxTerm>);
+    let doc = triple_serializer_factory
+        .try_new_stringifier(syntax_)
+        .map_err(UnKnownSyntaxError::from)?
+        .serialize_graph(&g1)
+        .map_err(|e| RoundtripError::Serialize(e.to_string()))?
+        .to_string();
+    let g2: HashSet<SliceTriple<BoxTerm>> = DynSynTripleParserFactory::default()
+        .try_new_parser::<BoxTerm>(syntax_, None, None)?
+        .parse_str(&doc)
+        .collect_triples()
+        .map_err(|e| RoundtripError::Parse(e.to_string(), doc.clone()))?;
+    match isomorphic_graphs(&g1, &g2) {
+        Ok(true) => Ok(()),
+        _ => Err(RoundtripError::NotIsomorphic(doc)),
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use proptest::prelude::*;
+    use sophia_turtle::serializer::{trig::TrigConfig, turtle::TurtleConfig};
+    use type_map::concurrent::TypeMap;
+
+    use crate::{syntax, tests::TRACING};
+
+    use super::*;
+
+    static PRETTY_TRIPLE_SERIALIZER_FACTORY: Lazy<DynSynTripleSerializerFactory> =
+        Lazy::new(|| {
+            let mut config_map = TypeMap::new();
+            config_map.insert::<TurtleConfig>(TurtleConfig::new().with_pretty(true));
+            DynSynTripleSerializerFactory::new(Some(config_map))
+        });
+
+    static PRETTY_QUAD_SERIALIZER_FACTORY: Lazy<DynSynQuadSerializerFactory> = Lazy::new(|| {
+        let mut config_map = TypeMap::new();
+        config_map.insert::<TrigConfig>(TrigConfig::new().with_pretty(true));
+        DynSynQuadSerializerFactory::new(Some(config_map))
+    });
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn datasets_roundtrip_through_quad_syntaxes(dataset in arb_dataset(24)) {
+            Lazy::force(&TRACING);
+            for syntax_ in [syntax::N_QUADS, syntax::TRIG] {
+                let r = roundtrips(syntax_, &dataset);
+                prop_assert!(r.is_ok(), "{:?}", r);
+            }
+        }
+
+        #[test]
+        fn graphs_roundtrip_through_triple_syntaxes(dataset in arb_dataset(24)) {
+            Lazy::force(&TRACING);
+            for syntax_ in [
+                syntax::N_TRIPLES,
+                syntax::TURTLE,
+                #[cfg(feature = "rdf-xml")]
+                syntax::RDF_XML,
+            ] {
+                let r = roundtrips(syntax_, &dataset);
+                prop_assert!(r.is_ok(), "{:?}", r);
+            }
+        }
+
+        #[test]
+        fn datasets_roundtrip_with_pretty_config(dataset in arb_dataset(24)) {
+            Lazy::force(&TRACING);
+            for syntax_ in [syntax::TURTLE, syntax::TRIG] {
+                let r = roundtrips_with(
+                    syntax_,
+                    &dataset,
+                    &PRETTY_TRIPLE_SERIALIZER_FACTORY,
+                    &PRETTY_QUAD_SERIALIZER_FACTORY,
+                );
+                prop_assert!(r.is_ok(), "{:?}", r);
+            }
+        }
+    }
+}
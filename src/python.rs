@@ -0,0 +1,233 @@
+//! This module provides python bindings through [PyO3](https://pyo3.rs), as a thin veneer over dynsyn parser, and serializer factories. Data engineers can convert large rdf dumps between syntaxes from python, much faster than with pure python libraries.
+//!
+//! Python module `rdf_dynsyn` exports two functions:
+//!
+//! - `parse(data: str | bytes, format: str, base_iri: str | None = None) -> list[tuple]` parses a document into a list of `(s, p, o, g)` tuples. `g` is `None` for statements in default graph.
+//! - `serialize(statements, format: str) -> str` serializes an iterable of `(s, p, o)`, or `(s, p, o, g)` tuples into a document. Serializing quads of named graphs into a triple syntax keeps only statements of default graph.
+//!
+//! Formats can be short names of syntaxes, like `turtle`, their media types, like `text/turtle`, or their spec iris. Terms are strings in n-triples syntax, like `<http://example.org/>`, `_:b0`, or `"chat"@fr`, which is also what rdflib's `from_n3` accepts. Errors are raised as `rdf_dynsyn.DynSynError`, with [error code](crate::error_code) of error prefixed to message.
+//!
+//! Build a python extension with [maturin](https://www.maturin.rs) using `python`, and `extension-module` features:
+//!
+//! ```text
+//! maturin build --release --features python,extension-module
+//! ```
+//!
+//! ```python
+//! import rdf_dynsyn
+//!
+//! quads = rdf_dynsyn.parse("@prefix : <http://example.org/> . :alice :knows :bob .", "turtle")
+//! assert quads == [("<http://example.org/alice>", "<http://example.org/knows>", "<http://example.org/bob>", None)]
+//! print(rdf_dynsyn.serialize(quads, "application/n-triples"))
+//! ```
+
+use pyo3::{create_exception, exceptions::PyValueError, prelude::*, types::PyBytes};
+use sophia_api::{
+    ns::xsd,
+    parser::QuadParser,
+    term::{term_eq, TTerm, TermKind},
+};
+use sophia_term::BoxTerm;
+
+use crate::{
+    convert::{convert, ConvertError, ConvertOptions},
+    error_code::ErrorCode,
+    parser::{errors::DynSynParseError, quads::DynSynQuadParserFactory},
+    syntax::{self, ParseSyntaxError, RdfSyntax, UnKnownSyntaxError},
+};
+
+/// A statement, with terms in n-triples syntax. Graph name is `None` for statements in default graph.
+pub type NtQuad = (String, String, String, Option<String>);
+
+/// An error in python bindings.
+#[derive(Debug, thiserror::Error)]
+pub enum PythonBindingError {
+    #[error(transparent)]
+    Syntax(#[from] ParseSyntaxError),
+    #[error(transparent)]
+    UnKnownSyntax(#[from] UnKnownSyntaxError),
+    #[error(transparent)]
+    Parse(#[from] DynSynParseError),
+    #[error(transparent)]
+    Convert(#[from] ConvertError),
+}
+
+impl ErrorCode for PythonBindingError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::Syntax(e) => e.code(),
+            Self::UnKnownSyntax(e) => e.code(),
+            Self::Parse(e) => e.code(),
+            Self::Convert(e) => e.code(),
+        }
+    }
+}
+
+create_exception!(rdf_dynsyn, DynSynError, PyValueError);
+
+impl From<PythonBindingError> for PyErr {
+    fn from(e: PythonBindingError) -> Self {
+        DynSynError::new_err(format!("{}: {}", e.code(), e))
+    }
+}
+
+/// Write given term in n-triples syntax.
+pub fn nt_term<T: TTerm + ?Sized>(t: &T) -> String {
+    match t.kind() {
+        TermKind::Iri => format!("<{}>", t.value()),
+        TermKind::BlankNode => format!("_:{}", t.value()),
+        TermKind::Variable => format!("?{}", t.value()),
+        TermKind::Literal => {
+            let mut lexical = String::from("\"");
+            for c in t.value().chars() {
+                match c {
+                    '"' => lexical.push_str("\\\""),
+                    '\\' => lexical.push_str("\\\\"),
+                    '\n' => lexical.push_str("\\n"),
+                    '\r' => lexical.push_str("\\r"),
+                    c => lexical.push(c),
+                }
+            }
+            lexical.push('"');
+            match (t.language(), t.datatype()) {
+                (Some(language), _) => format!("{}@{}", lexical, language),
+                (None, Some(dt)) if !term_eq(&dt, &xsd::string) => {
+                    format!("{}^^<{}>", lexical, dt.value())
+                }
+                _ => lexical,
+            }
+        }
+    }
+}
+
+/// Parse document in `data` in syntax `format` into statements, with terms in n-triples syntax.
+pub fn parse_statements(
+    data: &[u8],
+    format: &str,
+    base_iri: Option<String>,
+) -> Result<Vec<NtQuad>, PythonBindingError> {
+    let syntax_: RdfSyntax = format.parse()?;
+    let parser =
+        DynSynQuadParserFactory::default().try_new_parser::<BoxTerm>(syntax_, base_iri, None)?;
+    let mut statements = Vec::new();
+    parser.parse(data).for_each_quad_scoped(|([s, p, o], g)| {
+        statements.push((nt_term(s), nt_term(p), nt_term(o), g.map(nt_term)));
+    })?;
+    Ok(statements)
+}
+
+/// Serialize given statements, with terms in n-triples syntax, into a document in syntax `format`.
+pub fn serialize_statements(
+    statements: &[NtQuad],
+    format: &str,
+) -> Result<String, PythonBindingError> {
+    let syntax_: RdfSyntax = format.parse()?;
+    let mut doc = String::new();
+    for (s, p, o, g) in statements {
+        doc.push_str(&format!("{} {} {}", s, p, o));
+        if let Some(g) = g {
+            doc.push(' ');
+            doc.push_str(g);
+        }
+        doc.push_str(" .\n");
+    }
+    let mut out = Vec::new();
+    convert(
+        doc.as_bytes(),
+        syntax::N_QUADS,
+        &mut out,
+        syntax_,
+        &ConvertOptions::default(),
+    )?;
+    Ok(String::from_utf8(out).expect("serializers write utf-8"))
+}
+
+/// Parse document in `data` in syntax `format` into a list of `(s, p, o, g)` tuples.
+#[pyfunction]
+#[pyo3(signature = (data, format, base_iri = None))]
+fn parse(data: &PyAny, format: &str, base_iri: Option<String>) -> PyResult<Vec<NtQuad>> {
+    let data = match data.downcast::<PyBytes>() {
+        Ok(bytes) => bytes.as_bytes(),
+        Err(_) => data.extract::<&str>()?.as_bytes(),
+    };
+    Ok(parse_statements(data, format, base_iri)?)
+}
+
+/// Serialize an iterable of `(s, p, o)`, or `(s, p, o, g)` tuples into a document in syntax `format`.
+#[pyfunction]
+fn serialize(statements: &PyAny, format: &str) -> PyResult<String> {
+    let statements = statements
+        .iter()?
+        .map(|item| {
+            let item = item?;
+            match item.extract::<(String, String, String)>() {
+                Ok((s, p, o)) => Ok((s, p, o, None)),
+                Err(_) => item.extract::<NtQuad>(),
+            }
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(serialize_statements(&statements, format)?)
+}
+
+/// Python module `rdf_dynsyn`.
+#[pymodule]
+fn rdf_dynsyn(py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(parse, m)?)?;
+    m.add_function(wrap_pyfunction!(serialize, m)?)?;
+    m.add("DynSynError", py.get_type::<DynSynError>())?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use test_case::test_case;
+
+    use super::{parse_statements, serialize_statements};
+    use crate::{
+        error_code::{self, ErrorCode},
+        tests::TRACING,
+    };
+
+    #[test]
+    pub fn parses_statements_with_nt_terms() {
+        Lazy::force(&TRACING);
+        let doc = r#"
+            @prefix : <http://example.org/> .
+            :alice :name "Alice \"A\"", "Alicia"@es ; :age 42 .
+            :g { :alice :knows [] . }
+        "#;
+        let statements = parse_statements(doc.as_bytes(), "trig", None).unwrap();
+        let terms: Vec<_> = statements.iter().map(|q| q.2.as_str()).collect();
+        assert!(terms.contains(&r#""Alice \"A\"""#));
+        assert!(terms.contains(&r#""Alicia"@es"#));
+        assert!(terms.contains(&r#""42"^^<http://www.w3.org/2001/XMLSchema#integer>"#));
+        assert_eq!(statements.iter().filter(|q| q.3.is_some()).count(), 1);
+        assert!(statements.iter().any(|q| q.2.starts_with("_:")));
+    }
+
+    #[test_case("n-quads")]
+    #[test_case("application/trig")]
+    pub fn round_trips_statements(format: &str) {
+        Lazy::force(&TRACING);
+        let doc = r#"<http://ex.org/s> <http://ex.org/p> "o\nx"@en <http://ex.org/g> ."#;
+        let statements = parse_statements(doc.as_bytes(), "n-quads", None).unwrap();
+        let serialized = serialize_statements(&statements, format).unwrap();
+        let reparsed = parse_statements(serialized.as_bytes(), format, None).unwrap();
+        assert_eq!(reparsed, statements);
+    }
+
+    #[test_case("json-ld", error_code::DYNSYN_UNKNOWN_SYNTAX)]
+    #[test_case("text/plain", error_code::DYNSYN_UNKNOWN_SYNTAX)]
+    #[test_case("turtle", error_code::DYNSYN_PARSE_SYNTAX)]
+    pub fn parsing_statements_will_error(format: &str, expected_code: &str) {
+        Lazy::force(&TRACING);
+        let e =
+            parse_statements(b"<http://ex.org/a> <http://ex.org/b> .", format, None).unwrap_err();
+        assert_eq!(e.code(), expected_code);
+    }
+}
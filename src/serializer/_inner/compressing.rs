@@ -0,0 +1,150 @@
+//! This module provides a writer adaptor, that compresses each serialized document into a frame of configured codec.
+
+use std::{
+    fmt::Debug,
+    io::{self, Write},
+};
+
+#[cfg(any(test, feature = "compression"))]
+use crate::compression::{Compression, CompressionConfig};
+
+/// State of a [`CompressingWrite`]. Between documents, it is always `Plain`.
+enum Frame<W: Write> {
+    Plain(W),
+    #[cfg(any(test, feature = "compression"))]
+    Gzip(flate2::write::GzEncoder<W>),
+    #[cfg(any(test, feature = "compression"))]
+    Bzip2(bzip2::write::BzEncoder<W>),
+    #[cfg(any(test, feature = "compression"))]
+    Xz(xz2::write::XzEncoder<W>),
+    #[cfg(any(test, feature = "compression"))]
+    Zstd(zstd::stream::write::Encoder<'static, W>),
+}
+
+/// A writer adaptor, that compresses data written between [`start_frame`](Self::start_frame), and [`finish_frame`](Self::finish_frame) calls into a complete frame of configured codec. If no codec is configured, it writes through as is.
+pub(crate) struct CompressingWrite<W: Write> {
+    /// It is `None` only if finishing a frame failed.
+    frame: Option<Frame<W>>,
+    #[cfg(any(test, feature = "compression"))]
+    config: CompressionConfig,
+}
+
+impl<W: Write> Debug for CompressingWrite<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut s = f.debug_struct("CompressingWrite");
+        #[cfg(any(test, feature = "compression"))]
+        s.field("config", &self.config);
+        s.finish()
+    }
+}
+
+fn broken() -> io::Error {
+    io::Error::other("compressed output is broken by an earlier error")
+}
+
+impl<W: Write> CompressingWrite<W> {
+    pub(crate) fn new(write: W) -> Self {
+        Self {
+            frame: Some(Frame::Plain(write)),
+            #[cfg(any(test, feature = "compression"))]
+            config: CompressionConfig::default(),
+        }
+    }
+
+    /// Configure compression of subsequent frames.
+    #[cfg(any(test, feature = "compression"))]
+    pub(crate) fn set_config(&mut self, config: CompressionConfig) {
+        self.config = config;
+    }
+
+    /// Get reference to underlying writer. It returns `None`, while a frame is being written, or if output is broken.
+    pub(crate) fn get_ref(&self) -> Option<&W> {
+        match self.frame.as_ref()? {
+            Frame::Plain(w) => Some(w),
+            #[cfg(any(test, feature = "compression"))]
+            _ => None,
+        }
+    }
+
+    /// Start a new frame. Subsequent writes are compressed into it, till it is finished.
+    pub(crate) fn start_frame(&mut self) -> io::Result<()> {
+        #[cfg(any(test, feature = "compression"))]
+        if let Some(compression) = self.config.compression {
+            let write = match self.frame.take() {
+                Some(Frame::Plain(write)) => write,
+                frame => {
+                    self.frame = frame;
+                    return Ok(());
+                }
+            };
+            let level = self.config.level;
+            self.frame = Some(match compression {
+                Compression::Gzip => Frame::Gzip(flate2::write::GzEncoder::new(
+                    write,
+                    level.map(flate2::Compression::new).unwrap_or_default(),
+                )),
+                Compression::Bzip2 => Frame::Bzip2(bzip2::write::BzEncoder::new(
+                    write,
+                    level.map(bzip2::Compression::new).unwrap_or_default(),
+                )),
+                Compression::Xz => Frame::Xz(xz2::write::XzEncoder::new(write, level.unwrap_or(6))),
+                Compression::Zstd => Frame::Zstd(zstd::stream::write::Encoder::new(
+                    write,
+                    level.map_or(0, |l| l as i32),
+                )?),
+            });
+        }
+        Ok(())
+    }
+
+    /// Finish current frame, if any, writing it's trailer.
+    #[cfg_attr(
+        not(any(test, feature = "compression")),
+        allow(clippy::infallible_destructuring_match)
+    )]
+    pub(crate) fn finish_frame(&mut self) -> io::Result<()> {
+        let write = match self.frame.take().ok_or_else(broken)? {
+            Frame::Plain(write) => write,
+            #[cfg(any(test, feature = "compression"))]
+            Frame::Gzip(e) => e.finish()?,
+            #[cfg(any(test, feature = "compression"))]
+            Frame::Bzip2(e) => e.finish()?,
+            #[cfg(any(test, feature = "compression"))]
+            Frame::Xz(e) => e.finish()?,
+            #[cfg(any(test, feature = "compression"))]
+            Frame::Zstd(e) => e.finish()?,
+        };
+        self.frame = Some(Frame::Plain(write));
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for CompressingWrite<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.frame.as_mut().ok_or_else(broken)? {
+            Frame::Plain(w) => w.write(buf),
+            #[cfg(any(test, feature = "compression"))]
+            Frame::Gzip(w) => w.write(buf),
+            #[cfg(any(test, feature = "compression"))]
+            Frame::Bzip2(w) => w.write(buf),
+            #[cfg(any(test, feature = "compression"))]
+            Frame::Xz(w) => w.write(buf),
+            #[cfg(any(test, feature = "compression"))]
+            Frame::Zstd(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.frame.as_mut().ok_or_else(broken)? {
+            Frame::Plain(w) => w.flush(),
+            #[cfg(any(test, feature = "compression"))]
+            Frame::Gzip(w) => w.flush(),
+            #[cfg(any(test, feature = "compression"))]
+            Frame::Bzip2(w) => w.flush(),
+            #[cfg(any(test, feature = "compression"))]
+            Frame::Xz(w) => w.flush(),
+            #[cfg(any(test, feature = "compression"))]
+            Frame::Zstd(w) => w.flush(),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! This module defines a statement source wrapper, that counts statements pulled from underlying source.
+
+use std::error::Error;
+
+use sophia_api::{
+    quad::{stream::QuadSource, streaming_mode::StreamedQuad},
+    triple::{
+        stream::{StreamResult, TripleSource},
+        streaming_mode::StreamedTriple,
+    },
+};
+
+/// A statement source, that delegates to underlying source, and counts statements, that are successfully handled by sink. Underlying source is borrowed, so that callers can inspect it, once it is consumed.
+pub(crate) struct Counted<'c, S> {
+    source: &'c mut S,
+    count: &'c mut usize,
+}
+
+impl<'c, S> Counted<'c, S> {
+    pub(crate) fn new(source: &'c mut S, count: &'c mut usize) -> Self {
+        Self { source, count }
+    }
+}
+
+impl<'c, TS: TripleSource> TripleSource for Counted<'c, TS> {
+    type Error = TS::Error;
+
+    type Triple = TS::Triple;
+
+    fn try_for_some_triple<F, E>(&mut self, f: &mut F) -> StreamResult<bool, Self::Error, E>
+    where
+        F: FnMut(StreamedTriple<Self::Triple>) -> Result<(), E>,
+        E: Error,
+    {
+        let count = &mut *self.count;
+        self.source.try_for_some_triple(&mut |t| {
+            f(t)?;
+            *count += 1;
+            Ok(())
+        })
+    }
+
+    fn size_hint_triples(&self) -> (usize, Option<usize>) {
+        self.source.size_hint_triples()
+    }
+}
+
+impl<'c, QS: QuadSource> QuadSource for Counted<'c, QS> {
+    type Error = QS::Error;
+
+    type Quad = QS::Quad;
+
+    fn try_for_some_quad<F, E>(&mut self, f: &mut F) -> StreamResult<bool, Self::Error, E>
+    where
+        F: FnMut(StreamedQuad<Self::Quad>) -> Result<(), E>,
+        E: Error,
+    {
+        let count = &mut *self.count;
+        self.source.try_for_some_quad(&mut |q| {
+            f(q)?;
+            *count += 1;
+            Ok(())
+        })
+    }
+
+    fn size_hint_quads(&self) -> (usize, Option<usize>) {
+        self.source.size_hint_quads()
+    }
+}
@@ -1,38 +1,86 @@
-use std::{fmt::Debug, io};
+use std::fmt::Debug;
 
 use sophia_turtle::serializer::{
-    nq::NqSerializer, nt::NtSerializer, trig::TrigSerializer, turtle::TurtleSerializer,
+    nq::NqConfig, nt::NtConfig, trig::TrigConfig, turtle::TurtleConfig,
 };
-use sophia_xml::serializer::RdfXmlSerializer;
+#[cfg(feature = "rdf-xml")]
+use sophia_xml::serializer::RdfXmlConfig;
 
-/// This is a sum-type that wraps around different quad-serializers from sophia.
-pub(crate) enum InnerQuadSerializer<W: io::Write> {
-    NQuads(NqSerializer<W>),
-    Trig(TrigSerializer<W>),
+#[cfg(any(test, feature = "canon"))]
+use super::canon::CanonConfig;
+use super::escape::EscapeStyle;
+
+pub mod compressing;
+pub mod counted;
+
+/// This is a sum-type that wraps around configurations of different quad-serializers from sophia. Serializers are instantiated against dynsyn serializer's writer for each serialization.
+pub(crate) enum InnerQuadSerializer {
+    NQuads(NqConfig),
+    #[cfg(any(test, feature = "canon"))]
+    CanonicalNQuads(CanonConfig),
+    Trig(TrigConfig),
+    #[cfg(feature = "trix")]
+    Trix,
+    NdRdfJson,
+}
+
+impl InnerQuadSerializer {
+    /// Get escaping convention of serializer's output, if it has to be escaped after it is written. Syntaxes, that dynsyn writes by itself, are escaped while writing terms instead.
+    pub(crate) fn escape_style(&self) -> Option<EscapeStyle> {
+        match self {
+            Self::Trig(_) => Some(EscapeStyle::Turtle),
+            _ => None,
+        }
+    }
 }
 
-impl<W: io::Write> Debug for InnerQuadSerializer<W> {
+impl Debug for InnerQuadSerializer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::NQuads(_) => f.debug_tuple("NQuads").finish(),
+            #[cfg(any(test, feature = "canon"))]
+            Self::CanonicalNQuads(c) => f.debug_tuple("CanonicalNQuads").field(c).finish(),
             Self::Trig(_) => f.debug_tuple("Trig").finish(),
+            #[cfg(feature = "trix")]
+            Self::Trix => f.debug_tuple("Trix").finish(),
+            Self::NdRdfJson => f.debug_tuple("NdRdfJson").finish(),
         }
     }
 }
 
-/// This is a sum-type that wraps around different triple-serializers from sophia.
-pub(crate) enum InnerTripleSerializer<W: io::Write> {
-    NTriples(NtSerializer<W>),
-    Turtle(TurtleSerializer<W>),
-    RdfXml(RdfXmlSerializer<W>),
+/// This is a sum-type that wraps around configurations of different triple-serializers from sophia. Serializers are instantiated against dynsyn serializer's writer for each serialization.
+pub(crate) enum InnerTripleSerializer {
+    NTriples(NtConfig),
+    Turtle(TurtleConfig),
+    #[cfg(feature = "rdf-xml")]
+    RdfXml(RdfXmlConfig),
+    #[cfg(feature = "trix")]
+    Trix,
+    NdRdfJson,
+}
+
+impl InnerTripleSerializer {
+    /// Get escaping convention of serializer's output, if it has to be escaped after it is written. Syntaxes, that dynsyn writes by itself, are escaped while writing terms instead.
+    pub(crate) fn escape_style(&self) -> Option<EscapeStyle> {
+        match self {
+            Self::Turtle(_) => Some(EscapeStyle::Turtle),
+            #[cfg(feature = "rdf-xml")]
+            Self::RdfXml(_) => Some(EscapeStyle::Xml),
+            _ => None,
+        }
+    }
 }
 
-impl<W: io::Write> Debug for InnerTripleSerializer<W> {
+impl Debug for InnerTripleSerializer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::NTriples(_) => f.debug_tuple("NTriples").finish(),
             Self::Turtle(_) => f.debug_tuple("Turtle").finish(),
+            #[cfg(feature = "rdf-xml")]
             Self::RdfXml(_) => f.debug_tuple("RdfXml").finish(),
+            #[cfg(feature = "trix")]
+            Self::Trix => f.debug_tuple("Trix").finish(),
+            Self::NdRdfJson => f.debug_tuple("NdRdfJson").finish(),
         }
     }
 }
@@ -0,0 +1,341 @@
+//! This module provides [`AnySerializer`], a dynsyn serializer with erased writer type, that can serialize either triples, or quads. Sophia's serializer traits have generic methods, and hence cannot be made into trait objects. Instead, serializers here write into a [`BoxedWrite`], so that downstream code can store heterogeneous serializers in collections, without being generic over writer type.
+//!
+//! Example:
+//!
+//! ```
+//! use std::collections::HashMap;
+//!
+//! use rdf_dynsyn::{
+//!     serializer::{any::AnySerializer, serializable::DynSynSerializerFactories},
+//!     syntax::{self, RdfSyntax},
+//! };
+//! use sophia_term::StaticTerm;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = DynSynSerializerFactories::default();
+//! let mut sinks: HashMap<RdfSyntax, AnySerializer> = HashMap::new();
+//! sinks.insert(
+//!     syntax::TURTLE,
+//!     AnySerializer::try_new(syntax::TURTLE, Box::new(std::io::sink()), &config)?,
+//! );
+//! sinks.insert(
+//!     syntax::N_QUADS,
+//!     AnySerializer::try_new(syntax::N_QUADS, Box::new(std::io::stderr()), &config)?,
+//! );
+//!
+//! let me = StaticTerm::new_iri("http://example.org/#me")?;
+//! for serializer in sinks.values_mut() {
+//!     serializer.serialize_graph(&vec![[me, me, me]])?;
+//! }
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::{convert::Infallible, io};
+
+use mime::Mime;
+use sophia_api::{
+    dataset::Dataset,
+    graph::Graph,
+    quad::stream::QuadSource,
+    serializer::{QuadSerializer, TripleSerializer},
+    triple::stream::{StreamError, StreamResult, TripleSource},
+};
+use sophia_term::BoxTerm;
+
+use crate::syntax::{RdfSyntax, UnKnownSyntaxError};
+
+use super::{
+    errors::SerializerRole,
+    quads::DynSynQuadSerializer,
+    serializable::{
+        DynSynSerializeError, DynSynSerializerFactories, GraphTriples, NamedGraphAction,
+    },
+    triples::DynSynTripleSerializer,
+};
+
+/// A type-erased writer, that dynsyn serializers can write into.
+pub type BoxedWrite = Box<dyn io::Write + Send>;
+
+/// A [`DynSynTripleSerializer`] with erased writer type.
+pub type BoxedTripleSerializer = DynSynTripleSerializer<BoxedWrite>;
+
+/// A [`DynSynQuadSerializer`] with erased writer type.
+pub type BoxedQuadSerializer = DynSynQuadSerializer<BoxedWrite>;
+
+/// A dynsyn serializer with erased writer type, that serializes either triples, or quads, depending on the syntax it is created for.
+///
+/// It can serialize both triple-sources, and quad-sources irrespective of it's role, with same semantics as of [`DynSynSerializable`](super::serializable::DynSynSerializable): triples are serialized into quad-only syntaxes as quads in default graph, and quads are serialized into triple-only syntaxes as triples, with quads in named graphs handled as per [`NamedGraphAction`]. With [`NamedGraphAction::Error`], a quad in a named graph fails serialization with an [`io::Error`] of kind [`InvalidData`](io::ErrorKind::InvalidData), that wraps a [`DynSynSerializeError::NamedGraph`].
+pub enum AnySerializer {
+    Triples {
+        /// Underlying triple serializer.
+        serializer: BoxedTripleSerializer,
+        /// Action to take on quads in named graphs, that are serialized through this serializer.
+        named_graph_action: NamedGraphAction,
+    },
+    Quads(BoxedQuadSerializer),
+}
+
+impl std::fmt::Debug for AnySerializer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnySerializer")
+            .field("role", &self.role())
+            .field("content_type", self.content_type())
+            .finish()
+    }
+}
+
+impl AnySerializer {
+    /// Try to create a new [`AnySerializer`] for given `syntax_`, writing to `write`, with serializers from given `config`. Quad serializer is preferred for syntaxes, that can be serialized from both triples, and quads.
+    ///
+    /// # Errors
+    /// returns [`UnKnownSyntaxError`] if `syntax_` is not supported by any of serializers.
+    pub fn try_new(
+        syntax_: RdfSyntax,
+        write: BoxedWrite,
+        config: &DynSynSerializerFactories,
+    ) -> Result<Self, UnKnownSyntaxError> {
+        // Check support up front, as writer is moved into whichever serializer is created.
+        if config
+            .quad_serializer_factory
+            .content_type(syntax_)
+            .is_ok()
+        {
+            return Ok(Self::Quads(
+                config
+                    .quad_serializer_factory
+                    .try_new_serializer(syntax_, write)
+                    .map_err(UnKnownSyntaxError::from)?,
+            ));
+        }
+        Ok(Self::Triples {
+            serializer: config
+                .triple_serializer_factory
+                .try_new_serializer(syntax_, write)
+                .map_err(UnKnownSyntaxError::from)?,
+            named_graph_action: config.named_graph_action,
+        })
+    }
+
+    /// Get role of underlying serializer.
+    pub fn role(&self) -> SerializerRole {
+        match self {
+            Self::Triples { .. } => SerializerRole::Triples,
+            Self::Quads(_) => SerializerRole::Quads,
+        }
+    }
+
+    /// Returns content type of documents produced by this serializer.
+    pub fn content_type(&self) -> &Mime {
+        match self {
+            Self::Triples { serializer, .. } => serializer.content_type(),
+            Self::Quads(s) => s.content_type(),
+        }
+    }
+
+    /// Serializes all triples from `source`.
+    pub fn serialize_triples<TS>(&mut self, source: TS) -> StreamResult<(), TS::Error, io::Error>
+    where
+        TS: TripleSource,
+    {
+        match self {
+            Self::Triples { serializer, .. } => serializer.serialize_triples(source).map(|_| ()),
+            Self::Quads(s) => s
+                .serialize_triples_in_graph(source, None::<&BoxTerm>)
+                .map(|_| ()),
+        }
+    }
+
+    /// Serializes all quads from `source`.
+    pub fn serialize_quads<QS>(&mut self, source: QS) -> StreamResult<(), QS::Error, io::Error>
+    where
+        QS: QuadSource,
+    {
+        match self {
+            Self::Quads(s) => s.serialize_quads(source).map(|_| ()),
+            Self::Triples {
+                serializer,
+                named_graph_action,
+            } => serializer
+                .serialize_triples(GraphTriples::new(source, *named_graph_action))
+                .map(|_| ())
+                .map_err(|e| match e {
+                    StreamError::SourceError(DynSynSerializeError::Source(e)) => {
+                        StreamError::SourceError(e)
+                    }
+                    StreamError::SourceError(DynSynSerializeError::NamedGraph(g)) => {
+                        StreamError::SinkError(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            DynSynSerializeError::<Infallible>::NamedGraph(g),
+                        ))
+                    }
+                    StreamError::SourceError(e) => {
+                        StreamError::SinkError(io::Error::other(e.to_string()))
+                    }
+                    StreamError::SinkError(e) => StreamError::SinkError(e),
+                }),
+        }
+    }
+
+    /// Serializes given `graph`.
+    pub fn serialize_graph<G>(&mut self, graph: &G) -> StreamResult<(), G::Error, io::Error>
+    where
+        G: Graph,
+    {
+        self.serialize_triples(graph.triples())
+    }
+
+    /// Serializes given `dataset`.
+    pub fn serialize_dataset<D>(&mut self, dataset: &D) -> StreamResult<(), D::Error, io::Error>
+    where
+        D: Dataset,
+    {
+        self.serialize_quads(dataset.quads())
+    }
+}
+
+/// Converts into a serializer, that errs on quads in named graphs.
+impl From<BoxedTripleSerializer> for AnySerializer {
+    fn from(serializer: BoxedTripleSerializer) -> Self {
+        Self::Triples {
+            serializer,
+            named_graph_action: NamedGraphAction::Error,
+        }
+    }
+}
+
+impl From<BoxedQuadSerializer> for AnySerializer {
+    fn from(s: BoxedQuadSerializer) -> Self {
+        Self::Quads(s)
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        convert::Infallible,
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    use once_cell::sync::Lazy;
+    use sophia_api::{
+        dataset::isomorphic_datasets, parser::QuadParser, quad::stream::QuadSource,
+        triple::stream::StreamError,
+    };
+    use sophia_inmem::dataset::FastDataset;
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+
+    use crate::{
+        fixtures::TESTS_TRIG,
+        parser::quads::DynSynQuadParserFactory,
+        serializer::{
+            errors::SerializerRole,
+            serializable::{DynSynSerializeError, DynSynSerializerFactories, NamedGraphAction},
+        },
+        syntax::{self, RdfSyntax},
+        tests::TRACING,
+    };
+
+    use super::AnySerializer;
+
+    /// A writer, that can be boxed, and read back after serialization.
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test_case(syntax::TURTLE, SerializerRole::Triples)]
+    #[test_case(syntax::N_TRIPLES, SerializerRole::Triples)]
+    #[test_case(syntax::TRIG, SerializerRole::Quads)]
+    #[test_case(syntax::N_QUADS, SerializerRole::Quads)]
+    #[test_case(syntax::ND_RDF_JSON, SerializerRole::Quads)]
+    pub fn picks_role_by_syntax(syntax_: RdfSyntax, expected: SerializerRole) {
+        Lazy::force(&TRACING);
+        let serializer = AnySerializer::try_new(
+            syntax_,
+            Box::new(io::sink()),
+            &DynSynSerializerFactories::default(),
+        )
+        .unwrap();
+        assert_eq!(serializer.role(), expected);
+    }
+
+    #[test]
+    pub fn errors_for_unsupported_syntax() {
+        Lazy::force(&TRACING);
+        assert!(AnySerializer::try_new(
+            syntax::JSON_LD,
+            Box::new(io::sink()),
+            &DynSynSerializerFactories::default(),
+        )
+        .is_err());
+    }
+
+    #[test]
+    pub fn heterogeneous_serializers_write_through_boxed_writers() {
+        Lazy::force(&TRACING);
+        let parser = DynSynQuadParserFactory::default()
+            .try_new_parser(syntax::TRIG, None, None as Option<BoxTerm>)
+            .unwrap();
+        let d1: FastDataset = parser.parse_str(TESTS_TRIG[1]).collect_quads().unwrap();
+
+        let config =
+            DynSynSerializerFactories::default().with_named_graph_action(NamedGraphAction::Merge);
+        let (trig_buf, turtle_buf) = (SharedBuf::default(), SharedBuf::default());
+        let mut serializers = [
+            AnySerializer::try_new(syntax::TRIG, Box::new(trig_buf.clone()), &config).unwrap(),
+            AnySerializer::try_new(syntax::TURTLE, Box::new(turtle_buf.clone()), &config)
+                .unwrap(),
+        ];
+        for serializer in serializers.iter_mut() {
+            serializer.serialize_dataset(&d1).unwrap();
+        }
+
+        let trig_doc = String::from_utf8(trig_buf.0.lock().unwrap().clone()).unwrap();
+        let d2: FastDataset = parser.parse_str(&trig_doc).collect_quads().unwrap();
+        assert!(isomorphic_datasets(&d1, &d2).unwrap());
+        assert!(String::from_utf8(turtle_buf.0.lock().unwrap().clone()).is_ok());
+    }
+
+    #[test]
+    pub fn serializing_named_graphs_into_triple_syntax_will_error_by_default() {
+        Lazy::force(&TRACING);
+        let d1: FastDataset = DynSynQuadParserFactory::default()
+            .try_new_parser(syntax::TRIG, None, None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(TESTS_TRIG[1])
+            .collect_quads()
+            .unwrap();
+        let mut serializer = AnySerializer::try_new(
+            syntax::TURTLE,
+            Box::new(io::sink()),
+            &DynSynSerializerFactories::default(),
+        )
+        .unwrap();
+        let e = match serializer.serialize_dataset(&d1).unwrap_err() {
+            StreamError::SinkError(e) => e,
+            StreamError::SourceError(e) => match e {},
+        };
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+        let e = e
+            .get_ref()
+            .unwrap()
+            .downcast_ref::<DynSynSerializeError<Infallible>>();
+        assert!(matches!(e, Some(DynSynSerializeError::NamedGraph(_))));
+    }
+}
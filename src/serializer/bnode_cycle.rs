@@ -0,0 +1,403 @@
+//! This module defines detection of blank node cycles, for pretty turtle, and trig serialization. Pretty serializers nest blank nodes, that are objects of statements, inside of their referrers. A cycle of blank nodes, like `_:a :p _:b. _:b :p _:a.`, can't be nested, and has to be broken with a labeled blank node, at a place of serializer's choosing.
+//!
+//! Detection is enabled by inserting a [`BlankNodeCycleConfig`] into serializer config map of [`DynSynTripleSerializerFactory`](super::triples::DynSynTripleSerializerFactory), or [`DynSynQuadSerializerFactory`](super::quads::DynSynQuadSerializerFactory). Serializers then check statements for cycles up front, before writing anything, and either fall back to non-pretty output, in which all blank nodes are labeled, or fail with a [`BlankNodeCycle`] error, that names blank nodes in the cycle. Detection buffers all statements in memory, which pretty serializers do anyway. It has no effect on other syntaxes, or on non-pretty output.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{
+//!     error_code::{self, ErrorCode},
+//!     serializer::{
+//!         bnode_cycle::{BlankNodeCycleAction, BlankNodeCycleConfig},
+//!         pretty::PrettyConfig,
+//!         serializable::{DynSynSerializable, DynSynSerializerFactories},
+//!         triples::DynSynTripleSerializerFactory,
+//!     },
+//!     syntax,
+//! };
+//! use sophia_term::BoxTerm;
+//! use type_map::concurrent::TypeMap;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let (a, b) = (BoxTerm::new_bnode("a")?, BoxTerm::new_bnode("b")?);
+//! let p = BoxTerm::new_iri("http://example.org/p")?;
+//! let graph = vec![[a.clone(), p.clone(), b.clone()], [b, p, a]];
+//!
+//! let mut config_map = TypeMap::new();
+//! config_map.insert(PrettyConfig::new().with_pretty(true));
+//! config_map.insert(BlankNodeCycleConfig::new(BlankNodeCycleAction::Error));
+//! let config = DynSynSerializerFactories {
+//!     triple_serializer_factory: DynSynTripleSerializerFactory::new(Some(config_map)),
+//!     ..Default::default()
+//! };
+//!
+//! let e = (&graph)
+//!     .serialize_to(syntax::TURTLE, Vec::new(), &config)
+//!     .unwrap_err();
+//! assert_eq!(e.code(), error_code::DYNSYN_BLANK_NODE_CYCLE);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::{
+    collections::{HashMap, HashSet},
+    convert::Infallible,
+    io,
+};
+
+use sophia_api::{
+    quad::{stream::QuadSource, Quad},
+    term::{CopyTerm, TTerm, TermKind},
+    triple::{
+        stream::{StreamError, StreamResult, TripleSource},
+        Triple,
+    },
+};
+use sophia_term::BoxTerm;
+use sophia_turtle::serializer::turtle::TurtleConfig;
+
+use crate::error_code::{self, ErrorCode};
+
+/// Action to take, when statements to be pretty serialized have a blank node cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlankNodeCycleAction {
+    /// Serialize without pretty-printing, labeling all blank nodes.
+    FallBack,
+    /// Fail serialization with a [`BlankNodeCycle`] error.
+    Error,
+}
+
+/// Configuration of blank node cycle detection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlankNodeCycleConfig {
+    /// Action to take on a cycle.
+    pub on_cycle: BlankNodeCycleAction,
+}
+
+impl Default for BlankNodeCycleConfig {
+    fn default() -> Self {
+        Self::new(BlankNodeCycleAction::FallBack)
+    }
+}
+
+impl BlankNodeCycleConfig {
+    pub fn new(on_cycle: BlankNodeCycleAction) -> Self {
+        Self { on_cycle }
+    }
+
+    /// Get turtle, or trig config to serialize statements with, given `cycle` found among them, if any.
+    pub(crate) fn resolve(
+        &self,
+        config: &TurtleConfig,
+        cycle: Option<BlankNodeCycle>,
+    ) -> Result<TurtleConfig, BlankNodeCycle> {
+        let cycle = match cycle {
+            Some(cycle) => cycle,
+            None => return Ok(config.clone()),
+        };
+        match self.on_cycle {
+            BlankNodeCycleAction::FallBack => {
+                tracing::debug!("{}. Falling back to non-pretty output.", cycle);
+                Ok(config.clone().with_pretty(false))
+            }
+            BlankNodeCycleAction::Error => Err(cycle),
+        }
+    }
+}
+
+/// An error of statements to be pretty serialized having a blank node cycle. It is surfaced from serializers as an [`io::Error`] of kind [`InvalidData`](io::ErrorKind::InvalidData), and can be recovered from it with [`BlankNodeCycle::from_io_error`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Blank nodes form a cycle, that can't be pretty serialized: {}", cycle_display(.labels))]
+pub struct BlankNodeCycle {
+    /// Labels of blank nodes in the cycle, in order of their references. Last one references first one.
+    pub labels: Vec<String>,
+}
+
+fn cycle_display(labels: &[String]) -> String {
+    labels
+        .iter()
+        .chain(labels.first())
+        .map(|label| format!("_:{}", label))
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+impl ErrorCode for BlankNodeCycle {
+    fn code(&self) -> &'static str {
+        error_code::DYNSYN_BLANK_NODE_CYCLE
+    }
+}
+
+impl BlankNodeCycle {
+    /// Get the cycle error, that given io error from a serializer wraps, if any.
+    pub fn from_io_error(e: &io::Error) -> Option<&Self> {
+        e.get_ref()?.downcast_ref::<Self>()
+    }
+}
+
+impl From<BlankNodeCycle> for io::Error {
+    fn from(e: BlankNodeCycle) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, e)
+    }
+}
+
+/// Find a cycle of blank nodes, following edges from subjects to objects of given statements. Returns labels of blank nodes in first found cycle.
+pub(crate) fn find_cycle<'t, T, I>(statements: I) -> Option<BlankNodeCycle>
+where
+    T: TTerm + ?Sized + 't,
+    I: IntoIterator<Item = (&'t T, &'t T)>,
+{
+    let mut edges: HashMap<String, Vec<String>> = HashMap::new();
+    for (s, o) in statements {
+        if s.kind() == TermKind::BlankNode && o.kind() == TermKind::BlankNode {
+            edges
+                .entry(s.value().to_string())
+                .or_default()
+                .push(o.value().to_string());
+        }
+    }
+    let mut roots: Vec<&String> = edges.keys().collect();
+    // Deterministic report, regardless of hash order.
+    roots.sort();
+
+    let mut done: HashSet<&str> = HashSet::new();
+    for root in roots {
+        if done.contains(root.as_str()) {
+            continue;
+        }
+        // Iterative dfs, with path of nodes being visited, and index of next edge of each.
+        let mut path: Vec<(&str, usize)> = vec![(root, 0)];
+        let mut on_path: HashSet<&str> = HashSet::from([root.as_str()]);
+        while let Some((node, next)) = path.last_mut() {
+            let node = *node;
+            match edges.get(node).and_then(|targets| targets.get(*next)) {
+                Some(target) => {
+                    *next += 1;
+                    if on_path.contains(target.as_str()) {
+                        let start = path.iter().position(|(n, _)| *n == target)?;
+                        return Some(BlankNodeCycle {
+                            labels: path[start..].iter().map(|(n, _)| n.to_string()).collect(),
+                        });
+                    }
+                    if !done.contains(target.as_str()) {
+                        on_path.insert(target);
+                        path.push((target, 0));
+                    }
+                }
+                None => {
+                    on_path.remove(node);
+                    done.insert(node);
+                    path.pop();
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Buffer all triples of `source`, to check them before serialization.
+pub(crate) fn buffer_triples<TS: TripleSource>(
+    mut source: TS,
+) -> StreamResult<Vec<[BoxTerm; 3]>, TS::Error, io::Error> {
+    let mut triples = Vec::new();
+    source
+        .try_for_each_triple(|t| {
+            triples.push([
+                BoxTerm::copy(t.s()),
+                BoxTerm::copy(t.p()),
+                BoxTerm::copy(t.o()),
+            ]);
+            Ok::<_, Infallible>(())
+        })
+        .map_err(source_only)?;
+    Ok(triples)
+}
+
+/// Buffer all quads of `source`, to check them before serialization.
+#[allow(clippy::type_complexity)]
+pub(crate) fn buffer_quads<QS: QuadSource>(
+    mut source: QS,
+) -> StreamResult<Vec<([BoxTerm; 3], Option<BoxTerm>)>, QS::Error, io::Error> {
+    let mut quads = Vec::new();
+    source
+        .try_for_each_quad(|q| {
+            quads.push((
+                [
+                    BoxTerm::copy(q.s()),
+                    BoxTerm::copy(q.p()),
+                    BoxTerm::copy(q.o()),
+                ],
+                q.g().map(BoxTerm::copy),
+            ));
+            Ok::<_, Infallible>(())
+        })
+        .map_err(source_only)?;
+    Ok(quads)
+}
+
+fn source_only<E: std::error::Error>(e: StreamError<E, Infallible>) -> StreamError<E, io::Error> {
+    match e {
+        StreamError::SourceError(e) => StreamError::SourceError(e),
+        StreamError::SinkError(e) => match e {},
+    }
+}
+
+/// Adapt error from serializing buffered statements, which can only be a sink error.
+pub(crate) fn sink_only<E: std::error::Error>(
+    e: StreamError<Infallible, io::Error>,
+) -> StreamError<E, io::Error> {
+    match e {
+        StreamError::SourceError(e) => match e {},
+        StreamError::SinkError(e) => StreamError::SinkError(e),
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use sophia_api::{
+        dataset::isomorphic_datasets,
+        graph::isomorphic_graphs,
+        parser::{QuadParser, TripleParser},
+        quad::stream::QuadSource,
+        serializer::{QuadSerializer, Stringifier, TripleSerializer},
+        triple::{stream::TripleSource, Triple},
+    };
+    use sophia_inmem::{dataset::FastDataset, graph::FastGraph};
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+    use type_map::concurrent::TypeMap;
+
+    use super::{find_cycle, BlankNodeCycle, BlankNodeCycleAction, BlankNodeCycleConfig};
+    use crate::{
+        error_code::{self, ErrorCode},
+        parser::{quads::DynSynQuadParserFactory, triples::DynSynTripleParserFactory},
+        serializer::{
+            pretty::PrettyConfig, quads::DynSynQuadSerializerFactory,
+            triples::DynSynTripleSerializerFactory,
+        },
+        syntax,
+        tests::TRACING,
+    };
+
+    static CYCLIC_DOC: &str = r#"
+        @prefix : <http://example.org/> .
+        :x :p _:a .
+        _:a :p _:b .
+        _:b :p _:a ; :q "b" .
+    "#;
+
+    fn config_map(pretty: bool, on_cycle: BlankNodeCycleAction) -> TypeMap {
+        let mut config_map = TypeMap::new();
+        config_map.insert(PrettyConfig::new().with_pretty(pretty));
+        config_map.insert(BlankNodeCycleConfig::new(on_cycle));
+        config_map
+    }
+
+    fn term(t: &str) -> BoxTerm {
+        match t.strip_prefix("_:") {
+            Some(label) => BoxTerm::new_bnode(label).unwrap(),
+            None => BoxTerm::new_iri(t).unwrap(),
+        }
+    }
+
+    fn edges(edges: &[(&str, &str)]) -> Vec<[BoxTerm; 3]> {
+        edges
+            .iter()
+            .map(|(s, o)| [term(s), term("tag:p"), term(o)])
+            .collect()
+    }
+
+    #[test_case(&[("_:a", "_:a")], Some(vec!["a"]))]
+    #[test_case(&[("_:a", "_:b"), ("_:b", "_:a")], Some(vec!["a", "b"]))]
+    #[test_case(&[("_:x", "_:b"), ("_:b", "_:c"), ("_:c", "_:b")], Some(vec!["b", "c"]))]
+    #[test_case(&[("_:a", "_:b"), ("_:b", "_:c"), ("_:a", "_:c")], None)]
+    #[test_case(&[("_:a", "tag:b"), ("tag:b", "_:a")], None)]
+    #[test_case(&[], None)]
+    pub fn finds_cycles(edges_: &[(&str, &str)], expected: Option<Vec<&str>>) {
+        Lazy::force(&TRACING);
+        let triples = edges(edges_);
+        let cycle = find_cycle(triples.iter().map(|t| (t.s(), t.o())));
+        assert_eq!(
+            cycle.map(|c| c.labels),
+            expected.map(|labels| labels.into_iter().map(String::from).collect())
+        );
+    }
+
+    #[test]
+    pub fn falls_back_to_labeled_bnodes() {
+        Lazy::force(&TRACING);
+        let graph: FastGraph = DynSynTripleParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)
+            .unwrap()
+            .parse_str(CYCLIC_DOC)
+            .collect_triples()
+            .unwrap();
+        let mut serializer = DynSynTripleSerializerFactory::new(Some(config_map(
+            true,
+            BlankNodeCycleAction::FallBack,
+        )))
+        .try_new_stringifier(syntax::TURTLE)
+        .unwrap();
+        serializer.serialize_graph(&graph).unwrap();
+        assert!(!serializer.as_str().contains('['));
+
+        let reparsed: FastGraph = DynSynTripleParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)
+            .unwrap()
+            .parse_str(serializer.as_str())
+            .collect_triples()
+            .unwrap();
+        assert!(isomorphic_graphs(&graph, &reparsed).unwrap());
+    }
+
+    #[test]
+    pub fn errors_on_cycles_with_error_action() {
+        Lazy::force(&TRACING);
+        let dataset: FastDataset = DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)
+            .unwrap()
+            .parse_str(CYCLIC_DOC)
+            .collect_quads()
+            .unwrap();
+        let mut serializer =
+            DynSynQuadSerializerFactory::new(Some(config_map(true, BlankNodeCycleAction::Error)))
+                .try_new_stringifier(syntax::TRIG)
+                .unwrap();
+        let e = match serializer.serialize_dataset(&dataset) {
+            Err(e) => e.unwrap_sink_error(),
+            Ok(_) => panic!("cycle must be reported"),
+        };
+        let cycle = BlankNodeCycle::from_io_error(&e).unwrap();
+        assert_eq!(cycle.code(), error_code::DYNSYN_BLANK_NODE_CYCLE);
+        assert_eq!(cycle.labels.len(), 2);
+        assert!(serializer.as_str().is_empty());
+    }
+
+    #[test]
+    pub fn non_pretty_output_is_not_checked() {
+        Lazy::force(&TRACING);
+        let dataset: FastDataset = DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)
+            .unwrap()
+            .parse_str(CYCLIC_DOC)
+            .collect_quads()
+            .unwrap();
+        let mut serializer =
+            DynSynQuadSerializerFactory::new(Some(config_map(false, BlankNodeCycleAction::Error)))
+                .try_new_stringifier(syntax::TRIG)
+                .unwrap();
+        serializer.serialize_dataset(&dataset).unwrap();
+        let reparsed: FastDataset = DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::TRIG, None, None)
+            .unwrap()
+            .parse_str(serializer.as_str())
+            .collect_quads()
+            .unwrap();
+        assert!(isomorphic_datasets(&dataset, &reparsed).unwrap());
+    }
+}
@@ -0,0 +1,632 @@
+//! This module implements [RDF Dataset Canonicalization](https://www.w3.org/TR/rdf-canon/) (RDFC-1.0, formerly URDNA2015) with SHA-256. Canonicalization labels blank nodes of a dataset deterministically, as `_:c14n0`, `_:c14n1`, ..., such that isomorphic datasets produce identical canonical n-quads documents. It enables hashing, and signing of datasets, and reliable textual diffs.
+//!
+//! Canonical output is enabled by inserting a [`CanonConfig`] with `canonical` option into serializer config map of [`DynSynQuadSerializerFactory`](super::quads::DynSynQuadSerializerFactory). Its [`n-quads`](crate::syntax::N_QUADS) serializers then buffer all quads, and write them as canonical n-quads: with canonical blank node labels, canonical escapes, one quad per line, lines sorted by unicode code points, and duplicate quads removed. Canonical output is never escaped into ascii, as canonical form mandates utf-8. It has no effect on other syntaxes.
+//!
+//! Canonicalizing some specially crafted datasets takes exponential time. Hence work of canonicalization is bounded by [`CanonConfig::max_n_degree_steps`]. Exceeding it fails serialization with a [`CanonLimitExceeded`] error.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{
+//!     parser::quads::DynSynQuadParserFactory,
+//!     serializer::{canon::CanonConfig, quads::DynSynQuadSerializerFactory},
+//!     syntax,
+//! };
+//! use sophia_api::{parser::QuadParser, serializer::{QuadSerializer, Stringifier}};
+//! use sophia_term::BoxTerm;
+//! use type_map::concurrent::TypeMap;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut config_map = TypeMap::new();
+//! config_map.insert(CanonConfig::new().with_canonical(true));
+//! let serializer_factory = DynSynQuadSerializerFactory::new(Some(config_map));
+//! let parser = DynSynQuadParserFactory::default()
+//!     .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)?;
+//!
+//! let mut canonical_docs = Vec::new();
+//! for doc in [
+//!     "@prefix : <http://example.org/> . :alice :knows [ :name \"Bob\" ] .",
+//!     "@prefix : <http://example.org/> . _:x :name \"Bob\" . :alice :knows _:x .",
+//! ] {
+//!     let mut serializer = serializer_factory.try_new_stringifier(syntax::N_QUADS)?;
+//!     serializer.serialize_quads(parser.parse_str(doc))?;
+//!     canonical_docs.push(serializer.as_str().to_owned());
+//! }
+//! assert_eq!(canonical_docs[0], canonical_docs[1]);
+//! assert_eq!(
+//!     canonical_docs[0],
+//!     "<http://example.org/alice> <http://example.org/knows> _:c14n0 .\n_:c14n0 <http://example.org/name> \"Bob\" .\n"
+//! );
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    io,
+};
+
+use sha2::{Digest, Sha256};
+use sophia_api::{
+    ns::xsd,
+    quad::Quad,
+    term::{term_eq, TTerm, TermKind},
+};
+
+use crate::error_code::{self, ErrorCode};
+
+/// Default value of [`CanonConfig::max_n_degree_steps`].
+pub const DEFAULT_MAX_N_DEGREE_STEPS: usize = 65_536;
+
+/// Configuration of canonical n-quads output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CanonConfig {
+    /// If true, n-quads serializers write canonical n-quads.
+    pub canonical: bool,
+    /// Max number of steps of hash n-degree quads algorithm, over all of it's invocations for a dataset. Each invocation, and each permutation of related blank nodes examined by it is a step. Datasets, whose blank nodes can be told apart by their own statements, take no steps.
+    pub max_n_degree_steps: usize,
+}
+
+impl Default for CanonConfig {
+    fn default() -> Self {
+        Self {
+            canonical: false,
+            max_n_degree_steps: DEFAULT_MAX_N_DEGREE_STEPS,
+        }
+    }
+}
+
+impl CanonConfig {
+    /// Create a new config with default options, that doesn't canonicalize.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether to write canonical n-quads.
+    pub fn with_canonical(mut self, canonical: bool) -> Self {
+        self.canonical = canonical;
+        self
+    }
+
+    /// Set max number of steps of hash n-degree quads algorithm.
+    pub fn with_max_n_degree_steps(mut self, max_n_degree_steps: usize) -> Self {
+        self.max_n_degree_steps = max_n_degree_steps;
+        self
+    }
+
+    /// Get canonical labels of blank nodes of given quads, keyed by their labels in quads.
+    ///
+    /// # Errors
+    /// returns [`CanonLimitExceeded`] if canonicalization takes more steps than configured.
+    pub fn canonical_labels<Q: Quad>(
+        &self,
+        quads: &[Q],
+    ) -> Result<HashMap<String, String>, CanonLimitExceeded> {
+        let mut canonicalizer = Canonicalizer::new(quads, self.max_n_degree_steps);
+        canonicalizer.issue_canonical_labels()?;
+        Ok(canonicalizer.canonical_issuer.issued)
+    }
+
+    /// Get canonical n-quads document of given quads.
+    ///
+    /// # Errors
+    /// returns [`CanonLimitExceeded`] if canonicalization takes more steps than configured.
+    pub fn canonical_n_quads<Q: Quad>(&self, quads: &[Q]) -> Result<String, CanonLimitExceeded> {
+        let labels = self.canonical_labels(quads)?;
+        let mut lines: Vec<String> = quads
+            .iter()
+            .map(|q| n_quad(q, |label| format!("_:{}", labels[label])))
+            .collect();
+        lines.sort_unstable();
+        lines.dedup();
+        Ok(lines.concat())
+    }
+}
+
+/// An error of canonicalization taking more steps than configured. It is surfaced from serializers as an [`io::Error`] of kind [`InvalidData`](io::ErrorKind::InvalidData), and can be recovered from it with [`CanonLimitExceeded::from_io_error`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Canonicalization of dataset takes more than {max_n_degree_steps} steps of hash n-degree quads algorithm")]
+pub struct CanonLimitExceeded {
+    /// Configured max number of steps.
+    pub max_n_degree_steps: usize,
+}
+
+impl ErrorCode for CanonLimitExceeded {
+    fn code(&self) -> &'static str {
+        error_code::DYNSYN_CANON_LIMIT_EXCEEDED
+    }
+}
+
+impl CanonLimitExceeded {
+    /// Get the limit error, that given io error from a serializer wraps, if any.
+    pub fn from_io_error(e: &io::Error) -> Option<&Self> {
+        e.get_ref()?.downcast_ref::<Self>()
+    }
+}
+
+impl From<CanonLimitExceeded> for io::Error {
+    fn from(e: CanonLimitExceeded) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, e)
+    }
+}
+
+/// Write given quad as a canonical n-quads line, labeling blank nodes with `bnode`.
+fn n_quad<Q, F>(q: &Q, bnode: F) -> String
+where
+    Q: Quad,
+    F: Fn(&str) -> String,
+{
+    let mut line = String::new();
+    for t in [q.s(), q.p(), q.o()].into_iter().chain(q.g()) {
+        match t.kind() {
+            TermKind::Iri => write!(line, "<{}>", t.value()),
+            TermKind::BlankNode => write!(line, "{}", bnode(&t.value())),
+            TermKind::Variable => write!(line, "?{}", t.value()),
+            TermKind::Literal => {
+                write_literal_value(&mut line, &t.value());
+                match (t.language(), t.datatype()) {
+                    (Some(language), _) => write!(line, "@{}", language),
+                    (None, Some(dt)) if !term_eq(&dt, &xsd::string) => {
+                        write!(line, "^^<{}>", dt.value())
+                    }
+                    _ => Ok(()),
+                }
+            }
+        }
+        .expect("writing to a string doesn't fail");
+        line.push(' ');
+    }
+    line.push_str(".\n");
+    line
+}
+
+/// Write lexical value of a literal in quotes, with canonical escapes of n-quads.
+fn write_literal_value(line: &mut String, value: &str) {
+    line.push('"');
+    for c in value.chars() {
+        match c {
+            '\u{8}' => line.push_str("\\b"),
+            '\t' => line.push_str("\\t"),
+            '\n' => line.push_str("\\n"),
+            '\u{c}' => line.push_str("\\f"),
+            '\r' => line.push_str("\\r"),
+            '"' => line.push_str("\\\""),
+            '\\' => line.push_str("\\\\"),
+            '\u{0}'..='\u{1f}' | '\u{7f}' => {
+                write!(line, "\\u{:04X}", c as u32).expect("writing to a string doesn't fail")
+            }
+            c => line.push(c),
+        }
+    }
+    line.push('"');
+}
+
+fn sha256_hex(data: &str) -> String {
+    Sha256::digest(data.as_bytes())
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, b| {
+            write!(hex, "{:02x}", b).expect("writing to a string doesn't fail");
+            hex
+        })
+}
+
+/// Issuer of blank node identifiers with a prefix, and a counter.
+#[derive(Debug, Clone)]
+struct IdentifierIssuer {
+    prefix: &'static str,
+    issued: HashMap<String, String>,
+    issued_order: Vec<String>,
+}
+
+impl IdentifierIssuer {
+    fn new(prefix: &'static str) -> Self {
+        Self {
+            prefix,
+            issued: HashMap::new(),
+            issued_order: Vec::new(),
+        }
+    }
+
+    /// Issue identifier for given existing identifier, if not issued already, and return it.
+    fn issue(&mut self, existing: &str) -> String {
+        if let Some(issued) = self.issued.get(existing) {
+            return issued.clone();
+        }
+        let issued = format!("{}{}", self.prefix, self.issued_order.len());
+        self.issued.insert(existing.to_owned(), issued.clone());
+        self.issued_order.push(existing.to_owned());
+        issued
+    }
+}
+
+/// State of canonicalization of a dataset.
+struct Canonicalizer<'q, Q> {
+    blank_node_to_quads: HashMap<String, Vec<&'q Q>>,
+    canonical_issuer: IdentifierIssuer,
+    steps_left: usize,
+    max_n_degree_steps: usize,
+}
+
+impl<'q, Q: Quad> Canonicalizer<'q, Q> {
+    fn new(quads: &'q [Q], max_n_degree_steps: usize) -> Self {
+        let mut blank_node_to_quads: HashMap<String, Vec<&'q Q>> = HashMap::new();
+        for q in quads {
+            let labels: Vec<String> = [q.s(), q.p(), q.o()]
+                .into_iter()
+                .chain(q.g())
+                .filter(|t| t.kind() == TermKind::BlankNode)
+                .map(|t| t.value().to_string())
+                .collect();
+            for label in labels {
+                let quads = blank_node_to_quads.entry(label).or_default();
+                if !quads.iter().any(|other| std::ptr::eq(*other, q)) {
+                    quads.push(q);
+                }
+            }
+        }
+        Self {
+            blank_node_to_quads,
+            canonical_issuer: IdentifierIssuer::new("c14n"),
+            steps_left: max_n_degree_steps,
+            max_n_degree_steps,
+        }
+    }
+
+    fn step(&mut self) -> Result<(), CanonLimitExceeded> {
+        self.steps_left = self.steps_left.checked_sub(1).ok_or(CanonLimitExceeded {
+            max_n_degree_steps: self.max_n_degree_steps,
+        })?;
+        Ok(())
+    }
+
+    /// Issue canonical labels to all blank nodes.
+    fn issue_canonical_labels(&mut self) -> Result<(), CanonLimitExceeded> {
+        let mut hash_to_blank_nodes: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut labels: Vec<&String> = self.blank_node_to_quads.keys().collect();
+        // Labels with same first degree hash are visited in their order.
+        labels.sort();
+        for label in labels {
+            hash_to_blank_nodes
+                .entry(self.hash_first_degree_quads(label))
+                .or_default()
+                .push(label.clone());
+        }
+
+        let mut shared_hashes = Vec::new();
+        for (_, labels) in hash_to_blank_nodes {
+            match &labels[..] {
+                [label] => {
+                    self.canonical_issuer.issue(label);
+                }
+                _ => shared_hashes.push(labels),
+            }
+        }
+
+        for labels in shared_hashes {
+            let mut hash_path_list = Vec::new();
+            for label in labels {
+                if self.canonical_issuer.issued.contains_key(&label) {
+                    continue;
+                }
+                let mut temporary_issuer = IdentifierIssuer::new("b");
+                temporary_issuer.issue(&label);
+                hash_path_list.push(self.hash_n_degree_quads(&label, temporary_issuer)?);
+            }
+            hash_path_list.sort_by(|(hash1, _), (hash2, _)| hash1.cmp(hash2));
+            for (_, issuer) in hash_path_list {
+                for existing in &issuer.issued_order {
+                    self.canonical_issuer.issue(existing);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn hash_first_degree_quads(&self, reference: &str) -> String {
+        let mut n_quads: Vec<String> = self.blank_node_to_quads[reference]
+            .iter()
+            .map(|q| {
+                n_quad(*q, |label| {
+                    if label == reference { "_:a" } else { "_:z" }.to_owned()
+                })
+            })
+            .collect();
+        n_quads.sort_unstable();
+        sha256_hex(&n_quads.concat())
+    }
+
+    fn hash_related_blank_node(
+        &self,
+        related: &str,
+        q: &Q,
+        issuer: &IdentifierIssuer,
+        position: char,
+    ) -> String {
+        let mut input = String::from(position);
+        if position != 'g' {
+            write!(input, "<{}>", q.p().value()).expect("writing to a string doesn't fail");
+        }
+        match (
+            self.canonical_issuer.issued.get(related),
+            issuer.issued.get(related),
+        ) {
+            (Some(id), _) | (None, Some(id)) => {
+                input.push_str("_:");
+                input.push_str(id);
+            }
+            (None, None) => input.push_str(&self.hash_first_degree_quads(related)),
+        }
+        sha256_hex(&input)
+    }
+
+    fn hash_n_degree_quads(
+        &mut self,
+        reference: &str,
+        mut issuer: IdentifierIssuer,
+    ) -> Result<(String, IdentifierIssuer), CanonLimitExceeded> {
+        self.step()?;
+        let mut hash_to_related: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for q in &self.blank_node_to_quads[reference] {
+            let components = [(Some(q.s()), 's'), (Some(q.o()), 'o'), (q.g(), 'g')];
+            for (t, position) in components {
+                let t = match t {
+                    Some(t) if t.kind() == TermKind::BlankNode && &*t.value() != reference => t,
+                    _ => continue,
+                };
+                let related = t.value().to_string();
+                hash_to_related
+                    .entry(self.hash_related_blank_node(&related, q, &issuer, position))
+                    .or_default()
+                    .push(related);
+            }
+        }
+
+        let mut data_to_hash = String::new();
+        for (related_hash, mut related_nodes) in hash_to_related {
+            data_to_hash.push_str(&related_hash);
+            let mut chosen: Option<(String, IdentifierIssuer)> = None;
+            related_nodes.sort();
+            loop {
+                self.step()?;
+                if let Some(candidate) = self.path_of(&related_nodes, &issuer, chosen.as_ref())? {
+                    chosen = Some(candidate);
+                }
+                if !next_permutation(&mut related_nodes) {
+                    break;
+                }
+            }
+            let (chosen_path, chosen_issuer) = chosen.expect("a permutation is always chosen");
+            data_to_hash.push_str(&chosen_path);
+            issuer = chosen_issuer;
+        }
+        Ok((sha256_hex(&data_to_hash), issuer))
+    }
+
+    /// Get path, and issuer for given permutation of related blank nodes, unless it's path is known to be greater than `chosen` path.
+    fn path_of(
+        &mut self,
+        permutation: &[String],
+        issuer: &IdentifierIssuer,
+        chosen: Option<&(String, IdentifierIssuer)>,
+    ) -> Result<Option<(String, IdentifierIssuer)>, CanonLimitExceeded> {
+        let exceeds_chosen = |path: &str| matches!(chosen, Some((chosen_path, _)) if path.len() >= chosen_path.len() && path > chosen_path.as_str());
+        let mut issuer_copy = issuer.clone();
+        let mut path = String::new();
+        let mut recursion_list = Vec::new();
+        for related in permutation {
+            match self.canonical_issuer.issued.get(related) {
+                Some(id) => {
+                    path.push_str("_:");
+                    path.push_str(id);
+                }
+                None => {
+                    if !issuer_copy.issued.contains_key(related) {
+                        recursion_list.push(related);
+                    }
+                    path.push_str("_:");
+                    path.push_str(&issuer_copy.issue(related));
+                }
+            }
+            if exceeds_chosen(&path) {
+                return Ok(None);
+            }
+        }
+        for related in recursion_list {
+            let (hash, result_issuer) = self.hash_n_degree_quads(related, issuer_copy.clone())?;
+            path.push_str("_:");
+            path.push_str(&issuer_copy.issue(related));
+            path.push('<');
+            path.push_str(&hash);
+            path.push('>');
+            issuer_copy = result_issuer;
+            if exceeds_chosen(&path) {
+                return Ok(None);
+            }
+        }
+        match chosen {
+            Some((chosen_path, _)) if path >= *chosen_path => Ok(None),
+            _ => Ok(Some((path, issuer_copy))),
+        }
+    }
+}
+
+/// Rearrange given items into their next permutation in lexicographic order. Returns false, if they are in their last permutation.
+fn next_permutation<T: Ord>(items: &mut [T]) -> bool {
+    let pivot = match items.windows(2).rposition(|w| w[0] < w[1]) {
+        Some(pivot) => pivot,
+        None => return false,
+    };
+    let successor = items
+        .iter()
+        .rposition(|item| *item > items[pivot])
+        .expect("an item after pivot is greater than it");
+    items.swap(pivot, successor);
+    items[pivot + 1..].reverse();
+    true
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use sophia_api::{
+        dataset::isomorphic_datasets,
+        parser::QuadParser,
+        quad::stream::QuadSource,
+        serializer::{QuadSerializer, Stringifier},
+    };
+    use sophia_inmem::dataset::FastDataset;
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+    use type_map::concurrent::TypeMap;
+
+    use super::{next_permutation, CanonConfig, CanonLimitExceeded};
+    use crate::{
+        error_code::{self, ErrorCode},
+        parser::quads::DynSynQuadParserFactory,
+        serializer::{escape::EscapeConfig, quads::DynSynQuadSerializerFactory},
+        syntax,
+        tests::TRACING,
+    };
+
+    fn canonicalize(doc: &str, config: CanonConfig) -> Result<String, std::io::Error> {
+        let mut config_map = TypeMap::new();
+        config_map.insert(config);
+        config_map.insert(EscapeConfig::new().with_escape_non_ascii(true));
+        let mut serializer = DynSynQuadSerializerFactory::new(Some(config_map))
+            .try_new_stringifier(syntax::N_QUADS)
+            .unwrap();
+        let quads = DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+            .unwrap()
+            .parse_str(doc);
+        match serializer.serialize_quads(quads) {
+            Ok(_) => Ok(serializer.as_str().to_owned()),
+            Err(e) => Err(e.unwrap_sink_error()),
+        }
+    }
+
+    /// Blank nodes, that have unique first degree hashes. `_:b` has smaller hash.
+    const UNIQUE_HASHES_DOC: &str = r#"
+        _:a <http://ex.org/q> "v" .
+        <http://ex.org/s> <http://ex.org/p> _:b .
+    "#;
+
+    /// Example of blank nodes with shared first degree hashes from RDFC-1.0 spec.
+    const SHARED_HASHES_DOC: &str = r#"
+        _:e0 <http://example.org/vocab#next> _:e1 .
+        _:e0 <http://example.org/vocab#prev> _:e2 .
+        _:e1 <http://example.org/vocab#next> _:e2 .
+        _:e1 <http://example.org/vocab#prev> _:e0 .
+        _:e2 <http://example.org/vocab#next> _:e0 .
+        _:e2 <http://example.org/vocab#prev> _:e1 .
+    "#;
+
+    #[test_case(
+        UNIQUE_HASHES_DOC,
+        "<http://ex.org/s> <http://ex.org/p> _:c14n0 .\n_:c14n1 <http://ex.org/q> \"v\" .\n"
+    )]
+    #[test_case(
+        SHARED_HASHES_DOC,
+        concat!(
+            "_:c14n0 <http://example.org/vocab#next> _:c14n2 .\n",
+            "_:c14n0 <http://example.org/vocab#prev> _:c14n1 .\n",
+            "_:c14n1 <http://example.org/vocab#next> _:c14n0 .\n",
+            "_:c14n1 <http://example.org/vocab#prev> _:c14n2 .\n",
+            "_:c14n2 <http://example.org/vocab#next> _:c14n1 .\n",
+            "_:c14n2 <http://example.org/vocab#prev> _:c14n0 .\n",
+        )
+    )]
+    pub fn writes_canonical_n_quads(doc: &str, expected: &str) {
+        Lazy::force(&TRACING);
+        assert_eq!(
+            canonicalize(doc, CanonConfig::new().with_canonical(true)).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    pub fn canonical_n_quads_are_independent_of_labels_and_order() {
+        Lazy::force(&TRACING);
+        let relabeled: String = SHARED_HASHES_DOC
+            .replace("_:e0", "_:x")
+            .replace("_:e1", "_:e0")
+            .replace("_:x", "_:e1")
+            .lines()
+            .rev()
+            .map(|line| format!("{}\n", line))
+            .collect();
+        let config = CanonConfig::new().with_canonical(true);
+        assert_eq!(
+            canonicalize(&relabeled, config).unwrap(),
+            canonicalize(SHARED_HASHES_DOC, config).unwrap()
+        );
+    }
+
+    #[test]
+    pub fn canonical_n_quads_are_isomorphic_to_input() {
+        Lazy::force(&TRACING);
+        let doc = r#"
+            _:a <http://ex.org/p> "tab\tquote\" bell\u0007 é"@en _:g .
+            _:a <http://ex.org/p> _:a _:g .
+            _:a <http://ex.org/p> _:a _:g .
+            <http://ex.org/s> <http://ex.org/p> "1"^^<http://www.w3.org/2001/XMLSchema#integer> .
+        "#;
+        let out = canonicalize(doc, CanonConfig::new().with_canonical(true)).unwrap();
+        assert_eq!(out.lines().count(), 3);
+        assert!(out.contains(r#""tab\tquote\" bell\u0007 é"@en"#));
+
+        let parser = DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+            .unwrap();
+        let d1: FastDataset = parser.parse_str(doc).collect_quads().unwrap();
+        let d2: FastDataset = parser.parse_str(&out).collect_quads().unwrap();
+        assert!(isomorphic_datasets(&d1, &d2).unwrap());
+    }
+
+    #[test]
+    pub fn canonicalization_fails_beyond_max_steps() {
+        Lazy::force(&TRACING);
+        let e = canonicalize(
+            SHARED_HASHES_DOC,
+            CanonConfig::new()
+                .with_canonical(true)
+                .with_max_n_degree_steps(2),
+        )
+        .unwrap_err();
+        let e = CanonLimitExceeded::from_io_error(&e).unwrap();
+        assert_eq!(e.code(), error_code::DYNSYN_CANON_LIMIT_EXCEEDED);
+    }
+
+    #[test]
+    pub fn writes_non_canonical_n_quads_by_default() {
+        Lazy::force(&TRACING);
+        let out = canonicalize(UNIQUE_HASHES_DOC, CanonConfig::new()).unwrap();
+        assert!(!out.contains("c14n"));
+    }
+
+    #[test]
+    pub fn permutes_in_lexicographic_order() {
+        let mut items = vec![1, 2, 3];
+        let mut permutations = vec![items.clone()];
+        while next_permutation(&mut items) {
+            permutations.push(items.clone());
+        }
+        assert_eq!(
+            permutations,
+            vec![
+                vec![1, 2, 3],
+                vec![1, 3, 2],
+                vec![2, 1, 3],
+                vec![2, 3, 1],
+                vec![3, 1, 2],
+                vec![3, 2, 1]
+            ]
+        );
+    }
+}
@@ -0,0 +1,237 @@
+//! This module provides functions to extract [concise bounded description](https://www.w3.org/Submission/CBD/) of a resource from a triple-source, and to serialize it. LDP resource handlers can use them to respond with just the per-resource slice of a larger document.
+//!
+//! Concise bounded description of a subject consists of all triples with that subject, and recursively, all triples with subjects that are blank-node objects of already included triples. Reifications of included triples are not included.
+//!
+//! Source is read in a single pass. Only triples with the subject, and triples with blank-node subjects are buffered, as blank-nodes may be described before, or after they are referenced.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{
+//!     parser::triples::DynSynTripleParserFactory,
+//!     serializer::{cbd::serialize_cbd, serializable::DynSynSerializerFactories},
+//!     syntax,
+//! };
+//! use sophia_api::parser::TripleParser;
+//! use sophia_term::{BoxTerm, StaticTerm};
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let doc = r#"
+//!     @prefix ex: <http://example.org/> .
+//!     ex:alice ex:address [ ex:city "Paris" ] .
+//!     ex:bob ex:name "Bob" .
+//! "#;
+//! let parser = DynSynTripleParserFactory::default()
+//!     .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)?;
+//! let alice = StaticTerm::new_iri("http://example.org/alice")?;
+//!
+//! let mut out = Vec::new();
+//! let count = serialize_cbd(
+//!     parser.parse_str(doc),
+//!     &alice,
+//!     syntax::N_TRIPLES,
+//!     &mut out,
+//!     &DynSynSerializerFactories::default(),
+//! )?;
+//! assert_eq!(count, 2);
+//! assert!(!String::from_utf8(out)?.contains("Bob"));
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    convert::Infallible,
+    io,
+};
+
+use sophia_api::{
+    term::{term_eq, CopyTerm, TTerm, TermKind},
+    triple::{stream::TripleSource, Triple},
+};
+use sophia_term::BoxTerm;
+
+use crate::syntax::RdfSyntax;
+
+use super::serializable::{DynSynSerializable, DynSynSerializeError, DynSynSerializerFactories};
+
+/// Extracts concise bounded description of `subject` from given triple-source. Triples with `subject` are returned in their source order, followed by triples describing reachable blank-nodes, in breadth-first order.
+///
+/// # Errors
+/// returns error from source, if any.
+pub fn extract_cbd<TS, T>(mut source: TS, subject: &T) -> Result<Vec<[BoxTerm; 3]>, TS::Error>
+where
+    TS: TripleSource,
+    T: TTerm + ?Sized,
+{
+    let mut description = Vec::new();
+    let mut bnode_descriptions: HashMap<BoxTerm, Vec<[BoxTerm; 3]>> = HashMap::new();
+
+    source.for_each_triple(|t| {
+        let is_subject = term_eq(t.s(), subject);
+        if !is_subject && t.s().kind() != TermKind::BlankNode {
+            return;
+        }
+        let triple = [
+            BoxTerm::copy(t.s()),
+            BoxTerm::copy(t.p()),
+            BoxTerm::copy(t.o()),
+        ];
+        if is_subject {
+            description.push(triple);
+        } else {
+            bnode_descriptions
+                .entry(triple[0].clone())
+                .or_default()
+                .push(triple);
+        }
+    })?;
+
+    let mut visited: HashSet<BoxTerm> = HashSet::new();
+    let mut pending: VecDeque<BoxTerm> = VecDeque::new();
+    let mut enqueue_bnode_objects = |triples: &[[BoxTerm; 3]], pending: &mut VecDeque<BoxTerm>| {
+        for [_, _, o] in triples {
+            if o.kind() == TermKind::BlankNode && visited.insert(o.clone()) {
+                pending.push_back(o.clone());
+            }
+        }
+    };
+
+    enqueue_bnode_objects(&description, &mut pending);
+    while let Some(bnode) = pending.pop_front() {
+        if let Some(triples) = bnode_descriptions.remove(&bnode) {
+            enqueue_bnode_objects(&triples, &mut pending);
+            description.extend(triples);
+        }
+    }
+    Ok(description)
+}
+
+/// Extracts concise bounded description of `subject` from given triple-source, and serializes it into `syntax_`, writing to `write`, with serializers from given `config`. Returns number of triples serialized.
+///
+/// # Errors
+/// returns [`DynSynSerializeError::UnKnownSyntax`] if `syntax_` is not supported by any of serializers, and other variants on errors from source, or writer.
+pub fn serialize_cbd<TS, T, W>(
+    source: TS,
+    subject: &T,
+    syntax_: RdfSyntax,
+    write: W,
+    config: &DynSynSerializerFactories,
+) -> Result<usize, DynSynSerializeError<TS::Error>>
+where
+    TS: TripleSource,
+    T: TTerm + ?Sized,
+    W: io::Write,
+{
+    let description = extract_cbd(source, subject).map_err(DynSynSerializeError::Source)?;
+    let count = description.len();
+    description
+        .into_iter()
+        .map(Ok::<_, Infallible>)
+        .serialize_to(syntax_, write, config)
+        .map_err(|e| match e {
+            DynSynSerializeError::UnKnownSyntax(e) => DynSynSerializeError::UnKnownSyntax(e),
+            DynSynSerializeError::Io(e) => DynSynSerializeError::Io(e),
+            DynSynSerializeError::NamedGraph(g) => DynSynSerializeError::NamedGraph(g),
+            DynSynSerializeError::Source(e) => match e {},
+        })?;
+    Ok(count)
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use sophia_api::{
+        graph::isomorphic_graphs, parser::TripleParser, term::TTerm, triple::stream::TripleSource,
+    };
+    use sophia_inmem::graph::FastGraph;
+    use sophia_term::{BoxTerm, StaticTerm};
+    use test_case::test_case;
+
+    use super::{extract_cbd, serialize_cbd};
+    use crate::{
+        parser::triples::DynSynTripleParserFactory,
+        serializer::serializable::DynSynSerializerFactories,
+        syntax::{self, RdfSyntax},
+        tests::TRACING,
+    };
+
+    static TRIPLE_PARSER_FACTORY: Lazy<DynSynTripleParserFactory> =
+        Lazy::new(DynSynTripleParserFactory::default);
+
+    static DOC: &str = r#"
+        @prefix ex: <http://example.org/> .
+        _:later ex:city "Paris" ; ex:geo [ ex:lat 1 ; ex:long 2 ] .
+        ex:alice ex:name "Alice" ; ex:address _:later ; ex:knows ex:bob .
+        ex:bob ex:name "Bob" ; ex:address [ ex:city "Rome" ] .
+        _:cycle1 ex:next _:cycle2 .
+        _:cycle2 ex:next _:cycle1 .
+        ex:carol ex:ring _:cycle1 .
+    "#;
+
+    fn parse(doc: &str) -> FastGraph {
+        TRIPLE_PARSER_FACTORY
+            .try_new_parser(syntax::TURTLE, None, None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(doc)
+            .collect_triples()
+            .unwrap()
+    }
+
+    #[test_case("http://example.org/alice", 7)]
+    #[test_case("http://example.org/bob", 3)]
+    #[test_case("http://example.org/carol", 3)]
+    #[test_case("http://example.org/nobody", 0)]
+    pub fn extracts_cbd_following_blank_nodes(subject: &'static str, expected: usize) {
+        Lazy::force(&TRACING);
+        let subject = StaticTerm::new_iri(subject).unwrap();
+        let cbd = extract_cbd(
+            TRIPLE_PARSER_FACTORY
+                .try_new_parser(syntax::TURTLE, None, None as Option<BoxTerm>)
+                .unwrap()
+                .parse_str(DOC),
+            &subject,
+        )
+        .unwrap();
+        assert_eq!(cbd.len(), expected);
+        if expected > 0 {
+            assert_eq!(cbd[0][0].value(), subject.value());
+        }
+    }
+
+    #[test_case(syntax::N_TRIPLES)]
+    #[test_case(syntax::TURTLE)]
+    #[test_case(syntax::N_QUADS)]
+    pub fn serializes_cbd_in_chosen_syntax(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let subject = StaticTerm::new_iri("http://example.org/bob").unwrap();
+        let mut out = Vec::new();
+        let count = serialize_cbd(
+            TRIPLE_PARSER_FACTORY
+                .try_new_parser(syntax::TURTLE, None, None as Option<BoxTerm>)
+                .unwrap()
+                .parse_str(DOC),
+            &subject,
+            syntax_,
+            &mut out,
+            &DynSynSerializerFactories::default(),
+        )
+        .unwrap();
+        assert_eq!(count, 3);
+
+        let expected = parse(
+            r#"
+            @prefix ex: <http://example.org/> .
+            ex:bob ex:name "Bob" ; ex:address [ ex:city "Rome" ] .
+            "#,
+        );
+        let out = String::from_utf8(out).unwrap();
+        // Quads in default graph are also valid turtle.
+        let actual = parse(&out);
+        assert!(isomorphic_graphs(&expected, &actual).unwrap());
+    }
+}
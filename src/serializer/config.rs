@@ -0,0 +1,212 @@
+//! This module defines [`DynSynSerializerConfig`], syntax-agnostic serialization options, that serializer factories apply to whichever syntax is selected.
+//!
+//! Inserting a [`DynSynSerializerConfig`] into serializer config map of [`DynSynTripleSerializerFactory`](super::triples::DynSynTripleSerializerFactory), or [`DynSynQuadSerializerFactory`](super::quads::DynSynQuadSerializerFactory) overrides corresponding options of syntax specific configs, like `TurtleConfig`. Options that are left unset retain those of syntax specific configs.
+//!
+//! Support of each option by syntaxes is as follows:
+//!
+//! | option | turtle, trig | rdf-xml | n-triples, n-quads, nd-rdf-json |
+//! |--------|--------------|---------|---------------------------------|
+//! | `pretty` | yes | no, sophia's rdf-xml serializer has no formatting options | not applicable, one statement per line |
+//! | `prefix_map` | yes, when pretty-printing | no | not applicable, iris are always written in full |
+//! | `preserve_bnode_labels` | yes, it only disables pretty-printing | not applicable, labels are always preserved | not applicable, labels are always preserved |
+//!
+//! Options that a syntax doesn't support are ignored for that syntax, and documents are serialized as if they were not set.
+//!
+//! `pretty` option lets format-agnostic code get consistent pretty output with a single config, without populating config map with config of each syntax. It overrides `pretty` option of [`PrettyConfig`](super::pretty::PrettyConfig) too, which can still be used for finer formatting options, like indentation.
+//!
+//! Sophia's streaming turtle, and trig serializers write iris in full. Hence prefix map takes effect only when pretty-printing, as configured by `pretty` option, by syntax specific config, or by [`PrettyConfig`](super::pretty::PrettyConfig).
+//!
+//! Preserving blank node labels across a conversion takes both sides. On parser side, `preserve_bnode_labels` option of [`ParserConfig`](crate::parser::recovery::ParserConfig) makes dynsyn parser sources hand out blank nodes with labels of the document, in syntaxes like rdfa, whose parsers would otherwise relabel them, and keeps them from being skolemized. On serializer side, sophia's pretty turtle, and trig serializers render blank nodes inline, and invent labels for those, that can't be inlined. Hence, when `preserve_bnode_labels` option of [`DynSynSerializerConfig`] is set, turtle, and trig documents are not pretty-printed, so that original labels survive conversions, like n-quads to trig. It takes precedence over `pretty` option. Pretty output with original labels is not supported. Other syntaxes always write labels as they are, and are not affected by it.
+//!
+//! Prefixes of a parsed document, as tracked by [`DocumentPrefixes`](crate::parser::prefixes::DocumentPrefixes), can be re-emitted on serialization:
+//!
+//! ```
+//! use rdf_dynsyn::{
+//!     parser::triples::DynSynTripleParserFactory,
+//!     serializer::{config::DynSynSerializerConfig, triples::DynSynTripleSerializerFactory},
+//!     syntax,
+//! };
+//! use sophia_api::{
+//!     graph::{Graph, MutableGraph},
+//!     parser::TripleParser,
+//!     serializer::{Stringifier, TripleSerializer},
+//!     triple::{stream::TripleSource, Triple},
+//! };
+//! use sophia_inmem::graph::FastGraph;
+//! use sophia_term::BoxTerm;
+//! use type_map::concurrent::TypeMap;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let parser = DynSynTripleParserFactory::default()
+//!     .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)?;
+//! let mut source = parser.parse_str(
+//!     "@prefix foaf: <http://xmlns.com/foaf/0.1/> .\n<http://example.org/me> foaf:name \"Alice\" .",
+//! );
+//! let mut graph = FastGraph::new();
+//! source.for_each_triple(|t| {
+//!     graph.insert(t.s(), t.p(), t.o()).unwrap();
+//! })?;
+//!
+//! let mut config_map = TypeMap::new();
+//! config_map.insert(
+//!     DynSynSerializerConfig::new()
+//!         .with_pretty(true)
+//!         .with_prefix_map(source.prefixes().prefix_map()),
+//! );
+//! let out = DynSynTripleSerializerFactory::new(Some(config_map))
+//!     .try_new_stringifier(syntax::TURTLE)?
+//!     .serialize_triples(graph.triples())?
+//!     .to_string();
+//! assert!(out.contains("foaf:name"));
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use sophia_api::prefix::{PrefixBox, PrefixMap};
+use sophia_iri::IriBox;
+use sophia_turtle::serializer::turtle::TurtleConfig;
+use type_map::concurrent::TypeMap;
+
+/// Syntax-agnostic serialization options.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DynSynSerializerConfig {
+    /// Whether to pretty-print, where syntax supports it. If `None`, pretty-printing option of syntax specific config is retained.
+    pub pretty: Option<bool>,
+    /// Prefix map to use, where syntax supports prefixes. If `None`, prefix map of syntax specific config is retained.
+    pub prefix_map: Option<Vec<(PrefixBox, IriBox)>>,
+    /// Whether to write blank node labels as they are in serialized statements. It disables pretty-printing of turtle, and trig, which would otherwise relabel blank nodes. Other syntaxes always preserve labels. Pair it with `preserve_bnode_labels` option of [`ParserConfig`](crate::parser::recovery::ParserConfig), for labels to survive conversions.
+    pub preserve_bnode_labels: bool,
+}
+
+impl DynSynSerializerConfig {
+    /// Create a new config, that leaves all options of syntax specific configs as they are.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether to pretty-print.
+    pub fn with_pretty(mut self, pretty: bool) -> Self {
+        self.pretty = Some(pretty);
+        self
+    }
+
+    /// Set prefix map to a copy of given one.
+    pub fn with_prefix_map<P: PrefixMap + ?Sized>(self, pm: &P) -> Self {
+        self.with_own_prefix_map(pm.to_vec())
+    }
+
+    /// Set prefix map.
+    pub fn with_own_prefix_map(mut self, pm: Vec<(PrefixBox, IriBox)>) -> Self {
+        self.prefix_map = Some(pm);
+        self
+    }
+
+    /// Set whether to preserve blank node labels.
+    pub fn with_preserve_bnode_labels(mut self, preserve_bnode_labels: bool) -> Self {
+        self.preserve_bnode_labels = preserve_bnode_labels;
+        self
+    }
+
+    /// Apply options to given turtle, or trig config.
+    pub(crate) fn apply_to_turtle(&self, config: TurtleConfig) -> TurtleConfig {
+        let pretty = match self.preserve_bnode_labels {
+            true => Some(false),
+            false => self.pretty,
+        };
+        let config = match pretty {
+            Some(pretty) => config.with_pretty(pretty),
+            None => config,
+        };
+        match &self.prefix_map {
+            Some(pm) => config.with_own_prefix_map(pm.clone()),
+            None => config,
+        }
+    }
+}
+
+/// Serializer config map of a factory, with per-call overrides layered over it. A config in overrides takes precedence over config of same type in factory's map. Configs of other types are still taken from factory's map.
+pub(crate) struct LayeredConfigMap<'a> {
+    base: &'a TypeMap,
+    overrides: Option<&'a TypeMap>,
+}
+
+impl<'a> LayeredConfigMap<'a> {
+    pub(crate) fn new(base: &'a TypeMap, overrides: Option<&'a TypeMap>) -> Self {
+        Self { base, overrides }
+    }
+
+    /// Get config of given type, if it is configured in any layer.
+    pub(crate) fn get<T: 'static>(&self) -> Option<&'a T> {
+        self.overrides
+            .and_then(|m| m.get::<T>())
+            .or_else(|| self.base.get::<T>())
+    }
+
+    /// Get config of given type, or it's default, if it is not configured in any layer.
+    pub(crate) fn get_config<T: Clone + Default + 'static>(&self) -> T {
+        self.get::<T>().cloned().unwrap_or_default()
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use sophia_api::prefix::PrefixBox;
+    use sophia_iri::IriBox;
+    use sophia_turtle::serializer::turtle::TurtleConfig;
+    use test_case::test_case;
+
+    use super::DynSynSerializerConfig;
+    use crate::tests::TRACING;
+
+    #[test]
+    pub fn overrides_only_set_options_of_turtle_config() {
+        Lazy::force(&TRACING);
+        let base = TurtleConfig::new().with_pretty(true);
+        let default_prefix_map = base.prefix_map().to_vec();
+
+        let config = DynSynSerializerConfig::new().apply_to_turtle(base.clone());
+        assert_eq!(config.prefix_map(), &default_prefix_map[..]);
+
+        let pm = [(
+            PrefixBox::new_unchecked("ex".into()),
+            IriBox::new_unchecked("http://example.org/".into()),
+        )];
+        let config = DynSynSerializerConfig::new()
+            .with_prefix_map(&pm[..])
+            .apply_to_turtle(base);
+        assert_eq!(config.prefix_map(), &pm[..]);
+        assert!(config.pretty());
+    }
+
+    #[test_case(None, true)]
+    #[test_case(Some(true), true)]
+    #[test_case(Some(false), false)]
+    pub fn overrides_pretty_option_of_turtle_config_if_set(pretty: Option<bool>, expected: bool) {
+        Lazy::force(&TRACING);
+        let config = DynSynSerializerConfig {
+            pretty,
+            ..Default::default()
+        }
+        .apply_to_turtle(TurtleConfig::new().with_pretty(true).with_indentation("\t"));
+        assert_eq!(config.pretty(), expected);
+        assert_eq!(config.indentation(), "\t");
+    }
+
+    #[test_case(None)]
+    #[test_case(Some(true))]
+    pub fn preserving_bnode_labels_disables_pretty_printing(pretty: Option<bool>) {
+        Lazy::force(&TRACING);
+        let config = DynSynSerializerConfig {
+            pretty,
+            ..Default::default()
+        }
+        .with_preserve_bnode_labels(true)
+        .apply_to_turtle(TurtleConfig::new().with_pretty(true));
+        assert!(!config.pretty());
+    }
+}
@@ -0,0 +1,202 @@
+//! This module defines configuration of media type parameters, that dynsyn serializers attach to content type they report. It allows http layers to send `Content-Type` header exactly as reported by [`content_type`](super::triples::DynSynTripleSerializer::content_type) accessors of serializers, or by [`DynSynSerializerFactories::content_type`](super::serializable::DynSynSerializerFactories::content_type).
+//!
+//! Parameters are configured by inserting a [`ContentTypeConfig`] into serializer config map of [`DynSynTripleSerializerFactory`](super::triples::DynSynTripleSerializerFactory), or [`DynSynQuadSerializerFactory`](super::quads::DynSynQuadSerializerFactory). Without it, content type is just the canonical media type of the syntax.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{
+//!     serializer::{content_type::ContentTypeConfig, triples::DynSynTripleSerializerFactory},
+//!     syntax,
+//! };
+//! use type_map::concurrent::TypeMap;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut serializer_config_map = TypeMap::new();
+//! serializer_config_map.insert(
+//!     ContentTypeConfig::new()
+//!         .with_charset_utf8(true)
+//!         .with_param(syntax::N_TRIPLES, "profile", "http://example.org/profile"),
+//! );
+//! let factory = DynSynTripleSerializerFactory::new(Some(serializer_config_map));
+//!
+//! let serializer = factory.try_new_stringifier(syntax::TURTLE)?;
+//! assert_eq!(serializer.content_type().to_string(), "text/turtle; charset=utf-8");
+//! assert_eq!(
+//!     factory.content_type(syntax::N_TRIPLES)?.to_string(),
+//!     r#"application/n-triples; profile="http://example.org/profile"; charset=utf-8"#
+//! );
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::collections::HashMap;
+
+use mime::Mime;
+
+use crate::{
+    correspondence::SYNTAX_TO_MEDIA_TYPE_CORRESPONDENCE, media_type::takes_charset,
+    syntax::RdfSyntax,
+};
+
+/// Configuration of media type parameters, that serializers attach to reported content type.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContentTypeConfig {
+    /// If true, `charset=utf-8` parameter is attached to content type of every syntax, that [takes it](crate::media_type::takes_charset), unless a `charset` parameter is configured explicitly for that syntax.
+    pub charset_utf8: bool,
+    /// Parameters to attach to content type of each syntax, in order. `charset` parameters are attached only to syntaxes, that take it.
+    pub params: HashMap<RdfSyntax, Vec<(String, String)>>,
+}
+
+impl ContentTypeConfig {
+    /// Create a new config, that attaches no parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether to attach `charset=utf-8` parameter.
+    pub fn with_charset_utf8(mut self, charset_utf8: bool) -> Self {
+        self.charset_utf8 = charset_utf8;
+        self
+    }
+
+    /// Add a parameter to content type of given syntax. Values that are not http tokens are quoted.
+    ///
+    /// # Panics
+    /// Panics if `name` is not an http token, or if `value` contains characters other than visible ascii characters, and spaces, or if it contains `"`, or `\`.
+    pub fn with_param<N: ToString, V: ToString>(
+        mut self,
+        syntax_: RdfSyntax,
+        name: N,
+        value: V,
+    ) -> Self {
+        let (name, value) = (name.to_string(), value.to_string());
+        assert!(
+            !name.is_empty() && name.chars().all(is_token_char),
+            "parameter name must be an http token"
+        );
+        assert!(
+            value
+                .chars()
+                .all(|c| (c == ' ' || c.is_ascii_graphic()) && c != '"' && c != '\\'),
+            "parameter value can't contain quotes, backslashes, or non-printable characters"
+        );
+        self.params.entry(syntax_).or_default().push((name, value));
+        self
+    }
+
+    /// Get content type of given syntax, with configured parameters attached. Returns `None` if syntax has no known media type.
+    pub fn content_type(&self, syntax_: RdfSyntax) -> Option<Mime> {
+        let media_type = SYNTAX_TO_MEDIA_TYPE_CORRESPONDENCE.get(&syntax_)?.value;
+        let params = self.params.get(&syntax_).map(Vec::as_slice).unwrap_or(&[]);
+        let mut content_type = media_type.essence_str().to_string();
+        for (name, value) in params {
+            if name.eq_ignore_ascii_case("charset") && !takes_charset(syntax_) {
+                continue;
+            }
+            content_type.push_str(&format!("; {}={}", name, quoted(value)));
+        }
+        if self.charset_utf8
+            && takes_charset(syntax_)
+            && !params
+                .iter()
+                .any(|(n, _)| n.eq_ignore_ascii_case("charset"))
+        {
+            content_type.push_str("; charset=utf-8");
+        }
+        // Names are tokens, and values are quoted as needed. Hence it always parses.
+        content_type.parse().ok()
+    }
+}
+
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
+}
+
+/// Quote given parameter value, if it is not a token.
+fn quoted(value: &str) -> String {
+    if !value.is_empty() && value.chars().all(is_token_char) {
+        return value.to_string();
+    }
+    format!("\"{}\"", value)
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use test_case::test_case;
+
+    use super::ContentTypeConfig;
+    use crate::{
+        syntax::{self, RdfSyntax},
+        tests::TRACING,
+    };
+
+    #[test_case(ContentTypeConfig::new(), syntax::TURTLE, "text/turtle")]
+    #[test_case(
+        ContentTypeConfig::new().with_charset_utf8(true),
+        syntax::N_QUADS,
+        "application/n-quads; charset=utf-8"
+    )]
+    #[test_case(
+        ContentTypeConfig::new()
+            .with_charset_utf8(true)
+            .with_param(syntax::RDF_XML, "charset", "us-ascii"),
+        syntax::RDF_XML,
+        "application/rdf+xml; charset=us-ascii"
+    )]
+    #[test_case(
+        ContentTypeConfig::new().with_charset_utf8(true),
+        syntax::ND_RDF_JSON,
+        "application/x-nd-rdf-json"
+    )]
+    #[test_case(
+        ContentTypeConfig::new().with_param(syntax::HDT, "charset", "utf-8"),
+        syntax::HDT,
+        "application/vnd.hdt"
+    )]
+    #[test_case(
+        ContentTypeConfig::new().with_param(syntax::JSON_LD, "profile", "a b:c"),
+        syntax::JSON_LD,
+        r#"application/ld+json; profile="a b:c""#
+    )]
+    #[test_case(
+        ContentTypeConfig::new().with_param(syntax::JSON_LD, "profile", "x"),
+        syntax::TURTLE,
+        "text/turtle"
+    )]
+    pub fn attaches_configured_params(
+        config: ContentTypeConfig,
+        syntax_: RdfSyntax,
+        expected: &str,
+    ) {
+        Lazy::force(&TRACING);
+        assert_eq!(config.content_type(syntax_).unwrap().to_string(), expected);
+    }
+
+    #[test]
+    pub fn returns_none_for_syntax_without_media_type() {
+        Lazy::force(&TRACING);
+        assert!(ContentTypeConfig::new()
+            .content_type(RdfSyntax("http://example.org/unknown"))
+            .is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn rejects_non_token_param_name() {
+        Lazy::force(&TRACING);
+        let _ = ContentTypeConfig::new().with_param(syntax::TURTLE, "pro file", "x");
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn rejects_param_value_with_quotes() {
+        Lazy::force(&TRACING);
+        let _ = ContentTypeConfig::new().with_param(syntax::TURTLE, "profile", "a\"b");
+    }
+}
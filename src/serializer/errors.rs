@@ -0,0 +1,182 @@
+use std::fmt::Display;
+
+use crate::{
+    error_code::{self, ErrorCode},
+    syntax::{RdfSyntax, UnKnownSyntaxError},
+};
+
+use super::{quads::DynSynQuadSerializerFactory, triples::DynSynTripleSerializerFactory};
+
+/// Kind of statements, that a serializer serializes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SerializerRole {
+    /// Serializes triples, from graphs, or triple sources.
+    Triples,
+    /// Serializes quads, from datasets, or quad sources.
+    Quads,
+}
+
+impl Display for SerializerRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Triples => write!(f, "triples"),
+            Self::Quads => write!(f, "quads"),
+        }
+    }
+}
+
+/// An error of a syntax being requested from serializer for one kind of statements, while it is supported only by serializers for other kind. Callers can use it to offer a fallback, like serializing union graph of a dataset instead.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Syntax {syntax} can't be serialized from {requested_role}, but only from {}", supported_roles[0])]
+pub struct SyntaxRoleMismatch {
+    /// Requested syntax.
+    pub syntax: RdfSyntax,
+    /// Role of the serializer, syntax is requested from.
+    pub requested_role: SerializerRole,
+    /// Roles of serializers, that support the syntax. It is never empty.
+    pub supported_roles: Vec<SerializerRole>,
+}
+
+impl ErrorCode for SyntaxRoleMismatch {
+    fn code(&self) -> &'static str {
+        error_code::DYNSYN_SYNTAX_ROLE_MISMATCH
+    }
+}
+
+/// An error in creating a serializer from a dynsyn serializer factory.
+#[derive(Debug, thiserror::Error)]
+pub enum NewSerializerError {
+    #[error(transparent)]
+    UnKnownSyntax(#[from] UnKnownSyntaxError),
+    #[error(transparent)]
+    SyntaxRoleMismatch(#[from] SyntaxRoleMismatch),
+}
+
+impl NewSerializerError {
+    /// Create an error for `syntax_` being not supported by serializer of `requested_role`. It will be a [`SyntaxRoleMismatch`], if syntax is supported by serializers of other role.
+    pub(crate) fn unsupported(syntax_: RdfSyntax, requested_role: SerializerRole) -> Self {
+        let supported_roles: Vec<SerializerRole> = [
+            (
+                SerializerRole::Triples,
+                DynSynTripleSerializerFactory::default()
+                    .content_type(syntax_)
+                    .is_ok(),
+            ),
+            (
+                SerializerRole::Quads,
+                DynSynQuadSerializerFactory::default()
+                    .content_type(syntax_)
+                    .is_ok(),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(role, is_supported)| (is_supported && role != requested_role).then_some(role))
+        .collect();
+
+        if supported_roles.is_empty() {
+            UnKnownSyntaxError(syntax_).into()
+        } else {
+            SyntaxRoleMismatch {
+                syntax: syntax_,
+                requested_role,
+                supported_roles,
+            }
+            .into()
+        }
+    }
+
+    /// Returns syntax, for which serializer couldn't be created.
+    pub fn syntax(&self) -> RdfSyntax {
+        match self {
+            Self::UnKnownSyntax(e) => e.0,
+            Self::SyntaxRoleMismatch(e) => e.syntax,
+        }
+    }
+}
+
+impl ErrorCode for NewSerializerError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::UnKnownSyntax(e) => e.code(),
+            Self::SyntaxRoleMismatch(e) => e.code(),
+        }
+    }
+}
+
+/// For callers, that fall back between serializers of both roles, syntax that none of them support is unknown.
+impl From<NewSerializerError> for UnKnownSyntaxError {
+    fn from(e: NewSerializerError) -> Self {
+        Self(e.syntax())
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use test_case::test_case;
+
+    use super::{NewSerializerError, SerializerRole};
+    use crate::{
+        error_code::{self, ErrorCode},
+        serializer::{quads::DynSynQuadSerializerFactory, triples::DynSynTripleSerializerFactory},
+        syntax::{self, RdfSyntax, UnKnownSyntaxError},
+        tests::TRACING,
+    };
+
+    #[test_case(syntax::N_QUADS, SerializerRole::Quads)]
+    #[test_case(syntax::TRIG, SerializerRole::Quads)]
+    pub fn quads_only_syntaxes_will_report_role_mismatch_for_triples(
+        syntax_: RdfSyntax,
+        supported_role: SerializerRole,
+    ) {
+        Lazy::force(&TRACING);
+        let e = DynSynTripleSerializerFactory::default()
+            .try_new_stringifier(syntax_)
+            .unwrap_err();
+        assert_eq!(e.code(), error_code::DYNSYN_SYNTAX_ROLE_MISMATCH);
+        let e = match e {
+            NewSerializerError::SyntaxRoleMismatch(e) => e,
+            e => panic!("unexpected error: {}", e),
+        };
+        assert_eq!(e.syntax, syntax_);
+        assert_eq!(e.requested_role, SerializerRole::Triples);
+        assert_eq!(e.supported_roles, vec![supported_role]);
+        assert_eq!(
+            e.to_string(),
+            format!(
+                "Syntax {} can't be serialized from triples, but only from quads",
+                syntax_
+            )
+        );
+    }
+
+    #[test_case(syntax::N_TRIPLES)]
+    #[test_case(syntax::TURTLE)]
+    #[cfg_attr(feature = "rdf-xml", test_case(syntax::RDF_XML))]
+    pub fn triples_only_syntaxes_will_report_role_mismatch_for_quads(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let e = DynSynQuadSerializerFactory::default()
+            .try_new_stringifier(syntax_)
+            .unwrap_err();
+        let e = match e {
+            NewSerializerError::SyntaxRoleMismatch(e) => e,
+            e => panic!("unexpected error: {}", e),
+        };
+        assert_eq!(e.requested_role, SerializerRole::Quads);
+        assert_eq!(e.supported_roles, vec![SerializerRole::Triples]);
+    }
+
+    #[test_case(syntax::JSON_LD)]
+    #[test_case(syntax::N3)]
+    pub fn syntaxes_unsupported_by_both_will_report_unknown_syntax(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let e = DynSynQuadSerializerFactory::default()
+            .try_new_stringifier(syntax_)
+            .unwrap_err();
+        assert_eq!(e.code(), error_code::DYNSYN_UNKNOWN_SYNTAX);
+        assert_eq!(UnKnownSyntaxError::from(e).0, syntax_);
+    }
+}
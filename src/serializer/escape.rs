@@ -0,0 +1,365 @@
+//! This module defines configuration to escape non-ascii characters in serialized documents, and helpers that do the escaping.
+//!
+//! Sophia serializers for [`n-triples`](crate::syntax::N_TRIPLES), and [`n-quads`](crate::syntax::N_QUADS) have an `ascii` flag in their configs, but it is not implemented, and serializing with it set panics. Hence dynsyn serializers escape their output by themselves, consistently across all text syntaxes they support:
+//!
+//! - In [`n-triples`](crate::syntax::N_TRIPLES), [`n-quads`](crate::syntax::N_QUADS), [`turtle`](crate::syntax::TURTLE), and [`trig`](crate::syntax::TRIG), non-ascii characters in iris, and string literals are escaped as `\uXXXX`, or `\UXXXXXXXX`. These syntaxes don't allow escapes in blank-node labels, and prefixed names, hence non-ascii characters in them are written as is.
+//! - In [`rdf-xml`](crate::syntax::RDF_XML), and [`trix`](crate::syntax::TRIX), non-ascii characters in text, and attribute values are escaped as character references `&#xHH;`. Non-ascii characters in element, and attribute names are written as is.
+//! - In [`nd-rdf-json`](crate::syntax::ND_RDF_JSON), non-ascii characters are escaped as `\uXXXX`, with surrogate pairs for characters outside basic multilingual plane.
+//!
+//! Escaping can be enabled by inserting an [`EscapeConfig`] into serializer config map of [`DynSynTripleSerializerFactory`](super::triples::DynSynTripleSerializerFactory), or [`DynSynQuadSerializerFactory`](super::quads::DynSynQuadSerializerFactory). `ascii` flag of `NtConfig`, and `NqConfig` is not honoured, as sophia neither implements, nor exposes it. Dynsyn serializers always clear it.
+//!
+//! N-triples, n-quads, trix, and nd-rdf-json are written by dynsyn's own term writers, which escape while writing terms. Turtle, trig, and rdf-xml are written by sophia serializers, that have no hook to write terms. Hence their output is escaped by a writer adaptor, that tracks lexical context of written document.
+
+use std::io;
+
+use sophia_api::{
+    ns::xsd,
+    quad::{stream::QuadSource, Quad},
+    serializer::{QuadSerializer, TripleSerializer},
+    term::{TTerm, TermKind},
+    triple::{
+        stream::{StreamResult, TripleSource},
+        Triple,
+    },
+};
+
+/// Configuration to escape non-ascii characters in serialized documents. It applies to all syntaxes supported by dynsyn serializers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EscapeConfig {
+    /// If true, non-ascii characters are escaped, wherever syntax allows escaping.
+    pub escape_non_ascii: bool,
+}
+
+impl EscapeConfig {
+    /// Create a new config, that doesn't escape non-ascii characters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether to escape non-ascii characters.
+    pub fn with_escape_non_ascii(mut self, escape_non_ascii: bool) -> Self {
+        self.escape_non_ascii = escape_non_ascii;
+        self
+    }
+}
+
+/// Escaping conventions of supported syntaxes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EscapeStyle {
+    /// Escaping as in n-triples, n-quads, turtle, and trig.
+    Turtle,
+    /// Escaping as in rdf-xml, and trix.
+    #[cfg_attr(not(any(feature = "rdf-xml", feature = "trix")), allow(dead_code))]
+    Xml,
+    /// Escaping as in json.
+    Json,
+}
+
+/// Lexical context of writer, that decides whether a non-ascii character can be escaped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LexState {
+    Normal,
+    Iri,
+    String,
+    StringEscape,
+    Tag,
+    Attribute(u8),
+}
+
+/// Write given non-ascii character escaped, as per given [`EscapeStyle`].
+pub(crate) fn write_escaped_char<W: io::Write>(
+    w: &mut W,
+    style: EscapeStyle,
+    c: char,
+) -> io::Result<()> {
+    match style {
+        EscapeStyle::Turtle if (c as u32) <= 0xFFFF => write!(w, "\\u{:04X}", c as u32),
+        EscapeStyle::Turtle => write!(w, "\\U{:08X}", c as u32),
+        EscapeStyle::Xml => write!(w, "&#x{:X};", c as u32),
+        EscapeStyle::Json => c
+            .encode_utf16(&mut [0; 2])
+            .iter()
+            .try_for_each(|u| write!(w, "\\u{:04x}", u)),
+    }
+}
+
+/// A writer adaptor, that escapes non-ascii characters in written document, as per given [`EscapeStyle`]. If no style is given, it writes through as is. It is used for documents written by sophia serializers, whose terms can't be escaped while they are written.
+#[derive(Debug)]
+pub(crate) struct EscapingWrite<W> {
+    write: W,
+    style: Option<EscapeStyle>,
+    state: LexState,
+    /// Bytes of a partially written utf-8 sequence.
+    pending: Vec<u8>,
+}
+
+impl<W: io::Write> EscapingWrite<W> {
+    pub(crate) fn new(write: W, style: Option<EscapeStyle>) -> Self {
+        Self {
+            write,
+            style,
+            state: LexState::Normal,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Get reference to underlying writer.
+    pub(crate) fn get_ref(&self) -> &W {
+        &self.write
+    }
+
+    /// Get mutable reference to underlying writer.
+    pub(crate) fn get_mut(&mut self) -> &mut W {
+        &mut self.write
+    }
+
+    fn can_escape(&self, style: EscapeStyle) -> bool {
+        match style {
+            EscapeStyle::Turtle => matches!(
+                self.state,
+                LexState::Iri | LexState::String | LexState::StringEscape
+            ),
+            EscapeStyle::Xml => matches!(self.state, LexState::Normal | LexState::Attribute(_)),
+            EscapeStyle::Json => true,
+        }
+    }
+
+    fn advance(&mut self, style: EscapeStyle, b: u8) {
+        self.state = match (style, self.state, b) {
+            (EscapeStyle::Turtle, LexState::Normal, b'<') => LexState::Iri,
+            (EscapeStyle::Turtle, LexState::Normal, b'"') => LexState::String,
+            (EscapeStyle::Turtle, LexState::Iri, b'>') => LexState::Normal,
+            (EscapeStyle::Turtle, LexState::String, b'\\') => LexState::StringEscape,
+            (EscapeStyle::Turtle, LexState::String, b'"') => LexState::Normal,
+            (EscapeStyle::Turtle, LexState::StringEscape, _) => LexState::String,
+            (EscapeStyle::Xml, LexState::Normal, b'<') => LexState::Tag,
+            (EscapeStyle::Xml, LexState::Tag, b'"' | b'\'') => LexState::Attribute(b),
+            (EscapeStyle::Xml, LexState::Tag, b'>') => LexState::Normal,
+            (EscapeStyle::Xml, LexState::Attribute(q), _) if q == b => LexState::Tag,
+            (_, state, _) => state,
+        };
+    }
+
+    fn escape(&mut self, style: EscapeStyle, buf: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(buf.len());
+        for &b in buf {
+            if b.is_ascii() && self.pending.is_empty() {
+                self.advance(style, b);
+                out.push(b);
+                continue;
+            }
+            self.pending.push(b);
+            let expected_len = match self.pending[0] {
+                0xF0..=0xFF => 4,
+                0xE0..=0xEF => 3,
+                _ => 2,
+            };
+            if self.pending.len() < expected_len {
+                continue;
+            }
+            match std::str::from_utf8(&self.pending) {
+                Ok(s) if self.can_escape(style) => s
+                    .chars()
+                    // Writing to a vec doesn't fail.
+                    .for_each(|c| {
+                        let _ = write_escaped_char(&mut out, style, c);
+                    }),
+                // Write invalid, or unescapable sequences as is.
+                _ => out.extend_from_slice(&self.pending),
+            }
+            self.pending.clear();
+        }
+        out
+    }
+}
+
+impl<W: io::Write> io::Write for EscapingWrite<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.style {
+            Some(style) => {
+                let out = self.escape(style, buf);
+                self.write.write_all(&out)?;
+                Ok(buf.len())
+            }
+            None => self.write.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write.flush()
+    }
+}
+
+/// An n-triples, and n-quads serializer, that escapes non-ascii characters of iris, and literals while writing them. It stands in for sophia serializers in ascii mode, which is not implemented.
+pub(crate) struct AsciiNtSerializer<W> {
+    write: W,
+}
+
+impl<W: io::Write> AsciiNtSerializer<W> {
+    pub(crate) fn new(write: W) -> Self {
+        Self { write }
+    }
+
+    fn write_statement<T>(&mut self, terms: &[&T]) -> io::Result<()>
+    where
+        T: TTerm + ?Sized,
+    {
+        for (i, t) in terms.iter().enumerate() {
+            if i > 0 {
+                self.write.write_all(b" ")?;
+            }
+            write_nt_term(&mut self.write, *t)?;
+        }
+        self.write.write_all(b".\n")
+    }
+}
+
+impl<W: io::Write> TripleSerializer for AsciiNtSerializer<W> {
+    type Error = io::Error;
+
+    fn serialize_triples<TS>(
+        &mut self,
+        mut source: TS,
+    ) -> StreamResult<&mut Self, TS::Error, Self::Error>
+    where
+        TS: TripleSource,
+    {
+        source
+            .try_for_each_triple(|t| self.write_statement(&[t.s(), t.p(), t.o()]))
+            .map(|_| self)
+    }
+}
+
+impl<W: io::Write> QuadSerializer for AsciiNtSerializer<W> {
+    type Error = io::Error;
+
+    fn serialize_quads<QS>(
+        &mut self,
+        mut source: QS,
+    ) -> StreamResult<&mut Self, QS::Error, Self::Error>
+    where
+        QS: QuadSource,
+    {
+        source
+            .try_for_each_quad(|q| match q.g() {
+                Some(g) => self.write_statement(&[q.s(), q.p(), q.o(), g]),
+                None => self.write_statement(&[q.s(), q.p(), q.o()]),
+            })
+            .map(|_| self)
+    }
+}
+
+/// Write given term in n-triples form, with non-ascii characters of iris, and literals escaped.
+fn write_nt_term<W, T>(w: &mut W, t: &T) -> io::Result<()>
+where
+    W: io::Write,
+    T: TTerm + ?Sized,
+{
+    match t.kind() {
+        TermKind::Iri => {
+            w.write_all(b"<")?;
+            write_nt_text(w, &t.value())?;
+            w.write_all(b">")
+        }
+        TermKind::Literal => {
+            w.write_all(b"\"")?;
+            write_nt_text(w, &t.value())?;
+            w.write_all(b"\"")?;
+            match (t.language(), t.datatype()) {
+                (Some(tag), _) => write!(w, "@{}", tag),
+                (None, Some(dt)) if xsd::string != dt => {
+                    w.write_all(b"^^")?;
+                    write_nt_term(w, &dt)
+                }
+                _ => Ok(()),
+            }
+        }
+        TermKind::BlankNode => write!(w, "_:{}", t.value()),
+        TermKind::Variable => write!(w, "?{}", t.value()),
+    }
+}
+
+/// Write given text with escapes of n-triples strings, and with non-ascii characters escaped.
+fn write_nt_text<W: io::Write>(w: &mut W, v: &str) -> io::Result<()> {
+    for c in v.chars() {
+        match c {
+            '\n' => w.write_all(b"\\n")?,
+            '\r' => w.write_all(b"\\r")?,
+            '\\' => w.write_all(b"\\\\")?,
+            '"' => w.write_all(b"\\\"")?,
+            c if c.is_ascii() => w.write_all(&[c as u8])?,
+            c => write_escaped_char(w, EscapeStyle::Turtle, c)?,
+        }
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use once_cell::sync::Lazy;
+    use sophia_api::serializer::QuadSerializer;
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+
+    use super::{AsciiNtSerializer, EscapeStyle, EscapingWrite};
+    use crate::tests::TRACING;
+
+    #[test_case(
+        EscapeStyle::Turtle,
+        "<http://ex.org/é> _:é \"é😀\\\"é\" .",
+        "<http://ex.org/\\u00E9> _:é \"\\u00E9\\U0001F600\\\"\\u00E9\" ."
+    )]
+    #[test_case(EscapeStyle::Turtle, "ex:é \"a\"@en .", "ex:é \"a\"@en .")]
+    #[test_case(
+        EscapeStyle::Xml,
+        "<ex:é a=\"é\" b='é'>é😀</ex:é>",
+        "<ex:é a=\"&#xE9;\" b='&#xE9;'>&#xE9;&#x1F600;</ex:é>"
+    )]
+    #[test_case(
+        EscapeStyle::Json,
+        "{\"v\":\"é😀\"}",
+        "{\"v\":\"\\u00e9\\ud83d\\ude00\"}"
+    )]
+    pub fn escapes_non_ascii_where_allowed(style: EscapeStyle, doc: &str, expected: &str) {
+        Lazy::force(&TRACING);
+        let mut w = EscapingWrite::new(Vec::new(), Some(style));
+        w.write_all(doc.as_bytes()).unwrap();
+        assert_eq!(String::from_utf8(w.get_ref().clone()).unwrap(), expected);
+    }
+
+    #[test]
+    pub fn escapes_utf8_sequences_split_across_writes() {
+        Lazy::force(&TRACING);
+        let mut w = EscapingWrite::new(Vec::new(), Some(EscapeStyle::Turtle));
+        for b in "\"😀\"".as_bytes() {
+            w.write_all(&[*b]).unwrap();
+        }
+        assert_eq!(w.get_ref().as_slice(), b"\"\\U0001F600\"");
+    }
+
+    #[test]
+    pub fn escapes_terms_of_n_quads_while_writing_them() {
+        Lazy::force(&TRACING);
+        let dataset = vec![(
+            [
+                BoxTerm::new_iri("http://ex.org/é").unwrap(),
+                BoxTerm::new_iri("http://ex.org/p").unwrap(),
+                BoxTerm::new_literal_lang("é😀\"\n", "fr").unwrap(),
+            ],
+            Some(BoxTerm::new_bnode("g").unwrap()),
+        )];
+        let mut out = Vec::new();
+        AsciiNtSerializer::new(&mut out)
+            .serialize_dataset(&dataset)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "<http://ex.org/\\u00E9> <http://ex.org/p> \"\\u00E9\\U0001F600\\\"\\n\"@fr _:g.\n"
+        );
+    }
+}
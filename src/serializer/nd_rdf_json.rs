@@ -0,0 +1,225 @@
+//! Serializer for non-standard [`nd-rdf-json`](crate::syntax::ND_RDF_JSON) debug syntax. It writes one json object per statement, one per line, which is convenient to grep, and to ship with logs.
+//!
+//! Each object has keys `s`, `p`, `o`, and `g`, with each term encoded as an object with `kind`, and `value` keys. Literal terms additionally have `datatype` key, and `language` key if they are language-tagged. `g` is `null` for statements in default graph, and for triples.
+//!
+//! ```text
+//! {"s":{"kind":"iri","value":"http://example.org/#me"},"p":{"kind":"iri","value":"http://schema.org/name"},"o":{"kind":"literal","value":"Me","datatype":"http://www.w3.org/1999/02/22-rdf-syntax-ns#langString","language":"en"},"g":null}
+//! ```
+
+use std::io;
+
+use sophia_api::{
+    quad::{stream::QuadSource, Quad},
+    serializer::{QuadSerializer, Stringifier, TripleSerializer},
+    term::{TTerm, TermKind},
+    triple::{
+        stream::{StreamResult, TripleSource},
+        Triple,
+    },
+};
+
+use super::escape::{write_escaped_char, EscapeStyle};
+
+/// Serializer for nd-rdf-json syntax.
+pub struct NdRdfJsonSerializer<W> {
+    write: W,
+    escape_non_ascii: bool,
+}
+
+impl<W> NdRdfJsonSerializer<W>
+where
+    W: io::Write,
+{
+    /// Build a new nd-rdf-json serializer writing to `write`.
+    pub fn new(write: W) -> Self {
+        Self {
+            write,
+            escape_non_ascii: false,
+        }
+    }
+
+    /// Set whether to escape non-ascii characters of strings.
+    pub(crate) fn with_escape_non_ascii(mut self, escape_non_ascii: bool) -> Self {
+        self.escape_non_ascii = escape_non_ascii;
+        self
+    }
+
+    fn write_statement<T>(&mut self, s: &T, p: &T, o: &T, g: Option<&T>) -> io::Result<()>
+    where
+        T: TTerm + ?Sized,
+    {
+        let (w, ascii) = (&mut self.write, self.escape_non_ascii);
+        w.write_all(b"{\"s\":")?;
+        write_term(w, s, ascii)?;
+        w.write_all(b",\"p\":")?;
+        write_term(w, p, ascii)?;
+        w.write_all(b",\"o\":")?;
+        write_term(w, o, ascii)?;
+        w.write_all(b",\"g\":")?;
+        match g {
+            Some(g) => write_term(w, g, ascii)?,
+            None => w.write_all(b"null")?,
+        }
+        w.write_all(b"}\n")
+    }
+}
+
+impl<W> TripleSerializer for NdRdfJsonSerializer<W>
+where
+    W: io::Write,
+{
+    type Error = io::Error;
+
+    fn serialize_triples<TS>(
+        &mut self,
+        mut source: TS,
+    ) -> StreamResult<&mut Self, TS::Error, Self::Error>
+    where
+        TS: TripleSource,
+    {
+        source
+            .try_for_each_triple(|t| self.write_statement(t.s(), t.p(), t.o(), None))
+            .map(|_| self)
+    }
+}
+
+impl<W> QuadSerializer for NdRdfJsonSerializer<W>
+where
+    W: io::Write,
+{
+    type Error = io::Error;
+
+    fn serialize_quads<QS>(
+        &mut self,
+        mut source: QS,
+    ) -> StreamResult<&mut Self, QS::Error, Self::Error>
+    where
+        QS: QuadSource,
+    {
+        source
+            .try_for_each_quad(|q| self.write_statement(q.s(), q.p(), q.o(), q.g()))
+            .map(|_| self)
+    }
+}
+
+impl Stringifier for NdRdfJsonSerializer<Vec<u8>> {
+    fn as_utf8(&self) -> &[u8] {
+        &self.write[..]
+    }
+}
+
+/// Write given term as a json object. Non-ascii characters of it's strings are escaped, if `ascii` is true.
+pub(crate) fn write_term<W, T>(w: &mut W, t: &T, ascii: bool) -> io::Result<()>
+where
+    W: io::Write,
+    T: TTerm + ?Sized,
+{
+    let kind = match t.kind() {
+        TermKind::Iri => "iri",
+        TermKind::Literal => "literal",
+        TermKind::BlankNode => "bnode",
+        TermKind::Variable => "variable",
+    };
+    write!(w, "{{\"kind\":\"{}\",\"value\":", kind)?;
+    write_json_str(w, &t.value(), ascii)?;
+    if let Some(dt) = t.datatype() {
+        w.write_all(b",\"datatype\":")?;
+        write_json_str(w, &dt.value(), ascii)?;
+    }
+    if let Some(lang) = t.language() {
+        w.write_all(b",\"language\":")?;
+        write_json_str(w, lang, ascii)?;
+    }
+    w.write_all(b"}")
+}
+
+/// Write given string as a json string. Non-ascii characters are escaped, if `ascii` is true.
+pub(crate) fn write_json_str<W: io::Write>(w: &mut W, v: &str, ascii: bool) -> io::Result<()> {
+    w.write_all(b"\"")?;
+    let mut start = 0;
+    for (i, c) in v.char_indices() {
+        let escaped = match c {
+            '"' => "\\\"",
+            '\\' => "\\\\",
+            '\n' => "\\n",
+            '\r' => "\\r",
+            '\t' => "\\t",
+            c if c < '\u{20}' || (ascii && !c.is_ascii()) => "",
+            _ => continue,
+        };
+        w.write_all(&v.as_bytes()[start..i])?;
+        if escaped.is_empty() {
+            write_escaped_char(w, EscapeStyle::Json, c)?;
+        } else {
+            w.write_all(escaped.as_bytes())?;
+        }
+        start = i + c.len_utf8();
+    }
+    w.write_all(&v.as_bytes()[start..])?;
+    w.write_all(b"\"")
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use sophia_api::{ns::xsd, serializer::QuadSerializer};
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+
+    use crate::tests::TRACING;
+
+    use super::*;
+
+    #[test_case("plain", false, "\"plain\"")]
+    #[test_case("quo\"te\\", false, "\"quo\\\"te\\\\\"")]
+    #[test_case("multi\nline\ttext\r", false, "\"multi\\nline\\ttext\\r\"")]
+    #[test_case("bell\u{7}", false, "\"bell\\u0007\"")]
+    #[test_case("ಕನ್ನಡ", false, "\"ಕನ್ನಡ\"")]
+    #[test_case("é😀\"", true, "\"\\u00e9\\ud83d\\ude00\\\"\"")]
+    pub fn escapes_json_strings(v: &str, ascii: bool, expected: &str) {
+        Lazy::force(&TRACING);
+        let mut out = Vec::new();
+        write_json_str(&mut out, v, ascii).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    pub fn writes_one_object_per_statement() {
+        Lazy::force(&TRACING);
+        let s = BoxTerm::new_iri("http://example.org/#me").unwrap();
+        let p = BoxTerm::new_iri("http://example.org/#p").unwrap();
+        let dataset = vec![
+            (
+                [s.clone(), p.clone(), BoxTerm::new_bnode("b1").unwrap()],
+                None,
+            ),
+            (
+                [
+                    s.clone(),
+                    p.clone(),
+                    BoxTerm::new_literal_lang("Me", "en").unwrap(),
+                ],
+                Some(s.clone()),
+            ),
+            (
+                [s, p, BoxTerm::new_literal_dt("42", xsd::integer).unwrap()],
+                None,
+            ),
+        ];
+        let mut serializer = NdRdfJsonSerializer::new(Vec::new());
+        let out = serializer.serialize_dataset(&dataset).unwrap().to_string();
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                r#"{"s":{"kind":"iri","value":"http://example.org/#me"},"p":{"kind":"iri","value":"http://example.org/#p"},"o":{"kind":"bnode","value":"b1"},"g":null}"#,
+                r#"{"s":{"kind":"iri","value":"http://example.org/#me"},"p":{"kind":"iri","value":"http://example.org/#p"},"o":{"kind":"literal","value":"Me","datatype":"http://www.w3.org/1999/02/22-rdf-syntax-ns#langString","language":"en"},"g":{"kind":"iri","value":"http://example.org/#me"}}"#,
+                r#"{"s":{"kind":"iri","value":"http://example.org/#me"},"p":{"kind":"iri","value":"http://example.org/#p"},"o":{"kind":"literal","value":"42","datatype":"http://www.w3.org/2001/XMLSchema#integer"},"g":null}"#,
+            ]
+        );
+    }
+}
@@ -0,0 +1,247 @@
+//! This module provides [`NegotiatedSerializer`], that bundles outcome of content negotiation: chosen syntax, it's content type, and serializer config to serialize into it. Frameworks can negotiate in a middleware, and serialize in a handler, with a writer supplied then, without re-doing the lookup.
+//!
+//! Example:
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use rdf_dynsyn::{
+//!     serializer::{negotiated::NegotiatedSerializer, serializable::DynSynSerializerFactories},
+//!     syntax,
+//! };
+//! use sophia_term::StaticTerm;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = Arc::new(DynSynSerializerFactories::default());
+//!
+//! // In middleware.
+//! let negotiated = NegotiatedSerializer::negotiate(
+//!     "application/n-triples;q=0.5, text/turtle;q=0.9, */*;q=0.1",
+//!     config,
+//! )?;
+//! assert_eq!(negotiated.syntax(), syntax::TURTLE);
+//! assert_eq!(negotiated.content_type().essence_str(), "text/turtle");
+//!
+//! // In handler.
+//! let me = StaticTerm::new_iri("http://example.org/#me")?;
+//! let mut body = Vec::new();
+//! negotiated.serialize(&vec![[me, me, me]], &mut body)?;
+//! assert!(!body.is_empty());
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::{io, sync::Arc};
+
+use mime::Mime;
+
+use crate::{
+    conneg::best_match,
+    error_code::{self, ErrorCode},
+    syntax::{RdfSyntax, UnKnownSyntaxError, KNOWN_SYNTAXES},
+};
+
+use super::serializable::{DynSynSerializable, DynSynSerializeError, DynSynSerializerFactories};
+
+/// Outcome of content negotiation, that can serialize into negotiated syntax, to a writer supplied later.
+///
+/// It is cheap to clone, as config is shared.
+#[derive(Clone)]
+pub struct NegotiatedSerializer {
+    syntax: RdfSyntax,
+    content_type: Mime,
+    config: Arc<DynSynSerializerFactories>,
+}
+
+impl std::fmt::Debug for NegotiatedSerializer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NegotiatedSerializer")
+            .field("syntax", &self.syntax)
+            .field("content_type", &self.content_type)
+            .finish()
+    }
+}
+
+impl NegotiatedSerializer {
+    /// Create a negotiated serializer for already chosen `syntax_`.
+    ///
+    /// # Errors
+    /// returns [`UnKnownSyntaxError`] if `syntax_` is not supported by any of serializers of `config`.
+    pub fn new(
+        syntax_: RdfSyntax,
+        config: Arc<DynSynSerializerFactories>,
+    ) -> Result<Self, UnKnownSyntaxError> {
+        Ok(Self {
+            syntax: syntax_,
+            content_type: config.content_type(syntax_)?,
+            config,
+        })
+    }
+
+    /// Negotiate syntax from value of an `Accept` header, among syntaxes supported by serializers of `config`.
+    ///
+    /// Each supported syntax gets quality value of most specific media range, that matches it's content type, as in [`resolve_syntax_for_accept`](crate::conneg::resolve_syntax_for_accept). If request has no `Accept` header, `*/*` should be passed.
+    ///
+    /// # Errors
+    /// returns [`NotAcceptableError`] if no supported syntax is acceptable.
+    pub fn negotiate(
+        accept: &str,
+        config: Arc<DynSynSerializerFactories>,
+    ) -> Result<Self, NotAcceptableError> {
+        let candidates = KNOWN_SYNTAXES
+            .into_iter()
+            .filter_map(|syntax_| Some((syntax_, config.content_type(syntax_).ok()?)));
+        let (syntax_, content_type) = best_match(accept, candidates)
+            .ok_or_else(|| NotAcceptableError(accept.to_string()))?;
+        Ok(Self {
+            syntax: syntax_,
+            content_type,
+            config,
+        })
+    }
+
+    /// Returns negotiated syntax.
+    pub fn syntax(&self) -> RdfSyntax {
+        self.syntax
+    }
+
+    /// Returns content type of serialized documents, with parameters configured through [`ContentTypeConfig`](super::content_type::ContentTypeConfig), if any.
+    pub fn content_type(&self) -> &Mime {
+        &self.content_type
+    }
+
+    /// Serializes given serializable into negotiated syntax, writing to `write`.
+    ///
+    /// # Errors
+    /// returns [`DynSynSerializeError`] if statements couldn't be read, or written.
+    pub fn serialize<S, M, W>(
+        &self,
+        serializable: S,
+        write: W,
+    ) -> Result<(), DynSynSerializeError<S::SourceError>>
+    where
+        S: DynSynSerializable<M>,
+        W: io::Write,
+    {
+        serializable.serialize_to(self.syntax, write, &self.config)
+    }
+}
+
+/// An error of no supported syntax being acceptable.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("None of supported syntaxes is acceptable for {0:?}")]
+pub struct NotAcceptableError(pub String);
+
+impl ErrorCode for NotAcceptableError {
+    fn code(&self) -> &'static str {
+        error_code::DYNSYN_NOT_ACCEPTABLE
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use once_cell::sync::Lazy;
+    use sophia_api::{graph::isomorphic_graphs, parser::TripleParser, triple::stream::TripleSource};
+    use sophia_inmem::graph::FastGraph;
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+    use type_map::concurrent::TypeMap;
+
+    use super::NegotiatedSerializer;
+    use crate::{
+        error_code::{self, ErrorCode},
+        parser::triples::DynSynTripleParserFactory,
+        serializer::{
+            content_type::ContentTypeConfig, quads::DynSynQuadSerializerFactory,
+            serializable::DynSynSerializerFactories, triples::DynSynTripleSerializerFactory,
+        },
+        syntax::{self, RdfSyntax},
+        tests::TRACING,
+    };
+
+    static CONFIG: Lazy<Arc<DynSynSerializerFactories>> =
+        Lazy::new(|| Arc::new(DynSynSerializerFactories::default()));
+
+    #[test_case("text/turtle", syntax::TURTLE)]
+    #[test_case("*/*", syntax::TURTLE)]
+    #[test_case("application/n-quads, text/turtle", syntax::TURTLE)]
+    #[test_case("application/n-quads;q=1, text/turtle;q=0.8", syntax::N_QUADS)]
+    #[test_case(
+        "application/n-triples;q=0.6, application/*;q=0.5, text/turtle;q=0.1",
+        syntax::N_TRIPLES
+    )]
+    #[test_case("text/html, application/trig;q=0.2, */*;q=0.1", syntax::TRIG)]
+    #[test_case("application/trig;q=0.5, */*;q=0.4, text/turtle;q=0", syntax::TRIG)]
+    #[test_case("garbage, application/n-triples;q=0.3", syntax::N_TRIPLES)]
+    pub fn negotiates_syntax(accept: &str, expected: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let negotiated = NegotiatedSerializer::negotiate(accept, CONFIG.clone()).unwrap();
+        assert_eq!(negotiated.syntax(), expected);
+    }
+
+    #[test_case("text/html")]
+    #[test_case("application/ld+json, image/*")]
+    #[test_case("*/*;q=0")]
+    #[test_case("text/*;q=0.5, text/turtle;q=0")]
+    #[test_case("" ; "empty")]
+    pub fn reports_not_acceptable(accept: &str) {
+        Lazy::force(&TRACING);
+        let e = NegotiatedSerializer::negotiate(accept, CONFIG.clone()).unwrap_err();
+        assert_eq!(e.code(), error_code::DYNSYN_NOT_ACCEPTABLE);
+    }
+
+    #[test]
+    pub fn reports_configured_content_type() {
+        Lazy::force(&TRACING);
+        let mut config_map = TypeMap::new();
+        config_map.insert(ContentTypeConfig::new().with_charset_utf8(true));
+        let config = Arc::new(DynSynSerializerFactories::new(
+            DynSynTripleSerializerFactory::new(Some(config_map)),
+            DynSynQuadSerializerFactory::new(None),
+        ));
+        let negotiated = NegotiatedSerializer::negotiate("text/turtle", config.clone()).unwrap();
+        assert_eq!(
+            negotiated.content_type().to_string(),
+            "text/turtle; charset=utf-8"
+        );
+        assert!(NegotiatedSerializer::new(syntax::JSON_LD, config).is_err());
+    }
+
+    #[test]
+    pub fn serializes_to_writer_supplied_later() {
+        Lazy::force(&TRACING);
+        let doc = "<http://e/a> <http://e/p> <http://e/b> .\n";
+        let graph: FastGraph = DynSynTripleParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::N_TRIPLES, None, None)
+            .unwrap()
+            .parse_str(doc)
+            .collect_triples()
+            .unwrap();
+        let negotiated = NegotiatedSerializer::negotiate("application/n-quads", CONFIG.clone())
+            .unwrap()
+            .clone();
+        let mut out = Vec::new();
+        negotiated.serialize(&graph, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "<http://e/a> <http://e/p> <http://e/b>.\n"
+        );
+
+        let negotiated = NegotiatedSerializer::negotiate("text/turtle", CONFIG.clone()).unwrap();
+        let mut out = Vec::new();
+        negotiated.serialize(&graph, &mut out).unwrap();
+        let actual: FastGraph = DynSynTripleParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)
+            .unwrap()
+            .parse(&out[..])
+            .collect_triples()
+            .unwrap();
+        assert!(isomorphic_graphs(&graph, &actual).unwrap());
+    }
+}
@@ -0,0 +1,717 @@
+//! This module defines unified pretty-printing options, that map onto configuration of each serialization syntax, where supported.
+//!
+//! Inserting a [`PrettyConfig`] into serializer config map of [`DynSynTripleSerializerFactory`](super::triples::DynSynTripleSerializerFactory), or [`DynSynQuadSerializerFactory`](super::quads::DynSynQuadSerializerFactory) overrides corresponding options of syntax specific configs, like `TurtleConfig`, so that "pretty output" means the same thing regardless of which syntax was negotiated. Other options of syntax specific configs, like prefix map, are retained.
+//!
+//! Support of each option by syntaxes is as follows:
+//!
+//! | option | turtle, trig | rdf-xml | n-triples, n-quads, nd-rdf-json |
+//! |--------|--------------|---------|---------------------------------|
+//! | `pretty` | yes | no | not applicable, one statement per line |
+//! | `indent` | yes | no | not applicable |
+//! | `max_line_width` | yes, objects of a predicate are packed onto a line, while it fits | no | not applicable |
+//! | `align_objects` | yes | no | not applicable |
+//! | `render_collections` | yes | no, lists are written as raw triples | not applicable |
+//!
+//! Options that a syntax doesn't support are ignored for that syntax.
+//!
+//! Sophia's pretty turtle, and trig serializers always render well-formed `rdf:List` structures as `( ... )` collections, write each object on it's own line, and don't align objects. Hence if `render_collections` is disabled, or if `max_line_width`, or `align_objects` is set, dynsyn serializers pretty-print with their own prettifier instead. It groups, and indents statements like sophia's, and honours these options. Line widths, and columns are counted in characters.
+
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+};
+
+use sophia_api::{
+    ns::rdf,
+    quad::{stream::QuadSource, Quad},
+    serializer::{QuadSerializer, TripleSerializer},
+    term::{CopyTerm, TTerm, TermKind},
+    triple::{
+        stream::{SinkError, SourceError, StreamResult, TripleSource},
+        Triple,
+    },
+};
+use sophia_term::BoxTerm;
+use sophia_turtle::serializer::turtle::{write_prefixes, write_term, TurtleConfig};
+
+use crate::error_code::{self, ErrorCode};
+
+/// Unified pretty-printing options for all serialization syntaxes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrettyConfig {
+    /// If true, serializers make extra effort to group related statements, and to indent them. This may require buffering whole graph in memory.
+    pub pretty: bool,
+    /// Indentation string for each nesting level. It can only contain ascii whitespaces.
+    pub indent: String,
+    /// Maximum line width, that serializers should try to keep lines within. If set, objects of a predicate are packed onto a line, while it fits. Else each object is written on it's own line. It is a no-op for syntaxes other than turtle, and trig.
+    pub max_line_width: Option<usize>,
+    /// If true, objects of statements with same subject are aligned to same column. It is a no-op for syntaxes other than turtle, and trig.
+    pub align_objects: bool,
+    /// If true, well-formed `rdf:List` structures are rendered as `( ... )` collections, where syntax supports it. Else they are written as raw `rdf:first`, and `rdf:rest` triples.
+    pub render_collections: bool,
+}
+
+impl Default for PrettyConfig {
+    fn default() -> Self {
+        Self {
+            pretty: false,
+            indent: "  ".to_string(),
+            max_line_width: None,
+            align_objects: false,
+            render_collections: true,
+        }
+    }
+}
+
+impl PrettyConfig {
+    /// Create a new config with default options, that doesn't pretty-print.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether to pretty-print.
+    pub fn with_pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+
+    /// Set indentation string.
+    ///
+    /// # Errors
+    /// returns [`InvalidIndent`] if `indent` contains characters other than ascii whitespaces.
+    pub fn with_indent<T: ToString>(mut self, indent: T) -> Result<Self, InvalidIndent> {
+        let indent = indent.to_string();
+        if !indent.chars().all(|c| c.is_ascii_whitespace()) {
+            return Err(InvalidIndent(indent));
+        }
+        self.indent = indent;
+        Ok(self)
+    }
+
+    /// Set maximum line width.
+    pub fn with_max_line_width(mut self, max_line_width: Option<usize>) -> Self {
+        self.max_line_width = max_line_width;
+        self
+    }
+
+    /// Set whether to align objects.
+    pub fn with_align_objects(mut self, align_objects: bool) -> Self {
+        self.align_objects = align_objects;
+        self
+    }
+
+    /// Set whether to render `rdf:List` structures as collections.
+    pub fn with_render_collections(mut self, render_collections: bool) -> Self {
+        self.render_collections = render_collections;
+        self
+    }
+
+    /// Apply options to given turtle, or trig config. Other options are honoured by serializers, as sophia's config has no such options.
+    pub(crate) fn apply_to_turtle(&self, config: TurtleConfig) -> TurtleConfig {
+        config
+            .with_pretty(self.pretty)
+            .with_indentation(&self.indent)
+    }
+
+    /// Returns true, if turtle, and trig documents are to be pretty-printed with dynsyn's own prettifier, as sophia's one doesn't support configured layout.
+    pub(crate) fn needs_dynsyn_prettifier(&self) -> bool {
+        !self.render_collections || self.max_line_width.is_some() || self.align_objects
+    }
+}
+
+/// An error of an indentation string containing characters other than ascii whitespaces.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Indent {0:?} contains characters other than ascii whitespaces")]
+pub struct InvalidIndent(pub String);
+
+impl ErrorCode for InvalidIndent {
+    fn code(&self) -> &'static str {
+        error_code::DYNSYN_INVALID_INDENT
+    }
+}
+
+/// Statements of a graph, grouped by subject, and then by predicate, in order of their first occurrence.
+#[derive(Debug, Default)]
+struct GroupedGraph {
+    subjects: Vec<BoxTerm>,
+    properties: HashMap<BoxTerm, Vec<(BoxTerm, Vec<BoxTerm>)>>,
+    in_degrees: HashMap<BoxTerm, usize>,
+    /// First subject, that references each object.
+    parents: HashMap<BoxTerm, BoxTerm>,
+    predicates: HashSet<BoxTerm>,
+}
+
+impl GroupedGraph {
+    fn insert(&mut self, s: BoxTerm, p: BoxTerm, o: BoxTerm) {
+        if !self.parents.contains_key(&o) {
+            self.parents.insert(o.clone(), s.clone());
+        }
+        let properties = match self.properties.get_mut(&s) {
+            Some(properties) => properties,
+            None => {
+                self.subjects.push(s.clone());
+                self.properties.entry(s).or_default()
+            }
+        };
+        let objects = match properties.iter().position(|(q, _)| q == &p) {
+            Some(i) => &mut properties[i].1,
+            None => {
+                self.predicates.insert(p.clone());
+                properties.push((p, Vec::new()));
+                &mut properties.last_mut().unwrap().1
+            }
+        };
+        if !objects.contains(&o) {
+            *self.in_degrees.entry(o.clone()).or_default() += 1;
+            objects.push(o);
+        }
+    }
+
+    fn in_degree(&self, t: &BoxTerm) -> usize {
+        self.in_degrees.get(t).copied().unwrap_or_default()
+    }
+}
+
+/// A pretty turtle, and trig serializer, that honours layout options of a [`PrettyConfig`]. It writes same structure as sophia's pretty serializers: blank nodes, that are referenced at most once, and only in one graph, are nested as `[ ... ]`, statements are grouped by subject, and predicate, and well-formed lists are rendered as `( ... )` collections, if configured so. Pretty-printing, and indentation options are taken from turtle config.
+pub(crate) struct DynSynPrettySerializer<W> {
+    write: W,
+    config: TurtleConfig,
+    layout: PrettyConfig,
+}
+
+impl<W: io::Write> DynSynPrettySerializer<W> {
+    pub(crate) fn new(write: W, config: TurtleConfig, layout: PrettyConfig) -> Self {
+        Self {
+            write,
+            config,
+            layout,
+        }
+    }
+}
+
+impl<W: io::Write> TripleSerializer for DynSynPrettySerializer<W> {
+    type Error = io::Error;
+
+    fn serialize_triples<TS>(
+        &mut self,
+        mut source: TS,
+    ) -> StreamResult<&mut Self, TS::Error, Self::Error>
+    where
+        TS: TripleSource,
+    {
+        let mut graph = GroupedGraph::default();
+        source
+            .for_each_triple(|t| {
+                graph.insert(
+                    BoxTerm::copy(t.s()),
+                    BoxTerm::copy(t.p()),
+                    BoxTerm::copy(t.o()),
+                )
+            })
+            .map_err(SourceError)?;
+        write_prefixes(&mut self.write, self.config.prefix_map()).map_err(SinkError)?;
+        prettify(
+            &graph,
+            &mut self.write,
+            &self.config,
+            &self.layout,
+            &HashSet::new(),
+            "",
+        )
+        .map_err(SinkError)?;
+        self.write.flush().map_err(SinkError)?;
+        Ok(self)
+    }
+}
+
+impl<W: io::Write> QuadSerializer for DynSynPrettySerializer<W> {
+    type Error = io::Error;
+
+    fn serialize_quads<QS>(
+        &mut self,
+        mut source: QS,
+    ) -> StreamResult<&mut Self, QS::Error, Self::Error>
+    where
+        QS: QuadSource,
+    {
+        let mut default_graph = GroupedGraph::default();
+        let mut graph_names = Vec::<BoxTerm>::new();
+        let mut named_graphs = HashMap::<BoxTerm, GroupedGraph>::new();
+        let mut bnode_graphs = HashMap::<BoxTerm, Option<BoxTerm>>::new();
+        let mut anon_blacklist = HashSet::new();
+        source
+            .for_each_quad(|q| {
+                let g = q.g().map(BoxTerm::copy);
+                let [s, p, o] = [q.s(), q.p(), q.o()].map(BoxTerm::copy);
+                // Blank nodes, that are in more than one graph, must be labeled.
+                for t in [&s, &p, &o] {
+                    if t.kind() != TermKind::BlankNode {
+                        continue;
+                    }
+                    match bnode_graphs.get(t) {
+                        None => {
+                            bnode_graphs.insert(t.clone(), g.clone());
+                        }
+                        Some(g2) if g2 != &g => {
+                            anon_blacklist.insert(t.clone());
+                        }
+                        _ => (),
+                    }
+                }
+                let graph = match g {
+                    None => &mut default_graph,
+                    Some(g) => {
+                        if !named_graphs.contains_key(&g) {
+                            graph_names.push(g.clone());
+                        }
+                        named_graphs.entry(g).or_default()
+                    }
+                };
+                graph.insert(s, p, o);
+            })
+            .map_err(SourceError)?;
+        for g in &graph_names {
+            if bnode_graphs.contains_key(g) {
+                anon_blacklist.insert(g.clone());
+            }
+        }
+
+        write_prefixes(&mut self.write, self.config.prefix_map()).map_err(SinkError)?;
+        prettify(
+            &default_graph,
+            &mut self.write,
+            &self.config,
+            &self.layout,
+            &anon_blacklist,
+            "",
+        )
+        .map_err(SinkError)?;
+        for g in &graph_names {
+            self.write.write_all(b"GRAPH ").map_err(SinkError)?;
+            write_term(
+                &mut self.write,
+                g,
+                &self.config,
+                !anon_blacklist.contains(g),
+            )
+            .map_err(SinkError)?;
+            self.write.write_all(b" {").map_err(SinkError)?;
+            prettify(
+                &named_graphs[g],
+                &mut self.write,
+                &self.config,
+                &self.layout,
+                &anon_blacklist,
+                self.config.indentation(),
+            )
+            .map_err(SinkError)?;
+            self.write.write_all(b"}\n").map_err(SinkError)?;
+        }
+        self.write.flush().map_err(SinkError)?;
+        Ok(self)
+    }
+}
+
+/// Write statements of `graph` in pretty turtle. Blank nodes in `anon_blacklist` are always labeled.
+fn prettify<W: io::Write>(
+    graph: &GroupedGraph,
+    write: W,
+    config: &TurtleConfig,
+    layout: &PrettyConfig,
+    anon_blacklist: &HashSet<BoxTerm>,
+    base_indent: &str,
+) -> io::Result<()> {
+    let mut roots = Vec::new();
+    let mut anons = HashSet::new();
+    for s in &graph.subjects {
+        let in_degree = graph.in_degree(s);
+        let anon = s.kind() == TermKind::BlankNode
+            && in_degree <= 1
+            && !graph.predicates.contains(s)
+            && !anon_blacklist.contains(s);
+        if anon {
+            anons.insert(s.clone());
+        }
+        if !anon || in_degree == 0 {
+            roots.push(s);
+        }
+    }
+    let mut p = Prettifier {
+        write,
+        indent: base_indent.to_string(),
+        indent_lens: Vec::new(),
+        column: 0,
+        config,
+        layout,
+        graph,
+        anons,
+    };
+    for root in roots {
+        p.write_root(root)?;
+    }
+    // Blank nodes, that are still anonymous, are in cycles, or hang off them. Cycles are broken
+    // by labeling a node in them, so that nodes hanging off them can still be nested.
+    for s in &graph.subjects {
+        let mut node = s;
+        let mut walked = HashSet::new();
+        while p.anons.contains(node) && walked.insert(node) {
+            match graph.parents.get(node) {
+                Some(parent) if p.anons.contains(parent) => node = parent,
+                _ => break,
+            }
+        }
+        if p.anons.remove(node) {
+            p.write_root(node)?;
+        }
+    }
+    p.write_bytes(b"\n")
+}
+
+/// Number of characters in given utf-8 bytes.
+fn char_count(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|b| (**b & 0xC0) != 0x80).count()
+}
+
+struct Prettifier<'a, W> {
+    write: W,
+    indent: String,
+    /// Lengths of indent before each nesting level, that is pushed.
+    indent_lens: Vec<usize>,
+    /// Column of cursor on current line.
+    column: usize,
+    config: &'a TurtleConfig,
+    layout: &'a PrettyConfig,
+    graph: &'a GroupedGraph,
+    anons: HashSet<BoxTerm>,
+}
+
+impl<'a, W: io::Write> Prettifier<'a, W> {
+    fn write_root(&mut self, root: &BoxTerm) -> io::Result<()> {
+        self.write_newline()?;
+        self.write_term(root, true)?;
+        self.write_bytes(b" ")?;
+        self.write_properties(root)?;
+        self.write_bytes(b".\n")
+    }
+
+    /// Returns true, if given term is written nested, instead of being written as is.
+    fn is_nested(&self, t: &BoxTerm) -> bool {
+        t.kind() == TermKind::BlankNode && self.anons.contains(t)
+    }
+
+    /// Get items of well-formed list, that starts at given anonymous blank node, if it is one.
+    fn list_items(&self, head: &BoxTerm) -> Option<Vec<&'a BoxTerm>> {
+        let mut items = Vec::new();
+        let mut nodes = HashSet::new();
+        let mut node = head;
+        loop {
+            if !self.is_nested(node) || !nodes.insert(node) {
+                return None;
+            }
+            let (first, rest) = match self.graph.properties.get(node)?.as_slice() {
+                [(p1, o1), (p2, o2)] if p1 == &rdf::first && p2 == &rdf::rest => (o1, o2),
+                [(p1, o1), (p2, o2)] if p1 == &rdf::rest && p2 == &rdf::first => (o2, o1),
+                _ => return None,
+            };
+            match (first.as_slice(), rest.as_slice()) {
+                ([item], [rest]) => {
+                    items.push(item);
+                    if rest == &rdf::nil {
+                        return Some(items);
+                    }
+                    node = rest;
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    fn write_term(&mut self, t: &BoxTerm, root: bool) -> io::Result<()> {
+        if self.is_nested(t) {
+            if root {
+                self.anons.remove(t);
+                return self.write_bytes(b"[]");
+            }
+            if self.layout.render_collections {
+                if let Some(items) = self.list_items(t) {
+                    return self.write_list(t, items);
+                }
+            }
+            self.anons.remove(t);
+            self.write_bytes(b"[ ")?;
+            self.write_properties(t)?;
+            return self.write_bytes(b" ]");
+        }
+        let rendered = self.render(t)?;
+        self.write_bytes(&rendered)
+    }
+
+    fn write_list(&mut self, head: &BoxTerm, items: Vec<&BoxTerm>) -> io::Result<()> {
+        // List nodes are rendered by collection, and are not written otherwise.
+        let mut node = head.clone();
+        while node != rdf::nil {
+            self.anons.remove(&node);
+            let properties = &self.graph.properties[&node];
+            node = properties.iter().find(|(p, _)| p == &rdf::rest).unwrap().1[0].clone();
+        }
+        self.write_bytes(b"(")?;
+        for item in items {
+            self.write_bytes(b" ")?;
+            self.write_term(item, false)?;
+        }
+        self.write_bytes(b" )")
+    }
+
+    /// Render given term, that is not nested.
+    fn render(&self, t: &BoxTerm) -> io::Result<Vec<u8>> {
+        let mut rendered = Vec::new();
+        write_term(&mut rendered, t, self.config, false)?;
+        Ok(rendered)
+    }
+
+    /// Get column, at which objects of given properties start, if objects are to be aligned. `a` is written at current column, and other predicates on new lines, at predicate-level indent.
+    fn objects_column(&self, properties: &[(BoxTerm, Vec<BoxTerm>)]) -> io::Result<Option<usize>> {
+        if !self.layout.align_objects {
+            return Ok(None);
+        }
+        let predicate_column =
+            char_count(self.indent.as_bytes()) + char_count(self.config.indentation().as_bytes());
+        let mut column = 0;
+        for (p, _) in properties {
+            let end = match p == &rdf::type_ {
+                true => self.column + 1,
+                false => predicate_column + char_count(&self.render(p)?),
+            };
+            column = column.max(end + 1);
+        }
+        Ok(Some(column))
+    }
+
+    fn write_properties(&mut self, node: &BoxTerm) -> io::Result<()> {
+        let properties = self
+            .graph
+            .properties
+            .get(node)
+            .map_or(&[][..], Vec::as_slice);
+        let objects_column = self.objects_column(properties)?;
+        self.push_indent(self.config.indentation()); // to predicate-level
+        let mut written = false;
+        if let Some((_, types)) = properties.iter().find(|(p, _)| p == &rdf::type_) {
+            self.write_bytes(b"a")?;
+            self.write_objects(types, objects_column)?;
+            written = true;
+        }
+        for (p, objects) in properties.iter().filter(|(p, _)| p != &rdf::type_) {
+            if written {
+                self.write_bytes(b";")?;
+            }
+            self.write_newline()?;
+            self.write_term(p, false)?;
+            self.write_objects(objects, objects_column)?;
+            written = true;
+        }
+        self.pop_indent(); // back to original level
+        Ok(())
+    }
+
+    /// Write objects of a predicate, that is just written. Objects start at given column if any, else after a space.
+    fn write_objects(&mut self, objects: &[BoxTerm], column: Option<usize>) -> io::Result<()> {
+        let pad = column.map_or(1, |c| c.saturating_sub(self.column).max(1));
+        self.write_bytes(" ".repeat(pad).as_bytes())?;
+        match column {
+            Some(column) => {
+                let extra = column.saturating_sub(char_count(self.indent.as_bytes()));
+                self.push_indent(&" ".repeat(extra));
+            }
+            None => self.push_indent(self.config.indentation()), // to object-level
+        }
+        for (i, o) in objects.iter().enumerate() {
+            if i > 0 {
+                self.write_bytes(b",")?;
+                match self.packed(o)? {
+                    Some(rendered) => {
+                        self.write_bytes(b" ")?;
+                        self.write_bytes(&rendered)?;
+                        continue;
+                    }
+                    None => self.write_newline()?,
+                }
+            }
+            self.write_term(o, false)?;
+        }
+        self.pop_indent(); // back to predicate-level
+        Ok(())
+    }
+
+    /// Get rendered object, if it is to be packed onto current line, as it fits within max line width.
+    fn packed(&self, o: &BoxTerm) -> io::Result<Option<Vec<u8>>> {
+        let max_line_width = match self.layout.max_line_width {
+            Some(max_line_width) if !self.is_nested(o) => max_line_width,
+            _ => return Ok(None),
+        };
+        let rendered = self.render(o)?;
+        // Object is followed by one of `,`, `;`, `.`, or ` ]`.
+        let fits = self.column + 1 + char_count(&rendered) + 2 <= max_line_width;
+        Ok(fits.then_some(rendered))
+    }
+
+    fn write_newline(&mut self) -> io::Result<()> {
+        self.write_bytes(b"\n")?;
+        let indent = std::mem::take(&mut self.indent);
+        let r = self.write_bytes(indent.as_bytes());
+        self.indent = indent;
+        r
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.column = match bytes.iter().rposition(|b| *b == b'\n') {
+            Some(i) => char_count(&bytes[i + 1..]),
+            None => self.column + char_count(bytes),
+        };
+        self.write.write_all(bytes)
+    }
+
+    fn push_indent(&mut self, indent: &str) {
+        self.indent_lens.push(self.indent.len());
+        self.indent.push_str(indent);
+    }
+
+    fn pop_indent(&mut self) {
+        let len = self.indent_lens.pop().unwrap_or_default();
+        self.indent.truncate(len);
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use once_cell::sync::Lazy;
+    use sophia_api::{
+        dataset::isomorphic_datasets, parser::QuadParser, quad::stream::QuadSource,
+        serializer::QuadSerializer,
+    };
+    use sophia_inmem::dataset::FastDataset;
+    use sophia_term::BoxTerm;
+    use sophia_turtle::serializer::turtle::TurtleConfig;
+    use test_case::test_case;
+
+    use super::{DynSynPrettySerializer, InvalidIndent, PrettyConfig};
+    use crate::{
+        error_code::{self, ErrorCode},
+        parser::quads::DynSynQuadParserFactory,
+        syntax,
+        tests::TRACING,
+    };
+
+    #[test]
+    pub fn overrides_only_pretty_options_of_turtle_config() {
+        Lazy::force(&TRACING);
+        let base = TurtleConfig::new().with_own_prefix_map(vec![]);
+        let config = PrettyConfig::new()
+            .with_pretty(true)
+            .with_indent("\t")
+            .unwrap()
+            .apply_to_turtle(base);
+        assert!(config.pretty());
+        assert_eq!(config.indentation(), "\t");
+        assert!(config.prefix_map().is_empty());
+    }
+
+    #[test]
+    pub fn disabling_collections_keeps_turtle_prettifier() {
+        Lazy::force(&TRACING);
+        let config = PrettyConfig::new()
+            .with_pretty(true)
+            .with_render_collections(false)
+            .apply_to_turtle(TurtleConfig::new());
+        assert!(config.pretty());
+    }
+
+    /// Serialize given trig document with dynsyn prettifier, and check that output round trips.
+    fn prettify(doc: &str, layout: PrettyConfig) -> String {
+        let parser = DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::TRIG, None, None)
+            .unwrap();
+        let d1: FastDataset = parser.parse_str(doc).collect_quads().unwrap();
+        let mut out = Vec::new();
+        DynSynPrettySerializer::new(&mut out, TurtleConfig::new().with_pretty(true), layout)
+            .serialize_dataset(&d1)
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        let d2: FastDataset = parser.parse_str(&out).collect_quads().unwrap();
+        assert!(isomorphic_datasets(&d1, &d2).unwrap(), "{}", out);
+        out
+    }
+
+    #[test_case("<tag:g1> { <tag:s> <tag:p> _:b } <tag:g2> { _:b <tag:p> (1) }"; "blank node in two graphs")]
+    #[test_case("_:g { <tag:s> <tag:p> [ <tag:p> _:g ], (1) }"; "blank node graph name")]
+    #[test_case("_:a <tag:p> _:b . _:b <tag:p> _:a , (1) ."; "blank node cycle")]
+    pub fn unfolded_prettifier_round_trips(doc: &str) {
+        Lazy::force(&TRACING);
+        let out = prettify(doc, PrettyConfig::new().with_render_collections(false));
+        assert!(out.contains("rdf:first"));
+    }
+
+    #[test_case("<tag:s> <tag:p> (1 [ <tag:p> (2) ] <tag:o>) .", true; "nested lists")]
+    #[test_case("<tag:s> <tag:p> (1), (2) . _:l <tag:p> (3) .", true; "lists among other objects")]
+    #[test_case("_:a <tag:p> _:b . _:b <tag:p> _:a , (1) .", true; "blank node cycle")]
+    #[test_case("<tag:s> <tag:p> _:l . _:l rdf:first 1 ; rdf:rest _:l .", false; "cyclic list")]
+    #[test_case("<tag:s> <tag:p> _:l . _:l rdf:first 1, 2 ; rdf:rest rdf:nil .", false; "list node with two items")]
+    pub fn prettifier_renders_well_formed_lists_as_collections(doc: &str, collection: bool) {
+        Lazy::force(&TRACING);
+        let doc = format!(
+            "PREFIX rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#>\n{}",
+            doc
+        );
+        let out = prettify(&doc, PrettyConfig::new().with_align_objects(true));
+        assert_eq!(out.contains("( "), collection, "{}", out);
+        assert_eq!(out.contains("rdf:first"), !collection, "{}", out);
+    }
+
+    #[test]
+    pub fn prettifier_aligns_objects() {
+        Lazy::force(&TRACING);
+        let doc = "<tag:s> a <tag:T> ; <tag:p> <tag:o> ; <tag:longer> <tag:o1>, <tag:o2> .";
+        let out = prettify(doc, PrettyConfig::new().with_align_objects(true));
+        let columns: HashSet<_> = out
+            .lines()
+            .filter_map(|l| l.find("<tag:o").or_else(|| l.find("<tag:T")))
+            .collect();
+        assert_eq!(columns.len(), 1, "{}", out);
+    }
+
+    #[test_case(None, 3)]
+    #[test_case(Some(80), 1)]
+    #[test_case(Some(30), 2)]
+    pub fn prettifier_packs_objects_within_max_line_width(
+        max_line_width: Option<usize>,
+        lines: usize,
+    ) {
+        Lazy::force(&TRACING);
+        let doc = "<tag:s> <tag:p> <tag:o1>, <tag:o2>, <tag:o3> .";
+        let out = prettify(doc, PrettyConfig::new().with_max_line_width(max_line_width));
+        assert_eq!(out.lines().filter(|l| l.contains("<tag:o")).count(), lines);
+        if let Some(max_line_width) = max_line_width {
+            assert!(
+                out.lines()
+                    .filter(|l| l.contains("<tag:"))
+                    .all(|l| l.chars().count() <= max_line_width),
+                "{}",
+                out
+            );
+        }
+    }
+
+    #[test]
+    pub fn rejects_non_whitespace_indent() {
+        Lazy::force(&TRACING);
+        let e = PrettyConfig::new().with_indent("->").unwrap_err();
+        assert_eq!(e, InvalidIndent("->".into()));
+        assert_eq!(e.code(), error_code::DYNSYN_INVALID_INDENT);
+    }
+}
@@ -1,6 +1,17 @@
-use std::io;
+use std::{io, path::Path, sync::Arc};
 
-use sophia_api::serializer::{QuadSerializer, Stringifier};
+use mime::Mime;
+use sophia_api::{
+    dataset::Dataset,
+    quad::{stream::QuadSource, Quad},
+    serializer::{QuadSerializer, Stringifier},
+    term::{CopyTerm, TTerm},
+    triple::{
+        stream::{StreamError, StreamResult, TripleSource},
+        Triple,
+    },
+};
+use sophia_term::BoxTerm;
 use sophia_turtle::serializer::{
     nq::{NqConfig, NqSerializer},
     trig::{TrigConfig, TrigSerializer},
@@ -8,17 +19,38 @@ use sophia_turtle::serializer::{
 use type_map::concurrent::TypeMap;
 
 use crate::{
+    correspondence::{syntax_for_path, ForPathError},
     syntax::UnKnownSyntaxError,
     syntax::{self, RdfSyntax},
 };
 
-use super::_inner::InnerQuadSerializer;
+#[cfg(any(test, feature = "compression"))]
+use crate::compression::CompressionConfig;
+
+#[cfg(any(test, feature = "canon"))]
+use super::canon::CanonConfig;
+
+use super::{
+    _inner::{compressing::CompressingWrite, counted::Counted, InnerQuadSerializer},
+    any::{BoxedQuadSerializer, BoxedWrite},
+    bnode_cycle::{buffer_quads, find_cycle, sink_only, BlankNodeCycleConfig},
+    config::{DynSynSerializerConfig, LayeredConfigMap},
+    errors::{NewSerializerError, SerializerRole},
+    content_type::ContentTypeConfig,
+    escape::{AsciiNtSerializer, EscapeConfig, EscapingWrite},
+    pretty::{DynSynPrettySerializer, PrettyConfig},
+    nd_rdf_json::NdRdfJsonSerializer,
+    sorted::{write_sorted_lines, SortConfig},
+};
+
+#[cfg(feature = "trix")]
+use super::trix::TrixSerializer;
 
 /// A [`QuadSerializer`], that can be instantiated at run time against any of supported rdf-syntaxes. We can get it's tuned instance from [`DynSynQuadSerializerFactory::try_new_serializer`] factory method.
 ///
-/// It can currently serialize quad-sources/datasets into documents in any of concrete_syntaxes: [`n-quads`](syntax::N_QUADS), [`trig`](syntax::TRIG). Other syntaxes that cannot represent quads are not supported
+/// It can currently serialize quad-sources/datasets into documents in any of concrete_syntaxes: [`n-quads`](syntax::N_QUADS), [`trig`](syntax::TRIG), [`trix`](syntax::TRIX) with `trix` feature, and non-standard debug syntax [`nd-rdf-json`](syntax::ND_RDF_JSON). Other syntaxes that cannot represent quads are not supported
 ///
-/// For each supported serialization syntax, it also supports corresponding formatting options that sophia supports.
+/// For each supported serialization syntax, it also supports corresponding formatting options that sophia supports, and unified pretty-printing options configured by [`PrettyConfig`], and syntax-agnostic options like prefix map configured by [`DynSynSerializerConfig`]. It can also escape non-ascii characters in any of them, as configured by [`EscapeConfig`]. It reports [`content_type`](Self::content_type) of documents it produces, with media type parameters configured by [`ContentTypeConfig`].
 ///
 /// Example:
 ///
@@ -81,12 +113,111 @@ use super::_inner::InnerQuadSerializer;
 
 #[derive(Debug)]
 pub struct DynSynQuadSerializer<W: io::Write> {
-    inner_serializer: InnerQuadSerializer<W>, // NOTE can be a trait object. serializers seems amenable to be trait objects unlike parsers and sources
+    inner_serializer: InnerQuadSerializer,
+    write: EscapingWrite<CompressingWrite<W>>,
+    content_type: Mime,
+    escape_non_ascii: bool,
+    pretty_config: PrettyConfig,
+    bnode_cycle_config: Option<BlankNodeCycleConfig>,
+    sort_config: SortConfig,
 }
 
 impl<W: io::Write> DynSynQuadSerializer<W> {
-    pub(crate) fn new(inner_serializer: InnerQuadSerializer<W>) -> Self {
-        Self { inner_serializer }
+    pub(crate) fn new(
+        inner_serializer: InnerQuadSerializer,
+        write: W,
+        escape_config: EscapeConfig,
+        content_type: Mime,
+    ) -> Self {
+        let style = escape_config
+            .escape_non_ascii
+            .then(|| inner_serializer.escape_style())
+            .flatten();
+        Self {
+            inner_serializer,
+            write: EscapingWrite::new(CompressingWrite::new(write), style),
+            content_type,
+            escape_non_ascii: escape_config.escape_non_ascii,
+            pretty_config: PrettyConfig::default(),
+            bnode_cycle_config: None,
+            sort_config: SortConfig::default(),
+        }
+    }
+
+    pub(crate) fn with_bnode_cycle_config(
+        mut self,
+        bnode_cycle_config: Option<BlankNodeCycleConfig>,
+    ) -> Self {
+        self.bnode_cycle_config = bnode_cycle_config;
+        self
+    }
+
+    pub(crate) fn with_pretty_config(mut self, pretty_config: PrettyConfig) -> Self {
+        self.pretty_config = pretty_config;
+        self
+    }
+
+    pub(crate) fn with_sort_config(mut self, sort_config: SortConfig) -> Self {
+        self.sort_config = sort_config;
+        self
+    }
+
+    #[cfg(any(test, feature = "compression"))]
+    pub(crate) fn with_compression_config(mut self, compression_config: CompressionConfig) -> Self {
+        self.write.get_mut().set_config(compression_config);
+        self
+    }
+
+    /// Returns content type of documents produced by this serializer, with media type parameters configured by [`ContentTypeConfig`].
+    pub fn content_type(&self) -> &Mime {
+        &self.content_type
+    }
+
+    /// Serializes all quads from `source`, like [`serialize_quads`](QuadSerializer::serialize_quads), and also returns number of quads serialized. It allows callers to log, and validate output without a second pass over the source.
+    pub fn serialize_quads_counted<QS>(
+        &mut self,
+        mut source: QS,
+    ) -> StreamResult<(&mut Self, usize), QS::Error, io::Error>
+    where
+        QS: QuadSource,
+    {
+        let mut count = 0;
+        self.serialize_quads(Counted::new(&mut source, &mut count))?;
+        Ok((self, count))
+    }
+
+    /// Serializes given `dataset`, like [`serialize_dataset`](QuadSerializer::serialize_dataset), and also returns number of quads serialized.
+    pub fn serialize_dataset_counted<D>(
+        &mut self,
+        dataset: &D,
+    ) -> StreamResult<(&mut Self, usize), D::Error, io::Error>
+    where
+        D: Dataset,
+    {
+        self.serialize_quads_counted(dataset.quads())
+    }
+
+    /// Serializes all triples from `source`, as quads in graph named `graph_iri`, or in default graph if it is `None`. Triples are adapted into quads on the fly, like by quad parsers for triple syntaxes. It allows to export a graph into a named graph of a dataset document in one call.
+    pub fn serialize_triples_in_graph<TS, G>(
+        &mut self,
+        source: TS,
+        graph_iri: Option<&G>,
+    ) -> StreamResult<&mut Self, TS::Error, io::Error>
+    where
+        TS: TripleSource,
+        G: TTerm + ?Sized,
+    {
+        let graph_iri = graph_iri.map(BoxTerm::copy);
+        self.serialize_quads(source.filter_map_triples(|t| {
+            Some((
+                [
+                    BoxTerm::copy(t.s()),
+                    BoxTerm::copy(t.p()),
+                    BoxTerm::copy(t.o()),
+                ],
+                graph_iri.clone(),
+            ))
+        }))
     }
 }
 
@@ -101,41 +232,109 @@ impl<W: io::Write> QuadSerializer for DynSynQuadSerializer<W> {
         QS: sophia_api::quad::stream::QuadSource,
         Self: Sized,
     {
-        match &mut self.inner_serializer {
-            InnerQuadSerializer::NQuads(s) => match s.serialize_quads(source) {
-                Ok(_) => Ok(self),
-                Err(e) => Err(e),
-            },
-            InnerQuadSerializer::Trig(s) => match s.serialize_quads(source) {
-                Ok(_) => Ok(self),
-                Err(e) => Err(e),
+        self.write
+            .get_mut()
+            .start_frame()
+            .map_err(StreamError::SinkError)?;
+        let write = &mut self.write;
+        let result = match &self.inner_serializer {
+            InnerQuadSerializer::NQuads(c) if self.sort_config.sorted => {
+                let mut doc = Vec::new();
+                match self.escape_non_ascii {
+                    true => AsciiNtSerializer::new(&mut doc)
+                        .serialize_quads(source)
+                        .map(|_| ())?,
+                    false => NqSerializer::new_with_config(&mut doc, c.clone())
+                        .serialize_quads(source)
+                        .map(|_| ())?,
+                }
+                write_sorted_lines(write, &doc).map_err(StreamError::SinkError)
+            }
+            InnerQuadSerializer::NQuads(_) if self.escape_non_ascii => {
+                AsciiNtSerializer::new(write)
+                    .serialize_quads(source)
+                    .map(|_| ())
+            }
+            InnerQuadSerializer::NQuads(c) => NqSerializer::new_with_config(write, c.clone())
+                .serialize_quads(source)
+                .map(|_| ()),
+            #[cfg(any(test, feature = "canon"))]
+            InnerQuadSerializer::CanonicalNQuads(c) => {
+                let quads = buffer_quads(source)?;
+                let doc = c
+                    .canonical_n_quads(&quads)
+                    .map_err(|e| StreamError::SinkError(e.into()))?;
+                io::Write::write_all(write, doc.as_bytes()).map_err(StreamError::SinkError)
+            }
+            InnerQuadSerializer::Trig(c) => match self.bnode_cycle_config.filter(|_| c.pretty()) {
+                None if c.pretty() && self.pretty_config.needs_dynsyn_prettifier() => {
+                    DynSynPrettySerializer::new(write, c.clone(), self.pretty_config.clone())
+                        .serialize_quads(source)
+                        .map(|_| ())
+                }
+                None => TrigSerializer::new_with_config(write, c.clone())
+                    .serialize_quads(source)
+                    .map(|_| ()),
+                Some(cycle_config) => {
+                    let quads = buffer_quads(source)?;
+                    let config = cycle_config
+                        .resolve(c, find_cycle(quads.iter().map(|q| (q.s(), q.o()))))
+                        .map_err(|e| StreamError::SinkError(e.into()))?;
+                    let quads = quads.into_iter().map(Ok);
+                    match config.pretty() && self.pretty_config.needs_dynsyn_prettifier() {
+                        true => {
+                            DynSynPrettySerializer::new(write, config, self.pretty_config.clone())
+                                .serialize_quads(quads)
+                                .map(|_| ())
+                        }
+                        false => TrigSerializer::new_with_config(write, config)
+                            .serialize_quads(quads)
+                            .map(|_| ()),
+                    }
+                    .map_err(sink_only)
+                }
             },
+            #[cfg(feature = "trix")]
+            InnerQuadSerializer::Trix => TrixSerializer::new(write)
+                .with_escape_non_ascii(self.escape_non_ascii)
+                .serialize_quads(source)
+                .map(|_| ()),
+            InnerQuadSerializer::NdRdfJson => NdRdfJsonSerializer::new(write)
+                .with_escape_non_ascii(self.escape_non_ascii)
+                .serialize_quads(source)
+                .map(|_| ()),
+        };
+        match result {
+            Ok(_) => {
+                self.write
+                    .get_mut()
+                    .finish_frame()
+                    .map_err(StreamError::SinkError)?;
+                Ok(self)
+            }
+            Err(e) => Err(e),
         }
     }
 }
 
 impl Stringifier for DynSynQuadSerializer<Vec<u8>> {
     fn as_utf8(&self) -> &[u8] {
-        match &self.inner_serializer {
-            InnerQuadSerializer::NQuads(s) => s.as_utf8(),
-            InnerQuadSerializer::Trig(s) => s.as_utf8(),
-        }
+        self.write.get_ref().get_ref().map_or(&[], |w| &w[..])
     }
 }
 
 /// A factory to instantiate [`DynSynQuadSerializer`].
+///
+/// Factory is `Send + Sync`, and keeps its config map behind an [`Arc`]. Hence it is cheap to clone, and clones can be shared across threads, like in application state of web servers.
+#[derive(Debug, Clone)]
 pub struct DynSynQuadSerializerFactory {
-    serializer_config_map: TypeMap,
+    serializer_config_map: Arc<TypeMap>,
 }
 
 impl DynSynQuadSerializerFactory {
-    /// Instantiate a factory. It takes a `serializer_config_map`, an optional [`TypeMap`], which can be populated with configuration structures corresponding to supported syntaxes.
+    /// Instantiate a factory. It takes a `serializer_config_map`, an optional [`TypeMap`], which can be populated with configuration structures corresponding to supported syntaxes, with a [`BlankNodeCycleConfig`], with a [`SortConfig`], with a `CanonConfig` from `canon` module, when `canon` feature is enabled, and with a `CompressionConfig` from `compression` module, when `compression` feature is enabled.
     pub fn new(serializer_config_map: Option<TypeMap>) -> Self {
-        let serializer_config_map = if let Some(v) = serializer_config_map {
-            v
-        } else {
-            TypeMap::new()
-        };
+        let serializer_config_map = Arc::new(serializer_config_map.unwrap_or_default());
         Self {
             serializer_config_map,
         }
@@ -148,61 +347,210 @@ impl DynSynQuadSerializerFactory {
             .unwrap_or_default()
     }
 
+    /// Get trig config, with unified pretty-printing options, and syntax-agnostic options applied, if they are configured.
+    fn trig_config(configs: &LayeredConfigMap) -> TrigConfig {
+        let config = configs.get_config::<TrigConfig>();
+        let config = match configs.get::<PrettyConfig>() {
+            Some(pretty_config) => pretty_config.apply_to_turtle(config),
+            None => config,
+        };
+        match configs.get::<DynSynSerializerConfig>() {
+            Some(dynsyn_config) => dynsyn_config.apply_to_turtle(config),
+            None => config,
+        }
+    }
+
     /// Try to create new [`DynSynQuadSerializer`] instance, for given `syntax_`, `write`,
     ///
     /// # Errors
-    /// returns [`UnKnownSyntaxError`](crate::syntax::UnKnownSyntaxError) if requested syntax is not known/supported.
+    /// returns [`NewSerializerError`] if requested syntax is not known/supported. It will be a [`SyntaxRoleMismatch`](super::errors::SyntaxRoleMismatch), if syntax is supported only by serializers for other kind of statements.
     pub fn try_new_serializer<W: io::Write>(
         &self,
         syntax_: RdfSyntax,
         write: W,
-    ) -> Result<DynSynQuadSerializer<W>, UnKnownSyntaxError> {
-        match syntax_ {
-            syntax::N_QUADS => Ok(DynSynQuadSerializer::new(InnerQuadSerializer::NQuads(
-                NqSerializer::new_with_config(write, self.get_config::<NqConfig>()),
-            ))),
-            syntax::TRIG => Ok(DynSynQuadSerializer::new(InnerQuadSerializer::Trig(
-                TrigSerializer::new_with_config(write, self.get_config::<TrigConfig>()),
-            ))),
-            _ => Err(UnKnownSyntaxError(syntax_)),
+    ) -> Result<DynSynQuadSerializer<W>, NewSerializerError> {
+        self.try_new_serializer_in(syntax_, write, None)
+    }
+
+    /// Try to create new [`DynSynQuadSerializer`] instance, for given `syntax_`, `write`, with configs in given `config` map overriding those of same type in factory's config map. Configs of other types are still taken from factory's config map. It allows honouring per-request formatting preferences, without building a new factory for each request.
+    ///
+    /// # Errors
+    /// returns [`NewSerializerError`] if requested syntax is not known/supported. It will be a [`SyntaxRoleMismatch`](super::errors::SyntaxRoleMismatch), if syntax is supported only by serializers for other kind of statements.
+    pub fn try_new_serializer_with_config<W: io::Write>(
+        &self,
+        syntax_: RdfSyntax,
+        write: W,
+        config: TypeMap,
+    ) -> Result<DynSynQuadSerializer<W>, NewSerializerError> {
+        self.try_new_serializer_in(syntax_, write, Some(&config))
+    }
+
+    fn try_new_serializer_in<W: io::Write>(
+        &self,
+        syntax_: RdfSyntax,
+        write: W,
+        overrides: Option<&TypeMap>,
+    ) -> Result<DynSynQuadSerializer<W>, NewSerializerError> {
+        let configs = LayeredConfigMap::new(&self.serializer_config_map, overrides);
+        let escape_config = configs.get_config::<EscapeConfig>();
+        let inner_serializer = match syntax_ {
+            #[cfg(any(test, feature = "canon"))]
+            syntax::N_QUADS if configs.get_config::<CanonConfig>().canonical => {
+                // Canonical n-quads are utf-8, with only mandatory escapes. Hence canonical writer doesn't escape non-ascii characters.
+                InnerQuadSerializer::CanonicalNQuads(configs.get_config::<CanonConfig>())
+            }
+            syntax::N_QUADS => {
+                let mut config = configs.get_config::<NqConfig>();
+                // Sophia's ascii mode is not implemented, and serializing with it panics. We escape by ourselves instead, as per `EscapeConfig`.
+                config.set_ascii(false);
+                InnerQuadSerializer::NQuads(config)
+            }
+            syntax::TRIG => InnerQuadSerializer::Trig(Self::trig_config(&configs)),
+            #[cfg(feature = "trix")]
+            syntax::TRIX => InnerQuadSerializer::Trix,
+            syntax::ND_RDF_JSON => InnerQuadSerializer::NdRdfJson,
+            _ => return Err(NewSerializerError::unsupported(syntax_, SerializerRole::Quads)),
+        };
+        let serializer = DynSynQuadSerializer::new(
+            inner_serializer,
+            write,
+            escape_config,
+            Self::content_type_in(&configs, syntax_)?,
+        )
+        .with_pretty_config(configs.get_config::<PrettyConfig>())
+        .with_bnode_cycle_config(configs.get::<BlankNodeCycleConfig>().cloned())
+        .with_sort_config(configs.get_config::<SortConfig>());
+        #[cfg(any(test, feature = "compression"))]
+        let serializer =
+            serializer.with_compression_config(configs.get_config::<CompressionConfig>());
+        Ok(serializer)
+    }
+
+    /// Try to create new [`DynSynQuadSerializer`] instance with erased writer type, for given `syntax_`, writing to `write`. It allows storing serializers for different writers in same collection, without being generic over writer type. See [`AnySerializer`](super::any::AnySerializer) for a serializer, that can also be of either role.
+    ///
+    /// # Errors
+    /// returns [`NewSerializerError`] if requested syntax is not known/supported. It will be a [`SyntaxRoleMismatch`](super::errors::SyntaxRoleMismatch), if syntax is supported only by serializers for other kind of statements.
+    pub fn try_new_boxed_serializer(
+        &self,
+        syntax_: RdfSyntax,
+        write: BoxedWrite,
+    ) -> Result<BoxedQuadSerializer, NewSerializerError> {
+        self.try_new_serializer(syntax_, write)
+    }
+
+    /// Try to create new [`DynSynQuadSerializer`] instance, for syntax corresponding to file extension of given `path`, writing to `write`. File extension is matched case-insensitively.
+    ///
+    /// # Errors
+    /// returns [`ForPathError`] if path has no file extension, or if it doesn't correspond to any rdf syntax, or if corresponding syntax is not supported.
+    pub fn try_new_serializer_for_path<W: io::Write>(
+        &self,
+        path: &Path,
+        write: W,
+    ) -> Result<DynSynQuadSerializer<W>, ForPathError> {
+        Ok(self.try_new_serializer(syntax_for_path(path)?, write)?)
+    }
+
+    /// Get content type of documents, that serializers from this factory produce for given `syntax_`, with media type parameters configured by [`ContentTypeConfig`] attached. It allows http layers to set `Content-Type` header before serializing.
+    ///
+    /// # Errors
+    /// returns [`UnKnownSyntaxError`] if requested syntax is not known/supported.
+    pub fn content_type(&self, syntax_: RdfSyntax) -> Result<Mime, UnKnownSyntaxError> {
+        Self::content_type_in(
+            &LayeredConfigMap::new(&self.serializer_config_map, None),
+            syntax_,
+        )
+    }
+
+    fn content_type_in(
+        configs: &LayeredConfigMap,
+        syntax_: RdfSyntax,
+    ) -> Result<Mime, UnKnownSyntaxError> {
+        let supported = matches!(syntax_, syntax::N_QUADS | syntax::TRIG | syntax::ND_RDF_JSON)
+            || (cfg!(feature = "trix") && syntax_ == syntax::TRIX);
+        if !supported {
+            return Err(UnKnownSyntaxError(syntax_));
         }
+        configs
+            .get_config::<ContentTypeConfig>()
+            .content_type(syntax_)
+            .ok_or(UnKnownSyntaxError(syntax_))
     }
 
     /// Try to create new [`DynSynQuadSerializer`] instance, that can be stringified after serialization, for given `syntax_`.
     ///
     /// # Errors
-    /// returns [`UnKnownSyntaxError`](crate::syntax::UnKnownSyntaxError) if requested syntax is not known/supported.
+    /// returns [`NewSerializerError`] if requested syntax is not known/supported. It will be a [`SyntaxRoleMismatch`](super::errors::SyntaxRoleMismatch), if syntax is supported only by serializers for other kind of statements.
     pub fn try_new_stringifier(
         &self,
         syntax_: RdfSyntax,
-    ) -> Result<DynSynQuadSerializer<Vec<u8>>, UnKnownSyntaxError> {
-        self.try_new_serializer(syntax_, Vec::new())
+    ) -> Result<DynSynQuadSerializer<Vec<u8>>, NewSerializerError> {
+        self.try_new_stringifier_in(syntax_, None)
+    }
+
+    /// Try to create new [`DynSynQuadSerializer`] instance, that can be stringified after serialization, for given `syntax_`, with configs in given `config` map overriding those of same type in factory's config map. See [`try_new_serializer_with_config`](Self::try_new_serializer_with_config).
+    ///
+    /// # Errors
+    /// returns [`NewSerializerError`] if requested syntax is not known/supported. It will be a [`SyntaxRoleMismatch`](super::errors::SyntaxRoleMismatch), if syntax is supported only by serializers for other kind of statements.
+    pub fn try_new_stringifier_with_config(
+        &self,
+        syntax_: RdfSyntax,
+        config: TypeMap,
+    ) -> Result<DynSynQuadSerializer<Vec<u8>>, NewSerializerError> {
+        self.try_new_stringifier_in(syntax_, Some(&config))
+    }
+
+    fn try_new_stringifier_in(
+        &self,
+        syntax_: RdfSyntax,
+        overrides: Option<&TypeMap>,
+    ) -> Result<DynSynQuadSerializer<Vec<u8>>, NewSerializerError> {
+        let serializer = self.try_new_serializer_in(syntax_, Vec::new(), overrides)?;
+        // Stringified documents are never compressed.
+        #[cfg(any(test, feature = "compression"))]
+        let serializer = serializer.with_compression_config(CompressionConfig::default());
+        Ok(serializer)
+    }
+}
+
+impl Default for DynSynQuadSerializerFactory {
+    fn default() -> Self {
+        Self::new(None)
     }
 }
 
-/// ---------------------------------------------------------------------------------
-///                                  tests
-/// ---------------------------------------------------------------------------------
+// ---------------------------------------------------------------------------------
+//                                  tests
+// ---------------------------------------------------------------------------------
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+
     use claim::{assert_err, assert_ok};
     use once_cell::sync::Lazy;
     use sophia_api::{
-        dataset::{isomorphic_datasets, Dataset},
-        parser::QuadParser,
-        quad::stream::QuadSource,
+        dataset::{isomorphic_datasets, Dataset, MutableDataset},
+        graph::Graph,
+        parser::{QuadParser, TripleParser},
+        quad::{stream::QuadSource, Quad},
         serializer::{QuadSerializer, Stringifier},
+        term::TTerm,
+        triple::stream::TripleSource,
     };
-    use sophia_inmem::dataset::FastDataset;
+    use sophia_inmem::{dataset::FastDataset, graph::FastGraph};
     use sophia_term::BoxTerm;
-    use sophia_turtle::serializer::{nq::NqConfig, trig::TrigConfig};
+    use sophia_turtle::{
+        parser::turtle::TurtleParser,
+        serializer::{nq::NqConfig, trig::TrigConfig},
+    };
     use test_case::test_case;
     use type_map::concurrent::TypeMap;
 
     use crate::{
-        parser::quads::DynSynQuadParserFactory,
-        serializer::test_data::{TESTS_NQUADS, TESTS_TRIG},
+        error_code::{self, ErrorCode},
+        parser::{quads::DynSynQuadParserFactory, recovery::ParserConfig, skolem::Skolemization},
+        serializer::{config::DynSynSerializerConfig, escape::EscapeConfig, pretty::PrettyConfig},
+        fixtures::{TESTS_NQUADS, TESTS_TRIG},
         syntax::{self, RdfSyntax},
         tests::TRACING,
     };
@@ -223,14 +571,15 @@ mod tests {
 
     /// As DynSyn parsers can be non-cyclically tested, we can use them here.
     static QUAD_PARSER_FACTORY: Lazy<DynSynQuadParserFactory> =
-        Lazy::new(|| DynSynQuadParserFactory::default());
+        Lazy::new(DynSynQuadParserFactory::default);
 
     #[test_case(syntax::JSON_LD)]
     #[test_case(syntax::HTML_RDFA)]
     #[test_case(syntax::N_TRIPLES)]
     #[test_case(syntax::N3)]
     #[test_case(syntax::OWL2_XML)]
-    #[test_case(syntax::RDF_XML)]
+    #[cfg_attr(feature = "rdf-xml", test_case(syntax::RDF_XML))]
+    #[cfg_attr(not(feature = "trix"), test_case(syntax::TRIX))]
     #[test_case(syntax::TURTLE)]
     #[test_case(syntax::XHTML_RDFA)]
     pub fn creating_parser_for_un_supported_syntax_will_error(syntax_: RdfSyntax) {
@@ -239,12 +588,29 @@ mod tests {
     }
 
     #[test_case(syntax::N_QUADS)]
+    #[test_case(syntax::ND_RDF_JSON)]
     #[test_case(syntax::TRIG)]
+    #[cfg_attr(feature = "trix", test_case(syntax::TRIX))]
     pub fn creating_parser_for_supported_syntax_will_succeed(syntax_: RdfSyntax) {
         Lazy::force(&TRACING);
         assert_ok!(SERIALIZER_FACTORY.try_new_stringifier(syntax_));
     }
 
+    #[test_case("out/data.nq", Ok("application/n-quads"))]
+    #[test_case("out/DATA.TriG", Ok("application/trig"))]
+    #[test_case("out/data.ttl", Err(error_code::DYNSYN_SYNTAX_ROLE_MISMATCH))]
+    #[test_case("out/data.jsonld", Err(error_code::DYNSYN_UNKNOWN_SYNTAX))]
+    #[test_case("out/data.png", Err(error_code::DYNSYN_NON_RDF_FILE_EXTENSION))]
+    #[test_case("out/data", Err(error_code::DYNSYN_UNDETERMINED_SYNTAX))]
+    pub fn creating_serializer_for_path_works_correctly(path: &str, expected: Result<&str, &str>) {
+        Lazy::force(&TRACING);
+        let actual = SERIALIZER_FACTORY
+            .try_new_serializer_for_path(Path::new(path), Vec::new())
+            .map(|s| s.content_type().essence_str().to_owned())
+            .map_err(|e| e.code());
+        assert_eq!(actual.as_deref().map_err(|e| *e), expected);
+    }
+
     #[test_case(syntax::TRIG, TESTS_TRIG[0], false)]
     #[test_case(syntax::TRIG, TESTS_TRIG[1], false)]
     #[test_case(syntax::TRIG, TESTS_TRIG[2], false)]
@@ -281,4 +647,336 @@ mod tests {
         let d2: FastDataset = parser.parse_str(&out).collect_quads().unwrap();
         assert!(isomorphic_datasets(&d1, &d2).unwrap());
     }
+    #[test_case(syntax::N_QUADS, false)]
+    #[test_case(syntax::TRIG, false)]
+    #[test_case(syntax::TRIG, true)]
+    #[test_case(syntax::ND_RDF_JSON, false)]
+    pub fn counts_serialized_quads(syntax_: RdfSyntax, pretty: bool) {
+        Lazy::force(&TRACING);
+        let d: FastDataset = QUAD_PARSER_FACTORY
+            .try_new_parser(syntax::TRIG, None, None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(TESTS_TRIG[1])
+            .collect_quads()
+            .unwrap();
+        let expected = d.quads().count();
+
+        let factory = if pretty {
+            &SERIALIZER_FACTORY_WITH_PRETTY_CONFIG
+        } else {
+            &SERIALIZER_FACTORY
+        };
+        let mut stringifier = factory.try_new_stringifier(syntax_).unwrap();
+        let (stringifier, count) = stringifier.serialize_dataset_counted(&d).unwrap();
+        assert!(expected > 0);
+        assert_eq!(count, expected);
+        assert!(!stringifier.as_str().is_empty());
+    }
+
+    static NON_ASCII_TRIG_DOC: &str = r#"
+        @prefix ex: <http://example.org/> .
+        <http://example.org/café> ex:name "Café 😀"@fr ; ex:label "naïve \"q\"" .
+        ex:g { <http://example.org/é> ex:p "ü" . }
+    "#;
+
+    #[test_case(syntax::N_QUADS, false)]
+    #[test_case(syntax::TRIG, false)]
+    #[test_case(syntax::TRIG, true)]
+    #[cfg_attr(feature = "trix", test_case(syntax::TRIX, false))]
+    pub fn escapes_non_ascii_when_configured(syntax_: RdfSyntax, pretty: bool) {
+        Lazy::force(&TRACING);
+        let d1: FastDataset = QUAD_PARSER_FACTORY
+            .try_new_parser(syntax::TRIG, None, None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(NON_ASCII_TRIG_DOC)
+            .collect_quads()
+            .unwrap();
+
+        let mut config_map = TypeMap::new();
+        config_map.insert::<EscapeConfig>(EscapeConfig::new().with_escape_non_ascii(true));
+        config_map.insert::<TrigConfig>(TrigConfig::new().with_pretty(pretty));
+        let factory = DynSynQuadSerializerFactory::new(Some(config_map));
+
+        let out = factory
+            .try_new_stringifier(syntax_)
+            .unwrap()
+            .serialize_quads(d1.quads())
+            .unwrap()
+            .to_string();
+        assert!(out.is_ascii(), "{}", out);
+
+        let d2: FastDataset = QUAD_PARSER_FACTORY
+            .try_new_parser(syntax_, None, None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(&out)
+            .collect_quads()
+            .unwrap();
+        assert!(isomorphic_datasets(&d1, &d2).unwrap());
+    }
+
+    // Ascii flag of sophia's config is not honoured, but it doesn't make serializer panic either.
+    #[test_case(false; "by default")]
+    #[test_case(true; "with ascii flag of nq config")]
+    pub fn writes_non_ascii_as_is_by_default(nq_config_ascii: bool) {
+        Lazy::force(&TRACING);
+        let d: FastDataset = QUAD_PARSER_FACTORY
+            .try_new_parser(syntax::TRIG, None, None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(NON_ASCII_TRIG_DOC)
+            .collect_quads()
+            .unwrap();
+        let mut config_map = TypeMap::new();
+        let mut nq_config = NqConfig::default();
+        nq_config.set_ascii(nq_config_ascii);
+        config_map.insert::<NqConfig>(nq_config);
+        let out = DynSynQuadSerializerFactory::new(Some(config_map))
+            .try_new_stringifier(syntax::N_QUADS)
+            .unwrap()
+            .serialize_quads(d.quads())
+            .unwrap()
+            .to_string();
+        assert!(out.contains("café"));
+    }
+
+    #[test_case(syntax::TRIG, false)]
+    #[test_case(syntax::TRIG, true)]
+    #[test_case(syntax::N_QUADS, true)]
+    pub fn applies_unified_pretty_options_where_supported(syntax_: RdfSyntax, layout: bool) {
+        Lazy::force(&TRACING);
+        let parser = QUAD_PARSER_FACTORY
+            .try_new_parser(syntax::TRIG, None, None as Option<BoxTerm>)
+            .unwrap();
+        let d1: FastDataset = parser.parse_str(TESTS_TRIG[1]).collect_quads().unwrap();
+
+        let mut config_map = TypeMap::new();
+        config_map.insert::<PrettyConfig>(
+            PrettyConfig::new()
+                .with_pretty(true)
+                .with_indent("\t")
+                .unwrap()
+                .with_max_line_width(layout.then_some(80))
+                .with_align_objects(layout),
+        );
+        let out = DynSynQuadSerializerFactory::new(Some(config_map))
+            .try_new_stringifier(syntax_)
+            .unwrap()
+            .serialize_quads(d1.quads())
+            .unwrap()
+            .to_string();
+        assert_eq!(syntax_ == syntax::TRIG, out.contains("\n\t"));
+
+        let d2: FastDataset = QUAD_PARSER_FACTORY
+            .try_new_parser(syntax_, None, None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(&out)
+            .collect_quads()
+            .unwrap();
+        assert!(isomorphic_datasets(&d1, &d2).unwrap());
+    }
+
+    #[test_case(syntax::N_QUADS)]
+    #[test_case(syntax::TRIG)]
+    pub fn preserves_bnode_labels_when_configured(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let mut parser_config_map = TypeMap::new();
+        parser_config_map.insert(Skolemization::try_new("https://data.example").unwrap());
+        parser_config_map.insert(ParserConfig::preserve_bnode_labels());
+        let d1: FastDataset = DynSynQuadParserFactory::new(Some(parser_config_map))
+            .try_new_parser(syntax::N_QUADS, None, None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(
+                "_:alice <http://example.org/knows> _:bob <http://example.org/g> .\n_:bob <http://example.org/knows> _:carol .\n",
+            )
+            .collect_quads()
+            .unwrap();
+
+        let mut config_map = TypeMap::new();
+        config_map.insert(
+            DynSynSerializerConfig::new()
+                .with_pretty(true)
+                .with_preserve_bnode_labels(true),
+        );
+        let out = DynSynQuadSerializerFactory::new(Some(config_map))
+            .try_new_stringifier(syntax_)
+            .unwrap()
+            .serialize_quads(d1.quads())
+            .unwrap()
+            .to_string();
+        for label in ["_:alice", "_:bob", "_:carol"] {
+            assert!(out.contains(label), "{} is not preserved in {}", label, out);
+        }
+    }
+
+    #[test_case(true)]
+    #[test_case(false)]
+    pub fn toggles_rendering_of_collections(render_collections: bool) {
+        Lazy::force(&TRACING);
+        let parser = QUAD_PARSER_FACTORY
+            .try_new_parser(syntax::TRIG, None, None as Option<BoxTerm>)
+            .unwrap();
+        let d1: FastDataset = parser
+            .parse_str(
+                "<http://example.org/g> { <http://example.org/s> <http://example.org/p> (1 2) }",
+            )
+            .collect_quads()
+            .unwrap();
+
+        let mut config_map = TypeMap::new();
+        config_map.insert::<PrettyConfig>(
+            PrettyConfig::new()
+                .with_pretty(true)
+                .with_render_collections(render_collections),
+        );
+        let out = DynSynQuadSerializerFactory::new(Some(config_map))
+            .try_new_stringifier(syntax::TRIG)
+            .unwrap()
+            .serialize_quads(d1.quads())
+            .unwrap()
+            .to_string();
+        assert_eq!(!render_collections, out.contains("rdf:first"));
+        assert!(out.contains("\n    <http://example.org/p> "));
+
+        let d2: FastDataset = parser.parse_str(&out).collect_quads().unwrap();
+        assert!(isomorphic_datasets(&d1, &d2).unwrap());
+    }
+
+    #[test_case(syntax::TRIG, true)]
+    #[test_case(syntax::TRIG, false)]
+    #[test_case(syntax::N_QUADS, true)]
+    pub fn applies_prefix_map_where_supported(syntax_: RdfSyntax, pretty: bool) {
+        Lazy::force(&TRACING);
+        let parser = QUAD_PARSER_FACTORY
+            .try_new_parser(syntax::TRIG, None, None as Option<BoxTerm>)
+            .unwrap();
+        let mut source = parser.parse_str(
+            "PREFIX ex: <http://example.org/ns#>\nex:g { ex:s ex:p ex:o }",
+        );
+        let mut d1 = FastDataset::new();
+        source
+            .for_each_quad(|q| {
+                d1.insert(q.s(), q.p(), q.o(), q.g()).unwrap();
+            })
+            .unwrap();
+
+        let mut config_map = TypeMap::new();
+        config_map.insert::<PrettyConfig>(PrettyConfig::new().with_pretty(pretty));
+        config_map.insert::<DynSynSerializerConfig>(
+            DynSynSerializerConfig::new().with_prefix_map(source.prefixes().prefix_map()),
+        );
+        let out = DynSynQuadSerializerFactory::new(Some(config_map))
+            .try_new_stringifier(syntax_)
+            .unwrap()
+            .serialize_quads(d1.quads())
+            .unwrap()
+            .to_string();
+        assert_eq!(syntax_ == syntax::TRIG && pretty, out.contains("ex:s"));
+
+        let d2: FastDataset = QUAD_PARSER_FACTORY
+            .try_new_parser(syntax_, None, None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(&out)
+            .collect_quads()
+            .unwrap();
+        assert!(isomorphic_datasets(&d1, &d2).unwrap());
+    }
+
+    #[test]
+    pub fn per_call_configs_override_factory_configs_of_same_type() {
+        Lazy::force(&TRACING);
+        let d1: FastDataset = QUAD_PARSER_FACTORY
+            .try_new_parser(syntax::TRIG, None, None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(NON_ASCII_TRIG_DOC)
+            .collect_quads()
+            .unwrap();
+
+        let mut config_map = TypeMap::new();
+        config_map.insert(EscapeConfig::new().with_escape_non_ascii(true));
+        config_map.insert(PrettyConfig::new().with_pretty(false));
+        let factory = DynSynQuadSerializerFactory::new(Some(config_map));
+
+        let mut overrides = TypeMap::new();
+        overrides.insert(PrettyConfig::new().with_pretty(true));
+        let mut serializer = factory
+            .try_new_serializer_with_config(syntax::TRIG, Vec::new(), overrides)
+            .unwrap();
+        let pretty_out = serializer.serialize_dataset(&d1).unwrap().to_string();
+        // Escape config of factory is retained.
+        assert!(pretty_out.is_ascii(), "{}", pretty_out);
+
+        // Factory configs are unchanged for other serializers.
+        let out = factory
+            .try_new_stringifier(syntax::TRIG)
+            .unwrap()
+            .serialize_dataset(&d1)
+            .unwrap()
+            .to_string();
+        assert_ne!(out, pretty_out);
+        assert!(out.is_ascii(), "{}", out);
+
+        let d2: FastDataset = QUAD_PARSER_FACTORY
+            .try_new_parser(syntax::TRIG, None, None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(&out)
+            .collect_quads()
+            .unwrap();
+        assert!(isomorphic_datasets(&d1, &d2).unwrap());
+    }
+
+    #[test]
+    pub fn cloned_factories_share_config_across_threads() {
+        Lazy::force(&TRACING);
+        let mut config_map = TypeMap::new();
+        config_map.insert(EscapeConfig::new().with_escape_non_ascii(true));
+        let factory = DynSynQuadSerializerFactory::new(Some(config_map));
+        let cloned = factory.clone();
+        assert!(std::sync::Arc::ptr_eq(
+            &factory.serializer_config_map,
+            &cloned.serializer_config_map
+        ));
+
+        let handle = std::thread::spawn(move || {
+            let d1: FastDataset = QUAD_PARSER_FACTORY
+                .try_new_parser(syntax::TRIG, None, None as Option<BoxTerm>)
+                .unwrap()
+                .parse_str(NON_ASCII_TRIG_DOC)
+                .collect_quads()
+                .unwrap();
+            cloned
+                .try_new_stringifier(syntax::N_QUADS)
+                .unwrap()
+                .serialize_dataset(&d1)
+                .unwrap()
+                .to_string()
+        });
+        let out = handle.join().unwrap();
+        assert!(out.is_ascii(), "{}", out);
+    }
+
+    #[test_case(syntax::N_QUADS)]
+    #[test_case(syntax::TRIG)]
+    pub fn serializes_triples_in_chosen_graph(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let graph: FastGraph = TurtleParser { base: None }
+            .parse_str(r#"<http://ex.org/a> <http://ex.org/b> "c", "d" ."#)
+            .collect_triples()
+            .unwrap();
+        let g = BoxTerm::new_iri("http://ex.org/g").unwrap();
+        let mut serializer = SERIALIZER_FACTORY.try_new_stringifier(syntax_).unwrap();
+        serializer
+            .serialize_triples_in_graph(graph.triples(), Some(&g))
+            .unwrap();
+
+        let dataset: FastDataset = QUAD_PARSER_FACTORY
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap()
+            .parse_str(serializer.as_str())
+            .collect_quads()
+            .unwrap();
+        assert_eq!(dataset.quads().count(), 2);
+        assert!(dataset
+            .quads()
+            .all(|q| q.unwrap().g().map(|g| g.value().to_string())
+                == Some("http://ex.org/g".to_string())));
+    }
 }
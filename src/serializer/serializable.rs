@@ -0,0 +1,471 @@
+//! This module provides [`DynSynSerializable`] trait, that abstracts over anything that can be serialized by dynsyn serializers: graphs, datasets, triple-sources, and quad-sources. Generic application code can serialize any of them through a single [`serialize_to`](DynSynSerializable::serialize_to) entry point, instead of having separate code paths.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{serializer::serializable::*, syntax::{self, RdfSyntax}};
+//! use sophia_term::StaticTerm;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! fn export<S, M>(s: S, syntax_: RdfSyntax) -> Vec<u8>
+//! where
+//!     S: DynSynSerializable<M>,
+//! {
+//!     let mut out = Vec::new();
+//!     s.serialize_to(syntax_, &mut out, &DynSynSerializerFactories::default()).unwrap();
+//!     out
+//! }
+//!
+//! let me = StaticTerm::new_iri("http://example.org/#me")?;
+//! let graph = vec![[me, me, me]];
+//! let dataset = vec![([me, me, me], None)];
+//!
+//! assert_eq!(export(&graph, syntax::N_QUADS), export(&dataset, syntax::N_QUADS));
+//! assert_eq!(export(&graph, syntax::TURTLE), export(&dataset, syntax::TURTLE));
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::io;
+
+use mime::Mime;
+use sophia_api::{
+    dataset::Dataset,
+    graph::Graph,
+    quad::{stream::QuadSource, Quad},
+    serializer::{QuadSerializer, TripleSerializer},
+    term::{term_to_string, CopyTerm},
+    triple::{
+        stream::{StreamError, StreamResult, TripleSource},
+        streaming_mode::{ByValue, StreamedTriple},
+        Triple,
+    },
+};
+use sophia_term::BoxTerm;
+
+use crate::{
+    error_code::{self, ErrorCode},
+    syntax::{RdfSyntax, UnKnownSyntaxError},
+};
+
+use super::{
+    bnode_cycle::BlankNodeCycle, quads::DynSynQuadSerializerFactory,
+    triples::DynSynTripleSerializerFactory,
+};
+
+/// Action to take, when quads in named graphs are serialized into a triple-only syntax, that can't represent graphs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum NamedGraphAction {
+    /// Fail serialization with [`DynSynSerializeError::NamedGraph`] on first quad in a named graph.
+    #[default]
+    Error,
+    /// Serialize only quads in default graph, and drop quads in named graphs.
+    Drop,
+    /// Serialize quads of all graphs as triples, merging them into a single graph.
+    Merge,
+}
+
+/// Serializer factories, with which a [`DynSynSerializable`] is serialized. Factories in turn hold formatting options for each syntax, like [`DynSynSerializerConfig`](super::config::DynSynSerializerConfig).
+#[derive(Default)]
+pub struct DynSynSerializerFactories {
+    /// Factory of serializers, with which graphs, and triple-sources are serialized. Datasets, and quad-sources are serialized with it too, into syntaxes that quad serializer factory doesn't support.
+    pub triple_serializer_factory: DynSynTripleSerializerFactory,
+    /// Factory of serializers, with which datasets, and quad-sources are serialized. Graphs, and triple-sources are serialized with it too, into syntaxes that triple serializer factory doesn't support.
+    pub quad_serializer_factory: DynSynQuadSerializerFactory,
+    /// Action to take on quads in named graphs, when a dataset, or a quad-source is serialized into a triple-only syntax. Defaults to [`NamedGraphAction::Error`].
+    pub named_graph_action: NamedGraphAction,
+}
+
+impl DynSynSerializerFactories {
+    /// Create new serializer factories with given triple, and quad serializer factories. Serializing quads in named graphs into triple-only syntaxes will error, unless another [`NamedGraphAction`] is set with [`with_named_graph_action`](Self::with_named_graph_action).
+    pub fn new(
+        triple_serializer_factory: DynSynTripleSerializerFactory,
+        quad_serializer_factory: DynSynQuadSerializerFactory,
+    ) -> Self {
+        Self {
+            triple_serializer_factory,
+            quad_serializer_factory,
+            named_graph_action: NamedGraphAction::default(),
+        }
+    }
+
+    /// Set action to take on quads in named graphs, when they are serialized into a triple-only syntax.
+    pub fn with_named_graph_action(mut self, named_graph_action: NamedGraphAction) -> Self {
+        self.named_graph_action = named_graph_action;
+        self
+    }
+
+    /// Get content type of documents in `syntax_`, that [`serialize_to`](DynSynSerializable::serialize_to) produces with this config. Content type is taken from triple serializer factory if it supports `syntax_`, else from quad serializer factory.
+    ///
+    /// # Errors
+    /// returns [`UnKnownSyntaxError`] if `syntax_` is not supported by any of serializers.
+    pub fn content_type(&self, syntax_: RdfSyntax) -> Result<Mime, UnKnownSyntaxError> {
+        self.triple_serializer_factory
+            .content_type(syntax_)
+            .or_else(|_| self.quad_serializer_factory.content_type(syntax_))
+    }
+}
+
+/// An error in serializing a [`DynSynSerializable`].
+#[derive(Debug, thiserror::Error)]
+pub enum DynSynSerializeError<E>
+where
+    E: std::error::Error + 'static,
+{
+    #[error(transparent)]
+    UnKnownSyntax(#[from] UnKnownSyntaxError),
+    #[error("Error in reading statements: {0}")]
+    Source(#[source] E),
+    #[error("Error in writing serialized doc: {0}")]
+    Io(#[source] io::Error),
+    #[error("Quad in named graph {0} can't be serialized into a triple-only syntax")]
+    NamedGraph(String),
+}
+
+impl<E: std::error::Error + 'static> From<StreamError<E, io::Error>> for DynSynSerializeError<E> {
+    fn from(e: StreamError<E, io::Error>) -> Self {
+        match e {
+            StreamError::SourceError(e) => Self::Source(e),
+            StreamError::SinkError(e) => Self::Io(e),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> ErrorCode for DynSynSerializeError<E> {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::UnKnownSyntax(e) => e.code(),
+            Self::Source(_) => error_code::DYNSYN_STATEMENT_SOURCE,
+            Self::NamedGraph(_) => error_code::DYNSYN_NAMED_GRAPH_IN_TRIPLE_SYNTAX,
+            Self::Io(e) => match BlankNodeCycle::from_io_error(e) {
+                Some(cycle) => cycle.code(),
+                None => error_code::DYNSYN_SERIALIZE_IO,
+            },
+        }
+    }
+}
+
+/// Marker for [`DynSynSerializable`] impl on graphs.
+pub struct GraphMarker;
+/// Marker for [`DynSynSerializable`] impl on datasets.
+pub struct DatasetMarker;
+/// Marker for [`DynSynSerializable`] impl on triple-sources.
+pub struct TripleSourceMarker;
+/// Marker for [`DynSynSerializable`] impl on quad-sources.
+pub struct QuadSourceMarker;
+
+/// A trait for anything, that can be serialized by dynsyn serializers.
+///
+/// It is implemented for references to sophia [`Graph`]s, and [`Dataset`]s, and for [`TripleSource`]s, and [`QuadSource`]s. Type parameter `M` is a marker that only disambiguates these impls, and is always inferred.
+///
+/// Triples are serialized into quad-only syntaxes as quads in default graph. Quads are serialized into triple-only syntaxes as triples, and quads in named graphs are handled as per configured [`NamedGraphAction`]. By default, they fail serialization, instead of being silently lost.
+pub trait DynSynSerializable<M> {
+    /// Type of error in reading statements from this serializable.
+    type SourceError: std::error::Error + 'static;
+
+    /// Serializes this into `syntax_`, writing to `write`, with serializers from given `config`.
+    ///
+    /// # Errors
+    /// returns [`DynSynSerializeError::UnKnownSyntax`] if `syntax_` is not supported by any of serializers, and [`DynSynSerializeError::NamedGraph`] if a quad in a named graph is serialized into a triple-only syntax with [`NamedGraphAction::Error`].
+    fn serialize_to<W: io::Write>(
+        self,
+        syntax_: RdfSyntax,
+        write: W,
+        config: &DynSynSerializerFactories,
+    ) -> Result<(), DynSynSerializeError<Self::SourceError>>;
+}
+
+impl<QS: QuadSource> DynSynSerializable<QuadSourceMarker> for QS {
+    type SourceError = QS::Error;
+
+    fn serialize_to<W: io::Write>(
+        self,
+        syntax_: RdfSyntax,
+        mut write: W,
+        config: &DynSynSerializerFactories,
+    ) -> Result<(), DynSynSerializeError<Self::SourceError>> {
+        if let Ok(mut serializer) = config
+            .quad_serializer_factory
+            .try_new_serializer(syntax_, &mut write)
+        {
+            serializer.serialize_quads(self)?;
+            return Ok(());
+        }
+        let mut serializer = config
+            .triple_serializer_factory
+            .try_new_serializer(syntax_, &mut write)
+            .map_err(UnKnownSyntaxError::from)?;
+        serializer
+            .serialize_triples(GraphTriples::new(self, config.named_graph_action))
+            .map_err(|e| match e {
+                StreamError::SourceError(e) => e,
+                StreamError::SinkError(e) => DynSynSerializeError::Io(e),
+            })?;
+        Ok(())
+    }
+}
+
+/// Triples of quads of a source, whose quads in named graphs are handled as per a [`NamedGraphAction`].
+pub(crate) struct GraphTriples<QS> {
+    source: QS,
+    action: NamedGraphAction,
+}
+
+impl<QS> GraphTriples<QS> {
+    pub(crate) fn new(source: QS, action: NamedGraphAction) -> Self {
+        Self { source, action }
+    }
+}
+
+/// An error of a sink of [`GraphTriples`], or of a quad in a named graph, that can't be serialized.
+#[derive(Debug, thiserror::Error)]
+enum GraphTriplesSinkError<E: std::error::Error> {
+    #[error(transparent)]
+    Sink(E),
+    #[error("Quad in named graph {0}")]
+    NamedGraph(String),
+}
+
+impl<QS: QuadSource> TripleSource for GraphTriples<QS> {
+    type Error = DynSynSerializeError<QS::Error>;
+
+    type Triple = ByValue<[BoxTerm; 3]>;
+
+    fn try_for_some_triple<F, E>(&mut self, f: &mut F) -> StreamResult<bool, Self::Error, E>
+    where
+        F: FnMut(StreamedTriple<Self::Triple>) -> Result<(), E>,
+        E: std::error::Error,
+    {
+        let action = self.action;
+        self.source
+            .try_for_some_quad(&mut |q| {
+                if let Some(g) = q.g() {
+                    match action {
+                        NamedGraphAction::Error => {
+                            return Err(GraphTriplesSinkError::NamedGraph(term_to_string(g)))
+                        }
+                        NamedGraphAction::Drop => return Ok(()),
+                        NamedGraphAction::Merge => {}
+                    }
+                }
+                f(StreamedTriple::by_value([
+                    BoxTerm::copy(q.s()),
+                    BoxTerm::copy(q.p()),
+                    BoxTerm::copy(q.o()),
+                ]))
+                .map_err(GraphTriplesSinkError::Sink)
+            })
+            .map_err(|e| match e {
+                StreamError::SourceError(e) => {
+                    StreamError::SourceError(DynSynSerializeError::Source(e))
+                }
+                StreamError::SinkError(GraphTriplesSinkError::Sink(e)) => StreamError::SinkError(e),
+                StreamError::SinkError(GraphTriplesSinkError::NamedGraph(g)) => {
+                    StreamError::SourceError(DynSynSerializeError::NamedGraph(g))
+                }
+            })
+    }
+}
+
+impl<TS: TripleSource> DynSynSerializable<TripleSourceMarker> for TS {
+    type SourceError = TS::Error;
+
+    fn serialize_to<W: io::Write>(
+        self,
+        syntax_: RdfSyntax,
+        mut write: W,
+        config: &DynSynSerializerFactories,
+    ) -> Result<(), DynSynSerializeError<Self::SourceError>> {
+        if let Ok(mut serializer) = config
+            .triple_serializer_factory
+            .try_new_serializer(syntax_, &mut write)
+        {
+            serializer.serialize_triples(self)?;
+            return Ok(());
+        }
+        let mut serializer = config
+            .quad_serializer_factory
+            .try_new_serializer(syntax_, &mut write)
+            .map_err(UnKnownSyntaxError::from)?;
+        serializer.serialize_quads(self.filter_map_triples(|t| {
+            Some((
+                [
+                    BoxTerm::copy(t.s()),
+                    BoxTerm::copy(t.p()),
+                    BoxTerm::copy(t.o()),
+                ],
+                None::<BoxTerm>,
+            ))
+        }))?;
+        Ok(())
+    }
+}
+
+impl<G: Graph> DynSynSerializable<GraphMarker> for &G {
+    type SourceError = G::Error;
+
+    fn serialize_to<W: io::Write>(
+        self,
+        syntax_: RdfSyntax,
+        write: W,
+        config: &DynSynSerializerFactories,
+    ) -> Result<(), DynSynSerializeError<Self::SourceError>> {
+        self.triples().serialize_to(syntax_, write, config)
+    }
+}
+
+impl<D: Dataset> DynSynSerializable<DatasetMarker> for &D {
+    type SourceError = D::Error;
+
+    fn serialize_to<W: io::Write>(
+        self,
+        syntax_: RdfSyntax,
+        write: W,
+        config: &DynSynSerializerFactories,
+    ) -> Result<(), DynSynSerializeError<Self::SourceError>> {
+        self.quads().serialize_to(syntax_, write, config)
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use once_cell::sync::Lazy;
+    use sophia_api::{dataset::isomorphic_datasets, parser::QuadParser};
+    use sophia_inmem::dataset::FastDataset;
+    use test_case::test_case;
+    use type_map::concurrent::TypeMap;
+
+    use crate::{
+        parser::quads::{source::TupleQuad, DynSynQuadParserFactory},
+        serializer::content_type::ContentTypeConfig,
+        syntax,
+        tests::TRACING,
+    };
+
+    use super::*;
+
+    static CONFIG: Lazy<DynSynSerializerFactories> = Lazy::new(DynSynSerializerFactories::default);
+
+    fn me() -> BoxTerm {
+        BoxTerm::new_iri("http://example.org/#me").unwrap()
+    }
+
+    fn to_string<S: DynSynSerializable<M>, M>(s: S, syntax_: RdfSyntax) -> String {
+        let mut out = Vec::new();
+        s.serialize_to(syntax_, &mut out, &CONFIG).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn parse(doc: &str, syntax_: RdfSyntax) -> FastDataset {
+        DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax_, None, None)
+            .unwrap()
+            .parse_str(doc)
+            .collect_quads()
+            .unwrap()
+    }
+
+    #[test_case(syntax::N_QUADS)]
+    #[test_case(syntax::TRIG)]
+    #[test_case(syntax::N_TRIPLES)]
+    #[test_case(syntax::TURTLE)]
+    #[cfg_attr(feature = "rdf-xml", test_case(syntax::RDF_XML))]
+    pub fn graphs_and_triple_sources_serialize_alike(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let graph = vec![[me(), me(), me()]];
+        let from_graph = to_string(&graph, syntax_);
+        let from_source = to_string(graph.iter().map(Ok::<_, Infallible>), syntax_);
+        assert_eq!(from_graph, from_source);
+        let d = parse(&from_graph, syntax_);
+        assert_eq!(d.quads().count(), 1);
+    }
+
+    #[test_case(syntax::N_QUADS)]
+    #[test_case(syntax::TRIG)]
+    pub fn datasets_serialize_into_quad_syntaxes(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let dataset: Vec<TupleQuad<BoxTerm>> =
+            vec![([me(), me(), me()], None), ([me(), me(), me()], Some(me()))];
+        let d1 = parse(&to_string(&dataset, syntax_), syntax_);
+        let d2 = parse(
+            &to_string(dataset.iter().map(Ok::<_, Infallible>), syntax_),
+            syntax_,
+        );
+        assert!(isomorphic_datasets(&dataset, &d1).unwrap());
+        assert!(isomorphic_datasets(&dataset, &d2).unwrap());
+    }
+
+    #[test_case(NamedGraphAction::Drop, 1)]
+    #[test_case(NamedGraphAction::Merge, 2)]
+    pub fn datasets_serialize_into_triple_syntaxes_as_per_named_graph_action(
+        action: NamedGraphAction,
+        lines: usize,
+    ) {
+        Lazy::force(&TRACING);
+        let other = BoxTerm::new_iri("http://example.org/#other").unwrap();
+        let dataset: Vec<TupleQuad<BoxTerm>> = vec![
+            ([me(), me(), me()], None),
+            ([me(), me(), other], Some(me())),
+        ];
+        let config = DynSynSerializerFactories::default().with_named_graph_action(action);
+        let mut out = Vec::new();
+        (&dataset)
+            .serialize_to(syntax::N_TRIPLES, &mut out, &config)
+            .unwrap();
+        assert_eq!(String::from_utf8(out).unwrap().lines().count(), lines);
+    }
+
+    #[test]
+    pub fn serializing_named_graphs_into_triple_syntaxes_will_error_by_default() {
+        Lazy::force(&TRACING);
+        let dataset: Vec<TupleQuad<BoxTerm>> =
+            vec![([me(), me(), me()], None), ([me(), me(), me()], Some(me()))];
+        let e = (&dataset)
+            .serialize_to(syntax::TURTLE, Vec::new(), &CONFIG)
+            .unwrap_err();
+        assert_eq!(e.code(), error_code::DYNSYN_NAMED_GRAPH_IN_TRIPLE_SYNTAX);
+        assert!(matches!(
+            e,
+            DynSynSerializeError::NamedGraph(g) if g == "<http://example.org/#me>"
+        ));
+
+        // Datasets without named graphs serialize, as there is nothing to lose.
+        let doc = to_string(&dataset[..1].to_vec(), syntax::TURTLE);
+        assert_eq!(parse(&doc, syntax::TURTLE).quads().count(), 1);
+    }
+
+    #[test]
+    pub fn serializing_into_unsupported_syntax_will_error() {
+        Lazy::force(&TRACING);
+        let graph = vec![[me(), me(), me()]];
+        let e = (&graph)
+            .serialize_to(syntax::JSON_LD, Vec::new(), &CONFIG)
+            .unwrap_err();
+        assert_eq!(e.code(), error_code::DYNSYN_UNKNOWN_SYNTAX);
+    }
+
+    #[test]
+    pub fn reports_content_type_from_supporting_factory() {
+        Lazy::force(&TRACING);
+        let mut config_map = TypeMap::new();
+        config_map.insert(ContentTypeConfig::new().with_charset_utf8(true));
+        let config = DynSynSerializerFactories::new(
+            DynSynTripleSerializerFactory::default(),
+            DynSynQuadSerializerFactory::new(Some(config_map)),
+        );
+        let content_type = |syntax_| config.content_type(syntax_).unwrap().to_string();
+        assert_eq!(content_type(syntax::TURTLE), "text/turtle");
+        assert_eq!(content_type(syntax::TRIG), "application/trig; charset=utf-8");
+        assert_eq!(
+            config.content_type(syntax::JSON_LD).unwrap_err().code(),
+            error_code::DYNSYN_UNKNOWN_SYNTAX
+        );
+    }
+}
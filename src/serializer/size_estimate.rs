@@ -0,0 +1,221 @@
+//! This module provides functions to predict size of serialized output of a quad-source, without writing it anywhere. Services can use them to set `Content-Length` headers, or to reject oversized exports before doing the full work.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{
+//!     serializer::{quads::DynSynQuadSerializerFactory, size_estimate::estimate_size},
+//!     syntax,
+//! };
+//! use sophia_api::{dataset::Dataset, serializer::{QuadSerializer, Stringifier}};
+//! use sophia_term::StaticTerm;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let me = StaticTerm::new_iri("http://example.org/#me")?;
+//! let dataset = vec![([me, me, me], None), ([me, me, me], Some(me))];
+//!
+//! let size = estimate_size(dataset.quads(), syntax::N_QUADS)?;
+//!
+//! // Same as the size of actually serialized document.
+//! let mut serializer = DynSynQuadSerializerFactory::new(None).try_new_stringifier(syntax::N_QUADS)?;
+//! assert_eq!(size, serializer.serialize_dataset(&dataset)?.as_utf8().len());
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::io;
+
+use sophia_api::{
+    quad::{stream::QuadSource, Quad},
+    serializer::{QuadSerializer, TripleSerializer},
+    term::CopyTerm,
+    triple::stream::StreamError,
+};
+use sophia_term::BoxTerm;
+
+use crate::{
+    error_code::{self, ErrorCode},
+    syntax::{RdfSyntax, UnKnownSyntaxError},
+};
+
+use super::{quads::DynSynQuadSerializerFactory, triples::DynSynTripleSerializerFactory};
+
+/// An [`io::Write`] sink, that discards all bytes written to it, but counts them.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ByteCounter {
+    count: usize,
+}
+
+impl ByteCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns number of bytes written to this counter so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.count += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// An error in estimating size of serialized output.
+#[derive(Debug, thiserror::Error)]
+pub enum SizeEstimationError<E>
+where
+    E: std::error::Error + 'static,
+{
+    #[error(transparent)]
+    UnKnownSyntax(#[from] UnKnownSyntaxError),
+    #[error("Error in reading quad source: {0}")]
+    Source(#[source] E),
+    #[error("Error in formatting statements: {0}")]
+    Format(#[source] io::Error),
+}
+
+impl<E: std::error::Error + 'static> From<StreamError<E, io::Error>> for SizeEstimationError<E> {
+    fn from(e: StreamError<E, io::Error>) -> Self {
+        match e {
+            StreamError::SourceError(e) => Self::Source(e),
+            StreamError::SinkError(e) => Self::Format(e),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> ErrorCode for SizeEstimationError<E> {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::UnKnownSyntax(e) => e.code(),
+            Self::Source(_) => error_code::DYNSYN_STATEMENT_SOURCE,
+            Self::Format(_) => error_code::DYNSYN_SERIALIZE_IO,
+        }
+    }
+}
+
+/// Estimates size in bytes of the document, that results from serializing given `quad_source` into `syntax_` with default serializer configurations. See [`estimate_size_with`].
+pub fn estimate_size<QS>(
+    quad_source: QS,
+    syntax_: RdfSyntax,
+) -> Result<usize, SizeEstimationError<QS::Error>>
+where
+    QS: QuadSource,
+{
+    estimate_size_with(
+        quad_source,
+        syntax_,
+        &DynSynTripleSerializerFactory::new(None),
+        &DynSynQuadSerializerFactory::new(None),
+    )
+}
+
+/// Estimates size in bytes of the document, that results from serializing given `quad_source` into `syntax_` with serializers from given factories.
+///
+/// Statements are streamed through formatting of corresponding serializer, but formatted bytes are only counted, not stored. Hence estimate is exact, and memory usage is independent of output size.
+///
+/// If `syntax_` cannot represent quads, only quads in default graph will be accounted, as only those will be serialized by a triple serializer.
+pub fn estimate_size_with<QS>(
+    quad_source: QS,
+    syntax_: RdfSyntax,
+    triple_serializer_factory: &DynSynTripleSerializerFactory,
+    quad_serializer_factory: &DynSynQuadSerializerFactory,
+) -> Result<usize, SizeEstimationError<QS::Error>>
+where
+    QS: QuadSource,
+{
+    let mut counter = ByteCounter::new();
+    // Serializers are dropped explicitly before counter is read, as they borrow it till then.
+    if quad_serializer_factory.content_type(syntax_).is_ok() {
+        let mut serializer = quad_serializer_factory
+            .try_new_serializer(syntax_, &mut counter)
+            .map_err(UnKnownSyntaxError::from)?;
+        serializer.serialize_quads(quad_source)?;
+        drop(serializer);
+        return Ok(counter.count());
+    }
+
+    let mut serializer = triple_serializer_factory
+        .try_new_serializer(syntax_, &mut counter)
+        .map_err(UnKnownSyntaxError::from)?;
+    serializer.serialize_triples(quad_source.filter_map_quads(|q| {
+        if q.g().is_some() {
+            return None;
+        }
+        Some([
+            BoxTerm::copy(q.s()),
+            BoxTerm::copy(q.p()),
+            BoxTerm::copy(q.o()),
+        ])
+    }))?;
+    drop(serializer);
+    Ok(counter.count())
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use sophia_api::serializer::Stringifier;
+    use test_case::test_case;
+
+    use crate::{
+        gen::{DatasetGenConfig, DatasetGenerator, DatasetShape},
+        syntax,
+        tests::TRACING,
+    };
+
+    use super::*;
+
+    #[test_case(syntax::N_QUADS, DatasetShape::ManyGraphs)]
+    #[test_case(syntax::TRIG, DatasetShape::ManyGraphs)]
+    #[test_case(syntax::N_TRIPLES, DatasetShape::LiteralHeavy)]
+    #[test_case(syntax::TURTLE, DatasetShape::BNodeHeavy)]
+    #[cfg_attr(feature = "rdf-xml", test_case(syntax::RDF_XML, DatasetShape::Star))]
+    pub fn estimate_matches_serialized_size(syntax_: RdfSyntax, shape: DatasetShape) {
+        Lazy::force(&TRACING);
+        let config = DatasetGenConfig::new(shape, 200).with_seed(1);
+        let estimate = estimate_size(DatasetGenerator::new(config.clone()), syntax_).unwrap();
+
+        let actual = match DynSynQuadSerializerFactory::new(None).try_new_stringifier(syntax_) {
+            Ok(mut s) => s
+                .serialize_quads(DatasetGenerator::new(config))
+                .unwrap()
+                .as_utf8()
+                .len(),
+            Err(_) => {
+                let mut s = DynSynTripleSerializerFactory::new(None)
+                    .try_new_stringifier(syntax_)
+                    .unwrap();
+                let quads: Vec<_> = DatasetGenerator::new(config).map(Result::unwrap).collect();
+                let triples: Vec<_> = quads.into_iter().map(|(t, _)| t).collect();
+                s.serialize_triples(triples.iter().map(Ok::<_, std::convert::Infallible>))
+                    .unwrap()
+                    .as_utf8()
+                    .len()
+            }
+        };
+        assert_eq!(estimate, actual);
+    }
+
+    #[test]
+    pub fn estimating_for_unsupported_syntax_will_error() {
+        Lazy::force(&TRACING);
+        let r = estimate_size(
+            DatasetGenerator::new(DatasetGenConfig::default()),
+            syntax::JSON_LD,
+        );
+        assert!(matches!(r, Err(SizeEstimationError::UnKnownSyntax(_))));
+    }
+}
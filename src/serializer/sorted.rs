@@ -0,0 +1,171 @@
+//! This module defines configuration to sort statements in serialized [`n-triples`](crate::syntax::N_TRIPLES), and [`n-quads`](crate::syntax::N_QUADS) documents. Sorted output is byte-stable for same set of statements, whatever their order in source is, as long as blank node labels are stable. It suits reproducible builds of vocabularies, and test fixtures, that are checked into version control.
+//!
+//! Sorting is enabled by inserting a [`SortConfig`] into serializer config map of [`DynSynTripleSerializerFactory`](super::triples::DynSynTripleSerializerFactory), or [`DynSynQuadSerializerFactory`](super::quads::DynSynQuadSerializerFactory). Serializers of these line-based syntaxes then buffer serialized statements, and write them in lexicographic order of their lines. Duplicate statements are retained. It has no effect on other syntaxes. For output, that is also independent of blank node labels, see `canon` module with `canon` feature.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{
+//!     serializer::{sorted::SortConfig, triples::DynSynTripleSerializerFactory},
+//!     syntax,
+//! };
+//! use sophia_api::serializer::{Stringifier, TripleSerializer};
+//! use sophia_term::BoxTerm;
+//! use type_map::concurrent::TypeMap;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let p = BoxTerm::new_iri("http://example.org/p")?;
+//! let (a, b) = (BoxTerm::new_iri("http://example.org/a")?, BoxTerm::new_iri("http://example.org/b")?);
+//! let graph = vec![[b.clone(), p.clone(), a.clone()], [a, p, b]];
+//!
+//! let mut config_map = TypeMap::new();
+//! config_map.insert(SortConfig::new().with_sorted(true));
+//! let mut serializer = DynSynTripleSerializerFactory::new(Some(config_map))
+//!     .try_new_stringifier(syntax::N_TRIPLES)?;
+//! serializer.serialize_graph(&graph)?;
+//!
+//! assert_eq!(
+//!     serializer.as_str(),
+//!     "<http://example.org/a> <http://example.org/p> <http://example.org/b>.\n<http://example.org/b> <http://example.org/p> <http://example.org/a>.\n"
+//! );
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::io;
+
+/// Configuration to sort statements in serialized documents of line-based syntaxes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SortConfig {
+    /// If true, statements are written in lexicographic order of their lines.
+    pub sorted: bool,
+}
+
+impl SortConfig {
+    /// Create a new config, that doesn't sort statements.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether to sort statements.
+    pub fn with_sorted(mut self, sorted: bool) -> Self {
+        self.sorted = sorted;
+        self
+    }
+}
+
+/// Write lines of given line-based document to `write`, in lexicographic order.
+pub(crate) fn write_sorted_lines<W: io::Write>(write: &mut W, doc: &[u8]) -> io::Result<()> {
+    let mut lines: Vec<&[u8]> = doc.split_inclusive(|b| *b == b'\n').collect();
+    lines.sort_unstable();
+    for line in lines {
+        write.write_all(line)?;
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use sophia_api::{
+        parser::{QuadParser, TripleParser},
+        serializer::{QuadSerializer, Stringifier, TripleSerializer},
+    };
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+    use type_map::concurrent::TypeMap;
+
+    use super::SortConfig;
+    use crate::{
+        parser::{quads::DynSynQuadParserFactory, triples::DynSynTripleParserFactory},
+        serializer::{
+            escape::EscapeConfig, quads::DynSynQuadSerializerFactory,
+            triples::DynSynTripleSerializerFactory,
+        },
+        syntax,
+        tests::TRACING,
+    };
+
+    const NQ_DOC: &str = r#"<http://ex.org/s2> <http://ex.org/p> "é" <http://ex.org/g> .
+<http://ex.org/s1> <http://ex.org/p> <http://ex.org/o> .
+<http://ex.org/s1> <http://ex.org/p> "multi\nline" <http://ex.org/g> .
+<http://ex.org/s1> <http://ex.org/p> <http://ex.org/o> .
+"#;
+
+    fn config_map(sorted: bool) -> TypeMap {
+        let mut config_map = TypeMap::new();
+        config_map.insert(SortConfig::new().with_sorted(sorted));
+        config_map.insert(EscapeConfig::new().with_escape_non_ascii(true));
+        config_map
+    }
+
+    fn serialize_nq(doc: &str, sorted: bool) -> String {
+        let quads = DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::N_QUADS, None, None)
+            .unwrap()
+            .parse_str(doc);
+        let mut serializer = DynSynQuadSerializerFactory::new(Some(config_map(sorted)))
+            .try_new_stringifier(syntax::N_QUADS)
+            .unwrap();
+        serializer.serialize_quads(quads).unwrap();
+        serializer.as_str().to_owned()
+    }
+
+    fn serialize_nt(doc: &str, sorted: bool) -> String {
+        let triples = DynSynTripleParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::N_TRIPLES, None, None)
+            .unwrap()
+            .parse_str(doc);
+        let mut serializer = DynSynTripleSerializerFactory::new(Some(config_map(sorted)))
+            .try_new_stringifier(syntax::N_TRIPLES)
+            .unwrap();
+        serializer.serialize_triples(triples).unwrap();
+        serializer.as_str().to_owned()
+    }
+
+    fn reversed(doc: &str) -> String {
+        doc.lines().rev().map(|line| format!("{}\n", line)).collect()
+    }
+
+    #[test]
+    pub fn sorted_n_quads_are_byte_stable() {
+        Lazy::force(&TRACING);
+        let out = serialize_nq(NQ_DOC, true);
+        assert_eq!(out, serialize_nq(&reversed(NQ_DOC), true));
+
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(lines[1], lines[2]);
+        assert!(lines[3].starts_with("<http://ex.org/s2>"));
+    }
+
+    #[test]
+    pub fn sorted_n_triples_are_byte_stable() {
+        Lazy::force(&TRACING);
+        let doc: String = NQ_DOC.replace(" <http://ex.org/g> .", " .");
+        let out = serialize_nt(&doc, true);
+        assert_eq!(out, serialize_nt(&reversed(&doc), true));
+        assert!(out.starts_with("<http://ex.org/s1> <http://ex.org/p> \"multi\\nline\".\n"));
+    }
+
+    #[test_case(false)]
+    #[test_case(true)]
+    pub fn unsorted_output_retains_source_order(reverse: bool) {
+        Lazy::force(&TRACING);
+        let doc = match reverse {
+            true => reversed(NQ_DOC),
+            false => NQ_DOC.to_owned(),
+        };
+        let out = serialize_nq(&doc, false);
+        assert_eq!(
+            out.lines().next().unwrap().starts_with("<http://ex.org/s2>"),
+            !reverse
+        );
+    }
+}
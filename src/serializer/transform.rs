@@ -0,0 +1,231 @@
+//! This module provides [`TransformingSerializer`], that wraps a serializer, and rewrites each statement with a user-supplied transform before it is written. Transforms can rewrite iris, skolemize blank nodes, or drop statements, like those with certain predicates, without separate pipeline code.
+//!
+//! Transform takes each statement as an owned triple, or quad of [`BoxTerm`]s, and returns statement to be written, or `None` to drop it.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{
+//!     serializer::{quads::DynSynQuadSerializerFactory, transform::TransformingSerializer},
+//!     syntax,
+//! };
+//! use sophia_api::{
+//!     serializer::{QuadSerializer, Stringifier},
+//!     term::TTerm,
+//! };
+//! use sophia_term::{BoxTerm, StaticTerm};
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let me = StaticTerm::new_iri("http://example.org/#me")?;
+//! let secret = StaticTerm::new_iri("http://example.org/#secret")?;
+//! let dataset = vec![([me, me, me], None), ([me, secret, me], None)];
+//!
+//! let serializer = DynSynQuadSerializerFactory::default().try_new_stringifier(syntax::N_QUADS)?;
+//! let mut serializer = TransformingSerializer::new(
+//!     serializer,
+//!     |([s, p, o], g): ([BoxTerm; 3], Option<BoxTerm>)| {
+//!         let keep = p.value() != "http://example.org/#secret";
+//!         keep.then(|| ([s, p, o], g))
+//!     },
+//! );
+//! serializer.serialize_dataset(&dataset)?;
+//! assert_eq!(serializer.as_str().lines().count(), 1);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use sophia_api::{
+    quad::{stream::QuadSource, Quad},
+    serializer::{QuadSerializer, Stringifier, TripleSerializer},
+    term::CopyTerm,
+    triple::{
+        stream::{StreamResult, TripleSource},
+        Triple,
+    },
+};
+use sophia_term::BoxTerm;
+
+use crate::parser::{quads::source::TupleQuad, triples::source::SliceTriple};
+
+/// A serializer, that rewrites each statement with a transform, before it is written by underlying serializer.
+///
+/// It is a [`QuadSerializer`], if underlying serializer is a quad serializer, and transform is a `FnMut(TupleQuad<BoxTerm>) -> Option<TupleQuad<BoxTerm>>`. It is a [`TripleSerializer`], if underlying serializer is a triple serializer, and transform is a `FnMut([BoxTerm; 3]) -> Option<[BoxTerm; 3]>`. Statements, for which transform returns `None` are dropped.
+pub struct TransformingSerializer<S, F> {
+    inner: S,
+    transform: F,
+}
+
+impl<S, F> TransformingSerializer<S, F> {
+    /// Wrap given serializer, to rewrite statements with given transform.
+    pub fn new(inner: S, transform: F) -> Self {
+        Self { inner, transform }
+    }
+
+    /// Get underlying serializer.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get back underlying serializer.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S: std::fmt::Debug, F> std::fmt::Debug for TransformingSerializer<S, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TransformingSerializer")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<S, F> QuadSerializer for TransformingSerializer<S, F>
+where
+    S: QuadSerializer,
+    F: FnMut(TupleQuad<BoxTerm>) -> Option<TupleQuad<BoxTerm>>,
+{
+    type Error = S::Error;
+
+    fn serialize_quads<QS>(&mut self, source: QS) -> StreamResult<&mut Self, QS::Error, S::Error>
+    where
+        QS: QuadSource,
+        Self: Sized,
+    {
+        let transform = &mut self.transform;
+        self.inner.serialize_quads(source.filter_map_quads(|q| {
+            transform((
+                [
+                    BoxTerm::copy(q.s()),
+                    BoxTerm::copy(q.p()),
+                    BoxTerm::copy(q.o()),
+                ],
+                q.g().map(BoxTerm::copy),
+            ))
+        }))?;
+        Ok(self)
+    }
+}
+
+impl<S, F> TripleSerializer for TransformingSerializer<S, F>
+where
+    S: TripleSerializer,
+    F: FnMut(SliceTriple<BoxTerm>) -> Option<SliceTriple<BoxTerm>>,
+{
+    type Error = S::Error;
+
+    fn serialize_triples<TS>(&mut self, source: TS) -> StreamResult<&mut Self, TS::Error, S::Error>
+    where
+        TS: TripleSource,
+        Self: Sized,
+    {
+        let transform = &mut self.transform;
+        self.inner
+            .serialize_triples(source.filter_map_triples(|t| {
+                transform([
+                    BoxTerm::copy(t.s()),
+                    BoxTerm::copy(t.p()),
+                    BoxTerm::copy(t.o()),
+                ])
+            }))?;
+        Ok(self)
+    }
+}
+
+impl<S: Stringifier, F> Stringifier for TransformingSerializer<S, F> {
+    fn as_utf8(&self) -> &[u8] {
+        self.inner.as_utf8()
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use sophia_api::{
+        serializer::{QuadSerializer, Stringifier, TripleSerializer},
+        term::{TTerm, TermKind},
+    };
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+
+    use super::TransformingSerializer;
+    use crate::{
+        serializer::{quads::DynSynQuadSerializerFactory, triples::DynSynTripleSerializerFactory},
+        syntax::{self, RdfSyntax},
+        tests::TRACING,
+    };
+
+    fn iri(value: &str) -> BoxTerm {
+        BoxTerm::new_iri(value).unwrap()
+    }
+
+    /// Rewrite iris in `http://old.example/` namespace into `http://new.example/` namespace.
+    fn rewrite(t: BoxTerm) -> BoxTerm {
+        let rewritten = match t.value().strip_prefix("http://old.example/") {
+            Some(local) if t.kind() == TermKind::Iri => {
+                Some(iri(&format!("http://new.example/{}", local)))
+            }
+            _ => None,
+        };
+        rewritten.unwrap_or(t)
+    }
+
+    #[test_case(syntax::N_TRIPLES)]
+    #[test_case(syntax::TURTLE)]
+    pub fn rewrites_and_drops_triples(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let graph = vec![
+            [
+                iri("http://old.example/a"),
+                iri("http://old.example/p"),
+                iri("http://x.example/b"),
+            ],
+            [
+                iri("http://old.example/a"),
+                iri("http://x.example/drop"),
+                iri("http://x.example/b"),
+            ],
+        ];
+        let serializer = DynSynTripleSerializerFactory::default()
+            .try_new_stringifier(syntax_)
+            .unwrap();
+        let mut serializer = TransformingSerializer::new(serializer, |[s, p, o]: [BoxTerm; 3]| {
+            let keep = p.value() != "http://x.example/drop";
+            keep.then(|| [rewrite(s), rewrite(p), rewrite(o)])
+        });
+        serializer.serialize_graph(&graph).unwrap();
+        let out = serializer.as_str();
+
+        assert!(out.contains("http://new.example/a"));
+        assert!(out.contains("http://new.example/p"));
+        assert!(!out.contains("http://old.example/"));
+        assert!(!out.contains("http://x.example/drop"));
+    }
+
+    #[test]
+    pub fn rewrites_graph_names_of_quads() {
+        Lazy::force(&TRACING);
+        let dataset = vec![(
+            [
+                iri("http://x.example/a"),
+                iri("http://x.example/p"),
+                iri("http://x.example/b"),
+            ],
+            Some(iri("http://old.example/g")),
+        )];
+        let serializer = DynSynQuadSerializerFactory::default()
+            .try_new_stringifier(syntax::N_QUADS)
+            .unwrap();
+        let mut serializer =
+            TransformingSerializer::new(serializer, |(t, g): ([BoxTerm; 3], Option<BoxTerm>)| {
+                Some((t, g.map(rewrite)))
+            });
+        serializer.serialize_dataset(&dataset).unwrap();
+        assert!(serializer.as_str().contains("<http://new.example/g>"));
+    }
+}
@@ -1,25 +1,57 @@
-use std::io;
+use std::{io, path::Path, sync::Arc};
 
-use sophia_api::serializer::{Stringifier, TripleSerializer};
+use mime::Mime;
+use sophia_api::{
+    graph::Graph,
+    quad::{stream::QuadSource, Quad},
+    serializer::{Stringifier, TripleSerializer},
+    term::{CopyTerm, TTerm},
+    triple::{
+        stream::{StreamError, StreamResult, TripleSource},
+        Triple,
+    },
+};
+use sophia_term::BoxTerm;
 use sophia_turtle::serializer::{
     nt::{NtConfig, NtSerializer},
     turtle::{TurtleConfig, TurtleSerializer},
 };
+#[cfg(feature = "rdf-xml")]
+#[cfg(feature = "rdf-xml")]
 use sophia_xml::serializer::{RdfXmlConfig, RdfXmlSerializer};
 use type_map::concurrent::TypeMap;
 
 use crate::{
+    correspondence::{syntax_for_path, ForPathError},
+    parser::graph_selector::GraphSelector,
     syntax::UnKnownSyntaxError,
     syntax::{self, RdfSyntax},
 };
 
-use super::_inner::InnerTripleSerializer;
+#[cfg(any(test, feature = "compression"))]
+use crate::compression::CompressionConfig;
+
+use super::{
+    _inner::{compressing::CompressingWrite, counted::Counted, InnerTripleSerializer},
+    any::{BoxedTripleSerializer, BoxedWrite},
+    bnode_cycle::{buffer_triples, find_cycle, sink_only, BlankNodeCycleConfig},
+    config::{DynSynSerializerConfig, LayeredConfigMap},
+    errors::{NewSerializerError, SerializerRole},
+    content_type::ContentTypeConfig,
+    escape::{AsciiNtSerializer, EscapeConfig, EscapingWrite},
+    pretty::{DynSynPrettySerializer, PrettyConfig},
+    nd_rdf_json::NdRdfJsonSerializer,
+    sorted::{write_sorted_lines, SortConfig},
+};
+
+#[cfg(feature = "trix")]
+use super::trix::TrixSerializer;
 
 /// A [`TripleSerializer`], that can be instantiated at run time against any of supported rdf-syntaxes. We can get it's tuned instance from [`DynSynTripleSerializerFactory::try_new_serializer`] factory method.
 ///
-/// It can currently serialize triple-sources/graphs into documents in any of concrete_syntaxes: [`turtle`](syntax::TURTLE), [`n-triples`](syntax::N_TRIPLES), [rdf-xml](syntax::RDF_XML). Other syntaxes that can represent quads are not supported. We can just get virtual quad-source from a graph serialize as quads in such case.
+/// It can currently serialize triple-sources/graphs into documents in any of concrete_syntaxes: [`turtle`](syntax::TURTLE), [`n-triples`](syntax::N_TRIPLES), [rdf-xml](syntax::RDF_XML), [`trix`](syntax::TRIX) with `trix` feature, and non-standard debug syntax [`nd-rdf-json`](syntax::ND_RDF_JSON). Triples are written into default graph in `trix`. Other syntaxes that can represent quads are not supported. We can just get virtual quad-source from a graph serialize as quads in such case.
 ///
-/// For each supported serialization syntax, it also supports corresponding formatting options that sophia supports.
+/// For each supported serialization syntax, it also supports corresponding formatting options that sophia supports, and unified pretty-printing options configured by [`PrettyConfig`], and syntax-agnostic options like prefix map configured by [`DynSynSerializerConfig`]. It can also escape non-ascii characters in any of them, as configured by [`EscapeConfig`]. It reports [`content_type`](Self::content_type) of documents it produces, with media type parameters configured by [`ContentTypeConfig`].
 ///
 /// Example:
 ///
@@ -74,27 +106,123 @@ use super::_inner::InnerTripleSerializer;
 /// let rdf_xml_doc = rdf_xml_serializer.as_str();
 /// # Ok(())
 /// # }
-/// # fn main() {try_main().unwrap();}
+/// # fn main() {
+/// #     #[cfg(feature = "rdf-xml")]
+/// #     try_main().unwrap();
+/// # }
 ///```
 ///
 #[derive(Debug)]
 pub struct DynSynTripleSerializer<W: io::Write> {
-    inner_serializer: InnerTripleSerializer<W>,
+    inner_serializer: InnerTripleSerializer,
+    write: EscapingWrite<CompressingWrite<W>>,
+    content_type: Mime,
+    escape_non_ascii: bool,
+    pretty_config: PrettyConfig,
+    bnode_cycle_config: Option<BlankNodeCycleConfig>,
+    sort_config: SortConfig,
 }
 
 impl<W: io::Write> DynSynTripleSerializer<W> {
-    pub(crate) fn new(inner_serializer: InnerTripleSerializer<W>) -> Self {
-        Self { inner_serializer }
+    pub(crate) fn new(
+        inner_serializer: InnerTripleSerializer,
+        write: W,
+        escape_config: EscapeConfig,
+        content_type: Mime,
+    ) -> Self {
+        let style = escape_config
+            .escape_non_ascii
+            .then(|| inner_serializer.escape_style())
+            .flatten();
+        Self {
+            inner_serializer,
+            write: EscapingWrite::new(CompressingWrite::new(write), style),
+            content_type,
+            escape_non_ascii: escape_config.escape_non_ascii,
+            pretty_config: PrettyConfig::default(),
+            bnode_cycle_config: None,
+            sort_config: SortConfig::default(),
+        }
+    }
+
+    pub(crate) fn with_bnode_cycle_config(
+        mut self,
+        bnode_cycle_config: Option<BlankNodeCycleConfig>,
+    ) -> Self {
+        self.bnode_cycle_config = bnode_cycle_config;
+        self
+    }
+
+    pub(crate) fn with_pretty_config(mut self, pretty_config: PrettyConfig) -> Self {
+        self.pretty_config = pretty_config;
+        self
+    }
+
+    pub(crate) fn with_sort_config(mut self, sort_config: SortConfig) -> Self {
+        self.sort_config = sort_config;
+        self
+    }
+
+    #[cfg(any(test, feature = "compression"))]
+    pub(crate) fn with_compression_config(mut self, compression_config: CompressionConfig) -> Self {
+        self.write.get_mut().set_config(compression_config);
+        self
+    }
+
+    /// Returns content type of documents produced by this serializer, with media type parameters configured by [`ContentTypeConfig`].
+    pub fn content_type(&self) -> &Mime {
+        &self.content_type
+    }
+
+    /// Serializes all triples from `source`, like [`serialize_triples`](TripleSerializer::serialize_triples), and also returns number of triples serialized. It allows callers to log, and validate output without a second pass over the source.
+    pub fn serialize_triples_counted<TS>(
+        &mut self,
+        mut source: TS,
+    ) -> StreamResult<(&mut Self, usize), TS::Error, io::Error>
+    where
+        TS: TripleSource,
+    {
+        let mut count = 0;
+        self.serialize_triples(Counted::new(&mut source, &mut count))?;
+        Ok((self, count))
+    }
+
+    /// Serializes given `graph`, like [`serialize_graph`](TripleSerializer::serialize_graph), and also returns number of triples serialized.
+    pub fn serialize_graph_counted<G>(
+        &mut self,
+        graph: &G,
+    ) -> StreamResult<(&mut Self, usize), G::Error, io::Error>
+    where
+        G: Graph,
+    {
+        self.serialize_triples_counted(graph.triples())
+    }
+
+    /// Serializes triples of graphs of quad `source`, that are selected by `selector`. Quads are projected into triples on the fly, like by triple parsers for quad syntaxes, without collecting the dataset into memory. Triples, that are in more than one selected graph, are written once per graph.
+    pub fn serialize_quads_from_graph<QS, G>(
+        &mut self,
+        source: QS,
+        selector: &GraphSelector<G>,
+    ) -> StreamResult<&mut Self, QS::Error, io::Error>
+    where
+        QS: QuadSource,
+        G: TTerm,
+    {
+        self.serialize_triples(source.filter_map_quads(|q| {
+            selector.selects(q.g(), None).then(|| {
+                [
+                    BoxTerm::copy(q.s()),
+                    BoxTerm::copy(q.p()),
+                    BoxTerm::copy(q.o()),
+                ]
+            })
+        }))
     }
 }
 
 impl Stringifier for DynSynTripleSerializer<Vec<u8>> {
     fn as_utf8(&self) -> &[u8] {
-        match &self.inner_serializer {
-            InnerTripleSerializer::NTriples(s) => s.as_utf8(),
-            InnerTripleSerializer::Turtle(s) => s.as_utf8(),
-            InnerTripleSerializer::RdfXml(s) => s.as_utf8(),
-        }
+        self.write.get_ref().get_ref().map_or(&[], |w| &w[..])
     }
 }
 
@@ -109,36 +237,103 @@ impl<W: io::Write> TripleSerializer for DynSynTripleSerializer<W> {
         TS: sophia_api::triple::stream::TripleSource,
         Self: Sized,
     {
-        match &mut self.inner_serializer {
-            InnerTripleSerializer::NTriples(s) => match s.serialize_triples(source) {
-                Ok(_) => Ok(self),
-                Err(e) => Err(e),
-            },
-            InnerTripleSerializer::Turtle(s) => match s.serialize_triples(source) {
-                Ok(_) => Ok(self),
-                Err(e) => Err(e),
-            },
-            InnerTripleSerializer::RdfXml(s) => match s.serialize_triples(source) {
-                Ok(_) => Ok(self),
-                Err(e) => Err(e),
+        self.write
+            .get_mut()
+            .start_frame()
+            .map_err(StreamError::SinkError)?;
+        let write = &mut self.write;
+        let result = match &self.inner_serializer {
+            InnerTripleSerializer::NTriples(c) if self.sort_config.sorted => {
+                let mut doc = Vec::new();
+                match self.escape_non_ascii {
+                    true => AsciiNtSerializer::new(&mut doc)
+                        .serialize_triples(source)
+                        .map(|_| ())?,
+                    false => NtSerializer::new_with_config(&mut doc, c.clone())
+                        .serialize_triples(source)
+                        .map(|_| ())?,
+                }
+                write_sorted_lines(write, &doc).map_err(StreamError::SinkError)
+            }
+            InnerTripleSerializer::NTriples(_) if self.escape_non_ascii => {
+                AsciiNtSerializer::new(write)
+                    .serialize_triples(source)
+                    .map(|_| ())
+            }
+            InnerTripleSerializer::NTriples(c) => {
+                NtSerializer::new_with_config(write, c.clone())
+                    .serialize_triples(source)
+                    .map(|_| ())
+            }
+            InnerTripleSerializer::Turtle(c) => match self.bnode_cycle_config.filter(|_| c.pretty()) {
+                None if c.pretty() && self.pretty_config.needs_dynsyn_prettifier() => {
+                    DynSynPrettySerializer::new(write, c.clone(), self.pretty_config.clone())
+                        .serialize_triples(source)
+                        .map(|_| ())
+                }
+                None => TurtleSerializer::new_with_config(write, c.clone())
+                    .serialize_triples(source)
+                    .map(|_| ()),
+                Some(cycle_config) => {
+                    let triples = buffer_triples(source)?;
+                    let config = cycle_config
+                        .resolve(c, find_cycle(triples.iter().map(|t| (t.s(), t.o()))))
+                        .map_err(|e| StreamError::SinkError(e.into()))?;
+                    let triples = triples.into_iter().map(Ok);
+                    match config.pretty() && self.pretty_config.needs_dynsyn_prettifier() {
+                        true => {
+                            DynSynPrettySerializer::new(write, config, self.pretty_config.clone())
+                                .serialize_triples(triples)
+                                .map(|_| ())
+                        }
+                        false => TurtleSerializer::new_with_config(write, config)
+                            .serialize_triples(triples)
+                            .map(|_| ()),
+                    }
+                    .map_err(sink_only)
+                }
             },
+            #[cfg(feature = "rdf-xml")]
+            InnerTripleSerializer::RdfXml(c) => {
+                RdfXmlSerializer::new_with_config(write, c.clone())
+                    .serialize_triples(source)
+                    .map(|_| ())
+            }
+            #[cfg(feature = "trix")]
+            InnerTripleSerializer::Trix => TrixSerializer::new(write)
+                .with_escape_non_ascii(self.escape_non_ascii)
+                .serialize_triples(source)
+                .map(|_| ()),
+            InnerTripleSerializer::NdRdfJson => NdRdfJsonSerializer::new(write)
+                .with_escape_non_ascii(self.escape_non_ascii)
+                .serialize_triples(source)
+                .map(|_| ()),
+        };
+        match result {
+            Ok(_) => {
+                self.write
+                    .get_mut()
+                    .finish_frame()
+                    .map_err(StreamError::SinkError)?;
+                Ok(self)
+            }
+            Err(e) => Err(e),
         }
     }
 }
 
 /// A factory to instantiate [`DynSynTripleSerializer`].
+///
+/// Factory is `Send + Sync`, and keeps its config map behind an [`Arc`]. Hence it is cheap to clone, and clones can be shared across threads, like in application state of web servers.
+#[derive(Debug, Clone)]
 pub struct DynSynTripleSerializerFactory {
-    serializer_config_map: TypeMap,
+    serializer_config_map: Arc<TypeMap>,
 }
 
 impl DynSynTripleSerializerFactory {
-    /// Instantiate a factory. It takes a `serializer_config_map`, an optional [`TypeMap`], which can be populated with configuration structures corresponding to supported syntaxes.
+    /// Instantiate a factory. It takes a `serializer_config_map`, an optional [`TypeMap`], which can be populated with configuration structures corresponding to supported syntaxes, with a [`BlankNodeCycleConfig`], with a [`SortConfig`], and with a `CompressionConfig` from `compression` module, when `compression` feature is enabled.
     pub fn new(serializer_config_map: Option<TypeMap>) -> Self {
-        let serializer_config_map = if let Some(v) = serializer_config_map {
-            v
-        } else {
-            TypeMap::new()
-        };
+        let serializer_config_map = Arc::new(serializer_config_map.unwrap_or_default());
         Self {
             serializer_config_map,
         }
@@ -151,67 +346,203 @@ impl DynSynTripleSerializerFactory {
             .unwrap_or_default()
     }
 
+    /// Get turtle config, with unified pretty-printing options, and syntax-agnostic options applied, if they are configured.
+    fn turtle_config(configs: &LayeredConfigMap) -> TurtleConfig {
+        let config = configs.get_config::<TurtleConfig>();
+        let config = match configs.get::<PrettyConfig>() {
+            Some(pretty_config) => pretty_config.apply_to_turtle(config),
+            None => config,
+        };
+        match configs.get::<DynSynSerializerConfig>() {
+            Some(dynsyn_config) => dynsyn_config.apply_to_turtle(config),
+            None => config,
+        }
+    }
+
     /// Try to create new [`DynSynTripleSerializer`] instance, for given `syntax_`, `write`,
     ///
     /// # Errors
-    /// returns [`UnKnownSyntaxError`] if requested syntax is not known/supported.
+    /// returns [`NewSerializerError`] if requested syntax is not known/supported. It will be a [`SyntaxRoleMismatch`](super::errors::SyntaxRoleMismatch), if syntax is supported only by serializers for other kind of statements.
     pub fn try_new_serializer<W: io::Write>(
         &self,
         syntax_: RdfSyntax,
         write: W,
-    ) -> Result<DynSynTripleSerializer<W>, UnKnownSyntaxError> {
-        match syntax_ {
-            syntax::N_TRIPLES => Ok(DynSynTripleSerializer::new(
-                InnerTripleSerializer::NTriples(NtSerializer::new_with_config(
-                    write,
-                    self.get_config::<NtConfig>(),
-                )),
-            )),
-            syntax::TURTLE => Ok(DynSynTripleSerializer::new(InnerTripleSerializer::Turtle(
-                TurtleSerializer::new_with_config(write, self.get_config::<TurtleConfig>()),
-            ))),
-            syntax::RDF_XML => Ok(DynSynTripleSerializer::new(InnerTripleSerializer::RdfXml(
-                RdfXmlSerializer::new_with_config(write, self.get_config::<RdfXmlConfig>()),
-            ))),
-            _ => Err(UnKnownSyntaxError(syntax_)),
+    ) -> Result<DynSynTripleSerializer<W>, NewSerializerError> {
+        self.try_new_serializer_in(syntax_, write, None)
+    }
+
+    /// Try to create new [`DynSynTripleSerializer`] instance, for given `syntax_`, `write`, with configs in given `config` map overriding those of same type in factory's config map. Configs of other types are still taken from factory's config map. It allows honouring per-request formatting preferences, without building a new factory for each request.
+    ///
+    /// # Errors
+    /// returns [`NewSerializerError`] if requested syntax is not known/supported. It will be a [`SyntaxRoleMismatch`](super::errors::SyntaxRoleMismatch), if syntax is supported only by serializers for other kind of statements.
+    pub fn try_new_serializer_with_config<W: io::Write>(
+        &self,
+        syntax_: RdfSyntax,
+        write: W,
+        config: TypeMap,
+    ) -> Result<DynSynTripleSerializer<W>, NewSerializerError> {
+        self.try_new_serializer_in(syntax_, write, Some(&config))
+    }
+
+    fn try_new_serializer_in<W: io::Write>(
+        &self,
+        syntax_: RdfSyntax,
+        write: W,
+        overrides: Option<&TypeMap>,
+    ) -> Result<DynSynTripleSerializer<W>, NewSerializerError> {
+        let configs = LayeredConfigMap::new(&self.serializer_config_map, overrides);
+        let escape_config = configs.get_config::<EscapeConfig>();
+        let inner_serializer = match syntax_ {
+            syntax::N_TRIPLES => {
+                let mut config = configs.get_config::<NtConfig>();
+                // Sophia's ascii mode is not implemented, and serializing with it panics. We escape by ourselves instead, as per `EscapeConfig`.
+                config.set_ascii(false);
+                InnerTripleSerializer::NTriples(config)
+            }
+            syntax::TURTLE => InnerTripleSerializer::Turtle(Self::turtle_config(&configs)),
+            #[cfg(feature = "rdf-xml")]
+            syntax::RDF_XML => InnerTripleSerializer::RdfXml(configs.get_config::<RdfXmlConfig>()),
+            #[cfg(feature = "trix")]
+            syntax::TRIX => InnerTripleSerializer::Trix,
+            syntax::ND_RDF_JSON => InnerTripleSerializer::NdRdfJson,
+            _ => return Err(NewSerializerError::unsupported(syntax_, SerializerRole::Triples)),
+        };
+        let serializer = DynSynTripleSerializer::new(
+            inner_serializer,
+            write,
+            escape_config,
+            Self::content_type_in(&configs, syntax_)?,
+        )
+        .with_pretty_config(configs.get_config::<PrettyConfig>())
+        .with_bnode_cycle_config(configs.get::<BlankNodeCycleConfig>().cloned())
+        .with_sort_config(configs.get_config::<SortConfig>());
+        #[cfg(any(test, feature = "compression"))]
+        let serializer =
+            serializer.with_compression_config(configs.get_config::<CompressionConfig>());
+        Ok(serializer)
+    }
+
+    /// Try to create new [`DynSynTripleSerializer`] instance with erased writer type, for given `syntax_`, writing to `write`. It allows storing serializers for different writers in same collection, without being generic over writer type. See [`AnySerializer`](super::any::AnySerializer) for a serializer, that can also be of either role.
+    ///
+    /// # Errors
+    /// returns [`NewSerializerError`] if requested syntax is not known/supported. It will be a [`SyntaxRoleMismatch`](super::errors::SyntaxRoleMismatch), if syntax is supported only by serializers for other kind of statements.
+    pub fn try_new_boxed_serializer(
+        &self,
+        syntax_: RdfSyntax,
+        write: BoxedWrite,
+    ) -> Result<BoxedTripleSerializer, NewSerializerError> {
+        self.try_new_serializer(syntax_, write)
+    }
+
+    /// Try to create new [`DynSynTripleSerializer`] instance, for syntax corresponding to file extension of given `path`, writing to `write`. File extension is matched case-insensitively.
+    ///
+    /// # Errors
+    /// returns [`ForPathError`] if path has no file extension, or if it doesn't correspond to any rdf syntax, or if corresponding syntax is not supported.
+    pub fn try_new_serializer_for_path<W: io::Write>(
+        &self,
+        path: &Path,
+        write: W,
+    ) -> Result<DynSynTripleSerializer<W>, ForPathError> {
+        Ok(self.try_new_serializer(syntax_for_path(path)?, write)?)
+    }
+
+    /// Get content type of documents, that serializers from this factory produce for given `syntax_`, with media type parameters configured by [`ContentTypeConfig`] attached. It allows http layers to set `Content-Type` header before serializing.
+    ///
+    /// # Errors
+    /// returns [`UnKnownSyntaxError`] if requested syntax is not known/supported.
+    pub fn content_type(&self, syntax_: RdfSyntax) -> Result<Mime, UnKnownSyntaxError> {
+        Self::content_type_in(
+            &LayeredConfigMap::new(&self.serializer_config_map, None),
+            syntax_,
+        )
+    }
+
+    fn content_type_in(
+        configs: &LayeredConfigMap,
+        syntax_: RdfSyntax,
+    ) -> Result<Mime, UnKnownSyntaxError> {
+        let supported = matches!(syntax_, syntax::N_TRIPLES | syntax::TURTLE | syntax::ND_RDF_JSON)
+            || (cfg!(feature = "rdf-xml") && syntax_ == syntax::RDF_XML)
+            || (cfg!(feature = "trix") && syntax_ == syntax::TRIX);
+        if !supported {
+            return Err(UnKnownSyntaxError(syntax_));
         }
+        configs
+            .get_config::<ContentTypeConfig>()
+            .content_type(syntax_)
+            .ok_or(UnKnownSyntaxError(syntax_))
     }
 
     /// Try to create new [`DynSynTripleSerializer`] instance, that can be stringified after serialization, for given `syntax_`.
     ///
     /// # Errors
-    /// returns [`UnKnownSyntaxError`] if requested syntax is not known/supported.
+    /// returns [`NewSerializerError`] if requested syntax is not known/supported. It will be a [`SyntaxRoleMismatch`](super::errors::SyntaxRoleMismatch), if syntax is supported only by serializers for other kind of statements.
     pub fn try_new_stringifier(
         &self,
         syntax_: RdfSyntax,
-    ) -> Result<DynSynTripleSerializer<Vec<u8>>, UnKnownSyntaxError> {
-        self.try_new_serializer(syntax_, Vec::new())
+    ) -> Result<DynSynTripleSerializer<Vec<u8>>, NewSerializerError> {
+        self.try_new_stringifier_in(syntax_, None)
+    }
+
+    /// Try to create new [`DynSynTripleSerializer`] instance, that can be stringified after serialization, for given `syntax_`, with configs in given `config` map overriding those of same type in factory's config map. See [`try_new_serializer_with_config`](Self::try_new_serializer_with_config).
+    ///
+    /// # Errors
+    /// returns [`NewSerializerError`] if requested syntax is not known/supported. It will be a [`SyntaxRoleMismatch`](super::errors::SyntaxRoleMismatch), if syntax is supported only by serializers for other kind of statements.
+    pub fn try_new_stringifier_with_config(
+        &self,
+        syntax_: RdfSyntax,
+        config: TypeMap,
+    ) -> Result<DynSynTripleSerializer<Vec<u8>>, NewSerializerError> {
+        self.try_new_stringifier_in(syntax_, Some(&config))
+    }
+
+    fn try_new_stringifier_in(
+        &self,
+        syntax_: RdfSyntax,
+        overrides: Option<&TypeMap>,
+    ) -> Result<DynSynTripleSerializer<Vec<u8>>, NewSerializerError> {
+        let serializer = self.try_new_serializer_in(syntax_, Vec::new(), overrides)?;
+        // Stringified documents are never compressed.
+        #[cfg(any(test, feature = "compression"))]
+        let serializer = serializer.with_compression_config(CompressionConfig::default());
+        Ok(serializer)
+    }
+}
+
+impl Default for DynSynTripleSerializerFactory {
+    fn default() -> Self {
+        Self::new(None)
     }
 }
 
-/// ---------------------------------------------------------------------------------
-///                                      tests
-/// ---------------------------------------------------------------------------------
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
 #[cfg(test)]
 mod tests {
     use claim::{assert_err, assert_ok};
     use once_cell::sync::Lazy;
     use sophia_api::{
         graph::{isomorphic_graphs, Graph},
-        parser::TripleParser,
+        parser::{QuadParser, TripleParser},
         serializer::{Stringifier, TripleSerializer},
         triple::stream::TripleSource,
     };
     use sophia_inmem::graph::FastGraph;
     use sophia_term::BoxTerm;
     use sophia_turtle::serializer::{nt::NtConfig, turtle::TurtleConfig};
+    #[cfg(feature = "rdf-xml")]
     use sophia_xml::serializer::RdfXmlConfig;
     use test_case::test_case;
     use type_map::concurrent::TypeMap;
 
     use crate::{
-        parser::triples::DynSynTripleParserFactory,
-        serializer::test_data::{TESTS_NTRIPLES, TESTS_RDF_XML, TESTS_TURTLE},
+        parser::{
+            graph_selector::GraphSelector, quads::DynSynQuadParserFactory,
+            triples::DynSynTripleParserFactory,
+        },
+        serializer::{config::DynSynSerializerConfig, escape::EscapeConfig, pretty::PrettyConfig},
+        fixtures::{TESTS_NTRIPLES, TESTS_TURTLE},
         syntax::{self, RdfSyntax},
         tests::TRACING,
     };
@@ -226,6 +557,7 @@ mod tests {
             let mut config_map = TypeMap::new();
             config_map.insert::<TurtleConfig>(TurtleConfig::new().with_pretty(true));
             config_map.insert::<NtConfig>(NtConfig::default());
+            #[cfg(feature = "rdf-xml")]
             config_map.insert::<RdfXmlConfig>(RdfXmlConfig::default());
 
             DynSynTripleSerializerFactory::new(Some(config_map))
@@ -233,7 +565,7 @@ mod tests {
 
     /// As DynSyn parsers can be non-cyclically tested, we can use them here.
     static TRIPLE_PARSER_FACTORY: Lazy<DynSynTripleParserFactory> =
-        Lazy::new(|| DynSynTripleParserFactory::default());
+        Lazy::new(DynSynTripleParserFactory::default);
 
     #[test_case(syntax::JSON_LD)]
     #[test_case(syntax::HTML_RDFA)]
@@ -241,6 +573,7 @@ mod tests {
     #[test_case(syntax::N3)]
     #[test_case(syntax::OWL2_XML)]
     #[test_case(syntax::TRIG)]
+    #[cfg_attr(not(feature = "trix"), test_case(syntax::TRIX))]
     #[test_case(syntax::XHTML_RDFA)]
     pub fn creating_parser_for_un_supported_syntax_will_error(syntax_: RdfSyntax) {
         Lazy::force(&TRACING);
@@ -248,7 +581,9 @@ mod tests {
     }
 
     #[test_case(syntax::N_TRIPLES)]
-    #[test_case(syntax::RDF_XML)]
+    #[test_case(syntax::ND_RDF_JSON)]
+    #[cfg_attr(feature = "rdf-xml", test_case(syntax::RDF_XML))]
+    #[cfg_attr(feature = "trix", test_case(syntax::TRIX))]
     #[test_case(syntax::TURTLE)]
     pub fn creating_parser_for_supported_syntax_will_succeed(syntax_: RdfSyntax) {
         Lazy::force(&TRACING);
@@ -269,8 +604,14 @@ mod tests {
     #[test_case(syntax::TURTLE, TESTS_TURTLE[5], true)]
     #[test_case(syntax::N_TRIPLES, TESTS_NTRIPLES[0], false)]
     #[test_case(syntax::N_TRIPLES, TESTS_NTRIPLES[0], true)]
-    #[test_case(syntax::RDF_XML, TESTS_RDF_XML[0], false)]
-    #[test_case(syntax::RDF_XML, TESTS_RDF_XML[0], true)]
+    #[cfg_attr(
+        feature = "rdf-xml",
+        test_case(syntax::RDF_XML, crate::fixtures::TESTS_RDF_XML[0], false)
+    )]
+    #[cfg_attr(
+        feature = "rdf-xml",
+        test_case(syntax::RDF_XML, crate::fixtures::TESTS_RDF_XML[0], true)
+    )]
     pub fn correctly_roundtrips_for_syntax(syntax_: RdfSyntax, rdf_doc: &str, pretty: bool) {
         Lazy::force(&TRACING);
         let parser = TRIPLE_PARSER_FACTORY
@@ -293,4 +634,281 @@ mod tests {
         let g2: FastGraph = parser.parse_str(&out).collect_triples().unwrap();
         assert!(isomorphic_graphs(&g1, &g2).unwrap());
     }
+    #[test_case(syntax::N_TRIPLES, false)]
+    #[test_case(syntax::TURTLE, false)]
+    #[test_case(syntax::TURTLE, true)]
+    #[cfg_attr(feature = "rdf-xml", test_case(syntax::RDF_XML, false))]
+    #[test_case(syntax::ND_RDF_JSON, false)]
+    pub fn counts_serialized_triples(syntax_: RdfSyntax, pretty: bool) {
+        Lazy::force(&TRACING);
+        let g: FastGraph = TRIPLE_PARSER_FACTORY
+            .try_new_parser(syntax::TURTLE, None, None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(TESTS_TURTLE[1])
+            .collect_triples()
+            .unwrap();
+        let expected = g.triples().count();
+
+        let factory = if pretty {
+            &SERIALIZER_FACTORY_WITH_PRETTY_CONFIG
+        } else {
+            &SERIALIZER_FACTORY
+        };
+        let mut stringifier = factory.try_new_stringifier(syntax_).unwrap();
+        let (stringifier, count) = stringifier.serialize_graph_counted(&g).unwrap();
+        assert!(expected > 0);
+        assert_eq!(count, expected);
+        assert!(!stringifier.as_str().is_empty());
+    }
+
+    static NON_ASCII_TURTLE_DOC: &str = r#"
+        @prefix ex: <http://example.org/> .
+        <http://example.org/café> ex:name "Café 😀"@fr ; ex:label "naïve \"q\"" .
+        <http://example.org/é> ex:p "ü" .
+    "#;
+
+    #[test_case(syntax::N_TRIPLES, false)]
+    #[test_case(syntax::TURTLE, false)]
+    #[test_case(syntax::TURTLE, true)]
+    #[cfg_attr(feature = "rdf-xml", test_case(syntax::RDF_XML, false))]
+    pub fn escapes_non_ascii_when_configured(syntax_: RdfSyntax, pretty: bool) {
+        Lazy::force(&TRACING);
+        let g1: FastGraph = TRIPLE_PARSER_FACTORY
+            .try_new_parser(syntax::TURTLE, None, None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(NON_ASCII_TURTLE_DOC)
+            .collect_triples()
+            .unwrap();
+
+        let mut config_map = TypeMap::new();
+        config_map.insert::<EscapeConfig>(EscapeConfig::new().with_escape_non_ascii(true));
+        config_map.insert::<TurtleConfig>(TurtleConfig::new().with_pretty(pretty));
+        let factory = DynSynTripleSerializerFactory::new(Some(config_map));
+
+        let out = factory
+            .try_new_stringifier(syntax_)
+            .unwrap()
+            .serialize_triples(g1.triples())
+            .unwrap()
+            .to_string();
+        assert!(out.is_ascii(), "{}", out);
+
+        let g2: FastGraph = TRIPLE_PARSER_FACTORY
+            .try_new_parser(syntax_, None, None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(&out)
+            .collect_triples()
+            .unwrap();
+        assert!(isomorphic_graphs(&g1, &g2).unwrap());
+    }
+
+    #[test]
+    pub fn escapes_non_ascii_in_nd_rdf_json_strings() {
+        Lazy::force(&TRACING);
+        let g: FastGraph = TRIPLE_PARSER_FACTORY
+            .try_new_parser(syntax::TURTLE, None, None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(NON_ASCII_TURTLE_DOC)
+            .collect_triples()
+            .unwrap();
+        let mut config_map = TypeMap::new();
+        config_map.insert::<EscapeConfig>(EscapeConfig::new().with_escape_non_ascii(true));
+        let out = DynSynTripleSerializerFactory::new(Some(config_map))
+            .try_new_stringifier(syntax::ND_RDF_JSON)
+            .unwrap()
+            .serialize_triples(g.triples())
+            .unwrap()
+            .to_string();
+        assert!(out.is_ascii());
+        let values: Vec<String> = out
+            .lines()
+            .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap())
+            .map(|v| v["o"]["value"].as_str().unwrap().to_owned())
+            .collect();
+        assert!(values.iter().any(|v| v == "Café 😀"));
+    }
+
+    #[test_case(syntax::TURTLE)]
+    #[test_case(syntax::N_TRIPLES)]
+    #[cfg_attr(feature = "rdf-xml", test_case(syntax::RDF_XML))]
+    pub fn applies_unified_pretty_options_where_supported(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let parser = TRIPLE_PARSER_FACTORY
+            .try_new_parser(syntax::TURTLE, None, None as Option<BoxTerm>)
+            .unwrap();
+        let g1: FastGraph = parser.parse_str(TESTS_TURTLE[1]).collect_triples().unwrap();
+
+        let mut config_map = TypeMap::new();
+        config_map.insert::<TurtleConfig>(TurtleConfig::new().with_pretty(false));
+        config_map.insert::<PrettyConfig>(
+            PrettyConfig::new()
+                .with_pretty(true)
+                .with_indent("\t")
+                .unwrap()
+                .with_max_line_width(Some(80))
+                .with_align_objects(true),
+        );
+        let out = DynSynTripleSerializerFactory::new(Some(config_map))
+            .try_new_stringifier(syntax_)
+            .unwrap()
+            .serialize_triples(g1.triples())
+            .unwrap()
+            .to_string();
+        assert_eq!(syntax_ == syntax::TURTLE, out.contains("\n\t"));
+
+        let g2: FastGraph = TRIPLE_PARSER_FACTORY
+            .try_new_parser(syntax_, None, None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(&out)
+            .collect_triples()
+            .unwrap();
+        assert!(isomorphic_graphs(&g1, &g2).unwrap());
+    }
+
+    #[test_case(syntax::TURTLE)]
+    #[test_case(syntax::N_TRIPLES)]
+    #[cfg_attr(feature = "rdf-xml", test_case(syntax::RDF_XML))]
+    pub fn applies_syntax_agnostic_pretty_option_where_supported(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let parser = TRIPLE_PARSER_FACTORY
+            .try_new_parser(syntax::TURTLE, None, None as Option<BoxTerm>)
+            .unwrap();
+        let g1: FastGraph = parser.parse_str(TESTS_TURTLE[1]).collect_triples().unwrap();
+
+        let mut config_map = TypeMap::new();
+        config_map.insert::<PrettyConfig>(
+            PrettyConfig::new()
+                .with_pretty(false)
+                .with_indent("\t")
+                .unwrap(),
+        );
+        config_map.insert(DynSynSerializerConfig::new().with_pretty(true));
+        let out = DynSynTripleSerializerFactory::new(Some(config_map))
+            .try_new_stringifier(syntax_)
+            .unwrap()
+            .serialize_triples(g1.triples())
+            .unwrap()
+            .to_string();
+        assert_eq!(syntax_ == syntax::TURTLE, out.contains("\n\t"));
+
+        let g2: FastGraph = TRIPLE_PARSER_FACTORY
+            .try_new_parser(syntax_, None, None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(&out)
+            .collect_triples()
+            .unwrap();
+        assert!(isomorphic_graphs(&g1, &g2).unwrap());
+    }
+
+    #[test_case(true)]
+    #[test_case(false)]
+    pub fn toggles_rendering_of_collections(render_collections: bool) {
+        Lazy::force(&TRACING);
+        let parser = TRIPLE_PARSER_FACTORY
+            .try_new_parser(syntax::TURTLE, None, None as Option<BoxTerm>)
+            .unwrap();
+        let g1: FastGraph = parser
+            .parse_str("<http://example.org/s> <http://example.org/p> (1 2 3) .")
+            .collect_triples()
+            .unwrap();
+
+        let mut config_map = TypeMap::new();
+        config_map.insert::<PrettyConfig>(
+            PrettyConfig::new()
+                .with_pretty(true)
+                .with_render_collections(render_collections),
+        );
+        let out = DynSynTripleSerializerFactory::new(Some(config_map))
+            .try_new_stringifier(syntax::TURTLE)
+            .unwrap()
+            .serialize_triples(g1.triples())
+            .unwrap()
+            .to_string();
+        assert_eq!(!render_collections, out.contains("rdf:first"));
+        assert!(out.contains("\n  <http://example.org/p> "));
+
+        let g2: FastGraph = parser.parse_str(&out).collect_triples().unwrap();
+        assert!(isomorphic_graphs(&g1, &g2).unwrap());
+    }
+
+    #[test_case(syntax::TURTLE, true)]
+    #[test_case(syntax::N_TRIPLES, false)]
+    pub fn per_call_configs_override_factory_configs_of_same_type(
+        syntax_: RdfSyntax,
+        pretty_printed: bool,
+    ) {
+        Lazy::force(&TRACING);
+        let g1: FastGraph = TRIPLE_PARSER_FACTORY
+            .try_new_parser(syntax::TURTLE, None, None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(NON_ASCII_TURTLE_DOC)
+            .collect_triples()
+            .unwrap();
+
+        let mut config_map = TypeMap::new();
+        config_map.insert(EscapeConfig::new().with_escape_non_ascii(true));
+        config_map.insert(PrettyConfig::new().with_pretty(false));
+        let factory = DynSynTripleSerializerFactory::new(Some(config_map));
+
+        let mut overrides = TypeMap::new();
+        overrides.insert(DynSynSerializerConfig::new().with_pretty(true));
+        let overridden_out = factory
+            .try_new_stringifier_with_config(syntax_, overrides)
+            .unwrap()
+            .serialize_triples(g1.triples())
+            .unwrap()
+            .to_string();
+        // Escape config of factory is retained.
+        assert!(overridden_out.is_ascii(), "{}", overridden_out);
+
+        // Factory configs are unchanged for other serializers.
+        let out = factory
+            .try_new_stringifier(syntax_)
+            .unwrap()
+            .serialize_triples(g1.triples())
+            .unwrap()
+            .to_string();
+        assert_eq!(out != overridden_out, pretty_printed);
+
+        let g2: FastGraph = TRIPLE_PARSER_FACTORY
+            .try_new_parser(syntax_, None, None as Option<BoxTerm>)
+            .unwrap()
+            .parse_str(&out)
+            .collect_triples()
+            .unwrap();
+        assert!(isomorphic_graphs(&g1, &g2).unwrap());
+    }
+
+    #[test_case(GraphSelector::Default, &["default"])]
+    #[test_case(GraphSelector::Named(BoxTerm::new_iri("http://ex.org/g1").unwrap()), &["g1"])]
+    #[test_case(GraphSelector::UnionOfAllGraphs, &["default", "g1", "g2"])]
+    pub fn serializes_quads_from_selected_graphs(
+        selector: GraphSelector<BoxTerm>,
+        expected: &[&str],
+    ) {
+        Lazy::force(&TRACING);
+        let doc = r#"
+            <http://ex.org/s> <http://ex.org/p> "default" .
+            <http://ex.org/g1> { <http://ex.org/s> <http://ex.org/p> "g1" . }
+            <http://ex.org/g2> { <http://ex.org/s> <http://ex.org/p> "g2" . }
+        "#;
+        let quads = DynSynQuadParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::TRIG, None, None)
+            .unwrap()
+            .parse_str(doc);
+        let mut serializer = SERIALIZER_FACTORY
+            .try_new_stringifier(syntax::N_TRIPLES)
+            .unwrap();
+        serializer
+            .serialize_quads_from_graph(quads, &selector)
+            .unwrap();
+
+        let mut objects = serializer
+            .as_str()
+            .lines()
+            .map(|line| line.split('"').nth(1).unwrap().to_string())
+            .collect::<Vec<_>>();
+        objects.sort();
+        assert_eq!(objects, expected);
+    }
 }
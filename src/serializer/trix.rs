@@ -0,0 +1,331 @@
+//! Serializer for [`trix`](crate::syntax::TRIX) syntax.
+//!
+//! Quads are written in `<graph>` elements, one for each run of consecutive quads with same graph name. Hence a graph can be split across several `<graph>` elements, if quads of it are not consecutive in the source. Triples are written in a single `<graph>` element without a name, that trix readers read into default graph.
+//!
+//! Simple literals are written as `<plainLiteral>` elements, with an `xml:lang` attribute if they are language-tagged, and other literals as `<typedLiteral>` elements.
+//!
+//! ```text
+//! <?xml version="1.0" encoding="utf-8"?>
+//! <TriX xmlns="http://www.w3.org/2004/03/trix/trix-1/">
+//!   <graph>
+//!     <uri>http://example.org/g</uri>
+//!     <triple>
+//!       <uri>http://example.org/#me</uri>
+//!       <uri>http://schema.org/name</uri>
+//!       <plainLiteral xml:lang="en">Me</plainLiteral>
+//!     </triple>
+//!   </graph>
+//! </TriX>
+//! ```
+
+use std::io;
+
+use sophia_api::{
+    ns::xsd,
+    quad::{stream::QuadSource, Quad},
+    serializer::{QuadSerializer, Stringifier, TripleSerializer},
+    term::{term_eq, TTerm, TermKind},
+    triple::{
+        stream::{StreamError, StreamResult, TripleSource},
+        Triple,
+    },
+};
+
+use super::escape::{write_escaped_char, EscapeStyle};
+
+/// Namespace of trix elements.
+const TRIX_NS: &str = "http://www.w3.org/2004/03/trix/trix-1/";
+
+/// Serializer for trix syntax.
+pub struct TrixSerializer<W> {
+    write: W,
+    escape_non_ascii: bool,
+}
+
+impl<W> TrixSerializer<W>
+where
+    W: io::Write,
+{
+    /// Build a new trix serializer writing to `write`.
+    pub fn new(write: W) -> Self {
+        Self {
+            write,
+            escape_non_ascii: false,
+        }
+    }
+
+    /// Set whether to escape non-ascii characters of text, and attribute values.
+    #[cfg_attr(not(feature = "trix"), allow(dead_code))]
+    pub(crate) fn with_escape_non_ascii(mut self, escape_non_ascii: bool) -> Self {
+        self.escape_non_ascii = escape_non_ascii;
+        self
+    }
+
+    fn write_start(&mut self) -> io::Result<()> {
+        write!(
+            self.write,
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<TriX xmlns=\"{}\">\n",
+            TRIX_NS
+        )
+    }
+
+    fn write_end(&mut self, in_graph: bool) -> io::Result<()> {
+        if in_graph {
+            self.write.write_all(b"  </graph>\n")?;
+        }
+        self.write.write_all(b"</TriX>\n")
+    }
+
+    /// Write start tag of a graph element, along with element of it's name, if any.
+    fn write_graph_start<T>(&mut self, g: Option<&T>) -> io::Result<()>
+    where
+        T: TTerm + ?Sized,
+    {
+        self.write.write_all(b"  <graph>\n")?;
+        if let Some(g) = g {
+            self.write.write_all(b"    ")?;
+            write_term(&mut self.write, g, self.escape_non_ascii)?;
+            self.write.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    fn write_triple<T>(&mut self, s: &T, p: &T, o: &T) -> io::Result<()>
+    where
+        T: TTerm + ?Sized,
+    {
+        let (w, ascii) = (&mut self.write, self.escape_non_ascii);
+        w.write_all(b"    <triple>\n")?;
+        for t in [s, p, o] {
+            w.write_all(b"      ")?;
+            write_term(w, t, ascii)?;
+            w.write_all(b"\n")?;
+        }
+        w.write_all(b"    </triple>\n")
+    }
+}
+
+impl<W> TripleSerializer for TrixSerializer<W>
+where
+    W: io::Write,
+{
+    type Error = io::Error;
+
+    fn serialize_triples<TS>(
+        &mut self,
+        mut source: TS,
+    ) -> StreamResult<&mut Self, TS::Error, Self::Error>
+    where
+        TS: TripleSource,
+    {
+        self.write_start().map_err(StreamError::SinkError)?;
+        let mut in_graph = false;
+        source.try_for_each_triple(|t| {
+            if !in_graph {
+                self.write.write_all(b"  <graph>\n")?;
+                in_graph = true;
+            }
+            self.write_triple(t.s(), t.p(), t.o())
+        })?;
+        self.write_end(in_graph).map_err(StreamError::SinkError)?;
+        Ok(self)
+    }
+}
+
+impl<W> QuadSerializer for TrixSerializer<W>
+where
+    W: io::Write,
+{
+    type Error = io::Error;
+
+    fn serialize_quads<QS>(
+        &mut self,
+        mut source: QS,
+    ) -> StreamResult<&mut Self, QS::Error, Self::Error>
+    where
+        QS: QuadSource,
+    {
+        self.write_start().map_err(StreamError::SinkError)?;
+        // Graph name of current graph element, if one is open.
+        let mut current_graph: Option<Option<(TermKind, String)>> = None;
+        source.try_for_each_quad(|q| {
+            let g = q.g().map(|g| (g.kind(), g.value().to_string()));
+            if current_graph.as_ref() != Some(&g) {
+                if current_graph.is_some() {
+                    self.write.write_all(b"  </graph>\n")?;
+                }
+                self.write_graph_start(q.g())?;
+                current_graph = Some(g);
+            }
+            self.write_triple(q.s(), q.p(), q.o())
+        })?;
+        self.write_end(current_graph.is_some())
+            .map_err(StreamError::SinkError)?;
+        Ok(self)
+    }
+}
+
+impl Stringifier for TrixSerializer<Vec<u8>> {
+    fn as_utf8(&self) -> &[u8] {
+        &self.write[..]
+    }
+}
+
+/// Write given term as a trix element. Non-ascii characters are escaped as character references, if `ascii` is true.
+fn write_term<W, T>(w: &mut W, t: &T, ascii: bool) -> io::Result<()>
+where
+    W: io::Write,
+    T: TTerm + ?Sized,
+{
+    match t.kind() {
+        TermKind::Iri => {
+            w.write_all(b"<uri>")?;
+            write_xml_text(w, &t.value(), ascii)?;
+            w.write_all(b"</uri>")
+        }
+        TermKind::BlankNode => {
+            w.write_all(b"<id>")?;
+            write_xml_text(w, &t.value(), ascii)?;
+            w.write_all(b"</id>")
+        }
+        TermKind::Literal => {
+            let datatype = t
+                .datatype()
+                .filter(|dt| t.language().is_none() && !term_eq(dt, &xsd::string));
+            match (t.language(), datatype) {
+                (_, Some(dt)) => {
+                    w.write_all(b"<typedLiteral datatype=\"")?;
+                    write_xml_text(w, &dt.value(), ascii)?;
+                    w.write_all(b"\">")?;
+                    write_xml_text(w, &t.value(), ascii)?;
+                    w.write_all(b"</typedLiteral>")
+                }
+                (Some(lang), None) => {
+                    w.write_all(b"<plainLiteral xml:lang=\"")?;
+                    write_xml_text(w, lang, ascii)?;
+                    w.write_all(b"\">")?;
+                    write_xml_text(w, &t.value(), ascii)?;
+                    w.write_all(b"</plainLiteral>")
+                }
+                (None, None) => {
+                    w.write_all(b"<plainLiteral>")?;
+                    write_xml_text(w, &t.value(), ascii)?;
+                    w.write_all(b"</plainLiteral>")
+                }
+            }
+        }
+        TermKind::Variable => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "variables can't be serialized in trix",
+        )),
+    }
+}
+
+/// Write given value escaped, so that it can be used both as text, and as a quoted attribute value. Non-ascii characters are escaped as character references, if `ascii` is true.
+fn write_xml_text<W: io::Write>(w: &mut W, v: &str, ascii: bool) -> io::Result<()> {
+    let mut start = 0;
+    for (i, c) in v.char_indices() {
+        let escaped = match c {
+            '&' => "&amp;",
+            '<' => "&lt;",
+            '>' => "&gt;",
+            '"' => "&quot;",
+            '\r' => "&#xD;",
+            c if ascii && !c.is_ascii() => "",
+            _ => continue,
+        };
+        w.write_all(&v.as_bytes()[start..i])?;
+        if escaped.is_empty() {
+            write_escaped_char(w, EscapeStyle::Xml, c)?;
+        } else {
+            w.write_all(escaped.as_bytes())?;
+        }
+        start = i + c.len_utf8();
+    }
+    w.write_all(&v.as_bytes()[start..])
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use sophia_api::{
+        dataset::{isomorphic_datasets, Dataset},
+        graph::{isomorphic_graphs, Graph},
+        parser::QuadParser,
+        quad::{stream::QuadSource, Quad},
+        serializer::{QuadSerializer, Stringifier, TripleSerializer},
+        triple::stream::TripleSource,
+    };
+    use sophia_inmem::{dataset::FastDataset, graph::FastGraph};
+    use sophia_term::BoxTerm;
+    use sophia_turtle::parser::{trig, turtle};
+
+    use super::TrixSerializer;
+    use crate::{parser::trix::TrixParser, tests::TRACING};
+
+    static TRIG_DOC: &str = r#"
+        @prefix ex: <http://example.org/> .
+        ex:alice ex:knows _:bob ; ex:note "a < b & \"c\"\r\n" .
+        ex:g1 { _:bob ex:name "Bob"@en ; ex:age 42 . }
+        _:g2 { ex:alice ex:name "Alice" . }
+        ex:g1 { ex:alice ex:p ex:o . }
+    "#;
+
+    #[test]
+    pub fn roundtrips_quads() {
+        Lazy::force(&TRACING);
+        let d1: FastDataset = trig::parse_str(TRIG_DOC).collect_quads().unwrap();
+        let out = TrixSerializer::new(Vec::new())
+            .serialize_dataset(&d1)
+            .unwrap()
+            .to_string();
+        let d2: FastDataset = TrixParser::default()
+            .parse_str(&out)
+            .collect_quads()
+            .unwrap();
+        assert!(isomorphic_datasets(&d1, &d2).unwrap(), "{}", out);
+        assert!(out.contains("<plainLiteral xml:lang=\"en\">Bob</plainLiteral>"));
+        assert!(out.contains("<plainLiteral>Alice</plainLiteral>"));
+    }
+
+    #[test]
+    pub fn writes_triples_in_unnamed_graph() {
+        Lazy::force(&TRACING);
+        let g1: FastGraph =
+            turtle::parse_str("<http://example.org/s> <http://example.org/p> \"o\", 1 .")
+                .collect_triples()
+                .unwrap();
+        let out = TrixSerializer::new(Vec::new())
+            .serialize_graph(&g1)
+            .unwrap()
+            .to_string();
+        assert_eq!(out.matches("<graph>").count(), 1);
+        assert!(!out.contains("<graph>\n    <uri>"));
+
+        let d: FastDataset = TrixParser::default()
+            .parse_str(&out)
+            .collect_quads()
+            .unwrap();
+        assert!(d.quads().all(|q| q.unwrap().g().is_none()));
+        let g2: FastGraph = d.graph(None as Option<&BoxTerm>).triples().collect_triples().unwrap();
+        assert!(isomorphic_graphs(&g1, &g2).unwrap());
+    }
+
+    #[test]
+    pub fn empty_sources_produce_valid_documents() {
+        Lazy::force(&TRACING);
+        let out = TrixSerializer::new(Vec::new())
+            .serialize_graph(&FastGraph::new())
+            .unwrap()
+            .to_string();
+        assert!(!out.contains("<graph>"));
+        let d: FastDataset = TrixParser::default()
+            .parse_str(&out)
+            .collect_quads()
+            .unwrap();
+        assert_eq!(d.quads().count(), 0);
+    }
+}
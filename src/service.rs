@@ -0,0 +1,523 @@
+//! This module provides [`RdfContentService`], that packages what an http server needs to accept, and to respond with rdf payloads: syntax resolution from `Content-Type` header, parsing within limits, content negotiation against `Accept` header, and serialization. It is framework-agnostic. Request headers are read through [`HeaderSource`] trait, and errors map onto http status codes through [`ServiceError::status_code`].
+//!
+//! [`HeaderSource`] is implemented for slices of name-value pairs, and for hash maps. When `http` feature is enabled, it is also implemented for [`http::HeaderMap`](https://docs.rs/http/latest/http/header/struct.HeaderMap.html), that axum, and hyper use, and [`ResponsePayload`] can be converted into an `http::Response`. These are the only framework adapters it provides. Frameworks, that have their own header types, like actix-web, are not adapted by this crate, and their handlers can wrap their header maps in a newtype implementing [`HeaderSource`], as in second example below.
+//!
+//! Parsers are taken from configured [`DynSynQuadParserFactory`], so that policies in it's config map, like [`IriPolicy`](crate::parser::iri_policy::IriPolicy), and [`LiteralPolicy`](crate::parser::literal_policy::LiteralPolicy), apply to payloads too. [`ServiceLimits`] are enforced per payload, with a fresh [`SessionBudget`].
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::service::RdfContentService;
+//! use sophia_api::dataset::Dataset;
+//! use sophia_inmem::dataset::FastDataset;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let service = RdfContentService::default();
+//!
+//! let request_headers = [("Content-Type", "text/turtle; charset=utf-8")];
+//! let body = "<http://example.org/a> <http://example.org/p> <http://example.org/b> .";
+//! let dataset: FastDataset = service.read_payload(&request_headers[..], body.as_bytes())?;
+//! assert_eq!(dataset.quads().count(), 1);
+//!
+//! let request_headers = [("Accept", "application/n-quads;q=0.9, text/html")];
+//! let response = service.write_payload(&request_headers[..], &dataset)?;
+//! assert_eq!(response.content_type.essence_str(), "application/n-quads");
+//! assert!(!response.body.is_empty());
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+//!
+//! Adapting header map of a framework, that is not built on `http` crate:
+//!
+//! ```
+//! use std::borrow::Cow;
+//!
+//! use rdf_dynsyn::service::{HeaderSource, RdfContentService};
+//! use sophia_inmem::dataset::FastDataset;
+//! # mod framework {
+//! #     pub struct HeaderMap(pub Vec<(String, String)>);
+//! #     impl HeaderMap {
+//! #         pub fn get(&self, name: &str) -> Option<&str> {
+//! #             self.0.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+//! #         }
+//! #     }
+//! # }
+//!
+//! /// Header map of framework, wrapped to implement [`HeaderSource`].
+//! struct Headers<'h>(&'h framework::HeaderMap);
+//!
+//! impl HeaderSource for Headers<'_> {
+//!     fn header(&self, name: &str) -> Option<Cow<'_, str>> {
+//!         self.0.get(name).map(Cow::Borrowed)
+//!     }
+//! }
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let headers = framework::HeaderMap(vec![("content-type".into(), "application/n-triples".into())]);
+//! let body = "<http://example.org/a> <http://example.org/p> <http://example.org/b> .";
+//! let dataset: FastDataset =
+//!     RdfContentService::default().read_payload(&Headers(&headers), body.as_bytes())?;
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::{
+    borrow::Cow, collections::HashMap, hash::BuildHasher, io::BufRead, sync::Arc, time::Duration,
+};
+
+use mime::Mime;
+use sophia_api::{
+    dataset::CollectibleDataset, parser::QuadParser, quad::stream::QuadSource,
+    triple::stream::StreamError,
+};
+use sophia_term::BoxTerm;
+
+use crate::{
+    error_code::{self, ErrorCode},
+    parser::{errors::DynSynParseError, limits::SessionBudget, quads::DynSynQuadParserFactory},
+    serializer::{
+        negotiated::{NegotiatedSerializer, NotAcceptableError},
+        serializable::{DynSynSerializable, DynSynSerializeError, DynSynSerializerFactories},
+    },
+    syntax::UnKnownSyntaxError,
+    syntax_hint::{SyntaxHint, SyntaxHintError},
+};
+
+/// A source of http headers, like headers of a request.
+pub trait HeaderSource {
+    /// Get value of header with given name, matched case-insensitively. Values of a header, that occurs multiple times are joined with `, `.
+    fn header(&self, name: &str) -> Option<Cow<'_, str>>;
+}
+
+/// Join values of a header, that may occur multiple times.
+fn join_values<'v>(mut values: impl Iterator<Item = &'v str>) -> Option<Cow<'v, str>> {
+    let first = values.next()?;
+    Some(match values.next() {
+        None => Cow::Borrowed(first),
+        Some(second) => Cow::Owned(
+            [first, second]
+                .into_iter()
+                .chain(values)
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+    })
+}
+
+impl<K: AsRef<str>, V: AsRef<str>> HeaderSource for [(K, V)] {
+    fn header(&self, name: &str) -> Option<Cow<'_, str>> {
+        join_values(
+            self.iter()
+                .filter(|(k, _)| k.as_ref().eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.as_ref()),
+        )
+    }
+}
+
+impl<K: AsRef<str>, V: AsRef<str>, S: BuildHasher> HeaderSource for HashMap<K, V, S> {
+    fn header(&self, name: &str) -> Option<Cow<'_, str>> {
+        join_values(
+            self.iter()
+                .filter(|(k, _)| k.as_ref().eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.as_ref()),
+        )
+    }
+}
+
+#[cfg(any(test, feature = "http"))]
+impl HeaderSource for http::HeaderMap {
+    fn header(&self, name: &str) -> Option<Cow<'_, str>> {
+        // Values, that are not visible ascii can't be media types, and are skipped.
+        join_values(self.get_all(name).iter().filter_map(|v| v.to_str().ok()))
+    }
+}
+
+/// Limits, that each payload read by a [`RdfContentService`] is parsed within.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ServiceLimits {
+    /// Maximum number of bytes of a payload body.
+    pub max_body_bytes: Option<u64>,
+    /// Maximum number of statements in a payload.
+    pub max_statements: Option<u64>,
+    /// Maximum time to parse a payload in.
+    pub max_parse_time: Option<Duration>,
+}
+
+impl ServiceLimits {
+    /// Get a fresh budget with these limits, if any limit is set.
+    fn session_budget(&self) -> Option<SessionBudget> {
+        (*self != Self::default()).then(|| {
+            SessionBudget::new(
+                self.max_statements,
+                self.max_body_bytes,
+                self.max_parse_time,
+            )
+        })
+    }
+}
+
+/// A serialized response payload, along with it's content type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponsePayload {
+    /// Content type of the payload, to be set as `Content-Type` header.
+    pub content_type: Mime,
+    /// Serialized payload.
+    pub body: Vec<u8>,
+}
+
+#[cfg(any(test, feature = "http"))]
+impl From<ResponsePayload> for http::Response<Vec<u8>> {
+    fn from(payload: ResponsePayload) -> Self {
+        let mut response = http::Response::new(payload.body);
+        if let Ok(value) = http::HeaderValue::from_str(payload.content_type.as_ref()) {
+            response
+                .headers_mut()
+                .insert(http::header::CONTENT_TYPE, value);
+        }
+        response
+    }
+}
+
+/// An error in reading, or writing a payload.
+#[derive(Debug, thiserror::Error)]
+pub enum ServiceError {
+    /// `Content-Type` header of request is missing, is invalid, or is not an rdf media type. Responded with 415.
+    #[error(transparent)]
+    UnsupportedMediaType(#[from] SyntaxHintError),
+    /// Payload is in an rdf syntax, that no parser is enabled for. Responded with 415.
+    #[error(transparent)]
+    UnsupportedSyntax(#[from] UnKnownSyntaxError),
+    /// None of syntaxes accepted by `Accept` header of request can be serialized into. Responded with 406.
+    #[error(transparent)]
+    NotAcceptable(#[from] NotAcceptableError),
+    /// Payload couldn't be parsed. Responded with 413, if it exceeded [`ServiceLimits`], and with 400 otherwise.
+    #[error("Error in parsing payload: {0}")]
+    Parse(#[source] DynSynParseError),
+    /// Parsed statements couldn't be collected into target dataset. Responded with 500.
+    #[error("Error in collecting parsed payload: {0}")]
+    Dataset(String),
+    /// Statements of response dataset couldn't be read, to serialize them. Responded with 500.
+    #[error("Error in reading statements to serialize: {0}")]
+    Statements(String),
+    /// Response dataset has quads in named graphs, that negotiated triple-only syntax can't represent, and serializer factories of service are configured with [`NamedGraphAction::Error`](crate::serializer::serializable::NamedGraphAction::Error). Responded with 500.
+    #[error("Error in serializing named graphs: {0}")]
+    NamedGraph(String),
+}
+
+impl ErrorCode for ServiceError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::UnsupportedMediaType(e) => e.code(),
+            Self::UnsupportedSyntax(e) => e.code(),
+            Self::NotAcceptable(e) => e.code(),
+            Self::Parse(e) => e.code(),
+            Self::Dataset(_) => error_code::DYNSYN_SERVICE_DATASET,
+            Self::Statements(_) => error_code::DYNSYN_STATEMENT_SOURCE,
+            Self::NamedGraph(_) => error_code::DYNSYN_NAMED_GRAPH_IN_TRIPLE_SYNTAX,
+        }
+    }
+}
+
+impl ServiceError {
+    /// Get http status code, that this error should be responded with.
+    pub fn status_code(&self) -> u16 {
+        match self {
+            Self::UnsupportedMediaType(_) | Self::UnsupportedSyntax(_) => 415,
+            Self::NotAcceptable(_) => 406,
+            Self::Parse(e) if e.code() == error_code::DYNSYN_LIMIT_EXCEEDED => 413,
+            Self::Parse(_) => 400,
+            Self::Dataset(_) | Self::Statements(_) | Self::NamedGraph(_) => 500,
+        }
+    }
+}
+
+/// A service, that reads rdf payloads of requests, and writes rdf payloads of responses, in whatever syntaxes clients send, and accept.
+///
+/// It is cheap to share across request handlers, as it is immutable.
+pub struct RdfContentService {
+    parser_factory: DynSynQuadParserFactory,
+    serializer_factories: Arc<DynSynSerializerFactories>,
+    limits: ServiceLimits,
+}
+
+impl Default for RdfContentService {
+    fn default() -> Self {
+        Self::new(DynSynQuadParserFactory::default(), Default::default())
+    }
+}
+
+impl RdfContentService {
+    /// Create a new service with given parser factory, and serializer config, without any limits.
+    pub fn new(
+        parser_factory: DynSynQuadParserFactory,
+        serializer_factories: Arc<DynSynSerializerFactories>,
+    ) -> Self {
+        Self {
+            parser_factory,
+            serializer_factories,
+            limits: ServiceLimits::default(),
+        }
+    }
+
+    /// Set limits, that each payload is parsed within.
+    pub fn with_limits(mut self, limits: ServiceLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Returns limits, that each payload is parsed within.
+    pub fn limits(&self) -> &ServiceLimits {
+        &self.limits
+    }
+
+    /// Read payload `body` of a request with given `headers` into a dataset. Syntax is resolved from `Content-Type` header. Relative iris are resolved against `Content-Location` header, if any. Triples of triple syntaxes are read into default graph.
+    ///
+    /// # Errors
+    /// returns [`ServiceError`] if syntax of payload can't be resolved, or is not supported, or if payload can't be parsed within limits.
+    pub fn read_payload<D, H, R>(&self, headers: &H, body: R) -> Result<D, ServiceError>
+    where
+        D: CollectibleDataset,
+        H: HeaderSource + ?Sized,
+        R: BufRead,
+    {
+        let syntax_ = SyntaxHint::from_http(headers.header("content-type").as_deref(), None)?
+            .resolve()?
+            .value;
+        let base_iri = headers.header("content-location").map(Cow::into_owned);
+        let mut parser = self
+            .parser_factory
+            .try_new_parser::<BoxTerm>(syntax_, base_iri, None)?;
+        if let Some(budget) = self.limits.session_budget() {
+            parser = parser.with_session_budget(Some(budget));
+        }
+        parser
+            .parse(body)
+            .collect_quads::<D>()
+            .map_err(|e| match e {
+                StreamError::SourceError(e) => ServiceError::Parse(e),
+                StreamError::SinkError(e) => ServiceError::Dataset(e.to_string()),
+            })
+    }
+
+    /// Negotiate syntax of response payload against `Accept` header of request with given `headers`. If there is no `Accept` header, any syntax is acceptable. Returned serializer can be used to stream large responses.
+    ///
+    /// # Errors
+    /// returns [`ServiceError::NotAcceptable`] if no supported syntax is acceptable.
+    pub fn negotiate<H>(&self, headers: &H) -> Result<NegotiatedSerializer, ServiceError>
+    where
+        H: HeaderSource + ?Sized,
+    {
+        let accept = headers.header("accept");
+        Ok(NegotiatedSerializer::negotiate(
+            accept.as_deref().unwrap_or("*/*"),
+            self.serializer_factories.clone(),
+        )?)
+    }
+
+    /// Write `data` into payload of response to a request with given `headers`, in a syntax negotiated against it's `Accept` header.
+    ///
+    /// Quads of `data` in named graphs are handled as per [`NamedGraphAction`](crate::serializer::serializable::NamedGraphAction) of serializer factories of this service, if negotiated syntax is triple-only.
+    ///
+    /// # Errors
+    /// returns [`ServiceError`] if no supported syntax is acceptable, if statements of `data` couldn't be read, or if they are in named graphs, that negotiated syntax can't represent.
+    pub fn write_payload<H, S, M>(
+        &self,
+        headers: &H,
+        data: S,
+    ) -> Result<ResponsePayload, ServiceError>
+    where
+        H: HeaderSource + ?Sized,
+        S: DynSynSerializable<M>,
+    {
+        let negotiated = self.negotiate(headers)?;
+        let mut body = Vec::new();
+        negotiated.serialize(data, &mut body).map_err(|e| match e {
+            DynSynSerializeError::NamedGraph(_) => ServiceError::NamedGraph(e.to_string()),
+            e => ServiceError::Statements(e.to_string()),
+        })?;
+        Ok(ResponsePayload {
+            content_type: negotiated.content_type().clone(),
+            body,
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use once_cell::sync::Lazy;
+    use sophia_api::dataset::Dataset;
+    use sophia_inmem::dataset::FastDataset;
+    use test_case::test_case;
+    use type_map::concurrent::TypeMap;
+
+    use super::{HeaderSource, RdfContentService, ServiceLimits};
+    use crate::{
+        error_code::{self, ErrorCode},
+        parser::{iri_policy::IriPolicy, limits::LimitAction, quads::DynSynQuadParserFactory},
+        serializer::serializable::{DynSynSerializerFactories, NamedGraphAction},
+        tests::TRACING,
+    };
+
+    static SERVICE: Lazy<RdfContentService> = Lazy::new(RdfContentService::default);
+
+    static TRIG_DOC: &str = r#"
+        @prefix : <http://example.org/> .
+        :a :p <b> .
+        :g { :a :p :c . }
+    "#;
+
+    #[test]
+    pub fn reads_headers_case_insensitively() {
+        Lazy::force(&TRACING);
+        let headers = [("ACCEPT", "text/turtle"), ("accept", "*/*;q=0.1")];
+        assert_eq!(
+            headers[..].header("Accept").as_deref(),
+            Some("text/turtle, */*;q=0.1")
+        );
+        assert_eq!(headers[..].header("Content-Type"), None);
+
+        let headers = HashMap::from([("Content-Type".to_string(), "text/turtle".to_string())]);
+        assert_eq!(
+            headers.header("content-type").as_deref(),
+            Some("text/turtle")
+        );
+
+        let mut headers = http::HeaderMap::new();
+        headers.append(http::header::ACCEPT, "text/turtle".parse().unwrap());
+        headers.append(http::header::ACCEPT, "application/trig".parse().unwrap());
+        assert_eq!(
+            headers.header("Accept").as_deref(),
+            Some("text/turtle, application/trig")
+        );
+    }
+
+    #[test]
+    pub fn reads_payload_against_content_location() {
+        Lazy::force(&TRACING);
+        let headers = [
+            ("Content-Type", "application/trig"),
+            ("Content-Location", "http://example.org/doc"),
+        ];
+        let dataset: FastDataset = SERVICE
+            .read_payload(&headers[..], TRIG_DOC.as_bytes())
+            .unwrap();
+        assert_eq!(dataset.quads().count(), 2);
+    }
+
+    #[test_case(&[], 415, error_code::DYNSYN_UNDETERMINED_SYNTAX)]
+    #[test_case(&[("Content-Type", "application/ld+json")], 415, error_code::DYNSYN_UNKNOWN_SYNTAX)]
+    #[test_case(&[("Content-Type", "application/n-quads")], 400, error_code::DYNSYN_PARSE_SYNTAX)]
+    pub fn reports_unreadable_payloads(headers: &[(&str, &str)], status: u16, code: &str) {
+        Lazy::force(&TRACING);
+        let e = SERVICE
+            .read_payload::<FastDataset, _, _>(headers, TRIG_DOC.as_bytes())
+            .map(|_| ())
+            .unwrap_err();
+        assert_eq!((e.status_code(), e.code()), (status, code));
+    }
+
+    #[test]
+    pub fn reads_payload_within_limits() {
+        Lazy::force(&TRACING);
+        let service = RdfContentService::default().with_limits(ServiceLimits {
+            max_statements: Some(1),
+            ..Default::default()
+        });
+        let headers = [("Content-Type", "application/trig")];
+        let doc = TRIG_DOC.replace("<b>", ":b");
+        let e = service
+            .read_payload::<FastDataset, _, _>(&headers[..], doc.as_bytes())
+            .map(|_| ())
+            .unwrap_err();
+        assert_eq!(
+            (e.status_code(), e.code()),
+            (413, error_code::DYNSYN_LIMIT_EXCEEDED)
+        );
+    }
+
+    #[test]
+    pub fn applies_policies_of_parser_factory() {
+        Lazy::force(&TRACING);
+        let mut config_map = TypeMap::new();
+        config_map.insert(IriPolicy::new(LimitAction::Error).with_denied_host("example.org"));
+        let service = RdfContentService::new(
+            DynSynQuadParserFactory::new(Some(config_map)),
+            Arc::default(),
+        );
+        let headers = [
+            ("Content-Type", "application/trig"),
+            ("Content-Location", "http://example.org/doc"),
+        ];
+        let e = service
+            .read_payload::<FastDataset, _, _>(&headers[..], TRIG_DOC.as_bytes())
+            .map(|_| ())
+            .unwrap_err();
+        assert_eq!(e.code(), error_code::DYNSYN_IRI_POLICY_VIOLATION);
+    }
+
+    #[test_case(&[], "text/turtle")]
+    #[test_case(&[("Accept", "application/trig, text/turtle;q=0.5")], "application/trig")]
+    pub fn writes_payload_in_negotiated_syntax(headers: &[(&str, &str)], content_type: &str) {
+        Lazy::force(&TRACING);
+        let headers_in = [
+            ("Content-Type", "application/trig"),
+            ("Content-Location", "http://example.org/doc"),
+        ];
+        let dataset: FastDataset = SERVICE
+            .read_payload(&headers_in[..], TRIG_DOC.as_bytes())
+            .unwrap();
+        let service = RdfContentService::new(
+            DynSynQuadParserFactory::default(),
+            Arc::new(
+                DynSynSerializerFactories::default()
+                    .with_named_graph_action(NamedGraphAction::Merge),
+            ),
+        );
+        let payload = service.write_payload(headers, &dataset).unwrap();
+        assert_eq!(payload.content_type.essence_str(), content_type);
+
+        let response: http::Response<Vec<u8>> = payload.clone().into();
+        assert_eq!(
+            response.headers()[http::header::CONTENT_TYPE],
+            payload.content_type.as_ref()
+        );
+    }
+
+    #[test]
+    pub fn reports_named_graphs_unrepresentable_in_negotiated_syntax() {
+        Lazy::force(&TRACING);
+        let headers_in = [
+            ("Content-Type", "application/trig"),
+            ("Content-Location", "http://example.org/doc"),
+        ];
+        let dataset: FastDataset = SERVICE
+            .read_payload(&headers_in[..], TRIG_DOC.as_bytes())
+            .unwrap();
+        let e = SERVICE
+            .write_payload(&[("Accept", "text/turtle")][..], &dataset)
+            .unwrap_err();
+        assert_eq!(
+            (e.status_code(), e.code()),
+            (500, error_code::DYNSYN_NAMED_GRAPH_IN_TRIPLE_SYNTAX)
+        );
+    }
+
+    #[test]
+    pub fn reports_unacceptable_requests() {
+        Lazy::force(&TRACING);
+        let e = SERVICE
+            .write_payload(&[("Accept", "text/html")][..], &FastDataset::new())
+            .unwrap_err();
+        assert_eq!(
+            (e.status_code(), e.code()),
+            (406, error_code::DYNSYN_NOT_ACCEPTABLE)
+        );
+    }
+}
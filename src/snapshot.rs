@@ -0,0 +1,289 @@
+//! This module provides golden-file snapshot helpers, that serialize a graph, or dataset with a fixed serializer config, and compare output against a stored golden file. Applications can use them to pin their public rdf output format across upgrades of this crate, and of it's dependencies. It is available only when `snapshot` feature is enabled.
+//!
+//! Blank node labels are not stable across serializations. Hence snapshots are normalized before comparison: blank node labels are renamed to `b0`, `b1`, ... in order of their first appearance. In line based syntaxes, blank nodes are ordered by lines they appear in instead, and lines are sorted. Normalization is textual, and is not a full canonicalization. For stable snapshots of syntaxes, that are not line based, statements should be given in a deterministic order.
+//!
+//! If `DYNSYN_UPDATE_SNAPSHOTS` environment variable is set, golden files are (re-)written with current output, instead of being compared against.
+//!
+//! Example:
+//!
+//! ```no_run
+//! use rdf_dynsyn::{
+//!     serializer::serializable::DynSynSerializerFactories, snapshot::check_snapshot, syntax,
+//! };
+//! use sophia_term::BoxTerm;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let me = BoxTerm::new_iri("http://example.org/#me")?;
+//! let graph = vec![[me.clone(), me.clone(), me]];
+//! check_snapshot(
+//!     &graph,
+//!     syntax::TURTLE,
+//!     &DynSynSerializerFactories::default(),
+//!     "tests/golden/me.ttl",
+//! )?;
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    error_code::{self, ErrorCode},
+    serializer::serializable::{DynSynSerializable, DynSynSerializerFactories},
+    syntax::{self, RdfSyntax},
+};
+
+/// Name of environment variable, that makes [`check_snapshot`] write golden files, instead of comparing against them.
+pub const UPDATE_SNAPSHOTS_ENV_VAR: &str = "DYNSYN_UPDATE_SNAPSHOTS";
+
+/// An error that describes why a snapshot check failed.
+#[derive(Debug, thiserror::Error)]
+pub enum SnapshotError {
+    #[error("Error in serializing snapshot: {0}")]
+    Serialize(String),
+    #[error("Io error in accessing golden file {0:?}: {1}")]
+    Io(PathBuf, io::Error),
+    #[error("Snapshot doesn't match golden file {golden_path:?}.\nexpected:\n{expected}\nactual:\n{actual}")]
+    Mismatch {
+        golden_path: PathBuf,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl ErrorCode for SnapshotError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::Serialize(_) => error_code::DYNSYN_SNAPSHOT_SERIALIZE,
+            Self::Io(_, _) => error_code::DYNSYN_SNAPSHOT_IO,
+            Self::Mismatch { .. } => error_code::DYNSYN_SNAPSHOT_MISMATCH,
+        }
+    }
+}
+
+/// Serialize `serializable` into `syntax_` with given `config`, and normalize output. See [module docs](self) for normalization done.
+pub fn normalized_snapshot<S, M>(
+    serializable: S,
+    syntax_: RdfSyntax,
+    config: &DynSynSerializerFactories,
+) -> Result<String, SnapshotError>
+where
+    S: DynSynSerializable<M>,
+{
+    let mut doc = Vec::new();
+    serializable
+        .serialize_to(syntax_, &mut doc, config)
+        .map_err(|e| SnapshotError::Serialize(e.to_string()))?;
+    let doc = String::from_utf8(doc).map_err(|e| SnapshotError::Serialize(e.to_string()))?;
+    Ok(normalize(&doc, syntax_))
+}
+
+/// Serialize `serializable` into `syntax_` with given `config`, and compare normalized output against golden file at `golden_path`. If [`UPDATE_SNAPSHOTS_ENV_VAR`] is set, golden file is written instead.
+pub fn check_snapshot<S, M>(
+    serializable: S,
+    syntax_: RdfSyntax,
+    config: &DynSynSerializerFactories,
+    golden_path: impl AsRef<Path>,
+) -> Result<(), SnapshotError>
+where
+    S: DynSynSerializable<M>,
+{
+    let golden_path = golden_path.as_ref();
+    let actual = normalized_snapshot(serializable, syntax_, config)?;
+    let io_error = |e| SnapshotError::Io(golden_path.to_owned(), e);
+
+    if std::env::var_os(UPDATE_SNAPSHOTS_ENV_VAR).is_some() {
+        if let Some(parent) = golden_path.parent() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        return fs::write(golden_path, actual).map_err(io_error);
+    }
+
+    let expected = fs::read_to_string(golden_path).map_err(io_error)?;
+    // Golden files may get their line endings converted on checkout.
+    if expected.replace("\r\n", "\n") != actual {
+        return Err(SnapshotError::Mismatch {
+            golden_path: golden_path.to_owned(),
+            expected,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+/// Normalize a document serialized into `syntax_`. See [module docs](self) for normalization done.
+pub fn normalize(doc: &str, syntax_: RdfSyntax) -> String {
+    if !is_line_based(syntax_) {
+        return relabel_bnodes(doc, |_| ());
+    }
+    // Rank each blank node by lines it appears in, so that relabeling doesn't depend on original labels, or line order.
+    let lines: Vec<&str> = doc.lines().collect();
+    let mut signatures: HashMap<String, Vec<String>> = HashMap::new();
+    for line in &lines {
+        map_bnode_labels(line, |label| {
+            let signature = map_bnode_labels(line, |other| {
+                if other == label { "@" } else { "" }.to_owned()
+            });
+            signatures
+                .entry(label.to_owned())
+                .or_default()
+                .push(signature);
+            String::new()
+        });
+    }
+    signatures
+        .values_mut()
+        .for_each(|signature| signature.sort());
+    let mut lines: Vec<String> = relabel_bnodes(&lines.join("\n"), |label| {
+        signatures.get(label).cloned().unwrap_or_default()
+    })
+    .lines()
+    .map(ToOwned::to_owned)
+    .collect();
+    lines.sort();
+    lines.into_iter().map(|line| line + "\n").collect()
+}
+
+fn is_line_based(syntax_: RdfSyntax) -> bool {
+    [syntax::N_TRIPLES, syntax::N_QUADS, syntax::ND_RDF_JSON].contains(&syntax_)
+}
+
+/// Rename blank node labels to `b0`, `b1`, ..., in order of their rank, and of their first appearance among equally ranked ones.
+fn relabel_bnodes<K: Ord>(doc: &str, rank: impl Fn(&str) -> K) -> String {
+    let mut labels: Vec<String> = Vec::new();
+    map_bnode_labels(doc, |label| {
+        if !labels.iter().any(|l| l == label) {
+            labels.push(label.to_owned());
+        }
+        String::new()
+    });
+    labels.sort_by_cached_key(|label| rank(label));
+    let labels: HashMap<String, String> = labels
+        .into_iter()
+        .enumerate()
+        .map(|(i, label)| (label, format!("b{}", i)))
+        .collect();
+    map_bnode_labels(doc, |label| labels[label].clone())
+}
+
+/// Replace every blank node label in `doc`, like `x` in `_:x`, with result of `f`.
+fn map_bnode_labels(doc: &str, mut f: impl FnMut(&str) -> String) -> String {
+    let mut out = String::with_capacity(doc.len());
+    let mut rest = doc;
+    while let Some(i) = rest.find("_:") {
+        out.push_str(&rest[..i + 2]);
+        rest = &rest[i + 2..];
+        let mut end = rest
+            .find(|c: char| !(c.is_alphanumeric() || "_-.".contains(c)))
+            .unwrap_or(rest.len());
+        // A label can't end with a dot.
+        while rest[..end].ends_with('.') {
+            end -= 1;
+        }
+        if end > 0 {
+            out.push_str(&f(&rest[..end]));
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use once_cell::sync::Lazy;
+    use sophia_api::{parser::TripleParser, triple::stream::TripleSource};
+    use sophia_inmem::graph::FastGraph;
+    use sophia_term::BoxTerm;
+    use test_case::test_case;
+
+    use super::{check_snapshot, normalize, normalized_snapshot};
+    use crate::{
+        error_code::{self, ErrorCode},
+        parser::triples::DynSynTripleParserFactory,
+        serializer::serializable::DynSynSerializerFactories,
+        syntax::{self, RdfSyntax},
+        tests::TRACING,
+    };
+
+    const DOC: &str = r#"
+        @prefix : <http://example.org/>.
+        :alice :knows [ :name "Bob" ], [ :name "Carol" ].
+        :alice :name "Alice".
+    "#;
+
+    fn parse(doc: &str) -> FastGraph {
+        DynSynTripleParserFactory::default()
+            .try_new_parser::<BoxTerm>(syntax::TURTLE, None, None)
+            .unwrap()
+            .parse_str(doc)
+            .collect_triples()
+            .unwrap()
+    }
+
+    #[test]
+    pub fn relabels_bnodes_in_order_of_appearance() {
+        Lazy::force(&TRACING);
+        assert_eq!(
+            normalize("_:x <p> _:yz.\n_:yz <p> _:x .\n", syntax::TURTLE),
+            "_:b0 <p> _:b1.\n_:b1 <p> _:b0 .\n"
+        );
+    }
+
+    #[test]
+    pub fn sorts_lines_of_line_based_syntaxes() {
+        Lazy::force(&TRACING);
+        let d1 = "_:q <http://e/p> \"b\".\n<http://e/a> <http://e/p> _:r.\n";
+        let d2 = "<http://e/a> <http://e/p> _:x1.\n_:x2 <http://e/p> \"b\".\n";
+        assert_eq!(
+            normalize(d1, syntax::N_TRIPLES),
+            normalize(d2, syntax::N_TRIPLES)
+        );
+    }
+
+    #[test_case(syntax::N_TRIPLES)]
+    #[test_case(syntax::N_QUADS)]
+    pub fn snapshots_of_line_based_syntaxes_are_stable(syntax_: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let config = DynSynSerializerFactories::default();
+        assert_eq!(
+            normalized_snapshot(&parse(DOC), syntax_, &config).unwrap(),
+            normalized_snapshot(&parse(DOC), syntax_, &config).unwrap()
+        );
+    }
+
+    #[test]
+    pub fn checks_against_golden_files() {
+        Lazy::force(&TRACING);
+        let dir = std::env::temp_dir().join(format!("dynsyn-snapshot-{}", std::process::id()));
+        let golden_path = dir.join("alice.nt");
+        let config = DynSynSerializerFactories::default();
+        let graph = parse(DOC);
+
+        let e = check_snapshot(&graph, syntax::N_TRIPLES, &config, &golden_path).unwrap_err();
+        assert_eq!(e.code(), error_code::DYNSYN_SNAPSHOT_IO);
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            &golden_path,
+            normalized_snapshot(&graph, syntax::N_TRIPLES, &config).unwrap(),
+        )
+        .unwrap();
+        check_snapshot(&parse(DOC), syntax::N_TRIPLES, &config, &golden_path).unwrap();
+
+        let changed = parse(&DOC.replace("Carol", "Dave"));
+        let e = check_snapshot(&changed, syntax::N_TRIPLES, &config, &golden_path).unwrap_err();
+        assert_eq!(e.code(), error_code::DYNSYN_SNAPSHOT_MISMATCH);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,199 @@
+//! This module provides a programmatic support matrix, that tells for each [known syntax](crate::syntax::KNOWN_SYNTAXES), which of parsing, and serialization capabilities are available in this build. UIs, and capability documents, like solid server configs can introspect it at runtime, instead of hard coding it.
+//!
+//! Matrix is computed by probing dynsyn parser, and serializer factories. Hence it always agrees with what they can instantiate, with whatever cargo features this crate is built. Lists of syntaxes per capability, like [`parsable_quad_syntaxes`], are computed once, and can be used to build `Accept`, or `Content-Type` advertisement lists.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{support::{self, SyntaxSupport}, syntax};
+//!
+//! let turtle = SyntaxSupport::of(syntax::TURTLE);
+//! assert!(turtle.triple_parsing && turtle.triple_serialization);
+//! assert!(!SyntaxSupport::of(syntax::JSON_LD).is_supported());
+//!
+//! assert!(support::serializable_triple_syntaxes().contains(&syntax::TURTLE));
+//! assert!(!support::serializable_quad_syntaxes().contains(&syntax::TURTLE));
+//! ```
+
+use once_cell::sync::Lazy;
+use sophia_term::BoxTerm;
+
+use crate::{
+    parser::{
+        ordering::{statement_order, StatementOrder},
+        quads::DynSynQuadParserFactory,
+        triples::DynSynTripleParserFactory,
+    },
+    serializer::{quads::DynSynQuadSerializerFactory, triples::DynSynTripleSerializerFactory},
+    syntax::{RdfSyntax, KNOWN_SYNTAXES},
+};
+
+/// Capabilities available for a syntax in this build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SyntaxSupport {
+    /// The syntax.
+    pub syntax: RdfSyntax,
+    /// Whether quads can be parsed from documents in the syntax.
+    pub quad_parsing: bool,
+    /// Whether triples can be parsed from documents in the syntax.
+    pub triple_parsing: bool,
+    /// Whether quads can be serialized into the syntax.
+    pub quad_serialization: bool,
+    /// Whether triples can be serialized into the syntax.
+    pub triple_serialization: bool,
+    /// Order, in which parsed statements are emitted, if syntax can be parsed.
+    pub parse_order: Option<StatementOrder>,
+}
+
+impl SyntaxSupport {
+    /// Get capabilities available for given syntax.
+    pub fn of(syntax_: RdfSyntax) -> Self {
+        Self {
+            syntax: syntax_,
+            quad_parsing: DynSynQuadParserFactory::default()
+                .try_new_parser::<BoxTerm>(syntax_, None, None)
+                .is_ok(),
+            triple_parsing: DynSynTripleParserFactory::default()
+                .try_new_parser::<BoxTerm>(syntax_, None, None)
+                .is_ok(),
+            quad_serialization: DynSynQuadSerializerFactory::default()
+                .content_type(syntax_)
+                .is_ok(),
+            triple_serialization: DynSynTripleSerializerFactory::default()
+                .content_type(syntax_)
+                .is_ok(),
+            parse_order: statement_order(syntax_),
+        }
+    }
+
+    /// Returns true, if any of capabilities is available for the syntax.
+    pub fn is_supported(&self) -> bool {
+        self.quad_parsing
+            || self.triple_parsing
+            || self.quad_serialization
+            || self.triple_serialization
+    }
+}
+
+/// Get support matrix of all [known syntaxes](KNOWN_SYNTAXES), in their order.
+pub fn support_matrix() -> Vec<SyntaxSupport> {
+    KNOWN_SYNTAXES.iter().copied().map(SyntaxSupport::of).collect()
+}
+
+static SUPPORT_MATRIX: Lazy<Vec<SyntaxSupport>> = Lazy::new(support_matrix);
+
+fn syntaxes_with(capability: fn(&SyntaxSupport) -> bool) -> Vec<RdfSyntax> {
+    SUPPORT_MATRIX
+        .iter()
+        .filter(|s| capability(s))
+        .map(|s| s.syntax)
+        .collect()
+}
+
+static PARSABLE_QUAD_SYNTAXES: Lazy<Vec<RdfSyntax>> =
+    Lazy::new(|| syntaxes_with(|s| s.quad_parsing));
+static PARSABLE_TRIPLE_SYNTAXES: Lazy<Vec<RdfSyntax>> =
+    Lazy::new(|| syntaxes_with(|s| s.triple_parsing));
+static SERIALIZABLE_QUAD_SYNTAXES: Lazy<Vec<RdfSyntax>> =
+    Lazy::new(|| syntaxes_with(|s| s.quad_serialization));
+static SERIALIZABLE_TRIPLE_SYNTAXES: Lazy<Vec<RdfSyntax>> =
+    Lazy::new(|| syntaxes_with(|s| s.triple_serialization));
+
+/// Get syntaxes, from which quads can be parsed in this build, in order of [`KNOWN_SYNTAXES`].
+pub fn parsable_quad_syntaxes() -> &'static [RdfSyntax] {
+    &PARSABLE_QUAD_SYNTAXES
+}
+
+/// Get syntaxes, from which triples can be parsed in this build, in order of [`KNOWN_SYNTAXES`].
+pub fn parsable_triple_syntaxes() -> &'static [RdfSyntax] {
+    &PARSABLE_TRIPLE_SYNTAXES
+}
+
+/// Get syntaxes, into which quads can be serialized in this build, in order of [`KNOWN_SYNTAXES`].
+pub fn serializable_quad_syntaxes() -> &'static [RdfSyntax] {
+    &SERIALIZABLE_QUAD_SYNTAXES
+}
+
+/// Get syntaxes, into which triples can be serialized in this build, in order of [`KNOWN_SYNTAXES`].
+pub fn serializable_triple_syntaxes() -> &'static [RdfSyntax] {
+    &SERIALIZABLE_TRIPLE_SYNTAXES
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use test_case::test_case;
+
+    use super::{
+        parsable_quad_syntaxes, parsable_triple_syntaxes, serializable_quad_syntaxes,
+        serializable_triple_syntaxes, support_matrix, SyntaxSupport,
+    };
+    use crate::{
+        syntax::{self, RdfSyntax, KNOWN_SYNTAXES},
+        tests::TRACING,
+    };
+
+    #[test_case(syntax::N_QUADS, [true, true, true, false])]
+    #[test_case(syntax::TRIG, [true, true, true, false])]
+    #[test_case(syntax::N_TRIPLES, [true, true, false, true])]
+    #[test_case(syntax::TURTLE, [true, true, false, true])]
+    #[cfg_attr(feature = "rdf-xml", test_case(syntax::RDF_XML, [true, true, false, true]))]
+    #[test_case(syntax::ND_RDF_JSON, [false, false, true, true])]
+    #[test_case(syntax::JSON_LD, [false, false, false, false])]
+    #[cfg_attr(feature = "n3", test_case(syntax::N3, [true, true, false, false]))]
+    #[cfg_attr(not(feature = "n3"), test_case(syntax::N3, [false, false, false, false]))]
+    #[cfg_attr(feature = "rdfa", test_case(syntax::HTML_RDFA, [true, true, false, false]))]
+    #[cfg_attr(not(feature = "rdfa"), test_case(syntax::HTML_RDFA, [false, false, false, false]))]
+    #[cfg_attr(feature = "trix", test_case(syntax::TRIX, [true, true, true, true]))]
+    #[cfg_attr(not(feature = "trix"), test_case(syntax::TRIX, [false, false, false, false]))]
+    #[cfg_attr(feature = "hdt", test_case(syntax::HDT, [true, true, false, false]))]
+    #[cfg_attr(not(feature = "hdt"), test_case(syntax::HDT, [false, false, false, false]))]
+    pub fn reports_capabilities_of_syntax(syntax_: RdfSyntax, expected: [bool; 4]) {
+        Lazy::force(&TRACING);
+        let s = SyntaxSupport::of(syntax_);
+        assert_eq!(
+            [
+                s.quad_parsing,
+                s.triple_parsing,
+                s.quad_serialization,
+                s.triple_serialization
+            ],
+            expected
+        );
+        assert_eq!(s.is_supported(), expected.contains(&true));
+    }
+
+    #[test]
+    pub fn matrix_covers_all_known_syntaxes() {
+        Lazy::force(&TRACING);
+        let matrix = support_matrix();
+        assert_eq!(matrix.len(), KNOWN_SYNTAXES.len());
+        assert!(matrix
+            .iter()
+            .zip(KNOWN_SYNTAXES)
+            .all(|(s, syntax_)| s.syntax == syntax_));
+    }
+
+    #[test]
+    pub fn capability_lists_agree_with_matrix() {
+        Lazy::force(&TRACING);
+        assert_eq!(syntax::all(), KNOWN_SYNTAXES);
+        for s in support_matrix() {
+            assert_eq!(parsable_quad_syntaxes().contains(&s.syntax), s.quad_parsing);
+            assert_eq!(parsable_triple_syntaxes().contains(&s.syntax), s.triple_parsing);
+            assert_eq!(
+                serializable_quad_syntaxes().contains(&s.syntax),
+                s.quad_serialization
+            );
+            assert_eq!(
+                serializable_triple_syntaxes().contains(&s.syntax),
+                s.triple_serialization
+            );
+        }
+        assert!(parsable_quad_syntaxes().contains(&syntax::TURTLE));
+        assert!(!parsable_quad_syntaxes().contains(&syntax::JSON_LD));
+    }
+}
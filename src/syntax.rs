@@ -1,5 +1,11 @@
 //! This module defines struct for rdf concrete syntax. It also exports few syntax constants.
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    correspondence::correspondent_for_media_type,
+    error_code::{self, ErrorCode},
+    support,
+};
 
 /// A concrete rdf syntax is a syntax in which we can serialize rdf graphs or datasets unambiguously. see [https://www.w3.org/TR/rdf11-concepts/#rdf-documents](https://www.w3.org/TR/rdf11-concepts/#rdf-documents)
 ///
@@ -33,12 +39,111 @@ use std::fmt::Display;
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct RdfSyntax(pub &'static str);
 
+/// Displays spec iri of the syntax, which is a stable identifier. [Known syntaxes](KNOWN_SYNTAXES) can be parsed back from it with [`FromStr`].
 impl Display for RdfSyntax {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0)
     }
 }
 
+impl RdfSyntax {
+    /// Get short name of the syntax, like `turtle`, or `n-quads`, if it is a [known syntax](KNOWN_SYNTAXES).
+    pub fn name(&self) -> Option<&'static str> {
+        SYNTAX_NAMES
+            .iter()
+            .find(|(syntax_, _)| syntax_ == self)
+            .map(|(_, name)| *name)
+    }
+
+    /// Get capabilities of the syntax. Flags describing the syntax itself are false for unknown syntaxes. Flags `parseable`, and `serializable` tell, what is available in this build. See [`support`](crate::support) module for them in detail.
+    pub fn capabilities(&self) -> SyntaxCapabilities {
+        SyntaxCapabilities {
+            supports_quads: [N_QUADS, TRIG, TRIX, JSON_LD, ND_RDF_JSON].contains(self),
+            supports_prefixes: [
+                TURTLE,
+                TRIG,
+                N3,
+                RDF_XML,
+                OWL2_XML,
+                OWL2_MANCHESTER,
+                JSON_LD,
+                XHTML_RDFA,
+                HTML_RDFA,
+            ]
+            .contains(self),
+            supports_rdfstar: [TURTLE, TRIG, N_TRIPLES, N_QUADS, N3].contains(self),
+            is_linebased: [N_TRIPLES, N_QUADS, ND_RDF_JSON].contains(self),
+            binary: *self == HDT,
+            parseable: support::parsable_quad_syntaxes().contains(self)
+                || support::parsable_triple_syntaxes().contains(self),
+            serializable: support::serializable_quad_syntaxes().contains(self)
+                || support::serializable_triple_syntaxes().contains(self),
+        }
+    }
+}
+
+/// Capabilities of a syntax, as returned by [`RdfSyntax::capabilities`]. Generic tooling, like uis listing export formats, or servers advertising accepted syntaxes, can query them instead of hard coding knowledge of each syntax.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SyntaxCapabilities {
+    /// Whether documents in the syntax can have statements in named graphs.
+    pub supports_quads: bool,
+    /// Whether documents in the syntax can declare prefixes, or namespaces, to abbreviate iris.
+    pub supports_prefixes: bool,
+    /// Whether syntax has an rdf-star variant, that dynsyn parsers read with [reification of quoted triples](crate::parser::rdf_star).
+    pub supports_rdfstar: bool,
+    /// Whether each statement of a document in the syntax is on a line of it's own.
+    pub is_linebased: bool,
+    /// Whether documents in the syntax are binary, rather than text.
+    pub binary: bool,
+    /// Whether documents in the syntax can be parsed in this build.
+    pub parseable: bool,
+    /// Whether statements can be serialized into the syntax in this build.
+    pub serializable: bool,
+}
+
+/// Parses a [known syntax](KNOWN_SYNTAXES) from it's short name, like `turtle`, `trig`, or `rdf-xml`, from it's spec iri, as displayed, or from a corresponding media type, like `text/turtle`. Names are matched case-insensitively, and `_` is treated as `-`. Common aliases, like `ttl`, or `nquads` are accepted too.
+///
+/// Example:
+///
+/// ```
+/// use rdf_dynsyn::syntax::{self, RdfSyntax};
+///
+/// # fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+/// assert_eq!("Turtle".parse::<RdfSyntax>()?, syntax::TURTLE);
+/// assert_eq!("rdf_xml".parse::<RdfSyntax>()?, syntax::RDF_XML);
+/// assert_eq!("application/n-quads".parse::<RdfSyntax>()?, syntax::N_QUADS);
+/// assert_eq!(syntax::TRIG.to_string().parse::<RdfSyntax>()?, syntax::TRIG);
+/// assert!("yaml".parse::<RdfSyntax>().is_err());
+/// # Ok(())
+/// # }
+/// # fn main() {try_main().unwrap();}
+/// ```
+impl FromStr for RdfSyntax {
+    type Err = ParseSyntaxError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim();
+        if let Some(syntax_) = KNOWN_SYNTAXES.iter().find(|syntax_| syntax_.0 == value) {
+            return Ok(*syntax_);
+        }
+        let name = value.to_ascii_lowercase().replace('_', "-");
+        if let Some((syntax_, _)) = SYNTAX_NAMES
+            .iter()
+            .chain(SYNTAX_NAME_ALIASES.iter())
+            .find(|(_, n)| *n == name)
+        {
+            return Ok(*syntax_);
+        }
+        value
+            .parse::<mime::Mime>()
+            .ok()
+            .filter(|media_type| !media_type.subtype().as_str().is_empty())
+            .and_then(|media_type| correspondent_for_media_type(&media_type))
+            .map(|c| c.value)
+            .ok_or_else(|| ParseSyntaxError(s.to_string()))
+    }
+}
+
 ///RDF 1.1 Turtle: Terse RDF Triple Language
 ///
 /// Spec: [http://www.w3.org/TR/turtle/](http://www.w3.org/TR/turtle/)
@@ -94,7 +199,233 @@ pub const XHTML_RDFA: RdfSyntax = RdfSyntax("https://www.w3.org/TR/xhtml-rdfa/")
 ///  Spec: [https://www.w3.org/TR/html-rdfa/](https://www.w3.org/TR/html-rdfa/)
 pub const HTML_RDFA: RdfSyntax = RdfSyntax("https://www.w3.org/TR/html-rdfa/");
 
+/// TriX: RDF Triples in XML
+///
+/// Spec: [https://www.hpl.hp.com/techreports/2004/HPL-2004-56.html](https://www.hpl.hp.com/techreports/2004/HPL-2004-56.html)
+pub const TRIX: RdfSyntax = RdfSyntax("http://www.w3.org/2004/03/trix/");
+
+/// HDT: Header-Dictionary-Triples, a compressed binary rdf format for large, read-only graphs
+///
+/// Spec: [https://www.w3.org/Submission/2011/SUBM-HDT-20110330/](https://www.w3.org/Submission/2011/SUBM-HDT-20110330/)
+pub const HDT: RdfSyntax = RdfSyntax("https://www.w3.org/Submission/2011/SUBM-HDT-20110330/");
+
+/// Newline delimited rdf-json: A non-standard line-based debug syntax, that writes one json object per statement. It is output-only.
+///
+/// See [`nd_rdf_json`](crate::serializer::nd_rdf_json) module for format.
+pub const ND_RDF_JSON: RdfSyntax = RdfSyntax("https://github.com/manomayam/rdf_dynsyn#nd-rdf-json");
+
+/// All syntaxes, for which this module exports constants.
+pub const KNOWN_SYNTAXES: [RdfSyntax; 14] = [
+    TURTLE,
+    RDF_XML,
+    N3,
+    N_TRIPLES,
+    N_QUADS,
+    OWL2_XML,
+    OWL2_MANCHESTER,
+    TRIG,
+    JSON_LD,
+    XHTML_RDFA,
+    HTML_RDFA,
+    TRIX,
+    HDT,
+    ND_RDF_JSON,
+];
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for RdfSyntax {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name().unwrap_or(self.0))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RdfSyntax {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Short names of known syntaxes, as returned by [`RdfSyntax::name`].
+const SYNTAX_NAMES: [(RdfSyntax, &str); 14] = [
+    (TURTLE, "turtle"),
+    (RDF_XML, "rdf-xml"),
+    (N3, "n3"),
+    (N_TRIPLES, "n-triples"),
+    (N_QUADS, "n-quads"),
+    (OWL2_XML, "owl2-xml"),
+    (OWL2_MANCHESTER, "owl2-manchester"),
+    (TRIG, "trig"),
+    (JSON_LD, "json-ld"),
+    (XHTML_RDFA, "xhtml-rdfa"),
+    (HTML_RDFA, "html-rdfa"),
+    (TRIX, "trix"),
+    (HDT, "hdt"),
+    (ND_RDF_JSON, "nd-rdf-json"),
+];
+
+/// Other names, that known syntaxes are commonly referred with.
+const SYNTAX_NAME_ALIASES: [(RdfSyntax, &str); 7] = [
+    (TURTLE, "ttl"),
+    (RDF_XML, "rdfxml"),
+    (N_TRIPLES, "ntriples"),
+    (N_TRIPLES, "nt"),
+    (N_QUADS, "nquads"),
+    (N_QUADS, "nq"),
+    (JSON_LD, "jsonld"),
+];
+
+/// Get all syntaxes, for which this module exports constants. See [`support`](crate::support) module for syntaxes, that are supported in this build.
+pub fn all() -> &'static [RdfSyntax] {
+    &KNOWN_SYNTAXES
+}
+
 /// An error indicating, given syntax is not known/supported in given context
 #[derive(Debug, thiserror::Error)]
 #[error("Un supported syntax: {0}")]
 pub struct UnKnownSyntaxError(pub RdfSyntax);
+
+impl ErrorCode for UnKnownSyntaxError {
+    fn code(&self) -> &'static str {
+        error_code::DYNSYN_UNKNOWN_SYNTAX
+    }
+}
+
+/// An error indicating, given string doesn't identify any known syntax
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{0:?} doesn't identify any known syntax")]
+pub struct ParseSyntaxError(pub String);
+
+impl ErrorCode for ParseSyntaxError {
+    fn code(&self) -> &'static str {
+        error_code::DYNSYN_UNKNOWN_SYNTAX
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use test_case::test_case;
+
+    use super::{RdfSyntax, SyntaxCapabilities, KNOWN_SYNTAXES};
+    use crate::{
+        error_code::{self, ErrorCode},
+        syntax,
+        tests::TRACING,
+    };
+
+    #[test_case("turtle", syntax::TURTLE)]
+    #[test_case(" TriG ", syntax::TRIG)]
+    #[test_case("rdf-xml", syntax::RDF_XML; "rdf-xml name")]
+    #[test_case("RDF_XML", syntax::RDF_XML; "rdf-xml name in upper snake case")]
+    #[test_case("nquads", syntax::N_QUADS)]
+    #[test_case("nt", syntax::N_TRIPLES)]
+    #[test_case("text/turtle; charset=utf-8", syntax::TURTLE)]
+    #[test_case("application/ld+json", syntax::JSON_LD)]
+    #[test_case("http://www.w3.org/TR/n-triples/", syntax::N_TRIPLES)]
+    pub fn parses_syntax_from_str(value: &str, expected: RdfSyntax) {
+        Lazy::force(&TRACING);
+        assert_eq!(value.parse::<RdfSyntax>().unwrap(), expected);
+    }
+
+    #[test_case("yaml"; "unknown name")]
+    #[test_case("text/csv"; "unknown media type")]
+    #[test_case("text/"; "malformed media type")]
+    #[test_case(""; "empty string")]
+    pub fn rejects_unknown_syntax_names(value: &str) {
+        Lazy::force(&TRACING);
+        let e = value.parse::<RdfSyntax>().unwrap_err();
+        assert_eq!(e.code(), error_code::DYNSYN_UNKNOWN_SYNTAX);
+    }
+
+    #[test]
+    pub fn known_syntaxes_round_trip_through_display_and_name() {
+        Lazy::force(&TRACING);
+        for syntax_ in KNOWN_SYNTAXES {
+            assert_eq!(syntax_.to_string().parse::<RdfSyntax>().unwrap(), syntax_);
+            let name = syntax_.name().unwrap();
+            assert_eq!(name.parse::<RdfSyntax>().unwrap(), syntax_);
+        }
+        assert_eq!(RdfSyntax("tag:unknown").name(), None);
+    }
+
+    #[test_case(syntax::N_QUADS, SyntaxCapabilities {
+        supports_quads: true,
+        supports_prefixes: false,
+        supports_rdfstar: true,
+        is_linebased: true,
+        binary: false,
+        parseable: true,
+        serializable: true,
+    }; "n-quads")]
+    #[test_case(syntax::TURTLE, SyntaxCapabilities {
+        supports_quads: false,
+        supports_prefixes: true,
+        supports_rdfstar: true,
+        is_linebased: false,
+        binary: false,
+        parseable: true,
+        serializable: true,
+    }; "turtle")]
+    #[test_case(syntax::N3, SyntaxCapabilities {
+        supports_quads: false,
+        supports_prefixes: true,
+        supports_rdfstar: true,
+        is_linebased: false,
+        binary: false,
+        parseable: cfg!(feature = "n3"),
+        serializable: false,
+    }; "n3")]
+    #[test_case(syntax::JSON_LD, SyntaxCapabilities {
+        supports_quads: true,
+        supports_prefixes: true,
+        supports_rdfstar: false,
+        is_linebased: false,
+        binary: false,
+        parseable: false,
+        serializable: false,
+    }; "json-ld")]
+    #[test_case(syntax::HDT, SyntaxCapabilities {
+        supports_quads: false,
+        supports_prefixes: false,
+        supports_rdfstar: false,
+        is_linebased: false,
+        binary: true,
+        parseable: cfg!(feature = "hdt"),
+        serializable: false,
+    }; "hdt")]
+    #[test_case(RdfSyntax("tag:unknown"), SyntaxCapabilities {
+        supports_quads: false,
+        supports_prefixes: false,
+        supports_rdfstar: false,
+        is_linebased: false,
+        binary: false,
+        parseable: false,
+        serializable: false,
+    }; "unknown syntax")]
+    pub fn reports_capabilities_of_syntax(syntax_: RdfSyntax, expected: SyntaxCapabilities) {
+        Lazy::force(&TRACING);
+        assert_eq!(syntax_.capabilities(), expected);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    pub fn syntax_round_trips_through_serde() {
+        Lazy::force(&TRACING);
+        assert_eq!(serde_json::to_string(&syntax::TRIG).unwrap(), r#""trig""#);
+        for syntax_ in KNOWN_SYNTAXES {
+            let json = serde_json::to_string(&syntax_).unwrap();
+            assert_eq!(serde_json::from_str::<RdfSyntax>(&json).unwrap(), syntax_);
+        }
+        assert_eq!(
+            serde_json::from_str::<RdfSyntax>(r#""text/turtle""#).unwrap(),
+            syntax::TURTLE
+        );
+        assert!(serde_json::from_str::<RdfSyntax>(r#""yaml""#).is_err());
+    }
+}
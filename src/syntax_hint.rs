@@ -0,0 +1,425 @@
+//! This module defines [`SyntaxHint`], that models hints about syntax of a document, as they are found in http messages, file names, or explicit configuration, and resolves them to rdf syntaxes. Hints are constructed from raw header values, and request paths, and failures to construct them are reported as structured [`SyntaxHintError`]s.
+//!
+//! When a hint doesn't resolve, like for [`SyntaxHint::Unknown`], syntax can be sniffed from start of the document with [`SyntaxHint::resolve_or_sniff`]. Parser factories accept hints along with start of the document, through their `try_new_parser_for_hint` methods, giving one resolution path for all kinds of hints.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::{syntax, syntax_hint::SyntaxHint};
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let hint = SyntaxHint::from_content_type_header("text/turtle; charset=utf-8")?;
+//! assert_eq!(hint.resolve()?.value, syntax::TURTLE);
+//!
+//! let hint = SyntaxHint::from_http(Some("text/plain"), Some("/people/alice.nt?v=2"))?;
+//! assert_eq!(hint.resolve()?.value, syntax::N_TRIPLES);
+//!
+//! let head: &[u8] = b"@prefix foaf: <http://xmlns.com/foaf/0.1/> .";
+//! assert_eq!(SyntaxHint::Unknown.resolve_or_sniff(Some(head))?, syntax::TURTLE);
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use mime::Mime;
+
+use crate::{
+    correspondence::{Correspondent, NonRdfFileExtensionError, NonRdfMediaTypeError},
+    error_code::{self, ErrorCode},
+    file_extension::FileExtension,
+    metrics,
+    syntax::{self, RdfSyntax},
+};
+
+/// A hint about syntax of a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxHint {
+    /// Media type of the document, like from a `Content-Type` header. It has no parameters.
+    MediaType(Mime),
+    /// File extension of the document, like from a request path, or a file name. It is lowercased.
+    FileExtension(FileExtension),
+    /// Media type of the document, along with it's file extension, as found in an http message. Media type takes precedence, and file extension is consulted, only if media type doesn't correspond to a syntax exclusively.
+    MediaTypeAndFileExtension(Mime, FileExtension),
+    /// Syntax of the document, as explicitly specified, like by a command line option.
+    Explicit(RdfSyntax),
+    /// No hint is available. Syntax can only be sniffed from content of the document.
+    Unknown,
+}
+
+impl SyntaxHint {
+    /// Create a hint from value of a `Content-Type` header. Media type parameters, like charset are ignored.
+    ///
+    /// # Errors
+    /// returns [`SyntaxHintError::InvalidContentType`], if value is not a valid media type.
+    pub fn from_content_type_header(value: &str) -> Result<Self, SyntaxHintError> {
+        let invalid = || SyntaxHintError::InvalidContentType(value.to_string());
+        let media_type: Mime = value.trim().parse().map_err(|_| invalid())?;
+        // mime crate accepts empty subtypes.
+        if media_type.subtype().as_str().is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self::MediaType(
+            media_type.essence_str().parse().map_err(|_| invalid())?,
+        ))
+    }
+
+    /// Create a hint from a request path, or an iri. Query, and fragment are ignored.
+    ///
+    /// # Errors
+    /// returns [`SyntaxHintError::NoFileExtension`], if last segment of the path has no file extension.
+    pub fn from_request_path(path: &str) -> Result<Self, SyntaxHintError> {
+        let path_only = path.split(['?', '#']).next().unwrap_or_default();
+        let extension = path_only
+            .rsplit('/')
+            .next()
+            .and_then(FileExtension::from_path_str)
+            .filter(|extension| !extension.is_empty())
+            .ok_or_else(|| SyntaxHintError::NoFileExtension(path.to_string()))?;
+        Ok(Self::FileExtension(FileExtension::from(
+            extension.to_ascii_lowercase(),
+        )))
+    }
+
+    /// Create a hint from parts of an http message, that carry hints: value of it's `Content-Type` header, and request path. Request path is ignored, if it has no file extension.
+    ///
+    /// # Errors
+    /// returns [`SyntaxHintError::InvalidContentType`], if content type is not a valid media type, or [`SyntaxHintError::NoHint`], if neither part gives a hint.
+    pub fn from_http(
+        content_type_header: Option<&str>,
+        request_path: Option<&str>,
+    ) -> Result<Self, SyntaxHintError> {
+        let media_type = content_type_header
+            .map(Self::from_content_type_header)
+            .transpose()?;
+        let extension = request_path.and_then(|path| Self::from_request_path(path).ok());
+        match (media_type, extension) {
+            (Some(Self::MediaType(m)), Some(Self::FileExtension(e))) => {
+                Ok(Self::MediaTypeAndFileExtension(m, e))
+            }
+            (Some(hint), None) | (None, Some(hint)) => Ok(hint),
+            _ => Err(SyntaxHintError::NoHint),
+        }
+    }
+
+    /// Resolve syntax, that this hint corresponds to.
+    ///
+    /// # Errors
+    /// returns [`SyntaxHintError::NonRdfMediaType`], or [`SyntaxHintError::NonRdfFileExtension`], if hint doesn't correspond to any rdf syntax, or [`SyntaxHintError::NoHint`] for [`SyntaxHint::Unknown`].
+    pub fn resolve(&self) -> Result<Correspondent<RdfSyntax>, SyntaxHintError> {
+        match self {
+            Self::Explicit(syntax_) => Ok(Correspondent {
+                value: *syntax_,
+                is_total: true,
+            }),
+            Self::Unknown => Err(SyntaxHintError::NoHint),
+            Self::MediaType(m) => Ok(Correspondent::<RdfSyntax>::try_from(m)?),
+            Self::FileExtension(e) => Ok(Correspondent::<RdfSyntax>::try_from(e)?),
+            Self::MediaTypeAndFileExtension(m, e) => {
+                match Correspondent::<RdfSyntax>::try_from(m) {
+                    Ok(c) if c.is_total => Ok(c),
+                    r => match Correspondent::<RdfSyntax>::try_from(e) {
+                        Ok(c) => {
+                            metrics::record_fallback();
+                            Ok(c)
+                        }
+                        Err(_) => Ok(r?),
+                    },
+                }
+            }
+        }
+    }
+
+    /// Resolve syntax, that this hint corresponds to. If hint doesn't resolve, syntax is sniffed from `content_head`, the start of the document, if given.
+    ///
+    /// # Errors
+    /// returns error of [`resolve`](Self::resolve), if hint doesn't resolve, and syntax can't be sniffed.
+    pub fn resolve_or_sniff(
+        &self,
+        content_head: Option<&[u8]>,
+    ) -> Result<RdfSyntax, SyntaxHintError> {
+        match self.resolve() {
+            Ok(c) => Ok(c.value),
+            Err(e) => content_head.and_then(sniff_syntax).ok_or(e),
+        }
+    }
+}
+
+/// Sniff syntax of a document from it's start. It recognizes html, xml based syntaxes, json-ld, hdt, turtle family documents by their directives, and n-triples, and n-quads documents by their first statement. Returns `None`, if syntax can't be recognized. Resolutions are counted in [`metrics`].
+pub fn sniff_syntax(head: &[u8]) -> Option<RdfSyntax> {
+    let sniffed = sniff(head);
+    if sniffed.is_some() {
+        metrics::record_sniff_resolution();
+    }
+    sniffed
+}
+
+fn sniff(head: &[u8]) -> Option<RdfSyntax> {
+    if head.starts_with(b"$HDT") {
+        return Some(syntax::HDT);
+    }
+    let head = String::from_utf8_lossy(head.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(head));
+    let body = head
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty() || line.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let lowercase = body.to_ascii_lowercase();
+    if lowercase.starts_with("<!doctype html") || lowercase.starts_with("<html") {
+        return Some(syntax::HTML_RDFA);
+    }
+    if ["<?xml", "<!--", "<rdf:rdf"]
+        .iter()
+        .any(|prolog| lowercase.starts_with(prolog))
+    {
+        return Some(if body.contains("<TriX") {
+            syntax::TRIX
+        } else if lowercase.contains("<html") {
+            syntax::XHTML_RDFA
+        } else if body.contains("<Ontology") {
+            syntax::OWL2_XML
+        } else {
+            syntax::RDF_XML
+        });
+    }
+    if body.starts_with('{') || body.starts_with('[') {
+        return Some(syntax::JSON_LD);
+    }
+    if ["@prefix", "@base", "prefix ", "base "]
+        .iter()
+        .any(|directive| lowercase.starts_with(directive))
+    {
+        return Some(if body.contains('{') {
+            syntax::TRIG
+        } else {
+            syntax::TURTLE
+        });
+    }
+    if body.starts_with('<') || body.starts_with("_:") {
+        let first_line = body.lines().next().unwrap_or_default();
+        return Some(match statement_terms(first_line) {
+            Some(3) => syntax::N_TRIPLES,
+            Some(4) => syntax::N_QUADS,
+            _ if body.contains('{') => syntax::TRIG,
+            _ => syntax::TURTLE,
+        });
+    }
+    None
+}
+
+/// Count terms of a line-based statement, like `<s> <p> "o"@en <g> .`. Returns `None`, if line is not a complete statement of simple terms.
+fn statement_terms(line: &str) -> Option<usize> {
+    let mut chars = line.trim().chars().peekable();
+    let mut terms = 0;
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        match chars.next()? {
+            '.' => {
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                return matches!(chars.peek(), None | Some('#')).then_some(terms);
+            }
+            '<' => {
+                chars.by_ref().find(|c| *c == '>')?;
+            }
+            '"' => {
+                let mut escaped = false;
+                chars.by_ref().find(|c| {
+                    let end = !escaped && *c == '"';
+                    escaped = !escaped && *c == '\\';
+                    end
+                })?;
+                match chars.peek() {
+                    Some('@') => while chars.next_if(|c| !c.is_whitespace()).is_some() {},
+                    Some('^') => {
+                        chars.by_ref().find(|c| *c == '>')?;
+                    }
+                    _ => {}
+                }
+            }
+            '_' if chars.next_if_eq(&':').is_some() => {
+                while chars.next_if(|c| !c.is_whitespace() && *c != '.').is_some() {}
+            }
+            _ => return None,
+        }
+        terms += 1;
+    }
+}
+
+/// An error in constructing, or resolving a [`SyntaxHint`].
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum SyntaxHintError {
+    #[error("Content type {0:?} is not a valid media type")]
+    InvalidContentType(String),
+    #[error("Path {0:?} has no file extension")]
+    NoFileExtension(String),
+    #[error("Http message has no syntax hint")]
+    NoHint,
+    #[error(transparent)]
+    NonRdfMediaType(#[from] NonRdfMediaTypeError),
+    #[error(transparent)]
+    NonRdfFileExtension(#[from] NonRdfFileExtensionError),
+}
+
+impl ErrorCode for SyntaxHintError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidContentType(_) => error_code::DYNSYN_INVALID_CONTENT_TYPE,
+            Self::NoFileExtension(_) | Self::NoHint => error_code::DYNSYN_UNDETERMINED_SYNTAX,
+            Self::NonRdfMediaType(e) => e.code(),
+            Self::NonRdfFileExtension(e) => e.code(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use test_case::test_case;
+
+    use super::{sniff_syntax, SyntaxHint};
+    use crate::{
+        error_code::{self, ErrorCode},
+        file_extension::{self, FileExtension},
+        media_type,
+        syntax::{self, RdfSyntax},
+        tests::TRACING,
+    };
+
+    #[test_case("text/turtle", syntax::TURTLE)]
+    #[test_case("Application/N-Quads", syntax::N_QUADS)]
+    #[test_case(" application/trig; charset=utf-8", syntax::TRIG)]
+    pub fn resolves_content_type_header(value: &str, expected: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let hint = SyntaxHint::from_content_type_header(value).unwrap();
+        assert_eq!(hint.resolve().unwrap().value, expected);
+    }
+
+    #[test_case("/data/alice.ttl", syntax::TURTLE)]
+    #[test_case("/data/Alice.NT?format=x#me", syntax::N_TRIPLES)]
+    #[test_case("http://example.org/g.trig", syntax::TRIG)]
+    pub fn resolves_request_path(path: &str, expected: RdfSyntax) {
+        Lazy::force(&TRACING);
+        let hint = SyntaxHint::from_request_path(path).unwrap();
+        assert_eq!(hint.resolve().unwrap().value, expected);
+    }
+
+    #[test_case(Some("text/turtle"), Some("/a.nt"), syntax::TURTLE)]
+    #[test_case(Some("text/plain"), Some("/a.nt"), syntax::N_TRIPLES)]
+    #[test_case(None, Some("/a.nq"), syntax::N_QUADS)]
+    #[test_case(Some("application/n-quads"), Some("/a"), syntax::N_QUADS)]
+    pub fn resolves_http_parts(
+        content_type: Option<&str>,
+        path: Option<&str>,
+        expected: RdfSyntax,
+    ) {
+        Lazy::force(&TRACING);
+        let hint = SyntaxHint::from_http(content_type, path).unwrap();
+        assert_eq!(hint.resolve().unwrap().value, expected);
+    }
+
+    #[test]
+    pub fn combines_http_parts() {
+        Lazy::force(&TRACING);
+        assert_eq!(
+            SyntaxHint::from_http(Some("text/plain; charset=utf-8"), Some("/a/b.TTL")).unwrap(),
+            SyntaxHint::MediaTypeAndFileExtension(mime::TEXT_PLAIN, file_extension::TTL)
+        );
+        assert_eq!(
+            SyntaxHint::from_http(Some("text/turtle"), None).unwrap(),
+            SyntaxHint::MediaType(media_type::TEXT_TURTLE.clone())
+        );
+        assert_eq!(
+            SyntaxHint::from_request_path("/x.Rdf").unwrap(),
+            SyntaxHint::FileExtension(FileExtension::from("rdf"))
+        );
+    }
+
+    #[test]
+    pub fn reports_construction_failures() {
+        Lazy::force(&TRACING);
+        let code = |r: Result<SyntaxHint, _>| r.map_err(|e: super::SyntaxHintError| e.code());
+        assert_eq!(
+            code(SyntaxHint::from_content_type_header("turtle")),
+            Err(error_code::DYNSYN_INVALID_CONTENT_TYPE)
+        );
+        assert_eq!(
+            code(SyntaxHint::from_http(Some("text/"), Some("/a.ttl"))),
+            Err(error_code::DYNSYN_INVALID_CONTENT_TYPE)
+        );
+        assert_eq!(
+            code(SyntaxHint::from_request_path("/data.d/alice")),
+            Err(error_code::DYNSYN_UNDETERMINED_SYNTAX)
+        );
+        assert_eq!(
+            code(SyntaxHint::from_http(None, Some("/alice"))),
+            Err(error_code::DYNSYN_UNDETERMINED_SYNTAX)
+        );
+    }
+
+    #[test_case(Some("image/png"), None, error_code::DYNSYN_NON_RDF_MEDIA_TYPE)]
+    #[test_case(Some("image/png"), Some("/a.png"), error_code::DYNSYN_NON_RDF_MEDIA_TYPE)]
+    #[test_case(None, Some("/a.png"), error_code::DYNSYN_NON_RDF_FILE_EXTENSION)]
+    pub fn reports_resolution_failures(
+        content_type: Option<&str>,
+        path: Option<&str>,
+        expected: &str,
+    ) {
+        Lazy::force(&TRACING);
+        let hint = SyntaxHint::from_http(content_type, path).unwrap();
+        assert_eq!(hint.resolve().unwrap_err().code(), expected);
+    }
+
+    #[test_case(b"<http://ex.org/s> <http://ex.org/p> \"o\"@en .\n", Some(syntax::N_TRIPLES))]
+    #[test_case(b"# c\n_:b <tag:p> \"1\"^^<tag:int> <tag:g> .", Some(syntax::N_QUADS))]
+    #[test_case(b"<tag:s> <tag:p> \"a \\\" .\" .", Some(syntax::N_TRIPLES))]
+    #[test_case(b"<tag:s> <tag:p> <tag:o> ; <tag:q> <tag:r> .", Some(syntax::TURTLE))]
+    #[test_case(b"\xEF\xBB\xBF@prefix ex: <http://ex.org/> .\nex:a ex:b ex:c .", Some(syntax::TURTLE))]
+    #[test_case(b"PREFIX ex: <http://ex.org/>\nex:g { ex:a ex:b ex:c }", Some(syntax::TRIG))]
+    #[test_case(b"<?xml version=\"1.0\"?>\n<rdf:RDF xmlns:rdf=\"x\"/>", Some(syntax::RDF_XML))]
+    #[test_case(b"<?xml version=\"1.0\"?>\n<TriX xmlns=\"x\"/>", Some(syntax::TRIX))]
+    #[test_case(b"  <!DOCTYPE html><html></html>", Some(syntax::HTML_RDFA))]
+    #[test_case(b"{\"@context\": {}}", Some(syntax::JSON_LD))]
+    #[test_case(b"$HDT\x01", Some(syntax::HDT))]
+    #[test_case(b"hello world", None)]
+    pub fn sniffs_syntax_from_content(head: &[u8], expected: Option<RdfSyntax>) {
+        Lazy::force(&TRACING);
+        assert_eq!(sniff_syntax(head), expected);
+    }
+
+    #[test]
+    pub fn resolves_explicit_and_unknown_hints() {
+        Lazy::force(&TRACING);
+        let explicit = SyntaxHint::Explicit(syntax::TRIG);
+        assert_eq!(explicit.resolve().unwrap().value, syntax::TRIG);
+        assert_eq!(
+            explicit.resolve_or_sniff(Some(&b"<a> <b> <c> ."[..])).unwrap(),
+            syntax::TRIG
+        );
+        assert_eq!(
+            SyntaxHint::Unknown.resolve().unwrap_err().code(),
+            error_code::DYNSYN_UNDETERMINED_SYNTAX
+        );
+        assert_eq!(
+            SyntaxHint::Unknown
+                .resolve_or_sniff(Some(&b"<a> <b> <c> <d> ."[..]))
+                .unwrap(),
+            syntax::N_QUADS
+        );
+        assert_eq!(
+            SyntaxHint::MediaType(mime::IMAGE_PNG)
+                .resolve_or_sniff(Some(&b"<a> <b> <c> ."[..]))
+                .unwrap(),
+            syntax::N_TRIPLES
+        );
+        assert_eq!(
+            SyntaxHint::Unknown
+                .resolve_or_sniff(Some(&b"hello"[..]))
+                .unwrap_err()
+                .code(),
+            error_code::DYNSYN_UNDETERMINED_SYNTAX
+        );
+    }
+}
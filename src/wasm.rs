@@ -0,0 +1,182 @@
+//! This module provides [wasm-bindgen](https://docs.rs/wasm-bindgen) bindings, that convert rdf documents from their syntax into another syntax, for browser based rdf tooling, like visual editors, and validators. Syntaxes are given as strings, which can be their short names, like `turtle`, their media types, like `text/turtle`, or their spec iris. See [`RdfSyntax`'s `FromStr`](crate::syntax::RdfSyntax#impl-FromStr-for-RdfSyntax) implementation.
+//!
+//! Documents can be passed as js strings, or as `Uint8Array`s. Converted documents are returned as js strings. Errors are thrown as js `Error`s, whose `name` is [error code](crate::error_code) of the error.
+//!
+//! Crate compiles to `wasm32-unknown-unknown` target with `default-features = false`, and any of `rdf-xml`, `trix`, `n3`, `rdfa`, `hdt`, `serde`, and `wasm` features. `compression`, and `reqwest` features depend on native libraries, and are not supported on it. [`Converter::convert_all`](crate::convert::Converter::convert_all) needs threads, and file system, and fails there.
+//!
+//! Example:
+//!
+//! ```
+//! use rdf_dynsyn::wasm::convert_document;
+//!
+//! # pub fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! let doc = "@prefix : <http://example.org/> . :alice :knows :bob .";
+//! let converted = convert_document(doc.as_bytes(), "text/turtle", "n-quads", None)?;
+//! assert_eq!(
+//!     converted,
+//!     "<http://example.org/alice> <http://example.org/knows> <http://example.org/bob>.\n"
+//! );
+//! # Ok(())
+//! # }
+//! # fn main() {try_main().unwrap();}
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    convert::{convert, ConvertError, ConvertOptions},
+    error_code::ErrorCode,
+    syntax::{ParseSyntaxError, RdfSyntax},
+};
+
+/// An error in converting a document through wasm bindings.
+#[derive(Debug, thiserror::Error)]
+pub enum WasmConvertError {
+    #[error(transparent)]
+    Syntax(#[from] ParseSyntaxError),
+    #[error(transparent)]
+    Convert(#[from] ConvertError),
+}
+
+impl ErrorCode for WasmConvertError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::Syntax(e) => e.code(),
+            Self::Convert(e) => e.code(),
+        }
+    }
+}
+
+impl From<WasmConvertError> for JsValue {
+    fn from(e: WasmConvertError) -> Self {
+        let error = js_sys::Error::new(&e.to_string());
+        error.set_name(e.code());
+        error.into()
+    }
+}
+
+/// Converts `doc` from `syntax_in` into `syntax_out`. Relative iris in `doc` are resolved against `base_iri`, if it is given.
+///
+/// # Errors
+/// returns [`WasmConvertError`] if either of syntaxes is not known, or supported, or if conversion fails.
+pub fn convert_document(
+    doc: &[u8],
+    syntax_in: &str,
+    syntax_out: &str,
+    base_iri: Option<String>,
+) -> Result<String, WasmConvertError> {
+    let syntax_in: RdfSyntax = syntax_in.parse()?;
+    let syntax_out: RdfSyntax = syntax_out.parse()?;
+    let mut out = Vec::new();
+    convert(
+        doc,
+        syntax_in,
+        &mut out,
+        syntax_out,
+        &ConvertOptions {
+            base_iri,
+            ..Default::default()
+        },
+    )?;
+    Ok(String::from_utf8(out).expect("serializers write utf-8"))
+}
+
+/// Converts `doc` string from `syntaxIn` into `syntaxOut`.
+#[wasm_bindgen(js_name = convertString)]
+pub fn convert_string(
+    doc: &str,
+    syntax_in: &str,
+    syntax_out: &str,
+    base_iri: Option<String>,
+) -> Result<String, JsValue> {
+    Ok(convert_document(
+        doc.as_bytes(),
+        syntax_in,
+        syntax_out,
+        base_iri,
+    )?)
+}
+
+/// Converts `doc` bytes, passed as an `Uint8Array`, from `syntaxIn` into `syntaxOut`.
+#[wasm_bindgen(js_name = convertBytes)]
+pub fn convert_bytes(
+    doc: &[u8],
+    syntax_in: &str,
+    syntax_out: &str,
+    base_iri: Option<String>,
+) -> Result<String, JsValue> {
+    Ok(convert_document(doc, syntax_in, syntax_out, base_iri)?)
+}
+
+/// Converts `doc` string from `syntaxIn` into n-quads. Statements of all graphs are preserved.
+#[wasm_bindgen(js_name = toNQuads)]
+pub fn to_n_quads(doc: &str, syntax_in: &str, base_iri: Option<String>) -> Result<String, JsValue> {
+    Ok(convert_document(
+        doc.as_bytes(),
+        syntax_in,
+        "n-quads",
+        base_iri,
+    )?)
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+    use test_case::test_case;
+
+    use super::convert_document;
+    use crate::{
+        error_code::{self, ErrorCode},
+        tests::TRACING,
+    };
+
+    #[test_case(
+        "text/turtle",
+        "n-quads",
+        "<#alice> <http://example.org/knows> <#bob> ."
+    )]
+    #[test_case(
+        "trig",
+        "application/n-quads",
+        "<http://example.org/g> { <#alice> <http://example.org/knows> <#bob> . }"
+    )]
+    #[test_case(
+        "nt",
+        "turtle",
+        "<http://example.org/doc#alice> <http://example.org/knows> <http://example.org/doc#bob> ."
+    )]
+    pub fn converts_documents_between_named_syntaxes(syntax_in: &str, syntax_out: &str, doc: &str) {
+        Lazy::force(&TRACING);
+        let converted = convert_document(
+            doc.as_bytes(),
+            syntax_in,
+            syntax_out,
+            Some("http://example.org/doc".into()),
+        )
+        .unwrap();
+        assert!(converted.contains("http://example.org/doc#alice"));
+    }
+
+    #[test_case(
+        "text/turtle",
+        "application/ld+json",
+        error_code::DYNSYN_UNKNOWN_SYNTAX
+    )]
+    #[test_case("text/plain", "n-quads", error_code::DYNSYN_UNKNOWN_SYNTAX)]
+    #[test_case("text/turtle", "n-quads", error_code::DYNSYN_PARSE_SYNTAX)]
+    pub fn converting_documents_will_error(syntax_in: &str, syntax_out: &str, code: &str) {
+        Lazy::force(&TRACING);
+        let e = convert_document(
+            b"<http://ex.org/a> <http://ex.org/b> .",
+            syntax_in,
+            syntax_out,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(e.code(), code);
+    }
+}